@@ -354,12 +354,14 @@ EOF
 Type=Application
 Name=Clipboard History
 Comment=Windows 11-style Clipboard History Manager
-Exec=$HOME/.local/bin/win11-clipboard-history
+Exec=$HOME/.local/bin/win11-clipboard-history %U
 Icon=win11-clipboard-history
 Terminal=false
 Categories=Utility;
+MimeType=x-scheme-handler/clip;
 StartupWMClass=win11-clipboard-history
 EOF
+    update-desktop-database -q "$HOME/.local/share/applications" 2>/dev/null || true
     
     # Ask about udev rules for AppImage (optional - maintains portability)
     setup_udev_appimage_optional
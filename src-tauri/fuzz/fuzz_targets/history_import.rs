@@ -0,0 +1,21 @@
+//! A history file written by another version, or edited by hand, loaded and
+//! then captured into
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use win11_clipboard_history_lib::clipboard_manager::ClipboardManager;
+
+fuzz_target!(|data: &[u8]| {
+    let path = std::env::temp_dir().join(format!(
+        "win11-clipboard-history-fuzz-{}.json",
+        std::process::id()
+    ));
+    if std::fs::write(&path, data).is_err() {
+        return;
+    }
+
+    let mut manager = ClipboardManager::new(path.clone(), 50);
+    let _ = manager.get_history();
+    let _ = manager.add_text(String::from_utf8_lossy(data).into_owned(), None);
+    let _ = std::fs::remove_file(&path);
+});
@@ -0,0 +1,29 @@
+//! Rich text as apps put it on the clipboard, through the hidden character
+//! checks and every paste format
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use win11_clipboard_history_lib::clipboard_manager::ClipboardItem;
+use win11_clipboard_history_lib::content_classifier::{classify, clean_hidden_chars};
+use win11_clipboard_history_lib::paste_profiles::{apply, PasteFormat};
+
+fuzz_target!(|input: (&str, &str)| {
+    let (plain, html) = input;
+    let _ = classify(plain);
+    let _ = clean_hidden_chars(plain);
+
+    let items = [
+        ClipboardItem::new_text(plain.to_string()),
+        ClipboardItem::new_rich_text(plain.to_string(), html.to_string()),
+    ];
+    for item in &items {
+        for format in [
+            PasteFormat::AsIs,
+            PasteFormat::PlainText,
+            PasteFormat::CodeBlock,
+            PasteFormat::Html,
+        ] {
+            let _ = apply(format, item);
+        }
+    }
+});
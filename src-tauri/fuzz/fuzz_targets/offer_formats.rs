@@ -0,0 +1,15 @@
+//! Mime types advertised by the selection owner, one per line, and the PNG
+//! bytes it sends for an image offer
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use win11_clipboard_history_lib::wayland_clipboard::{choose_offer_format, decode_offer_image};
+
+fuzz_target!(|data: &[u8]| {
+    let mime_types: Vec<String> = String::from_utf8_lossy(data)
+        .lines()
+        .map(str::to_string)
+        .collect();
+    let _ = choose_offer_format(&mime_types);
+    let _ = decode_offer_image(data);
+});
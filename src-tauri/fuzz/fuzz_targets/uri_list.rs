@@ -0,0 +1,13 @@
+//! Copied text as a file list (text/uri-list, GNOME's "copy" format) and as
+//! clip:// item links
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use win11_clipboard_history_lib::clipboard_manager::{parse_hash, ClipboardItem};
+use win11_clipboard_history_lib::item_link::parse_item_link;
+
+fuzz_target!(|text: &str| {
+    let _ = ClipboardItem::new_text(text.to_string()).file_paths();
+    let _ = parse_item_link(text);
+    let _ = parse_hash(text);
+});
@@ -0,0 +1,44 @@
+# Deutsch. Keys left out fall back to English (en.ftl).
+
+language-name = Deutsch
+
+## Item ages
+
+age-just-now = Gerade eben
+age-minutes = vor { $count } Min.
+age-hours = vor { $count } Std.
+age-yesterday = Gestern
+age-days = vor { $count } Tagen
+# strftime patterns for items older than a week
+age-date = %d.%m.
+age-date-with-year = %d.%m.%Y
+
+## Screen reader summaries
+
+summary-text = Text: { $preview }
+summary-long-text = Text, { $words } Wörter: { $preview }
+summary-multiline-text = Text, { $words } Wörter in { $lines } Zeilen: { $preview }
+summary-link = Link zu { $host }
+summary-image = Bild, { $width } mal { $height } Pixel
+summary-file = Datei { $name }
+summary-files = { $count } Dateien, { $names }
+summary-more = { $names } und { $count } weitere
+summary-missing = { $summary }, nicht mehr vorhanden
+summary-pinned = { $summary }, angeheftet
+
+## Errors
+
+error-linux-only = Diese Funktion ist nur unter Linux verfügbar.
+error-background-work-off = Hintergrundarbeit ist ausgeschaltet
+error-no-previous-window = Kein vorheriges Fenster gespeichert
+
+## Manual shortcut setup
+
+shortcut-instructions-generic =
+    **Allgemeine Anleitung:**
+    1. Öffne die Tastenkürzel-Einstellungen deiner Desktopumgebung
+    2. Füge ein neues eigenes Tastenkürzel hinzu
+    3. Befehl: `win11-clipboard-history`
+    4. Tastenkürzel: Super+V (oder eine beliebige andere Kombination)
+
+    **Alternative:** Nimm Strg+Alt+V, falls Super+V mit deiner Desktopumgebung kollidiert.
@@ -0,0 +1,200 @@
+# English messages, used for any key another catalog leaves out.
+# Syntax: `key = value`, values continued on indented lines and `{ $name }`
+# for variables.
+
+language-name = English
+
+## Item ages
+
+age-just-now = Just now
+age-minutes = { $count } min ago
+age-hours = { $count } h ago
+age-yesterday = Yesterday
+age-days = { $count } days ago
+# strftime patterns for items older than a week
+age-date = %b %-d
+age-date-with-year = %b %-d, %Y
+
+## Screen reader summaries
+
+summary-text = Text: { $preview }
+summary-long-text = Text, { $words } words: { $preview }
+summary-multiline-text = Text, { $words } words on { $lines } lines: { $preview }
+summary-link = Link to { $host }
+summary-image = Image, { $width } by { $height } pixels
+summary-file = File { $name }
+summary-files = { $count } files, { $names }
+summary-more = { $names } and { $count } more
+summary-missing = { $summary }, no longer on disk
+summary-pinned = { $summary }, pinned
+
+## Errors
+
+error-linux-only = This feature is only available on Linux.
+error-background-work-off = Background work is turned off
+error-no-previous-window = No previous window saved
+
+## Manual shortcut setup
+
+shortcut-instructions-gnome =
+    **GNOME Settings:**
+    1. Open Settings → Keyboard → Keyboard Shortcuts → Custom Shortcuts
+    2. Click "+" to add a new shortcut
+    3. Name: "Clipboard History"
+    4. Command: `win11-clipboard-history`
+    5. Shortcut: Press Super+V
+
+    **⚠️ Note:** GNOME uses Super+V for the Notification Center by default.
+    To free up Super+V, run:
+    ```
+    gsettings set org.gnome.shell.keybindings toggle-message-tray "['<Super><Shift>v']"
+    ```
+
+shortcut-instructions-pop-os =
+    **Pop!_OS / Pop Shell:**
+    1. Open Settings → Keyboard → Keyboard Shortcuts → Custom Shortcuts
+    2. Add a new shortcut with command: `win11-clipboard-history`
+    3. Set the shortcut to Super+V
+
+    **⚠️ Note:** Pop!_OS inherits GNOME's Super+V for Notification Center.
+    To free up Super+V, run:
+    ```
+    gsettings set org.gnome.shell.keybindings toggle-message-tray "['<Super><Shift>v']"
+    ```
+
+    If Pop Shell uses Super+V for tiling, change it in:
+    Settings → Keyboard → Customize Shortcuts → Pop Shell
+
+shortcut-instructions-kde =
+    **KDE System Settings:**
+    1. Open System Settings → Shortcuts → Custom Shortcuts
+    2. Click "Edit" → "New" → "Global Shortcut" → "Command/URL"
+    3. Name: "Clipboard History"
+    4. Trigger: Click and press Meta+V
+    5. Action: `win11-clipboard-history`
+
+    **⚠️ Note:** If Klipper (KDE's clipboard) uses Meta+V:
+    1. Right-click Klipper in system tray → Configure
+    2. Change or disable its shortcut
+
+shortcut-instructions-cinnamon =
+    **Cinnamon Settings:**
+    1. Open System Settings → Keyboard → Shortcuts → Custom Shortcuts
+    2. Click "Add custom shortcut"
+    3. Name: "Clipboard History"
+    4. Command: `win11-clipboard-history`
+    5. Click on the shortcut area and press Super+V
+
+shortcut-instructions-xfce =
+    **XFCE Settings:**
+    1. Open Settings → Keyboard → Application Shortcuts
+    2. Click "Add"
+    3. Command: `win11-clipboard-history`
+    4. Press Super+V when prompted
+
+shortcut-instructions-mate =
+    **MATE Control Center:**
+    1. Open Control Center → Keyboard Shortcuts
+    2. Click "Add"
+    3. Name: "Clipboard History"
+    4. Command: `win11-clipboard-history`
+    5. Click on the shortcut and press Super+V
+
+shortcut-instructions-lxqt =
+    **LXQt Configuration:**
+    1. Open LXQt Configuration → Shortcut Keys
+    2. Click "Add"
+    3. Description: "Clipboard History"
+    4. Command: `win11-clipboard-history`
+    5. Set shortcut to Meta+V
+
+shortcut-instructions-lxde =
+    **LXDE/Openbox:**
+    1. Edit ~/.config/openbox/lxde-rc.xml
+    2. Add in <keyboard> section:
+
+    <keybind key="Super_L+v">
+      <action name="Execute">
+        <command>win11-clipboard-history</command>
+      </action>
+    </keybind>
+
+    3. Run: openbox --reconfigure
+
+shortcut-instructions-cosmic =
+    **COSMIC Settings:**
+    1. Open Settings → Keyboard → Custom Shortcuts
+    2. Add new shortcut
+    3. Command: `win11-clipboard-history`
+    4. Binding: Super+V
+
+    **Note:** If there's a conflict, check System shortcuts for Super+V bindings.
+
+shortcut-instructions-i3 =
+    **i3 Configuration:**
+    1. Edit your i3 config: `~/.config/i3/config`
+    2. Comment out or remove any existing `bindsym $mod+v` line
+    3. Add this line:
+    ```
+    bindsym $mod+v exec win11-clipboard-history
+    ```
+    4. Reload i3: Press $mod+Shift+r
+
+    **Alternative shortcut:**
+    ```
+    bindsym Ctrl+Mod1+v exec win11-clipboard-history
+    ```
+
+shortcut-instructions-sway =
+    **Sway Configuration:**
+    1. Edit your Sway config: `~/.config/sway/config`
+    2. Comment out or remove any existing `bindsym $mod+v` line
+    3. Add this line:
+    ```
+    bindsym $mod+v exec win11-clipboard-history
+    ```
+    4. Reload Sway: Press $mod+Shift+c
+
+    **Alternative shortcut:**
+    ```
+    bindsym Ctrl+Mod1+v exec win11-clipboard-history
+    ```
+
+shortcut-instructions-hyprland =
+    **Hyprland Configuration:**
+    1. Edit your Hyprland config: `~/.config/hypr/hyprland.conf`
+    2. Comment out or remove any existing `bind = SUPER, V, ...` line
+    3. Add this line:
+    ```
+    bind = SUPER, V, exec, win11-clipboard-history
+    ```
+    4. Config auto-reloads (or press Super+M to reload manually)
+
+    **Alternative shortcut:**
+    ```
+    bind = CTRL ALT, V, exec, win11-clipboard-history
+    ```
+
+shortcut-instructions-niri =
+    **Niri Configuration:**
+    1. Edit your Niri config: `~/.config/niri/config.kdl`
+    2. Comment out or remove any existing `Mod+V` entry in the `binds` section
+    3. Add this line inside `binds { ... }`:
+    ```
+    Mod+V { spawn "win11-clipboard-history"; }
+    ```
+    4. Niri reloads the config as soon as it is saved
+
+    **Alternative shortcut:**
+    ```
+    Ctrl+Alt+V { spawn "win11-clipboard-history"; }
+    ```
+
+shortcut-instructions-generic =
+    **Generic Instructions:**
+    1. Open your desktop environment's keyboard shortcuts settings
+    2. Add a new custom shortcut
+    3. Command: `win11-clipboard-history`
+    4. Shortcut: Super+V (or your preferred combination)
+
+    **Alternative:** Use Ctrl+Alt+V if Super+V conflicts with your DE.
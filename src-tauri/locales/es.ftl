@@ -0,0 +1,44 @@
+# Español. Keys left out fall back to English (en.ftl).
+
+language-name = Español
+
+## Item ages
+
+age-just-now = Ahora
+age-minutes = hace { $count } min
+age-hours = hace { $count } h
+age-yesterday = Ayer
+age-days = hace { $count } días
+# strftime patterns for items older than a week
+age-date = %d/%m
+age-date-with-year = %d/%m/%Y
+
+## Screen reader summaries
+
+summary-text = Texto: { $preview }
+summary-long-text = Texto, { $words } palabras: { $preview }
+summary-multiline-text = Texto, { $words } palabras en { $lines } líneas: { $preview }
+summary-link = Enlace a { $host }
+summary-image = Imagen, { $width } por { $height } píxeles
+summary-file = Archivo { $name }
+summary-files = { $count } archivos, { $names }
+summary-more = { $names } y { $count } más
+summary-missing = { $summary }, ya no existe
+summary-pinned = { $summary }, anclado
+
+## Errors
+
+error-linux-only = Esta función solo está disponible en Linux.
+error-background-work-off = El trabajo en segundo plano está desactivado
+error-no-previous-window = No se guardó ninguna ventana anterior
+
+## Manual shortcut setup
+
+shortcut-instructions-generic =
+    **Instrucciones generales:**
+    1. Abre la configuración de atajos de teclado de tu entorno de escritorio
+    2. Añade un nuevo atajo personalizado
+    3. Comando: `win11-clipboard-history`
+    4. Atajo: Super+V (o la combinación que prefieras)
+
+    **Alternativa:** usa Ctrl+Alt+V si Super+V entra en conflicto con tu entorno.
@@ -0,0 +1,44 @@
+# Français. Keys left out fall back to English (en.ftl).
+
+language-name = Français
+
+## Item ages
+
+age-just-now = À l'instant
+age-minutes = il y a { $count } min
+age-hours = il y a { $count } h
+age-yesterday = Hier
+age-days = il y a { $count } jours
+# strftime patterns for items older than a week
+age-date = %d/%m
+age-date-with-year = %d/%m/%Y
+
+## Screen reader summaries
+
+summary-text = Texte : { $preview }
+summary-long-text = Texte, { $words } mots : { $preview }
+summary-multiline-text = Texte, { $words } mots sur { $lines } lignes : { $preview }
+summary-link = Lien vers { $host }
+summary-image = Image, { $width } sur { $height } pixels
+summary-file = Fichier { $name }
+summary-files = { $count } fichiers, { $names }
+summary-more = { $names } et { $count } de plus
+summary-missing = { $summary }, n'existe plus
+summary-pinned = { $summary }, épinglé
+
+## Errors
+
+error-linux-only = Cette fonctionnalité n'est disponible que sous Linux.
+error-background-work-off = Le travail en arrière-plan est désactivé
+error-no-previous-window = Aucune fenêtre précédente enregistrée
+
+## Manual shortcut setup
+
+shortcut-instructions-generic =
+    **Instructions générales :**
+    1. Ouvrez les paramètres des raccourcis clavier de votre environnement de bureau
+    2. Ajoutez un nouveau raccourci personnalisé
+    3. Commande : `win11-clipboard-history`
+    4. Raccourci : Super+V (ou la combinaison de votre choix)
+
+    **Alternative :** utilisez Ctrl+Alt+V si Super+V entre en conflit avec votre environnement.
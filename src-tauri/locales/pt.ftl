@@ -0,0 +1,44 @@
+# Português. Keys left out fall back to English (en.ftl).
+
+language-name = Português
+
+## Item ages
+
+age-just-now = Agora
+age-minutes = há { $count } min
+age-hours = há { $count } h
+age-yesterday = Ontem
+age-days = há { $count } dias
+# strftime patterns for items older than a week
+age-date = %d/%m
+age-date-with-year = %d/%m/%Y
+
+## Screen reader summaries
+
+summary-text = Texto: { $preview }
+summary-long-text = Texto, { $words } palavras: { $preview }
+summary-multiline-text = Texto, { $words } palavras em { $lines } linhas: { $preview }
+summary-link = Link para { $host }
+summary-image = Imagem, { $width } por { $height } pixels
+summary-file = Arquivo { $name }
+summary-files = { $count } arquivos, { $names }
+summary-more = { $names } e mais { $count }
+summary-missing = { $summary }, não existe mais
+summary-pinned = { $summary }, fixado
+
+## Errors
+
+error-linux-only = Este recurso só está disponível no Linux.
+error-background-work-off = O trabalho em segundo plano está desativado
+error-no-previous-window = Nenhuma janela anterior foi salva
+
+## Manual shortcut setup
+
+shortcut-instructions-generic =
+    **Instruções gerais:**
+    1. Abra as configurações de atalhos de teclado do seu ambiente de trabalho
+    2. Adicione um novo atalho personalizado
+    3. Comando: `win11-clipboard-history`
+    4. Atalho: Super+V (ou a combinação que preferir)
+
+    **Alternativa:** use Ctrl+Alt+V se Super+V entrar em conflito com o seu ambiente.
@@ -0,0 +1,84 @@
+//! Activity Heatmap
+//! Aggregates when items were copied into counts per weekday and hour of the
+//! day (in local time), the data behind a GitHub-style activity heatmap.
+//! Only items still in history are counted.
+
+use chrono::{DateTime, Datelike, Duration, TimeZone, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+
+/// How far back the heatmap looks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HeatmapRange {
+    Week,
+    Month,
+    Year,
+    All,
+}
+
+impl HeatmapRange {
+    fn duration(self) -> Option<Duration> {
+        match self {
+            Self::Week => Some(Duration::days(7)),
+            Self::Month => Some(Duration::days(30)),
+            Self::Year => Some(Duration::days(365)),
+            Self::All => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ActivityHeatmap {
+    /// `counts[weekday][hour]`, weekdays starting on Monday
+    pub counts: Vec<Vec<u32>>,
+    pub total: u32,
+    /// Largest single cell, for scaling colors
+    pub max: u32,
+}
+
+/// Buckets the timestamps that fall inside `range` (ending at `now`) by
+/// weekday and hour in the `local` time zone
+pub fn build<Tz: TimeZone>(
+    timestamps: impl IntoIterator<Item = DateTime<Utc>>,
+    range: HeatmapRange,
+    now: DateTime<Utc>,
+    local: &Tz,
+) -> ActivityHeatmap {
+    let since = range.duration().map(|duration| now - duration);
+    let mut counts = vec![vec![0u32; 24]; 7];
+    let mut total = 0;
+
+    for timestamp in timestamps {
+        if since.is_some_and(|since| timestamp < since) || timestamp > now {
+            continue;
+        }
+        let local_time = timestamp.with_timezone(local);
+        let weekday = local_time.weekday().num_days_from_monday() as usize;
+        counts[weekday][local_time.hour() as usize] += 1;
+        total += 1;
+    }
+
+    let max = counts.iter().flatten().copied().max().unwrap_or(0);
+    ActivityHeatmap { counts, total, max }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_buckets_by_weekday_and_hour() {
+        // 2024-01-01 was a Monday
+        let at = |day: u32, hour: u32| Utc.with_ymd_and_hms(2024, 1, day, hour, 30, 0).unwrap();
+        let now = at(10, 12);
+        let timestamps = [at(8, 9), at(8, 9), at(9, 23), at(1, 9)];
+
+        let week = build(timestamps, HeatmapRange::Week, now, &Utc);
+        assert_eq!(week.counts[0][9], 2);
+        assert_eq!(week.counts[1][23], 1);
+        assert_eq!((week.total, week.max), (3, 2));
+
+        let all = build(timestamps, HeatmapRange::All, now, &Utc);
+        assert_eq!(all.counts[0][9], 3);
+    }
+}
@@ -0,0 +1,888 @@
+//! Command Line Interface
+//! Parses command line options and runs the ones that do not need the GUI,
+//! with structured output (`--json`), stable exit codes and shell completions
+//! so the binary composes with scripts and pipelines.
+
+use crate::clipboard_manager::{
+    calculate_hash, format_hash, parse_hash, ClipboardContent, ClipboardItem,
+};
+use crate::history_store::{open_store, StorageBackend, SLED_LOCKED};
+use crate::item_link;
+use crate::pinned_git;
+use crate::soak::{self, SoakConfig};
+use crate::user_settings::UserSettingsManager;
+use serde::Serialize;
+use std::io::Read;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+
+pub const BIN_NAME: &str = "win11-clipboard-history";
+
+/// Exit codes
+pub const EXIT_SUCCESS: i32 = 0;
+pub const EXIT_FAILURE: i32 = 1;
+pub const EXIT_USAGE: i32 = 2;
+/// Revisions shown by --history-log when --limit is not given
+const DEFAULT_LOG_LIMIT: usize = 20;
+
+/// Tabs accepted by --open-tab, as named in the frontend
+const TAB_NAMES: &[&str] = &[
+    "clipboard",
+    "selections",
+    "gifs",
+    "emoji",
+    "kaomoji",
+    "symbols",
+    "stickers",
+];
+
+/// --find-hash matched nothing
+pub const EXIT_NOT_FOUND: i32 = 3;
+
+/// Events per second of --soak when --soak-rate is not given
+const DEFAULT_SOAK_RATE: u32 = 200;
+
+/// Every option with its help text; used for --help and completion scripts
+const OPTIONS: &[(&str, Option<&str>, &str)] = &[
+    ("--help", Some("-h"), "Show this help message"),
+    ("--version", Some("-v"), "Show version information"),
+    (
+        "--background",
+        None,
+        "Start minimized to system tray (for autostart)",
+    ),
+    ("--settings", None, "Open settings window on startup"),
+    ("--emoji", None, "Open the window on the emoji panel"),
+    (
+        "--paste-recent",
+        None,
+        "Paste the Nth most recent item into the focused app",
+    ),
+    (
+        "--paste-last",
+        None,
+        "Paste the most recent item (--paste-recent 1)",
+    ),
+    ("--copy", None, "Copy text to the clipboard"),
+    ("--open-tab", None, "Open the window on a tab"),
+    (
+        "--search",
+        None,
+        "Open clipboard history filtered by a query",
+    ),
+    ("--list", None, "Print clipboard history and exit"),
+    ("--limit", None, "Maximum number of items printed by --list"),
+    ("--json", None, "Print machine-readable JSON output"),
+    (
+        "--find-hash",
+        None,
+        "Look up an item by content hash without printing its content",
+    ),
+    (
+        "--hash",
+        None,
+        "Print the content hash of stdin (text as given, or an image file)",
+    ),
+    (
+        "--history-log",
+        None,
+        "Show revisions of the git-tracked pinned items",
+    ),
+    (
+        "--completions",
+        None,
+        "Print a completion script (bash, zsh, fish, nu)",
+    ),
+    (
+        "--unregister-shortcuts",
+        None,
+        "Remove the desktop keyboard shortcuts and exit (for uninstall)",
+    ),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+    Nushell,
+}
+
+impl FromStr for Shell {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "bash" => Ok(Self::Bash),
+            "zsh" => Ok(Self::Zsh),
+            "fish" => Ok(Self::Fish),
+            "nu" | "nushell" => Ok(Self::Nushell),
+            other => Err(format!(
+                "unsupported shell '{}' (expected bash, zsh, fish or nu)",
+                other
+            )),
+        }
+    }
+}
+
+/// What the process should do
+#[derive(Debug, Clone, PartialEq)]
+pub enum CliAction {
+    /// Start (or signal) the GUI application
+    Run,
+    Help,
+    Version,
+    List {
+        limit: Option<usize>,
+    },
+    Completions(Shell),
+    FindHash(u64),
+    /// Prints the content hash of stdin
+    Hash,
+    HistoryLog {
+        limit: Option<usize>,
+    },
+    UnregisterShortcuts,
+    /// Hidden release check, see `soak`. Uses the configured backend when
+    /// none is given.
+    Soak {
+        duration: Duration,
+        rate: u32,
+        backend: Option<StorageBackend>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CliOptions {
+    pub action: CliAction,
+    pub json: bool,
+    pub background: bool,
+    pub settings: bool,
+    /// Tab the window opens on (`--open-tab`, `--emoji`)
+    pub tab: Option<String>,
+    /// Position of the item to paste (`--paste-recent N`, 1 = latest)
+    pub paste_recent: Option<usize>,
+    /// Text to put on the clipboard (`--copy`)
+    pub copy: Option<String>,
+    /// Query to open clipboard history with (`--search`)
+    pub search: Option<String>,
+    /// Item to focus, from a `clip://item/<id>` link
+    pub open_item: Option<String>,
+}
+
+impl CliOptions {
+    /// Whether the options ask a running instance to do something other
+    /// than toggling its window
+    pub fn has_instance_action(&self) -> bool {
+        self.settings
+            || self.tab.is_some()
+            || self.paste_recent.is_some()
+            || self.copy.is_some()
+            || self.search.is_some()
+            || self.open_item.is_some()
+    }
+}
+
+/// Accepts tab names as listed in TAB_NAMES, plus singular forms like "gif"
+fn parse_tab(name: &str) -> Result<String, String> {
+    let name = name.to_lowercase();
+    let plural = format!("{}s", name);
+    TAB_NAMES
+        .iter()
+        .find(|tab| **tab == name || **tab == plural)
+        .map(|tab| tab.to_string())
+        .ok_or_else(|| {
+            format!(
+                "unknown tab '{}' (expected one of {})",
+                name,
+                TAB_NAMES.join(", ")
+            )
+        })
+}
+
+/// Durations like "90s", "45m" or "2h"; plain numbers are seconds
+fn parse_duration(value: &str) -> Option<Duration> {
+    let (number, unit) = match value.find(|c: char| !c.is_ascii_digit()) {
+        Some(split) => value.split_at(split),
+        None => (value, "s"),
+    };
+    let number: u64 = number.parse().ok()?;
+    let secs = match unit {
+        "s" => number,
+        "m" => number.checked_mul(60)?,
+        "h" => number.checked_mul(3600)?,
+        _ => return None,
+    };
+    Some(Duration::from_secs(secs))
+}
+
+/// Parses arguments (without the program name)
+pub fn parse_args(args: &[String]) -> Result<CliOptions, String> {
+    let mut options = CliOptions {
+        action: CliAction::Run,
+        json: false,
+        background: false,
+        settings: false,
+        tab: None,
+        paste_recent: None,
+        copy: None,
+        search: None,
+        open_item: None,
+    };
+    let mut limit = None;
+    let mut list = false;
+    let mut history_log = false;
+    let mut soak_duration = None;
+    let mut soak_rate = DEFAULT_SOAK_RATE;
+    let mut soak_backend = None;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "-h" | "--help" => options.action = CliAction::Help,
+            // --help wins when both are given
+            "-v" | "--version" if options.action != CliAction::Help => {
+                options.action = CliAction::Version;
+            }
+            "-v" | "--version" => {}
+            "--background" => options.background = true,
+            "--settings" => options.settings = true,
+            "--emoji" => options.tab = Some("emoji".to_string()),
+            "--json" => options.json = true,
+            "--list" => list = true,
+            "--history-log" => history_log = true,
+            "--limit" => {
+                let value = iter.next().ok_or("--limit requires a number")?;
+                limit = Some(
+                    value
+                        .parse::<usize>()
+                        .map_err(|_| format!("invalid --limit value '{}'", value))?,
+                );
+            }
+            "--paste-recent" => {
+                let value = iter.next().ok_or("--paste-recent requires a position")?;
+                let position = value
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|&position| position > 0)
+                    .ok_or_else(|| format!("invalid --paste-recent value '{}'", value))?;
+                options.paste_recent = Some(position);
+            }
+            "--paste-last" => options.paste_recent = Some(1),
+            "--copy" => {
+                let text = iter.next().ok_or("--copy requires the text to copy")?;
+                options.copy = Some(text.clone());
+            }
+            "--open-tab" => {
+                let name = iter.next().ok_or("--open-tab requires a tab name")?;
+                options.tab = Some(parse_tab(name)?);
+            }
+            "--search" => {
+                let query = iter.next().ok_or("--search requires a query")?;
+                options.search = Some(query.clone());
+            }
+            "--find-hash" => {
+                let value = iter.next().ok_or("--find-hash requires a hash")?;
+                let hash = parse_hash(value).ok_or_else(|| format!("invalid hash '{}'", value))?;
+                options.action = CliAction::FindHash(hash);
+            }
+            "--hash" => options.action = CliAction::Hash,
+            "--unregister-shortcuts" => options.action = CliAction::UnregisterShortcuts,
+            // Not listed in OPTIONS: release testing only
+            "--soak" => {
+                let value = iter.next().ok_or("--soak requires a duration")?;
+                soak_duration = Some(
+                    parse_duration(value)
+                        .ok_or_else(|| format!("invalid --soak duration '{}'", value))?,
+                );
+            }
+            "--soak-rate" => {
+                let value = iter.next().ok_or("--soak-rate requires a number")?;
+                soak_rate = value
+                    .parse()
+                    .map_err(|_| format!("invalid --soak-rate value '{}'", value))?;
+            }
+            "--soak-backend" => {
+                let value = iter.next().ok_or("--soak-backend requires a backend")?;
+                soak_backend = Some(
+                    StorageBackend::from_setting(value)
+                        .ok_or_else(|| format!("unknown storage backend '{}'", value))?,
+                );
+            }
+            "--completions" => {
+                let shell = iter.next().ok_or("--completions requires a shell name")?;
+                options.action = CliAction::Completions(shell.parse()?);
+            }
+            other if other.starts_with('-') => {
+                return Err(format!("unknown option '{}'", other));
+            }
+            link if item_link::is_link(link) => {
+                let id = item_link::parse_item_link(link)
+                    .ok_or_else(|| format!("invalid link '{}'", link))?;
+                options.open_item = Some(id.to_string());
+            }
+            // Other positional arguments (e.g. %U from launchers) are ignored
+            _ => {}
+        }
+    }
+
+    if options.action == CliAction::Run {
+        if let Some(duration) = soak_duration {
+            options.action = CliAction::Soak {
+                duration,
+                rate: soak_rate,
+                backend: soak_backend,
+            };
+        } else if list {
+            options.action = CliAction::List { limit };
+        } else if history_log {
+            options.action = CliAction::HistoryLog { limit };
+        }
+    }
+
+    Ok(options)
+}
+
+/// Runs actions that don't need the GUI. Returns the exit code, or None
+/// if the application should start normally.
+pub fn run_headless(options: &CliOptions, version: &str) -> Option<i32> {
+    let code = match &options.action {
+        CliAction::Run => return None,
+        CliAction::Help => {
+            println!("{}", help_text(version));
+            EXIT_SUCCESS
+        }
+        CliAction::Version => {
+            if options.json {
+                print_json(&VersionInfo {
+                    name: BIN_NAME,
+                    version,
+                })
+            } else {
+                println!("{} {}", BIN_NAME, version);
+                EXIT_SUCCESS
+            }
+        }
+        CliAction::List { limit } => list_history(*limit, options.json),
+        CliAction::FindHash(hash) => find_by_hash(*hash, options.json),
+        CliAction::Hash => print_stdin_hash(options.json),
+        CliAction::HistoryLog { limit } => print_history_log(*limit, options.json),
+        CliAction::Completions(shell) => {
+            print!("{}", completion_script(*shell));
+            EXIT_SUCCESS
+        }
+        CliAction::UnregisterShortcuts => unregister_shortcuts(),
+        CliAction::Soak {
+            duration,
+            rate,
+            backend,
+        } => run_soak(*duration, *rate, *backend, options.json),
+    };
+    Some(code)
+}
+
+/// Soaks a scratch store, never the real history
+fn run_soak(duration: Duration, rate: u32, backend: Option<StorageBackend>, json: bool) -> i32 {
+    let settings = UserSettingsManager::new().load();
+    let config = SoakConfig {
+        duration,
+        rate,
+        backend: backend.unwrap_or_else(|| settings.storage_backend()),
+        max_history_size: settings.max_history_size,
+        dir: std::env::temp_dir().join(format!("{}-soak-{}", BIN_NAME, std::process::id())),
+    };
+    eprintln!(
+        "Soaking the {} store for {:?} at {} events/s in {}",
+        config.backend.as_str(),
+        duration,
+        rate,
+        config.dir.display()
+    );
+
+    let result = soak::run(&config);
+    let _ = std::fs::remove_dir_all(&config.dir);
+    let report = match result {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return EXIT_FAILURE;
+        }
+    };
+
+    if json {
+        if print_json(&report) != EXIT_SUCCESS {
+            return EXIT_FAILURE;
+        }
+    } else {
+        println!("{}", report.summary());
+    }
+    if report.passed() {
+        EXIT_SUCCESS
+    } else {
+        EXIT_FAILURE
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn unregister_shortcuts() -> i32 {
+    match crate::linux_shortcut_manager::unregister_global_shortcut() {
+        Ok(environment) => {
+            println!("Removed keyboard shortcuts from {}", environment);
+            EXIT_SUCCESS
+        }
+        Err(e) => {
+            eprintln!("error: {}", e);
+            EXIT_FAILURE
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn unregister_shortcuts() -> i32 {
+    eprintln!("--unregister-shortcuts is only supported on Linux");
+    EXIT_FAILURE
+}
+
+#[derive(Serialize)]
+struct VersionInfo<'a> {
+    name: &'a str,
+    version: &'a str,
+}
+
+fn print_json<T: Serialize>(value: &T) -> i32 {
+    match serde_json::to_string(value) {
+        Ok(json) => {
+            println!("{}", json);
+            EXIT_SUCCESS
+        }
+        Err(e) => {
+            eprintln!("error: failed to serialize output: {}", e);
+            EXIT_FAILURE
+        }
+    }
+}
+
+/// An item as printed by --list --json, with its content hash
+#[derive(Serialize)]
+struct ListedItem<'a> {
+    hash: String,
+    #[serde(flatten)]
+    item: &'a ClipboardItem,
+}
+
+/// Item metadata printed by --find-hash; deliberately omits the content
+#[derive(Serialize)]
+struct HashMatch<'a> {
+    id: &'a str,
+    hash: String,
+    timestamp: String,
+    pinned: bool,
+}
+
+/// Printed by --hash --json
+#[derive(Serialize)]
+struct HashOutput {
+    hash: String,
+}
+
+/// Reads the persisted history from the configured backend, printing the
+/// error and returning the exit code on failure
+fn load_history() -> Result<Vec<ClipboardItem>, i32> {
+    let Some(data_dir) = dirs::data_local_dir().map(|dir| dir.join("win11-clipboard-history"))
+    else {
+        eprintln!("error: could not resolve the data directory");
+        return Err(EXIT_FAILURE);
+    };
+
+    let backend = UserSettingsManager::new().load().storage_backend();
+    if backend == StorageBackend::Memory {
+        eprintln!("error: history is kept in memory only and cannot be read from the CLI");
+        return Err(EXIT_FAILURE);
+    }
+
+    open_store(backend, &data_dir)
+        .and_then(|mut store| store.load())
+        .map_err(|e| {
+            if e.contains(SLED_LOCKED) {
+                eprintln!(
+                    "error: the sled history is locked by the running app; quit it, or switch \
+                     to another storage backend, to read history from the command line"
+                );
+            } else {
+                eprintln!("error: failed to read {} history: {}", backend.as_str(), e);
+            }
+            EXIT_FAILURE
+        })
+}
+
+fn list_history(limit: Option<usize>, json: bool) -> i32 {
+    let items = match load_history() {
+        Ok(items) => items,
+        Err(code) => return code,
+    };
+
+    let items = &items[..limit.unwrap_or(items.len()).min(items.len())];
+
+    if json {
+        let listed: Vec<ListedItem> = items
+            .iter()
+            .map(|item| ListedItem {
+                hash: format_hash(item.content_hash()),
+                item,
+            })
+            .collect();
+        return print_json(&listed);
+    }
+
+    for item in items {
+        let text = match &item.content {
+            ClipboardContent::Text(text) => text.as_str(),
+            ClipboardContent::RichText { plain, .. } => plain.as_str(),
+            ClipboardContent::Image { .. } => item.preview.as_str(),
+        };
+        // One item per line so the output can be piped into fzf/grep
+        println!("{}\t{}", item.id, text.replace('\n', "\\n"));
+    }
+    EXIT_SUCCESS
+}
+
+fn find_by_hash(hash: u64, json: bool) -> i32 {
+    let items = match load_history() {
+        Ok(items) => items,
+        Err(code) => return code,
+    };
+
+    let Some(item) = items.iter().find(|item| item.content_hash() == hash) else {
+        if json {
+            println!("null");
+        }
+        return EXIT_NOT_FOUND;
+    };
+
+    if json {
+        print_json(&HashMatch {
+            id: &item.id,
+            hash: format_hash(hash),
+            timestamp: item.timestamp.to_rfc3339(),
+            pinned: item.pinned,
+        })
+    } else {
+        println!("{}", item.id);
+        EXIT_SUCCESS
+    }
+}
+
+/// The hash an item with this content gets: images are hashed by their
+/// RGBA pixels, as when copied, and anything else must be UTF-8 text
+fn hash_input(input: &[u8]) -> Result<u64, String> {
+    if image::guess_format(input).is_ok() {
+        let image =
+            image::load_from_memory(input).map_err(|e| format!("failed to decode image: {}", e))?;
+        return Ok(calculate_hash(&image.to_rgba8().into_raw()));
+    }
+    let text = std::str::from_utf8(input).map_err(|_| "input is neither text nor an image")?;
+    Ok(calculate_hash(&text))
+}
+
+fn print_stdin_hash(json: bool) -> i32 {
+    let mut input = Vec::new();
+    if let Err(e) = std::io::stdin().read_to_end(&mut input) {
+        eprintln!("error: failed to read stdin: {}", e);
+        return EXIT_FAILURE;
+    }
+    let hash = match hash_input(&input) {
+        Ok(hash) => format_hash(hash),
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return EXIT_FAILURE;
+        }
+    };
+    if json {
+        print_json(&HashOutput { hash })
+    } else {
+        println!("{}", hash);
+        EXIT_SUCCESS
+    }
+}
+
+fn print_history_log(limit: Option<usize>, json: bool) -> i32 {
+    // Browsing works even after the mirror was disabled
+    let settings = UserSettingsManager::new().load();
+    let dir = match settings.pinned_git_dir.trim() {
+        "" => pinned_git::default_dir(),
+        dir => Some(PathBuf::from(dir)),
+    };
+    let Some(dir) = dir else {
+        eprintln!("error: could not resolve the data directory");
+        return EXIT_FAILURE;
+    };
+
+    let revisions = match pinned_git::history_log(&dir, limit.unwrap_or(DEFAULT_LOG_LIMIT)) {
+        Ok(revisions) => revisions,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return EXIT_FAILURE;
+        }
+    };
+
+    if json {
+        return print_json(&revisions);
+    }
+    for revision in &revisions {
+        println!(
+            "{}\t{}\t{}",
+            revision.commit, revision.date, revision.message
+        );
+    }
+    EXIT_SUCCESS
+}
+
+/// OPTIONS with the tabs of TAB_NAMES filled into the --open-tab help
+fn options() -> impl Iterator<Item = (&'static str, Option<&'static str>, String)> {
+    OPTIONS.iter().map(|&(long, short, help)| {
+        let help = match long {
+            "--open-tab" => format!("{} ({})", help, TAB_NAMES.join(", ")),
+            _ => help.to_string(),
+        };
+        (long, short, help)
+    })
+}
+
+pub fn help_text(version: &str) -> String {
+    let mut text = format!(
+        "{} {}\n\nUSAGE:\n    {} [OPTIONS]\n\nOPTIONS:\n",
+        BIN_NAME, version, BIN_NAME
+    );
+    for (long, short, help) in options() {
+        let flags = match short {
+            Some(short) => format!("{}, {}", short, long),
+            None => format!("    {}", long),
+        };
+        text.push_str(&format!("    {:<20} {}\n", flags, help));
+    }
+    text.push_str(&format!(
+        "\nEXIT CODES:\n    {}  Success\n    {}  Failure\n    {}  Invalid usage\n    {}  No item matched --find-hash\n",
+        EXIT_SUCCESS, EXIT_FAILURE, EXIT_USAGE, EXIT_NOT_FOUND
+    ));
+    text.push_str("\nSHORTCUTS:\n    Super+V              Open clipboard history\n    Ctrl+Alt+V           Alternative shortcut");
+    text
+}
+
+pub fn completion_script(shell: Shell) -> String {
+    let longs: Vec<&str> = OPTIONS.iter().map(|(long, _, _)| *long).collect();
+
+    match shell {
+        Shell::Bash => format!(
+            r#"_{fn_name}() {{
+    local cur prev
+    cur="${{COMP_WORDS[COMP_CWORD]}}"
+    prev="${{COMP_WORDS[COMP_CWORD-1]}}"
+    if [[ "$prev" == "--completions" ]]; then
+        COMPREPLY=($(compgen -W "bash zsh fish nu" -- "$cur"))
+        return
+    fi
+    COMPREPLY=($(compgen -W "{opts}" -- "$cur"))
+}}
+complete -F _{fn_name} {bin}
+"#,
+            fn_name = BIN_NAME.replace('-', "_"),
+            opts = longs.join(" "),
+            bin = BIN_NAME
+        ),
+        Shell::Zsh => {
+            let mut script = format!("#compdef {}\n\n_arguments \\\n", BIN_NAME);
+            for (long, _, help) in options() {
+                let value = match long {
+                    "--completions" => ":shell:(bash zsh fish nu)",
+                    "--limit" => ":count:",
+                    "--find-hash" => ":hash:",
+                    _ => "",
+                };
+                script.push_str(&format!("  '{}[{}]{}' \\\n", long, help, value));
+            }
+            script.push_str("  '*:: :'\n");
+            script
+        }
+        Shell::Fish => {
+            let mut script = String::new();
+            for (long, short, help) in options() {
+                script.push_str(&format!(
+                    "complete -c {} -l {}",
+                    BIN_NAME,
+                    long.trim_start_matches("--")
+                ));
+                if let Some(short) = short {
+                    script.push_str(&format!(" -s {}", short.trim_start_matches('-')));
+                }
+                if long == "--completions" {
+                    script.push_str(" -x -a 'bash zsh fish nu'");
+                } else if long == "--limit" || long == "--find-hash" {
+                    script.push_str(" -x");
+                }
+                script.push_str(&format!(" -d '{}'\n", help));
+            }
+            script
+        }
+        Shell::Nushell => {
+            let mut script =
+                String::from("def \"nu-complete shells\" [] { [bash zsh fish nu] }\n\n");
+            script.push_str(&format!("export extern \"{}\" [\n", BIN_NAME));
+            for (long, short, help) in options() {
+                let flag = match short {
+                    Some(short) => format!("{}({})", long, short),
+                    None => long.to_string(),
+                };
+                let value = match long {
+                    "--completions" => ": string@\"nu-complete shells\"",
+                    "--limit" => ": int",
+                    "--find-hash" => ": string",
+                    _ => "",
+                };
+                script.push_str(&format!("  {}{}  # {}\n", flag, value, help));
+            }
+            script.push_str("]\n");
+            script
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_list_with_json_and_limit() {
+        let options = parse_args(&args(&["--list", "--json", "--limit", "5"])).unwrap();
+        assert_eq!(options.action, CliAction::List { limit: Some(5) });
+        assert!(options.json);
+    }
+
+    #[test]
+    fn test_parse_emoji_opens_emoji_tab() {
+        let options = parse_args(&args(&["--emoji"])).unwrap();
+        assert_eq!(options.action, CliAction::Run);
+        assert_eq!(options.tab.as_deref(), Some("emoji"));
+        assert_eq!(parse_args(&[]).unwrap().tab, None);
+    }
+
+    #[test]
+    fn test_parse_soak() {
+        let options =
+            parse_args(&args(&["--soak", "2h", "--soak-backend", "sled", "--json"])).unwrap();
+        assert_eq!(
+            options.action,
+            CliAction::Soak {
+                duration: Duration::from_secs(7200),
+                rate: DEFAULT_SOAK_RATE,
+                backend: Some(StorageBackend::Sled),
+            }
+        );
+        let options = parse_args(&args(&["--soak", "90", "--soak-rate", "0"])).unwrap();
+        assert!(matches!(options.action, CliAction::Soak { rate: 0, .. }));
+        assert!(parse_args(&args(&["--soak", "5d"])).is_err());
+        assert!(!help_text("1.0").contains("--soak"));
+    }
+
+    #[test]
+    fn test_parse_paste_recent() {
+        let options = parse_args(&args(&["--paste-recent", "3"])).unwrap();
+        assert_eq!(options.paste_recent, Some(3));
+        assert!(parse_args(&args(&["--paste-recent", "0"])).is_err());
+        assert!(parse_args(&args(&["--paste-recent"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_instance_actions() {
+        let options = parse_args(&args(&["--copy", "--not-an-option", "--paste-last"])).unwrap();
+        assert_eq!(options.copy.as_deref(), Some("--not-an-option"));
+        assert_eq!(options.paste_recent, Some(1));
+        assert!(options.has_instance_action());
+
+        let options = parse_args(&args(&["--open-tab", "GIF", "--search", "foo"])).unwrap();
+        assert_eq!(options.tab.as_deref(), Some("gifs"));
+        assert_eq!(options.search.as_deref(), Some("foo"));
+
+        assert!(parse_args(&args(&["--open-tab", "music"])).is_err());
+        assert!(TAB_NAMES.iter().all(|tab| help_text("1.0").contains(tab)));
+        assert!(parse_args(&args(&["--copy"])).is_err());
+        assert!(!parse_args(&args(&["--background"]))
+            .unwrap()
+            .has_instance_action());
+    }
+
+    #[test]
+    fn test_parse_item_link() {
+        let options = parse_args(&args(&["clip://item/0f8fad5b-d9cb"])).unwrap();
+        assert_eq!(options.open_item.as_deref(), Some("0f8fad5b-d9cb"));
+        assert!(options.has_instance_action());
+        assert!(parse_args(&args(&["clip://item/not an id"])).is_err());
+        assert!(parse_args(&args(&["/home/user/file.txt"]))
+            .unwrap()
+            .open_item
+            .is_none());
+    }
+
+    #[test]
+    fn test_parse_find_hash_round_trips_formatted_hash() {
+        let item = ClipboardItem::new_text("already copied".to_string());
+        let hash = format_hash(item.content_hash());
+        let options = parse_args(&args(&["--find-hash", &hash])).unwrap();
+        assert_eq!(options.action, CliAction::FindHash(item.content_hash()));
+        assert!(parse_args(&args(&["--find-hash", "not-hex"])).is_err());
+    }
+
+    #[test]
+    fn test_hash_input_follows_the_documented_algorithm() {
+        fn fnv1a(bytes: &[u8]) -> u64 {
+            bytes.iter().fold(0xcbf29ce484222325, |hash, &byte| {
+                (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+            })
+        }
+
+        let item = ClipboardItem::new_text("already copied".to_string());
+        assert_eq!(hash_input(b"already copied"), Ok(item.content_hash()));
+        assert_eq!(
+            hash_input(b"already copied"),
+            Ok(fnv1a(b"already copied\xff"))
+        );
+
+        let pixels = image::RgbaImage::from_pixel(2, 1, image::Rgba([1, 2, 3, 4]));
+        let mut png = std::io::Cursor::new(Vec::new());
+        pixels.write_to(&mut png, image::ImageFormat::Png).unwrap();
+        let mut expected = 8usize.to_ne_bytes().to_vec();
+        expected.extend_from_slice(pixels.as_raw());
+        assert_eq!(hash_input(png.get_ref()), Ok(fnv1a(&expected)));
+        assert!(hash_input(&[0xff, 0xfe, 0x00]).is_err());
+        assert_eq!(
+            parse_args(&args(&["--hash"])).unwrap().action,
+            CliAction::Hash
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_unknown_option_and_bad_shell() {
+        assert!(parse_args(&args(&["--frobnicate"])).is_err());
+        assert!(parse_args(&args(&["--completions", "tcsh"])).is_err());
+        assert!(parse_args(&args(&["--limit"])).is_err());
+    }
+
+    #[test]
+    fn test_completions_cover_every_option() {
+        for shell in [Shell::Bash, Shell::Zsh, Shell::Fish, Shell::Nushell] {
+            let script = completion_script(shell);
+            for (long, _, _) in OPTIONS {
+                assert!(
+                    script.contains(long.trim_start_matches("--")),
+                    "{:?} completion is missing {}",
+                    shell,
+                    long
+                );
+            }
+        }
+    }
+}
@@ -1,15 +1,24 @@
 //! Clipboard Manager Module
 //! Handles clipboard monitoring, history storage, and paste injection
 
+use crate::content_classifier::{self, HiddenChars};
+use crate::entity_detection::{self, Entity};
+use crate::history_store::{HistoryStore, JsonFileStore, MemoryStore};
+use crate::large_text::{self, LargeText};
+use crate::markdown;
+use crate::memory_budget;
+use crate::metrics;
+use crate::pinned_git::{self, PinnedGitMirror};
+use crate::snippet_packs::{self, Snippet};
 use arboard::{Clipboard, ImageData};
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
 use chrono::{DateTime, Utc};
 use image::{DynamicImage, ImageFormat};
 use serde::{Deserialize, Serialize};
-use std::fs;
+use std::collections::{HashMap, HashSet};
 use std::hash::{Hash, Hasher};
 use std::io::Cursor;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::thread;
 use std::time::Duration;
 use uuid::Uuid;
@@ -20,6 +29,14 @@ pub const DEFAULT_MAX_HISTORY_SIZE: usize = 50;
 const PREVIEW_TEXT_MAX_LEN: usize = 100;
 const GIF_CACHE_MARKER: &str = "win11-clipboard-history/gifs/";
 const FILE_URI_PREFIX: &str = "file://";
+/// Upper bound for per-type retention windows (10 years)
+pub const MAX_RETENTION_DAYS: u32 = 3650;
+/// A paste counts half as much toward the frequent list after this long
+const PASTE_SCORE_HALF_LIFE_DAYS: f64 = 7.0;
+/// Score an item needs to be listed as frequent, about two recent pastes
+const MIN_FREQUENT_SCORE: f64 = 1.5;
+/// Longest tag, in characters
+const MAX_TAG_LEN: usize = 32;
 
 // --- Helper Functions ---
 
@@ -48,13 +65,43 @@ impl Hasher for FnvHasher {
     }
 }
 
-/// Calculates a stable hash for any hashable data.
+/// Calculates a stable hash for any hashable data: 64-bit FNV-1a over the
+/// bytes Rust's `Hash` feeds the hasher. For a text that is its UTF-8 bytes
+/// followed by 0xff; for image RGBA bytes it is their length as a usize in
+/// native byte order (8 little-endian bytes on x86_64 and aarch64) followed
+/// by the bytes. `--hash` prints it for stdin.
 pub fn calculate_hash<T: Hash>(t: &T) -> u64 {
     let mut s = FnvHasher::default();
     t.hash(&mut s);
     s.finish()
 }
 
+/// Formats a content hash for external tools (16 hex digits, since JSON
+/// numbers lose precision above 2^53)
+pub fn format_hash(hash: u64) -> String {
+    format!("{:016x}", hash)
+}
+
+/// Parses a hash produced by `format_hash`
+pub fn parse_hash(hash: &str) -> Option<u64> {
+    u64::from_str_radix(hash.trim().trim_start_matches("0x"), 16).ok()
+}
+
+/// A tag as it is stored: trimmed, lowercase, without a leading '#', with
+/// whitespace runs as '-' and at most `MAX_TAG_LEN` characters. None when
+/// nothing is left.
+pub fn normalize_tag(tag: &str) -> Option<String> {
+    let tag = tag.trim().trim_start_matches('#').to_lowercase();
+    let tag: String = tag
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join("-")
+        .chars()
+        .take(MAX_TAG_LEN)
+        .collect();
+    (!tag.is_empty()).then_some(tag)
+}
+
 /// Helper to get a fresh clipboard instance.
 fn get_system_clipboard() -> Result<Clipboard, String> {
     Clipboard::new().map_err(|e| e.to_string())
@@ -70,7 +117,8 @@ pub enum ClipboardContent {
     Text(String),
     /// Rich text with HTML formatting (plain text + optional HTML)
     RichText { plain: String, html: String },
-    /// Image as base64 encoded PNG
+    /// Image as base64 encoded PNG, or WebP/JPEG once `image_compression`
+    /// re-encoded it
     Image {
         base64: String,
         width: u32,
@@ -78,6 +126,16 @@ pub enum ClipboardContent {
     },
 }
 
+/// Which X11/Wayland selection a listener watches
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SelectionKind {
+    /// Explicit copy (Ctrl+C)
+    #[default]
+    Clipboard,
+    /// Highlighted text (middle-click paste)
+    Primary,
+}
+
 /// A single clipboard history item
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ClipboardItem {
@@ -91,6 +149,64 @@ pub struct ClipboardItem {
     pub pinned: bool,
     /// Preview text (for display)
     pub preview: String,
+    /// WM_CLASS or app id of the window the item was copied from, if known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_app: Option<String>,
+    /// Invisible, bidi and non-breaking space characters in the text, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hidden_chars: Option<HiddenChars>,
+    /// Emails, phone numbers, IPs and paths in the text, see `entity_detection`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub entities: Vec<Entity>,
+    /// Set when the text reads as Markdown, so the preview can render it
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub markdown: bool,
+    /// Set on texts too large to keep inline; the content is then only the
+    /// start of the text, see `large_text`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub large_text: Option<LargeText>,
+    /// Set once the data of an image moved to disk under the memory budget;
+    /// its base64 is empty then, see `memory_budget`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub evicted: bool,
+    /// Set on copied files once one of them no longer exists
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub files_missing: bool,
+    /// How often and how lately the item was pasted, if it was
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub paste_frequency: Option<PasteFrequency>,
+    /// User labels such as "work" or "todo", normalized by `normalize_tag`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Id of the collection the item is filed in, see `collections`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub collection: Option<String>,
+}
+
+/// Pastes of an item as a score that halves every
+/// `PASTE_SCORE_HALF_LIFE_DAYS`, so daily pastes keep it high and a burst of
+/// pastes last month fades out
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct PasteFrequency {
+    /// The score as of `last_pasted`
+    pub score: f64,
+    pub last_pasted: DateTime<Utc>,
+}
+
+impl PasteFrequency {
+    /// The score decayed until `now`
+    pub fn score_at(&self, now: DateTime<Utc>) -> f64 {
+        let days = (now - self.last_pasted).num_seconds().max(0) as f64 / 86_400.0;
+        self.score * 0.5f64.powf(days / PASTE_SCORE_HALF_LIFE_DAYS)
+    }
+
+    /// Adds a paste made at `now`
+    pub fn pasted(frequency: Option<Self>, now: DateTime<Utc>) -> Self {
+        Self {
+            score: frequency.map_or(0.0, |f| f.score_at(now)) + 1.0,
+            last_pasted: now,
+        }
+    }
 }
 
 impl ClipboardItem {
@@ -136,12 +252,66 @@ impl ClipboardItem {
     }
 
     fn create(content: ClipboardContent, preview: String) -> Self {
-        Self {
+        let mut item = Self {
             id: Uuid::new_v4().to_string(),
             content,
             timestamp: Utc::now(),
             pinned: false,
             preview,
+            source_app: None,
+            hidden_chars: None,
+            entities: Vec::new(),
+            markdown: false,
+            large_text: None,
+            evicted: false,
+            files_missing: false,
+            paste_frequency: None,
+            tags: Vec::new(),
+            collection: None,
+        };
+        item.hidden_chars = item
+            .text()
+            .map(content_classifier::count_hidden_chars)
+            .filter(|counts| !counts.is_empty());
+        item.entities = item
+            .text()
+            .map(entity_detection::detect)
+            .unwrap_or_default();
+        item.markdown = item.text().is_some_and(markdown::looks_like_markdown);
+        item
+    }
+
+    /// Pinned or filed in a collection; history limits, retention and
+    /// clearing leave these alone
+    pub fn is_kept(&self) -> bool {
+        self.pinned || self.collection.is_some()
+    }
+
+    /// Local paths of copied files, empty for anything else
+    pub fn file_paths(&self) -> Vec<PathBuf> {
+        match self.text() {
+            Some(text) if is_file_reference(text) => file_reference_paths(text),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Whether this is a file reference to a file that is gone now
+    pub fn has_missing_files(&self) -> bool {
+        self.file_paths().iter().any(|path| !path.exists())
+    }
+
+    /// Stable hash of the item's content, the same one used for deduplication.
+    /// Rich text hashes its plain text so it matches a plain copy of the same text.
+    pub fn content_hash(&self) -> u64 {
+        if let Some(large) = &self.large_text {
+            return large.hash;
+        }
+        match &self.content {
+            ClipboardContent::Text(text) => calculate_hash(text),
+            ClipboardContent::RichText { plain, .. } => calculate_hash(plain),
+            ClipboardContent::Image { base64, .. } => self
+                .extract_image_hash()
+                .unwrap_or_else(|| calculate_hash(base64)),
         }
     }
 
@@ -156,6 +326,125 @@ impl ClipboardItem {
             .nth(1)
             .and_then(|h| h.parse::<u64>().ok())
     }
+
+    /// Plain text of a text or rich text item
+    pub fn text(&self) -> Option<&str> {
+        match &self.content {
+            ClipboardContent::Text(text) => Some(text),
+            ClipboardContent::RichText { plain, .. } => Some(plain),
+            ClipboardContent::Image { .. } => None,
+        }
+    }
+}
+
+/// How long unpinned items of each kind are kept, in days. 0 keeps them forever.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RetentionPolicy {
+    pub text_days: u32,
+    pub image_days: u32,
+    pub file_days: u32,
+}
+
+impl RetentionPolicy {
+    /// Retention window that applies to the given item
+    fn days_for(&self, item: &ClipboardItem) -> u32 {
+        match &item.content {
+            ClipboardContent::Image { .. } => self.image_days,
+            ClipboardContent::Text(text) if is_file_reference(text) => self.file_days,
+            _ => self.text_days,
+        }
+    }
+
+    /// Whether the item is older than its retention window
+    fn is_expired(&self, item: &ClipboardItem, now: DateTime<Utc>) -> bool {
+        match self.days_for(item) {
+            0 => false,
+            days => now - item.timestamp > chrono::Duration::days(i64::from(days)),
+        }
+    }
+}
+
+/// Whether `item` holds the text with `text_hash`; large texts only have
+/// their start inline, so they compare by hash
+fn holds_text(item: &ClipboardItem, text: &str, text_hash: u64) -> bool {
+    if let Some(large) = &item.large_text {
+        return large.hash == text_hash;
+    }
+    match &item.content {
+        ClipboardContent::Text(t) => t == text,
+        ClipboardContent::RichText { plain, .. } => plain == text,
+        _ => false,
+    }
+}
+
+/// Copied files arrive as text with file:// URIs
+fn is_file_reference(text: &str) -> bool {
+    text.trim_start().starts_with(FILE_URI_PREFIX)
+}
+
+/// Paths of the file:// URIs in a copied file list, one URI per line. Lines
+/// like the "copy" marker of GNOME's file list format are skipped.
+fn file_reference_paths(text: &str) -> Vec<PathBuf> {
+    text.lines()
+        .filter_map(|line| line.trim().strip_prefix(FILE_URI_PREFIX))
+        .map(|rest| rest.strip_prefix("localhost").unwrap_or(rest))
+        .map(|path| PathBuf::from(percent_decode(path)))
+        .collect()
+}
+
+/// Decodes %XX escapes, as in "My%20Files"
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%')
+            .then(|| text.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Which kinds of content are recorded at all
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CapturePolicy {
+    pub text: bool,
+    pub images: bool,
+    pub files: bool,
+    /// Keep the HTML of rich text; when off only the plain text is stored
+    pub html: bool,
+}
+
+impl Default for CapturePolicy {
+    fn default() -> Self {
+        Self {
+            text: true,
+            images: true,
+            files: true,
+            html: true,
+        }
+    }
+}
+
+impl CapturePolicy {
+    fn allows_text(&self, text: &str) -> bool {
+        if is_file_reference(text) {
+            self.files
+        } else {
+            self.text
+        }
+    }
 }
 
 // --- Manager Logic ---
@@ -168,10 +457,27 @@ pub struct ClipboardManager {
     last_pasted_image_hash: Option<u64>,
     /// Track last added text hash to prevent duplicates from rapid copies
     last_added_text_hash: Option<u64>,
-    /// Path to save the history file
-    persistence_path: PathBuf,
+    /// Where the history is persisted
+    store: Box<dyn HistoryStore>,
+    /// The configured store, set aside while session-only mode keeps history in memory
+    parked_store: Option<Box<dyn HistoryStore>>,
+    /// Location of the last store on disk, whose large texts and evicted
+    /// images items still point to after a switch to a store without one
+    data_location: Option<PathBuf>,
     /// Maximum number of history items to keep
     max_history_size: usize,
+    /// Age limits per content type
+    retention: RetentionPolicy,
+    /// Content types that are recorded
+    capture: CapturePolicy,
+    /// Optional git-tracked copy of the pinned items
+    pinned_mirror: Option<PinnedGitMirror>,
+    /// Nothing is recorded while set, e.g. from the tray
+    paused: bool,
+    /// Bytes of item data kept in memory before images are evicted; 0 for no limit
+    memory_budget: usize,
+    /// Called after every change to the history, with the manager locked
+    change_listener: Option<Box<dyn Fn() + Send>>,
 }
 
 impl ClipboardManager {
@@ -183,7 +489,15 @@ impl ClipboardManager {
         }
     }
 
+    /// Creates a manager that persists history to a single JSON file
     pub fn new(persistence_path: PathBuf, max_history_size: usize) -> Self {
+        Self::with_store(
+            Box::new(JsonFileStore::new(persistence_path)),
+            max_history_size,
+        )
+    }
+
+    pub fn with_store(store: Box<dyn HistoryStore>, max_history_size: usize) -> Self {
         // Normalize the requested max size and avoid huge allocations
         let max_size = Self::clamp_max_history_size(max_history_size);
         let mut manager = Self {
@@ -191,8 +505,16 @@ impl ClipboardManager {
             last_pasted_text: None,
             last_pasted_image_hash: None,
             last_added_text_hash: None,
-            persistence_path,
+            data_location: store.location().map(Path::to_path_buf),
+            store,
+            parked_store: None,
             max_history_size: max_size,
+            retention: RetentionPolicy::default(),
+            capture: CapturePolicy::default(),
+            pinned_mirror: None,
+            paused: false,
+            memory_budget: 0,
+            change_listener: None,
         };
         manager.load_history();
         manager
@@ -201,11 +523,11 @@ impl ClipboardManager {
     /// Updates the maximum history size and enforces the new limit
     pub fn set_max_history_size(&mut self, new_size: usize) {
         let mut clamped = Self::clamp_max_history_size(new_size);
-        // Do not set max less than number of pinned items; we won't delete pins automatically
-        let pinned_count = self.history.iter().filter(|i| i.pinned).count();
+        // Do not set max less than number of kept items; we won't delete pins automatically
+        let pinned_count = self.history.iter().filter(|i| i.is_kept()).count();
         if clamped < pinned_count {
             eprintln!(
-                "clipboard_manager: requested max history size ({}) is less than the number of pinned items ({}); increasing limit to preserve pinned items.",
+                "clipboard_manager: requested max history size ({}) is less than the number of pinned and filed items ({}); increasing limit to preserve them.",
                 clamped,
                 pinned_count
             );
@@ -223,74 +545,243 @@ impl ClipboardManager {
         self.max_history_size
     }
 
-    fn load_history(&mut self) {
-        if !self.persistence_path.exists() {
+    /// Updates the retention policy and prunes anything it expires
+    pub fn set_retention_policy(&mut self, policy: RetentionPolicy) {
+        self.retention = policy;
+        self.prune_expired();
+    }
+
+    /// Gets the current retention policy
+    pub fn get_retention_policy(&self) -> RetentionPolicy {
+        self.retention
+    }
+
+    /// Updates which content types are recorded from now on
+    pub fn set_capture_policy(&mut self, policy: CapturePolicy) {
+        self.capture = policy;
+    }
+
+    /// Sets the memory budget in megabytes (0 for none) and evicts images
+    /// past it right away
+    pub fn set_memory_budget(&mut self, megabytes: u32) {
+        self.memory_budget = megabytes as usize * 1024 * 1024;
+        if self.enforce_memory_budget() > 0 {
+            self.save_history();
+        }
+    }
+
+    pub fn get_capture_policy(&self) -> CapturePolicy {
+        self.capture
+    }
+
+    /// Stops or resumes recording. Not saved, capture is back on after a restart.
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Sets what to call after the history changes. It runs with the manager
+    /// locked, so it should only hand the news to another thread.
+    pub fn set_change_listener(&mut self, listener: impl Fn() + Send + 'static) {
+        self.change_listener = Some(Box::new(listener));
+    }
+
+    /// Switches the persistence backend, moving the current history into it.
+    /// The previous backend's data is left in place.
+    pub fn set_store(&mut self, mut store: Box<dyn HistoryStore>) {
+        if let Some(location) = store.location() {
+            self.data_location = Some(location.to_path_buf());
+        }
+        if let Some(parked) = &mut self.parked_store {
+            // Session-only: move what is on disk, not the session's items
+            match parked.load().and_then(|items| store.save(&items)) {
+                Ok(()) => self.parked_store = Some(store),
+                Err(e) => eprintln!("Failed to move history to the new store: {}", e),
+            }
             return;
         }
+        self.store = store;
+        self.save_history();
+    }
 
-        match fs::read_to_string(&self.persistence_path) {
-            Ok(content) => {
-                match serde_json::from_str::<Vec<ClipboardItem>>(&content) {
-                    Ok(items) => {
-                        // Reorder items so pinned come first while preserving order within each group
-                        let mut pinned_items = Vec::new();
-                        let mut unpinned_items = Vec::new();
-
-                        for item in items {
-                            if item.pinned {
-                                pinned_items.push(item);
-                            } else {
-                                unpinned_items.push(item);
-                            }
-                        }
-
-                        pinned_items.extend(unpinned_items);
-                        self.history = pinned_items;
-                        // Ensure loaded history respects configured limit immediately
-                        let history_trimmed = self.enforce_history_limit();
-                        // If the loaded history was trimmed, persist it so disk stays in sync.
-                        // Avoid saving when nothing changed.
-                        if history_trimmed {
-                            self.save_history();
-                        }
-                        // Initialize last_added_text_hash from the most recent item (even if pinned)
-                        // This prevents duplication on startup if the clipboard content matches the top item
-                        if let Some(first) = self.history.first() {
-                            match &first.content {
-                                ClipboardContent::Text(text) => {
-                                    self.last_added_text_hash = Some(calculate_hash(text));
-                                }
-                                ClipboardContent::RichText { plain, .. } => {
-                                    self.last_added_text_hash = Some(calculate_hash(plain));
-                                }
-                                ClipboardContent::Image { .. } => {
-                                    if let Some(_hash) = first.extract_image_hash() {
-                                        // We don't have a separate last_added_image_hash,
-                                        // but we can at least avoid text hash collision
-                                        self.last_added_text_hash = None;
-                                    }
-                                }
-                            }
-                        }
-                    }
-                    Err(e) => eprintln!("Failed to parse history: {}", e),
-                }
+    /// The configured store, also while session-only mode is active
+    pub fn store(&self) -> &dyn HistoryStore {
+        self.parked_store.as_deref().unwrap_or(self.store.as_ref())
+    }
+
+    /// Session-only mode: capture continues, but history is kept in memory and
+    /// nothing is written to disk. Leaving it discards everything captured or
+    /// changed during the session and reloads the history from disk.
+    pub fn set_session_only(&mut self, enabled: bool) {
+        if enabled == self.is_session_only() {
+            return;
+        }
+
+        if enabled {
+            let store = std::mem::replace(&mut self.store, Box::new(MemoryStore::default()));
+            self.parked_store = Some(store);
+        } else if let Some(store) = self.parked_store.take() {
+            self.store = store;
+            self.history.clear();
+            self.last_added_text_hash = None;
+            self.load_history();
+        }
+    }
+
+    pub fn is_session_only(&self) -> bool {
+        self.parked_store.is_some()
+    }
+
+    /// Whether history (and derived data such as thumbnails) may be written to disk
+    pub fn persists_to_disk(&self) -> bool {
+        !self.is_session_only() && self.store.location().is_some()
+    }
+
+    /// Enables or disables the git mirror of pinned items, syncing it right away
+    pub fn set_pinned_mirror(&mut self, mirror: Option<PinnedGitMirror>) {
+        if mirror.is_none() {
+            pinned_git::clear_error();
+        }
+        self.pinned_mirror = mirror;
+        if let Some(mirror) = self
+            .pinned_mirror
+            .as_ref()
+            .filter(|_| !self.is_session_only())
+        {
+            mirror.sync(&self.history, |item| self.with_full_content(item));
+        }
+    }
+
+    pub fn pinned_mirror_dir(&self) -> Option<&Path> {
+        self.pinned_mirror.as_ref().map(|mirror| mirror.dir())
+    }
+
+    /// Removes unpinned items older than their retention window.
+    /// Returns true if anything was removed (history is saved in that case).
+    pub fn prune_expired(&mut self) -> bool {
+        let pruned = self.remove_expired(Utc::now());
+        if pruned {
+            self.save_history();
+        }
+        pruned
+    }
+
+    /// Marks copied files that no longer exist, or removes them when `prune`
+    /// is set (pinned ones are only marked). Returns true if anything changed
+    /// (history is saved in that case).
+    pub fn check_file_references(&mut self, prune: bool) -> bool {
+        let mut changed = false;
+        self.history.retain_mut(|item| {
+            if item.file_paths().is_empty() {
+                return true;
+            }
+            let missing = item.has_missing_files();
+            if missing && prune && !item.is_kept() {
+                changed = true;
+                return false;
             }
-            Err(e) => eprintln!("Failed to read history file: {}", e),
+            changed |= item.files_missing != missing;
+            item.files_missing = missing;
+            true
+        });
+        if changed {
+            self.save_history();
         }
+        changed
+    }
+
+    fn remove_expired(&mut self, now: DateTime<Utc>) -> bool {
+        let before = self.history.len();
+        let retention = self.retention;
+        self.history
+            .retain(|item| item.is_kept() || !retention.is_expired(item, now));
+        self.history.len() != before
     }
 
-    fn save_history(&self) {
-        match serde_json::to_string_pretty(&self.history) {
-            Ok(content) => {
-                if let Some(parent) = self.persistence_path.parent() {
-                    let _ = fs::create_dir_all(parent);
+    fn load_history(&mut self) {
+        let items = match self.store.load() {
+            Ok(items) => items,
+            Err(e) => {
+                eprintln!("Failed to load history: {}", e);
+                return;
+            }
+        };
+
+        // Reorder items so pinned come first while preserving order within each group
+        let mut pinned_items = Vec::new();
+        let mut unpinned_items = Vec::new();
+
+        for item in items {
+            if item.pinned {
+                pinned_items.push(item);
+            } else {
+                unpinned_items.push(item);
+            }
+        }
+
+        pinned_items.extend(unpinned_items);
+        self.history = pinned_items;
+        // Ensure loaded history respects configured limit immediately
+        let history_trimmed = self.enforce_history_limit();
+        // If the loaded history was trimmed, persist it so disk stays in sync.
+        // Avoid saving when nothing changed.
+        if history_trimmed {
+            self.save_history();
+        }
+        // Initialize last_added_text_hash from the most recent item (even if pinned)
+        // This prevents duplication on startup if the clipboard content matches the top item
+        if let Some(first) = self.history.first() {
+            match &first.content {
+                ClipboardContent::Text(text) => {
+                    self.last_added_text_hash = Some(calculate_hash(text));
+                }
+                ClipboardContent::RichText { plain, .. } => {
+                    self.last_added_text_hash = Some(calculate_hash(plain));
                 }
-                if let Err(e) = fs::write(&self.persistence_path, content) {
-                    eprintln!("Failed to save history: {}", e);
+                ClipboardContent::Image { .. } => {
+                    if let Some(_hash) = first.extract_image_hash() {
+                        // We don't have a separate last_added_image_hash,
+                        // but we can at least avoid text hash collision
+                        self.last_added_text_hash = None;
+                    }
                 }
             }
-            Err(e) => eprintln!("Failed to serialize history: {}", e),
+        }
+    }
+
+    fn save_history(&mut self) {
+        // First, so pinned images it loads back are mirrored with their data
+        self.enforce_memory_budget();
+        if let Some(mirror) = self
+            .pinned_mirror
+            .as_ref()
+            .filter(|_| !self.is_session_only())
+        {
+            mirror.sync(&self.history, |item| self.with_full_content(item));
+        }
+
+        if let Err(e) = self.store.save(&self.history) {
+            eprintln!("Failed to save history: {}", e);
+        }
+        if let Some(dir) = self
+            .large_text_dir()
+            .filter(|dir| self.persists_to_disk() && dir.exists())
+        {
+            let keep: Vec<u64> = self
+                .history
+                .iter()
+                .filter_map(|item| item.large_text.as_ref().map(|large| large.hash))
+                .collect();
+            large_text::prune(&dir, &keep);
+        }
+        if let Some(dir) = self.blob_dir().filter(|_| self.persists_to_disk()) {
+            memory_budget::prune(&dir, &self.history);
+        }
+        if let Some(listener) = &self.change_listener {
+            listener();
         }
     }
 
@@ -332,9 +823,20 @@ impl ClipboardManager {
 
     /// Add text content to history, with optional HTML for rich text
     pub fn add_text(&mut self, text: String, html: Option<String>) -> Option<ClipboardItem> {
-        if self.should_skip_text(&text) {
+        self.add_text_from(text, html, None)
+    }
+
+    /// Like `add_text`, recording the app the text was copied from
+    pub fn add_text_from(
+        &mut self,
+        text: String,
+        html: Option<String>,
+        source_app: Option<String>,
+    ) -> Option<ClipboardItem> {
+        if self.paused || !self.capture.allows_text(&text) || self.should_skip_text(&text) {
             return None;
         }
+        let html = html.filter(|_| self.capture.html);
 
         let text_hash = calculate_hash(&text);
 
@@ -345,31 +847,58 @@ impl ClipboardManager {
 
         // Check if this exact text is already the most recent non-pinned item
         // If so, skip entirely - no need to add or move
-        if self.is_duplicate_text(&text) {
+        if self.is_duplicate_text(&text, text_hash) {
             self.last_added_text_hash = Some(text_hash);
             return None;
         }
 
         // Check if this text exists elsewhere in history (not at top)
         // If so, remove the old entry so we can add fresh at top
-        self.remove_duplicate_text_from_history(&text);
+        let old = self.remove_duplicate_text_from_history(&text, text_hash);
+
+        let mut item = self.text_item(text, html, text_hash);
+        item.source_app = source_app;
+        if let Some(old) = old {
+            item.paste_frequency = old.paste_frequency;
+            item.tags = old.tags;
+            item.collection = old.collection;
+        }
+        self.insert_item(item.clone());
+        metrics::record_capture();
+
+        self.last_added_text_hash = Some(text_hash);
+
+        Some(item)
+    }
+
+    /// A new item for `text`, as rich text when there is HTML
+    fn text_item(&self, text: String, html: Option<String>, text_hash: u64) -> ClipboardItem {
+        // Large texts keep only their start inline, without the HTML
+        let large = self
+            .large_text_dir()
+            .filter(|_| self.persists_to_disk() && large_text::is_large(&text))
+            .and_then(|dir| {
+                large_text::store(&dir, &text, text_hash)
+                    .map_err(|e| eprintln!("[ClipboardManager] {}", e))
+                    .ok()
+            });
 
         // Create new item - use RichText if HTML is available, otherwise plain Text
-        let item = match html {
+        let mut item = match html {
+            _ if large.is_some() => {
+                ClipboardItem::new_text(large_text::inline_part(&text).to_string())
+            }
             Some(html_content) if !html_content.trim().is_empty() => {
                 ClipboardItem::new_rich_text(text, html_content)
             }
             _ => ClipboardItem::new_text(text),
         };
-        self.insert_item(item.clone());
-
-        self.last_added_text_hash = Some(text_hash);
-
-        Some(item)
+        item.large_text = large;
+        item
     }
 
     pub fn add_image(&mut self, image_data: ImageData<'_>, hash: u64) -> Option<ClipboardItem> {
-        if self.should_skip_image(hash) {
+        if self.paused || !self.capture.images || self.should_skip_image(hash) {
             return None;
         }
 
@@ -383,6 +912,7 @@ impl ClipboardManager {
         );
 
         self.insert_item(item.clone());
+        metrics::record_capture();
         Some(item)
     }
 
@@ -432,31 +962,91 @@ impl ClipboardManager {
         false
     }
 
-    fn is_duplicate_text(&self, text: &str) -> bool {
+    fn is_duplicate_text(&self, text: &str, text_hash: u64) -> bool {
         // Check only the very first non-pinned item for exact match logic
         // used in rapid detection
-        if let Some(item) = self.history.iter().find(|item| !item.pinned) {
-            match &item.content {
-                ClipboardContent::Text(t) if t == text => return true,
-                ClipboardContent::RichText { plain, .. } if plain == text => return true,
-                _ => {}
-            }
+        self.history
+            .iter()
+            .find(|item| !item.pinned)
+            .is_some_and(|item| holds_text(item, text, text_hash))
+    }
+
+    fn remove_duplicate_text_from_history(
+        &mut self,
+        text: &str,
+        text_hash: u64,
+    ) -> Option<ClipboardItem> {
+        let pos = self
+            .history
+            .iter()
+            .position(|item| !item.pinned && holds_text(item, text, text_hash))?;
+        Some(self.history.remove(pos))
+    }
+
+    /// Where the configured store keeps its data, or the last store that
+    /// kept it on disk
+    fn data_location(&self) -> Option<&Path> {
+        self.store().location().or(self.data_location.as_deref())
+    }
+
+    /// Where large texts are, also once session-only mode or the memory
+    /// backend keeps new ones from being written
+    fn large_text_dir(&self) -> Option<PathBuf> {
+        self.data_location().and_then(large_text::dir_for)
+    }
+
+    /// Where evicted images are, likewise
+    fn blob_dir(&self) -> Option<PathBuf> {
+        self.data_location().and_then(memory_budget::dir_for)
+    }
+
+    /// Evicts images past the memory budget, returning how many items changed
+    fn enforce_memory_budget(&mut self) -> usize {
+        if self.memory_budget == 0 || !self.persists_to_disk() {
+            return 0;
+        }
+        match self.blob_dir() {
+            Some(dir) => memory_budget::enforce(&mut self.history, self.memory_budget, &dir),
+            None => 0,
         }
-        false
     }
 
-    fn remove_duplicate_text_from_history(&mut self, text: &str) {
-        if let Some(pos) = self.history.iter().position(|item| {
-            if item.pinned {
-                return false;
-            }
-            match &item.content {
-                ClipboardContent::Text(t) => t == text,
-                ClipboardContent::RichText { plain, .. } => plain == text,
-                _ => false,
+    /// The item with the data of an evicted image loaded back; others as they are
+    pub fn with_image_data(&self, item: &ClipboardItem) -> Result<ClipboardItem, String> {
+        if !item.evicted {
+            return Ok(item.clone());
+        }
+        let dir = self.blob_dir().ok_or("The image is no longer available")?;
+        let data = memory_budget::load(&dir, item)?;
+        let mut loaded = item.clone();
+        if let ClipboardContent::Image { base64, .. } = &mut loaded.content {
+            *base64 = data;
+        }
+        loaded.evicted = false;
+        Ok(loaded)
+    }
+
+    /// The item with the data of an evicted image or the whole of a large
+    /// text loaded back, for copies of it outside the history
+    pub fn with_full_content(&self, item: &ClipboardItem) -> Result<ClipboardItem, String> {
+        let mut loaded = self.with_image_data(item)?;
+        if item.large_text.is_some() {
+            loaded.content = ClipboardContent::Text(self.full_text(item)?.unwrap_or_default());
+            loaded.large_text = None;
+        }
+        Ok(loaded)
+    }
+
+    /// The whole text of a text item, read back from its file for large texts
+    pub fn full_text(&self, item: &ClipboardItem) -> Result<Option<String>, String> {
+        match &item.large_text {
+            Some(large) => {
+                let dir = self
+                    .large_text_dir()
+                    .ok_or("The full text is no longer available")?;
+                large_text::load(&dir, large).map(Some)
             }
-        }) {
-            self.history.remove(pos);
+            None => Ok(item.text().map(str::to_string)),
         }
     }
 
@@ -475,6 +1065,11 @@ impl ClipboardManager {
     }
 
     fn insert_item(&mut self, item: ClipboardItem) {
+        self.insert_items(vec![item]);
+    }
+
+    /// Puts `items` at the top in their order, saving once
+    fn insert_items(&mut self, items: Vec<ClipboardItem>) {
         // Insert after pinned items (first non-pinned slot)
         // If all items are pinned, insert at the end to preserve pinned ordering
         let insert_pos = self
@@ -482,9 +1077,10 @@ impl ClipboardManager {
             .iter()
             .position(|i| !i.pinned)
             .unwrap_or(self.history.len());
-        self.history.insert(insert_pos, item);
+        self.history.splice(insert_pos..insert_pos, items);
 
         // Trim history
+        self.remove_expired(Utc::now());
         self.enforce_history_limit();
         self.save_history();
     }
@@ -493,11 +1089,11 @@ impl ClipboardManager {
     fn enforce_history_limit(&mut self) -> bool {
         let before = self.history.len();
         while self.history.len() > self.max_history_size {
-            // Remove from the end, skipping pinned items if possible
-            if let Some(pos) = self.history.iter().rposition(|i| !i.pinned) {
+            // Remove from the end, skipping pinned and filed items if possible
+            if let Some(pos) = self.history.iter().rposition(|i| !i.is_kept()) {
                 self.history.remove(pos);
             } else {
-                // All items are pinned. We stopped removing to avoid deleting pins.
+                // All items are kept. We stopped removing to avoid deleting pins.
                 break;
             }
         }
@@ -510,12 +1106,221 @@ impl ClipboardManager {
         self.history.clone()
     }
 
+    /// The history without cloning it, for read-only aggregation
+    pub fn items(&self) -> &[ClipboardItem] {
+        &self.history
+    }
+
+    /// Finds the item whose content hash matches, see `ClipboardItem::content_hash`
+    pub fn find_by_hash(&self, hash: u64) -> Option<&ClipboardItem> {
+        self.history.iter().find(|item| item.content_hash() == hash)
+    }
+
+    /// The item copied `position`-th most recently (1 = latest), pinned or not
+    pub fn nth_recent(&self, position: usize) -> Option<&ClipboardItem> {
+        self.recent(position).get(position.checked_sub(1)?).copied()
+    }
+
+    /// Up to `count` items, most recently copied first
+    pub fn recent(&self, count: usize) -> Vec<&ClipboardItem> {
+        let mut items: Vec<&ClipboardItem> = self.history.iter().collect();
+        items.sort_by_key(|item| std::cmp::Reverse(item.timestamp));
+        items.truncate(count);
+        items
+    }
+
+    /// Up to `count` items pasted often and lately, highest score first
+    pub fn frequent(&self, count: usize, now: DateTime<Utc>) -> Vec<&ClipboardItem> {
+        let mut scored: Vec<(f64, &ClipboardItem)> = self
+            .history
+            .iter()
+            .filter_map(|item| Some((item.paste_frequency?.score_at(now), item)))
+            .filter(|(score, _)| *score >= MIN_FREQUENT_SCORE)
+            .collect();
+        scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+        scored
+            .into_iter()
+            .take(count)
+            .map(|(_, item)| item)
+            .collect()
+    }
+
+    /// Replaces the tags of an item, normalized, sorted and without repeats
+    pub fn set_tags(&mut self, id: &str, tags: &[String]) -> Option<ClipboardItem> {
+        let item = self.history.iter_mut().find(|item| item.id == id)?;
+        let mut tags: Vec<String> = tags.iter().filter_map(|tag| normalize_tag(tag)).collect();
+        tags.sort();
+        tags.dedup();
+        item.tags = tags;
+        let item_clone = item.clone();
+        self.save_history();
+        Some(item_clone)
+    }
+
+    /// Items carrying `tag`, in history order
+    pub fn with_tag(&self, tag: &str) -> Vec<&ClipboardItem> {
+        let Some(tag) = normalize_tag(tag) else {
+            return Vec::new();
+        };
+        self.history
+            .iter()
+            .filter(|item| item.tags.contains(&tag))
+            .collect()
+    }
+
+    /// Items copied from an app whose WM_CLASS or app id contains `app`,
+    /// ignoring case, so "code" finds "Code" and "firefox" finds
+    /// "org.mozilla.firefox"
+    pub fn copied_from(&self, app: &str) -> Vec<&ClipboardItem> {
+        let app = app.trim().to_lowercase();
+        if app.is_empty() {
+            return Vec::new();
+        }
+        self.history
+            .iter()
+            .filter(|item| {
+                item.source_app
+                    .as_deref()
+                    .is_some_and(|source| source.to_lowercase().contains(&app))
+            })
+            .collect()
+    }
+
+    /// Up to `count` tags in use that start with `prefix`, the most used first
+    pub fn tag_suggestions(&self, prefix: &str, count: usize) -> Vec<String> {
+        let prefix = normalize_tag(prefix).unwrap_or_default();
+        let mut uses: HashMap<&str, usize> = HashMap::new();
+        for tag in self.history.iter().flat_map(|item| &item.tags) {
+            if tag.starts_with(&prefix) {
+                *uses.entry(tag).or_default() += 1;
+            }
+        }
+        let mut tags: Vec<(&str, usize)> = uses.into_iter().collect();
+        tags.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        tags.into_iter()
+            .take(count)
+            .map(|(tag, _)| tag.to_string())
+            .collect()
+    }
+
+    /// Files the item into a collection, or takes it out of its collection
+    /// with None
+    pub fn file_item(&mut self, id: &str, collection: Option<String>) -> Option<ClipboardItem> {
+        let item = self.history.iter_mut().find(|item| item.id == id)?;
+        item.collection = collection;
+        let item_clone = item.clone();
+        self.save_history();
+        Some(item_clone)
+    }
+
+    /// Items filed in the collection, in history order
+    pub fn in_collection(&self, collection: &str) -> Vec<&ClipboardItem> {
+        self.history
+            .iter()
+            .filter(|item| item.collection.as_deref() == Some(collection))
+            .collect()
+    }
+
+    /// Files the snippets of an imported pack into `collection`. Snippets an
+    /// earlier import of the pack added are replaced, keeping their pin and
+    /// paste counts, and the ones the pack no longer has are taken out of
+    /// the collection. New snippets go below the rest of history.
+    pub fn import_snippets(&mut self, collection: &str, snippets: Vec<Snippet>) {
+        let mut imported = HashSet::new();
+        for snippet in snippets {
+            let derived = snippet_packs::derived_id(collection, &snippet.id);
+            let earlier = self.history.iter().position(|item| {
+                item.collection.as_deref() == Some(collection)
+                    && (item.id == snippet.id || item.id == derived)
+            });
+            let hash = calculate_hash(&snippet.text);
+            let mut item = self.text_item(snippet.text, snippet.html, hash);
+            item.tags = snippet
+                .tags
+                .iter()
+                .filter_map(|tag| normalize_tag(tag))
+                .collect();
+            item.collection = Some(collection.to_string());
+            match earlier {
+                Some(index) => {
+                    let old = &self.history[index];
+                    item.id = old.id.clone();
+                    item.timestamp = old.timestamp;
+                    item.pinned = old.pinned;
+                    item.paste_frequency = old.paste_frequency;
+                    imported.insert(item.id.clone());
+                    self.history[index] = item;
+                }
+                None => {
+                    item.id = match self.get_item(&snippet.id) {
+                        Some(_) => derived,
+                        None => snippet.id,
+                    };
+                    imported.insert(item.id.clone());
+                    self.history.push(item);
+                }
+            }
+        }
+        for item in &mut self.history {
+            if item.collection.as_deref() == Some(collection) && !imported.contains(&item.id) {
+                item.collection = None;
+            }
+        }
+        self.save_history();
+    }
+
+    /// Takes every item out of a deleted collection; they stay in history
+    pub fn unfile_collection(&mut self, collection: &str) {
+        let mut changed = false;
+        for item in &mut self.history {
+            if item.collection.as_deref() == Some(collection) {
+                item.collection = None;
+                changed = true;
+            }
+        }
+        if changed {
+            self.save_history();
+        }
+    }
+
+    /// Counts a paste of the item toward the frequent list
+    pub fn record_paste(&mut self, id: &str, now: DateTime<Utc>) {
+        let Some(item) = self.history.iter_mut().find(|item| item.id == id) else {
+            return;
+        };
+        item.paste_frequency = Some(PasteFrequency::pasted(item.paste_frequency, now));
+        self.save_history();
+    }
+
+    /// Swaps the data of images, by id, for the same pictures re-encoded
+    /// (see `image_compression`), saving the history once. Returns how many
+    /// were swapped; items that are gone are skipped.
+    pub fn replace_image_data(&mut self, replacements: Vec<(String, String)>) -> usize {
+        let mut replaced = 0;
+        for (id, encoded) in replacements {
+            // Left alone if it was evicted meanwhile, its data being on disk now
+            if let Some(ClipboardContent::Image { base64, .. }) = self
+                .history
+                .iter_mut()
+                .find(|item| item.id == id && !item.evicted)
+                .map(|item| &mut item.content)
+            {
+                *base64 = encoded;
+                replaced += 1;
+            }
+        }
+        if replaced > 0 {
+            self.save_history();
+        }
+        replaced
+    }
+
     pub fn get_item(&self, id: &str) -> Option<&ClipboardItem> {
         self.history.iter().find(|item| item.id == id)
     }
 
     pub fn clear(&mut self) {
-        self.history.retain(|item| item.pinned);
+        self.history.retain(|item| item.is_kept());
         self.save_history();
     }
 
@@ -532,6 +1337,100 @@ impl ClipboardManager {
         Some(item_clone)
     }
 
+    /// Splits a text item into one new item per piece, e.g. a copied list of
+    /// addresses into one item per address. An empty delimiter or "\n" splits
+    /// by lines. Pieces are trimmed, blanks dropped, and the new items placed
+    /// at the top in their original order; the source item is kept.
+    pub fn split_item(&mut self, id: &str, delimiter: &str) -> Result<Vec<ClipboardItem>, String> {
+        let item = self.get_item(id).ok_or("Item not found")?;
+        let text = self
+            .full_text(item)?
+            .ok_or("Only text items can be split")?;
+
+        let pieces: Vec<String> = if delimiter.is_empty() || delimiter == "\n" {
+            text.lines().map(str::to_string).collect()
+        } else {
+            text.split(delimiter).map(str::to_string).collect()
+        };
+        let mut seen = HashSet::new();
+        let pieces: Vec<String> = pieces
+            .into_iter()
+            .map(|piece| piece.trim().to_string())
+            .filter(|piece| !piece.is_empty() && seen.insert(piece.clone()))
+            .collect();
+        if pieces.len() < 2 {
+            return Err("Nothing to split".into());
+        }
+
+        let new_items: Vec<ClipboardItem> = pieces
+            .into_iter()
+            .map(|piece| self.derived_item(piece))
+            .collect();
+        self.insert_items(new_items.clone());
+        Ok(new_items)
+    }
+
+    /// Appends a text item to the previous (next older) text item, joined by
+    /// `separator`, and puts the combined text at the top as a new item.
+    /// Handy for assembling a paragraph from several copies; both sources are kept.
+    pub fn join_with_previous(
+        &mut self,
+        id: &str,
+        separator: &str,
+    ) -> Result<ClipboardItem, String> {
+        let pos = self
+            .history
+            .iter()
+            .position(|item| item.id == id)
+            .ok_or("Item not found")?;
+        let text = self
+            .full_text(&self.history[pos])?
+            .ok_or("Only text items can be joined")?;
+        let previous = self.history[pos + 1..]
+            .iter()
+            .find(|item| item.text().is_some())
+            .ok_or("No previous text item to join with")?;
+        let previous = self.full_text(previous)?.unwrap_or_default();
+
+        let combined = format!("{}{}{}", previous, separator, text);
+        let item = self.derived_item(combined);
+        self.insert_item(item.clone());
+        Ok(item)
+    }
+
+    /// Puts text made from another item, such as its translation, at the top
+    /// as a new item
+    pub fn add_derived_text(&mut self, text: String) -> ClipboardItem {
+        let item = self.derived_item(text);
+        self.insert_item(item.clone());
+        item
+    }
+
+    /// A new item for text made from other items, taking the place of an
+    /// item with the same text; kept out of line when large
+    fn derived_item(&mut self, text: String) -> ClipboardItem {
+        let hash = calculate_hash(&text);
+        self.remove_duplicate_text_from_history(&text, hash);
+        self.text_item(text, None, hash)
+    }
+
+    /// Puts a copy of a text item with its invisible and bidi characters
+    /// removed and non-breaking spaces made plain at the top; the source is kept
+    pub fn clean_hidden_chars(&mut self, id: &str) -> Result<ClipboardItem, String> {
+        let item = self.get_item(id).ok_or("Item not found")?;
+        if item.hidden_chars.is_none() {
+            return Err("Nothing to clean".into());
+        }
+        let text = self
+            .full_text(item)?
+            .ok_or("Only text items can be cleaned")?;
+
+        let cleaned = content_classifier::clean_hidden_chars(&text);
+        let item = self.derived_item(cleaned);
+        self.insert_item(item.clone());
+        Ok(item)
+    }
+
     // --- Paste Logic ---
 
     pub fn mark_as_pasted(&mut self, item: &ClipboardItem) {
@@ -568,6 +1467,10 @@ impl ClipboardManager {
         let mut clipboard = get_system_clipboard()?;
 
         match &item.content {
+            ClipboardContent::Text(_) if item.large_text.is_some() => {
+                let text = self.full_text(item)?.unwrap_or_default();
+                clipboard.set_text(text).map_err(|e| e.to_string())?;
+            }
             ClipboardContent::Text(text) => {
                 clipboard.set_text(text).map_err(|e| e.to_string())?;
             }
@@ -577,12 +1480,11 @@ impl ClipboardManager {
                     .set_html(html, Some(plain))
                     .map_err(|e| e.to_string())?;
             }
-            ClipboardContent::Image {
-                base64,
-                width,
-                height,
-            } => {
-                self.write_image_to_clipboard(&mut clipboard, base64, *width, *height)?;
+            ClipboardContent::Image { width, height, .. } => {
+                let loaded = self.with_image_data(item)?;
+                if let ClipboardContent::Image { base64, .. } = &loaded.content {
+                    self.write_image_to_clipboard(&mut clipboard, base64, *width, *height)?;
+                }
             }
         }
 
@@ -592,6 +1494,22 @@ impl ClipboardManager {
         Ok(())
     }
 
+    /// Pastes an image that is not in history, such as a custom emoji,
+    /// without recording it once it lands on the clipboard
+    pub fn paste_image(&mut self, base64_str: &str) -> Result<(), String> {
+        let bytes = BASE64
+            .decode(base64_str)
+            .map_err(|e| format!("Base64 decode failed: {}", e))?;
+        let rgba = image::load_from_memory(&bytes)
+            .map_err(|e| format!("Image load failed: {}", e))?
+            .to_rgba8();
+        // The hash the listener computes from the clipboard's RGBA bytes
+        let hash = calculate_hash(rgba.as_raw());
+        let item =
+            ClipboardItem::new_image(base64_str.to_string(), rgba.width(), rgba.height(), hash);
+        self.paste_item(&item)
+    }
+
     fn write_image_to_clipboard(
         &self,
         clipboard: &mut Clipboard,
@@ -630,3 +1548,428 @@ impl ClipboardManager {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_only_discards_session_items() {
+        let mut manager = ClipboardManager::with_store(Box::new(MemoryStore::default()), 10);
+        manager.add_text("saved".to_string(), None);
+
+        manager.set_session_only(true);
+        assert!(manager.is_session_only());
+        assert!(!manager.persists_to_disk());
+        manager.add_text("secret".to_string(), None);
+        assert_eq!(manager.get_history().len(), 2);
+
+        manager.set_session_only(false);
+        let history = manager.get_history();
+        assert_eq!(history.len(), 1);
+        assert!(matches!(&history[0].content, ClipboardContent::Text(t) if t == "saved"));
+    }
+
+    #[test]
+    fn test_large_texts_stay_readable_off_disk() {
+        let dir = std::env::temp_dir().join(format!("clipboard-large-{}", Uuid::new_v4()));
+        let mut manager = ClipboardManager::new(dir.join("history.json"), 10);
+        let text = "line\n".repeat(large_text::LARGE_TEXT_BYTES / 4);
+        let item = manager.add_text(text.clone(), None).unwrap();
+        assert!(item.large_text.is_some());
+
+        manager.set_session_only(true);
+        assert_eq!(
+            manager.full_text(&item).unwrap().as_deref(),
+            Some(text.as_str())
+        );
+        manager.set_session_only(false);
+        manager.set_store(Box::new(MemoryStore::default()));
+        assert_eq!(
+            manager.full_text(&item).unwrap().as_deref(),
+            Some(text.as_str())
+        );
+        let loaded = manager.with_full_content(&item).unwrap();
+        assert_eq!(loaded.text(), Some(text.as_str()));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_text_features_see_the_whole_of_large_texts() {
+        let dir = std::env::temp_dir().join(format!("clipboard-large-{}", Uuid::new_v4()));
+        let mut manager = ClipboardManager::new(dir.join("history.json"), 10);
+        let body = "line\n".repeat(large_text::LARGE_TEXT_BYTES / 4);
+        let text = format!("\u{200b}head|{}", body);
+        manager.add_text("prefix".to_string(), None);
+        let item = manager.add_text(text.clone(), None).unwrap();
+        assert!(item.large_text.is_some());
+        let full = |manager: &ClipboardManager, item: &ClipboardItem| {
+            manager.full_text(item).unwrap().unwrap()
+        };
+
+        let pieces = manager.split_item(&item.id, "|").unwrap();
+        assert_eq!(full(&manager, &pieces[0]), "\u{200b}head");
+        assert!(pieces[1].large_text.is_some());
+        assert_eq!(full(&manager, &pieces[1]), body.trim());
+
+        let joined = manager.join_with_previous(&item.id, " ").unwrap();
+        assert_eq!(full(&manager, &joined), format!("prefix {}", text));
+
+        let cleaned = manager.clean_hidden_chars(&item.id).unwrap();
+        assert_eq!(full(&manager, &cleaned), format!("head|{}", body));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_split_item() {
+        let mut manager = ClipboardManager::with_store(Box::new(MemoryStore::default()), 10);
+        manager.add_text("b@example.com".to_string(), None);
+        let source = manager
+            .add_text(
+                "a@example.com, b@example.com,, c@example.com".to_string(),
+                None,
+            )
+            .unwrap();
+
+        let pieces = manager.split_item(&source.id, ",").unwrap();
+        assert_eq!(pieces.len(), 3);
+
+        let texts: Vec<String> = manager
+            .get_history()
+            .iter()
+            .map(|item| item.preview.clone())
+            .collect();
+        assert_eq!(
+            texts,
+            [
+                "a@example.com",
+                "b@example.com",
+                "c@example.com",
+                "a@example.com, b@example.com,, c@example.com",
+            ]
+        );
+
+        let lines = manager.add_text("one\r\ntwo\n".to_string(), None).unwrap();
+        assert_eq!(manager.split_item(&lines.id, "").unwrap().len(), 2);
+        assert!(manager.split_item(&pieces[0].id, ",").is_err());
+    }
+
+    #[test]
+    fn test_join_with_previous() {
+        let mut manager = ClipboardManager::with_store(Box::new(MemoryStore::default()), 10);
+        let first = manager
+            .add_text("First sentence.".to_string(), None)
+            .unwrap();
+        let second = manager
+            .add_text("Second sentence.".to_string(), None)
+            .unwrap();
+
+        let joined = manager.join_with_previous(&second.id, " ").unwrap();
+        assert_eq!(joined.text(), Some("First sentence. Second sentence."));
+        assert_eq!(manager.get_history().len(), 3);
+        assert_eq!(manager.get_history()[0].id, joined.id);
+
+        assert!(manager.join_with_previous(&first.id, " ").is_err());
+    }
+
+    #[test]
+    fn test_retention_removes_only_expired_unkept_items() {
+        let now = Utc::now();
+        let aged = |mut item: ClipboardItem, days: i64| {
+            item.timestamp = now - chrono::Duration::days(days);
+            item
+        };
+        let stale_text = aged(ClipboardItem::new_text("stale".to_string()), 10);
+        let at_cutoff = aged(ClipboardItem::new_text("at cutoff".to_string()), 7);
+        let stale_file = aged(ClipboardItem::new_text("file:///tmp/a".to_string()), 2);
+        let old_image = aged(ClipboardItem::new_image(String::new(), 1, 1, 0), 400);
+        let mut pinned = aged(ClipboardItem::new_text("pinned".to_string()), 100);
+        pinned.pinned = true;
+        let mut filed = aged(ClipboardItem::new_text("filed".to_string()), 100);
+        filed.collection = Some("snippets".to_string());
+        let items = vec![stale_text, at_cutoff, stale_file, old_image, pinned, filed];
+        let ids = |manager: &ClipboardManager| -> Vec<String> {
+            manager.history.iter().map(|item| item.id.clone()).collect()
+        };
+
+        let mut manager = ClipboardManager::with_store(Box::new(MemoryStore::default()), 10);
+        manager.history = items.clone();
+        // Zero keeps everything, whatever its age
+        assert!(!manager.remove_expired(now));
+        assert_eq!(manager.history.len(), items.len());
+
+        manager.retention = RetentionPolicy {
+            text_days: 7,
+            image_days: 0,
+            file_days: 1,
+        };
+        assert!(manager.remove_expired(now));
+        // The stale text and file go, not the text just at its cutoff.
+        // Images are kept forever here, and
+        // pinned and filed items whatever the policy
+        let kept = [&items[1], &items[3], &items[4], &items[5]].map(|item| item.id.clone());
+        assert_eq!(ids(&manager), kept);
+        assert!(!manager.remove_expired(now));
+    }
+
+    #[test]
+    fn test_nth_recent_ignores_pinned_position() {
+        let mut manager = ClipboardManager::with_store(Box::new(MemoryStore::default()), 10);
+        let old = manager.add_text("old".to_string(), None).unwrap();
+        manager.toggle_pin(&old.id);
+        let newer = manager.add_text("newer".to_string(), None).unwrap();
+        let newest = manager.add_text("newest".to_string(), None).unwrap();
+
+        // The pinned item is listed first but was copied first
+        assert_eq!(manager.get_history()[0].id, old.id);
+        assert_eq!(manager.nth_recent(1).unwrap().id, newest.id);
+        assert_eq!(manager.nth_recent(2).unwrap().id, newer.id);
+        assert_eq!(manager.nth_recent(3).unwrap().id, old.id);
+        assert!(manager.nth_recent(0).is_none());
+        assert!(manager.nth_recent(4).is_none());
+    }
+
+    #[test]
+    fn test_frequent_ranks_by_decayed_paste_count() {
+        let mut manager = ClipboardManager::with_store(Box::new(MemoryStore::default()), 10);
+        let daily = manager.add_text("daily".to_string(), None).unwrap();
+        let burst = manager.add_text("burst".to_string(), None).unwrap();
+        let once = manager.add_text("once".to_string(), None).unwrap();
+        let now = Utc::now();
+        let days_ago = |days: i64| now - chrono::Duration::days(days);
+
+        for day in (0..10).rev() {
+            manager.record_paste(&daily.id, days_ago(day));
+        }
+        for _ in 0..8 {
+            manager.record_paste(&burst.id, days_ago(30));
+        }
+        manager.record_paste(&once.id, now);
+
+        let frequent: Vec<&str> = manager
+            .frequent(5, now)
+            .iter()
+            .map(|item| item.preview.as_str())
+            .collect();
+        assert_eq!(frequent, ["daily"]);
+
+        // Copying the text again keeps its score
+        manager.add_text("other".to_string(), None);
+        manager.add_text("daily".to_string(), None);
+        assert_eq!(manager.frequent(5, now)[0].preview, "daily");
+        assert_ne!(manager.frequent(5, now)[0].id, daily.id);
+    }
+
+    #[test]
+    fn test_tags() {
+        assert_eq!(
+            normalize_tag("  #Work Stuff "),
+            Some("work-stuff".to_string())
+        );
+        assert_eq!(normalize_tag(" # "), None);
+
+        let mut manager = ClipboardManager::with_store(Box::new(MemoryStore::default()), 10);
+        let invoice = manager.add_text("invoice 42".to_string(), None).unwrap();
+        let note = manager.add_text("call back".to_string(), None).unwrap();
+        let tags = |tags: &[&str]| tags.iter().map(|tag| tag.to_string()).collect::<Vec<_>>();
+
+        let tagged = manager
+            .set_tags(&invoice.id, &tags(&["Work", "invoice", "work", ""]))
+            .unwrap();
+        assert_eq!(tagged.tags, ["invoice", "work"]);
+        manager.set_tags(&note.id, &tags(&["todo", "work"]));
+
+        assert_eq!(manager.with_tag("#WORK").len(), 2);
+        assert_eq!(manager.with_tag("todo")[0].id, note.id);
+        assert_eq!(manager.tag_suggestions("", 5), ["work", "invoice", "todo"]);
+        assert_eq!(manager.tag_suggestions("in", 5), ["invoice"]);
+
+        // Copying the text again keeps its tags
+        manager.add_text("invoice 42".to_string(), None);
+        assert_eq!(manager.get_history()[0].tags, ["invoice", "work"]);
+    }
+
+    #[test]
+    fn test_filed_items_outlive_limits_and_clearing() {
+        let mut manager = ClipboardManager::with_store(Box::new(MemoryStore::default()), 2);
+        let snippet = manager.add_text("snippet".to_string(), None).unwrap();
+        manager.file_item(&snippet.id, Some("snippets".to_string()));
+        for text in ["a", "b", "c"] {
+            manager.add_text(text.to_string(), None);
+        }
+        assert!(manager.get_item(&snippet.id).is_some());
+        assert_eq!(manager.get_history().len(), 2);
+
+        manager.clear();
+        let history = manager.get_history();
+        assert_eq!(history.len(), 1);
+        assert_eq!(manager.in_collection("snippets")[0].id, snippet.id);
+
+        manager.unfile_collection("snippets");
+        assert!(manager.in_collection("snippets").is_empty());
+        manager.clear();
+        assert!(manager.get_history().is_empty());
+    }
+
+    #[test]
+    fn test_importing_a_pack_again_updates_it() {
+        let mut manager = ClipboardManager::with_store(Box::new(MemoryStore::default()), 10);
+        let collection = Uuid::new_v4().to_string();
+        let taken = manager.add_text("already here".to_string(), None).unwrap();
+        let snippet = |id: &str, text: &str| Snippet {
+            id: id.to_string(),
+            text: text.to_string(),
+            html: None,
+            tags: vec!["Support".to_string()],
+        };
+        let kept = Uuid::new_v4().to_string();
+        let dropped = Uuid::new_v4().to_string();
+        manager.import_snippets(
+            &collection,
+            vec![
+                snippet(&taken.id, "Hello"),
+                snippet(&kept, "Thanks"),
+                snippet(&dropped, "Bye"),
+            ],
+        );
+        let filed: Vec<&str> = manager
+            .in_collection(&collection)
+            .into_iter()
+            .map(|item| item.id.as_str())
+            .collect();
+        let derived = snippet_packs::derived_id(&collection, &taken.id);
+        assert_eq!(filed, [derived.as_str(), &kept, &dropped]);
+        assert_eq!(manager.get_item(&taken.id).unwrap().collection, None);
+        assert_eq!(manager.get_item(&kept).unwrap().tags, ["support"]);
+
+        manager.toggle_pin(&kept);
+        manager.import_snippets(
+            &collection,
+            vec![snippet(&taken.id, "Hello again"), snippet(&kept, "Thanks!")],
+        );
+        assert_eq!(manager.in_collection(&collection).len(), 2);
+        assert_eq!(
+            manager.get_item(&derived).unwrap().text(),
+            Some("Hello again")
+        );
+        let updated = manager.get_item(&kept).unwrap();
+        assert!(updated.pinned);
+        assert_eq!(updated.text(), Some("Thanks!"));
+        assert_eq!(manager.get_item(&dropped).unwrap().collection, None);
+    }
+
+    #[test]
+    fn test_copied_from_matches_part_of_the_app_id() {
+        let mut manager = ClipboardManager::with_store(Box::new(MemoryStore::default()), 10);
+        let from = |manager: &mut ClipboardManager, text: &str, app: Option<&str>| {
+            manager
+                .add_text_from(text.to_string(), None, app.map(str::to_string))
+                .unwrap()
+                .id
+        };
+        let editor = from(&mut manager, "fn main", Some("Code"));
+        let browser = from(
+            &mut manager,
+            "https://example.com",
+            Some("org.mozilla.firefox"),
+        );
+        from(&mut manager, "unknown", None);
+
+        let ids =
+            |items: Vec<&ClipboardItem>| items.iter().map(|i| i.id.clone()).collect::<Vec<_>>();
+        assert_eq!(ids(manager.copied_from("code")), [editor]);
+        assert_eq!(ids(manager.copied_from(" Firefox ")), [browser]);
+        assert!(manager.copied_from("").is_empty());
+    }
+
+    #[test]
+    fn test_clean_hidden_chars() {
+        let mut manager = ClipboardManager::with_store(Box::new(MemoryStore::default()), 10);
+        let dirty = manager
+            .add_text("sudo\u{00A0}apt\u{200B} update".to_string(), None)
+            .unwrap();
+        let plain = manager.add_text("sudo apt".to_string(), None).unwrap();
+        assert_eq!(dirty.hidden_chars.as_ref().unwrap().invisible, 1);
+        assert!(plain.hidden_chars.is_none());
+
+        let cleaned = manager.clean_hidden_chars(&dirty.id).unwrap();
+        assert_eq!(cleaned.text(), Some("sudo apt update"));
+        assert!(cleaned.hidden_chars.is_none());
+        assert!(manager.clean_hidden_chars(&plain.id).is_err());
+    }
+
+    #[test]
+    fn test_paused_capture_notifies_nothing() {
+        let mut manager = ClipboardManager::with_store(Box::new(MemoryStore::default()), 10);
+        let changes = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counter = changes.clone();
+        manager.set_change_listener(move || {
+            counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        });
+
+        manager.set_paused(true);
+        assert!(manager.add_text("secret".to_string(), None).is_none());
+        manager.set_paused(false);
+        manager.add_text("hello".to_string(), None).unwrap();
+        assert_eq!(changes.load(std::sync::atomic::Ordering::SeqCst), 1);
+        assert_eq!(manager.recent(5).len(), 1);
+    }
+
+    #[test]
+    fn test_capture_policy_filters_content_types() {
+        let mut manager = ClipboardManager::with_store(Box::new(MemoryStore::default()), 10);
+        manager.set_capture_policy(CapturePolicy {
+            files: false,
+            html: false,
+            ..Default::default()
+        });
+
+        assert!(manager
+            .add_text("file:///home/user/report.pdf".to_string(), None)
+            .is_none());
+
+        let item = manager
+            .add_text("hello".to_string(), Some("<b>hello</b>".to_string()))
+            .unwrap();
+        assert!(matches!(item.content, ClipboardContent::Text(_)));
+
+        manager.set_capture_policy(CapturePolicy {
+            text: false,
+            ..Default::default()
+        });
+        assert!(manager.add_text("world".to_string(), None).is_none());
+        assert!(manager
+            .add_text("file:///tmp/a.png".to_string(), None)
+            .is_some());
+    }
+
+    #[test]
+    fn test_check_file_references() {
+        let dir = std::env::temp_dir().join(format!("clipboard-files-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("My Report.txt");
+        std::fs::write(&file, "report").unwrap();
+
+        let mut manager = ClipboardManager::with_store(Box::new(MemoryStore::default()), 10);
+        let uri = format!("file://{}", file.display()).replace(' ', "%20");
+        let existing = manager.add_text(uri, None).unwrap();
+        assert_eq!(existing.file_paths(), [file.as_path()]);
+        let gone = manager
+            .add_text(format!("file://{}/deleted.txt", dir.display()), None)
+            .unwrap();
+        manager.toggle_pin(&gone.id);
+        manager.add_text("plain text".to_string(), None);
+
+        assert!(manager.check_file_references(true));
+        // Pinned items are kept, only marked
+        assert!(manager.get_item(&gone.id).unwrap().files_missing);
+        assert!(!manager.get_item(&existing.id).unwrap().files_missing);
+
+        std::fs::remove_file(&file).unwrap();
+        assert!(manager.check_file_references(true));
+        assert!(manager.get_item(&existing.id).is_none());
+        assert_eq!(manager.get_history().len(), 2);
+        assert!(!manager.check_file_references(true));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,253 @@
+//! Collections
+//! Named folders that history items can be filed into, for snippets that
+//! should outlive the daily history. The list of collections is kept in
+//! `collections.json`; which collection an item is in is saved on the item
+//! itself (`ClipboardItem::collection`). Filed items are kept like pinned
+//! ones: history limits, retention and clearing skip them. Collections can
+//! also come from snippet packs, see `snippet_packs`.
+
+use crate::snippet_packs::{PackConflict, SnippetPack};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+/// Persistence filename
+const COLLECTIONS_FILE: &str = "collections.json";
+
+/// Longest collection name, in characters
+const MAX_NAME_LEN: usize = 64;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Collection {
+    pub id: String,
+    pub name: String,
+    /// Imported from a pack marked read-only; its items can't be changed,
+    /// only the collection as a whole deleted
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub read_only: bool,
+}
+
+/// Keeps the list of collections
+pub struct Collections {
+    /// In creation order
+    collections: Vec<Collection>,
+    /// Path to the data directory
+    data_dir: PathBuf,
+}
+
+impl Collections {
+    /// Loads the collections saved in `data_dir`, if any
+    pub fn new(data_dir: PathBuf) -> Self {
+        let mut collections = Self {
+            collections: Vec::new(),
+            data_dir,
+        };
+        if let Err(e) = collections.load_from_disk() {
+            eprintln!("[Collections] Failed to load collections: {}", e);
+        }
+        collections
+    }
+
+    pub fn list(&self) -> Vec<Collection> {
+        self.collections.clone()
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Collection> {
+        self.collections.iter().find(|c| c.id == id)
+    }
+
+    pub fn create(&mut self, name: &str) -> Result<Collection, String> {
+        let name = self.checked_name(name, None)?;
+        let collection = Collection {
+            id: Uuid::new_v4().to_string(),
+            name,
+            read_only: false,
+        };
+        self.collections.push(collection.clone());
+        self.save_to_disk()?;
+        Ok(collection)
+    }
+
+    /// The collection to import `pack` into: the one an earlier import of
+    /// it made, when `conflict` says to update that, else a new one named
+    /// like the pack, numbered when another collection has that name
+    pub fn for_pack(
+        &mut self,
+        pack: &SnippetPack,
+        conflict: PackConflict,
+    ) -> Result<Collection, String> {
+        if let Some(earlier) = self.collections.iter_mut().find(|c| c.id == pack.id) {
+            if conflict == PackConflict::Update {
+                earlier.read_only = pack.read_only;
+                let earlier = earlier.clone();
+                self.save_to_disk()?;
+                return Ok(earlier);
+            }
+        }
+
+        let base: String = match pack.name.trim() {
+            "" => "Snippet pack".to_string(),
+            name => name.chars().take(MAX_NAME_LEN - 5).collect(),
+        };
+        let name = (1..)
+            .map(|n| match n {
+                1 => base.clone(),
+                n => format!("{} ({})", base, n),
+            })
+            .find(|name| self.checked_name(name, None).is_ok())
+            .unwrap_or(base);
+        let id = match self.get(&pack.id) {
+            Some(_) => Uuid::new_v4().to_string(),
+            None => pack.id.clone(),
+        };
+        let collection = Collection {
+            id,
+            name,
+            read_only: pack.read_only,
+        };
+        self.collections.push(collection.clone());
+        self.save_to_disk()?;
+        Ok(collection)
+    }
+
+    pub fn rename(&mut self, id: &str, name: &str) -> Result<Collection, String> {
+        let name = self.checked_name(name, Some(id))?;
+        let collection = self
+            .collections
+            .iter_mut()
+            .find(|c| c.id == id)
+            .ok_or("Collection not found")?;
+        collection.name = name;
+        let collection = collection.clone();
+        self.save_to_disk()?;
+        Ok(collection)
+    }
+
+    /// Removes the collection, not its items; the caller unfiles those
+    pub fn delete(&mut self, id: &str) -> Result<(), String> {
+        let before = self.collections.len();
+        self.collections.retain(|c| c.id != id);
+        if self.collections.len() == before {
+            return Err("Collection not found".to_string());
+        }
+        self.save_to_disk()
+    }
+
+    /// `name` trimmed, if it is not empty, too long or taken by another
+    /// collection than `id` (ignoring case)
+    fn checked_name(&self, name: &str, id: Option<&str>) -> Result<String, String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("A collection needs a name".to_string());
+        }
+        if name.chars().count() > MAX_NAME_LEN {
+            return Err(format!(
+                "Collection names are at most {} characters",
+                MAX_NAME_LEN
+            ));
+        }
+        let taken = self
+            .collections
+            .iter()
+            .any(|c| Some(c.id.as_str()) != id && c.name.to_lowercase() == name.to_lowercase());
+        if taken {
+            return Err(format!("There is already a collection named '{}'", name));
+        }
+        Ok(name.to_string())
+    }
+
+    // --- Persistence Helpers ---
+
+    fn collections_path(&self) -> PathBuf {
+        self.data_dir.join(COLLECTIONS_FILE)
+    }
+
+    fn load_from_disk(&mut self) -> Result<(), String> {
+        let path = self.collections_path();
+        if !path.exists() {
+            return Ok(());
+        }
+        let content = fs::read_to_string(&path).map_err(|e| format!("Read error: {}", e))?;
+        self.collections =
+            serde_json::from_str(&content).map_err(|e| format!("Parse error: {}", e))?;
+        Ok(())
+    }
+
+    fn save_to_disk(&self) -> Result<(), String> {
+        if !self.data_dir.exists() {
+            fs::create_dir_all(&self.data_dir)
+                .map_err(|e| format!("Failed to create data dir: {}", e))?;
+        }
+        let content = serde_json::to_string_pretty(&self.collections)
+            .map_err(|e| format!("Serialize error: {}", e))?;
+        fs::write(self.collections_path(), content).map_err(|e| format!("Write error: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env::temp_dir;
+
+    #[test]
+    fn test_create_rename_delete() {
+        let data_dir = temp_dir().join("collections_test");
+        let _ = fs::remove_dir_all(&data_dir);
+
+        let mut collections = Collections::new(data_dir.clone());
+        let snippets = collections.create("  Snippets ").unwrap();
+        assert_eq!(snippets.name, "Snippets");
+        assert!(collections.create("snippets").is_err());
+        assert!(collections.create(" ").is_err());
+        let work = collections.create("Work").unwrap();
+
+        assert!(collections.rename(&work.id, "SNIPPETS").is_err());
+        collections.rename(&snippets.id, "Code snippets").unwrap();
+
+        let mut collections = Collections::new(data_dir);
+        let names: Vec<String> = collections.list().into_iter().map(|c| c.name).collect();
+        assert_eq!(names, ["Code snippets", "Work"]);
+
+        collections.delete(&work.id).unwrap();
+        assert!(collections.delete(&work.id).is_err());
+        assert_eq!(collections.list().len(), 1);
+    }
+
+    #[test]
+    fn test_pack_collections_update_or_keep_both() {
+        let data_dir = temp_dir().join("collections_pack_test");
+        let _ = fs::remove_dir_all(&data_dir);
+
+        let mut collections = Collections::new(data_dir.clone());
+        collections.create("Support replies").unwrap();
+        let mut pack = SnippetPack::new(
+            &Collection {
+                id: Uuid::new_v4().to_string(),
+                name: "Support replies".to_string(),
+                read_only: false,
+            },
+            Vec::new(),
+            true,
+        );
+
+        let imported = collections.for_pack(&pack, PackConflict::Update).unwrap();
+        assert_eq!(imported.id, pack.id);
+        assert_eq!(imported.name, "Support replies (2)");
+        assert!(imported.read_only);
+
+        pack.read_only = false;
+        let updated = collections.for_pack(&pack, PackConflict::Update).unwrap();
+        assert_eq!(updated.id, imported.id);
+        assert!(
+            !Collections::new(data_dir)
+                .get(&updated.id)
+                .unwrap()
+                .read_only
+        );
+
+        let second = collections.for_pack(&pack, PackConflict::KeepBoth).unwrap();
+        assert_ne!(second.id, pack.id);
+        assert_eq!(second.name, "Support replies (3)");
+    }
+}
@@ -0,0 +1,134 @@
+//! Color Scheme
+//! Follows the desktop's dark/light preference through the XDG settings
+//! portal (`org.freedesktop.appearance` `color-scheme`), which GNOME, KDE and
+//! most portal backends keep up to date. WebKitGTK's prefers-color-scheme
+//! often doesn't, so the "system" theme mode goes by this instead.
+
+use crate::listener_supervisor::backoff_delay;
+use std::time::{Duration, Instant};
+use zbus::blocking::{Connection, Proxy};
+use zbus::zvariant::{OwnedValue, Value};
+
+const PORTAL_SERVICE: &str = "org.freedesktop.portal.Desktop";
+const PORTAL_PATH: &str = "/org/freedesktop/portal/desktop";
+const SETTINGS_INTERFACE: &str = "org.freedesktop.portal.Settings";
+const APPEARANCE_NAMESPACE: &str = "org.freedesktop.appearance";
+const COLOR_SCHEME_KEY: &str = "color-scheme";
+
+/// A watch that ran this long is healthy again; backoff starts over
+const WATCH_STABLE_AFTER: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorScheme {
+    NoPreference,
+    Dark,
+    Light,
+}
+
+impl ColorScheme {
+    /// From the portal's 0 (no preference), 1 (dark) or 2 (light)
+    pub fn from_portal(value: u32) -> Option<Self> {
+        match value {
+            0 => Some(Self::NoPreference),
+            1 => Some(Self::Dark),
+            2 => Some(Self::Light),
+            _ => None,
+        }
+    }
+
+    /// As sent to the windows
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::NoPreference => "no_preference",
+            Self::Dark => "dark",
+            Self::Light => "light",
+        }
+    }
+}
+
+/// The scheme in a portal reply. The deprecated `Read` wraps the value in
+/// one more variant than `ReadOne`.
+pub fn parse_color_scheme(value: &Value) -> Option<ColorScheme> {
+    match value {
+        Value::U32(scheme) => ColorScheme::from_portal(*scheme),
+        Value::Value(inner) => parse_color_scheme(inner),
+        _ => None,
+    }
+}
+
+fn settings_proxy(connection: &Connection) -> zbus::Result<Proxy<'static>> {
+    Proxy::new(connection, PORTAL_SERVICE, PORTAL_PATH, SETTINGS_INTERFACE)
+}
+
+/// The current preference, None without a settings portal
+pub fn current() -> Option<ColorScheme> {
+    let connection = Connection::session().ok()?;
+    let proxy = settings_proxy(&connection).ok()?;
+    let key = (APPEARANCE_NAMESPACE, COLOR_SCHEME_KEY);
+    let value: OwnedValue = proxy
+        .call("ReadOne", &key)
+        .or_else(|_| proxy.call("Read", &key))
+        .ok()?;
+    parse_color_scheme(&value)
+}
+
+/// Calls `on_change` from a background thread each time the preference
+/// changes. The subscription is made again with backoff if the bus drops.
+pub fn watch<F>(on_change: F)
+where
+    F: Fn(ColorScheme) + Send + 'static,
+{
+    std::thread::spawn(move || {
+        let mut attempt = 0;
+        loop {
+            let started = Instant::now();
+            if let Err(e) = run_watch(&on_change) {
+                eprintln!("[ColorScheme] Settings portal watch failed: {}", e);
+            }
+            if started.elapsed() >= WATCH_STABLE_AFTER {
+                attempt = 0;
+            }
+            attempt += 1;
+            std::thread::sleep(backoff_delay(attempt));
+        }
+    });
+}
+
+fn run_watch(on_change: &impl Fn(ColorScheme)) -> zbus::Result<()> {
+    let connection = Connection::session()?;
+    let proxy = settings_proxy(&connection)?;
+    for message in proxy.receive_signal("SettingChanged")? {
+        let Ok((namespace, key, value)) =
+            message.body().deserialize::<(String, String, OwnedValue)>()
+        else {
+            continue;
+        };
+        if namespace != APPEARANCE_NAMESPACE || key != COLOR_SCHEME_KEY {
+            continue;
+        }
+        if let Some(scheme) = parse_color_scheme(&value) {
+            on_change(scheme);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_color_scheme() {
+        assert_eq!(parse_color_scheme(&Value::U32(1)), Some(ColorScheme::Dark));
+        assert_eq!(
+            parse_color_scheme(&Value::Value(Box::new(Value::U32(2)))),
+            Some(ColorScheme::Light)
+        );
+        assert_eq!(
+            parse_color_scheme(&Value::U32(0)),
+            Some(ColorScheme::NoPreference)
+        );
+        assert_eq!(parse_color_scheme(&Value::U32(7)), None);
+        assert_eq!(parse_color_scheme(&Value::from("dark")), None);
+    }
+}
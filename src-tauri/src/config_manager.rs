@@ -1,5 +1,9 @@
 //! Config Manager Module
-//! Handles persistence of window state (position, monitor) specifically for Wayland usage.
+//! Decides where the window opens: next to the pointer, where it was left or
+//! docked to a screen edge, on which monitor (a fixed one, the pointer's or
+//! the focused window's), and persists the remembered window state
+//! (position, monitor, size, dock edge and width). Panels are kept clear of
+//! using each monitor's work area, worked out from EWMH struts on X11.
 
 use serde::{Deserialize, Serialize};
 use std::fs;
@@ -8,17 +12,169 @@ use tauri::{Monitor, PhysicalPosition, PhysicalSize};
 
 const CONFIG_FILE: &str = "window_state.json";
 
+/// Gap kept between a clamped window and the monitor edges
+const EDGE_PADDING: i32 = 10;
+
+/// A restored size is never shrunk below this (logical pixels), unless the
+/// monitor itself is smaller
+const MIN_WINDOW_SIZE: (u32, u32) = (280, 320);
+
+/// Width of the docked sidebar until it is resized (logical pixels)
+const DEFAULT_DOCK_WIDTH: u32 = 360;
+
+/// Screen edge the docked sidebar sits on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum DockEdge {
+    Left,
+    #[default]
+    Right,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct WindowState {
     pub monitor_name: Option<String>,
     pub x: i32,
     pub y: i32,
+    /// Size the window was resized to, in logical pixels so it looks the
+    /// same on monitors with other scale factors. None until first resized.
+    #[serde(default)]
+    pub width: Option<u32>,
+    #[serde(default)]
+    pub height: Option<u32>,
+    #[serde(default)]
+    pub dock_edge: DockEdge,
+    /// Width of the docked sidebar, logical pixels like `width`
+    #[serde(default)]
+    pub dock_width: Option<u32>,
+}
+
+/// Where the window opens
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlacementMode {
+    /// Near the pointer on X11, where it was left on Wayland, where the
+    /// pointer often can't be queried
+    Auto,
+    /// Next to the pointer
+    Cursor,
+    /// Where it was last moved to, bottom-center at first
+    Remembered,
+    /// A full-height sidebar on one edge that stays on top and open when
+    /// it loses focus
+    Docked,
+}
+
+impl PlacementMode {
+    pub const ALL: [PlacementMode; 4] = [Self::Auto, Self::Cursor, Self::Remembered, Self::Docked];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Auto => "auto",
+            Self::Cursor => "cursor",
+            Self::Remembered => "remembered",
+            Self::Docked => "docked",
+        }
+    }
+
+    pub fn from_setting(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|mode| mode.as_str() == value)
+    }
+
+    /// Whether the window follows the pointer rather than its saved position
+    pub fn follows_cursor(self, wayland: bool) -> bool {
+        match self {
+            Self::Auto => !wayland,
+            Self::Cursor => true,
+            Self::Remembered | Self::Docked => false,
+        }
+    }
+
+    /// Whether moving the window changes where it opens next time
+    pub fn remembers_position(self, wayland: bool) -> bool {
+        !self.follows_cursor(wayland) && self != Self::Docked
+    }
+}
+
+/// How tightly the history list is laid out
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UiDensity {
+    #[default]
+    Comfortable,
+    /// Smaller rows without previews, in a smaller window
+    Compact,
+}
+
+impl UiDensity {
+    pub const ALL: [UiDensity; 2] = [Self::Comfortable, Self::Compact];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Comfortable => "comfortable",
+            Self::Compact => "compact",
+        }
+    }
+
+    pub fn from_setting(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|density| density.as_str() == value)
+    }
+
+    /// Size of a window that was never resized (logical pixels)
+    pub fn default_window_size(self) -> (u32, u32) {
+        match self {
+            Self::Comfortable => (360, 480),
+            Self::Compact => (320, 400),
+        }
+    }
+}
+
+/// Which monitor the window opens on
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum MonitorChoice {
+    /// Wherever the placement mode puts it
+    #[default]
+    Any,
+    /// The monitor the pointer is on
+    Pointer,
+    /// The monitor of the window that had focus before opening
+    FocusedWindow,
+    /// A monitor by name, such as "DP-1"; like Any while it is unplugged
+    Named(String),
+}
+
+impl MonitorChoice {
+    /// Any value that isn't one of the keywords names a monitor
+    pub fn from_setting(value: &str) -> Self {
+        match value.trim() {
+            "" | "any" => Self::Any,
+            "pointer" => Self::Pointer,
+            "focused_window" => Self::FocusedWindow,
+            name => Self::Named(name.to_string()),
+        }
+    }
+
+    pub fn as_setting(&self) -> &str {
+        match self {
+            Self::Any => "any",
+            Self::Pointer => "pointer",
+            Self::FocusedWindow => "focused_window",
+            Self::Named(name) => name,
+        }
+    }
 }
 
 pub struct ConfigManager {
     data_dir: PathBuf,
     state: WindowState,
     dirty: bool, // Tracks if we have unsaved changes in memory
+    /// From the user settings, not saved with the window state
+    placement_mode: PlacementMode,
+    monitor_choice: MonitorChoice,
+    density: UiDensity,
+    /// Last size set by `resolve_window_geometry`, so the resize event it
+    /// causes isn't taken for one by the user
+    applied_size: Option<PhysicalSize<u32>>,
 }
 
 impl ConfigManager {
@@ -27,6 +183,10 @@ impl ConfigManager {
             data_dir,
             state: WindowState::default(),
             dirty: false,
+            placement_mode: PlacementMode::Auto,
+            monitor_choice: MonitorChoice::Any,
+            density: UiDensity::Comfortable,
+            applied_size: None,
         };
 
         if let Err(e) = manager.load() {
@@ -43,6 +203,58 @@ impl ConfigManager {
         self.state.clone()
     }
 
+    pub fn placement_mode(&self) -> PlacementMode {
+        self.placement_mode
+    }
+
+    pub fn set_placement_mode(&mut self, mode: PlacementMode) {
+        self.placement_mode = mode;
+    }
+
+    pub fn monitor_choice(&self) -> &MonitorChoice {
+        &self.monitor_choice
+    }
+
+    pub fn set_monitor_choice(&mut self, choice: MonitorChoice) {
+        self.monitor_choice = choice;
+    }
+
+    pub fn set_density(&mut self, density: UiDensity) {
+        self.density = density;
+    }
+
+    /// Size the window opens at until it is resized, for the density and
+    /// the monitor's `scale_factor`
+    pub fn default_window_size(&self, scale_factor: f64) -> PhysicalSize<u32> {
+        let (width, height) = self.density.default_window_size();
+        let to_physical = |logical: u32| (logical as f64 * scale_factor).round() as u32;
+        PhysicalSize::new(to_physical(width), to_physical(height))
+    }
+
+    /// The monitor the settings pin the window to, given where the pointer
+    /// and the previously focused window are (when known). None when any
+    /// monitor will do or the chosen one can't be found.
+    pub fn chosen_monitor(
+        &self,
+        monitors: &[MonitorGeometry],
+        pointer: Option<(i32, i32)>,
+        focused_window: Option<(i32, i32)>,
+    ) -> Option<MonitorGeometry> {
+        let at = |point: Option<(i32, i32)>| {
+            let (x, y) = point?;
+            monitor_containing(monitors, x, y)
+        };
+        match &self.monitor_choice {
+            MonitorChoice::Any => None,
+            MonitorChoice::Pointer => at(pointer),
+            MonitorChoice::FocusedWindow => at(focused_window),
+            MonitorChoice::Named(name) => monitors
+                .iter()
+                .find(|m| m.name.as_deref() == Some(name.as_str())),
+        }
+        .cloned()
+    }
+
     /// Updates the state in memory only. Use sync_to_disk() to flush.
     pub fn update_state(&mut self, monitor_name: Option<String>, x: i32, y: i32) {
         self.state.monitor_name = monitor_name;
@@ -51,6 +263,34 @@ impl ConfigManager {
         self.dirty = true;
     }
 
+    /// Records a resize in memory only, unless it is the size the window
+    /// was just given. While docked only the sidebar width is kept.
+    pub fn update_size(&mut self, size: PhysicalSize<u32>, scale_factor: f64) {
+        if self.applied_size.take() == Some(size) || scale_factor <= 0.0 {
+            return;
+        }
+        let to_logical = |physical: u32| (physical as f64 / scale_factor).round() as u32;
+        if self.placement_mode == PlacementMode::Docked {
+            self.state.dock_width = Some(to_logical(size.width));
+        } else {
+            self.state.width = Some(to_logical(size.width));
+            self.state.height = Some(to_logical(size.height));
+        }
+        self.dirty = true;
+    }
+
+    pub fn set_dock_edge(&mut self, edge: DockEdge) {
+        if self.state.dock_edge != edge {
+            self.state.dock_edge = edge;
+            self.dirty = true;
+        }
+    }
+
+    /// Called with the size the window is about to be given
+    pub fn set_applied_size(&mut self, size: PhysicalSize<u32>) {
+        self.applied_size = Some(size);
+    }
+
     /// Flushes changes to disk only if there are unsaved changes.
     pub fn sync_to_disk(&mut self) {
         if self.dirty {
@@ -88,20 +328,188 @@ impl ConfigManager {
     }
 }
 
-/// Determines where the window should be placed based on saved state and available monitors.
-pub fn resolve_window_position(
+/// The parts of a monitor that placement depends on, copied out of
+/// `tauri::Monitor` so the math can run (and be tested) without a display.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonitorGeometry {
+    pub name: Option<String>,
+    pub position: PhysicalPosition<i32>,
+    pub size: PhysicalSize<u32>,
+    pub scale_factor: f64,
+    /// The part not covered by panels and docks, None when unknown
+    pub work_area: Option<WorkArea>,
+}
+
+/// A rectangle in physical pixels
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WorkArea {
+    pub position: PhysicalPosition<i32>,
+    pub size: PhysicalSize<u32>,
+}
+
+impl WorkArea {
+    fn right(&self) -> i32 {
+        self.position.x + self.size.width as i32
+    }
+
+    fn bottom(&self) -> i32 {
+        self.position.y + self.size.height as i32
+    }
+}
+
+impl From<&Monitor> for MonitorGeometry {
+    fn from(monitor: &Monitor) -> Self {
+        Self {
+            name: monitor.name().cloned(),
+            position: *monitor.position(),
+            size: *monitor.size(),
+            scale_factor: monitor.scale_factor(),
+            work_area: None,
+        }
+    }
+}
+
+/// Space a panel or dock reserves along the screen edges
+/// (`_NET_WM_STRUT_PARTIAL`), in root window coordinates. Each edge's
+/// thickness comes with the range it covers along that edge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Strut {
+    pub left: u32,
+    pub right: u32,
+    pub top: u32,
+    pub bottom: u32,
+    pub left_y: (u32, u32),
+    pub right_y: (u32, u32),
+    pub top_x: (u32, u32),
+    pub bottom_x: (u32, u32),
+}
+
+impl Strut {
+    /// From the 12 values of `_NET_WM_STRUT_PARTIAL`
+    pub fn from_partial(values: &[u32; 12]) -> Self {
+        Self {
+            left: values[0],
+            right: values[1],
+            top: values[2],
+            bottom: values[3],
+            left_y: (values[4], values[5]),
+            right_y: (values[6], values[7]),
+            top_x: (values[8], values[9]),
+            bottom_x: (values[10], values[11]),
+        }
+    }
+
+    /// From the older `_NET_WM_STRUT`, which spans whole edges
+    pub fn from_legacy(values: &[u32; 4]) -> Self {
+        let whole = (0, u32::MAX);
+        Self {
+            left: values[0],
+            right: values[1],
+            top: values[2],
+            bottom: values[3],
+            left_y: whole,
+            right_y: whole,
+            top_x: whole,
+            bottom_x: whole,
+        }
+    }
+}
+
+/// What is left of `monitor` once the struts overlapping it are taken off.
+/// `screen` is the size of the root window the struts are measured from.
+pub fn work_area(
+    monitor: &MonitorGeometry,
+    screen: PhysicalSize<u32>,
+    struts: &[Strut],
+) -> WorkArea {
+    let (mut left, mut top) = (monitor.position.x, monitor.position.y);
+    let (mut right, mut bottom) = (monitor.right(), monitor.bottom());
+    let overlaps = |range: (u32, u32), start: i32, end: i32| {
+        (range.0 as i64) < end as i64 && (range.1 as i64) >= start as i64
+    };
+
+    for strut in struts {
+        let (screen_w, screen_h) = (screen.width as i32, screen.height as i32);
+        if strut.left > 0 && overlaps(strut.left_y, monitor.position.y, monitor.bottom()) {
+            let edge = strut.left as i32;
+            if edge > left && edge < right {
+                left = edge;
+            }
+        }
+        if strut.right > 0 && overlaps(strut.right_y, monitor.position.y, monitor.bottom()) {
+            let edge = screen_w - strut.right as i32;
+            if edge < right && edge > left {
+                right = edge;
+            }
+        }
+        if strut.top > 0 && overlaps(strut.top_x, monitor.position.x, monitor.right()) {
+            let edge = strut.top as i32;
+            if edge > top && edge < bottom {
+                top = edge;
+            }
+        }
+        if strut.bottom > 0 && overlaps(strut.bottom_x, monitor.position.x, monitor.right()) {
+            let edge = screen_h - strut.bottom as i32;
+            if edge < bottom && edge > top {
+                bottom = edge;
+            }
+        }
+    }
+
+    WorkArea {
+        position: PhysicalPosition::new(left, top),
+        size: PhysicalSize::new((right - left) as u32, (bottom - top) as u32),
+    }
+}
+
+impl MonitorGeometry {
+    fn right(&self) -> i32 {
+        self.position.x + self.size.width as i32
+    }
+
+    fn bottom(&self) -> i32 {
+        self.position.y + self.size.height as i32
+    }
+
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        x >= self.position.x && x < self.right() && y >= self.position.y && y < self.bottom()
+    }
+
+    /// The work area, or the whole monitor when it is unknown
+    fn usable(&self) -> WorkArea {
+        self.work_area.unwrap_or(WorkArea {
+            position: self.position,
+            size: self.size,
+        })
+    }
+
+    /// Scale factor, or 1 if the monitor reports none
+    fn scale(&self) -> f64 {
+        if self.scale_factor > 0.0 {
+            self.scale_factor
+        } else {
+            1.0
+        }
+    }
+}
+
+/// Determines where the window should be placed, and how big it should be,
+/// based on saved state and available monitors. `default_size` is used until
+/// the window has been resized. None when there are no monitors.
+pub fn resolve_window_geometry(
     state: &WindowState,
-    available_monitors: &[Monitor],
-    window_size: PhysicalSize<u32>,
-) -> PhysicalPosition<i32> {
+    available_monitors: &[MonitorGeometry],
+    default_size: PhysicalSize<u32>,
+) -> Option<(PhysicalPosition<i32>, PhysicalSize<u32>)> {
     // 1. Try to restore saved position if monitor exists and position is valid
     if let Some(saved_monitor_name) = &state.monitor_name {
-        if let Some(monitor) = available_monitors.iter().find(|m| {
-            m.name()
-                .is_some_and(|n| n.as_str() == saved_monitor_name.as_str())
-        }) {
+        if let Some(monitor) = available_monitors
+            .iter()
+            .find(|m| m.name.as_deref() == Some(saved_monitor_name.as_str()))
+        {
+            let window_size = resolve_window_size(state, monitor, default_size);
             if is_position_valid(state.x, state.y, monitor, window_size) {
-                return PhysicalPosition::new(state.x, state.y);
+                return Some((PhysicalPosition::new(state.x, state.y), window_size));
             }
         }
     }
@@ -109,53 +517,579 @@ pub fn resolve_window_position(
     // 2. Fallback: Default to Bottom-Center of Primary (or first available)
     let target_monitor = available_monitors
         .iter()
-        .find(|m| m.scale_factor() > 0.0) // Just a check to get first valid one
-        .unwrap_or(&available_monitors[0]);
+        .find(|m| m.scale_factor > 0.0) // Just a check to get first valid one
+        .or_else(|| available_monitors.first())?;
 
-    calculate_bottom_center(target_monitor, window_size)
+    let window_size = resolve_window_size(state, target_monitor, default_size);
+    Some((
+        calculate_bottom_center(target_monitor, window_size),
+        window_size,
+    ))
 }
 
-/// Checks if a coordinate is "valid" based on bounds and visibility heuristics.
-fn is_position_valid(x: i32, y: i32, monitor: &Monitor, window_size: PhysicalSize<u32>) -> bool {
-    is_top_left_within_monitor(x, y, monitor)
-        && has_min_vertical_visibility(y, monitor, window_size)
+/// The saved size on `monitor`, shrunk to fit inside its edges, or
+/// `default_size` if the window has never been resized
+pub fn resolve_window_size(
+    state: &WindowState,
+    monitor: &MonitorGeometry,
+    default_size: PhysicalSize<u32>,
+) -> PhysicalSize<u32> {
+    let (Some(width), Some(height)) = (state.width, state.height) else {
+        return default_size;
+    };
+    let to_physical = |logical: u32| (logical as f64 * monitor.scale()).round() as u32;
+
+    let fit = |saved: u32, min: u32, available: u32| {
+        let available = available.saturating_sub(2 * EDGE_PADDING as u32).max(1);
+        to_physical(saved.max(min)).min(available)
+    };
+    PhysicalSize::new(
+        fit(width, MIN_WINDOW_SIZE.0, monitor.usable().size.width),
+        fit(height, MIN_WINDOW_SIZE.1, monitor.usable().size.height),
+    )
 }
 
-/// Ensures the window's top-left corner is strictly inside the monitor bounds.
-fn is_top_left_within_monitor(x: i32, y: i32, monitor: &Monitor) -> bool {
-    let m_pos = monitor.position();
-    let m_size = monitor.size();
+/// The docked sidebar: the full height of the work area along the saved
+/// edge, at the saved width kept between the minimum and half the monitor
+pub fn dock_geometry(
+    state: &WindowState,
+    monitor: &MonitorGeometry,
+) -> (PhysicalPosition<i32>, PhysicalSize<u32>) {
+    let area = monitor.usable();
+    let logical = state
+        .dock_width
+        .unwrap_or(DEFAULT_DOCK_WIDTH)
+        .max(MIN_WINDOW_SIZE.0);
+    let width =
+        ((logical as f64 * monitor.scale()).round() as u32).min((area.size.width / 2).max(1));
+    let x = match state.dock_edge {
+        DockEdge::Left => area.position.x,
+        DockEdge::Right => area.right() - width as i32,
+    };
+    (
+        PhysicalPosition::new(x, area.position.y),
+        PhysicalSize::new(width, area.size.height),
+    )
+}
+
+/// Where a layer-shell surface goes. Layer surfaces can't be given absolute
+/// coordinates, only anchored to edges of a monitor with margins.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayerPlacement {
+    pub monitor_name: Option<String>,
+    /// Distance from the monitor's left and top edges, logical pixels
+    pub margin: (i32, i32),
+    /// Docked sidebars are anchored to this edge and stretched top to bottom
+    pub dock_edge: Option<DockEdge>,
+}
+
+/// The layer-shell equivalent of putting the window's top-left corner at
+/// `pos`. None when `pos` is on none of the monitors.
+pub fn layer_placement(
+    pos: PhysicalPosition<i32>,
+    monitors: &[MonitorGeometry],
+    dock_edge: Option<DockEdge>,
+) -> Option<LayerPlacement> {
+    let monitor = monitor_containing(monitors, pos.x, pos.y)?;
+    let to_logical = |offset: i32| (offset as f64 / monitor.scale()).round() as i32;
+    Some(LayerPlacement {
+        monitor_name: monitor.name.clone(),
+        margin: (
+            to_logical(pos.x - monitor.position.x),
+            to_logical(pos.y - monitor.position.y),
+        ),
+        dock_edge,
+    })
+}
 
-    x >= m_pos.x
-        && x < (m_pos.x + m_size.width as i32)
-        && y >= m_pos.y
-        && y < (m_pos.y + m_size.height as i32)
+/// The monitor showing the point (x, y), if any
+pub fn monitor_containing(
+    monitors: &[MonitorGeometry],
+    x: i32,
+    y: i32,
+) -> Option<&MonitorGeometry> {
+    monitors.iter().find(|m| m.contains(x, y))
+}
+
+/// Places the window with its top-left corner at the pointer, kept on the
+/// pointer's monitor, at the saved size fitted to that monitor. None when
+/// the pointer is on none of them.
+pub fn place_near_cursor(
+    cursor_x: i32,
+    cursor_y: i32,
+    state: &WindowState,
+    monitors: &[MonitorGeometry],
+    default_size: PhysicalSize<u32>,
+) -> Option<(PhysicalPosition<i32>, PhysicalSize<u32>)> {
+    let monitor = monitor_containing(monitors, cursor_x, cursor_y)?;
+    let window_size = resolve_window_size(state, monitor, default_size);
+    Some((
+        clamp_to_monitor(cursor_x, cursor_y, monitor, window_size),
+        window_size,
+    ))
+}
+
+/// Moves a window anchored at (x, y) so it stays in the monitor's work area,
+/// 10px in from the edges. A window too big for it is pinned to its top-left.
+pub fn clamp_to_monitor(
+    x: i32,
+    y: i32,
+    monitor: &MonitorGeometry,
+    window_size: PhysicalSize<u32>,
+) -> PhysicalPosition<i32> {
+    let area = monitor.usable();
+    let min_x = area.position.x + EDGE_PADDING;
+    let min_y = area.position.y + EDGE_PADDING;
+    // Never below the minimum, or clamp would panic
+    let max_x = (area.right() - window_size.width as i32 - EDGE_PADDING).max(min_x);
+    let max_y = (area.bottom() - window_size.height as i32 - EDGE_PADDING).max(min_y);
+
+    PhysicalPosition::new(x.clamp(min_x, max_x), y.clamp(min_y, max_y))
+}
+
+/// Checks if a coordinate is "valid" based on bounds and visibility heuristics.
+fn is_position_valid(
+    x: i32,
+    y: i32,
+    monitor: &MonitorGeometry,
+    window_size: PhysicalSize<u32>,
+) -> bool {
+    monitor.contains(x, y) && has_min_vertical_visibility(y, monitor, window_size)
 }
 
 /// Ensures at least the top half of the window remains visible on the monitor.
-fn has_min_vertical_visibility(y: i32, monitor: &Monitor, window_size: PhysicalSize<u32>) -> bool {
-    let m_pos = monitor.position();
-    let m_size = monitor.size();
+fn has_min_vertical_visibility(
+    y: i32,
+    monitor: &MonitorGeometry,
+    window_size: PhysicalSize<u32>,
+) -> bool {
     // Ensure y is not so low that the window falls off the bottom entirely.
     // We require the top half (height/2) to be above the bottom edge of the monitor.
-    y < (m_pos.y + m_size.height as i32 - (window_size.height as i32 / 2))
+    y < (monitor.bottom() - (window_size.height as i32 / 2))
 }
 
-/// Calculates a centered position at the bottom of the screen.
+/// Calculates a centered position at the bottom of the screen, just above
+/// the taskbar. Windows bigger than the monitor keep their top-left corner on it.
 fn calculate_bottom_center(
-    monitor: &Monitor,
+    monitor: &MonitorGeometry,
     window_size: PhysicalSize<u32>,
 ) -> PhysicalPosition<i32> {
+    // Room for a typical taskbar when the work area is unknown
     const PADDING_BOTTOM: i32 = 45;
+    // Gap above the taskbar when its top edge is known
+    const WORK_AREA_GAP: i32 = 12;
 
-    let m_pos = monitor.position();
-    let m_size = monitor.size();
+    let area = monitor.usable();
+    let m_pos = area.position;
+    let m_size = area.size;
+    let padding = if monitor.work_area.is_some() {
+        WORK_AREA_GAP
+    } else {
+        PADDING_BOTTOM
+    };
 
     // X = center horizontally
     let x = m_pos.x + (m_size.width as i32 / 2) - (window_size.width as i32 / 2);
 
     // Y = bottom - window height - padding
-    let y = m_pos.y + m_size.height as i32 - window_size.height as i32 - PADDING_BOTTOM;
+    let y = m_pos.y + m_size.height as i32 - window_size.height as i32 - padding;
+
+    PhysicalPosition::new(x.max(m_pos.x), y.max(m_pos.y))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    /// One to four monitors side by side, left to right, at random heights
+    /// and scales, like a typical multi-monitor desk
+    fn monitor_layout() -> impl Strategy<Value = Vec<MonitorGeometry>> {
+        (
+            -4000..4000i32,
+            prop::collection::vec(
+                (320..7680u32, 240..4320u32, -2000..2000i32, 0.5..3.0f64),
+                1..5,
+            ),
+        )
+            .prop_map(|(start_x, screens)| {
+                let mut x = start_x;
+                screens
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, (width, height, y, scale_factor))| {
+                        let monitor = MonitorGeometry {
+                            name: Some(format!("DP-{}", i + 1)),
+                            position: PhysicalPosition::new(x, y),
+                            size: PhysicalSize::new(width, height),
+                            scale_factor,
+                            work_area: None,
+                        };
+                        x += width as i32;
+                        monitor
+                    })
+                    .collect()
+            })
+    }
+
+    /// From tiny popups to windows bigger than the smallest monitors
+    fn window_size() -> impl Strategy<Value = PhysicalSize<u32>> {
+        (100..1600u32, 100..1200u32).prop_map(|(width, height)| PhysicalSize::new(width, height))
+    }
+
+    fn fits(monitor: &MonitorGeometry, window_size: PhysicalSize<u32>, padding: u32) -> bool {
+        window_size.width + 2 * padding <= monitor.size.width
+            && window_size.height + 2 * padding <= monitor.size.height
+    }
+
+    proptest! {
+        #[test]
+        fn prop_resolved_position_is_on_a_monitor(
+            monitors in monitor_layout(),
+            saved in (0..6usize, -12000..12000i32, -6000..6000i32),
+            size in window_size(),
+        ) {
+            let (index, x, y) = saved;
+            let state = WindowState {
+                // Past the end: a monitor that has been unplugged since
+                monitor_name: Some(format!("DP-{}", index + 1)),
+                x,
+                y,
+                ..Default::default()
+            };
+            let (pos, _) = resolve_window_geometry(&state, &monitors, size).unwrap();
+
+            prop_assert!(monitor_containing(&monitors, pos.x, pos.y).is_some());
+        }
+
+        #[test]
+        fn prop_valid_saved_position_is_kept(
+            monitors in monitor_layout(),
+            index in 0..4usize,
+            offset in (0.0..1.0f64, 0.0..1.0f64),
+            size in window_size(),
+        ) {
+            let monitor = &monitors[index % monitors.len()];
+            let x = monitor.position.x + (offset.0 * monitor.size.width as f64) as i32;
+            let y = monitor.position.y + (offset.1 * monitor.size.height as f64) as i32;
+            let state = WindowState {
+                monitor_name: monitor.name.clone(),
+                x,
+                y,
+                ..Default::default()
+            };
+            let (pos, _) = resolve_window_geometry(&state, &monitors, size).unwrap();
 
-    PhysicalPosition::new(x, y)
+            if is_position_valid(x, y, monitor, size) {
+                prop_assert_eq!(pos, PhysicalPosition::new(x, y));
+                // At least the top half of the window is on screen
+                prop_assert!(monitor.bottom() - y > size.height as i32 / 2);
+            }
+        }
+
+        #[test]
+        fn prop_default_position_fits_on_monitor(
+            monitors in monitor_layout(),
+            size in window_size(),
+        ) {
+            let state = WindowState::default();
+            let (pos, _) = resolve_window_geometry(&state, &monitors, size).unwrap();
+            let monitor = &monitors[0];
+
+            prop_assert!(monitor.contains(pos.x, pos.y));
+            if fits(monitor, size, 45) {
+                prop_assert!(is_position_valid(pos.x, pos.y, monitor, size));
+                prop_assert!(pos.x + size.width as i32 <= monitor.right());
+                prop_assert!(pos.y + size.height as i32 <= monitor.bottom());
+            }
+        }
+
+        #[test]
+        fn prop_default_position_clears_the_taskbar(
+            monitors in monitor_layout(),
+            panel in 24..120u32,
+            size in window_size(),
+        ) {
+            let mut monitors = monitors;
+            let screen_height = monitors.iter().map(|m| m.bottom()).max().unwrap() as u32;
+            let first = monitors[0].clone();
+            // Struts are measured in root window coordinates, which start at 0
+            prop_assume!(first.position.x >= 0 && first.position.y >= 0);
+            let strut = Strut {
+                bottom: screen_height - first.bottom() as u32 + panel,
+                bottom_x: (first.position.x as u32, first.right() as u32 - 1),
+                ..Default::default()
+            };
+            let screen = PhysicalSize::new(u32::MAX / 2, screen_height);
+            let area = work_area(&first, screen, &[strut]);
+            monitors[0].work_area = Some(area);
+
+            let state = WindowState::default();
+            let (pos, size) = resolve_window_geometry(&state, &monitors, size).unwrap();
+            if size.height + 2 * panel < first.size.height {
+                prop_assert!(pos.y + size.height as i32 <= area.bottom());
+                prop_assert!(area.bottom() <= first.bottom() - panel as i32);
+            }
+        }
+
+        #[test]
+        fn prop_cursor_placement_stays_on_pointer_monitor(
+            monitors in monitor_layout(),
+            index in 0..4usize,
+            offset in (0.0..1.0f64, 0.0..1.0f64),
+            size in window_size(),
+        ) {
+            let monitor = &monitors[index % monitors.len()];
+            let x = monitor.position.x + (offset.0 * monitor.size.width as f64) as i32;
+            let y = monitor.position.y + (offset.1 * monitor.size.height as f64) as i32;
+            let (pos, _) = place_near_cursor(x, y, &WindowState::default(), &monitors, size).unwrap();
+
+            prop_assert!(monitor.contains(pos.x, pos.y));
+            if fits(monitor, size, 10) {
+                prop_assert!(pos.x + size.width as i32 <= monitor.right() - 10);
+                prop_assert!(pos.y + size.height as i32 <= monitor.bottom() - 10);
+            }
+        }
+
+        #[test]
+        fn prop_clamped_window_stays_on_monitor(
+            monitors in monitor_layout(),
+            index in 0..4usize,
+            cursor in (-12000..12000i32, -6000..6000i32),
+            size in window_size(),
+        ) {
+            let monitor = &monitors[index % monitors.len()];
+            let pos = clamp_to_monitor(cursor.0, cursor.1, monitor, size);
+
+            prop_assert!(monitor.contains(pos.x, pos.y));
+            if fits(monitor, size, 10) {
+                prop_assert!(pos.x + size.width as i32 <= monitor.right() - 10);
+                prop_assert!(pos.y + size.height as i32 <= monitor.bottom() - 10);
+            }
+        }
+
+        #[test]
+        fn prop_dock_spans_monitor_edge(
+            monitors in monitor_layout(),
+            index in 0..4usize,
+            left in any::<bool>(),
+            dock_width in 1..8000u32,
+        ) {
+            let monitor = &monitors[index % monitors.len()];
+            let state = WindowState {
+                dock_edge: if left { DockEdge::Left } else { DockEdge::Right },
+                dock_width: Some(dock_width),
+                ..Default::default()
+            };
+            let (pos, size) = dock_geometry(&state, monitor);
+
+            prop_assert_eq!(pos.y, monitor.position.y);
+            prop_assert_eq!(size.height, monitor.size.height);
+            prop_assert!(size.width > 0 && size.width <= monitor.size.width / 2);
+            if left {
+                prop_assert_eq!(pos.x, monitor.position.x);
+            } else {
+                prop_assert_eq!(pos.x + size.width as i32, monitor.right());
+            }
+        }
+
+        #[test]
+        fn prop_restored_size_fits_on_monitor(
+            monitors in monitor_layout(),
+            index in 0..4usize,
+            saved in (1..8000u32, 1..8000u32),
+            default_size in window_size(),
+        ) {
+            let monitor = &monitors[index % monitors.len()];
+            let state = WindowState {
+                monitor_name: monitor.name.clone(),
+                width: Some(saved.0),
+                height: Some(saved.1),
+                ..Default::default()
+            };
+            let size = resolve_window_size(&state, monitor, default_size);
+
+            prop_assert!(size.width > 0 && size.height > 0);
+            prop_assert!(size.width + 20 <= monitor.size.width.max(21));
+            prop_assert!(size.height + 20 <= monitor.size.height.max(21));
+            // Kept as saved, scaled for the monitor, when there is room
+            let wanted = PhysicalSize::new(
+                (saved.0.max(MIN_WINDOW_SIZE.0) as f64 * monitor.scale_factor).round() as u32,
+                (saved.1.max(MIN_WINDOW_SIZE.1) as f64 * monitor.scale_factor).round() as u32,
+            );
+            if fits(monitor, wanted, 10) {
+                prop_assert_eq!(size, wanted);
+            }
+
+            let (pos, placed_size) =
+                resolve_window_geometry(&state, &monitors, default_size).unwrap();
+            prop_assert!(monitor_containing(&monitors, pos.x, pos.y).is_some());
+            let target = monitor_containing(&monitors, pos.x, pos.y).unwrap();
+            prop_assert_eq!(placed_size, resolve_window_size(&state, target, default_size));
+        }
+    }
+
+    #[test]
+    fn test_work_area_from_struts() {
+        let monitor = |x: i32, width: u32, height: u32| MonitorGeometry {
+            name: None,
+            position: PhysicalPosition::new(x, 0),
+            size: PhysicalSize::new(width, height),
+            scale_factor: 1.0,
+            work_area: None,
+        };
+        // A 1080p monitor next to a taller 1440p one, which has the taskbar
+        let small = monitor(0, 1920, 1080);
+        let tall = monitor(1920, 2560, 1440);
+        let screen = PhysicalSize::new(4480, 1440);
+        let taskbar = Strut::from_partial(&[0, 0, 0, 48, 0, 0, 0, 0, 0, 0, 1920, 4479]);
+        let top_bar = Strut::from_legacy(&[0, 0, 32, 0]);
+
+        assert_eq!(
+            work_area(&tall, screen, &[taskbar, top_bar]),
+            WorkArea {
+                position: PhysicalPosition::new(1920, 32),
+                size: PhysicalSize::new(2560, 1360),
+            }
+        );
+        // The taskbar's strut doesn't reach the smaller monitor
+        assert_eq!(
+            work_area(&small, screen, &[taskbar, top_bar]),
+            WorkArea {
+                position: PhysicalPosition::new(0, 32),
+                size: PhysicalSize::new(1920, 1048),
+            }
+        );
+
+        // Just above the taskbar rather than a guessed 45px
+        let tall = MonitorGeometry {
+            work_area: Some(work_area(&tall, screen, &[taskbar])),
+            ..tall
+        };
+        let pos = calculate_bottom_center(&tall, PhysicalSize::new(360, 480));
+        assert_eq!(pos, PhysicalPosition::new(3020, 1440 - 48 - 480 - 12));
+    }
+
+    #[test]
+    fn test_layer_placement_is_relative_to_the_monitor() {
+        let monitors = [
+            MonitorGeometry {
+                name: Some("DP-1".to_string()),
+                position: PhysicalPosition::new(0, 0),
+                size: PhysicalSize::new(1920, 1080),
+                scale_factor: 1.0,
+                work_area: None,
+            },
+            MonitorGeometry {
+                name: Some("DP-2".to_string()),
+                position: PhysicalPosition::new(1920, -200),
+                size: PhysicalSize::new(3840, 2160),
+                scale_factor: 2.0,
+                work_area: None,
+            },
+        ];
+
+        let placement = layer_placement(PhysicalPosition::new(2920, 800), &monitors, None).unwrap();
+        assert_eq!(placement.monitor_name.as_deref(), Some("DP-2"));
+        assert_eq!(placement.margin, (500, 500));
+        assert_eq!(
+            layer_placement(PhysicalPosition::new(-5, 0), &monitors, None),
+            None
+        );
+    }
+
+    #[test]
+    fn test_chosen_monitor() {
+        let monitor = |name: &str, x: i32| MonitorGeometry {
+            name: Some(name.to_string()),
+            position: PhysicalPosition::new(x, 0),
+            size: PhysicalSize::new(1920, 1080),
+            scale_factor: 1.0,
+            work_area: None,
+        };
+        let monitors = [monitor("DP-1", 0), monitor("HDMI-1", 1920)];
+        let mut config = ConfigManager::new(std::env::temp_dir().join("win11-clipboard-test-none"));
+        let chosen = |config: &ConfigManager| {
+            config
+                .chosen_monitor(&monitors, Some((100, 100)), Some((2500, 400)))
+                .and_then(|m| m.name)
+        };
+
+        assert_eq!(chosen(&config), None);
+        config.set_monitor_choice(MonitorChoice::from_setting("pointer"));
+        assert_eq!(chosen(&config).as_deref(), Some("DP-1"));
+        config.set_monitor_choice(MonitorChoice::from_setting("focused_window"));
+        assert_eq!(chosen(&config).as_deref(), Some("HDMI-1"));
+        config.set_monitor_choice(MonitorChoice::from_setting("HDMI-1"));
+        assert_eq!(chosen(&config).as_deref(), Some("HDMI-1"));
+        // Unplugged, or the pointer can't be found
+        config.set_monitor_choice(MonitorChoice::from_setting("DP-2"));
+        assert_eq!(chosen(&config), None);
+        config.set_monitor_choice(MonitorChoice::Pointer);
+        assert_eq!(config.chosen_monitor(&monitors, None, None), None);
+    }
+
+    #[test]
+    fn test_compact_density_opens_a_smaller_window() {
+        let mut config = ConfigManager::new(std::env::temp_dir().join("win11-clipboard-test-none"));
+        assert_eq!(config.default_window_size(2.0), PhysicalSize::new(720, 960));
+        config.set_density(UiDensity::Compact);
+        let compact = config.default_window_size(1.0);
+        assert!(compact.width < 360 && compact.height < 480);
+
+        // A size the user picked is kept
+        let monitor = MonitorGeometry {
+            name: None,
+            position: PhysicalPosition::new(0, 0),
+            size: PhysicalSize::new(1920, 1080),
+            scale_factor: 1.0,
+            work_area: None,
+        };
+        let state = WindowState {
+            width: Some(500),
+            height: Some(600),
+            ..Default::default()
+        };
+        assert_eq!(
+            resolve_window_size(&state, &monitor, compact),
+            PhysicalSize::new(500, 600)
+        );
+        assert_eq!(
+            resolve_window_size(&WindowState::default(), &monitor, compact),
+            compact
+        );
+    }
+
+    #[test]
+    fn test_dock_width_is_saved_while_docked() {
+        let mut config = ConfigManager::new(std::env::temp_dir().join("win11-clipboard-test-none"));
+        config.set_placement_mode(PlacementMode::Docked);
+        config.update_size(PhysicalSize::new(800, 1440), 2.0);
+        assert_eq!(config.get_state().dock_width, Some(400));
+        assert_eq!(config.get_state().height, None);
+    }
+
+    #[test]
+    fn test_window_size_is_saved_in_logical_pixels() {
+        let mut config = ConfigManager::new(std::env::temp_dir().join("win11-clipboard-test-none"));
+        config.update_size(PhysicalSize::new(720, 960), 2.0);
+        assert_eq!(
+            (config.get_state().width, config.get_state().height),
+            (Some(360), Some(480))
+        );
+
+        // The resize caused by applying a size is not recorded
+        config.set_applied_size(PhysicalSize::new(500, 500));
+        config.update_size(PhysicalSize::new(500, 500), 1.0);
+        assert_eq!(config.get_state().width, Some(360));
+    }
+
+    #[test]
+    fn test_no_monitors_has_no_position() {
+        let size = PhysicalSize::new(360, 480);
+        assert_eq!(
+            resolve_window_geometry(&WindowState::default(), &[], size),
+            None
+        );
+    }
 }
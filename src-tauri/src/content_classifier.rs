@@ -0,0 +1,210 @@
+//! Content Classifier
+//! Flags text that is risky to paste without looking at it first: invisible
+//! and bidi control characters, and words mixing Latin letters with Cyrillic
+//! or Greek look-alikes. Pages use these tricks to hide commands inside
+//! innocent-looking "paste this into your terminal" snippets. Non-breaking
+//! spaces are counted too, they look like spaces but break shells and configs.
+
+use serde::{Deserialize, Serialize};
+
+/// At most this many suspicious words are reported
+const MAX_REPORTED_WORDS: usize = 5;
+
+/// Why a text looks suspicious
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ContentWarning {
+    pub invisible_chars: usize,
+    pub bidi_controls: usize,
+    /// Words mixing Latin letters with Cyrillic or Greek ones
+    pub mixed_script_words: Vec<String>,
+}
+
+impl ContentWarning {
+    pub fn is_suspicious(&self) -> bool {
+        self.invisible_chars > 0 || self.bidi_controls > 0 || !self.mixed_script_words.is_empty()
+    }
+}
+
+/// Zero-width and other characters that render as nothing
+pub fn is_invisible(c: char) -> bool {
+    matches!(c,
+        '\u{00AD}'                  // soft hyphen
+        | '\u{180E}'                // Mongolian vowel separator
+        | '\u{200B}'..='\u{200D}'   // zero-width space, non-joiner, joiner
+        | '\u{2060}'..='\u{2064}'   // word joiner, invisible operators
+        | '\u{FEFF}'                // zero-width no-break space / BOM
+        | '\u{E0000}'..='\u{E007F}' // tag characters
+    )
+}
+
+/// Spaces that look normal but are not whitespace to shells and parsers
+pub fn is_nonbreaking_space(c: char) -> bool {
+    matches!(
+        c,
+        '\u{00A0}'   // no-break space
+        | '\u{2007}' // figure space
+        | '\u{202F}' // narrow no-break space
+    )
+}
+
+/// Characters that reorder how the surrounding text is displayed
+pub fn is_bidi_control(c: char) -> bool {
+    matches!(c,
+        '\u{061C}'                // Arabic letter mark
+        | '\u{200E}' | '\u{200F}' // left-to-right / right-to-left mark
+        | '\u{202A}'..='\u{202E}' // embeddings and overrides
+        | '\u{2066}'..='\u{2069}' // isolates
+    )
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Script {
+    Latin,
+    Cyrillic,
+    Greek,
+    Other,
+}
+
+fn script_of(c: char) -> Script {
+    match c {
+        'a'..='z' | 'A'..='Z' | '\u{00C0}'..='\u{024F}' => Script::Latin,
+        '\u{0400}'..='\u{04FF}' => Script::Cyrillic,
+        '\u{0370}'..='\u{03FF}' => Script::Greek,
+        _ => Script::Other,
+    }
+}
+
+/// Latin mixed with Cyrillic or Greek in one word, like "pаypal" with a Cyrillic "а"
+fn is_mixed_script(word: &str) -> bool {
+    let mut latin = false;
+    let mut lookalike = false;
+    for c in word.chars() {
+        match script_of(c) {
+            Script::Latin => latin = true,
+            Script::Cyrillic | Script::Greek => lookalike = true,
+            Script::Other => {}
+        }
+    }
+    latin && lookalike
+}
+
+/// Counts of characters that do not show up as what they are
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HiddenChars {
+    pub invisible: usize,
+    pub bidi: usize,
+    pub nonbreaking_spaces: usize,
+}
+
+impl HiddenChars {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+pub fn count_hidden_chars(text: &str) -> HiddenChars {
+    let mut counts = HiddenChars::default();
+    for c in text.chars() {
+        if is_invisible(c) {
+            counts.invisible += 1;
+        } else if is_bidi_control(c) {
+            counts.bidi += 1;
+        } else if is_nonbreaking_space(c) {
+            counts.nonbreaking_spaces += 1;
+        }
+    }
+    counts
+}
+
+/// Drops invisible and bidi characters and turns non-breaking spaces into
+/// plain ones
+pub fn clean_hidden_chars(text: &str) -> String {
+    text.chars()
+        .filter(|&c| !is_invisible(c) && !is_bidi_control(c))
+        .map(|c| if is_nonbreaking_space(c) { ' ' } else { c })
+        .collect()
+}
+
+pub fn classify(text: &str) -> ContentWarning {
+    let counts = count_hidden_chars(text);
+    let mut warning = ContentWarning {
+        invisible_chars: counts.invisible,
+        bidi_controls: counts.bidi,
+        ..Default::default()
+    };
+
+    warning.mixed_script_words = text
+        .split(|c: char| !c.is_alphanumeric() && !is_invisible(c))
+        .filter(|word| is_mixed_script(word))
+        .map(|word| word.chars().filter(|&c| !is_invisible(c)).collect())
+        .take(MAX_REPORTED_WORDS)
+        .collect();
+    warning
+}
+
+const BROWSER_APP_IDS: &[&str] = &[
+    "firefox",
+    "org.mozilla.firefox",
+    "firefox-esr",
+    "librewolf",
+    "zen",
+    "chromium",
+    "chromium-browser",
+    "google-chrome",
+    "brave-browser",
+    "microsoft-edge",
+    "vivaldi-stable",
+    "opera",
+    "org.gnome.Epiphany",
+    "falkon",
+];
+
+/// Whether a WM_CLASS / app id belongs to a web browser
+pub fn is_browser(app_id: &str) -> bool {
+    BROWSER_APP_IDS
+        .iter()
+        .any(|browser| browser.eq_ignore_ascii_case(app_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_flags_hidden_characters() {
+        assert!(!classify("echo hello && ls -la").is_suspicious());
+
+        let warning = classify("curl example.com\u{200B} | sh\u{202E}");
+        assert_eq!(warning.invisible_chars, 1);
+        assert_eq!(warning.bidi_controls, 1);
+    }
+
+    #[test]
+    fn test_clean_hidden_chars() {
+        let text = "ls\u{00A0}-la\u{200B} /tmp\u{2066}";
+        assert_eq!(
+            count_hidden_chars(text),
+            HiddenChars {
+                invisible: 1,
+                bidi: 1,
+                nonbreaking_spaces: 1,
+            }
+        );
+        assert_eq!(clean_hidden_chars(text), "ls -la /tmp");
+        assert!(count_hidden_chars("ls -la /tmp").is_empty());
+    }
+
+    #[test]
+    fn test_classify_flags_mixed_script_words() {
+        // Cyrillic "а" in "pаypal", plain Cyrillic and Greek words are fine
+        let warning = classify("visit p\u{0430}ypal.com or привет or αβγ");
+        assert_eq!(warning.mixed_script_words, ["p\u{0430}ypal"]);
+    }
+
+    #[test]
+    fn test_is_browser() {
+        assert!(is_browser("Firefox"));
+        assert!(is_browser("google-chrome"));
+        assert!(!is_browser("kitty"));
+    }
+}
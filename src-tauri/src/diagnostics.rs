@@ -0,0 +1,185 @@
+//! Diagnostics Module
+//! Probes runtime capabilities at startup and reports degraded features
+//! in a single desktop notification instead of failing silently on first use.
+
+use crate::listener_supervisor::{self, ListenerStatus};
+use crate::session::{self, command_exists};
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::process::Command;
+
+/// Application name shown in desktop notifications
+const NOTIFY_APP_NAME: &str = "Clipboard History";
+
+/// Action key returned by notify-send when the repair button is clicked
+const REPAIR_ACTION: &str = "repair";
+
+/// Result of the most recent startup probe
+static LAST_REPORT: Mutex<Option<HealthReport>> = Mutex::new(None);
+
+/// A single feature that is not working as expected
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct HealthIssue {
+    /// Short name of the affected feature (e.g. "Paste")
+    pub feature: String,
+    /// What is wrong and how it affects the user
+    pub detail: String,
+}
+
+/// Consolidated result of startup capability probing
+#[derive(Debug, Clone, Serialize, Default)]
+pub struct HealthReport {
+    /// Paste injection methods that appear usable
+    pub paste_methods: Vec<String>,
+    /// Whether the global shortcut was registered with the desktop environment
+    pub shortcut_registered: bool,
+    /// Everything that is degraded
+    pub issues: Vec<HealthIssue>,
+    /// Live state of the clipboard listeners, refreshed on every request
+    pub listeners: Vec<ListenerStatus>,
+}
+
+impl HealthReport {
+    pub fn is_degraded(&self) -> bool {
+        !self.issues.is_empty()
+    }
+
+    /// One line per issue, suitable for a notification body
+    pub fn summary(&self) -> String {
+        self.issues
+            .iter()
+            .map(|i| format!("• {}: {}", i.feature, i.detail))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Probes the capabilities the app depends on and stores the report.
+/// `shortcut_registered` comes from the startup shortcut registration.
+#[cfg(target_os = "linux")]
+pub fn probe_startup_health(shortcut_registered: bool) -> HealthReport {
+    let paste_methods: Vec<String> = crate::input_simulator::probe_paste_methods()
+        .into_iter()
+        .map(String::from)
+        .collect();
+
+    let mut issues = Vec::new();
+
+    if paste_methods.is_empty() {
+        issues.push(HealthIssue {
+            feature: "Paste".to_string(),
+            detail: "No keystroke injection method is available; items will only be copied."
+                .to_string(),
+        });
+    }
+
+    if !shortcut_registered {
+        issues.push(HealthIssue {
+            feature: "Shortcut".to_string(),
+            detail: "Super+V could not be registered with your desktop.".to_string(),
+        });
+    }
+
+    if session::is_wayland() && !command_exists("wl-copy") {
+        issues.push(HealthIssue {
+            feature: "GIF paste".to_string(),
+            detail: "wl-clipboard is not installed; GIFs will be pasted as links.".to_string(),
+        });
+    }
+
+    let report = HealthReport {
+        paste_methods,
+        shortcut_registered,
+        issues,
+        listeners: listener_supervisor::listener_statuses(),
+    };
+
+    *LAST_REPORT.lock() = Some(report.clone());
+    report
+}
+
+/// Shows one notification summarizing the degraded features.
+/// Blocks until the notification is dismissed and returns true if the user
+/// clicked the button to open the Setup Wizard.
+pub fn notify_degraded(report: &HealthReport) -> bool {
+    if !report.is_degraded() {
+        return false;
+    }
+
+    let title = "Clipboard History is running with limited features";
+    let body = report.summary();
+
+    // notify-send >= 0.7.10 supports actions and prints the chosen key on stdout
+    let with_action = Command::new("notify-send")
+        .args([
+            "--app-name",
+            NOTIFY_APP_NAME,
+            "--icon",
+            "win11-clipboard-history",
+        ])
+        .arg(format!("--action={}=Open Setup Wizard", REPAIR_ACTION))
+        .args([title, &body])
+        .output();
+
+    match with_action {
+        Ok(output) if output.status.success() => {
+            String::from_utf8_lossy(&output.stdout).trim() == REPAIR_ACTION
+        }
+        _ => {
+            // Older notify-send: plain notification without a button
+            if let Err(e) = Command::new("notify-send")
+                .args([
+                    "--app-name",
+                    NOTIFY_APP_NAME,
+                    "--icon",
+                    "win11-clipboard-history",
+                ])
+                .args([title, &body])
+                .status()
+            {
+                eprintln!("[Diagnostics] Failed to send notification: {}", e);
+            }
+            false
+        }
+    }
+}
+
+/// Returns the report from the startup probe, if it has run yet
+#[tauri::command]
+pub fn get_startup_health() -> Option<HealthReport> {
+    let mut report = LAST_REPORT.lock().clone()?;
+    report.listeners = listener_supervisor::listener_statuses();
+    Some(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summary_lists_every_issue() {
+        let report = HealthReport {
+            issues: vec![
+                HealthIssue {
+                    feature: "Paste".to_string(),
+                    detail: "broken".to_string(),
+                },
+                HealthIssue {
+                    feature: "Shortcut".to_string(),
+                    detail: "missing".to_string(),
+                },
+            ],
+            ..Default::default()
+        };
+
+        assert!(report.is_degraded());
+        assert_eq!(report.summary(), "• Paste: broken\n• Shortcut: missing");
+    }
+
+    #[test]
+    fn test_healthy_report_does_not_notify() {
+        let report = HealthReport::default();
+        assert!(!report.is_degraded());
+        assert!(!notify_degraded(&report));
+    }
+}
@@ -0,0 +1,156 @@
+//! Emoji Dataset
+//! The emoji panel ships with the emoji list of the frontend build. A newer
+//! list, for a Unicode release that came out after the binary, can be
+//! installed as a data file: EmojiManager loads it at startup and the panel
+//! puts its entries over the built-in ones, adding the new emojis and
+//! replacing the names and keywords of those it already had. The file holds
+//! the Unicode version it is for, and only a newer one replaces it.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+/// Name of the installed dataset, inside the data directory
+pub const DATASET_FILE: &str = "emoji_dataset.json";
+
+/// Larger files are refused; the full Unicode list is well under this
+const MAX_DATASET_BYTES: u64 = 16 * 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DatasetEmoji {
+    pub char: String,
+    pub name: String,
+    #[serde(default)]
+    pub keywords: Vec<String>,
+    /// One of the panel's categories; left out, the panel guesses it from
+    /// the keywords
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EmojiDataset {
+    /// Unicode (or Emoji) version, such as "15.1"
+    pub version: String,
+    pub emojis: Vec<DatasetEmoji>,
+}
+
+/// Numeric parts of a dotted version; none when a part is not a number
+fn version_parts(version: &str) -> Option<Vec<u32>> {
+    version
+        .trim()
+        .split('.')
+        .map(|part| part.parse().ok())
+        .collect()
+}
+
+/// Whether `version` is later than `than`, "15.1" being later than "15"
+pub fn is_newer(version: &str, than: &str) -> bool {
+    match (version_parts(version), version_parts(than)) {
+        (Some(mut version), Some(mut than)) => {
+            let len = version.len().max(than.len());
+            version.resize(len, 0);
+            than.resize(len, 0);
+            version > than
+        }
+        _ => false,
+    }
+}
+
+/// Reads a dataset from JSON, dropping entries without a character or a
+/// name and repeats of a character
+pub fn parse(json: &str) -> Result<EmojiDataset, String> {
+    let mut dataset: EmojiDataset =
+        serde_json::from_str(json).map_err(|e| format!("Invalid emoji dataset: {}", e))?;
+    if version_parts(&dataset.version).is_none() {
+        return Err(format!("Invalid dataset version \"{}\"", dataset.version));
+    }
+    let mut seen = HashSet::new();
+    dataset.emojis.retain(|emoji| {
+        !emoji.char.trim().is_empty()
+            && !emoji.name.trim().is_empty()
+            && seen.insert(emoji.char.clone())
+    });
+    if dataset.emojis.is_empty() {
+        return Err("The emoji dataset has no emojis".to_string());
+    }
+    Ok(dataset)
+}
+
+/// The dataset installed in `data_dir`, if any
+pub fn load(data_dir: &Path) -> Result<Option<EmojiDataset>, String> {
+    let path = data_dir.join(DATASET_FILE);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(&path).map_err(|e| format!("Read error: {}", e))?;
+    parse(&content).map(Some)
+}
+
+/// Checks the dataset at `source` and copies it into `data_dir`, when it is
+/// for a later version than `installed`
+pub fn install(
+    source: &Path,
+    data_dir: &Path,
+    installed: Option<&EmojiDataset>,
+) -> Result<EmojiDataset, String> {
+    let size = fs::metadata(source)
+        .map_err(|e| format!("Cannot read {}: {}", source.display(), e))?
+        .len();
+    if size > MAX_DATASET_BYTES {
+        return Err("The emoji dataset is too large".to_string());
+    }
+    let content = fs::read_to_string(source)
+        .map_err(|e| format!("Cannot read {}: {}", source.display(), e))?;
+    let dataset = parse(&content)?;
+    if let Some(installed) = installed {
+        if !is_newer(&dataset.version, &installed.version) {
+            return Err(format!(
+                "Emoji dataset {} is already installed",
+                installed.version
+            ));
+        }
+    }
+    fs::create_dir_all(data_dir).map_err(|e| format!("Failed to create data dir: {}", e))?;
+    fs::write(data_dir.join(DATASET_FILE), content).map_err(|e| format!("Write error: {}", e))?;
+    Ok(dataset)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_install_dataset() {
+        assert!(is_newer("15.1", "15"));
+        assert!(!is_newer("15.0", "15"));
+        assert!(!is_newer("beta", "15"));
+
+        let json = r#"{
+            "version": "15.1",
+            "emojis": [
+                {"char": "🫨", "name": "shaking face", "keywords": ["shock"]},
+                {"char": "🫨", "name": "repeated"},
+                {"char": "", "name": "empty"}
+            ]
+        }"#;
+        let dataset = parse(json).unwrap();
+        assert_eq!(dataset.emojis.len(), 1);
+        assert!(parse(r#"{"version": "x", "emojis": []}"#).is_err());
+
+        let dir = std::env::temp_dir().join("emoji_dataset_test");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let source = dir.join("download.json");
+        fs::write(&source, json).unwrap();
+        let data_dir = dir.join("data");
+
+        assert_eq!(load(&data_dir).unwrap(), None);
+        let installed = install(&source, &data_dir, None).unwrap();
+        assert_eq!(load(&data_dir).unwrap(), Some(installed.clone()));
+        // The same version again is refused
+        assert!(install(&source, &data_dir, Some(&installed)).is_err());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -1,9 +1,19 @@
 //! Emoji Manager Module
 //! Handles emoji usage tracking with LRU (Least Recently Used) semantics and disk persistence.
-
+//! Kaomojis have recents of their own, kept in the same file. Emojis are
+//! returned with the preferred skin tone and gender, see `emoji_variants`.
+//! Custom shortcodes such as `:shrug:` map to a text or a small image and
+//! are kept in a file of their own. A newer emoji list than the built-in
+//! one can be installed, see `emoji_dataset`.
+
+use crate::emoji_dataset::{self, EmojiDataset};
+use crate::emoji_variants::{self, EmojiVariantSettings};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use image::ImageFormat;
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
 use std::time::{SystemTime, UNIX_EPOCH};
 
 /// Maximum number of recent emojis to track
@@ -12,6 +22,28 @@ const MAX_RECENT_EMOJIS: usize = 20;
 /// Persistence filename
 const EMOJI_HISTORY_FILE: &str = "emoji_history.json";
 
+/// Persistence filename of the custom shortcodes
+const CUSTOM_EMOJI_FILE: &str = "custom_emoji.json";
+
+/// Custom images are scaled down to fit in a square this size, in pixels
+const CUSTOM_IMAGE_SIZE: u32 = 128;
+
+/// Larger image files are refused, in bytes
+const MAX_CUSTOM_IMAGE_BYTES: usize = 5 * 1024 * 1024;
+
+/// A user-registered shortcode and what it pastes: a text, or an image
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CustomEmoji {
+    /// Without the colons, e.g. "shrug"
+    pub shortcode: String,
+    /// Empty for images
+    #[serde(default)]
+    pub text: String,
+    /// PNG as base64, at most `CUSTOM_IMAGE_SIZE` on each side
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
+}
+
 /// A single emoji usage entry
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct EmojiUsage {
@@ -31,12 +63,26 @@ pub struct EmojiUsage {
 struct EmojiHistoryWrapper {
     #[serde(default)]
     emojis: Vec<EmojiUsage>,
+    #[serde(default)]
+    kaomojis: Vec<EmojiUsage>,
+    #[serde(default)]
+    symbols: Vec<EmojiUsage>,
 }
 
 /// Manages emoji usage tracking
 pub struct EmojiManager {
     /// Recent emojis ordered by recency (index 0 is most recent)
     recent: Vec<EmojiUsage>,
+    /// Recent kaomojis, in the same order
+    recent_kaomojis: Vec<EmojiUsage>,
+    /// Recent symbols, in the same order
+    recent_symbols: Vec<EmojiUsage>,
+    /// Default skin tone and gender
+    variants: EmojiVariantSettings,
+    /// Custom shortcodes, in the order they were added
+    custom: Vec<CustomEmoji>,
+    /// Installed emoji list, over the built-in one
+    dataset: Option<EmojiDataset>,
     /// Path to the data directory
     data_dir: PathBuf,
 }
@@ -46,51 +92,77 @@ impl EmojiManager {
     pub fn new(data_dir: PathBuf) -> Self {
         let mut manager = Self {
             recent: Vec::with_capacity(MAX_RECENT_EMOJIS),
+            recent_kaomojis: Vec::new(),
+            recent_symbols: Vec::new(),
+            variants: EmojiVariantSettings::default(),
+            custom: Vec::new(),
+            dataset: None,
             data_dir,
         };
 
         if let Err(e) = manager.load_from_disk() {
             eprintln!("[EmojiManager] Failed to load history: {}", e);
         }
+        if let Err(e) = manager.load_custom() {
+            eprintln!("[EmojiManager] Failed to load custom emoji: {}", e);
+        }
+        match emoji_dataset::load(&manager.data_dir) {
+            Ok(dataset) => manager.dataset = dataset,
+            Err(e) => eprintln!("[EmojiManager] Failed to load emoji dataset: {}", e),
+        }
 
         manager
     }
 
     /// Record emoji usage (LRU semantics: move to front, increment count)
     pub fn record_usage(&mut self, emoji_char: &str) {
-        let now = current_time_millis();
-
-        // Check if emoji exists in recent list
-        if let Some(index) = self.recent.iter().position(|e| e.char == emoji_char) {
-            // Remove existing entry to update it
-            let mut entry = self.recent.remove(index);
-            entry.use_count += 1;
-            entry.last_used = now;
-            self.recent.insert(0, entry);
-        } else {
-            // Create new entry
-            let entry = EmojiUsage {
-                char: emoji_char.to_string(),
-                use_count: 1,
-                last_used: now,
-            };
-            self.recent.insert(0, entry);
-        }
+        record_in(&mut self.recent, emoji_char);
+        self.persist();
+    }
 
-        // Enforce capacity (LRU eviction from end)
-        if self.recent.len() > MAX_RECENT_EMOJIS {
-            self.recent.truncate(MAX_RECENT_EMOJIS);
-        }
+    /// Record kaomoji usage, with the same semantics as emojis
+    pub fn record_kaomoji_usage(&mut self, kaomoji: &str) {
+        record_in(&mut self.recent_kaomojis, kaomoji);
+        self.persist();
+    }
 
-        // Persist to disk
-        if let Err(e) = self.save_to_disk() {
-            eprintln!("[EmojiManager] Failed to save history: {}", e);
-        }
+    /// Record symbol usage, with the same semantics as emojis
+    pub fn record_symbol_usage(&mut self, symbol: &str) {
+        record_in(&mut self.recent_symbols, symbol);
+        self.persist();
+    }
+
+    pub fn set_variant_preference(&mut self, variants: EmojiVariantSettings) {
+        self.variants = variants;
+    }
+
+    /// `emoji` with the preferred skin tone and gender, if it takes them
+    pub fn preferred(&self, emoji: &str) -> String {
+        emoji_variants::apply(emoji, &self.variants)
+    }
+
+    fn with_preference(&self, mut usage: EmojiUsage) -> EmojiUsage {
+        usage.char = self.preferred(&usage.char);
+        usage
     }
 
     /// Get recent emojis (most recently used first)
     pub fn get_recent(&self) -> Vec<EmojiUsage> {
-        self.recent.clone()
+        self.recent
+            .iter()
+            .cloned()
+            .map(|usage| self.with_preference(usage))
+            .collect()
+    }
+
+    /// Get recent kaomojis (most recently used first)
+    pub fn get_recent_kaomojis(&self) -> Vec<EmojiUsage> {
+        self.recent_kaomojis.clone()
+    }
+
+    /// Get recent symbols (most recently used first)
+    pub fn get_recent_symbols(&self) -> Vec<EmojiUsage> {
+        self.recent_symbols.clone()
     }
 
     /// Get top N most used emojis
@@ -104,10 +176,97 @@ impl EmojiManager {
         });
         sorted.truncate(n);
         sorted
+            .into_iter()
+            .map(|usage| self.with_preference(usage))
+            .collect()
+    }
+
+    // --- Custom Emoji ---
+
+    pub fn get_custom_emojis(&self) -> Vec<CustomEmoji> {
+        self.custom.clone()
+    }
+
+    /// The custom emoji of `shortcode`, with or without its colons
+    pub fn find_custom_emoji(&self, shortcode: &str) -> Option<&CustomEmoji> {
+        let shortcode = normalize_shortcode(shortcode)?;
+        self.custom
+            .iter()
+            .find(|emoji| emoji.shortcode == shortcode)
+    }
+
+    /// Registers `shortcode` for `text`, or for the image file in `image`
+    /// (base64, any format the image crate reads), replacing what it stood
+    /// for before
+    pub fn add_custom_emoji(
+        &mut self,
+        shortcode: &str,
+        text: &str,
+        image: Option<&str>,
+    ) -> Result<CustomEmoji, String> {
+        let shortcode = normalize_shortcode(shortcode).ok_or(
+            "Shortcodes use letters, digits, '_', '-' and '+' only, like :shrug:".to_string(),
+        )?;
+        let image = image.map(shrink_custom_image).transpose()?;
+        if image.is_none() && text.is_empty() {
+            return Err("A custom emoji needs a text or an image".to_string());
+        }
+        let emoji = CustomEmoji {
+            shortcode,
+            text: if image.is_some() {
+                String::new()
+            } else {
+                text.to_string()
+            },
+            image,
+        };
+        match self
+            .custom
+            .iter_mut()
+            .find(|existing| existing.shortcode == emoji.shortcode)
+        {
+            Some(existing) => *existing = emoji.clone(),
+            None => self.custom.push(emoji.clone()),
+        }
+        self.save_custom()?;
+        Ok(emoji)
+    }
+
+    /// Returns false when no custom emoji had that shortcode
+    pub fn remove_custom_emoji(&mut self, shortcode: &str) -> Result<bool, String> {
+        let Some(shortcode) = normalize_shortcode(shortcode) else {
+            return Ok(false);
+        };
+        let before = self.custom.len();
+        self.custom.retain(|emoji| emoji.shortcode != shortcode);
+        if self.custom.len() == before {
+            return Ok(false);
+        }
+        self.save_custom().map(|()| true)
     }
 
     // --- Persistence Helpers ---
 
+    /// The installed emoji list, none when the built-in one is used
+    pub fn get_dataset(&self) -> Option<&EmojiDataset> {
+        self.dataset.as_ref()
+    }
+
+    /// Installs the emoji list at `source`, which must be for a later
+    /// Unicode version than the installed one; returns that version
+    pub fn install_dataset(&mut self, source: &Path) -> Result<String, String> {
+        let dataset = emoji_dataset::install(source, &self.data_dir, self.dataset.as_ref())?;
+        let version = dataset.version.clone();
+        self.dataset = Some(dataset);
+        Ok(version)
+    }
+
+    fn persist(&self) {
+        if let Err(e) = self.save_to_disk() {
+            eprintln!("[EmojiManager] Failed to save history: {}", e);
+        }
+    }
+
     fn history_path(&self) -> PathBuf {
         self.data_dir.join(EMOJI_HISTORY_FILE)
     }
@@ -123,11 +282,13 @@ impl EmojiManager {
             serde_json::from_str(&content).map_err(|e| format!("Parse error: {}", e))?;
 
         self.recent = wrapper.emojis;
+        self.recent_kaomojis = wrapper.kaomojis;
+        self.recent_symbols = wrapper.symbols;
 
         // Ensure we respect limits even if disk file was modified manually
-        if self.recent.len() > MAX_RECENT_EMOJIS {
-            self.recent.truncate(MAX_RECENT_EMOJIS);
-        }
+        self.recent.truncate(MAX_RECENT_EMOJIS);
+        self.recent_kaomojis.truncate(MAX_RECENT_EMOJIS);
+        self.recent_symbols.truncate(MAX_RECENT_EMOJIS);
 
         eprintln!("[EmojiManager] Loaded {} recent emojis", self.recent.len());
         Ok(())
@@ -141,6 +302,8 @@ impl EmojiManager {
 
         let wrapper = EmojiHistoryWrapper {
             emojis: self.recent.clone(),
+            kaomojis: self.recent_kaomojis.clone(),
+            symbols: self.recent_symbols.clone(),
         };
 
         let content = serde_json::to_string_pretty(&wrapper)
@@ -149,6 +312,92 @@ impl EmojiManager {
         fs::write(self.history_path(), content).map_err(|e| format!("Write error: {}", e))?;
         Ok(())
     }
+
+    fn custom_path(&self) -> PathBuf {
+        self.data_dir.join(CUSTOM_EMOJI_FILE)
+    }
+
+    fn load_custom(&mut self) -> Result<(), String> {
+        let path = self.custom_path();
+        if !path.exists() {
+            return Ok(());
+        }
+        let content = fs::read_to_string(&path).map_err(|e| format!("Read error: {}", e))?;
+        self.custom = serde_json::from_str(&content).map_err(|e| format!("Parse error: {}", e))?;
+        Ok(())
+    }
+
+    fn save_custom(&self) -> Result<(), String> {
+        fs::create_dir_all(&self.data_dir)
+            .map_err(|e| format!("Failed to create data dir: {}", e))?;
+        let content = serde_json::to_string_pretty(&self.custom)
+            .map_err(|e| format!("Serialize error: {}", e))?;
+        fs::write(self.custom_path(), content).map_err(|e| format!("Write error: {}", e))
+    }
+}
+
+/// `shortcode` without its colons and in lower case; none when empty or
+/// holding other characters than letters, digits, '_', '-' and '+'
+fn normalize_shortcode(shortcode: &str) -> Option<String> {
+    let trimmed = shortcode.trim();
+    let bare = trimmed
+        .strip_prefix(':')
+        .and_then(|rest| rest.strip_suffix(':'))
+        .unwrap_or(trimmed)
+        .to_lowercase();
+    let valid = bare
+        .chars()
+        .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '+'));
+    (valid && !bare.is_empty()).then_some(bare)
+}
+
+/// Decodes a base64 image file and scales it down to fit
+/// `CUSTOM_IMAGE_SIZE`, returning it as base64 PNG
+fn shrink_custom_image(file: &str) -> Result<String, String> {
+    let bytes = BASE64
+        .decode(file)
+        .map_err(|e| format!("Invalid image data: {}", e))?;
+    if bytes.len() > MAX_CUSTOM_IMAGE_BYTES {
+        return Err("The image is too large for a custom emoji".to_string());
+    }
+    let image = image::load_from_memory(&bytes).map_err(|e| format!("Unreadable image: {}", e))?;
+    let image = if image.width() > CUSTOM_IMAGE_SIZE || image.height() > CUSTOM_IMAGE_SIZE {
+        image.thumbnail(CUSTOM_IMAGE_SIZE, CUSTOM_IMAGE_SIZE)
+    } else {
+        image
+    };
+    let mut png = Cursor::new(Vec::new());
+    image
+        .write_to(&mut png, ImageFormat::Png)
+        .map_err(|e| format!("Failed to encode image: {}", e))?;
+    Ok(BASE64.encode(png.into_inner()))
+}
+
+/// Moves `text` to the front of `recent`, counting the use, and evicts the
+/// least recent entry past the limit
+fn record_in(recent: &mut Vec<EmojiUsage>, text: &str) {
+    let now = current_time_millis();
+
+    // Check if it exists in the recent list
+    if let Some(index) = recent.iter().position(|e| e.char == text) {
+        // Remove existing entry to update it
+        let mut entry = recent.remove(index);
+        entry.use_count += 1;
+        entry.last_used = now;
+        recent.insert(0, entry);
+    } else {
+        recent.insert(
+            0,
+            EmojiUsage {
+                char: text.to_string(),
+                use_count: 1,
+                last_used: now,
+            },
+        );
+    }
+
+    // Enforce capacity (LRU eviction from end)
+    recent.truncate(MAX_RECENT_EMOJIS);
 }
 
 /// Helper to get current Unix timestamp in milliseconds
@@ -227,4 +476,67 @@ mod tests {
         assert_eq!(recent[0].char, "🦀");
         assert_eq!(recent[1].char, "🚀");
     }
+
+    #[test]
+    fn test_kaomoji_recents_are_separate() {
+        let (mut manager, dir) = get_temp_manager("emoji_kaomoji_test");
+
+        manager.record_usage("🙂");
+        manager.record_kaomoji_usage("¯\\_(ツ)_/¯");
+        manager.record_kaomoji_usage("(╯°□°）╯︵ ┻━┻");
+
+        let loaded_manager = EmojiManager::new(dir);
+        assert_eq!(loaded_manager.get_recent().len(), 1);
+        let kaomojis = loaded_manager.get_recent_kaomojis();
+        assert_eq!(kaomojis.len(), 2);
+        assert_eq!(kaomojis[0].char, "(╯°□°）╯︵ ┻━┻");
+    }
+
+    #[test]
+    fn test_symbol_recents_are_separate() {
+        let (mut manager, dir) = get_temp_manager("emoji_symbol_test");
+
+        manager.record_kaomoji_usage("¯\\_(ツ)_/¯");
+        manager.record_symbol_usage("→");
+        manager.record_symbol_usage("—");
+        manager.record_symbol_usage("→");
+
+        let loaded_manager = EmojiManager::new(dir);
+        assert_eq!(loaded_manager.get_recent_kaomojis().len(), 1);
+        let symbols = loaded_manager.get_recent_symbols();
+        assert_eq!(symbols.len(), 2);
+        assert_eq!(symbols[0].char, "→");
+        assert_eq!(symbols[0].use_count, 2);
+    }
+
+    #[test]
+    fn test_custom_emoji() {
+        let (mut manager, dir) = get_temp_manager("emoji_custom_test");
+
+        manager
+            .add_custom_emoji(":Shrug:", "¯\\_(ツ)_/¯", None)
+            .unwrap();
+        assert!(manager.add_custom_emoji("two words", "x", None).is_err());
+        assert!(manager.add_custom_emoji("empty", "", None).is_err());
+
+        let mut png = Cursor::new(Vec::new());
+        image::DynamicImage::new_rgba8(512, 256)
+            .write_to(&mut png, ImageFormat::Png)
+            .unwrap();
+        let party = manager
+            .add_custom_emoji("party", "", Some(&BASE64.encode(png.get_ref())))
+            .unwrap();
+        let bytes = BASE64.decode(party.image.unwrap()).unwrap();
+        let shrunk = image::load_from_memory(&bytes).unwrap();
+        assert_eq!((shrunk.width(), shrunk.height()), (128, 64));
+
+        let loaded_manager = EmojiManager::new(dir);
+        assert_eq!(loaded_manager.get_custom_emojis().len(), 2);
+        assert_eq!(
+            loaded_manager.find_custom_emoji("shrug").unwrap().text,
+            "¯\\_(ツ)_/¯"
+        );
+        assert!(manager.remove_custom_emoji(":party:").unwrap());
+        assert!(!manager.remove_custom_emoji("party").unwrap());
+    }
 }
@@ -0,0 +1,327 @@
+//! Emoji Variants
+//! Skin tones and genders of the people and hand emojis. The picker lists
+//! the base emojis; the tone and gender the user picked as default are
+//! applied to them here, and the other variants of an emoji can be listed
+//! for its variant picker. Only emojis that are a single base character,
+//! optionally with a tone and a gender sign, are handled; families, couples
+//! and professions keep their defaults.
+
+use serde::{Deserialize, Serialize};
+
+const ZWJ: char = '\u{200D}';
+/// Variation selector asking for the emoji presentation
+const VS16: char = '\u{FE0F}';
+const FEMALE_SIGN: char = '\u{2640}';
+const MALE_SIGN: char = '\u{2642}';
+
+/// Bases that take a skin tone modifier (Emoji_Modifier_Base)
+const TONE_BASES: &[(u32, u32)] = &[
+    (0x261D, 0x261D),
+    (0x26F9, 0x26F9),
+    (0x270A, 0x270D),
+    (0x1F385, 0x1F385),
+    (0x1F3C2, 0x1F3C4),
+    (0x1F3C7, 0x1F3C7),
+    (0x1F3CA, 0x1F3CC),
+    (0x1F442, 0x1F443),
+    (0x1F446, 0x1F450),
+    (0x1F466, 0x1F478),
+    (0x1F47C, 0x1F47C),
+    (0x1F481, 0x1F483),
+    (0x1F485, 0x1F487),
+    (0x1F48F, 0x1F48F),
+    (0x1F491, 0x1F491),
+    (0x1F4AA, 0x1F4AA),
+    (0x1F574, 0x1F575),
+    (0x1F57A, 0x1F57A),
+    (0x1F590, 0x1F590),
+    (0x1F595, 0x1F596),
+    (0x1F645, 0x1F647),
+    (0x1F64B, 0x1F64F),
+    (0x1F6A3, 0x1F6A3),
+    (0x1F6B4, 0x1F6B6),
+    (0x1F6C0, 0x1F6C0),
+    (0x1F6CC, 0x1F6CC),
+    (0x1F90C, 0x1F90C),
+    (0x1F90F, 0x1F90F),
+    (0x1F918, 0x1F91F),
+    (0x1F926, 0x1F926),
+    (0x1F930, 0x1F939),
+    (0x1F93C, 0x1F93E),
+    (0x1F977, 0x1F977),
+    (0x1F9B5, 0x1F9B6),
+    (0x1F9B8, 0x1F9B9),
+    (0x1F9BB, 0x1F9BB),
+    (0x1F9CD, 0x1F9CF),
+    (0x1F9D1, 0x1F9DD),
+    (0x1FAC3, 0x1FAC5),
+    (0x1FAF0, 0x1FAF8),
+];
+
+/// Bases with a woman and a man form, written with a gender sign
+const GENDER_BASES: &[u32] = &[
+    0x26F9, 0x1F3C3, 0x1F3C4, 0x1F3CA, 0x1F3CB, 0x1F3CC, 0x1F46E, 0x1F46F, 0x1F470, 0x1F471,
+    0x1F473, 0x1F477, 0x1F481, 0x1F482, 0x1F486, 0x1F487, 0x1F575, 0x1F645, 0x1F646, 0x1F647,
+    0x1F64B, 0x1F64D, 0x1F64E, 0x1F6A3, 0x1F6B4, 0x1F6B5, 0x1F6B6, 0x1F926, 0x1F935, 0x1F937,
+    0x1F938, 0x1F939, 0x1F93C, 0x1F93D, 0x1F93E, 0x1F9B8, 0x1F9B9, 0x1F9CD, 0x1F9CE, 0x1F9CF,
+    0x1F9D4, 0x1F9D6, 0x1F9D7, 0x1F9D8, 0x1F9D9, 0x1F9DA, 0x1F9DB, 0x1F9DC, 0x1F9DD, 0x1F9DE,
+    0x1F9DF,
+];
+
+/// Bases shown as text unless followed by VS16
+const TEXT_DEFAULT_BASES: &[u32] = &[
+    0x261D, 0x26F9, 0x270C, 0x270D, 0x1F3CB, 0x1F3CC, 0x1F574, 0x1F575, 0x1F590,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkinTone {
+    /// The yellow default
+    Default,
+    Light,
+    MediumLight,
+    Medium,
+    MediumDark,
+    Dark,
+}
+
+impl SkinTone {
+    pub const ALL: [SkinTone; 6] = [
+        Self::Default,
+        Self::Light,
+        Self::MediumLight,
+        Self::Medium,
+        Self::MediumDark,
+        Self::Dark,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Default => "default",
+            Self::Light => "light",
+            Self::MediumLight => "medium_light",
+            Self::Medium => "medium",
+            Self::MediumDark => "medium_dark",
+            Self::Dark => "dark",
+        }
+    }
+
+    pub fn from_setting(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|tone| tone.as_str() == value)
+    }
+
+    /// The Fitzpatrick modifier, none for the default
+    fn modifier(self) -> Option<char> {
+        match self {
+            Self::Default => None,
+            Self::Light => Some('\u{1F3FB}'),
+            Self::MediumLight => Some('\u{1F3FC}'),
+            Self::Medium => Some('\u{1F3FD}'),
+            Self::MediumDark => Some('\u{1F3FE}'),
+            Self::Dark => Some('\u{1F3FF}'),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Gender {
+    /// The gender-neutral person
+    Neutral,
+    Female,
+    Male,
+}
+
+impl Gender {
+    pub const ALL: [Gender; 3] = [Self::Neutral, Self::Female, Self::Male];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Neutral => "neutral",
+            Self::Female => "female",
+            Self::Male => "male",
+        }
+    }
+
+    pub fn from_setting(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|gender| gender.as_str() == value)
+    }
+
+    fn sign(self) -> Option<char> {
+        match self {
+            Self::Neutral => None,
+            Self::Female => Some(FEMALE_SIGN),
+            Self::Male => Some(MALE_SIGN),
+        }
+    }
+}
+
+/// The emoji variants section of the user settings
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EmojiVariantSettings {
+    /// Applied to emojis that take one, see `SkinTone`
+    #[serde(default = "default_skin_tone")]
+    pub skin_tone: String,
+    /// "neutral", "female" or "male", for emojis that have the three
+    #[serde(default = "default_gender")]
+    pub gender: String,
+}
+
+fn default_skin_tone() -> String {
+    SkinTone::Default.as_str().to_string()
+}
+
+fn default_gender() -> String {
+    Gender::Neutral.as_str().to_string()
+}
+
+impl Default for EmojiVariantSettings {
+    fn default() -> Self {
+        Self {
+            skin_tone: default_skin_tone(),
+            gender: default_gender(),
+        }
+    }
+}
+
+impl EmojiVariantSettings {
+    pub fn skin_tone(&self) -> SkinTone {
+        SkinTone::from_setting(&self.skin_tone).unwrap_or(SkinTone::Default)
+    }
+
+    pub fn gender(&self) -> Gender {
+        Gender::from_setting(&self.gender).unwrap_or(Gender::Neutral)
+    }
+
+    /// Falls back to the defaults for unknown values
+    pub fn validate(&mut self) {
+        if SkinTone::from_setting(&self.skin_tone).is_none() {
+            self.skin_tone = default_skin_tone();
+        }
+        if Gender::from_setting(&self.gender).is_none() {
+            self.gender = default_gender();
+        }
+    }
+}
+
+fn in_list(base: char, list: &[u32]) -> bool {
+    list.contains(&(base as u32))
+}
+
+fn takes_tone(base: char) -> bool {
+    let code = base as u32;
+    TONE_BASES
+        .iter()
+        .any(|&(first, last)| (first..=last).contains(&code))
+}
+
+fn is_tone_modifier(c: char) -> bool {
+    ('\u{1F3FB}'..='\u{1F3FF}').contains(&c)
+}
+
+/// The base character of `emoji` with its tone, gender sign and selector
+/// left out; none for other sequences
+fn base_of(emoji: &str) -> Option<char> {
+    let chars: Vec<char> = emoji.chars().filter(|&c| c != VS16).collect();
+    let (&base, mut rest) = chars.split_first()?;
+    if rest.first().is_some_and(|&c| is_tone_modifier(c)) {
+        rest = &rest[1..];
+    }
+    match rest {
+        [] => Some(base),
+        [ZWJ, sign] if *sign == FEMALE_SIGN || *sign == MALE_SIGN => Some(base),
+        _ => None,
+    }
+}
+
+fn variant(base: char, tone: SkinTone, gender: Gender) -> String {
+    let mut emoji = String::from(base);
+    match tone.modifier().filter(|_| takes_tone(base)) {
+        Some(modifier) => emoji.push(modifier),
+        None if in_list(base, TEXT_DEFAULT_BASES) => emoji.push(VS16),
+        None => {}
+    }
+    if let Some(sign) = gender.sign().filter(|_| in_list(base, GENDER_BASES)) {
+        emoji.push(ZWJ);
+        emoji.push(sign);
+        emoji.push(VS16);
+    }
+    emoji
+}
+
+/// `emoji` with the preferred tone and gender, when it is a plain base that
+/// takes either; anything else, including variants picked explicitly, is
+/// returned as is
+pub fn apply(emoji: &str, settings: &EmojiVariantSettings) -> String {
+    let plain = emoji.chars().filter(|&c| c != VS16).count() == 1;
+    match base_of(emoji).filter(|_| plain) {
+        Some(base) => {
+            let (tone, gender) = (settings.skin_tone(), settings.gender());
+            let changes = (tone != SkinTone::Default && takes_tone(base))
+                || (gender != Gender::Neutral && in_list(base, GENDER_BASES));
+            if changes {
+                variant(base, tone, gender)
+            } else {
+                emoji.to_string()
+            }
+        }
+        None => emoji.to_string(),
+    }
+}
+
+/// Every variant of the emoji `emoji` is one of, by gender then tone; just
+/// `emoji` when it has none
+pub fn variants(emoji: &str) -> Vec<String> {
+    let Some(base) = base_of(emoji) else {
+        return vec![emoji.to_string()];
+    };
+    let tones: &[SkinTone] = if takes_tone(base) {
+        &SkinTone::ALL
+    } else {
+        &[SkinTone::Default]
+    };
+    let genders: &[Gender] = if in_list(base, GENDER_BASES) {
+        &Gender::ALL
+    } else {
+        &[Gender::Neutral]
+    };
+    genders
+        .iter()
+        .flat_map(|&gender| tones.iter().map(move |&tone| variant(base, tone, gender)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_and_list_variants() {
+        let settings = |skin_tone: SkinTone, gender: Gender| EmojiVariantSettings {
+            skin_tone: skin_tone.as_str().to_string(),
+            gender: gender.as_str().to_string(),
+        };
+        let dark_woman = settings(SkinTone::Dark, Gender::Female);
+        assert_eq!(apply("👍", &dark_woman), "👍🏿");
+        assert_eq!(apply("🤷", &dark_woman), "🤷🏿\u{200D}♀\u{FE0F}");
+        assert_eq!(apply("☝\u{FE0F}", &dark_woman), "☝🏿");
+        assert_eq!(apply("🍕", &dark_woman), "🍕");
+        // Picked explicitly, or not a plain base
+        assert_eq!(apply("👍🏻", &dark_woman), "👍🏻");
+        assert_eq!(
+            apply("👨\u{200D}👩\u{200D}👧", &dark_woman),
+            "👨\u{200D}👩\u{200D}👧"
+        );
+        let defaults = EmojiVariantSettings::default();
+        assert_eq!(apply("☝\u{FE0F}", &defaults), "☝\u{FE0F}");
+
+        assert_eq!(variants("👍").len(), 6);
+        assert_eq!(variants("🍕"), ["🍕"]);
+        let shrugs = variants("🤷🏽\u{200D}♂\u{FE0F}");
+        assert_eq!(shrugs.len(), 18);
+        assert_eq!(shrugs[0], "🤷");
+        assert!(shrugs.contains(&"🤷🏽\u{200D}♂\u{FE0F}".to_string()));
+        // Gendered without tones
+        assert_eq!(variants("🧞").len(), 3);
+    }
+}
@@ -0,0 +1,220 @@
+//! Entity Detection
+//! Picks emails, phone numbers, IP addresses and file paths out of text
+//! items when they are copied, so their rows can offer "copy just the
+//! email", mailto:, ping or show-in-folder for each one. Detection is a
+//! single pass over the words of the text and stays conservative: plain
+//! numbers, dates and versions are not phone numbers.
+
+use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, Ipv6Addr};
+
+/// Entities kept per item
+const MAX_ENTITIES: usize = 10;
+
+/// Longer texts are not scanned
+const MAX_SCANNED_CHARS: usize = 20_000;
+
+/// Digits in a phone number, per E.164
+const PHONE_DIGITS: std::ops::RangeInclusive<usize> = 7..=15;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EntityKind {
+    Email,
+    Phone,
+    Ip,
+    Path,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Entity {
+    pub kind: EntityKind,
+    pub value: String,
+}
+
+/// Entities in `text`, in order of appearance, each value once
+pub fn detect(text: &str) -> Vec<Entity> {
+    if text.chars().count() > MAX_SCANNED_CHARS || is_file_uri_list(text) {
+        return Vec::new();
+    }
+
+    let mut entities: Vec<Entity> = Vec::new();
+    let mut push = |kind, value: &str| {
+        if !entities.iter().any(|entity| entity.value == value) {
+            entities.push(Entity {
+                kind,
+                value: value.to_string(),
+            });
+        }
+    };
+
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let mut i = 0;
+    while i < words.len() {
+        let word = trim_punctuation(words[i]);
+        if let Some(email) = email(word) {
+            push(EntityKind::Email, email);
+        } else if is_ip(word) {
+            push(EntityKind::Ip, word);
+        } else if is_path(word) {
+            push(EntityKind::Path, word);
+        } else if let Some((phone, used)) = phone(&words[i..]) {
+            push(EntityKind::Phone, &phone);
+            i += used;
+            continue;
+        }
+        i += 1;
+    }
+    entities.truncate(MAX_ENTITIES);
+    entities
+}
+
+/// Copied files come as file:// lines; those items have their own actions
+fn is_file_uri_list(text: &str) -> bool {
+    text.lines()
+        .filter(|line| !line.trim().is_empty())
+        .all(|line| line.trim().starts_with("file://"))
+}
+
+/// A word without the quotes, brackets and sentence punctuation around it
+fn trim_punctuation(word: &str) -> &str {
+    word.trim_matches(|c: char| "\"'()[]{}<>,;!?".contains(c))
+        .trim_end_matches(['.', ':'])
+}
+
+fn email(word: &str) -> Option<&str> {
+    let address = word.strip_prefix("mailto:").unwrap_or(word);
+    let (local, domain) = address.split_once('@')?;
+    let local_ok = !local.is_empty()
+        && local
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "._%+-".contains(c));
+    let labels: Vec<&str> = domain.split('.').collect();
+    let domain_ok = labels.len() >= 2
+        && labels.iter().all(|label| {
+            !label.is_empty()
+                && !label.starts_with('-')
+                && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        })
+        && labels
+            .last()
+            .is_some_and(|tld| tld.len() >= 2 && tld.chars().all(|c| c.is_ascii_alphabetic()));
+    (local_ok && domain_ok).then_some(address)
+}
+
+/// IPv4 in dotted form, or IPv6 with at least two groups written out
+fn is_ip(word: &str) -> bool {
+    match word.parse::<IpAddr>() {
+        Ok(IpAddr::V4(_)) => word.split('.').count() == 4,
+        Ok(IpAddr::V6(address)) => {
+            address != Ipv6Addr::UNSPECIFIED
+                && word.split(':').filter(|group| !group.is_empty()).count() >= 2
+        }
+        Err(_) => false,
+    }
+}
+
+/// Absolute or home-relative paths with at least one directory
+fn is_path(word: &str) -> bool {
+    let rest = word
+        .strip_prefix("~/")
+        .or_else(|| word.strip_prefix('/').filter(|rest| rest.contains('/')));
+    rest.is_some_and(|rest| {
+        !rest.is_empty()
+            && !rest.contains("//")
+            && rest
+                .chars()
+                .all(|c| !c.is_control() && !"<>|\"*?".contains(c))
+    })
+}
+
+/// A phone number starting at the first word, which may span a few words
+/// ("+1 555 123 4567"), and the number of words it takes. Needs a leading +
+/// or separators between its digits, and is not a date.
+fn phone(words: &[&str]) -> Option<(String, usize)> {
+    let is_phone_char = |c: char| c.is_ascii_digit() || "+-.() ".contains(c);
+    let mut number = String::new();
+    let mut used = 0;
+    for word in words.iter().take(5) {
+        // Keeps the brackets of an area code
+        let word = word
+            .trim_matches(|c: char| "\"',;!?".contains(c))
+            .trim_end_matches('.');
+        if word.is_empty() || !word.chars().all(is_phone_char) {
+            break;
+        }
+        // A + only starts a number
+        if used > 0 && word.contains('+') {
+            break;
+        }
+        if used > 0 {
+            number.push(' ');
+        }
+        number.push_str(word);
+        used += 1;
+    }
+
+    // Drop words taken after the last digit, like a lone "-"
+    while !number.ends_with(|c: char| c.is_ascii_digit()) {
+        let (rest, _) = number.rsplit_once(' ')?;
+        number.truncate(rest.len());
+        used -= 1;
+    }
+
+    let digits = number.chars().filter(char::is_ascii_digit).count();
+    let separated = number.contains(['-', ' ', '.', '(']);
+    let looks_like_date = number.len() == 10
+        && number.chars().enumerate().all(|(i, c)| match i {
+            4 | 7 => c == '-' || c == '.',
+            _ => c.is_ascii_digit(),
+        });
+    let is_phone = PHONE_DIGITS.contains(&digits)
+        && number.starts_with(|c: char| c == '+' || c == '(' || c.is_ascii_digit())
+        && (number.starts_with('+') || separated)
+        && !number.contains("..")
+        && !looks_like_date
+        // Dots alone separate versions and decimals, not phone digits
+        && (!number.contains('.') || number.contains(['-', ' ', '(', '+']));
+    is_phone.then_some((number, used))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_entities() {
+        let found = |text: &str| -> Vec<(EntityKind, String)> {
+            detect(text)
+                .into_iter()
+                .map(|entity| (entity.kind, entity.value))
+                .collect()
+        };
+        let entity = |kind, value: &str| (kind, value.to_string());
+
+        assert_eq!(
+            found(
+                "Mail <ana.silva+work@example.com.br>, call +55 11 91234-5678 or (555) 123-4567. \
+                 Server 192.168.0.10 / fe80::1ff:fe23:4567:890a, logs in ~/logs/app.log and /var/log/syslog."
+            ),
+            [
+                entity(EntityKind::Email, "ana.silva+work@example.com.br"),
+                entity(EntityKind::Phone, "+55 11 91234-5678"),
+                entity(EntityKind::Phone, "(555) 123-4567"),
+                entity(EntityKind::Ip, "192.168.0.10"),
+                entity(EntityKind::Ip, "fe80::1ff:fe23:4567:890a"),
+                entity(EntityKind::Path, "~/logs/app.log"),
+                entity(EntityKind::Path, "/var/log/syslog"),
+            ]
+        );
+
+        // Numbers, dates, versions and times are left alone
+        assert!(found("Order 123456789 on 2024-05-02, version 2.14.1 at 12:30:45").is_empty());
+        assert!(found("user@localhost and 3/4 and / alone and 1.2.3").is_empty());
+        assert_eq!(
+            found("mailto:bob@example.org"),
+            [entity(EntityKind::Email, "bob@example.org")]
+        );
+        assert!(found("file:///tmp/a.txt\nfile:///tmp/b.txt").is_empty());
+    }
+}
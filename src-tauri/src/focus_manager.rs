@@ -1,7 +1,12 @@
 //! Focus Manager Module
-//! Tracks and restores window focus for proper paste injection on X11.
+//! Tracks and restores window focus for proper paste injection, through a
+//! compositor adapter (Hyprland or sway/i3 IPC, KWin scripting, wlr foreign
+//! toplevel management) when one is running and X11 otherwise. The order can
+//! be overridden with `UserSettings::focus_strategies`.
 //! Also provides X11 window activation using EWMH protocols.
 
+#[cfg(target_os = "linux")]
+use parking_lot::Mutex;
 #[cfg(target_os = "linux")]
 use std::sync::atomic::{AtomicU32, Ordering};
 #[cfg(target_os = "linux")]
@@ -21,10 +26,129 @@ const FOCUS_RESTORE_DELAY: Duration = Duration::from_millis(150);
 #[cfg(target_os = "linux")]
 static LAST_FOCUSED_WINDOW: AtomicU32 = AtomicU32::new(0);
 
+// --- Compositor Adapters ---
+
+/// A window as identified by a compositor adapter
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdapterWindow {
+    /// Compositor-specific handle (Hyprland address, container id...)
+    pub id: String,
+    /// Application id or window class
+    pub app_id: String,
+}
+
+/// Compositor-specific way to find the active window and focus it again,
+/// for Wayland sessions where the X11 calls only reach XWayland clients
+pub trait FocusAdapter: Sync {
+    /// Name used in `UserSettings::focus_strategies`
+    fn id(&self) -> &'static str;
+
+    fn name(&self) -> &'static str;
+
+    /// Whether the compositor this adapter talks to is running
+    fn is_available(&self) -> bool;
+
+    fn active_window(&self) -> Option<AdapterWindow>;
+
+    /// Whether `active_window` is cheap enough to call on a timer. False for
+    /// adapters that load a compositor script per lookup.
+    fn is_cheap(&self) -> bool {
+        true
+    }
+
+    fn activate(&self, window: &AdapterWindow) -> Result<(), String>;
+}
+
+/// Settings name of the plain X11 get/set input focus strategy
+pub const X11_FOCUS_STRATEGY: &str = "x11";
+
+/// Focus strategies in their default order, by settings name
+pub const FOCUS_STRATEGY_NAMES: &[&str] = &[
+    "hyprland",
+    "sway",
+    "kwin",
+    "wlr_foreign_toplevel",
+    X11_FOCUS_STRATEGY,
+];
+
+/// Every adapter, looked up by id
+#[cfg(target_os = "linux")]
+static ADAPTERS: &[&dyn FocusAdapter] = &[
+    &crate::hyprland_ipc::HyprlandAdapter,
+    &crate::sway_ipc::SwayAdapter,
+    &crate::kwin_script::KWinAdapter,
+    &crate::foreign_toplevel::ForeignToplevelAdapter,
+];
+
+/// Window saved through an adapter, preferred over LAST_FOCUSED_WINDOW
+#[cfg(target_os = "linux")]
+static LAST_ADAPTER_WINDOW: Mutex<Option<(&'static dyn FocusAdapter, AdapterWindow)>> =
+    Mutex::new(None);
+
+/// Records the active window through an adapter, if its compositor is running
+#[cfg(target_os = "linux")]
+fn save_adapter_window(adapter: &'static dyn FocusAdapter) -> bool {
+    if !adapter.is_available() {
+        return false;
+    }
+    let Some(window) = adapter.active_window() else {
+        return false;
+    };
+
+    eprintln!(
+        "[FocusManager] Saved {} window: {} ({})",
+        adapter.name(),
+        window.id,
+        window.app_id
+    );
+    *LAST_ADAPTER_WINDOW.lock() = Some((adapter, window));
+    true
+}
+
 // --- Linux Implementation ---
 
+/// Tries the focus strategies in the configured order (the default order
+/// when none is set) until one records the active window
 #[cfg(target_os = "linux")]
 pub fn save_focused_window() {
+    forget_focused_window();
+
+    for name in strategy_order() {
+        let saved = if name == X11_FOCUS_STRATEGY {
+            save_x11_window()
+        } else {
+            adapter_by_id(&name).is_some_and(save_adapter_window)
+        };
+        if saved {
+            return;
+        }
+    }
+    eprintln!("[FocusManager] No focus strategy could record the active window");
+}
+
+/// Configured focus strategy names, or the default order
+#[cfg(target_os = "linux")]
+fn strategy_order() -> Vec<String> {
+    let order = crate::user_settings::UserSettingsManager::new()
+        .load()
+        .focus_strategies;
+    if order.is_empty() {
+        FOCUS_STRATEGY_NAMES
+            .iter()
+            .map(|name| name.to_string())
+            .collect()
+    } else {
+        order
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn adapter_by_id(id: &str) -> Option<&'static dyn FocusAdapter> {
+    ADAPTERS.iter().find(|adapter| adapter.id() == id).copied()
+}
+
+#[cfg(target_os = "linux")]
+fn save_x11_window() -> bool {
     match get_x11_connection() {
         Ok(conn) => match conn.get_input_focus() {
             Ok(cookie) => match cookie.reply() {
@@ -32,6 +156,7 @@ pub fn save_focused_window() {
                     let window_id = reply.focus;
                     LAST_FOCUSED_WINDOW.store(window_id, Ordering::SeqCst);
                     eprintln!("[FocusManager] Saved focused window: {}", window_id);
+                    return true;
                 }
                 Err(e) => eprintln!("[FocusManager] Failed to get focus reply: {}", e),
             },
@@ -39,14 +164,35 @@ pub fn save_focused_window() {
         },
         Err(e) => eprintln!("[FocusManager] X11 Connection failed: {}", e),
     }
+    false
+}
+
+/// Drops the saved window, which may no longer exist after a resume or a
+/// window manager restart
+#[cfg(target_os = "linux")]
+pub fn forget_focused_window() {
+    LAST_FOCUSED_WINDOW.store(0, Ordering::SeqCst);
+    *LAST_ADAPTER_WINDOW.lock() = None;
 }
 
 #[cfg(target_os = "linux")]
 pub fn restore_focused_window() -> Result<(), String> {
+    let adapter_window = LAST_ADAPTER_WINDOW.lock().clone();
+    if let Some((adapter, window)) = adapter_window {
+        eprintln!(
+            "[FocusManager] Restoring focus to {} window: {}",
+            adapter.name(),
+            window.id
+        );
+        adapter.activate(&window)?;
+        thread::sleep(FOCUS_RESTORE_DELAY);
+        return Ok(());
+    }
+
     let window_id = LAST_FOCUSED_WINDOW.load(Ordering::SeqCst);
 
     if window_id == 0 {
-        return Err("No previous window saved".to_string());
+        return Err(crate::i18n::tr("error-no-previous-window"));
     }
 
     eprintln!("[FocusManager] Restoring focus to window: {}", window_id);
@@ -76,6 +222,114 @@ pub fn get_focused_window() -> Option<u32> {
     Some(reply.focus)
 }
 
+/// WM_CLASS (instance and class names) of the window saved by
+/// `save_focused_window`, or its app id when saved through an adapter.
+/// The X11 focus often sits on a child of the top-level window, so parents
+/// are searched until one has the property.
+#[cfg(target_os = "linux")]
+pub fn focused_window_class() -> Option<Vec<String>> {
+    if let Some((_, window)) = LAST_ADAPTER_WINDOW.lock().as_ref() {
+        return Some(vec![window.app_id.clone()]).filter(|_| !window.app_id.is_empty());
+    }
+
+    x11_window_class(LAST_FOCUSED_WINDOW.load(Ordering::SeqCst))
+}
+
+/// WM_CLASS or app id of the window focused right now, e.g. the app a copy
+/// came from. Goes through the same strategies as `save_focused_window`
+/// without touching the saved window.
+#[cfg(target_os = "linux")]
+pub fn active_window_class() -> Option<Vec<String>> {
+    strategy_order().into_iter().find_map(|name| {
+        if name == X11_FOCUS_STRATEGY {
+            return x11_window_class(get_focused_window()?);
+        }
+        let adapter = adapter_by_id(&name).filter(|adapter| adapter.is_available())?;
+        let window = adapter.active_window()?;
+        Some(vec![window.app_id]).filter(|names| !names[0].is_empty())
+    })
+}
+
+/// `active_window_class` for polling: None when the adapter that would
+/// answer is not cheap, rather than falling through to a later strategy that
+/// would report some other window.
+#[cfg(target_os = "linux")]
+pub fn passive_window_class() -> Option<Vec<String>> {
+    let first = strategy_order().into_iter().find(|name| {
+        name == X11_FOCUS_STRATEGY
+            || adapter_by_id(name).is_some_and(|adapter| adapter.is_available())
+    })?;
+    if adapter_by_id(&first).is_some_and(|adapter| !adapter.is_cheap()) {
+        return None;
+    }
+    active_window_class()
+}
+
+/// Middle of the window saved by `save_focused_window`, in screen
+/// coordinates, for opening on the same monitor. Only known on X11 and
+/// Hyprland.
+#[cfg(target_os = "linux")]
+pub fn focused_window_center() -> Option<(i32, i32)> {
+    let adapter = LAST_ADAPTER_WINDOW
+        .lock()
+        .as_ref()
+        .map(|(adapter, _)| adapter.id());
+    if let Some(adapter) = adapter {
+        // Still the active one: the popup is not shown yet
+        return (adapter == "hyprland")
+            .then(crate::hyprland_ipc::active_window_center)
+            .flatten();
+    }
+
+    let window = LAST_FOCUSED_WINDOW.load(Ordering::SeqCst);
+    if window == 0 {
+        return None;
+    }
+    let conn = get_x11_connection().ok()?;
+    let geometry = conn.get_geometry(window).ok()?.reply().ok()?;
+    let origin = conn
+        .translate_coordinates(window, geometry.root, 0, 0)
+        .ok()?
+        .reply()
+        .ok()?;
+    Some((
+        origin.dst_x as i32 + geometry.width as i32 / 2,
+        origin.dst_y as i32 + geometry.height as i32 / 2,
+    ))
+}
+
+#[cfg(target_os = "linux")]
+fn x11_window_class(mut window: u32) -> Option<Vec<String>> {
+    if window == 0 {
+        return None;
+    }
+
+    let conn = get_x11_connection().ok()?;
+    loop {
+        let reply = conn
+            .get_property(false, window, AtomEnum::WM_CLASS, AtomEnum::STRING, 0, 256)
+            .ok()?
+            .reply()
+            .ok()?;
+        if !reply.value.is_empty() {
+            // Two NUL-terminated strings: instance, then class
+            let names = reply
+                .value
+                .split(|&b| b == 0)
+                .filter(|name| !name.is_empty())
+                .map(|name| String::from_utf8_lossy(name).into_owned())
+                .collect();
+            return Some(names);
+        }
+
+        let tree = conn.query_tree(window).ok()?.reply().ok()?;
+        if tree.parent == 0 || tree.parent == tree.root {
+            return None;
+        }
+        window = tree.parent;
+    }
+}
+
 /// Helper to establish X11 connection
 #[cfg(target_os = "linux")]
 fn get_x11_connection() -> Result<impl Connection, String> {
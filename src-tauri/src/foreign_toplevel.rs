@@ -0,0 +1,347 @@
+//! Foreign Toplevel Tracking
+//! Keeps a live list of toplevel windows and their app_ids on Wayland
+//! compositors that expose them to clients: zwlr_foreign_toplevel_management
+//! (wlroots) reports which one is active and can activate it again, while
+//! ext-foreign-toplevel-list only lists windows. The tracker runs on its own
+//! Wayland connection, started the first time it is needed.
+
+use crate::focus_manager::{AdapterWindow, FocusAdapter};
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
+use wayland_client::globals::{registry_queue_init, GlobalListContents};
+use wayland_client::protocol::{wl_registry, wl_seat};
+use wayland_client::{event_created_child, Connection, Dispatch, Proxy, QueueHandle};
+use wayland_protocols::ext::foreign_toplevel_list::v1::client::{
+    ext_foreign_toplevel_handle_v1::{self, ExtForeignToplevelHandleV1},
+    ext_foreign_toplevel_list_v1::{self, ExtForeignToplevelListV1},
+};
+use wayland_protocols_wlr::foreign_toplevel::v1::client::{
+    zwlr_foreign_toplevel_handle_v1::{self, ZwlrForeignToplevelHandleV1},
+    zwlr_foreign_toplevel_manager_v1::{self, ZwlrForeignToplevelManagerV1},
+};
+
+/// `zwlr_foreign_toplevel_handle_v1.state` value for the focused toplevel
+const STATE_ACTIVATED: u32 = 2;
+
+#[derive(Debug, Clone, Default)]
+struct ToplevelInfo {
+    app_id: String,
+    activated: bool,
+}
+
+/// Toplevels keyed by the protocol id of their handle
+#[derive(Default)]
+struct Toplevels {
+    wlr: HashMap<u32, (ZwlrForeignToplevelHandleV1, ToplevelInfo)>,
+    /// ext-foreign-toplevel-list entries with their stable identifier
+    ext: HashMap<u32, (String, ToplevelInfo)>,
+}
+
+struct TrackerState {
+    toplevels: Arc<Mutex<Toplevels>>,
+}
+
+struct Tracker {
+    connection: Connection,
+    seat: Option<wl_seat::WlSeat>,
+    has_wlr: bool,
+    toplevels: Arc<Mutex<Toplevels>>,
+}
+
+/// Whether the state array of a wlr toplevel contains "activated"
+fn is_activated(state: &[u8]) -> bool {
+    state.chunks_exact(4).any(|value| {
+        u32::from_ne_bytes([value[0], value[1], value[2], value[3]]) == STATE_ACTIVATED
+    })
+}
+
+/// Connects and binds whichever toplevel protocols the compositor offers.
+/// The initial roundtrips fill in the current windows before returning.
+fn start_tracker() -> Result<Tracker, String> {
+    if std::env::var_os("WAYLAND_DISPLAY").is_none() {
+        return Err("Not a Wayland session".to_string());
+    }
+    let connection =
+        Connection::connect_to_env().map_err(|e| format!("Wayland connect failed: {}", e))?;
+    let (globals, mut queue) = registry_queue_init::<TrackerState>(&connection)
+        .map_err(|e| format!("Wayland registry failed: {}", e))?;
+    let qh = queue.handle();
+
+    let seat = globals.bind::<wl_seat::WlSeat, _, _>(&qh, 1..=8, ()).ok();
+    let has_wlr = globals
+        .bind::<ZwlrForeignToplevelManagerV1, _, _>(&qh, 1..=3, ())
+        .is_ok();
+    let has_ext = globals
+        .bind::<ExtForeignToplevelListV1, _, _>(&qh, 1..=1, ())
+        .is_ok();
+    if !has_wlr && !has_ext {
+        return Err("No foreign toplevel protocol advertised".to_string());
+    }
+
+    let toplevels = Arc::new(Mutex::new(Toplevels::default()));
+    let mut state = TrackerState {
+        toplevels: toplevels.clone(),
+    };
+    // First roundtrip announces the toplevels, the second their properties
+    for _ in 0..2 {
+        queue
+            .roundtrip(&mut state)
+            .map_err(|e| format!("Wayland roundtrip failed: {}", e))?;
+    }
+
+    std::thread::spawn(move || loop {
+        if let Err(e) = queue.blocking_dispatch(&mut state) {
+            eprintln!("[ForeignToplevel] Dispatch failed, tracking stopped: {}", e);
+            return;
+        }
+    });
+
+    eprintln!(
+        "[ForeignToplevel] Tracking toplevels via {}",
+        if has_wlr {
+            "zwlr_foreign_toplevel_manager_v1"
+        } else {
+            "ext_foreign_toplevel_list_v1"
+        }
+    );
+    Ok(Tracker {
+        connection,
+        seat,
+        has_wlr,
+        toplevels,
+    })
+}
+
+fn tracker() -> Option<&'static Tracker> {
+    static TRACKER: OnceLock<Option<Tracker>> = OnceLock::new();
+    TRACKER.get_or_init(|| start_tracker().ok()).as_ref()
+}
+
+/// All known toplevels. Ids are `wlr:<handle>` or `ext:<identifier>`.
+pub fn toplevels() -> Vec<AdapterWindow> {
+    let Some(tracker) = tracker() else {
+        return Vec::new();
+    };
+    let toplevels = tracker.toplevels.lock();
+    if tracker.has_wlr {
+        toplevels
+            .wlr
+            .iter()
+            .map(|(id, (_, info))| AdapterWindow {
+                id: format!("wlr:{}", id),
+                app_id: info.app_id.clone(),
+            })
+            .collect()
+    } else {
+        toplevels
+            .ext
+            .values()
+            .map(|(identifier, info)| AdapterWindow {
+                id: format!("ext:{}", identifier),
+                app_id: info.app_id.clone(),
+            })
+            .collect()
+    }
+}
+
+/// Active toplevel and activation through zwlr_foreign_toplevel_management
+pub struct ForeignToplevelAdapter;
+
+impl FocusAdapter for ForeignToplevelAdapter {
+    fn id(&self) -> &'static str {
+        "wlr_foreign_toplevel"
+    }
+
+    fn name(&self) -> &'static str {
+        "wlr-foreign-toplevel"
+    }
+
+    fn is_available(&self) -> bool {
+        tracker().is_some_and(|tracker| tracker.has_wlr)
+    }
+
+    fn active_window(&self) -> Option<AdapterWindow> {
+        let tracker = tracker()?;
+        let toplevels = tracker.toplevels.lock();
+        toplevels
+            .wlr
+            .iter()
+            .find(|(_, (_, info))| info.activated)
+            .map(|(id, (_, info))| AdapterWindow {
+                id: format!("wlr:{}", id),
+                app_id: info.app_id.clone(),
+            })
+    }
+
+    fn activate(&self, window: &AdapterWindow) -> Result<(), String> {
+        let tracker = tracker().ok_or("Toplevel tracking unavailable")?;
+        let seat = tracker.seat.as_ref().ok_or("wl_seat unavailable")?;
+        let id = window
+            .id
+            .strip_prefix("wlr:")
+            .and_then(|id| id.parse::<u32>().ok())
+            .ok_or_else(|| format!("Not a wlr toplevel: {}", window.id))?;
+
+        let toplevels = tracker.toplevels.lock();
+        let (handle, _) = toplevels
+            .wlr
+            .get(&id)
+            .ok_or("Window was closed before it could be focused")?;
+        handle.activate(seat);
+        tracker
+            .connection
+            .flush()
+            .map_err(|e| format!("Wayland flush failed: {}", e))
+    }
+}
+
+impl Dispatch<wl_registry::WlRegistry, GlobalListContents> for TrackerState {
+    fn event(
+        _: &mut Self,
+        _: &wl_registry::WlRegistry,
+        _: wl_registry::Event,
+        _: &GlobalListContents,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<wl_seat::WlSeat, ()> for TrackerState {
+    fn event(
+        _: &mut Self,
+        _: &wl_seat::WlSeat,
+        _: wl_seat::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<ZwlrForeignToplevelManagerV1, ()> for TrackerState {
+    fn event(
+        state: &mut Self,
+        _: &ZwlrForeignToplevelManagerV1,
+        event: zwlr_foreign_toplevel_manager_v1::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        if let zwlr_foreign_toplevel_manager_v1::Event::Toplevel { toplevel } = event {
+            state.toplevels.lock().wlr.insert(
+                toplevel.id().protocol_id(),
+                (toplevel, ToplevelInfo::default()),
+            );
+        }
+    }
+
+    event_created_child!(TrackerState, ZwlrForeignToplevelManagerV1, [
+        zwlr_foreign_toplevel_manager_v1::EVT_TOPLEVEL_OPCODE => (ZwlrForeignToplevelHandleV1, ()),
+    ]);
+}
+
+impl Dispatch<ZwlrForeignToplevelHandleV1, ()> for TrackerState {
+    fn event(
+        state: &mut Self,
+        handle: &ZwlrForeignToplevelHandleV1,
+        event: zwlr_foreign_toplevel_handle_v1::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        let mut toplevels = state.toplevels.lock();
+        let id = handle.id().protocol_id();
+        match event {
+            zwlr_foreign_toplevel_handle_v1::Event::AppId { app_id } => {
+                if let Some((_, info)) = toplevels.wlr.get_mut(&id) {
+                    info.app_id = app_id;
+                }
+            }
+            zwlr_foreign_toplevel_handle_v1::Event::State { state } => {
+                if let Some((_, info)) = toplevels.wlr.get_mut(&id) {
+                    info.activated = is_activated(&state);
+                }
+            }
+            zwlr_foreign_toplevel_handle_v1::Event::Closed => {
+                if let Some((handle, _)) = toplevels.wlr.remove(&id) {
+                    handle.destroy();
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Dispatch<ExtForeignToplevelListV1, ()> for TrackerState {
+    fn event(
+        state: &mut Self,
+        _: &ExtForeignToplevelListV1,
+        event: ext_foreign_toplevel_list_v1::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        if let ext_foreign_toplevel_list_v1::Event::Toplevel { toplevel } = event {
+            state
+                .toplevels
+                .lock()
+                .ext
+                .insert(toplevel.id().protocol_id(), Default::default());
+        }
+    }
+
+    event_created_child!(TrackerState, ExtForeignToplevelListV1, [
+        ext_foreign_toplevel_list_v1::EVT_TOPLEVEL_OPCODE => (ExtForeignToplevelHandleV1, ()),
+    ]);
+}
+
+impl Dispatch<ExtForeignToplevelHandleV1, ()> for TrackerState {
+    fn event(
+        state: &mut Self,
+        handle: &ExtForeignToplevelHandleV1,
+        event: ext_foreign_toplevel_handle_v1::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        let mut toplevels = state.toplevels.lock();
+        let id = handle.id().protocol_id();
+        match event {
+            ext_foreign_toplevel_handle_v1::Event::Identifier { identifier } => {
+                if let Some(entry) = toplevels.ext.get_mut(&id) {
+                    entry.0 = identifier;
+                }
+            }
+            ext_foreign_toplevel_handle_v1::Event::AppId { app_id } => {
+                if let Some((_, info)) = toplevels.ext.get_mut(&id) {
+                    info.app_id = app_id;
+                }
+            }
+            ext_foreign_toplevel_handle_v1::Event::Closed => {
+                toplevels.ext.remove(&id);
+                handle.destroy();
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_activated() {
+        let states = |values: &[u32]| -> Vec<u8> {
+            values
+                .iter()
+                .flat_map(|value| value.to_ne_bytes())
+                .collect()
+        };
+        assert!(is_activated(&states(&[0, STATE_ACTIVATED])));
+        assert!(!is_activated(&states(&[0, 1])));
+        assert!(!is_activated(&[]));
+    }
+}
@@ -4,9 +4,22 @@
 //! IMPORTANT: This module handles specific OS-level clipboard commands (wl-copy/xclip)
 //! to ensure GIFs are pasted as files (text/uri-list) rather than raw bytes or text.
 //! This is required for rich media pasting in apps like Discord/Chrome on Linux.
+//!
+//! Searching and downloading go through the configured `GifProvider`.
+//!
+//! Downloaded GIFs are cached, and the least recently pasted ones are
+//! deleted once the cache is over the size set in the settings. Providers
+//! serve animated WebP and APNG too, so files are named by what they hold,
+//! and apps known to take WebP are pasted the WebP variant when there is one.
+//!
+//! `GifLibrary` keeps the user's favorite GIFs, with their file and preview
+//! downloaded next to the other data so they paste offline, and the GIFs
+//! pasted last.
 
+use crate::gif_provider::{Gif, GifProvider};
 use crate::session;
 use arboard::Clipboard;
+use serde::{Deserialize, Serialize};
 use std::collections::hash_map::DefaultHasher;
 use std::fs;
 use std::hash::{Hash, Hasher};
@@ -19,9 +32,30 @@ use std::time::Duration;
 
 const APP_CACHE_DIR: &str = "win11-clipboard-history/gifs";
 const MIME_URI_LIST: &str = "text/uri-list";
-const DOWNLOAD_TIMEOUT: u64 = 10;
 const WL_COPY_SETTLE_TIME: u64 = 150;
 
+/// Apps pasted animated WebP, by WM_CLASS or app id (case-insensitive);
+/// the rest get GIF, which every app takes
+const WEBP_APPS: &[&str] = &[
+    "firefox",
+    "org.mozilla.firefox",
+    "chromium",
+    "google-chrome",
+    "brave-browser",
+    "vivaldi-stable",
+    "microsoft-edge",
+    "discord",
+    "org.telegram.desktop",
+    "TelegramDesktop",
+    "Element",
+];
+
+/// Favorites and recents, inside the data directory
+const LIBRARY_FILE: &str = "gif_library.json";
+/// Files of the favorites, inside the data directory
+const FAVORITES_DIR: &str = "gif_favorites";
+const MAX_RECENT_GIFS: usize = 24;
+
 // --- Cache Management ---
 
 struct GifCache;
@@ -41,45 +75,160 @@ impl GifCache {
         Ok(cache_dir)
     }
 
-    /// Generate a file path based on the URL hash.
-    fn get_path_for_url(url: &str) -> Result<PathBuf, String> {
-        let mut hasher = DefaultHasher::new();
-        url.hash(&mut hasher);
-        let hash = hasher.finish();
+    /// Generate a file path based on the URL hash and the file's format.
+    fn get_path_for_url(url: &str, format: AnimatedFormat) -> Result<PathBuf, String> {
+        Ok(Self::get_dir()?.join(file_name_for_url(url, format)))
+    }
+}
+
+/// The animated image formats providers serve, told apart by their content
+/// as a URL ending in ".gif" may well serve WebP
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnimatedFormat {
+    Gif,
+    WebP,
+    /// Saved as ".png", which apps taking APNG read it as
+    Apng,
+}
 
-        Ok(Self::get_dir()?.join(format!("{}.gif", hash)))
+impl AnimatedFormat {
+    /// From the file signature; none for anything else
+    pub fn sniff(bytes: &[u8]) -> Option<Self> {
+        if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+            Some(Self::Gif)
+        } else if bytes.len() >= 12 && bytes.starts_with(b"RIFF") && &bytes[8..12] == b"WEBP" {
+            Some(Self::WebP)
+        } else if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+            Some(Self::Apng)
+        } else {
+            None
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Gif => "gif",
+            Self::WebP => "webp",
+            Self::Apng => "png",
+        }
     }
 }
 
-// --- Downloader ---
+/// The format to paste into the window with these names
+pub fn preferred_format(target: &[String]) -> AnimatedFormat {
+    let takes_webp = target
+        .iter()
+        .any(|name| WEBP_APPS.iter().any(|app| app.eq_ignore_ascii_case(name)));
+    if takes_webp {
+        AnimatedFormat::WebP
+    } else {
+        AnimatedFormat::Gif
+    }
+}
 
-struct Downloader;
+/// The URL of `gif` in `format`, or its GIF when it has no such variant
+fn url_in_format(gif: &Gif, format: AnimatedFormat) -> &str {
+    match (format, &gif.webp_url) {
+        (AnimatedFormat::WebP, Some(url)) => url,
+        _ => &gif.full_url,
+    }
+}
 
-impl Downloader {
-    /// Downloads a URL to a local file.
-    pub fn download(url: &str, destination: &Path) -> Result<(), String> {
-        eprintln!("[GifManager] Downloading: {}", url);
+/// The URL of `gif` in the format best for the window saved by the focus
+/// manager
+pub fn url_for_target(gif: &Gif) -> &str {
+    #[cfg(target_os = "linux")]
+    if let Some(target) = crate::focus_manager::focused_window_class() {
+        return url_in_format(gif, preferred_format(&target));
+    }
 
-        let client = reqwest::blocking::Client::builder()
-            .timeout(Duration::from_secs(DOWNLOAD_TIMEOUT))
-            .build()
-            .map_err(|e| format!("Client build error: {}", e))?;
+    &gif.full_url
+}
 
-        let response = client
-            .get(url)
-            .send()
-            .map_err(|e| format!("Network request failed: {}", e))?;
+/// What a cache cleanup deleted
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct CacheCleanup {
+    pub files: usize,
+    pub bytes: u64,
+}
 
-        if !response.status().is_success() {
-            return Err(format!("HTTP Error: {}", response.status()));
+/// Deletes the files of `dir` least recently written first, which is when
+/// they were last pasted as each paste downloads again, until they take at
+/// most `limit` bytes
+fn evict_lru(dir: &Path, limit: u64) -> CacheCleanup {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return CacheCleanup::default();
+    };
+    let mut files: Vec<(std::time::SystemTime, u64, PathBuf)> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok().filter(|m| m.is_file())?;
+            Some((metadata.modified().ok()?, metadata.len(), entry.path()))
+        })
+        .collect();
+    let mut total: u64 = files.iter().map(|(_, len, _)| len).sum();
+    files.sort_by_key(|(modified, _, _)| *modified);
+
+    let mut cleanup = CacheCleanup::default();
+    for (_, len, path) in files {
+        if total <= limit {
+            break;
         }
+        if fs::remove_file(&path).is_ok() {
+            total -= len;
+            cleanup.files += 1;
+            cleanup.bytes += len;
+        }
+    }
+    cleanup
+}
 
-        let bytes = response
-            .bytes()
-            .map_err(|e| format!("Failed to read bytes: {}", e))?;
+/// Trims the GIF cache to `limit` bytes; 0 keeps everything
+pub fn enforce_cache_limit(limit: u64) -> Result<CacheCleanup, String> {
+    if limit == 0 {
+        return Ok(CacheCleanup::default());
+    }
+    let cleanup = evict_lru(&GifCache::get_dir()?, limit);
+    if cleanup.files > 0 {
+        eprintln!(
+            "[GifManager] Evicted {} cached GIFs ({} bytes)",
+            cleanup.files, cleanup.bytes
+        );
+    }
+    Ok(cleanup)
+}
+
+/// Empties the GIF cache. Favorites are kept elsewhere and stay.
+pub fn clear_cache() -> Result<CacheCleanup, String> {
+    Ok(evict_lru(&GifCache::get_dir()?, 0))
+}
+
+fn file_name_for_url(url: &str, format: AnimatedFormat) -> String {
+    let mut hasher = DefaultHasher::new();
+    url.hash(&mut hasher);
+    format!("{}.{}", hasher.finish(), format.extension())
+}
+
+// --- Downloader ---
+
+struct Downloader;
+
+impl Downloader {
+    /// Downloads a URL to the local file `destination` gives for its
+    /// format (GIF when it is none of the known ones), returning that path.
+    pub fn download(
+        provider: &dyn GifProvider,
+        url: &str,
+        destination: impl FnOnce(AnimatedFormat) -> Result<PathBuf, String>,
+    ) -> Result<PathBuf, String> {
+        eprintln!("[GifManager] Downloading: {}", url);
+
+        let bytes = provider.download(url)?;
+        let destination =
+            destination(AnimatedFormat::sniff(&bytes).unwrap_or(AnimatedFormat::Gif))?;
 
         let mut file =
-            fs::File::create(destination).map_err(|e| format!("File creation failed: {}", e))?;
+            fs::File::create(&destination).map_err(|e| format!("File creation failed: {}", e))?;
 
         file.write_all(&bytes)
             .map_err(|e| format!("File write failed: {}", e))?;
@@ -89,7 +238,7 @@ impl Downloader {
             bytes.len(),
             destination
         );
-        Ok(())
+        Ok(destination)
     }
 }
 
@@ -208,30 +357,25 @@ impl ClipboardHandler {
 // --- Public API ---
 
 /// Downloads a GIF from the URL and returns the local file path.
-pub fn download_gif_to_file(url: &str) -> Result<PathBuf, String> {
-    let target_path = GifCache::get_path_for_url(url)?;
-
+pub fn download_gif_to_file(provider: &dyn GifProvider, url: &str) -> Result<PathBuf, String> {
     // Check if we already have it to avoid redownload (optional optimization,
     // but the original code overwrote every time. I'll maintain overwrite
     // to ensure validity, but using `Downloader` keeps it clean).
-    Downloader::download(url, &target_path)?;
-
-    Ok(target_path)
+    Downloader::download(provider, url, |format| {
+        GifCache::get_path_for_url(url, format)
+    })
 }
 
 /// Downloads GIF and sets clipboard.
 /// Returns Ok(Some(uri)) if successful (for history marking),
 /// Ok(Some(url)) if fallback used,
 /// Err if everything failed.
-pub fn paste_gif_to_clipboard_with_uri(url: &str) -> Result<Option<String>, String> {
-    let is_wayland = session::is_wayland();
-    eprintln!(
-        "[GifManager] Mode: {}",
-        if is_wayland { "Wayland" } else { "X11" }
-    );
-
+pub fn paste_gif_to_clipboard_with_uri(
+    provider: &dyn GifProvider,
+    url: &str,
+) -> Result<Option<String>, String> {
     // 1. Attempt Download
-    let gif_path = match download_gif_to_file(url) {
+    let gif_path = match download_gif_to_file(provider, url) {
         Ok(path) => path,
         Err(e) => {
             eprintln!("[GifManager] Download failed ({}), using URL fallback.", e);
@@ -241,13 +385,25 @@ pub fn paste_gif_to_clipboard_with_uri(url: &str) -> Result<Option<String>, Stri
     };
 
     // 2. Attempt Copy
+    copy_gif_file(&gif_path, url)
+}
+
+/// Sets the clipboard to the GIF file at `gif_path` downloaded from `url`,
+/// or to `url` when that fails; returns what it was set to
+pub fn copy_gif_file(gif_path: &Path, url: &str) -> Result<Option<String>, String> {
+    let is_wayland = session::is_wayland();
+    eprintln!(
+        "[GifManager] Mode: {}",
+        if is_wayland { "Wayland" } else { "X11" }
+    );
+
     let copy_result = if is_wayland {
-        ClipboardHandler::copy_wayland(&gif_path).or_else(|e| {
+        ClipboardHandler::copy_wayland(gif_path).or_else(|e| {
             eprintln!("[GifManager] Wayland copy failed ({}), trying X11...", e);
-            ClipboardHandler::copy_x11(&gif_path)
+            ClipboardHandler::copy_x11(gif_path)
         })
     } else {
-        ClipboardHandler::copy_x11(&gif_path)
+        ClipboardHandler::copy_x11(gif_path)
     };
 
     // 3. Handle Result
@@ -265,8 +421,8 @@ pub fn paste_gif_to_clipboard_with_uri(url: &str) -> Result<Option<String>, Stri
 }
 
 /// Convenience wrapper for cases where the URI return isn't needed.
-pub fn paste_gif_to_clipboard(url: &str) -> Result<(), String> {
-    paste_gif_to_clipboard_with_uri(url).map(|_| ())
+pub fn paste_gif_to_clipboard(provider: &dyn GifProvider, url: &str) -> Result<(), String> {
+    paste_gif_to_clipboard_with_uri(provider, url).map(|_| ())
 }
 
 /// Helper for external use if needed (legacy support)
@@ -274,6 +430,158 @@ pub fn copy_url_to_clipboard(url: &str) -> Result<(), String> {
     ClipboardHandler::copy_url_fallback(url)
 }
 
+// --- Favorites & Recents ---
+
+/// A favorite GIF and where its files were saved
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FavoriteGif {
+    #[serde(flatten)]
+    pub gif: Gif,
+    /// The GIF that is pasted, downloaded from `gif.full_url`
+    pub file: PathBuf,
+    /// Shown in the grid instead of `gif.preview_url`
+    pub preview_file: PathBuf,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct LibraryFile {
+    #[serde(default)]
+    favorites: Vec<FavoriteGif>,
+    #[serde(default)]
+    recent: Vec<Gif>,
+}
+
+/// Downloads the files of `gif` into `dir`, for `GifLibrary::add_favorite`.
+/// Kept apart from the library so its lock is not held over the network.
+pub fn download_favorite(
+    provider: &dyn GifProvider,
+    gif: Gif,
+    dir: &Path,
+) -> Result<FavoriteGif, String> {
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let file = Downloader::download(provider, &gif.full_url, |format| {
+        Ok(dir.join(file_name_for_url(&gif.full_url, format)))
+    })?;
+    let preview_file = Downloader::download(provider, &gif.preview_url, |format| {
+        Ok(dir.join(format!(
+            "preview-{}",
+            file_name_for_url(&gif.preview_url, format)
+        )))
+    })?;
+    Ok(FavoriteGif {
+        gif,
+        file,
+        preview_file,
+    })
+}
+
+/// The user's favorite GIFs and the ones pasted last
+pub struct GifLibrary {
+    /// Most recently added first
+    favorites: Vec<FavoriteGif>,
+    /// Most recently pasted first
+    recent: Vec<Gif>,
+    data_dir: PathBuf,
+}
+
+impl GifLibrary {
+    pub fn new(data_dir: PathBuf) -> Self {
+        let mut library = Self {
+            favorites: Vec::new(),
+            recent: Vec::new(),
+            data_dir,
+        };
+        match library.load() {
+            Ok(file) => {
+                library.favorites = file.favorites;
+                library.recent = file.recent;
+            }
+            Err(e) => eprintln!("[GifManager] Failed to load GIF library: {}", e),
+        }
+        library
+    }
+
+    /// Where `download_favorite` puts the files
+    pub fn favorites_dir(&self) -> PathBuf {
+        self.data_dir.join(FAVORITES_DIR)
+    }
+
+    pub fn favorites(&self) -> Vec<FavoriteGif> {
+        self.favorites.clone()
+    }
+
+    pub fn recent(&self) -> Vec<Gif> {
+        self.recent.clone()
+    }
+
+    /// The favorite pasting `url`, when its file is still there
+    pub fn find_favorite(&self, url: &str) -> Option<&FavoriteGif> {
+        self.favorites
+            .iter()
+            .find(|favorite| favorite.gif.full_url == url && favorite.file.exists())
+    }
+
+    /// Adds a downloaded favorite, replacing one with the same id
+    pub fn add_favorite(&mut self, favorite: FavoriteGif) {
+        self.favorites
+            .retain(|saved| saved.gif.id != favorite.gif.id);
+        self.favorites.insert(0, favorite);
+        self.persist();
+    }
+
+    /// Deletes the favorite and its files; false when there was none
+    pub fn remove_favorite(&mut self, id: &str) -> bool {
+        let Some(index) = self
+            .favorites
+            .iter()
+            .position(|favorite| favorite.gif.id == id)
+        else {
+            return false;
+        };
+        let favorite = self.favorites.remove(index);
+        let _ = fs::remove_file(&favorite.file);
+        let _ = fs::remove_file(&favorite.preview_file);
+        self.persist();
+        true
+    }
+
+    /// Moves `gif` to the front of the recents
+    pub fn record_recent(&mut self, gif: Gif) {
+        self.recent.retain(|recent| recent.id != gif.id);
+        self.recent.insert(0, gif);
+        self.recent.truncate(MAX_RECENT_GIFS);
+        self.persist();
+    }
+
+    fn path(&self) -> PathBuf {
+        self.data_dir.join(LIBRARY_FILE)
+    }
+
+    fn load(&self) -> Result<LibraryFile, String> {
+        let path = self.path();
+        if !path.exists() {
+            return Ok(LibraryFile::default());
+        }
+        let content = fs::read_to_string(&path).map_err(|e| format!("Read error: {}", e))?;
+        serde_json::from_str(&content).map_err(|e| format!("Parse error: {}", e))
+    }
+
+    fn persist(&self) {
+        let file = LibraryFile {
+            favorites: self.favorites.clone(),
+            recent: self.recent.clone(),
+        };
+        let result = fs::create_dir_all(&self.data_dir)
+            .map_err(|e| e.to_string())
+            .and_then(|_| serde_json::to_string_pretty(&file).map_err(|e| e.to_string()))
+            .and_then(|content| fs::write(self.path(), content).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            eprintln!("[GifManager] Failed to save GIF library: {}", e);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -285,10 +593,131 @@ mod tests {
         assert!(dir.unwrap().ends_with("win11-clipboard-history/gifs"));
     }
 
+    struct OfflineProvider;
+
+    impl GifProvider for OfflineProvider {
+        fn search_url(&self, _query: &str, _limit: usize) -> String {
+            String::new()
+        }
+
+        fn trending_url(&self, _limit: usize) -> String {
+            String::new()
+        }
+
+        fn parse(&self, _body: &str) -> Result<Vec<Gif>, String> {
+            Ok(Vec::new())
+        }
+
+        fn download(&self, url: &str) -> Result<Vec<u8>, String> {
+            Ok(url.as_bytes().to_vec())
+        }
+    }
+
+    fn gif(id: &str) -> Gif {
+        Gif {
+            id: id.to_string(),
+            title: "Wave".to_string(),
+            preview_url: format!("https://example.com/{}-small.gif", id),
+            full_url: format!("https://example.com/{}.gif", id),
+            webp_url: None,
+            width: 100,
+            height: 80,
+        }
+    }
+
+    #[test]
+    fn test_favorites_and_recents() {
+        let dir = std::env::temp_dir().join("gif_library_test");
+        let _ = fs::remove_dir_all(&dir);
+
+        let mut library = GifLibrary::new(dir.clone());
+        let favorite =
+            download_favorite(&OfflineProvider, gif("1"), &library.favorites_dir()).unwrap();
+        assert_eq!(
+            fs::read(&favorite.file).unwrap(),
+            b"https://example.com/1.gif"
+        );
+        library.add_favorite(favorite.clone());
+        for id in ["1", "2", "1"] {
+            library.record_recent(gif(id));
+        }
+
+        let loaded = GifLibrary::new(dir.clone());
+        assert_eq!(loaded.favorites(), vec![favorite.clone()]);
+        assert!(loaded.find_favorite("https://example.com/1.gif").is_some());
+        let recent: Vec<String> = loaded.recent().into_iter().map(|gif| gif.id).collect();
+        assert_eq!(recent, ["1", "2"]);
+
+        assert!(library.remove_favorite("1"));
+        assert!(!favorite.file.exists());
+        assert!(!library.remove_favorite("1"));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_evict_least_recently_used() {
+        let dir = std::env::temp_dir().join("gif_cache_lru_test");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        let now = std::time::SystemTime::now();
+        for (name, age) in [("old.gif", 30), ("mid.gif", 20), ("new.gif", 10)] {
+            let path = dir.join(name);
+            fs::write(&path, [0u8; 100]).unwrap();
+            fs::File::options()
+                .write(true)
+                .open(&path)
+                .unwrap()
+                .set_modified(now - Duration::from_secs(age))
+                .unwrap();
+        }
+
+        assert_eq!(evict_lru(&dir, 300), CacheCleanup::default());
+        assert_eq!(
+            evict_lru(&dir, 150),
+            CacheCleanup {
+                files: 2,
+                bytes: 200
+            }
+        );
+        assert!(dir.join("new.gif").exists());
+        assert_eq!(evict_lru(&dir, 0).files, 1);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
     #[test]
     fn test_path_generation() {
-        let path = GifCache::get_path_for_url("http://example.com/cat.gif");
+        let path = GifCache::get_path_for_url("http://example.com/cat.gif", AnimatedFormat::Gif);
         assert!(path.is_ok());
         assert!(path.unwrap().extension().unwrap() == "gif");
+        let path = GifCache::get_path_for_url("http://example.com/cat.gif", AnimatedFormat::WebP);
+        assert!(path.unwrap().extension().unwrap() == "webp");
+    }
+
+    #[test]
+    fn test_formats_by_content_and_target() {
+        assert_eq!(
+            AnimatedFormat::sniff(b"GIF89a..."),
+            Some(AnimatedFormat::Gif)
+        );
+        assert_eq!(
+            AnimatedFormat::sniff(b"RIFF\x10\0\0\0WEBPVP8X"),
+            Some(AnimatedFormat::WebP)
+        );
+        assert_eq!(
+            AnimatedFormat::sniff(b"\x89PNG\r\n\x1a\n\0\0\0\racTL"),
+            Some(AnimatedFormat::Apng)
+        );
+        assert_eq!(AnimatedFormat::sniff(b"<html>"), None);
+
+        let mut wave = gif("1");
+        assert_eq!(url_in_format(&wave, AnimatedFormat::WebP), wave.full_url);
+        wave.webp_url = Some("https://example.com/1.webp".to_string());
+        let target = |name: &str| preferred_format(&[name.to_string()]);
+        assert_eq!(
+            url_in_format(&wave, target("Firefox")),
+            "https://example.com/1.webp"
+        );
+        assert_eq!(url_in_format(&wave, target("libreoffice")), wave.full_url);
     }
 }
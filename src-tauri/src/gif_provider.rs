@@ -0,0 +1,420 @@
+//! GIF Providers
+//! The GIF panel searches the service picked in the settings: Tenor, GIPHY
+//! or a self-hosted server that speaks Tenor's v1 API (a caching proxy, or
+//! an instance of one of the open GIF search servers). Each is a
+//! `GifProvider`, which builds its requests, reads its responses into the
+//! app's `Gif`, and downloads the files that are pasted.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::time::Duration;
+
+/// Requests taking longer than this fail
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+const TENOR_API_BASE: &str = "https://g.tenor.com/v1";
+/// Tenor's public demo key, used while the user has not set one
+const TENOR_DEMO_KEY: &str = "LIVDSRZULELA";
+const GIPHY_API_BASE: &str = "https://api.giphy.com/v1/gifs";
+
+const DEFAULT_CACHE_LIMIT_MB: u32 = 200;
+/// Larger cache limits are cut to this
+pub const MAX_CACHE_LIMIT_MB: u32 = 10 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GifProviderKind {
+    Tenor,
+    Giphy,
+    SelfHosted,
+}
+
+impl GifProviderKind {
+    pub const ALL: [GifProviderKind; 3] = [Self::Tenor, Self::Giphy, Self::SelfHosted];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Tenor => "tenor",
+            Self::Giphy => "giphy",
+            Self::SelfHosted => "self_hosted",
+        }
+    }
+
+    pub fn from_setting(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|provider| provider.as_str() == value)
+    }
+}
+
+/// The GIF section of the user settings
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct GifSettings {
+    /// "tenor", "giphy" or "self_hosted"
+    #[serde(default = "default_provider")]
+    pub provider: String,
+    /// Tenor's demo key is used when blank
+    #[serde(default)]
+    pub tenor_api_key: String,
+    #[serde(default)]
+    pub giphy_api_key: String,
+    /// Base URL of the self-hosted server, the one its `/search` is under
+    #[serde(default)]
+    pub self_hosted_url: String,
+    /// Only needed by servers that ask for one
+    #[serde(default)]
+    pub self_hosted_api_key: String,
+    /// Size of the cache of downloaded GIFs in megabytes, past which the
+    /// least recently pasted go; 0 for no limit
+    #[serde(default = "default_cache_limit_mb")]
+    pub cache_limit_mb: u32,
+}
+
+fn default_provider() -> String {
+    GifProviderKind::Tenor.as_str().to_string()
+}
+
+fn default_cache_limit_mb() -> u32 {
+    DEFAULT_CACHE_LIMIT_MB
+}
+
+impl Default for GifSettings {
+    fn default() -> Self {
+        Self {
+            provider: default_provider(),
+            tenor_api_key: String::new(),
+            giphy_api_key: String::new(),
+            self_hosted_url: String::new(),
+            self_hosted_api_key: String::new(),
+            cache_limit_mb: default_cache_limit_mb(),
+        }
+    }
+}
+
+impl GifSettings {
+    pub fn provider(&self) -> GifProviderKind {
+        GifProviderKind::from_setting(&self.provider).unwrap_or(GifProviderKind::Tenor)
+    }
+
+    /// Falls back to Tenor for unknown providers, and trims keys and the
+    /// server URL
+    pub fn validate(&mut self) {
+        if GifProviderKind::from_setting(&self.provider).is_none() {
+            self.provider = default_provider();
+        }
+        self.tenor_api_key = self.tenor_api_key.trim().to_string();
+        self.giphy_api_key = self.giphy_api_key.trim().to_string();
+        self.self_hosted_url = self
+            .self_hosted_url
+            .trim()
+            .trim_end_matches('/')
+            .to_string();
+        self.self_hosted_api_key = self.self_hosted_api_key.trim().to_string();
+        self.cache_limit_mb = self.cache_limit_mb.min(MAX_CACHE_LIMIT_MB);
+    }
+
+    pub fn cache_limit_bytes(&self) -> u64 {
+        u64::from(self.cache_limit_mb) * 1024 * 1024
+    }
+
+    /// Whether the provider can be searched: GIPHY has no public key and a
+    /// self-hosted server needs its URL
+    pub fn is_configured(&self) -> bool {
+        match self.provider() {
+            GifProviderKind::Tenor => true,
+            GifProviderKind::Giphy => !self.giphy_api_key.is_empty(),
+            GifProviderKind::SelfHosted => !self.self_hosted_url.is_empty(),
+        }
+    }
+}
+
+/// A GIF as the panel shows it
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Gif {
+    pub id: String,
+    pub title: String,
+    /// Small version for the grid
+    pub preview_url: String,
+    /// What is pasted
+    pub full_url: String,
+    /// The same as animated WebP, pasted instead into apps that take it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub webp_url: Option<String>,
+    /// Of the preview
+    pub width: u32,
+    pub height: u32,
+}
+
+/// A GIF search service
+pub trait GifProvider: Send + Sync {
+    /// URL of a search for `query`
+    fn search_url(&self, query: &str, limit: usize) -> String;
+
+    /// URL of the trending GIFs
+    fn trending_url(&self, limit: usize) -> String;
+
+    /// The GIFs of a search or trending response, skipping malformed ones
+    fn parse(&self, body: &str) -> Result<Vec<Gif>, String>;
+
+    fn search(&self, query: &str, limit: usize) -> Result<Vec<Gif>, String> {
+        let url = if query.trim().is_empty() {
+            self.trending_url(limit)
+        } else {
+            self.search_url(query.trim(), limit)
+        };
+        let body = get(&url)?;
+        self.parse(&String::from_utf8_lossy(&body))
+    }
+
+    fn trending(&self, limit: usize) -> Result<Vec<Gif>, String> {
+        self.search("", limit)
+    }
+
+    /// The bytes of the GIF file at `url`
+    fn download(&self, url: &str) -> Result<Vec<u8>, String> {
+        get(url)
+    }
+}
+
+fn get(url: &str) -> Result<Vec<u8>, String> {
+    let client = reqwest::blocking::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Client build error: {}", e))?;
+    let response = client
+        .get(url)
+        .send()
+        .map_err(|e| format!("Network request failed: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("HTTP Error: {}", response.status()));
+    }
+    response
+        .bytes()
+        .map(|bytes| bytes.to_vec())
+        .map_err(|e| format!("Failed to read bytes: {}", e))
+}
+
+/// `base` with the non-empty `params` as its query
+fn with_query(base: &str, params: &[(&str, &str)]) -> String {
+    match reqwest::Url::parse(base) {
+        Ok(mut url) => {
+            url.query_pairs_mut()
+                .extend_pairs(params.iter().filter(|(_, value)| !value.is_empty()));
+            url.to_string()
+        }
+        // Left for the request to fail on
+        Err(_) => base.to_string(),
+    }
+}
+
+fn response_json(body: &str) -> Result<Value, String> {
+    serde_json::from_str(body).map_err(|_| "Unexpected response from the GIF service".to_string())
+}
+
+/// Tenor's v1 API, at Tenor or at a self-hosted server
+pub struct Tenor {
+    base: String,
+    key: String,
+}
+
+impl Tenor {
+    fn url(&self, endpoint: &str, params: &[(&str, &str)]) -> String {
+        let mut params = params.to_vec();
+        params.push(("key", &self.key));
+        params.push(("media_filter", "minimal"));
+        with_query(&format!("{}/{}", self.base, endpoint), &params)
+    }
+}
+
+impl GifProvider for Tenor {
+    fn search_url(&self, query: &str, limit: usize) -> String {
+        self.url("search", &[("q", query), ("limit", &limit.to_string())])
+    }
+
+    fn trending_url(&self, limit: usize) -> String {
+        self.url("trending", &[("limit", &limit.to_string())])
+    }
+
+    fn parse(&self, body: &str) -> Result<Vec<Gif>, String> {
+        let value = response_json(body)?;
+        let results = value["results"]
+            .as_array()
+            .ok_or("Unexpected response from the GIF service")?;
+        Ok(results
+            .iter()
+            .filter_map(|result| {
+                // v1 has the formats in an array, the first is the one
+                let media = &result["media"][0];
+                let preview = [&media["nanogif"], &media["tinygif"]]
+                    .into_iter()
+                    .find(|format| format["url"].is_string())?;
+                let full = [&media["tinygif"], &media["mediumgif"], &media["gif"]]
+                    .into_iter()
+                    .find(|format| format["url"].is_string())?;
+                let webp = [&media["tinywebp"], &media["webp"]]
+                    .into_iter()
+                    .find_map(|format| format["url"].as_str());
+                let title = [&result["content_description"], &result["title"]]
+                    .into_iter()
+                    .filter_map(Value::as_str)
+                    .find(|title| !title.is_empty())
+                    .unwrap_or("GIF");
+                Some(Gif {
+                    id: result["id"].as_str()?.to_string(),
+                    title: title.to_string(),
+                    preview_url: preview["url"].as_str()?.to_string(),
+                    full_url: full["url"].as_str()?.to_string(),
+                    webp_url: webp.map(str::to_string),
+                    width: preview["dims"][0].as_u64().unwrap_or(0) as u32,
+                    height: preview["dims"][1].as_u64().unwrap_or(0) as u32,
+                })
+            })
+            .collect())
+    }
+}
+
+pub struct Giphy {
+    key: String,
+}
+
+impl Giphy {
+    fn url(&self, endpoint: &str, params: &[(&str, &str)]) -> String {
+        let mut params = params.to_vec();
+        params.push(("api_key", &self.key));
+        with_query(&format!("{}/{}", GIPHY_API_BASE, endpoint), &params)
+    }
+}
+
+impl GifProvider for Giphy {
+    fn search_url(&self, query: &str, limit: usize) -> String {
+        self.url("search", &[("q", query), ("limit", &limit.to_string())])
+    }
+
+    fn trending_url(&self, limit: usize) -> String {
+        self.url("trending", &[("limit", &limit.to_string())])
+    }
+
+    fn parse(&self, body: &str) -> Result<Vec<Gif>, String> {
+        let value = response_json(body)?;
+        let data = value["data"].as_array().ok_or_else(|| {
+            value["meta"]["msg"]
+                .as_str()
+                .map(|message| format!("GIPHY: {}", message))
+                .unwrap_or_else(|| "Unexpected response from the GIF service".to_string())
+        })?;
+        // Sizes come as strings
+        let number = |value: &Value| value.as_str().and_then(|n| n.parse().ok()).unwrap_or(0);
+        Ok(data
+            .iter()
+            .filter_map(|result| {
+                let images = &result["images"];
+                let preview = &images["fixed_width_small"];
+                let full = [
+                    &images["fixed_width"],
+                    &images["downsized"],
+                    &images["original"],
+                ]
+                .into_iter()
+                .find(|format| format["url"].is_string())?;
+                let title = result["title"].as_str().filter(|title| !title.is_empty());
+                Some(Gif {
+                    id: result["id"].as_str()?.to_string(),
+                    title: title.unwrap_or("GIF").to_string(),
+                    preview_url: preview["url"].as_str()?.to_string(),
+                    full_url: full["url"].as_str()?.to_string(),
+                    webp_url: full["webp"].as_str().map(str::to_string),
+                    width: number(&preview["width"]),
+                    height: number(&preview["height"]),
+                })
+            })
+            .collect())
+    }
+}
+
+/// The provider picked in `settings`
+pub fn provider_for(settings: &GifSettings) -> Box<dyn GifProvider> {
+    match settings.provider() {
+        GifProviderKind::Tenor => Box::new(Tenor {
+            base: TENOR_API_BASE.to_string(),
+            key: if settings.tenor_api_key.is_empty() {
+                TENOR_DEMO_KEY.to_string()
+            } else {
+                settings.tenor_api_key.clone()
+            },
+        }),
+        GifProviderKind::Giphy => Box::new(Giphy {
+            key: settings.giphy_api_key.clone(),
+        }),
+        GifProviderKind::SelfHosted => Box::new(Tenor {
+            base: settings.self_hosted_url.clone(),
+            key: settings.self_hosted_api_key.clone(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_provider_requests_and_responses() {
+        let mut settings = GifSettings::default();
+        let tenor = provider_for(&settings);
+        assert_eq!(
+            tenor.search_url("cat & dog", 30),
+            "https://g.tenor.com/v1/search?q=cat+%26+dog&limit=30&key=LIVDSRZULELA&media_filter=minimal"
+        );
+        let gifs = tenor
+            .parse(
+                r#"{"results": [
+                    {"id": "1", "title": "", "content_description": "Cat",
+                     "media": [{"nanogif": {"url": "n.gif", "dims": [90, 60]},
+                                "tinygif": {"url": "t.gif", "dims": [220, 146]}}]},
+                    {"id": "2", "media": []}
+                ], "next": "10"}"#,
+            )
+            .unwrap();
+        assert_eq!(gifs.len(), 1);
+        assert_eq!(
+            (
+                gifs[0].title.as_str(),
+                gifs[0].full_url.as_str(),
+                gifs[0].width
+            ),
+            ("Cat", "t.gif", 90)
+        );
+        assert_eq!(gifs[0].webp_url, None);
+
+        settings.provider = "giphy".to_string();
+        assert!(!settings.is_configured());
+        settings.giphy_api_key = " key ".to_string();
+        settings.validate();
+        let giphy = provider_for(&settings);
+        assert_eq!(
+            giphy.trending_url(5),
+            "https://api.giphy.com/v1/gifs/trending?limit=5&api_key=key"
+        );
+        let gifs = giphy
+            .parse(
+                r#"{"data": [{"id": "a", "title": "Wave",
+                    "images": {"fixed_width_small": {"url": "s.gif", "width": "100", "height": "80"},
+                               "fixed_width": {"url": "w.gif", "webp": "w.webp"}}}]}"#,
+            )
+            .unwrap();
+        assert_eq!((gifs[0].height, gifs[0].full_url.as_str()), (80, "w.gif"));
+        assert_eq!(gifs[0].webp_url.as_deref(), Some("w.webp"));
+        assert_eq!(
+            giphy
+                .parse(r#"{"meta": {"msg": "Unauthorized"}}"#)
+                .unwrap_err(),
+            "GIPHY: Unauthorized"
+        );
+
+        settings.provider = "self_hosted".to_string();
+        settings.self_hosted_url = "https://gifs.example.org/v1/".to_string();
+        settings.validate();
+        assert!(provider_for(&settings)
+            .trending_url(1)
+            .starts_with("https://gifs.example.org/v1/trending?limit=1&media_filter"));
+    }
+}
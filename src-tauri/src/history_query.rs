@@ -0,0 +1,195 @@
+//! History Query
+//! The history list asks for one page of items at a time, filtered here,
+//! instead of receiving the whole history on every open. Pages are
+//! addressed by the id of the last item already shown, so items copied
+//! while the user scrolls don't shift the next page.
+
+use crate::clipboard_manager::{normalize_tag, ClipboardItem};
+use serde::{Deserialize, Serialize};
+
+/// Page size when the query leaves it out
+pub const DEFAULT_PAGE_SIZE: usize = 100;
+
+/// Larger pages are cut to this
+const MAX_PAGE_SIZE: usize = 1000;
+
+/// Filters and position of a page; everything left out matches all items
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct HistoryQuery {
+    /// Id of the last item of the previous page; none for the first page
+    #[serde(default)]
+    pub after: Option<String>,
+    #[serde(default)]
+    pub limit: Option<usize>,
+    /// Text the item contains, ignoring case
+    #[serde(default)]
+    pub text: String,
+    /// From "#work"
+    #[serde(default)]
+    pub tag: Option<String>,
+    /// From "app:code", part of the WM_CLASS or app id
+    #[serde(default)]
+    pub app: Option<String>,
+    /// Id of the collection the items are filed in
+    #[serde(default)]
+    pub collection: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HistoryPage {
+    pub items: Vec<ClipboardItem>,
+    /// Items matching the filters across all pages
+    pub total: usize,
+    /// Id to pass as `after` for the next page; none on the last one
+    pub next_cursor: Option<String>,
+}
+
+/// The filters of a query, normalized once rather than per item
+struct Filter<'a> {
+    text: String,
+    tag: Option<String>,
+    app: Option<String>,
+    collection: Option<&'a str>,
+}
+
+impl Filter<'_> {
+    fn new(query: &HistoryQuery) -> Filter<'_> {
+        Filter {
+            text: query.text.trim().to_lowercase(),
+            tag: query.tag.as_deref().and_then(normalize_tag),
+            app: query
+                .app
+                .as_deref()
+                .map(|app| app.trim().to_lowercase())
+                .filter(|app| !app.is_empty()),
+            collection: query.collection.as_deref(),
+        }
+    }
+
+    fn matches(&self, item: &ClipboardItem) -> bool {
+        if self
+            .collection
+            .is_some_and(|collection| item.collection.as_deref() != Some(collection))
+        {
+            return false;
+        }
+        if self
+            .tag
+            .as_ref()
+            .is_some_and(|tag| !item.tags.contains(tag))
+        {
+            return false;
+        }
+        if self.app.as_deref().is_some_and(|app| {
+            !item
+                .source_app
+                .as_deref()
+                .is_some_and(|source| source.to_lowercase().contains(app))
+        }) {
+            return false;
+        }
+        self.text.is_empty()
+            || item
+                .text()
+                .is_some_and(|content| content.to_lowercase().contains(&self.text))
+    }
+}
+
+/// The page of `history` (in history order) that `query` asks for. Fails
+/// when the `after` item is no longer in history, so the caller starts over.
+pub fn query(history: &[ClipboardItem], query: &HistoryQuery) -> Result<HistoryPage, String> {
+    let limit = query
+        .limit
+        .filter(|limit| *limit > 0)
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .min(MAX_PAGE_SIZE);
+    let start = match &query.after {
+        Some(id) => {
+            history
+                .iter()
+                .position(|item| &item.id == id)
+                .ok_or_else(|| format!("Item '{}' is no longer in history", id))?
+                + 1
+        }
+        None => 0,
+    };
+
+    let filter = Filter::new(query);
+    let matching = |item: &&ClipboardItem| filter.matches(item);
+
+    let total = history.iter().filter(matching).count();
+    let mut rest = history[start..].iter().filter(matching);
+    let items: Vec<ClipboardItem> = rest.by_ref().take(limit).cloned().collect();
+    let next_cursor = match rest.next() {
+        Some(_) => items.last().map(|item| item.id.clone()),
+        None => None,
+    };
+    Ok(HistoryPage {
+        items,
+        total,
+        next_cursor,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_query_pages() {
+        let history: Vec<ClipboardItem> = (0..250)
+            .map(|n| {
+                let mut item = ClipboardItem::new_text(format!("Item {}", n));
+                if n % 2 == 0 {
+                    item.tags = vec!["even".to_string()];
+                    item.source_app = Some("org.mozilla.firefox".to_string());
+                }
+                item
+            })
+            .collect();
+        let texts = |page: &HistoryPage| -> Vec<String> {
+            page.items
+                .iter()
+                .filter_map(|item| item.text())
+                .map(str::to_string)
+                .collect()
+        };
+
+        let first = query(&history, &HistoryQuery::default()).unwrap();
+        assert_eq!((first.items.len(), first.total), (DEFAULT_PAGE_SIZE, 250));
+        let rest = query(
+            &history,
+            &HistoryQuery {
+                after: first.next_cursor.clone(),
+                limit: Some(200),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(rest.items.len(), 150);
+        assert_eq!(rest.items[0].text(), Some("Item 100"));
+        assert_eq!(rest.next_cursor, None);
+
+        let filtered = query(
+            &history,
+            &HistoryQuery {
+                limit: Some(3),
+                text: "ITEM 1".to_string(),
+                tag: Some("#Even".to_string()),
+                app: Some("Firefox".to_string()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(texts(&filtered), ["Item 10", "Item 12", "Item 14"]);
+        // 10 to 18, 100 to 198, even ones only
+        assert_eq!(filtered.total, 55);
+        assert_eq!(filtered.next_cursor, Some(filtered.items[2].id.clone()));
+
+        let stale = HistoryQuery {
+            after: Some("deleted".to_string()),
+            ..Default::default()
+        };
+        assert!(query(&history, &stale).is_err());
+    }
+}
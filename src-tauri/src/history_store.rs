@@ -0,0 +1,1003 @@
+//! History Store Module
+//! Persistence backends for the clipboard history, selectable in settings:
+//! a single JSON file (default), one file per item for dotfile users, SQLite
+//! or sled for large histories, an append-only event log the history is
+//! replayed from, and memory-only for users who never want history on disk.
+
+use crate::clipboard_manager::{calculate_hash, ClipboardItem};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+const JSON_FILE: &str = "history.json";
+const FLAT_FILES_DIR: &str = "history";
+const FLAT_FILES_INDEX: &str = "index.json";
+const SQLITE_FILE: &str = "history.sqlite3";
+const SLED_DIR: &str = "history.sled";
+const EVENT_LOG_FILE: &str = "history.events.jsonl";
+
+/// The event log is rewritten as one event per item once it is this large,
+/// in bytes, and more than `COMPACT_FACTOR` times what that rewrite takes.
+/// Sizes, not event counts, as every change to an image item logs its data.
+const COMPACT_MIN_BYTES: u64 = 1024 * 1024;
+const COMPACT_FACTOR: u64 = 4;
+
+/// Key of the id list that records the history order (sled and SQLite)
+const ORDER_KEY: &str = "order";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageBackend {
+    Json,
+    FlatFiles,
+    Sqlite,
+    Sled,
+    EventLog,
+    Memory,
+}
+
+impl StorageBackend {
+    pub const ALL: [StorageBackend; 6] = [
+        Self::Json,
+        Self::FlatFiles,
+        Self::Sqlite,
+        Self::Sled,
+        Self::EventLog,
+        Self::Memory,
+    ];
+
+    /// Value used in the `storage_backend` setting
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::FlatFiles => "flat_files",
+            Self::Sqlite => "sqlite",
+            Self::Sled => "sled",
+            Self::EventLog => "event_log",
+            Self::Memory => "memory",
+        }
+    }
+
+    pub fn from_setting(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|backend| backend.as_str() == value)
+    }
+}
+
+/// Persistence layer behind `ClipboardManager`. `save` always receives the
+/// complete history in display order; backends decide how much to rewrite.
+pub trait HistoryStore: Send {
+    fn backend(&self) -> StorageBackend;
+
+    /// File or directory holding the history, or None if it is not persisted
+    fn location(&self) -> Option<&Path>;
+
+    fn load(&mut self) -> Result<Vec<ClipboardItem>, String>;
+
+    fn save(&mut self, items: &[ClipboardItem]) -> Result<(), String>;
+}
+
+/// Opens the store for `backend` inside the application data directory
+pub fn open_store(
+    backend: StorageBackend,
+    data_dir: &Path,
+) -> Result<Box<dyn HistoryStore>, String> {
+    Ok(match backend {
+        StorageBackend::Json => Box::new(JsonFileStore::new(data_dir.join(JSON_FILE))),
+        StorageBackend::FlatFiles => Box::new(FlatFileStore::new(data_dir.join(FLAT_FILES_DIR))),
+        StorageBackend::Sqlite => Box::new(SqliteStore::open(data_dir.join(SQLITE_FILE))?),
+        StorageBackend::Sled => Box::new(SledStore::open(data_dir.join(SLED_DIR))?),
+        StorageBackend::EventLog => Box::new(EventLogStore::new(data_dir.join(EVENT_LOG_FILE))),
+        StorageBackend::Memory => Box::new(MemoryStore::default()),
+    })
+}
+
+// --- Change Tracking ---
+
+/// Items that need to be written since the last save
+struct Changes {
+    /// (id, serialized item) for new or modified items
+    upserts: Vec<(String, String)>,
+    /// Ids of items no longer in the history
+    removed: Vec<String>,
+    /// Ids in history order
+    order: Vec<String>,
+}
+
+/// Remembers what the per-item backends last wrote, so a save after one copy
+/// writes one item instead of the whole history
+#[derive(Default)]
+struct ChangeTracker {
+    written: HashMap<String, u64>,
+}
+
+impl ChangeTracker {
+    /// Records items read back from disk as already written
+    fn loaded(&mut self, items: &[ClipboardItem]) {
+        self.written = items
+            .iter()
+            .filter_map(|item| {
+                let json = serde_json::to_string(item).ok()?;
+                Some((item.id.clone(), calculate_hash(&json)))
+            })
+            .collect();
+    }
+
+    fn changes(&mut self, items: &[ClipboardItem]) -> Result<Changes, String> {
+        let mut upserts = Vec::new();
+        let mut written = HashMap::with_capacity(items.len());
+
+        for item in items {
+            let json = serde_json::to_string(item).map_err(|e| e.to_string())?;
+            let hash = calculate_hash(&json);
+            if self.written.get(&item.id) != Some(&hash) {
+                upserts.push((item.id.clone(), json));
+            }
+            written.insert(item.id.clone(), hash);
+        }
+
+        let removed = self
+            .written
+            .keys()
+            .filter(|id| !written.contains_key(*id))
+            .cloned()
+            .collect();
+
+        self.written = written;
+        Ok(Changes {
+            upserts,
+            removed,
+            order: items.iter().map(|item| item.id.clone()).collect(),
+        })
+    }
+
+    /// Forgets everything after a failed write so the next save rewrites all items
+    fn reset(&mut self) {
+        self.written.clear();
+    }
+}
+
+/// Bytes used by the store: its directory, or its file and the files next to
+/// it sharing the name (SQLite's -wal and -shm)
+pub fn store_size(location: Option<&Path>) -> u64 {
+    fn dir_size(dir: &Path) -> u64 {
+        std::fs::read_dir(dir)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|entry| match entry.metadata() {
+                Ok(meta) if meta.is_dir() => dir_size(&entry.path()),
+                Ok(meta) => meta.len(),
+                Err(_) => 0,
+            })
+            .sum()
+    }
+
+    let Some(location) = location else {
+        return 0;
+    };
+    if location.is_dir() {
+        return dir_size(location);
+    }
+    let (Some(parent), Some(name)) = (location.parent(), location.file_name()) else {
+        return 0;
+    };
+    let name = name.to_string_lossy();
+    std::fs::read_dir(parent)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| {
+            entry
+                .file_name()
+                .to_string_lossy()
+                .starts_with(name.as_ref())
+        })
+        .filter_map(|entry| entry.metadata().ok())
+        .map(|meta| meta.len())
+        .sum()
+}
+
+/// Puts items back in the stored order. Items missing from the order list
+/// (interrupted write) are kept at the end.
+fn in_order(mut items: HashMap<String, ClipboardItem>, order: &[String]) -> Vec<ClipboardItem> {
+    let mut ordered: Vec<ClipboardItem> = order.iter().filter_map(|id| items.remove(id)).collect();
+    let mut rest: Vec<ClipboardItem> = items.into_values().collect();
+    rest.sort_by_key(|item| std::cmp::Reverse(item.timestamp));
+    ordered.extend(rest);
+    ordered
+}
+
+// --- JSON File ---
+
+/// The whole history in one pretty-printed JSON file
+pub struct JsonFileStore {
+    path: PathBuf,
+}
+
+impl JsonFileStore {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+}
+
+impl HistoryStore for JsonFileStore {
+    fn backend(&self) -> StorageBackend {
+        StorageBackend::Json
+    }
+
+    fn location(&self) -> Option<&Path> {
+        Some(&self.path)
+    }
+
+    fn load(&mut self) -> Result<Vec<ClipboardItem>, String> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = fs::read_to_string(&self.path)
+            .map_err(|e| format!("Failed to read history file: {}", e))?;
+        serde_json::from_str(&content).map_err(|e| format!("Failed to parse history: {}", e))
+    }
+
+    fn save(&mut self, items: &[ClipboardItem]) -> Result<(), String> {
+        let content = serde_json::to_string_pretty(items)
+            .map_err(|e| format!("Failed to serialize history: {}", e))?;
+        if let Some(parent) = self.path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        fs::write(&self.path, content).map_err(|e| e.to_string())
+    }
+}
+
+// --- Flat Files ---
+
+/// One `<id>.json` file per item plus an index with the order, so history can
+/// be diffed and synced by dotfile tooling
+pub struct FlatFileStore {
+    dir: PathBuf,
+    tracker: ChangeTracker,
+}
+
+impl FlatFileStore {
+    pub fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            tracker: ChangeTracker::default(),
+        }
+    }
+
+    fn write_changes(&self, changes: &Changes) -> Result<(), String> {
+        fs::create_dir_all(&self.dir).map_err(|e| e.to_string())?;
+
+        for (id, json) in &changes.upserts {
+            // Pretty-print item files; they are meant to be read by humans
+            let value: serde_json::Value = serde_json::from_str(json).map_err(|e| e.to_string())?;
+            let pretty = serde_json::to_string_pretty(&value).map_err(|e| e.to_string())?;
+            fs::write(self.dir.join(format!("{}.json", id)), pretty).map_err(|e| e.to_string())?;
+        }
+
+        let index = serde_json::to_string_pretty(&changes.order).map_err(|e| e.to_string())?;
+        fs::write(self.dir.join(FLAT_FILES_INDEX), index).map_err(|e| e.to_string())?;
+
+        for id in &changes.removed {
+            let _ = fs::remove_file(self.dir.join(format!("{}.json", id)));
+        }
+        Ok(())
+    }
+}
+
+impl HistoryStore for FlatFileStore {
+    fn backend(&self) -> StorageBackend {
+        StorageBackend::FlatFiles
+    }
+
+    fn location(&self) -> Option<&Path> {
+        Some(&self.dir)
+    }
+
+    fn load(&mut self) -> Result<Vec<ClipboardItem>, String> {
+        if !self.dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let order: Vec<String> = match fs::read_to_string(self.dir.join(FLAT_FILES_INDEX)) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_default(),
+            Err(_) => Vec::new(),
+        };
+
+        let mut items = HashMap::new();
+        for entry in fs::read_dir(&self.dir)
+            .map_err(|e| e.to_string())?
+            .flatten()
+        {
+            let path = entry.path();
+            let is_item = path.extension().is_some_and(|ext| ext == "json")
+                && path
+                    .file_name()
+                    .is_some_and(|name| name != FLAT_FILES_INDEX);
+            if !is_item {
+                continue;
+            }
+            match fs::read_to_string(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|c| serde_json::from_str::<ClipboardItem>(&c).map_err(|e| e.to_string()))
+            {
+                Ok(item) => {
+                    items.insert(item.id.clone(), item);
+                }
+                Err(e) => eprintln!("[HistoryStore] Skipping {}: {}", path.display(), e),
+            }
+        }
+
+        let items = in_order(items, &order);
+        self.tracker.loaded(&items);
+        Ok(items)
+    }
+
+    fn save(&mut self, items: &[ClipboardItem]) -> Result<(), String> {
+        let changes = self.tracker.changes(items)?;
+        self.write_changes(&changes)
+            .inspect_err(|_| self.tracker.reset())
+    }
+}
+
+// --- SQLite ---
+
+/// Items as JSON rows in an SQLite database
+pub struct SqliteStore {
+    path: PathBuf,
+    conn: rusqlite::Connection,
+    tracker: ChangeTracker,
+}
+
+impl SqliteStore {
+    pub fn open(path: PathBuf) -> Result<Self, String> {
+        if let Some(parent) = path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        let conn = rusqlite::Connection::open(&path)
+            .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+        conn.execute_batch(
+            "PRAGMA journal_mode = WAL;
+             CREATE TABLE IF NOT EXISTS items (id TEXT PRIMARY KEY, data TEXT NOT NULL);
+             CREATE TABLE IF NOT EXISTS meta (key TEXT PRIMARY KEY, value TEXT NOT NULL);",
+        )
+        .map_err(|e| format!("Failed to initialize {}: {}", path.display(), e))?;
+
+        Ok(Self {
+            path,
+            conn,
+            tracker: ChangeTracker::default(),
+        })
+    }
+
+    fn write_changes(&mut self, changes: &Changes) -> rusqlite::Result<()> {
+        let tx = self.conn.transaction()?;
+        {
+            let mut upsert = tx.prepare_cached(
+                "INSERT INTO items (id, data) VALUES (?1, ?2)
+                 ON CONFLICT(id) DO UPDATE SET data = excluded.data",
+            )?;
+            for (id, json) in &changes.upserts {
+                upsert.execute((id, json))?;
+            }
+
+            let mut delete = tx.prepare_cached("DELETE FROM items WHERE id = ?1")?;
+            for id in &changes.removed {
+                delete.execute([id])?;
+            }
+        }
+
+        let order = serde_json::to_string(&changes.order).unwrap_or_else(|_| "[]".to_string());
+        tx.execute(
+            "INSERT INTO meta (key, value) VALUES (?1, ?2)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+            (ORDER_KEY, order),
+        )?;
+        tx.commit()
+    }
+}
+
+impl HistoryStore for SqliteStore {
+    fn backend(&self) -> StorageBackend {
+        StorageBackend::Sqlite
+    }
+
+    fn location(&self) -> Option<&Path> {
+        Some(&self.path)
+    }
+
+    fn load(&mut self) -> Result<Vec<ClipboardItem>, String> {
+        let order: Vec<String> = self
+            .conn
+            .query_row(
+                "SELECT value FROM meta WHERE key = ?1",
+                [ORDER_KEY],
+                |row| row.get::<_, String>(0),
+            )
+            .ok()
+            .and_then(|value| serde_json::from_str(&value).ok())
+            .unwrap_or_default();
+
+        let mut statement = self
+            .conn
+            .prepare("SELECT data FROM items")
+            .map_err(|e| e.to_string())?;
+        let rows = statement
+            .query_map([], |row| row.get::<_, String>(0))
+            .map_err(|e| e.to_string())?;
+
+        let mut items = HashMap::new();
+        for data in rows.flatten() {
+            match serde_json::from_str::<ClipboardItem>(&data) {
+                Ok(item) => {
+                    items.insert(item.id.clone(), item);
+                }
+                Err(e) => eprintln!("[HistoryStore] Skipping unreadable row: {}", e),
+            }
+        }
+        drop(statement);
+
+        let items = in_order(items, &order);
+        self.tracker.loaded(&items);
+        Ok(items)
+    }
+
+    fn save(&mut self, items: &[ClipboardItem]) -> Result<(), String> {
+        let changes = self.tracker.changes(items)?;
+        self.write_changes(&changes).map_err(|e| {
+            self.tracker.reset();
+            e.to_string()
+        })
+    }
+}
+
+// --- sled ---
+
+/// In the error of opening a sled database another process holds
+pub const SLED_LOCKED: &str = "is in use by another process";
+
+/// Items as JSON values in a sled database. sled holds an exclusive lock, so
+/// the database can only be read while the app is not running.
+pub struct SledStore {
+    path: PathBuf,
+    items: sled::Tree,
+    db: sled::Db,
+    tracker: ChangeTracker,
+}
+
+impl SledStore {
+    pub fn open(path: PathBuf) -> Result<Self, String> {
+        let db = sled::open(&path).map_err(|e| match e {
+            // sled reports its lock file as a plain I/O error
+            sled::Error::Io(io) if io.to_string().starts_with("could not acquire lock") => {
+                format!("{} {}", path.display(), SLED_LOCKED)
+            }
+            e => format!("Failed to open {}: {}", path.display(), e),
+        })?;
+        let items = db.open_tree("items").map_err(|e| e.to_string())?;
+        Ok(Self {
+            path,
+            items,
+            db,
+            tracker: ChangeTracker::default(),
+        })
+    }
+
+    fn write_changes(&self, changes: &Changes) -> sled::Result<()> {
+        let mut batch = sled::Batch::default();
+        for (id, json) in &changes.upserts {
+            batch.insert(id.as_bytes(), json.as_bytes());
+        }
+        for id in &changes.removed {
+            batch.remove(id.as_bytes());
+        }
+        self.items.apply_batch(batch)?;
+
+        let order = serde_json::to_vec(&changes.order).unwrap_or_default();
+        self.db.insert(ORDER_KEY, order)?;
+        self.db.flush()?;
+        Ok(())
+    }
+}
+
+impl HistoryStore for SledStore {
+    fn backend(&self) -> StorageBackend {
+        StorageBackend::Sled
+    }
+
+    fn location(&self) -> Option<&Path> {
+        Some(&self.path)
+    }
+
+    fn load(&mut self) -> Result<Vec<ClipboardItem>, String> {
+        let order: Vec<String> = self
+            .db
+            .get(ORDER_KEY)
+            .map_err(|e| e.to_string())?
+            .and_then(|value| serde_json::from_slice(&value).ok())
+            .unwrap_or_default();
+
+        let mut items = HashMap::new();
+        for entry in self.items.iter() {
+            let (_, value) = entry.map_err(|e| e.to_string())?;
+            match serde_json::from_slice::<ClipboardItem>(&value) {
+                Ok(item) => {
+                    items.insert(item.id.clone(), item);
+                }
+                Err(e) => eprintln!("[HistoryStore] Skipping unreadable entry: {}", e),
+            }
+        }
+
+        let items = in_order(items, &order);
+        self.tracker.loaded(&items);
+        Ok(items)
+    }
+
+    fn save(&mut self, items: &[ClipboardItem]) -> Result<(), String> {
+        let changes = self.tracker.changes(items)?;
+        self.write_changes(&changes).map_err(|e| {
+            self.tracker.reset();
+            e.to_string()
+        })
+    }
+}
+
+// --- Event Log ---
+
+/// A change to the history, one JSON line each in the log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+enum HistoryEvent {
+    /// An item was copied or changed. On replay a new item goes to the top,
+    /// or above the unpinned ones when it isn't pinned itself, where copies
+    /// land.
+    Put {
+        item: Box<ClipboardItem>,
+    },
+    Remove {
+        id: String,
+    },
+    /// The order changed other than by new items landing on top
+    Order {
+        ids: Vec<String>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct LoggedEvent {
+    at: DateTime<Utc>,
+    #[serde(flatten)]
+    event: HistoryEvent,
+}
+
+/// The order events replay to, and which items are pinned along the way
+#[derive(Debug, Clone, Default)]
+struct Replay {
+    order: Vec<String>,
+    pinned: HashSet<String>,
+}
+
+impl Replay {
+    fn of(items: &[ClipboardItem]) -> Self {
+        Self {
+            order: items.iter().map(|item| item.id.clone()).collect(),
+            pinned: items
+                .iter()
+                .filter(|item| item.pinned)
+                .map(|item| item.id.clone())
+                .collect(),
+        }
+    }
+
+    fn put(&mut self, item: &ClipboardItem) {
+        if item.pinned {
+            self.pinned.insert(item.id.clone());
+        } else {
+            self.pinned.remove(&item.id);
+        }
+        if !self.order.contains(&item.id) {
+            let top = if item.pinned {
+                0
+            } else {
+                self.order
+                    .iter()
+                    .position(|id| !self.pinned.contains(id))
+                    .unwrap_or(self.order.len())
+            };
+            self.order.insert(top, item.id.clone());
+        }
+    }
+
+    fn remove(&mut self, id: &str) {
+        self.order.retain(|known| known != id);
+        self.pinned.remove(id);
+    }
+}
+
+/// The history as the events that led to it, appended to one file and
+/// replayed on load, so every change stays on record until the log is
+/// compacted
+pub struct EventLogStore {
+    path: PathBuf,
+    tracker: ChangeTracker,
+    /// What the log replays to
+    replay: Replay,
+    /// Size of the file
+    bytes: u64,
+    /// Size of the last Put of each item, which a compacted log holds
+    item_bytes: HashMap<String, u64>,
+}
+
+impl EventLogStore {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            tracker: ChangeTracker::default(),
+            replay: Replay::default(),
+            bytes: 0,
+            item_bytes: HashMap::new(),
+        }
+    }
+
+    /// The events as lines of the log, noting the size of each Put in
+    /// `item_bytes`
+    fn lines(
+        events: Vec<HistoryEvent>,
+        item_bytes: &mut HashMap<String, u64>,
+    ) -> Result<String, String> {
+        let at = Utc::now();
+        let mut lines = String::new();
+        for event in events {
+            let put = match &event {
+                HistoryEvent::Put { item } => Some(item.id.clone()),
+                _ => None,
+            };
+            let line = serde_json::to_string(&LoggedEvent { at, event })
+                .map_err(|e| format!("Failed to serialize history event: {}", e))?;
+            if let Some(id) = put {
+                item_bytes.insert(id, line.len() as u64 + 1);
+            }
+            lines.push_str(&line);
+            lines.push('\n');
+        }
+        Ok(lines)
+    }
+
+    fn append(&self, lines: &str) -> Result<(), String> {
+        if let Some(parent) = self.path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .and_then(|mut file| file.write_all(lines.as_bytes()))
+            .map_err(|e| format!("Failed to append to {}: {}", self.path.display(), e))
+    }
+
+    /// Whether the log has grown well past what compacting it would leave
+    fn needs_compacting(&self) -> bool {
+        let compacted: u64 = self.item_bytes.values().sum();
+        self.bytes >= COMPACT_MIN_BYTES && self.bytes > compacted.saturating_mul(COMPACT_FACTOR)
+    }
+
+    /// Replaces the log with one event per item, bottom first so replay
+    /// puts them back in order
+    fn compact(&mut self, items: &[ClipboardItem]) -> Result<(), String> {
+        let mut replay = Replay::default();
+        let mut events = Vec::new();
+        for item in items.iter().rev() {
+            replay.put(item);
+            events.push(HistoryEvent::Put {
+                item: Box::new(item.clone()),
+            });
+        }
+        // Only when a pinned item sits below an unpinned one
+        let ids: Vec<String> = items.iter().map(|item| item.id.clone()).collect();
+        if replay.order != ids {
+            events.push(HistoryEvent::Order { ids });
+        }
+        let mut item_bytes = HashMap::new();
+        let lines = Self::lines(events, &mut item_bytes)?;
+        let temp = self.path.with_extension("jsonl.tmp");
+        fs::write(&temp, &lines)
+            .and_then(|()| fs::rename(&temp, &self.path))
+            .map_err(|e| format!("Failed to compact {}: {}", self.path.display(), e))?;
+
+        self.tracker.loaded(items);
+        self.replay = Replay::of(items);
+        self.bytes = lines.len() as u64;
+        self.item_bytes = item_bytes;
+        Ok(())
+    }
+}
+
+impl HistoryStore for EventLogStore {
+    fn backend(&self) -> StorageBackend {
+        StorageBackend::EventLog
+    }
+
+    fn location(&self) -> Option<&Path> {
+        Some(&self.path)
+    }
+
+    fn load(&mut self) -> Result<Vec<ClipboardItem>, String> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        let read_error = |e: std::io::Error| format!("Failed to read history events: {}", e);
+        let file = fs::File::open(&self.path).map_err(read_error)?;
+
+        let mut items = HashMap::new();
+        let mut replay = Replay::default();
+        let mut bytes = 0;
+        let mut item_bytes = HashMap::new();
+        // A line at a time, as the log can be many times the history's size
+        for line in BufReader::new(file).lines() {
+            let line = line.map_err(read_error)?;
+            let size = line.len() as u64 + 1;
+            bytes += size;
+            if line.trim().is_empty() {
+                continue;
+            }
+            // A line cut short by a crash is the only one expected to fail
+            let logged: LoggedEvent = match serde_json::from_str(&line) {
+                Ok(logged) => logged,
+                Err(e) => {
+                    eprintln!("[HistoryStore] Skipping unreadable event: {}", e);
+                    continue;
+                }
+            };
+            match logged.event {
+                HistoryEvent::Put { item } => {
+                    replay.put(&item);
+                    item_bytes.insert(item.id.clone(), size);
+                    items.insert(item.id.clone(), *item);
+                }
+                HistoryEvent::Remove { id } => {
+                    replay.remove(&id);
+                    item_bytes.remove(&id);
+                    items.remove(&id);
+                }
+                HistoryEvent::Order { ids } => replay.order = ids,
+            }
+        }
+
+        let items = in_order(items, &replay.order);
+        self.tracker.loaded(&items);
+        self.replay = Replay::of(&items);
+        self.bytes = bytes;
+        self.item_bytes = item_bytes;
+        Ok(items)
+    }
+
+    fn save(&mut self, items: &[ClipboardItem]) -> Result<(), String> {
+        if self.needs_compacting() {
+            return self.compact(items);
+        }
+
+        let changes = self.tracker.changes(items)?;
+        let upserted: HashSet<&str> = changes.upserts.iter().map(|(id, _)| id.as_str()).collect();
+
+        // Replayed as they are written, to tell whether an Order is needed
+        let mut replay = self.replay.clone();
+        let mut events = Vec::new();
+        for id in &changes.removed {
+            replay.remove(id);
+            events.push(HistoryEvent::Remove { id: id.clone() });
+        }
+        // New items land on top of the unpinned ones, so go bottom first
+        for item in items
+            .iter()
+            .rev()
+            .filter(|item| upserted.contains(item.id.as_str()))
+        {
+            replay.put(item);
+            events.push(HistoryEvent::Put {
+                item: Box::new(item.clone()),
+            });
+        }
+        if replay.order != changes.order {
+            replay.order = changes.order.clone();
+            events.push(HistoryEvent::Order { ids: changes.order });
+        }
+        if events.is_empty() {
+            return Ok(());
+        }
+
+        let mut item_bytes = self.item_bytes.clone();
+        for id in &changes.removed {
+            item_bytes.remove(id);
+        }
+        let lines = Self::lines(events, &mut item_bytes)?;
+        self.append(&lines).inspect_err(|_| self.tracker.reset())?;
+        self.replay = replay;
+        self.bytes += lines.len() as u64;
+        self.item_bytes = item_bytes;
+        Ok(())
+    }
+}
+
+// --- Memory ---
+
+/// Keeps nothing on disk; history is gone when the app exits
+#[derive(Default)]
+pub struct MemoryStore {
+    items: Vec<ClipboardItem>,
+}
+
+impl HistoryStore for MemoryStore {
+    fn backend(&self) -> StorageBackend {
+        StorageBackend::Memory
+    }
+
+    fn location(&self) -> Option<&Path> {
+        None
+    }
+
+    fn load(&mut self) -> Result<Vec<ClipboardItem>, String> {
+        Ok(self.items.clone())
+    }
+
+    fn save(&mut self, items: &[ClipboardItem]) -> Result<(), String> {
+        self.items = items.to_vec();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+    use std::env::temp_dir;
+
+    fn ids(items: &[ClipboardItem]) -> HashSet<&str> {
+        items.iter().map(|item| item.id.as_str()).collect()
+    }
+
+    fn temp_data_dir(name: &str) -> PathBuf {
+        let dir = temp_dir().join(name);
+        let _ = fs::remove_dir_all(&dir); // Ensure clean start
+        dir
+    }
+
+    fn sample_items() -> Vec<ClipboardItem> {
+        let mut pinned = ClipboardItem::new_text("pinned".to_string());
+        pinned.pinned = true;
+        vec![
+            pinned,
+            ClipboardItem::new_text("newest".to_string()),
+            ClipboardItem::new_rich_text("older".to_string(), "<b>older</b>".to_string()),
+        ]
+    }
+
+    fn assert_round_trip(backend: StorageBackend, dir: &Path) {
+        let items = sample_items();
+        {
+            let mut store = open_store(backend, dir).unwrap();
+            assert!(store.load().unwrap().is_empty());
+            store.save(&items).unwrap();
+        }
+
+        // Drop an item and reorder, then reopen to read what is on disk
+        let updated = vec![items[2].clone(), items[0].clone()];
+        {
+            let mut store = open_store(backend, dir).unwrap();
+            assert_eq!(ids(&store.load().unwrap()), ids(&items));
+            store.save(&updated).unwrap();
+        }
+
+        let mut store = open_store(backend, dir).unwrap();
+        let loaded: Vec<String> = store.load().unwrap().into_iter().map(|i| i.id).collect();
+        let expected: Vec<String> = updated.into_iter().map(|i| i.id).collect();
+        assert_eq!(loaded, expected, "{:?}", backend);
+    }
+
+    #[test]
+    fn test_disk_backends_round_trip_in_order() {
+        for backend in [
+            StorageBackend::Json,
+            StorageBackend::FlatFiles,
+            StorageBackend::Sqlite,
+            StorageBackend::Sled,
+            StorageBackend::EventLog,
+        ] {
+            let dir = temp_data_dir(&format!("history_store_{}_test", backend.as_str()));
+            assert_round_trip(backend, &dir);
+            let _ = fs::remove_dir_all(&dir);
+        }
+    }
+
+    #[test]
+    fn test_sled_reports_a_held_lock() {
+        let dir = temp_data_dir("history_store_sled_lock_test");
+        let held = SledStore::open(dir.join(SLED_DIR)).unwrap();
+        let error = SledStore::open(dir.join(SLED_DIR)).err().unwrap();
+        assert!(error.contains(SLED_LOCKED), "{}", error);
+        drop(held);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_change_tracker_only_writes_modified_items() {
+        let mut items = sample_items();
+        let mut tracker = ChangeTracker::default();
+        assert_eq!(tracker.changes(&items).unwrap().upserts.len(), 3);
+
+        items[1].pinned = true;
+        let removed = items.pop().unwrap();
+        let changes = tracker.changes(&items).unwrap();
+        assert_eq!(changes.upserts.len(), 1);
+        assert_eq!(changes.upserts[0].0, items[1].id);
+        assert_eq!(changes.removed, vec![removed.id]);
+    }
+
+    #[test]
+    fn test_event_log_replays_and_compacts() {
+        let dir = temp_data_dir("history_store_event_log_replay_test");
+        let path = dir.join(EVENT_LOG_FILE);
+        let mut items = sample_items();
+        let mut store = EventLogStore::new(path.clone());
+        store.save(&items).unwrap();
+
+        // A copy only appends itself, a pin reorders
+        let events = || fs::read_to_string(&path).unwrap().lines().count();
+        items.insert(1, ClipboardItem::new_text("copied".to_string()));
+        store.save(&items).unwrap();
+        assert_eq!(events(), 4);
+        items[2].pinned = true;
+        items.swap(1, 2);
+        store.save(&items).unwrap();
+        assert_eq!(events(), 6);
+        assert_eq!(store.bytes, fs::metadata(&path).unwrap().len());
+
+        let expected: Vec<String> = items.iter().map(|item| item.id.clone()).collect();
+        let mut reopened = EventLogStore::new(path.clone());
+        let loaded: Vec<String> = reopened.load().unwrap().into_iter().map(|i| i.id).collect();
+        assert_eq!(loaded, expected);
+
+        assert_eq!(reopened.bytes, store.bytes);
+        reopened.bytes = COMPACT_MIN_BYTES;
+        reopened.save(&items).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), 4);
+        let mut compacted = EventLogStore::new(path);
+        let loaded: Vec<String> = compacted
+            .load()
+            .unwrap()
+            .into_iter()
+            .map(|i| i.id)
+            .collect();
+        assert_eq!(loaded, expected);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_event_log_compacts_by_size() {
+        let dir = temp_data_dir("history_store_event_log_size_test");
+        let path = dir.join(EVENT_LOG_FILE);
+        let mut store = EventLogStore::new(path.clone());
+        // Few events, each logging a large item again
+        let mut items = vec![ClipboardItem::new_text("x".repeat(200 * 1024))];
+        for _ in 0..20 {
+            items[0].pinned = !items[0].pinned;
+            store.save(&items).unwrap();
+        }
+        let size = fs::metadata(&path).unwrap().len();
+        // Twenty Puts of it would be 4 MB
+        assert!(size < 2 * COMPACT_MIN_BYTES, "log is {} bytes", size);
+        assert_eq!(store.load().unwrap().len(), 1);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_memory_store_never_touches_disk() {
+        let mut store = open_store(StorageBackend::Memory, Path::new("/nonexistent")).unwrap();
+        store.save(&sample_items()).unwrap();
+        assert_eq!(store.load().unwrap().len(), 3);
+        assert!(store.location().is_none());
+    }
+}
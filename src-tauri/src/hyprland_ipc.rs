@@ -0,0 +1,149 @@
+//! Hyprland IPC
+//! Talks to Hyprland's request socket to record the active window before the
+//! popup opens and focus it again before pasting. On Wayland the X11 focus
+//! calls only reach XWayland clients, so native windows need this. It also
+//! tells where the pointer and the active window are, which Wayland clients
+//! can't ask for.
+
+use crate::focus_manager::{AdapterWindow, FocusAdapter};
+use serde::Deserialize;
+use std::io::{Read, Write};
+use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Hyprland answers immediately; don't hang the paste if it doesn't
+const SOCKET_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Fields of `j/activewindow` that we need
+#[derive(Debug, Deserialize)]
+struct ActiveWindow {
+    address: String,
+    #[serde(default)]
+    class: String,
+    /// Top-left corner and size, in layout coordinates
+    #[serde(default)]
+    at: Option<(i32, i32)>,
+    #[serde(default)]
+    size: Option<(i32, i32)>,
+}
+
+/// Reply of `j/cursorpos`, in layout coordinates
+#[derive(Debug, Deserialize)]
+struct CursorPos {
+    x: f64,
+    y: f64,
+}
+
+/// Request socket of the running instance. Hyprland 0.40+ keeps it under
+/// $XDG_RUNTIME_DIR/hypr, older releases under /tmp/hypr.
+fn socket_path() -> Option<PathBuf> {
+    let signature = std::env::var_os("HYPRLAND_INSTANCE_SIGNATURE")?;
+
+    let runtime_dir = std::env::var_os("XDG_RUNTIME_DIR").map(PathBuf::from);
+    runtime_dir
+        .into_iter()
+        .chain([PathBuf::from("/tmp")])
+        .map(|dir| dir.join("hypr").join(&signature).join(".socket.sock"))
+        .find(|path| path.exists())
+}
+
+/// Sends one request and returns the reply
+fn request(command: &str) -> Result<String, String> {
+    let path = socket_path().ok_or("Hyprland socket not found")?;
+    let mut stream = UnixStream::connect(&path)
+        .map_err(|e| format!("Failed to connect to {}: {}", path.display(), e))?;
+    let _ = stream.set_read_timeout(Some(SOCKET_TIMEOUT));
+    let _ = stream.set_write_timeout(Some(SOCKET_TIMEOUT));
+
+    stream
+        .write_all(command.as_bytes())
+        .map_err(|e| format!("Failed to send request: {}", e))?;
+
+    let mut reply = String::new();
+    stream
+        .read_to_string(&mut reply)
+        .map_err(|e| format!("Failed to read reply: {}", e))?;
+    Ok(reply)
+}
+
+/// Parses `j/activewindow`; Hyprland replies `{}` when nothing is focused
+fn parse_active_window(reply: &str) -> Option<AdapterWindow> {
+    let window: ActiveWindow = serde_json::from_str(reply).ok()?;
+    if window.address.is_empty() {
+        return None;
+    }
+    Some(AdapterWindow {
+        id: window.address,
+        app_id: window.class,
+    })
+}
+
+/// Where the pointer is, for opening the window next to it
+pub fn cursor_position() -> Option<(i32, i32)> {
+    let reply = request("j/cursorpos").ok()?;
+    let pos: CursorPos = serde_json::from_str(&reply).ok()?;
+    Some((pos.x as i32, pos.y as i32))
+}
+
+/// Middle of the active window, for opening on its monitor
+pub fn active_window_center() -> Option<(i32, i32)> {
+    window_center(&request("j/activewindow").ok()?)
+}
+
+fn window_center(reply: &str) -> Option<(i32, i32)> {
+    let window: ActiveWindow = serde_json::from_str(reply).ok()?;
+    let ((x, y), (width, height)) = (window.at?, window.size?);
+    Some((x + width / 2, y + height / 2))
+}
+
+pub struct HyprlandAdapter;
+
+impl FocusAdapter for HyprlandAdapter {
+    fn id(&self) -> &'static str {
+        "hyprland"
+    }
+
+    fn name(&self) -> &'static str {
+        "Hyprland"
+    }
+
+    fn is_available(&self) -> bool {
+        socket_path().is_some()
+    }
+
+    fn active_window(&self) -> Option<AdapterWindow> {
+        parse_active_window(&request("j/activewindow").ok()?)
+    }
+
+    fn activate(&self, window: &AdapterWindow) -> Result<(), String> {
+        let reply = request(&format!("dispatch focuswindow address:{}", window.id))?;
+        match reply.trim() {
+            "ok" => Ok(()),
+            other => Err(format!("focuswindow failed: {}", other)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_active_window() {
+        let reply = r#"{"address": "0x55d1c2a0", "mapped": true, "class": "kitty", "title": "~"}"#;
+        assert_eq!(
+            parse_active_window(reply),
+            Some(AdapterWindow {
+                id: "0x55d1c2a0".to_string(),
+                app_id: "kitty".to_string(),
+            })
+        );
+        assert_eq!(parse_active_window("{}"), None);
+        assert_eq!(parse_active_window("unknown request"), None);
+
+        let reply = r#"{"address": "0x55d1c2a0", "at": [1930, 50], "size": [800, 600]}"#;
+        assert_eq!(window_center(reply), Some((2330, 350)));
+        assert_eq!(window_center("{}"), None);
+    }
+}
@@ -0,0 +1,228 @@
+//! Localization
+//! Strings the backend sends to the windows (item ages, manual shortcut
+//! instructions, some error messages) are looked up by key in the message
+//! catalogs under `locales/`, which are built into the binary. The catalogs
+//! use a small subset of Fluent: `key = value`, values continued on indented
+//! lines, `#` comments and `{ $name }` variables. A key a catalog leaves out
+//! falls back to English. The language is a user setting and can be switched
+//! while the app runs; "system" follows the session locale.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::OnceLock;
+
+/// Languages there is a catalog for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Language {
+    English,
+    Portuguese,
+    Spanish,
+    German,
+    French,
+}
+
+impl Language {
+    pub const ALL: [Language; 5] = [
+        Self::English,
+        Self::Portuguese,
+        Self::Spanish,
+        Self::German,
+        Self::French,
+    ];
+
+    /// ISO 639-1 code, as saved in the settings
+    pub fn code(self) -> &'static str {
+        match self {
+            Self::English => "en",
+            Self::Portuguese => "pt",
+            Self::Spanish => "es",
+            Self::German => "de",
+            Self::French => "fr",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|language| language.code() == code)
+    }
+
+    /// From a POSIX or BCP 47 locale such as "pt_BR.UTF-8" or "de-AT",
+    /// English for languages without a catalog
+    pub fn from_locale(locale: &str) -> Self {
+        let language = locale
+            .split(['_', '-', '.', '@'])
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+        Self::from_code(&language).unwrap_or(Self::English)
+    }
+
+    /// The session's language (LC_ALL, LC_MESSAGES, LANG)
+    pub fn system() -> Self {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|name| std::env::var(name).ok())
+            .find(|locale| !locale.is_empty())
+            .map(|locale| Self::from_locale(&locale))
+            .unwrap_or(Self::English)
+    }
+
+    fn source(self) -> &'static str {
+        match self {
+            Self::English => include_str!("../locales/en.ftl"),
+            Self::Portuguese => include_str!("../locales/pt.ftl"),
+            Self::Spanish => include_str!("../locales/es.ftl"),
+            Self::German => include_str!("../locales/de.ftl"),
+            Self::French => include_str!("../locales/fr.ftl"),
+        }
+    }
+
+    fn index(self) -> u8 {
+        self as u8
+    }
+}
+
+/// Index of the language messages are currently looked up in
+static CURRENT: AtomicU8 = AtomicU8::new(0);
+
+type Catalog = HashMap<String, String>;
+
+/// Switches the language of every message looked up from now on
+pub fn set_language(language: Language) {
+    CURRENT.store(language.index(), Ordering::Relaxed);
+}
+
+pub fn current() -> Language {
+    let index = CURRENT.load(Ordering::Relaxed) as usize;
+    Language::ALL
+        .get(index)
+        .copied()
+        .unwrap_or(Language::English)
+}
+
+/// Messages of a catalog by key. A value that starts on the line after its
+/// key is made of the indented lines that follow, with the common indent
+/// taken off.
+pub fn parse_catalog(source: &str) -> Catalog {
+    let mut catalog = Catalog::new();
+    let mut lines = source.lines().peekable();
+    while let Some(line) = lines.next() {
+        if line.starts_with([' ', '#']) || line.trim().is_empty() {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+
+        let mut value_lines = Vec::new();
+        if !value.trim().is_empty() {
+            value_lines.push(value.trim().to_string());
+        }
+        let mut continuation = Vec::new();
+        while let Some(next) = lines.peek() {
+            if next.starts_with(' ') {
+                continuation.push(*next);
+            } else if next.trim().is_empty() {
+                continuation.push("");
+            } else {
+                break;
+            }
+            lines.next();
+        }
+        // Blank lines after the last indented one belong to no message
+        while continuation
+            .last()
+            .is_some_and(|line| line.trim().is_empty())
+        {
+            continuation.pop();
+        }
+        let indent = continuation
+            .iter()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| line.len() - line.trim_start().len())
+            .min()
+            .unwrap_or(0);
+        value_lines.extend(continuation.iter().map(|line| {
+            line.get(indent..)
+                .unwrap_or_default()
+                .trim_end()
+                .to_string()
+        }));
+        catalog.insert(key.trim().to_string(), value_lines.join("\n"));
+    }
+    catalog
+}
+
+fn catalog(language: Language) -> &'static Catalog {
+    static CATALOGS: OnceLock<HashMap<Language, Catalog>> = OnceLock::new();
+    &CATALOGS.get_or_init(|| {
+        Language::ALL
+            .into_iter()
+            .map(|language| (language, parse_catalog(language.source())))
+            .collect()
+    })[&language]
+}
+
+/// Replaces each `{ $name }` with its value. Other braces are kept as they
+/// are, so shell and config snippets don't need escaping.
+fn substitute(pattern: &str, args: &[(&str, String)]) -> String {
+    let mut out = pattern.to_string();
+    for (name, value) in args {
+        out = out.replace(&format!("{{ ${} }}", name), value);
+    }
+    out
+}
+
+/// The message for `key` in `language`, in English when that catalog has
+/// none, or the key itself when no catalog does
+pub fn message(language: Language, key: &str, args: &[(&str, String)]) -> String {
+    let pattern = catalog(language)
+        .get(key)
+        .or_else(|| catalog(Language::English).get(key));
+    match pattern {
+        Some(pattern) => substitute(pattern, args),
+        None => key.to_string(),
+    }
+}
+
+/// The message for `key` in the current language
+pub fn tr(key: &str) -> String {
+    message(current(), key, &[])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_catalogs_and_fallback() {
+        let parsed = parse_catalog(
+            "# comment\none = Single line\nmany =\n    First\n\n      Indented { braces }\n\nnext = { $count } left\n",
+        );
+        assert_eq!(parsed["one"], "Single line");
+        assert_eq!(parsed["many"], "First\n\n  Indented { braces }");
+        assert_eq!(
+            substitute(&parsed["next"], &[("count", "3".to_string())]),
+            "3 left"
+        );
+
+        // Every catalog parses and has a name; English has every key
+        let english = catalog_keys(Language::English);
+        for language in Language::ALL {
+            assert!(catalog(language).contains_key("language-name"));
+            for key in catalog_keys(language) {
+                assert!(english.contains(&key), "{} in {:?}", key, language);
+            }
+        }
+        assert!(message(Language::German, "shortcut-instructions-sway", &[])
+            .starts_with("**Sway Configuration:**"));
+        assert_eq!(message(Language::French, "no-such-key", &[]), "no-such-key");
+        assert_eq!(Language::from_locale("pt_BR.UTF-8"), Language::Portuguese);
+        assert_eq!(Language::from_locale("ja_JP"), Language::English);
+    }
+
+    fn catalog_keys(language: Language) -> Vec<String> {
+        catalog(language).keys().cloned().collect()
+    }
+}
@@ -0,0 +1,251 @@
+//! Icon Resolver Module
+//! Maps a source application identifier (X11 WM_CLASS or Wayland app_id) to its
+//! icon through the desktop entry and the XDG icon theme, and hands it to the
+//! frontend as a data URL. Lookups are cached for the lifetime of the process.
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Icon sizes to try, in order of preference for chip/badge rendering
+const ICON_SIZES: &[&str] = &[
+    "48x48", "64x64", "scalable", "32x32", "128x128", "256x256", "24x24",
+];
+const ICON_EXTENSIONS: &[&str] = &["png", "svg"];
+const FALLBACK_THEME: &str = "hicolor";
+
+/// app_id -> data URL (None is cached too, so misses are not retried)
+static ICON_CACHE: Lazy<Mutex<HashMap<String, Option<String>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Fields of a .desktop file relevant for icon lookup
+#[derive(Debug, Default, PartialEq)]
+struct DesktopEntry {
+    icon: Option<String>,
+    startup_wm_class: Option<String>,
+}
+
+impl DesktopEntry {
+    fn parse(content: &str) -> Self {
+        let mut entry = Self::default();
+        let mut in_main_group = false;
+
+        for line in content.lines() {
+            let line = line.trim();
+            if line.starts_with('[') {
+                in_main_group = line == "[Desktop Entry]";
+                continue;
+            }
+            if !in_main_group {
+                continue;
+            }
+            if let Some((key, value)) = line.split_once('=') {
+                match key.trim() {
+                    "Icon" => entry.icon = Some(value.trim().to_string()),
+                    "StartupWMClass" => entry.startup_wm_class = Some(value.trim().to_string()),
+                    _ => {}
+                }
+            }
+        }
+        entry
+    }
+}
+
+/// $XDG_DATA_HOME followed by $XDG_DATA_DIRS
+fn data_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    if let Some(data_home) = dirs::data_dir() {
+        dirs.push(data_home);
+    }
+    let system = env::var("XDG_DATA_DIRS")
+        .ok()
+        .filter(|v| !v.is_empty())
+        .unwrap_or_else(|| "/usr/local/share:/usr/share".to_string());
+    dirs.extend(system.split(':').map(PathBuf::from));
+    dirs
+}
+
+/// Finds the desktop entry for an app id, by file name or StartupWMClass
+fn find_desktop_entry(app_id: &str) -> Option<DesktopEntry> {
+    let app_dirs: Vec<PathBuf> = data_dirs()
+        .into_iter()
+        .map(|d| d.join("applications"))
+        .filter(|d| d.is_dir())
+        .collect();
+
+    // Fast path: file named after the app id
+    for dir in &app_dirs {
+        for name in [app_id.to_string(), app_id.to_lowercase()] {
+            let path = dir.join(format!("{}.desktop", name));
+            if let Ok(content) = fs::read_to_string(&path) {
+                return Some(DesktopEntry::parse(&content));
+            }
+        }
+    }
+
+    // Slow path: reverse-DNS file names (org.gnome.Nautilus) or a matching StartupWMClass
+    let needle = app_id.to_lowercase();
+    for dir in &app_dirs {
+        let Ok(entries) = fs::read_dir(dir) else {
+            continue;
+        };
+        for file in entries.flatten() {
+            let path = file.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("desktop") {
+                continue;
+            }
+            let Ok(content) = fs::read_to_string(&path) else {
+                continue;
+            };
+            let entry = DesktopEntry::parse(&content);
+            let stem = path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or_default()
+                .to_lowercase();
+            let wm_class_matches = entry
+                .startup_wm_class
+                .as_deref()
+                .is_some_and(|c| c.eq_ignore_ascii_case(app_id));
+            if wm_class_matches || stem.ends_with(&format!(".{}", needle)) {
+                return Some(entry);
+            }
+        }
+    }
+
+    None
+}
+
+/// Name of the user's icon theme, if it can be determined
+fn current_icon_theme() -> Option<String> {
+    let output = std::process::Command::new("gsettings")
+        .args(["get", "org.gnome.desktop.interface", "icon-theme"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let theme = String::from_utf8_lossy(&output.stdout)
+        .trim()
+        .trim_matches('\'')
+        .to_string();
+    (!theme.is_empty()).then_some(theme)
+}
+
+/// Looks an icon name up in the current theme, hicolor, then pixmaps
+fn find_themed_icon(icon_name: &str) -> Option<PathBuf> {
+    let mut base_dirs: Vec<PathBuf> = Vec::new();
+    if let Some(home) = dirs::home_dir() {
+        base_dirs.push(home.join(".icons"));
+    }
+    base_dirs.extend(data_dirs().into_iter().map(|d| d.join("icons")));
+
+    let mut themes = Vec::new();
+    if let Some(theme) = current_icon_theme() {
+        themes.push(theme);
+    }
+    themes.push(FALLBACK_THEME.to_string());
+
+    for theme in &themes {
+        for base in &base_dirs {
+            let theme_dir = base.join(theme);
+            if !theme_dir.is_dir() {
+                continue;
+            }
+            for size in ICON_SIZES {
+                for ext in ICON_EXTENSIONS {
+                    let path = theme_dir
+                        .join(size)
+                        .join("apps")
+                        .join(format!("{}.{}", icon_name, ext));
+                    if path.is_file() {
+                        return Some(path);
+                    }
+                }
+            }
+        }
+    }
+
+    for dir in data_dirs() {
+        for ext in ICON_EXTENSIONS {
+            let path = dir.join("pixmaps").join(format!("{}.{}", icon_name, ext));
+            if path.is_file() {
+                return Some(path);
+            }
+        }
+    }
+
+    None
+}
+
+/// Resolves an app id to an icon file on disk
+pub fn resolve_icon_path(app_id: &str) -> Option<PathBuf> {
+    let icon = find_desktop_entry(app_id)
+        .and_then(|entry| entry.icon)
+        .unwrap_or_else(|| app_id.to_lowercase());
+
+    let as_path = Path::new(&icon);
+    if as_path.is_absolute() {
+        return as_path.is_file().then(|| as_path.to_path_buf());
+    }
+
+    find_themed_icon(&icon)
+}
+
+fn to_data_url(path: &Path) -> Option<String> {
+    let mime = match path.extension().and_then(|e| e.to_str())? {
+        "png" => "image/png",
+        "svg" => "image/svg+xml",
+        _ => return None,
+    };
+    let bytes = fs::read(path).ok()?;
+    Some(format!("data:{};base64,{}", mime, BASE64.encode(bytes)))
+}
+
+/// Returns the icon for a source app as a data URL, or None if it has no icon.
+/// The first lookup of an app scans desktop entries and icon themes, so it
+/// runs on the blocking pool.
+#[tauri::command]
+pub async fn get_app_icon(app_id: String) -> Option<String> {
+    tokio::task::spawn_blocking(move || app_icon(&app_id))
+        .await
+        .ok()
+        .flatten()
+}
+
+fn app_icon(app_id: &str) -> Option<String> {
+    let key = app_id.trim().to_string();
+    if key.is_empty() {
+        return None;
+    }
+
+    if let Some(cached) = ICON_CACHE.lock().get(&key) {
+        return cached.clone();
+    }
+
+    let url = resolve_icon_path(&key).and_then(|path| to_data_url(&path));
+    ICON_CACHE.lock().insert(key, url.clone());
+    url
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_desktop_entry_reads_main_group_only() {
+        let content = "[Desktop Entry]\nName=Code\nIcon=vscode\nStartupWMClass=Code\n\n[Desktop Action new-window]\nIcon=other\n";
+        let entry = DesktopEntry::parse(content);
+        assert_eq!(entry.icon.as_deref(), Some("vscode"));
+        assert_eq!(entry.startup_wm_class.as_deref(), Some("Code"));
+    }
+
+    #[test]
+    fn test_empty_app_id_has_no_icon() {
+        assert_eq!(app_icon("  "), None);
+    }
+}
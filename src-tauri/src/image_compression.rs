@@ -0,0 +1,211 @@
+//! Image Compression
+//! Screenshots are captured as PNG, which keeps a few large ones from
+//! weighing on memory and the history file. When turned on, stored images
+//! past the most recent few are re-encoded to lossless WebP, or to JPEG at
+//! the configured quality, and kept that way. It is off by default, so no
+//! image changes without the user asking. Pasting, thumbnails and the git
+//! mirror read any of the three formats.
+
+use crate::clipboard_manager::{ClipboardContent, ClipboardItem};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::webp::WebPEncoder;
+use image::{DynamicImage, ImageFormat};
+use serde::{Deserialize, Serialize};
+
+/// Start of the base64 of every PNG file, its signature
+const PNG_BASE64_PREFIX: &str = "iVBORw0KGgo";
+
+const DEFAULT_JPEG_QUALITY: u8 = 85;
+const DEFAULT_KEEP_ORIGINALS: usize = 5;
+
+/// How images are kept once they are no longer among the most recent
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StoredImageFormat {
+    /// As captured; nothing is re-encoded
+    Png,
+    /// Lossless, usually a third to half the size of the PNG
+    WebP,
+    /// Lossy at the configured quality. Images with transparency use WebP
+    /// instead, as JPEG has none.
+    Jpeg,
+}
+
+impl StoredImageFormat {
+    pub const ALL: [StoredImageFormat; 3] = [Self::Png, Self::WebP, Self::Jpeg];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Png => "png",
+            Self::WebP => "webp",
+            Self::Jpeg => "jpeg",
+        }
+    }
+
+    pub fn from_setting(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|format| format.as_str() == value)
+    }
+}
+
+/// The image compression section of the user settings
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ImageCompressionSettings {
+    /// "png" (off), "webp" or "jpeg"
+    #[serde(default = "default_format")]
+    pub format: String,
+    /// 1 to 100, for "jpeg"
+    #[serde(default = "default_jpeg_quality")]
+    pub jpeg_quality: u8,
+    /// How many of the most recent images keep the bytes they were copied with
+    #[serde(default = "default_keep_originals")]
+    pub keep_originals: usize,
+}
+
+fn default_format() -> String {
+    StoredImageFormat::Png.as_str().to_string()
+}
+
+fn default_jpeg_quality() -> u8 {
+    DEFAULT_JPEG_QUALITY
+}
+
+fn default_keep_originals() -> usize {
+    DEFAULT_KEEP_ORIGINALS
+}
+
+impl Default for ImageCompressionSettings {
+    fn default() -> Self {
+        Self {
+            format: default_format(),
+            jpeg_quality: default_jpeg_quality(),
+            keep_originals: default_keep_originals(),
+        }
+    }
+}
+
+impl ImageCompressionSettings {
+    pub fn format(&self) -> StoredImageFormat {
+        StoredImageFormat::from_setting(&self.format).unwrap_or(StoredImageFormat::Png)
+    }
+
+    /// Falls back to the default for unknown formats and keeps the quality
+    /// in range
+    pub fn validate(&mut self) {
+        if StoredImageFormat::from_setting(&self.format).is_none() {
+            self.format = default_format();
+        }
+        self.jpeg_quality = self.jpeg_quality.clamp(1, 100);
+    }
+}
+
+/// Extension for image bytes in any of the stored formats
+pub fn file_extension(bytes: &[u8]) -> &'static str {
+    match image::guess_format(bytes) {
+        Ok(ImageFormat::WebP) => "webp",
+        Ok(ImageFormat::Jpeg) => "jpg",
+        _ => "png",
+    }
+}
+
+/// Images in `history` still stored as captured, past the `keep_originals`
+/// most recent ones, with their ids
+pub fn candidates(history: &[ClipboardItem], keep_originals: usize) -> Vec<(String, String)> {
+    let mut images: Vec<&ClipboardItem> = history
+        .iter()
+        .filter(|item| matches!(item.content, ClipboardContent::Image { .. }))
+        .collect();
+    // Pinned items sit on top of history whatever their age
+    images.sort_by_key(|item| std::cmp::Reverse(item.timestamp));
+    images
+        .into_iter()
+        .skip(keep_originals)
+        .filter_map(|item| match &item.content {
+            ClipboardContent::Image { base64, .. } if base64.starts_with(PNG_BASE64_PREFIX) => {
+                Some((item.id.clone(), base64.clone()))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Re-encodes a base64 PNG into `settings`' format. None when that would not
+/// make it smaller, or the image does not decode.
+pub fn compress(base64: &str, settings: &ImageCompressionSettings) -> Option<String> {
+    let format = settings.format();
+    if format == StoredImageFormat::Png {
+        return None;
+    }
+    let bytes = BASE64.decode(base64).ok()?;
+    let image = image::load_from_memory(&bytes).ok()?;
+
+    let mut encoded = Vec::new();
+    let result = if format == StoredImageFormat::Jpeg && !has_transparency(&image) {
+        let encoder = JpegEncoder::new_with_quality(&mut encoded, settings.jpeg_quality);
+        DynamicImage::ImageRgb8(image.to_rgb8()).write_with_encoder(encoder)
+    } else {
+        image.write_with_encoder(WebPEncoder::new_lossless(&mut encoded))
+    };
+    if let Err(e) = result {
+        eprintln!("[ImageCompression] Failed to encode image: {}", e);
+        return None;
+    }
+    (encoded.len() < bytes.len()).then(|| BASE64.encode(encoded))
+}
+
+fn has_transparency(image: &DynamicImage) -> bool {
+    image.color().has_alpha() && image.to_rgba8().pixels().any(|pixel| pixel[3] < u8::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn png_base64(image: &DynamicImage) -> String {
+        let mut bytes = Cursor::new(Vec::new());
+        image.write_to(&mut bytes, ImageFormat::Png).unwrap();
+        BASE64.encode(bytes.get_ref())
+    }
+
+    #[test]
+    fn test_compress_images() {
+        // A gradient, which PNG stores poorly
+        let opaque = DynamicImage::ImageRgba8(image::RgbaImage::from_fn(64, 64, |x, y| {
+            image::Rgba([(x * 4) as u8, (y * 4) as u8, ((x + y) * 2) as u8, 255])
+        }));
+        let mut translucent = opaque.to_rgba8();
+        translucent.put_pixel(0, 0, image::Rgba([0, 0, 0, 0]));
+        let translucent = DynamicImage::ImageRgba8(translucent);
+
+        let decoded_format = |base64: &str| {
+            let bytes = BASE64.decode(base64).unwrap();
+            file_extension(&bytes)
+        };
+        let settings = |format: StoredImageFormat| ImageCompressionSettings {
+            format: format.as_str().to_string(),
+            ..Default::default()
+        };
+
+        let png = png_base64(&opaque);
+        assert!(png.starts_with(PNG_BASE64_PREFIX));
+        assert_eq!(compress(&png, &settings(StoredImageFormat::Png)), None);
+        assert_eq!(compress(&png, &ImageCompressionSettings::default()), None);
+
+        let webp = compress(&png, &settings(StoredImageFormat::WebP)).unwrap();
+        assert_eq!(decoded_format(&webp), "webp");
+        let bytes = BASE64.decode(&webp).unwrap();
+        assert_eq!(
+            image::load_from_memory(&bytes).unwrap().to_rgba8(),
+            opaque.to_rgba8()
+        );
+
+        let jpeg = compress(&png, &settings(StoredImageFormat::Jpeg)).unwrap();
+        assert_eq!(decoded_format(&jpeg), "jpg");
+        // JPEG has no transparency to keep
+        let translucent_png = png_base64(&translucent);
+        let kept = compress(&translucent_png, &settings(StoredImageFormat::Jpeg)).unwrap();
+        assert_eq!(decoded_format(&kept), "webp");
+    }
+}
@@ -1,8 +1,141 @@
 use crate::session;
+#[cfg(target_os = "linux")]
+use crate::session::command_exists;
+use serde::Serialize;
 use std::thread;
 use std::time::Duration;
 
-type PasteStrategy = (&'static str, fn() -> Result<(), String>);
+type PasteStrategy = (&'static str, fn(PasteKeys) -> Result<(), String>);
+
+/// Key combination sent to the target window: the paste itself, or the key
+/// that submits it afterwards (paste-and-submit)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PasteKeys {
+    CtrlV,
+    /// Terminal emulators (Ctrl+V is a literal ^V there)
+    CtrlShiftV,
+    ShiftInsert,
+    Enter,
+    /// Sends in apps where Enter adds a line (e.g. some chat and mail apps)
+    CtrlEnter,
+    ShiftEnter,
+}
+
+/// Values of the `submit_keys` setting
+pub const SUBMIT_KEY_NAMES: &[&str] = &["enter", "ctrl+enter", "shift+enter"];
+
+impl PasteKeys {
+    /// Parses the `terminal_paste_keys` setting; unknown values fall back to Ctrl+Shift+V
+    pub fn from_setting(value: &str) -> Self {
+        match value.to_lowercase().as_str() {
+            "shift+insert" => Self::ShiftInsert,
+            "ctrl+v" => Self::CtrlV,
+            _ => Self::CtrlShiftV,
+        }
+    }
+
+    /// Parses the `submit_keys` setting; unknown values fall back to Enter
+    pub fn submit_from_setting(value: &str) -> Self {
+        match value.to_lowercase().as_str() {
+            "ctrl+enter" => Self::CtrlEnter,
+            "shift+enter" => Self::ShiftEnter,
+            _ => Self::Enter,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::CtrlV => "Ctrl+V",
+            Self::CtrlShiftV => "Ctrl+Shift+V",
+            Self::ShiftInsert => "Shift+Insert",
+            Self::Enter => "Enter",
+            Self::CtrlEnter => "Ctrl+Enter",
+            Self::ShiftEnter => "Shift+Enter",
+        }
+    }
+
+    /// X11 keycodes, modifiers first
+    /// (Return 36, Control_L 37, Shift_L 50, v 55, Insert 118)
+    fn x11_keycodes(self) -> &'static [u8] {
+        match self {
+            Self::CtrlV => &[37, 55],
+            Self::CtrlShiftV => &[37, 50, 55],
+            Self::ShiftInsert => &[50, 118],
+            Self::Enter => &[36],
+            Self::CtrlEnter => &[37, 36],
+            Self::ShiftEnter => &[50, 36],
+        }
+    }
+
+    /// Linux input event codes, modifiers first
+    /// (KEY_ENTER 28, KEY_LEFTCTRL 29, KEY_LEFTSHIFT 42, KEY_V 47, KEY_INSERT 110)
+    fn evdev_codes(self) -> &'static [u16] {
+        match self {
+            Self::CtrlV => &[29, 47],
+            Self::CtrlShiftV => &[29, 42, 47],
+            Self::ShiftInsert => &[42, 110],
+            Self::Enter => &[28],
+            Self::CtrlEnter => &[29, 28],
+            Self::ShiftEnter => &[42, 28],
+        }
+    }
+
+    /// Modifier names and key as understood by xdotool and wtype
+    fn keysyms(self) -> (&'static [&'static str], &'static str) {
+        match self {
+            Self::CtrlV => (&["ctrl"], "v"),
+            Self::CtrlShiftV => (&["ctrl", "shift"], "v"),
+            Self::ShiftInsert => (&["shift"], "Insert"),
+            Self::Enter => (&[], "Return"),
+            Self::CtrlEnter => (&["ctrl"], "Return"),
+            Self::ShiftEnter => (&["shift"], "Return"),
+        }
+    }
+}
+
+/// Whether a WM_CLASS (instance and class names) belongs to one of the
+/// configured terminal emulators. Matching is case-insensitive.
+pub fn is_terminal_class(wm_class: &[String], terminals: &[String]) -> bool {
+    wm_class.iter().any(|name| {
+        terminals
+            .iter()
+            .any(|terminal| terminal.eq_ignore_ascii_case(name))
+    })
+}
+
+/// Paste injection methods by settings name, see `UserSettings::paste_strategies`
+pub const PASTE_STRATEGY_NAMES: &[&str] = &["xdotool", "xtest", "wtype", "ydotool", "uinput"];
+
+/// Every paste strategy, looked up by settings name
+#[cfg(target_os = "linux")]
+const ALL_PASTE_STRATEGIES: &[PasteStrategy] = &[
+    ("xdotool", simulate_paste_xdotool),
+    ("XTest", simulate_paste_xtest),
+    ("wtype", simulate_paste_wtype),
+    ("ydotool", simulate_paste_ydotool),
+    ("uinput", simulate_paste_uinput),
+];
+
+/// The user's strategy order when set, otherwise the session default.
+/// Names match case-insensitively; strategies left out are disabled.
+#[cfg(target_os = "linux")]
+fn order_strategies<T: Copy>(
+    defaults: &[(&'static str, T)],
+    all: &[(&'static str, T)],
+    order: &[String],
+) -> Vec<(&'static str, T)> {
+    if order.is_empty() {
+        return defaults.to_vec();
+    }
+    order
+        .iter()
+        .filter_map(|name| {
+            all.iter()
+                .find(|(strategy, _)| strategy.eq_ignore_ascii_case(name))
+        })
+        .copied()
+        .collect()
+}
 
 /// Delay before starting the paste sequence to ensure window focus is stable
 const PRE_PASTE_DELAY_MS: u64 = 50;
@@ -16,42 +149,234 @@ const UINPUT_DEVICE_SETTLE_MS: u64 = 100;
 /// Delay after paste sequence completes
 const POST_PASTE_DELAY_MS: u64 = 30;
 
+/// Delay between the paste and the submit key, so the target app has
+/// inserted the text before it is sent
+const PRE_SUBMIT_DELAY_MS: u64 = 150;
+
+/// Picks the paste keys for the window that had focus before we opened
 #[cfg(target_os = "linux")]
-pub fn simulate_paste_keystroke() -> Result<(), String> {
-    // Give window manager time to settle focus before sending keystrokes
-    thread::sleep(Duration::from_millis(PRE_PASTE_DELAY_MS));
+fn paste_keys_for_target() -> PasteKeys {
+    let Some(wm_class) = crate::focus_manager::focused_window_class() else {
+        return PasteKeys::CtrlV;
+    };
 
-    eprintln!("[SimulatePaste] Sending Ctrl+V...");
+    let settings = crate::user_settings::UserSettingsManager::new().load();
+    if is_terminal_class(&wm_class, &settings.terminal_wm_classes) {
+        eprintln!("[SimulatePaste] Target {:?} is a terminal", wm_class);
+        PasteKeys::from_setting(&settings.terminal_paste_keys)
+    } else {
+        PasteKeys::CtrlV
+    }
+}
 
+/// The injection methods to try for this session, in order
+#[cfg(target_os = "linux")]
+fn paste_strategies() -> Vec<PasteStrategy> {
     const X11_STRATEGIES: &[PasteStrategy] = &[
         ("xdotool", simulate_paste_xdotool),
         ("XTest", simulate_paste_xtest),
         ("uinput", simulate_paste_uinput),
     ];
 
+    // wtype talks to the compositor directly (virtual-keyboard protocol, sway/Hyprland/wlroots)
+    // ydotoold usually runs as root, so it works without /dev/uinput access
+    const WAYLAND_STRATEGIES: &[PasteStrategy] = &[
+        ("wtype", simulate_paste_wtype),
+        ("ydotool", simulate_paste_ydotool),
+        ("uinput", simulate_paste_uinput),
+    ];
+
     const NON_X11_STRATEGIES: &[PasteStrategy] = &[("uinput", simulate_paste_uinput)];
 
-    let strategies = if session::is_x11() {
+    let defaults = if session::is_x11() {
         X11_STRATEGIES
+    } else if session::is_wayland() {
+        WAYLAND_STRATEGIES
     } else {
         NON_X11_STRATEGIES
     };
+    let order = crate::user_settings::UserSettingsManager::new()
+        .load()
+        .paste_strategies;
+    order_strategies(defaults, ALL_PASTE_STRATEGIES, &order)
+}
+
+#[cfg(target_os = "linux")]
+pub fn simulate_paste_keystroke() -> Result<(), String> {
+    // Give window manager time to settle focus before sending keystrokes
+    thread::sleep(Duration::from_millis(PRE_PASTE_DELAY_MS));
+
+    let keys = paste_keys_for_target();
+    eprintln!("[SimulatePaste] Sending {}...", keys.label());
+
+    let strategies = &paste_strategies()[..];
+    if run_paste_strategies(strategies, keys) {
+        return Ok(());
+    }
+
+    // Several apps only honor the legacy binding, so try it before giving up
+    if keys != PasteKeys::ShiftInsert {
+        eprintln!("[SimulatePaste] Falling back to Shift+Insert...");
+        if run_paste_strategies(strategies, PasteKeys::ShiftInsert) {
+            return Ok(());
+        }
+    }
 
+    Err("All paste methods failed".to_string())
+}
+
+/// Sends the submit key after a paste (paste-and-submit)
+#[cfg(target_os = "linux")]
+pub fn simulate_submit_keystroke(keys: PasteKeys) -> Result<(), String> {
+    thread::sleep(Duration::from_millis(PRE_SUBMIT_DELAY_MS));
+    if run_paste_strategies(&paste_strategies(), keys) {
+        Ok(())
+    } else {
+        Err(format!("Could not send {}", keys.label()))
+    }
+}
+
+/// Tries each strategy in order until one sends the keys
+#[cfg(target_os = "linux")]
+fn run_paste_strategies(strategies: &[PasteStrategy], keys: PasteKeys) -> bool {
     for (name, func) in strategies {
-        match func() {
+        let result = func(keys);
+        crate::metrics::record_paste(name, result.is_ok());
+        match result {
             Ok(()) => {
-                eprintln!("[SimulatePaste] Ctrl+V sent via {}", name);
+                eprintln!("[SimulatePaste] {} sent via {}", keys.label(), name);
                 // Small delay after paste to let the target app process it
                 thread::sleep(Duration::from_millis(POST_PASTE_DELAY_MS));
-                return Ok(());
+                return true;
             }
             Err(err) => {
-                eprintln!("[SimulatePaste] {} failed: {}", name, err);
+                eprintln!(
+                    "[SimulatePaste] {} ({}) failed: {}",
+                    name,
+                    keys.label(),
+                    err
+                );
             }
         }
     }
+    false
+}
 
-    Err("All paste methods failed".to_string())
+/// Lists the paste injection methods that look usable in this session,
+/// without sending any keystrokes. Used by startup diagnostics.
+#[cfg(target_os = "linux")]
+pub fn probe_paste_methods() -> Vec<&'static str> {
+    let mut methods = Vec::new();
+
+    if session::is_x11() {
+        if command_exists("xdotool") {
+            methods.push("xdotool");
+        }
+        if x11rb::connect(None).is_ok() {
+            methods.push("XTest");
+        }
+    }
+
+    if session::is_wayland() && command_exists("wtype") {
+        methods.push("wtype");
+    }
+
+    if session::is_wayland() && ydotool_daemon_socket().is_some() {
+        methods.push("ydotool");
+    }
+
+    if std::fs::OpenOptions::new()
+        .write(true)
+        .open("/dev/uinput")
+        .is_ok()
+    {
+        methods.push("uinput");
+    }
+
+    methods
+}
+
+/// ydotool setup as seen by the Setup Wizard
+#[derive(Debug, Clone, Serialize)]
+pub struct YdotoolStatus {
+    pub installed: bool,
+    pub daemon_running: bool,
+    pub socket_path: Option<String>,
+    /// ydotool is not set up but would fix pasting in this session
+    pub recommended: bool,
+    pub suggestion: String,
+}
+
+/// Socket locations used by ydotoold, in the order ydotool itself checks them
+#[cfg(target_os = "linux")]
+fn ydotool_socket_candidates() -> Vec<std::path::PathBuf> {
+    let mut candidates = Vec::new();
+    if let Ok(path) = std::env::var("YDOTOOL_SOCKET") {
+        candidates.push(path.into());
+    }
+    if let Ok(runtime_dir) = std::env::var("XDG_RUNTIME_DIR") {
+        candidates.push(std::path::Path::new(&runtime_dir).join(".ydotool_socket"));
+    }
+    candidates.push("/tmp/.ydotool_socket".into());
+    candidates
+}
+
+/// Path of a ydotoold socket we can connect to
+#[cfg(target_os = "linux")]
+fn ydotool_daemon_socket() -> Option<std::path::PathBuf> {
+    use std::os::unix::net::UnixDatagram;
+
+    ydotool_socket_candidates().into_iter().find(|path| {
+        UnixDatagram::unbound()
+            .and_then(|socket| socket.connect(path))
+            .is_ok()
+    })
+}
+
+/// Reports whether ydotool is installed and its daemon reachable, and
+/// whether setting it up would help on this session
+#[cfg(target_os = "linux")]
+#[tauri::command]
+pub fn get_ydotool_status() -> YdotoolStatus {
+    let installed = command_exists("ydotool");
+    let socket = ydotool_daemon_socket();
+    let daemon_running = socket.is_some();
+
+    let uinput_writable = std::fs::OpenOptions::new()
+        .write(true)
+        .open("/dev/uinput")
+        .is_ok();
+    let recommended = session::is_wayland() && !daemon_running && !uinput_writable;
+
+    let suggestion = if daemon_running {
+        "ydotoold is running and will be used for pasting.".to_string()
+    } else if installed {
+        "ydotool is installed but ydotoold is not running. Start it with 'systemctl --user enable --now ydotool'.".to_string()
+    } else if recommended {
+        "Install ydotool and start ydotoold to paste without access to /dev/uinput.".to_string()
+    } else {
+        "Not needed: another paste method is available.".to_string()
+    };
+
+    YdotoolStatus {
+        installed,
+        daemon_running,
+        socket_path: socket.map(|p| p.to_string_lossy().into_owned()),
+        recommended,
+        suggestion,
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+#[tauri::command]
+pub fn get_ydotool_status() -> YdotoolStatus {
+    YdotoolStatus {
+        installed: false,
+        daemon_running: false,
+        socket_path: None,
+        recommended: false,
+        suggestion: "ydotool is only used on Linux.".to_string(),
+    }
 }
 
 /// Helper for XTest input generation
@@ -69,15 +394,15 @@ fn fake_key<C: x11rb::connection::Connection + x11rb::protocol::xtest::Connectio
     Ok(())
 }
 
-/// Simulate Ctrl+V using X11 XTest extension
+/// Simulate the paste keys using X11 XTest extension
 #[cfg(target_os = "linux")]
-fn simulate_paste_xtest() -> Result<(), String> {
+fn simulate_paste_xtest(keys: PasteKeys) -> Result<(), String> {
     use x11rb::connection::Connection;
     use x11rb::protocol::xtest::ConnectionExt as XtestConnectionExt;
     use x11rb::wrapper::ConnectionExt as WrapperConnectionExt; // Imported for sync()
 
-    const CTRL_L_KEYCODE: u8 = 37;
-    const V_KEYCODE: u8 = 55;
+    const KEY_PRESS: u8 = 2;
+    const KEY_RELEASE: u8 = 3;
 
     let (conn, screen_num) =
         x11rb::connect(None).map_err(|e| format!("X11 connect failed: {}", e))?;
@@ -92,59 +417,62 @@ fn simulate_paste_xtest() -> Result<(), String> {
     conn.sync()
         .map_err(|e| format!("Sync setup failed: {}", e))?;
 
-    // Press Ctrl and wait for it to be registered
-    fake_key(
-        &conn,
-        2,
-        CTRL_L_KEYCODE,
-        root_window,
-        "Failed to press Ctrl",
-    )?;
-    conn.sync()
-        .map_err(|e| format!("Sync after Ctrl press failed: {}", e))?;
-    thread::sleep(Duration::from_millis(KEY_EVENT_DELAY_MS));
+    let keycodes = keys.x11_keycodes();
 
-    // Press V
-    fake_key(&conn, 2, V_KEYCODE, root_window, "Failed to press V")?;
-    conn.sync()
-        .map_err(|e| format!("Sync after V press failed: {}", e))?;
-    thread::sleep(Duration::from_millis(KEY_EVENT_DELAY_MS));
+    // Press modifiers then the key, waiting for each to be registered
+    for &keycode in keycodes {
+        fake_key(
+            &conn,
+            KEY_PRESS,
+            keycode,
+            root_window,
+            "Failed to press key",
+        )?;
+        conn.sync()
+            .map_err(|e| format!("Sync after key press failed: {}", e))?;
+        thread::sleep(Duration::from_millis(KEY_EVENT_DELAY_MS));
+    }
 
-    // Release V
-    fake_key(&conn, 3, V_KEYCODE, root_window, "Failed to release V")?;
-    conn.sync()
-        .map_err(|e| format!("Sync after V release failed: {}", e))?;
-    thread::sleep(Duration::from_millis(KEY_EVENT_DELAY_MS));
-
-    // Release Ctrl
-    fake_key(
-        &conn,
-        3,
-        CTRL_L_KEYCODE,
-        root_window,
-        "Failed to release Ctrl",
-    )?;
+    // Release in reverse order
+    for &keycode in keycodes.iter().rev() {
+        fake_key(
+            &conn,
+            KEY_RELEASE,
+            keycode,
+            root_window,
+            "Failed to release key",
+        )?;
+        conn.sync()
+            .map_err(|e| format!("Sync after key release failed: {}", e))?;
+        thread::sleep(Duration::from_millis(KEY_EVENT_DELAY_MS));
+    }
 
-    conn.sync()
-        .map_err(|e| format!("Final sync failed: {}", e))?;
     Ok(())
 }
 
-/// Simulate Ctrl+V using xdotool
+/// Simulate the paste keys using xdotool
 #[cfg(target_os = "linux")]
-fn simulate_paste_xdotool() -> Result<(), String> {
-    // Send Ctrl+V to the currently focused window without specifying a target
+fn simulate_paste_xdotool(keys: PasteKeys) -> Result<(), String> {
+    let (modifiers, key) = keys.keysyms();
+    let combo = modifiers
+        .iter()
+        .chain(std::iter::once(&key))
+        .copied()
+        .collect::<Vec<_>>()
+        .join("+");
+
+    // Send the keys to the currently focused window without specifying a target
     // Using --delay ensures proper timing between key events
     let output = std::process::Command::new("xdotool")
         .args(["key", "--delay"])
         .arg(KEY_EVENT_DELAY_MS.to_string())
         .arg("--clearmodifiers")
-        .arg("ctrl+v")
+        .arg(&combo)
         .output()
         .map_err(|e| format!("Failed to run xdotool key: {}", e))?;
 
     if output.status.success() {
-        eprintln!("[SimulatePaste] xdotool sent ctrl+v to focused window");
+        eprintln!("[SimulatePaste] xdotool sent {} to focused window", combo);
         Ok(())
     } else {
         let stderr = String::from_utf8_lossy(&output.stderr);
@@ -152,30 +480,77 @@ fn simulate_paste_xdotool() -> Result<(), String> {
     }
 }
 
+/// Simulate the paste keys using wtype (Wayland virtual-keyboard protocol)
 #[cfg(target_os = "linux")]
-fn simulate_paste_uinput() -> Result<(), String> {
-    use std::fs::OpenOptions;
-    use std::io::Write;
-    use std::os::unix::io::AsRawFd;
+fn simulate_paste_wtype(keys: PasteKeys) -> Result<(), String> {
+    if !command_exists("wtype") {
+        return Err("wtype is not installed".to_string());
+    }
 
-    const EV_SYN: u16 = 0x00;
-    const EV_KEY: u16 = 0x01;
-    const SYN_REPORT: u16 = 0x00;
-    const KEY_LEFTCTRL: u16 = 29;
-    const KEY_V: u16 = 47;
+    let (modifiers, key) = keys.keysyms();
+    let mut command = std::process::Command::new("wtype");
+    command.arg("-d").arg(KEY_EVENT_DELAY_MS.to_string());
+    for modifier in modifiers {
+        command.args(["-M", modifier]);
+    }
+    command.args(["-k", key]);
+    for modifier in modifiers.iter().rev() {
+        command.args(["-m", modifier]);
+    }
+
+    let output = command
+        .output()
+        .map_err(|e| format!("Failed to run wtype: {}", e))?;
 
-    fn make_event(type_: u16, code: u16, value: i32) -> [u8; 24] {
-        let mut event = [0u8; 24];
-        event[16..18].copy_from_slice(&type_.to_ne_bytes());
-        event[18..20].copy_from_slice(&code.to_ne_bytes());
-        event[20..24].copy_from_slice(&value.to_ne_bytes());
-        event
+    if output.status.success() {
+        Ok(())
+    } else {
+        // GNOME and KDE do not implement the virtual-keyboard protocol
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        Err(format!("wtype failed: {}", stderr.trim()))
     }
+}
 
-    let mut uinput = OpenOptions::new()
-        .write(true)
-        .open("/dev/uinput")
-        .map_err(|e| format!("Failed to open /dev/uinput: {}", e))?;
+/// Simulate the paste keys through the ydotoold daemon
+#[cfg(target_os = "linux")]
+fn simulate_paste_ydotool(keys: PasteKeys) -> Result<(), String> {
+    let socket = ydotool_daemon_socket().ok_or("ydotoold is not running")?;
+
+    // ydotool takes input event codes; :1 is press and :0 release
+    let codes = keys.evdev_codes();
+    let events = codes
+        .iter()
+        .map(|code| format!("{}:1", code))
+        .chain(codes.iter().rev().map(|code| format!("{}:0", code)));
+
+    let output = std::process::Command::new("ydotool")
+        .env("YDOTOOL_SOCKET", &socket)
+        .args(["key", "--key-delay"])
+        .arg(KEY_EVENT_DELAY_MS.to_string())
+        .args(events)
+        .output()
+        .map_err(|e| format!("Failed to run ydotool: {}", e))?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        Err(format!("ydotool failed: {}", stderr.trim()))
+    }
+}
+
+/// Temporary virtual keyboard on /dev/uinput that can press the given keys.
+/// The device is destroyed when dropped.
+#[cfg(target_os = "linux")]
+struct UinputKeyboard {
+    device: std::fs::File,
+}
+
+#[cfg(target_os = "linux")]
+impl UinputKeyboard {
+    const EV_SYN: u16 = 0x00;
+    const EV_KEY: u16 = 0x01;
+    const SYN_REPORT: u16 = 0x00;
 
     const UI_SET_EVBIT: libc::c_ulong = 0x40045564;
     const UI_SET_KEYBIT: libc::c_ulong = 0x40045565;
@@ -183,97 +558,563 @@ fn simulate_paste_uinput() -> Result<(), String> {
     const UI_DEV_CREATE: libc::c_ulong = 0x5501;
     const UI_DEV_DESTROY: libc::c_ulong = 0x5502;
 
-    unsafe {
-        if libc::ioctl(uinput.as_raw_fd(), UI_SET_EVBIT, EV_KEY as libc::c_int) < 0 {
-            return Err("Failed to set EV_KEY".to_string());
+    fn create(codes: &[u16]) -> Result<Self, String> {
+        use std::fs::OpenOptions;
+        use std::os::unix::io::AsRawFd;
+
+        let device = OpenOptions::new()
+            .write(true)
+            .open("/dev/uinput")
+            .map_err(|e| format!("Failed to open /dev/uinput: {}", e))?;
+        let fd = device.as_raw_fd();
+
+        unsafe {
+            if libc::ioctl(fd, Self::UI_SET_EVBIT, Self::EV_KEY as libc::c_int) < 0 {
+                return Err("Failed to set EV_KEY".to_string());
+            }
+            for &code in codes {
+                if libc::ioctl(fd, Self::UI_SET_KEYBIT, code as libc::c_int) < 0 {
+                    return Err(format!("Failed to set key bit {}", code));
+                }
+            }
+
+            #[repr(C)]
+            struct UinputSetup {
+                id: [u16; 4],
+                name: [u8; 80],
+                ff_effects_max: u32,
+            }
+
+            let mut setup = UinputSetup {
+                id: [0x03, 0x1234, 0x5678, 0x0001],
+                name: [0; 80],
+                ff_effects_max: 0,
+            };
+            let name = b"emoji-paste-helper";
+            setup.name[..name.len()].copy_from_slice(name);
+
+            if libc::ioctl(fd, Self::UI_DEV_SETUP, &setup) < 0 {
+                return Err("Failed to setup uinput device".to_string());
+            }
+            if libc::ioctl(fd, Self::UI_DEV_CREATE) < 0 {
+                return Err("Failed to create uinput device".to_string());
+            }
         }
-        if libc::ioctl(
-            uinput.as_raw_fd(),
-            UI_SET_KEYBIT,
-            KEY_LEFTCTRL as libc::c_int,
-        ) < 0
-        {
-            return Err("Failed to set KEY_LEFTCTRL".to_string());
+
+        // Wait longer for the virtual device to be recognized by the system
+        // This is critical for some desktop environments (Cinnamon, GNOME)
+        thread::sleep(Duration::from_millis(UINPUT_DEVICE_SETTLE_MS));
+
+        Ok(Self { device })
+    }
+
+    /// Sends one press (1) or release (0) followed by a sync report
+    fn key(&mut self, code: u16, value: i32) -> Result<(), String> {
+        use std::io::Write;
+
+        fn make_event(type_: u16, code: u16, value: i32) -> [u8; 24] {
+            let mut event = [0u8; 24];
+            event[16..18].copy_from_slice(&type_.to_ne_bytes());
+            event[18..20].copy_from_slice(&code.to_ne_bytes());
+            event[20..24].copy_from_slice(&value.to_ne_bytes());
+            event
         }
-        if libc::ioctl(uinput.as_raw_fd(), UI_SET_KEYBIT, KEY_V as libc::c_int) < 0 {
-            return Err("Failed to set KEY_V".to_string());
+
+        self.device
+            .write_all(&make_event(Self::EV_KEY, code, value))
+            .map_err(|e| e.to_string())?;
+        self.device
+            .write_all(&make_event(Self::EV_SYN, Self::SYN_REPORT, 0))
+            .map_err(|e| e.to_string())?;
+        self.device.flush().map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl Drop for UinputKeyboard {
+    fn drop(&mut self) {
+        use std::os::unix::io::AsRawFd;
+
+        // Wait for events to be processed before destroying device
+        thread::sleep(Duration::from_millis(KEY_EVENT_DELAY_MS));
+        unsafe {
+            libc::ioctl(self.device.as_raw_fd(), Self::UI_DEV_DESTROY);
         }
+    }
+}
 
-        #[repr(C)]
-        struct UinputSetup {
-            id: [u16; 4],
-            name: [u8; 80],
-            ff_effects_max: u32,
+#[cfg(target_os = "linux")]
+fn simulate_paste_uinput(keys: PasteKeys) -> Result<(), String> {
+    let codes = keys.evdev_codes();
+    let mut keyboard = UinputKeyboard::create(codes)?;
+
+    // Press modifiers then the key, and release in reverse order
+    let events = codes
+        .iter()
+        .map(|&code| (code, 1))
+        .chain(codes.iter().rev().map(|&code| (code, 0)));
+    for (i, (code, value)) in events.enumerate() {
+        if i > 0 {
+            thread::sleep(Duration::from_millis(KEY_EVENT_DELAY_MS));
         }
+        keyboard.key(code, value)?;
+    }
+    drop(keyboard);
 
-        let mut setup = UinputSetup {
-            id: [0x03, 0x1234, 0x5678, 0x0001],
-            name: [0; 80],
-            ff_effects_max: 0,
-        };
-        let name = b"emoji-paste-helper";
-        setup.name[..name.len()].copy_from_slice(name);
+    // Small delay after device destruction
+    thread::sleep(Duration::from_millis(POST_PASTE_DELAY_MS));
 
-        if libc::ioctl(uinput.as_raw_fd(), UI_DEV_SETUP, &setup) < 0 {
-            return Err("Failed to setup uinput device".to_string());
+    Ok(())
+}
+
+// =============================================================================
+// Type-Out: inject text as individual keystrokes for apps that block paste
+// =============================================================================
+
+type TypeStrategy = (&'static str, fn(&str, Duration) -> Result<(), TypeError>);
+
+/// Why a typing method failed
+#[derive(Debug)]
+enum TypeError {
+    /// Nothing was typed, so the next method can be tried
+    Unavailable(String),
+    /// Some of the text may have been typed; trying another method would
+    /// type it twice
+    Interrupted(String),
+}
+
+impl From<String> for TypeError {
+    fn from(message: String) -> Self {
+        Self::Unavailable(message)
+    }
+}
+
+/// Bounds for the `type_out_delay_ms` setting
+pub const MIN_TYPE_DELAY_MS: u32 = 1;
+pub const MAX_TYPE_DELAY_MS: u32 = 500;
+
+/// Types `text` into the focused window one character at a time, waiting
+/// `delay_ms` between characters. Used where paste is blocked (remote
+/// desktops, some password fields and kiosk apps).
+#[cfg(target_os = "linux")]
+pub fn simulate_typing(text: &str, delay_ms: u32) -> Result<(), String> {
+    // Give window manager time to settle focus before sending keystrokes
+    thread::sleep(Duration::from_millis(PRE_PASTE_DELAY_MS));
+
+    let delay = Duration::from_millis(delay_ms.clamp(MIN_TYPE_DELAY_MS, MAX_TYPE_DELAY_MS).into());
+    eprintln!(
+        "[SimulateTyping] Typing {} characters...",
+        text.chars().count()
+    );
+
+    const X11_STRATEGIES: &[TypeStrategy] = &[
+        ("xdotool", type_text_xdotool),
+        ("XTest", type_text_xtest),
+        ("uinput", type_text_uinput),
+    ];
+
+    const WAYLAND_STRATEGIES: &[TypeStrategy] = &[
+        ("wtype", type_text_wtype),
+        ("ydotool", type_text_ydotool),
+        ("uinput", type_text_uinput),
+    ];
+
+    const NON_X11_STRATEGIES: &[TypeStrategy] = &[("uinput", type_text_uinput)];
+
+    let strategies = if session::is_x11() {
+        X11_STRATEGIES
+    } else if session::is_wayland() {
+        WAYLAND_STRATEGIES
+    } else {
+        NON_X11_STRATEGIES
+    };
+
+    for (name, func) in strategies {
+        match func(text, delay) {
+            Ok(()) => {
+                eprintln!("[SimulateTyping] Text typed via {}", name);
+                return Ok(());
+            }
+            Err(TypeError::Unavailable(err)) => {
+                eprintln!("[SimulateTyping] {} failed: {}", name, err);
+            }
+            Err(TypeError::Interrupted(err)) => {
+                return Err(format!("Typing via {} stopped: {}", name, err));
+            }
         }
-        if libc::ioctl(uinput.as_raw_fd(), UI_DEV_CREATE) < 0 {
-            return Err("Failed to create uinput device".to_string());
+    }
+
+    Err("All typing methods failed".to_string())
+}
+
+/// Runs a typing tool that reads the text from stdin, so it never shows up
+/// in the process list (the text may be a password)
+#[cfg(target_os = "linux")]
+fn type_with_tool(mut command: std::process::Command, text: &str) -> Result<(), TypeError> {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let program = command.get_program().to_string_lossy().into_owned();
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| TypeError::Unavailable(format!("Failed to run {}: {}", program, e)))?;
+
+    // From here on the tool may have typed some of the text
+    let written = child
+        .stdin
+        .take()
+        .map(|mut stdin| stdin.write_all(text.as_bytes()));
+    let output = child
+        .wait_with_output()
+        .map_err(|e| TypeError::Interrupted(format!("{} failed: {}", program, e)))?;
+    if let Some(Err(e)) = written {
+        return Err(TypeError::Interrupted(format!(
+            "Failed to send the text to {}: {}",
+            program, e
+        )));
+    }
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(TypeError::Interrupted(format!(
+            "{} failed: {}",
+            program,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )))
+    }
+}
+
+/// Type text using xdotool, which remaps keys for characters outside the layout
+#[cfg(target_os = "linux")]
+fn type_text_xdotool(text: &str, delay: Duration) -> Result<(), TypeError> {
+    let mut command = std::process::Command::new("xdotool");
+    command
+        .args(["type", "--delay"])
+        .arg(delay.as_millis().to_string())
+        .args(["--clearmodifiers", "--file", "-"]);
+    type_with_tool(command, text)
+}
+
+/// Type text using wtype (Wayland virtual-keyboard protocol)
+#[cfg(target_os = "linux")]
+fn type_text_wtype(text: &str, delay: Duration) -> Result<(), TypeError> {
+    if !command_exists("wtype") {
+        return Err(TypeError::Unavailable("wtype is not installed".to_string()));
+    }
+
+    let mut command = std::process::Command::new("wtype");
+    command
+        .arg("-d")
+        .arg(delay.as_millis().to_string())
+        .arg("-");
+    type_with_tool(command, text)
+}
+
+/// Type text through the ydotoold daemon
+#[cfg(target_os = "linux")]
+fn type_text_ydotool(text: &str, delay: Duration) -> Result<(), TypeError> {
+    let socket = ydotool_daemon_socket()
+        .ok_or_else(|| TypeError::Unavailable("ydotoold is not running".to_string()))?;
+
+    let mut command = std::process::Command::new("ydotool");
+    command
+        .env("YDOTOOL_SOCKET", &socket)
+        .args(["type", "--key-delay"])
+        .arg(delay.as_millis().to_string())
+        .args(["--file", "-"]);
+    type_with_tool(command, text)
+}
+
+/// X11 keysym for a character (Latin-1 maps directly, the rest use the
+/// Unicode keysym range)
+fn char_keysym(ch: char) -> u32 {
+    match ch {
+        '\n' => 0xff0d, // Return
+        '\t' => 0xff09, // Tab
+        '\u{20}'..='\u{7e}' | '\u{a0}'..='\u{ff}' => ch as u32,
+        _ => 0x0100_0000 + ch as u32,
+    }
+}
+
+/// Type text using XTest. Characters missing from the keyboard layout are
+/// typed by temporarily binding them to an unused keycode.
+#[cfg(target_os = "linux")]
+fn type_text_xtest(text: &str, delay: Duration) -> Result<(), TypeError> {
+    use x11rb::connection::Connection;
+    use x11rb::protocol::xproto::ConnectionExt as XprotoConnectionExt;
+    use x11rb::wrapper::ConnectionExt as WrapperConnectionExt;
+
+    const KEY_PRESS: u8 = 2;
+    const KEY_RELEASE: u8 = 3;
+    const XK_SHIFT_L: u32 = 0xffe1;
+
+    let (conn, screen_num) =
+        x11rb::connect(None).map_err(|e| format!("X11 connect failed: {}", e))?;
+    let root_window = conn.setup().roots[screen_num].root;
+    let min_keycode = conn.setup().min_keycode;
+    let max_keycode = conn.setup().max_keycode;
+
+    let mapping = conn
+        .get_keyboard_mapping(min_keycode, max_keycode - min_keycode + 1)
+        .map_err(|e| format!("Keyboard mapping query failed: {}", e))?
+        .reply()
+        .map_err(|e| format!("Keyboard mapping query failed: {}", e))?;
+    let per_keycode = usize::from(mapping.keysyms_per_keycode).max(1);
+    let keysyms_of = |keycode: u8| {
+        let start = usize::from(keycode - min_keycode) * per_keycode;
+        &mapping.keysyms[start..start + per_keycode]
+    };
+
+    // Keycode and shift level (first two levels only) for a keysym
+    let find = |keysym: u32| {
+        (min_keycode..=max_keycode).find_map(|keycode| {
+            keysyms_of(keycode)
+                .iter()
+                .take(2)
+                .position(|&sym| sym == keysym)
+                .map(|level| (keycode, level == 1))
+        })
+    };
+    let shift = find(XK_SHIFT_L).map(|(keycode, _)| keycode);
+    let spare = (min_keycode..=max_keycode)
+        .rev()
+        .find(|&keycode| keysyms_of(keycode).iter().all(|&sym| sym == 0));
+    let mut spare_bound = false;
+
+    let press = |keycode: u8, kind: u8| {
+        fake_key(&conn, kind, keycode, root_window, "Failed to send key")?;
+        conn.sync()
+            .map_err(|e| format!("Sync after key event failed: {}", e))
+    };
+
+    let mut result = Ok(());
+    let mut typed_any = false;
+    for ch in text.chars() {
+        let keysym = char_keysym(ch);
+        let (keycode, needs_shift) = match find(keysym) {
+            Some(found) => found,
+            None => {
+                let Some(spare) = spare else {
+                    result = Err(format!("No free keycode to type {:?}", ch));
+                    break;
+                };
+                // Bind the keysym to both levels so the shift state doesn't matter
+                if let Err(e) = conn
+                    .change_keyboard_mapping(1, spare, 2, &[keysym, keysym])
+                    .map_err(|e| e.to_string())
+                    .and_then(|_| conn.sync().map_err(|e| e.to_string()))
+                {
+                    result = Err(format!("Failed to remap keycode: {}", e));
+                    break;
+                }
+                spare_bound = true;
+                // Clients need a moment to pick up the MappingNotify
+                thread::sleep(delay.max(Duration::from_millis(KEY_EVENT_DELAY_MS)));
+                (spare, false)
+            }
+        };
+
+        let shift = shift.filter(|_| needs_shift);
+        let keys: Vec<u8> = shift.into_iter().chain([keycode]).collect();
+        let sent = keys
+            .iter()
+            .try_for_each(|&keycode| press(keycode, KEY_PRESS))
+            .and_then(|_| {
+                keys.iter()
+                    .rev()
+                    .try_for_each(|&keycode| press(keycode, KEY_RELEASE))
+            });
+        if let Err(e) = sent {
+            result = Err(e);
+            break;
         }
+        typed_any = true;
+        thread::sleep(delay);
     }
 
-    // Wait longer for the virtual device to be recognized by the system
-    // This is critical for some desktop environments (Cinnamon, GNOME)
-    thread::sleep(Duration::from_millis(UINPUT_DEVICE_SETTLE_MS));
-
-    // Press Ctrl
-    uinput
-        .write_all(&make_event(EV_KEY, KEY_LEFTCTRL, 1))
-        .map_err(|e| e.to_string())?;
-    uinput
-        .write_all(&make_event(EV_SYN, SYN_REPORT, 0))
-        .map_err(|e| e.to_string())?;
-    uinput.flush().map_err(|e| e.to_string())?;
-    thread::sleep(Duration::from_millis(KEY_EVENT_DELAY_MS));
-
-    // Press V
-    uinput
-        .write_all(&make_event(EV_KEY, KEY_V, 1))
-        .map_err(|e| e.to_string())?;
-    uinput
-        .write_all(&make_event(EV_SYN, SYN_REPORT, 0))
-        .map_err(|e| e.to_string())?;
-    uinput.flush().map_err(|e| e.to_string())?;
-    thread::sleep(Duration::from_millis(KEY_EVENT_DELAY_MS));
-
-    // Release V
-    uinput
-        .write_all(&make_event(EV_KEY, KEY_V, 0))
-        .map_err(|e| e.to_string())?;
-    uinput
-        .write_all(&make_event(EV_SYN, SYN_REPORT, 0))
-        .map_err(|e| e.to_string())?;
-    uinput.flush().map_err(|e| e.to_string())?;
-    thread::sleep(Duration::from_millis(KEY_EVENT_DELAY_MS));
-
-    // Release Ctrl
-    uinput
-        .write_all(&make_event(EV_KEY, KEY_LEFTCTRL, 0))
-        .map_err(|e| e.to_string())?;
-    uinput
-        .write_all(&make_event(EV_SYN, SYN_REPORT, 0))
-        .map_err(|e| e.to_string())?;
-    uinput.flush().map_err(|e| e.to_string())?;
-
-    // Wait for events to be processed before destroying device
-    thread::sleep(Duration::from_millis(KEY_EVENT_DELAY_MS));
-
-    unsafe {
-        libc::ioctl(uinput.as_raw_fd(), UI_DEV_DESTROY);
+    // Give the spare keycode back its empty mapping
+    if let (Some(spare), true) = (spare, spare_bound) {
+        let _ = conn.change_keyboard_mapping(1, spare, 2, &[0, 0]);
+        let _ = conn.sync();
     }
 
-    // Small delay after device destruction
-    thread::sleep(Duration::from_millis(POST_PASTE_DELAY_MS));
+    result.map_err(|e| {
+        if typed_any {
+            TypeError::Interrupted(e)
+        } else {
+            TypeError::Unavailable(e)
+        }
+    })
+}
+
+/// Linux key code and whether Shift is needed for a character on a US
+/// layout. uinput sends physical keys, so other layouts and non-ASCII text
+/// cannot be typed this way.
+fn us_layout_key(ch: char) -> Option<(u16, bool)> {
+    const LETTERS: [u16; 26] = [
+        30, 48, 46, 32, 18, 33, 34, 35, 23, 36, 37, 38, 50, 49, 24, 25, 16, 19, 31, 20, 22, 47, 17,
+        45, 21, 44,
+    ];
+    const DIGITS: [u16; 10] = [11, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+    const SHIFTED_DIGITS: &str = ")!@#$%^&*(";
+
+    let key = match ch {
+        'a'..='z' => (LETTERS[(ch as u8 - b'a') as usize], false),
+        'A'..='Z' => (LETTERS[(ch as u8 - b'A') as usize], true),
+        '0'..='9' => (DIGITS[(ch as u8 - b'0') as usize], false),
+        ' ' => (57, false),
+        '\n' => (28, false),
+        '\t' => (15, false),
+        '-' => (12, false),
+        '_' => (12, true),
+        '=' => (13, false),
+        '+' => (13, true),
+        '[' => (26, false),
+        '{' => (26, true),
+        ']' => (27, false),
+        '}' => (27, true),
+        ';' => (39, false),
+        ':' => (39, true),
+        '\'' => (40, false),
+        '"' => (40, true),
+        '`' => (41, false),
+        '~' => (41, true),
+        '\\' => (43, false),
+        '|' => (43, true),
+        ',' => (51, false),
+        '<' => (51, true),
+        '.' => (52, false),
+        '>' => (52, true),
+        '/' => (53, false),
+        '?' => (53, true),
+        _ => {
+            let digit = SHIFTED_DIGITS.find(ch)?;
+            (DIGITS[digit], true)
+        }
+    };
+    Some(key)
+}
+
+/// Type text through a temporary uinput keyboard (ASCII on a US layout only)
+#[cfg(target_os = "linux")]
+fn type_text_uinput(text: &str, delay: Duration) -> Result<(), TypeError> {
+    const KEY_LEFTSHIFT: u16 = 42;
+
+    let keys = text
+        .chars()
+        .map(|ch| us_layout_key(ch).ok_or_else(|| format!("uinput cannot type {:?}", ch)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut codes: Vec<u16> = keys.iter().map(|&(code, _)| code).collect();
+    codes.push(KEY_LEFTSHIFT);
+    codes.sort_unstable();
+    codes.dedup();
+    let mut keyboard = UinputKeyboard::create(&codes)?;
+
+    let mut press = |code: u16, shift: bool| -> Result<(), String> {
+        if shift {
+            keyboard.key(KEY_LEFTSHIFT, 1)?;
+        }
+        keyboard.key(code, 1)?;
+        keyboard.key(code, 0)?;
+        if shift {
+            keyboard.key(KEY_LEFTSHIFT, 0)?;
+        }
+        Ok(())
+    };
+    for (i, (code, shift)) in keys.into_iter().enumerate() {
+        press(code, shift).map_err(|e| match i {
+            0 => TypeError::Unavailable(e),
+            _ => TypeError::Interrupted(e),
+        })?;
+        thread::sleep(delay);
+    }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_order_strategies() {
+        let all = [("xdotool", 1), ("XTest", 2), ("uinput", 3)];
+        let defaults = &all[1..];
+        let order = |names: &[&str]| names.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(order_strategies(defaults, &all, &[]), defaults);
+        assert_eq!(
+            order_strategies(defaults, &all, &order(&["uinput", "xtest", "bogus"])),
+            [("uinput", 3), ("XTest", 2)]
+        );
+    }
+
+    #[test]
+    fn test_terminal_class_matches_instance_or_class() {
+        let terminals = vec!["Alacritty".to_string(), "gnome-terminal-server".to_string()];
+        let alacritty = vec!["Alacritty".to_string(), "Alacritty".to_string()];
+        let gnome = vec![
+            "gnome-terminal-server".to_string(),
+            "Gnome-terminal".to_string(),
+        ];
+        let firefox = vec!["Navigator".to_string(), "firefox".to_string()];
+
+        assert!(is_terminal_class(&alacritty, &terminals));
+        assert!(is_terminal_class(&gnome, &terminals));
+        assert!(!is_terminal_class(&firefox, &terminals));
+        assert!(is_terminal_class(&["alacritty".to_string()], &terminals));
+    }
+
+    #[test]
+    fn test_char_keysym() {
+        assert_eq!(char_keysym('a'), 0x61);
+        assert_eq!(char_keysym('é'), 0xe9);
+        assert_eq!(char_keysym('\n'), 0xff0d);
+        assert_eq!(char_keysym('€'), 0x0100_20ac);
+    }
+
+    #[test]
+    fn test_us_layout_key() {
+        assert_eq!(us_layout_key('a'), Some((30, false)));
+        assert_eq!(us_layout_key('Z'), Some((44, true)));
+        assert_eq!(us_layout_key('0'), Some((11, false)));
+        assert_eq!(us_layout_key('!'), Some((2, true)));
+        assert_eq!(us_layout_key('('), Some((10, true)));
+        assert_eq!(us_layout_key('é'), None);
+    }
+
+    #[test]
+    fn test_paste_keys_from_setting() {
+        assert_eq!(
+            PasteKeys::from_setting("shift+insert"),
+            PasteKeys::ShiftInsert
+        );
+        assert_eq!(
+            PasteKeys::from_setting("Shift+Insert"),
+            PasteKeys::ShiftInsert
+        );
+        assert_eq!(
+            PasteKeys::from_setting("ctrl+shift+v"),
+            PasteKeys::CtrlShiftV
+        );
+        assert_eq!(PasteKeys::from_setting("bogus"), PasteKeys::CtrlShiftV);
+
+        assert_eq!(
+            PasteKeys::submit_from_setting("Ctrl+Enter"),
+            PasteKeys::CtrlEnter
+        );
+        assert_eq!(PasteKeys::submit_from_setting("bogus"), PasteKeys::Enter);
+        for name in SUBMIT_KEY_NAMES {
+            assert_eq!(
+                PasteKeys::submit_from_setting(name).label().to_lowercase(),
+                *name
+            );
+        }
+    }
+}
@@ -0,0 +1,159 @@
+//! Item Ages
+//! Humanized "2 min ago" / "Yesterday" labels for history items, worked out
+//! here so the list doesn't need a timer per row. `next_change` says when a
+//! label goes stale, which sets how often fresh labels are sent. The wording
+//! and date formats come from the `age-*` messages of the i18n catalogs.
+
+use crate::i18n::{self, Language};
+use chrono::{DateTime, Datelike, Duration, TimeZone, Utc};
+use std::collections::HashMap;
+
+/// Items older than this get a date instead of a count of days
+const DAYS_BEFORE_DATE: i64 = 7;
+
+/// Calendar days between the two instants in the `local` time zone
+fn days_between<Tz: TimeZone>(timestamp: DateTime<Utc>, now: DateTime<Utc>, local: &Tz) -> i64 {
+    let day = |instant: DateTime<Utc>| instant.with_timezone(local).date_naive();
+    (day(now) - day(timestamp)).num_days()
+}
+
+/// The label for an item copied at `timestamp`, as seen at `now`
+pub fn humanize<Tz: TimeZone>(
+    timestamp: DateTime<Utc>,
+    now: DateTime<Utc>,
+    local: &Tz,
+    language: Language,
+) -> String
+where
+    Tz::Offset: std::fmt::Display,
+{
+    let text = |key: &str| i18n::message(language, key, &[]);
+    let count = |key: &str, n: i64| i18n::message(language, key, &[("count", n.to_string())]);
+
+    // Clock changes can put items slightly in the future
+    let elapsed = (now - timestamp).max(Duration::zero());
+    if elapsed < Duration::minutes(1) {
+        return text("age-just-now");
+    }
+    if elapsed < Duration::hours(1) {
+        return count("age-minutes", elapsed.num_minutes());
+    }
+    match days_between(timestamp, now, local) {
+        days if days <= 0 => count("age-hours", elapsed.num_hours()),
+        1 => text("age-yesterday"),
+        days if days < DAYS_BEFORE_DATE => count("age-days", days),
+        _ => {
+            let copied = timestamp.with_timezone(local);
+            let format = if copied.year() != now.with_timezone(local).year() {
+                text("age-date-with-year")
+            } else {
+                text("age-date")
+            };
+            copied.format(&format).to_string()
+        }
+    }
+}
+
+/// How long the label of an item copied at `timestamp` stays as it is at
+/// `now`, None once it is a date
+pub fn next_change<Tz: TimeZone>(
+    timestamp: DateTime<Utc>,
+    now: DateTime<Utc>,
+    local: &Tz,
+) -> Option<Duration> {
+    let elapsed = (now - timestamp).max(Duration::zero());
+    let until_next = |unit: Duration| {
+        let unit_secs = unit.num_seconds();
+        Duration::seconds(unit_secs - elapsed.num_seconds() % unit_secs)
+    };
+    if elapsed < Duration::hours(1) {
+        return Some(until_next(Duration::minutes(1)));
+    }
+
+    let days = days_between(timestamp, now, local);
+    if days >= DAYS_BEFORE_DATE {
+        return None;
+    }
+    let next_day = now.with_timezone(local).date_naive().succ_opt()?;
+    let until_midnight = local
+        .from_local_datetime(&next_day.and_hms_opt(0, 0, 0)?)
+        .earliest()
+        .map(|midnight| midnight.with_timezone(&Utc) - now)?;
+    if days <= 0 {
+        Some(until_next(Duration::hours(1)).min(until_midnight))
+    } else {
+        Some(until_midnight)
+    }
+}
+
+/// Labels for every `(id, timestamp)` pair, keyed by id
+pub fn item_ages<'a, Tz: TimeZone>(
+    items: impl IntoIterator<Item = (&'a str, DateTime<Utc>)>,
+    now: DateTime<Utc>,
+    local: &Tz,
+    language: Language,
+) -> HashMap<String, String>
+where
+    Tz::Offset: std::fmt::Display,
+{
+    items
+        .into_iter()
+        .map(|(id, timestamp)| (id.to_string(), humanize(timestamp, now, local, language)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_humanize_and_next_change() {
+        let at = |day: u32, hour: u32, minute: u32| {
+            Utc.with_ymd_and_hms(2024, 3, day, hour, minute, 0).unwrap()
+        };
+        let now = at(10, 12, 0);
+        let english = |timestamp| humanize(timestamp, now, &Utc, Language::English);
+
+        assert_eq!(
+            english(Utc.with_ymd_and_hms(2024, 3, 10, 11, 59, 30).unwrap()),
+            "Just now"
+        );
+        assert_eq!(english(at(10, 11, 58)), "2 min ago");
+        assert_eq!(english(at(10, 9, 0)), "3 h ago");
+        assert_eq!(english(at(9, 23, 0)), "Yesterday");
+        assert_eq!(english(at(6, 12, 0)), "4 days ago");
+        assert_eq!(english(at(1, 12, 0)), "Mar 1");
+        assert_eq!(
+            english(Utc.with_ymd_and_hms(2023, 12, 24, 0, 0, 0).unwrap()),
+            "Dec 24, 2023"
+        );
+        assert_eq!(
+            humanize(
+                at(10, 11, 58),
+                now,
+                &Utc,
+                Language::from_locale("pt_BR.UTF-8")
+            ),
+            "há 2 min"
+        );
+
+        // Minutes tick every minute, hours until midnight, then days
+        assert_eq!(
+            next_change(
+                Utc.with_ymd_and_hms(2024, 3, 10, 11, 57, 20).unwrap(),
+                now,
+                &Utc
+            ),
+            Some(Duration::seconds(20))
+        );
+        assert_eq!(
+            next_change(at(10, 9, 30), now, &Utc),
+            Some(Duration::minutes(30))
+        );
+        assert_eq!(
+            next_change(at(9, 23, 0), now, &Utc),
+            Some(Duration::hours(12))
+        );
+        assert_eq!(next_change(at(1, 12, 0), now, &Utc), None);
+    }
+}
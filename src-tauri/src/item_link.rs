@@ -0,0 +1,42 @@
+//! Item Links
+//! `clip://item/<id>` links to a history item, so notes and task managers can
+//! point back at something that was copied. The desktop entry registers the
+//! app as the `x-scheme-handler/clip` handler; opening a link focuses the
+//! item in the running instance.
+
+pub const SCHEME: &str = "clip";
+
+pub fn item_link(id: &str) -> String {
+    format!("{}://item/{}", SCHEME, id)
+}
+
+/// The item id in a `clip://item/<id>` link. Ids are UUIDs, anything with
+/// other characters is rejected.
+pub fn parse_item_link(link: &str) -> Option<&str> {
+    let rest = link.strip_prefix(SCHEME)?.strip_prefix("://")?;
+    let id = rest.strip_prefix("item/")?.trim_end_matches('/');
+    let valid = !id.is_empty() && id.chars().all(|c| c.is_ascii_hexdigit() || c == '-');
+    valid.then_some(id)
+}
+
+/// Whether a command line argument is meant as a link, valid or not
+pub fn is_link(arg: &str) -> bool {
+    arg.starts_with(&format!("{}://", SCHEME))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_item_link_round_trip() {
+        let id = "0f8fad5b-d9cb-469f-a165-70867728950e";
+        assert_eq!(parse_item_link(&item_link(id)), Some(id));
+        assert_eq!(parse_item_link("clip://item/abc-123/"), Some("abc-123"));
+
+        assert!(parse_item_link("clip://item/").is_none());
+        assert!(parse_item_link("clip://settings/abc").is_none());
+        assert!(parse_item_link("clip://item/../../etc").is_none());
+        assert!(parse_item_link("https://item/abc").is_none());
+    }
+}
@@ -0,0 +1,178 @@
+//! Item Summaries
+//! Short descriptions of history items for screen readers, used as the
+//! accessible names of the rows so Orca reads "Image, 800 by 600 pixels"
+//! rather than a data URL, and a few words of a long text rather than all
+//! of it. The windows add the item's age. Worded by the `summary-*` messages
+//! of the i18n catalogs.
+
+use crate::clipboard_manager::{ClipboardContent, ClipboardItem};
+use crate::i18n::{self, Language};
+use std::collections::HashMap;
+
+/// Text up to this long is read out whole
+const SPOKEN_TEXT_MAX_CHARS: usize = 100;
+
+/// File names read out before "and N more"
+const SPOKEN_FILE_NAMES: usize = 2;
+
+/// Whitespace runs, newlines included, as single spaces
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// The first `max_chars` of `text`, cut at a word boundary where there is one
+fn spoken_prefix(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let cut: String = text.chars().take(max_chars).collect();
+    match cut.rfind(' ') {
+        Some(space) if space > 0 => cut[..space].to_string(),
+        _ => cut,
+    }
+}
+
+/// Host of a text that is nothing but an http(s) link
+fn link_host(text: &str) -> Option<&str> {
+    let text = text.trim();
+    if text.contains(char::is_whitespace) {
+        return None;
+    }
+    let rest = text
+        .strip_prefix("https://")
+        .or_else(|| text.strip_prefix("http://"))?;
+    let host = rest.split(['/', '?', '#', ':']).next()?;
+    (!host.is_empty()).then_some(host)
+}
+
+fn text_summary(text: &str, language: Language) -> String {
+    if let Some(host) = link_host(text) {
+        return i18n::message(language, "summary-link", &[("host", host.to_string())]);
+    }
+    let spoken = collapse_whitespace(text);
+    let lines = text.trim().lines().count();
+    if lines <= 1 && spoken.chars().count() <= SPOKEN_TEXT_MAX_CHARS {
+        return i18n::message(language, "summary-text", &[("preview", spoken)]);
+    }
+    let mut args = vec![
+        ("words", spoken.split(' ').count().to_string()),
+        ("preview", spoken_prefix(&spoken, SPOKEN_TEXT_MAX_CHARS)),
+    ];
+    let key = if lines > 1 {
+        args.push(("lines", lines.to_string()));
+        "summary-multiline-text"
+    } else {
+        "summary-long-text"
+    };
+    i18n::message(language, key, &args)
+}
+
+fn files_summary(item: &ClipboardItem, language: Language) -> String {
+    let paths = item.file_paths();
+    let names: Vec<String> = paths
+        .iter()
+        .map(|path| {
+            path.file_name()
+                .unwrap_or(path.as_os_str())
+                .to_string_lossy()
+                .into_owned()
+        })
+        .collect();
+    let summary = match names.as_slice() {
+        [name] => i18n::message(language, "summary-file", &[("name", name.clone())]),
+        _ => {
+            let mut listed = names[..names.len().min(SPOKEN_FILE_NAMES)].join(", ");
+            if names.len() > SPOKEN_FILE_NAMES {
+                listed = i18n::message(
+                    language,
+                    "summary-more",
+                    &[
+                        ("names", listed),
+                        ("count", (names.len() - SPOKEN_FILE_NAMES).to_string()),
+                    ],
+                );
+            }
+            i18n::message(
+                language,
+                "summary-files",
+                &[("count", names.len().to_string()), ("names", listed)],
+            )
+        }
+    };
+    if item.files_missing {
+        i18n::message(language, "summary-missing", &[("summary", summary)])
+    } else {
+        summary
+    }
+}
+
+/// What a screen reader says for `item`
+pub fn summarize(item: &ClipboardItem, language: Language) -> String {
+    let summary = match &item.content {
+        ClipboardContent::Image { width, height, .. } => i18n::message(
+            language,
+            "summary-image",
+            &[("width", width.to_string()), ("height", height.to_string())],
+        ),
+        _ if !item.file_paths().is_empty() => files_summary(item, language),
+        _ => text_summary(item.text().unwrap_or_default(), language),
+    };
+    if item.pinned {
+        i18n::message(language, "summary-pinned", &[("summary", summary)])
+    } else {
+        summary
+    }
+}
+
+/// Summaries of all `items`, keyed by id
+pub fn item_summaries<'a>(
+    items: impl IntoIterator<Item = &'a ClipboardItem>,
+    language: Language,
+) -> HashMap<String, String> {
+    items
+        .into_iter()
+        .map(|item| (item.id.clone(), summarize(item, language)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summaries() {
+        let english = |item: &ClipboardItem| summarize(item, Language::English);
+
+        assert_eq!(
+            english(&ClipboardItem::new_text("  hello\tworld ".to_string())),
+            "Text: hello world"
+        );
+        assert_eq!(
+            english(&ClipboardItem::new_text(
+                "https://example.com/a?b=c".to_string()
+            )),
+            "Link to example.com"
+        );
+        let long = english(&ClipboardItem::new_text("word ".repeat(60)));
+        assert!(long.starts_with("Text, 60 words: word word"));
+        assert!(long.chars().count() < 160);
+        assert_eq!(
+            english(&ClipboardItem::new_text(
+                "fn main() {\n    run();\n}".to_string()
+            )),
+            "Text, 5 words on 3 lines: fn main() { run(); }"
+        );
+
+        let mut files = ClipboardItem::new_text(
+            "file:///tmp/a.txt\nfile:///tmp/b%20c.png\nfile:///tmp/d.pdf".to_string(),
+        );
+        files.pinned = true;
+        assert_eq!(
+            english(&files),
+            "3 files, a.txt, b c.png and 1 more, pinned"
+        );
+
+        let image = ClipboardItem::new_image(String::new(), 800, 600, 0);
+        assert_eq!(english(&image), "Image, 800 by 600 pixels");
+    }
+}
@@ -0,0 +1,242 @@
+//! Keymap
+//! Remappable key sequences for driving the history list from the home row,
+//! Vim style: j/k to move, gg/G to jump, dd to delete, p to paste and / to
+//! search, plus a key for every button of a row, for switching tabs and for
+//! searching GIFs, so nothing needs the mouse in keyboard-only mode.
+//! Sequences are written the way Vim writes them, printable keys as
+//! themselves and named keys in angle brackets ("<Enter>", "<Del>").
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Something a key sequence can do in the history list
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyAction {
+    MoveDown,
+    MoveUp,
+    JumpTop,
+    JumpBottom,
+    Delete,
+    Paste,
+    Search,
+    /// Paste, then press the submit key
+    PasteAndSubmit,
+    TogglePin,
+    TypeOut,
+    Split,
+    JoinWithPrevious,
+    CleanHiddenChars,
+    CopyLink,
+    NextTab,
+    PreviousTab,
+    SearchGifs,
+}
+
+impl KeyAction {
+    pub const ALL: &'static [KeyAction] = &[
+        Self::MoveDown,
+        Self::MoveUp,
+        Self::JumpTop,
+        Self::JumpBottom,
+        Self::Delete,
+        Self::Paste,
+        Self::Search,
+        Self::PasteAndSubmit,
+        Self::TogglePin,
+        Self::TypeOut,
+        Self::Split,
+        Self::JoinWithPrevious,
+        Self::CleanHiddenChars,
+        Self::CopyLink,
+        Self::NextTab,
+        Self::PreviousTab,
+        Self::SearchGifs,
+    ];
+
+    /// Name used as the key in the settings file
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::MoveDown => "move_down",
+            Self::MoveUp => "move_up",
+            Self::JumpTop => "jump_top",
+            Self::JumpBottom => "jump_bottom",
+            Self::Delete => "delete",
+            Self::Paste => "paste",
+            Self::Search => "search",
+            Self::PasteAndSubmit => "paste_and_submit",
+            Self::TogglePin => "toggle_pin",
+            Self::TypeOut => "type_out",
+            Self::Split => "split",
+            Self::JoinWithPrevious => "join_with_previous",
+            Self::CleanHiddenChars => "clean_hidden_chars",
+            Self::CopyLink => "copy_link",
+            Self::NextTab => "next_tab",
+            Self::PreviousTab => "previous_tab",
+            Self::SearchGifs => "search_gifs",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|action| action.name() == name)
+    }
+
+    fn default_keys(self) -> &'static str {
+        match self {
+            Self::MoveDown => "j",
+            Self::MoveUp => "k",
+            Self::JumpTop => "gg",
+            Self::JumpBottom => "G",
+            Self::Delete => "dd",
+            Self::Paste => "p",
+            Self::Search => "/",
+            Self::PasteAndSubmit => "P",
+            Self::TogglePin => "m",
+            Self::TypeOut => "t",
+            Self::Split => "s",
+            Self::JoinWithPrevious => "J",
+            Self::CleanHiddenChars => "x",
+            Self::CopyLink => "yl",
+            Self::NextTab => "gt",
+            Self::PreviousTab => "gT",
+            Self::SearchGifs => "gi",
+        }
+    }
+}
+
+/// Action name -> key sequence. Kept as strings so settings written by a
+/// newer version with more actions still load.
+pub type Keymap = BTreeMap<String, String>;
+
+pub fn default_keymap() -> Keymap {
+    KeyAction::ALL
+        .iter()
+        .map(|action| (action.name().to_string(), action.default_keys().to_string()))
+        .collect()
+}
+
+/// Neither sequence can be typed without triggering the other
+fn conflicts(a: &str, b: &str) -> bool {
+    a.starts_with(b) || b.starts_with(a)
+}
+
+/// Drops unknown actions and fills in missing ones. A sequence that is empty
+/// or clashes with an earlier action (same keys, or one a prefix of the other
+/// like "g" and "gg") falls back to its default, or is left unbound if that
+/// clashes too.
+pub fn normalize(keymap: &mut Keymap) {
+    let mut bound: Vec<&str> = Vec::new();
+    let mut normalized = Keymap::new();
+
+    for action in KeyAction::ALL {
+        let configured = keymap.get(action.name()).map(|keys| keys.trim());
+        let keys = [configured, Some(action.default_keys())]
+            .into_iter()
+            .flatten()
+            .find(|keys| !keys.is_empty() && !bound.iter().any(|other| conflicts(keys, other)))
+            .unwrap_or("");
+        if !keys.is_empty() {
+            bound.push(keys);
+        }
+        normalized.insert(action.name().to_string(), keys.to_string());
+    }
+    *keymap = normalized;
+}
+
+/// What the keys typed so far amount to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", content = "action", rename_all = "snake_case")]
+pub enum KeyResolution {
+    /// Run this action and start over
+    Action(KeyAction),
+    /// Start of a longer sequence, wait for the next key
+    Pending,
+    /// Not bound, start over
+    None,
+}
+
+pub fn resolve(keymap: &Keymap, sequence: &str) -> KeyResolution {
+    if sequence.is_empty() {
+        return KeyResolution::None;
+    }
+
+    let mut pending = false;
+    for (name, keys) in keymap {
+        if keys.is_empty() {
+            continue;
+        }
+        if keys == sequence {
+            if let Some(action) = KeyAction::from_name(name) {
+                return KeyResolution::Action(action);
+            }
+        } else if keys.starts_with(sequence) {
+            pending = true;
+        }
+    }
+
+    if pending {
+        KeyResolution::Pending
+    } else {
+        KeyResolution::None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_default_sequences() {
+        let keymap = default_keymap();
+        assert_eq!(
+            resolve(&keymap, "j"),
+            KeyResolution::Action(KeyAction::MoveDown)
+        );
+        assert_eq!(resolve(&keymap, "g"), KeyResolution::Pending);
+        assert_eq!(
+            resolve(&keymap, "gg"),
+            KeyResolution::Action(KeyAction::JumpTop)
+        );
+        assert_eq!(
+            resolve(&keymap, "G"),
+            KeyResolution::Action(KeyAction::JumpBottom)
+        );
+        assert_eq!(resolve(&keymap, "dx"), KeyResolution::None);
+        assert_eq!(
+            resolve(&keymap, "gt"),
+            KeyResolution::Action(KeyAction::NextTab)
+        );
+        assert_eq!(resolve(&keymap, "y"), KeyResolution::Pending);
+    }
+
+    #[test]
+    fn test_default_keymap_has_no_clashes() {
+        let keymap = default_keymap();
+        let mut normalized = keymap.clone();
+        normalize(&mut normalized);
+        assert_eq!(normalized, keymap);
+    }
+
+    #[test]
+    fn test_normalize_resolves_clashes() {
+        let mut keymap = Keymap::new();
+        keymap.insert("move_down".to_string(), "<Down>".to_string());
+        // "j" is still the default of move_down, but no longer taken
+        keymap.insert("move_up".to_string(), "j".to_string());
+        // Prefix of "gg"
+        keymap.insert("jump_bottom".to_string(), "g".to_string());
+        keymap.insert("paste".to_string(), "  ".to_string());
+        keymap.insert("launch_rockets".to_string(), "x".to_string());
+        normalize(&mut keymap);
+
+        assert_eq!(keymap["move_down"], "<Down>");
+        assert_eq!(keymap["move_up"], "j");
+        assert_eq!(keymap["jump_bottom"], "G");
+        assert_eq!(keymap["paste"], "p");
+        assert!(!keymap.contains_key("launch_rockets"));
+        assert_eq!(keymap.len(), KeyAction::ALL.len());
+    }
+}
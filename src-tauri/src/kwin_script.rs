@@ -0,0 +1,207 @@
+//! KWin Scripting
+//! On Plasma Wayland, X11 SetInputFocus does nothing for native Wayland
+//! clients, and KWin has no direct "activate window" call. Instead this loads
+//! short-lived KWin scripts over D-Bus: one reports the active window back to
+//! an object we serve on the session bus, the other activates it again.
+
+use crate::focus_manager::{AdapterWindow, FocusAdapter};
+use parking_lot::Mutex;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::OnceLock;
+use std::time::Duration;
+use zbus::blocking::Connection;
+
+const KWIN_SERVICE: &str = "org.kde.KWin";
+const SCRIPTING_PATH: &str = "/Scripting";
+const SCRIPTING_INTERFACE: &str = "org.kde.kwin.Scripting";
+const SCRIPT_INTERFACE: &str = "org.kde.kwin.Script";
+const PLUGIN_NAME: &str = "win11-clipboard-history-focus";
+
+const REPORT_PATH: &str = "/org/gustavosett/ClipboardHistory/Focus";
+const REPORT_INTERFACE: &str = "org.gustavosett.ClipboardHistory.Focus";
+
+/// How long to wait for the capture script to call back
+const REPORT_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Receives the window reported by the capture script
+struct FocusReporter {
+    sender: Mutex<Sender<AdapterWindow>>,
+}
+
+#[zbus::interface(name = "org.gustavosett.ClipboardHistory.Focus")]
+impl FocusReporter {
+    fn report(&self, id: String, app_id: String) {
+        let _ = self.sender.lock().send(AdapterWindow { id, app_id });
+    }
+}
+
+struct KWinBus {
+    connection: Connection,
+    reports: Mutex<Receiver<AdapterWindow>>,
+}
+
+/// Session bus connection serving the reporter, created on first use
+fn bus() -> Option<&'static KWinBus> {
+    static BUS: OnceLock<Option<KWinBus>> = OnceLock::new();
+    BUS.get_or_init(|| {
+        let (sender, receiver) = channel();
+        let reporter = FocusReporter {
+            sender: Mutex::new(sender),
+        };
+        let connection = zbus::blocking::connection::Builder::session()
+            .and_then(|builder| builder.serve_at(REPORT_PATH, reporter))
+            .and_then(|builder| builder.build())
+            .map_err(|e| eprintln!("[KWinScript] Failed to connect to session bus: {}", e))
+            .ok()?;
+        Some(KWinBus {
+            connection,
+            reports: Mutex::new(receiver),
+        })
+    })
+    .as_ref()
+}
+
+/// JS for the active window; KWin 5 calls windows "clients"
+const ACTIVE_WINDOW_JS: &str =
+    "(workspace.activeWindow !== undefined ? workspace.activeWindow : workspace.activeClient)";
+
+/// Script that calls back `report(id, app_id)` with the active window
+fn capture_script(bus_name: &str) -> String {
+    format!(
+        "const w = {active};\n\
+         if (w) {{\n\
+         \x20   callDBus({bus}, {path}, {iface}, \"report\", w.internalId.toString(), String(w.resourceClass));\n\
+         }}\n",
+        active = ACTIVE_WINDOW_JS,
+        bus = js_string(bus_name),
+        path = js_string(REPORT_PATH),
+        iface = js_string(REPORT_INTERFACE),
+    )
+}
+
+/// Script that activates the window with the given internal id
+fn activate_script(id: &str) -> String {
+    format!(
+        "const windows = workspace.windowList ? workspace.windowList() : workspace.clientList();\n\
+         for (const w of windows) {{\n\
+         \x20   if (w.internalId.toString() === {id}) {{\n\
+         \x20       if (workspace.activeWindow !== undefined) workspace.activeWindow = w;\n\
+         \x20       else workspace.activeClient = w;\n\
+         \x20   }}\n\
+         }}\n",
+        id = js_string(id),
+    )
+}
+
+/// Quotes a string as a JS literal (JSON strings are valid JS)
+fn js_string(value: &str) -> String {
+    serde_json::to_string(value).unwrap_or_else(|_| "\"\"".to_string())
+}
+
+/// Loads, runs and unloads a one-shot KWin script
+fn run_script(connection: &Connection, source: &str) -> Result<(), String> {
+    let path = std::env::temp_dir().join(format!("{}-{}.js", PLUGIN_NAME, std::process::id()));
+    std::fs::write(&path, source).map_err(|e| format!("Failed to write KWin script: {}", e))?;
+
+    // A script left over from an interrupted run would make loadScript fail
+    unload_script(connection);
+
+    let result = connection
+        .call_method(
+            Some(KWIN_SERVICE),
+            SCRIPTING_PATH,
+            Some(SCRIPTING_INTERFACE),
+            "loadScript",
+            &(path.to_string_lossy().as_ref(), PLUGIN_NAME),
+        )
+        .and_then(|reply| reply.body().deserialize::<i32>())
+        .map_err(|e| format!("loadScript failed: {}", e))
+        .and_then(|id| {
+            // Plasma 6 serves scripts under /Scripting/Script<id>, Plasma 5 under /<id>
+            [format!("/Scripting/Script{}", id), format!("/{}", id)]
+                .iter()
+                .find_map(|script_path| {
+                    connection
+                        .call_method(
+                            Some(KWIN_SERVICE),
+                            script_path.as_str(),
+                            Some(SCRIPT_INTERFACE),
+                            "run",
+                            &(),
+                        )
+                        .ok()
+                })
+                .map(|_| ())
+                .ok_or_else(|| format!("Failed to run KWin script {}", id))
+        });
+
+    unload_script(connection);
+    let _ = std::fs::remove_file(&path);
+    result
+}
+
+fn unload_script(connection: &Connection) {
+    let _ = connection.call_method(
+        Some(KWIN_SERVICE),
+        SCRIPTING_PATH,
+        Some(SCRIPTING_INTERFACE),
+        "unloadScript",
+        &(PLUGIN_NAME,),
+    );
+}
+
+pub struct KWinAdapter;
+
+impl FocusAdapter for KWinAdapter {
+    fn id(&self) -> &'static str {
+        "kwin"
+    }
+
+    fn name(&self) -> &'static str {
+        "KWin"
+    }
+
+    fn is_available(&self) -> bool {
+        let is_kde = std::env::var("XDG_CURRENT_DESKTOP")
+            .map(|desktop| desktop.to_uppercase().contains("KDE"))
+            .unwrap_or(false);
+        is_kde && std::env::var_os("WAYLAND_DISPLAY").is_some()
+    }
+
+    fn is_cheap(&self) -> bool {
+        false
+    }
+
+    fn active_window(&self) -> Option<AdapterWindow> {
+        let bus = bus()?;
+        let bus_name = bus.connection.unique_name()?.to_string();
+        let reports = bus.reports.lock();
+        // Drop reports that arrived after an earlier timeout
+        while reports.try_recv().is_ok() {}
+
+        if let Err(e) = run_script(&bus.connection, &capture_script(&bus_name)) {
+            eprintln!("[KWinScript] {}", e);
+            return None;
+        }
+        reports.recv_timeout(REPORT_TIMEOUT).ok()
+    }
+
+    fn activate(&self, window: &AdapterWindow) -> Result<(), String> {
+        let bus = bus().ok_or("Session bus unavailable")?;
+        run_script(&bus.connection, &activate_script(&window.id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scripts_quote_values() {
+        let script = activate_script("{8e2f\"x}");
+        assert!(script.contains(r#"=== "{8e2f\"x}""#));
+
+        let script = capture_script(":1.42");
+        assert!(script.contains(r#"callDBus(":1.42", "/org/gustavosett/ClipboardHistory/Focus""#));
+    }
+}
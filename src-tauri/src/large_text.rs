@@ -0,0 +1,134 @@
+//! Large Text
+//! Copying a whole log file used to put every byte of it into memory, the
+//! history file and each message to the webview. Texts over
+//! `LARGE_TEXT_BYTES` now go to a file of their own next to the history,
+//! and the item keeps only the start of the text, which is what the list,
+//! search and the text features see. The full text is read back only when
+//! the item is pasted. Without a history on disk they stay inline.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Texts longer than this, in bytes, are kept out of line
+pub const LARGE_TEXT_BYTES: usize = 512 * 1024;
+
+/// How much of a large text stays in the item
+const INLINE_BYTES: usize = 64 * 1024;
+
+/// Directory of the text files, inside the data directory
+const LARGE_TEXT_DIR: &str = "large_text";
+
+/// Where the full text of a large item is, and what it holds
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LargeText {
+    /// Hash of the full text, which names its file and stands in for the
+    /// item's content hash
+    pub hash: u64,
+    pub bytes: usize,
+    pub lines: usize,
+}
+
+pub fn is_large(text: &str) -> bool {
+    text.len() > LARGE_TEXT_BYTES
+}
+
+/// Directory of the large texts of the history stored at `location`
+pub fn dir_for(location: &Path) -> Option<PathBuf> {
+    location
+        .parent()
+        .map(|data_dir| data_dir.join(LARGE_TEXT_DIR))
+}
+
+fn file_path(dir: &Path, hash: u64) -> PathBuf {
+    dir.join(format!("{}.txt", hash))
+}
+
+/// The start of `text` kept inline, cut at the last line break that fits,
+/// or at a character boundary for texts with very long lines
+pub fn inline_part(text: &str) -> &str {
+    if text.len() <= INLINE_BYTES {
+        return text;
+    }
+    let mut end = INLINE_BYTES;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    match text[..end].rfind('\n') {
+        Some(line_end) if line_end > 0 => &text[..line_end],
+        _ => &text[..end],
+    }
+}
+
+/// Writes `text` to its file in `dir`
+pub fn store(dir: &Path, text: &str, hash: u64) -> Result<LargeText, String> {
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let path = file_path(dir, hash);
+    // The same text copied again already has its file
+    if !path.exists() {
+        fs::write(&path, text).map_err(|e| format!("Failed to write large text: {}", e))?;
+    }
+    Ok(LargeText {
+        hash,
+        bytes: text.len(),
+        lines: text.lines().count(),
+    })
+}
+
+/// Reads the full text back
+pub fn load(dir: &Path, large: &LargeText) -> Result<String, String> {
+    fs::read_to_string(file_path(dir, large.hash))
+        .map_err(|e| format!("Failed to read the full text of the item: {}", e))
+}
+
+/// Deletes the files of texts whose hash is not in `keep`, returning how
+/// many went
+pub fn prune(dir: &Path, keep: &[u64]) -> usize {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .filter(|entry| {
+            let name = entry.file_name();
+            name.to_str()
+                .and_then(|name| name.strip_suffix(".txt"))
+                .and_then(|hash| hash.parse::<u64>().ok())
+                .is_some_and(|hash| !keep.contains(&hash))
+        })
+        .filter(|entry| fs::remove_file(entry.path()).is_ok())
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_store_and_prune_large_text() {
+        let dir = std::env::temp_dir().join("large_text_test");
+        let _ = fs::remove_dir_all(&dir);
+
+        let line = "2024-05-02 12:00:00 INFO something happened ✓\n";
+        let text = line.repeat(LARGE_TEXT_BYTES / line.len() + 100);
+        assert!(is_large(&text));
+        let inline = inline_part(&text);
+        assert!(inline.len() <= INLINE_BYTES && inline.ends_with("✓"));
+        assert!(text.starts_with(inline));
+        // One long line is cut at a character boundary
+        let long_line = "é".repeat(INLINE_BYTES);
+        assert!(inline_part(&long_line).len() <= INLINE_BYTES);
+
+        let large = store(&dir, &text, 42).unwrap();
+        assert_eq!(
+            (large.bytes, large.lines),
+            (text.len(), text.lines().count())
+        );
+        assert_eq!(load(&dir, &large).unwrap(), text);
+
+        store(&dir, "other", 7).unwrap();
+        assert_eq!(prune(&dir, &[42]), 1);
+        assert!(load(&dir, &large).is_ok());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,69 @@
+//! Layer Shell
+//! Turns the main window into a wlr-layer-shell overlay on compositors that
+//! offer it (wlroots, KDE), so it is placed natively instead of through
+//! XWayland. Needs GTK running on Wayland and is built with the `layer-shell`
+//! feature. Placement math lives in `config_manager::layer_placement`.
+
+use crate::config_manager::{DockEdge, LayerPlacement};
+use gtk::prelude::*;
+use gtk_layer_shell::{Edge, KeyboardMode, Layer, LayerShell};
+use tauri::WebviewWindow;
+
+const NAMESPACE: &str = "win11-clipboard-history";
+
+/// Makes `window` a layer surface. Must run on the main thread before the
+/// window is first shown.
+pub fn init(window: &WebviewWindow) -> Result<(), String> {
+    if !gtk_layer_shell::is_supported() {
+        return Err("GTK is not running on a compositor with wlr-layer-shell".to_string());
+    }
+    let gtk_window = window.gtk_window().map_err(|e| e.to_string())?;
+
+    // A surface's role is fixed once it is mapped
+    gtk_window.hide();
+    if gtk_window.is_realized() {
+        gtk_window.unrealize();
+    }
+    gtk_window.init_layer_shell();
+    gtk_window.set_namespace(NAMESPACE);
+    gtk_window.set_layer(Layer::Overlay);
+    gtk_window.set_keyboard_mode(KeyboardMode::OnDemand);
+    Ok(())
+}
+
+/// Anchors the surface as described by `placement`
+pub fn place(window: &WebviewWindow, placement: LayerPlacement) {
+    let target = window.clone();
+    let result = window.run_on_main_thread(move || {
+        let Ok(gtk_window) = target.gtk_window() else {
+            return;
+        };
+
+        let display = gtk_window.display();
+        let monitor = (0..display.n_monitors())
+            .filter_map(|i| display.monitor(i))
+            .find(|m| m.model().as_deref() == placement.monitor_name.as_deref());
+        if let Some(monitor) = monitor {
+            gtk_window.set_monitor(&monitor);
+        }
+
+        let (left, top) = placement.margin;
+        let (anchor_left, anchor_right) = match placement.dock_edge {
+            Some(DockEdge::Left) => (true, false),
+            Some(DockEdge::Right) => (false, true),
+            None => (true, false),
+        };
+        let docked = placement.dock_edge.is_some();
+        gtk_window.set_anchor(Edge::Left, anchor_left);
+        gtk_window.set_anchor(Edge::Right, anchor_right);
+        gtk_window.set_anchor(Edge::Top, true);
+        gtk_window.set_anchor(Edge::Bottom, docked);
+        gtk_window.set_layer_shell_margin(Edge::Left, if docked { 0 } else { left });
+        gtk_window.set_layer_shell_margin(Edge::Top, if docked { 0 } else { top });
+        gtk_window.set_layer_shell_margin(Edge::Right, 0);
+        gtk_window.set_layer_shell_margin(Edge::Bottom, 0);
+    });
+    if let Err(e) = result {
+        eprintln!("[LayerShell] Failed to place window: {}", e);
+    }
+}
@@ -1,24 +1,85 @@
 //! Windows 11 Clipboard History For Linux Library
 //! This module re-exports the core functionality for use as a library
 
+pub mod activity_heatmap;
 pub mod autostart_manager;
+pub mod cli;
 pub mod clipboard_manager;
+pub mod collections;
 pub mod config_manager;
+pub mod content_classifier;
+pub mod diagnostics;
+pub mod emoji_dataset;
 pub mod emoji_manager;
+pub mod emoji_variants;
+pub mod entity_detection;
 pub mod focus_manager;
 pub mod gif_manager;
+pub mod gif_provider;
+pub mod history_query;
+pub mod history_store;
+pub mod i18n;
+pub mod icon_resolver;
+pub mod image_compression;
 pub mod input_simulator;
+pub mod item_age;
+pub mod item_link;
+pub mod item_summary;
+pub mod keymap;
+pub mod large_text;
+pub mod listener_supervisor;
+pub mod markdown;
+pub mod memory_budget;
+pub mod metrics;
+pub mod paste_profiles;
 pub mod permission_checker;
+pub mod pinned_git;
+pub mod placeholders;
+pub mod primary_selection;
+pub mod quick_actions;
 pub mod session;
+pub mod shell_actions;
 pub mod shortcut_conflict_detector;
 pub mod shortcut_setup;
+pub mod snippet_packs;
+pub mod soak;
+pub mod sticker_manager;
+pub mod theme;
+pub mod thumbnail_manager;
+pub mod translation;
+pub mod tray_badge;
+pub mod unified_search;
+pub mod unit_conversion;
+pub mod usage_stats;
 pub mod user_settings;
+pub mod worker_pool;
 
+#[cfg(target_os = "linux")]
+pub mod color_scheme;
+#[cfg(target_os = "linux")]
+pub mod foreign_toplevel;
+#[cfg(target_os = "linux")]
+pub mod hyprland_ipc;
+#[cfg(target_os = "linux")]
+pub mod kwin_script;
+#[cfg(all(target_os = "linux", feature = "layer-shell"))]
+pub mod layer_shell;
 #[cfg(target_os = "linux")]
 pub mod linux_shortcut_manager;
+#[cfg(target_os = "linux")]
+pub mod session_monitor;
+#[cfg(target_os = "linux")]
+pub mod sway_ipc;
+#[cfg(target_os = "linux")]
+pub mod wayland_clipboard;
+#[cfg(target_os = "linux")]
+pub mod work_area;
+#[cfg(target_os = "linux")]
+pub mod x11_clipboard;
 
 pub use clipboard_manager::{ClipboardContent, ClipboardItem, ClipboardManager};
 pub use config_manager::ConfigManager;
+pub use diagnostics::{HealthIssue, HealthReport};
 pub use emoji_manager::{EmojiManager, EmojiUsage};
 pub use focus_manager::{restore_focused_window, save_focused_window};
 
@@ -35,6 +96,6 @@ pub use shortcut_conflict_detector::{
 };
 pub use shortcut_setup::{
     check_shortcut_tools, detect_conflicts, get_desktop_environment, register_de_shortcut,
-    resolve_conflicts, ShortcutToolsStatus,
+    resolve_conflicts, unregister_de_shortcut, ShortcutToolsStatus,
 };
 pub use user_settings::{UserSettings, UserSettingsManager};
@@ -5,75 +5,613 @@ use std::fs;
 use std::io::{self, Write};
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::OnceLock;
 use std::time::{SystemTime, UNIX_EPOCH};
 use uuid::Uuid;
 
+use crate::session::command_exists;
+use crate::user_settings::{UserSettings, UserSettingsManager};
+
 // =============================================================================
 // Configuration
 // =============================================================================
 
 #[derive(Debug, Clone)]
 pub struct ShortcutConfig {
-    pub id: &'static str,
-    pub name: &'static str,
-    pub command: &'static str,
-    pub gnome_binding: &'static str,
-    pub kde_binding: &'static str,
-    pub xfce_binding: &'static str,
-    pub cosmic_mods: &'static str,
-    pub cosmic_key: &'static str,
+    pub id: String,
+    pub name: String,
+    pub command: String,
+    pub gnome_binding: String,
+    pub kde_binding: String,
+    pub xfce_binding: String,
+    pub cosmic_mods: String,
+    pub cosmic_key: String,
+    pub openbox_binding: String,
     // Tiling WM bindings
-    pub i3_binding: &'static str,
-    pub sway_binding: &'static str,
-    pub hyprland_binding: &'static str,
+    pub i3_binding: String,
+    pub sway_binding: String,
+    pub hyprland_binding: String,
+    pub niri_binding: String,
 }
 
 fn get_command_path() -> &'static str {
-    // First, check if binary is in PATH (production install)
-    if Utils::command_exists("win11-clipboard-history") {
-        return "win11-clipboard-history";
+    static COMMAND_PATH: OnceLock<String> = OnceLock::new();
+    COMMAND_PATH.get_or_init(|| {
+        // First, check if binary is in PATH (production install)
+        if command_exists("win11-clipboard-history") {
+            return "win11-clipboard-history".to_string();
+        }
+
+        // Try to find the current executable path (for development)
+        if let Ok(exe_path) = env::current_exe() {
+            return exe_path.to_string_lossy().to_string();
+        }
+
+        // Fallback to just the name
+        "win11-clipboard-history".to_string()
+    })
+}
+
+/// A key combination as written in the settings, e.g. "Super+Shift+V".
+/// The key is kept as an X keysym name ("v", "period", "F9"), which most
+/// DEs use as is; KDE and LXQt get their own spelling.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Accelerator {
+    pub super_key: bool,
+    pub ctrl: bool,
+    pub alt: bool,
+    pub shift: bool,
+    pub key: String,
+}
+
+/// Keysym names for punctuation commonly typed literally in the settings
+const KEY_NAMES: &[(&str, &str)] = &[
+    (".", "period"),
+    (",", "comma"),
+    (";", "semicolon"),
+    ("/", "slash"),
+    ("\\", "backslash"),
+    ("'", "apostrophe"),
+    ("`", "grave"),
+    ("-", "minus"),
+    ("=", "equal"),
+    ("[", "bracketleft"),
+    ("]", "bracketright"),
+];
+
+impl Accelerator {
+    /// The default binding, Super+V
+    pub fn primary_default() -> Self {
+        Self {
+            super_key: true,
+            key: "v".into(),
+            ..Default::default()
+        }
+    }
+
+    fn alternative() -> Self {
+        Self {
+            ctrl: true,
+            alt: true,
+            key: "v".into(),
+            ..Default::default()
+        }
+    }
+
+    /// Parses "Modifier+...+Key", accepting the usual modifier spellings
+    /// (Super/Meta/Win, Ctrl/Control/Primary, Alt, Shift), case-insensitively
+    pub fn parse(text: &str) -> Option<Self> {
+        let parts: Vec<&str> = text.split('+').map(str::trim).collect();
+        let (key, modifiers) = parts.split_last()?;
+        if key.is_empty() {
+            return None;
+        }
+
+        let mut accelerator = Self::default();
+        for modifier in modifiers {
+            match modifier.to_lowercase().as_str() {
+                "super" | "meta" | "win" | "logo" | "mod4" | "$mod" => accelerator.super_key = true,
+                "ctrl" | "control" | "primary" => accelerator.ctrl = true,
+                "alt" | "mod1" => accelerator.alt = true,
+                "shift" => accelerator.shift = true,
+                _ => return None,
+            }
+        }
+
+        let named = KEY_NAMES
+            .iter()
+            .find(|(text, name)| text == key || name.eq_ignore_ascii_case(key));
+        accelerator.key = match named {
+            Some((_, name)) => name.to_string(),
+            None if key.chars().count() == 1 => key.to_lowercase(),
+            None if key.eq_ignore_ascii_case("space") => "space".into(),
+            None => key.to_string(),
+        };
+        Some(accelerator)
+    }
+
+    /// Modifier names in Super, Ctrl, Alt, Shift order
+    fn modifiers<'a>(&self, names: [&'a str; 4]) -> Vec<&'a str> {
+        [self.super_key, self.ctrl, self.alt, self.shift]
+            .iter()
+            .zip(names)
+            .filter(|(enabled, _)| **enabled)
+            .map(|(_, name)| name)
+            .collect()
+    }
+
+    /// Joins modifiers and key with `separator`, e.g. "Meta+Shift+V"
+    fn joined(&self, names: [&str; 4], separator: &str, key: &str) -> String {
+        let mut parts = self.modifiers(names);
+        parts.push(key);
+        parts.join(separator)
+    }
+
+    /// Single characters upper-cased, keysym names capitalized ("F9", "Space")
+    fn display_key(&self) -> String {
+        if let Some((text, _)) = KEY_NAMES.iter().find(|(_, name)| *name == self.key) {
+            return text.to_string();
+        }
+        let mut chars = self.key.chars();
+        match chars.next() {
+            Some(first) => first.to_uppercase().chain(chars).collect(),
+            None => String::new(),
+        }
+    }
+
+    /// gsettings format, e.g. "<Super><Shift>v"
+    fn gnome(&self) -> String {
+        let modifiers: String = self
+            .modifiers(["<Super>", "<Ctrl>", "<Alt>", "<Shift>"])
+            .concat();
+        format!("{}{}", modifiers, self.key)
+    }
+
+    /// xfce4-keyboard-shortcuts format, e.g. "<Primary><Alt>v"
+    fn xfce(&self) -> String {
+        let modifiers: String = self
+            .modifiers(["<Super>", "<Primary>", "<Alt>", "<Shift>"])
+            .concat();
+        format!("{}{}", modifiers, self.key)
+    }
+
+    /// Qt key sequence, e.g. "Meta+Shift+V"
+    fn kde(&self) -> String {
+        self.joined(["Meta", "Ctrl", "Alt", "Shift"], "+", &self.display_key())
+    }
+
+    /// i3 and sway `bindsym` format, e.g. "$mod+Shift+v"
+    fn i3(&self) -> String {
+        self.joined(["$mod", "Ctrl", "Mod1", "Shift"], "+", &self.key)
+    }
+
+    /// Single characters upper-cased, keysym names as they are
+    fn upper_key(&self) -> String {
+        if self.key.chars().count() == 1 {
+            self.key.to_uppercase()
+        } else {
+            self.key.clone()
+        }
+    }
+
+    /// Hyprland `bind` modifiers and key, e.g. "SUPER SHIFT, V"
+    fn hyprland(&self) -> String {
+        format!(
+            "{}, {}",
+            self.modifiers(["SUPER", "CTRL", "ALT", "SHIFT"]).join(" "),
+            self.upper_key()
+        )
     }
 
-    // Try to find the current executable path (for development)
-    if let Ok(exe_path) = env::current_exe() {
-        let path_str = exe_path.to_string_lossy().to_string();
-        // Leak the string to get a 'static lifetime
-        // This is acceptable since this is called once at startup
-        return Box::leak(path_str.into_boxed_str());
+    /// Niri `binds` key format, e.g. "Mod+Shift+V"
+    fn niri(&self) -> String {
+        self.joined(["Mod", "Ctrl", "Alt", "Shift"], "+", &self.upper_key())
     }
 
-    // Fallback to just the name
-    "win11-clipboard-history"
+    /// Openbox `keybind key` format, e.g. "W-S-v"
+    fn openbox(&self) -> String {
+        self.joined(["W", "C", "A", "S"], "-", &self.key)
+    }
 }
 
-const SHORTCUTS: &[ShortcutConfig] = &[
-    ShortcutConfig {
-        id: "win11-clipboard-history",
-        name: "Clipboard History",
-        command: "win11-clipboard-history", // Will be replaced at runtime
-        gnome_binding: "<Super>v",
-        kde_binding: "Meta+V",
-        xfce_binding: "<Super>v",
-        cosmic_mods: "Super",
-        cosmic_key: "v",
-        i3_binding: "$mod+v",
-        sway_binding: "$mod+v",
-        hyprland_binding: "SUPER, V",
-    },
+fn shortcut_config(id: &str, name: &str, command: String, keys: &Accelerator) -> ShortcutConfig {
     ShortcutConfig {
-        id: "win11-clipboard-history-alt",
-        name: "Clipboard History (Alt)",
-        command: "win11-clipboard-history", // Will be replaced at runtime
-        gnome_binding: "<Ctrl><Alt>v",
-        kde_binding: "Ctrl+Alt+V",
-        xfce_binding: "<Primary><Alt>v",
-        cosmic_mods: "Ctrl, Alt",
-        cosmic_key: "v",
-        i3_binding: "Ctrl+Mod1+v",
-        sway_binding: "Ctrl+Mod1+v",
-        hyprland_binding: "CTRL ALT, V",
-    },
-];
+        id: id.to_string(),
+        name: name.to_string(),
+        command,
+        gnome_binding: keys.gnome(),
+        kde_binding: keys.kde(),
+        xfce_binding: keys.xfce(),
+        cosmic_mods: keys.modifiers(["Super", "Ctrl", "Alt", "Shift"]).join(", "),
+        cosmic_key: keys.key.clone(),
+        openbox_binding: keys.openbox(),
+        i3_binding: keys.i3(),
+        sway_binding: keys.i3(),
+        hyprland_binding: keys.hyprland(),
+        niri_binding: keys.niri(),
+    }
+}
+
+/// Super+Ctrl+1 up to this number paste recent items when enabled
+const QUICK_PASTE_COUNT: usize = 9;
+
+/// Our bindings for the given settings: the main key combination (Super+V
+/// when it cannot be parsed), the fixed Ctrl+Alt+V alternative, the emoji
+/// panel shortcut unless it is left empty, and the optional quick paste keys
+fn shortcuts_for(settings: &UserSettings) -> Vec<ShortcutConfig> {
+    let command = get_command_path();
+    let primary = Accelerator::parse(&settings.global_shortcut).unwrap_or_else(|| {
+        eprintln!(
+            "[ShortcutManager] Invalid shortcut '{}', using Super+V",
+            settings.global_shortcut
+        );
+        Accelerator::primary_default()
+    });
+    let alternative = Accelerator::alternative();
+
+    let mut shortcuts = vec![shortcut_config(
+        "win11-clipboard-history",
+        "Clipboard History",
+        command.to_string(),
+        &primary,
+    )];
+    if primary != alternative {
+        shortcuts.push(shortcut_config(
+            "win11-clipboard-history-alt",
+            "Clipboard History (Alt)",
+            command.to_string(),
+            &alternative,
+        ));
+    }
+
+    if !settings.emoji_shortcut.is_empty() {
+        match Accelerator::parse(&settings.emoji_shortcut) {
+            Some(keys) if keys != primary && keys != alternative => {
+                shortcuts.push(shortcut_config(
+                    "win11-clipboard-history-emoji",
+                    "Emoji Panel",
+                    format!("{} --emoji", command),
+                    &keys,
+                ));
+            }
+            Some(_) => eprintln!("[ShortcutManager] Emoji shortcut clashes with the main one"),
+            None => eprintln!(
+                "[ShortcutManager] Invalid emoji shortcut '{}', skipped",
+                settings.emoji_shortcut
+            ),
+        }
+    }
+
+    if settings.quick_paste_shortcuts {
+        for position in 1..=QUICK_PASTE_COUNT {
+            if let Some(keys) = Accelerator::parse(&format!("Super+Ctrl+{}", position)) {
+                shortcuts.push(shortcut_config(
+                    &format!("win11-clipboard-history-paste-{}", position),
+                    &format!("Paste Recent Item {}", position),
+                    format!("{} --paste-recent {}", command, position),
+                    &keys,
+                ));
+            }
+        }
+    }
+    shortcuts
+}
+
+/// Bindings for the shortcuts chosen in the settings
+fn shortcuts() -> Vec<ShortcutConfig> {
+    shortcuts_for(&UserSettingsManager::new().load())
+}
+
+/// Whether a tiling WM config line ends by running exactly `command`, so the
+/// main binding is not mistaken for one of the others that add arguments
+fn runs_command(line: &str, command: &str) -> bool {
+    line.trim_end()
+        .strip_suffix(command)
+        .is_some_and(|rest| rest.ends_with([' ', ',']))
+}
+
+/// Lines between these markers in i3, sway and Hyprland configs belong to us
+/// and are rewritten on every change
+const MANAGED_BLOCK_BEGIN: &str =
+    "# BEGIN win11-clipboard-history (managed, change the shortcut in the app settings)";
+const MANAGED_BLOCK_END: &str = "# END win11-clipboard-history";
+/// Older versions appended each binding after this comment instead
+const LEGACY_BINDING_MARKER: &str = "# Clipboard History (added by win11-clipboard-history)";
+/// Suffix of the user's own bindings we commented out because they clashed
+const COMMENTED_MARKER: &str = " # Commented by win11-clipboard-history";
+
+/// A tiling WM config split into the user's lines and our managed block
+struct ManagedConfig {
+    outside: Vec<String>,
+    block: Vec<String>,
+}
+
+impl ManagedConfig {
+    /// Splits `content`, dropping the legacy entry for `command` so it moves
+    /// into the block
+    fn parse(content: &str, command: &str) -> Self {
+        let mut config = Self {
+            outside: Vec::new(),
+            block: Vec::new(),
+        };
+        let mut in_block = false;
+        let mut after_legacy_marker = false;
+
+        for line in content.lines() {
+            let trimmed = line.trim();
+            if trimmed == MANAGED_BLOCK_BEGIN {
+                in_block = true;
+            } else if trimmed == MANAGED_BLOCK_END {
+                in_block = false;
+            } else if in_block {
+                config.block.push(line.to_string());
+            } else if trimmed == LEGACY_BINDING_MARKER {
+                after_legacy_marker = true;
+                continue;
+            } else if !(after_legacy_marker && runs_command(line, command)) {
+                if after_legacy_marker {
+                    config.outside.push(LEGACY_BINDING_MARKER.to_string());
+                }
+                config.outside.push(line.to_string());
+            }
+            after_legacy_marker = false;
+        }
+        config
+    }
+
+    /// The user's lines with the block (if it has anything) at the end
+    fn render(&self) -> String {
+        let mut lines = self.outside.clone();
+        while lines.last().is_some_and(|line| line.trim().is_empty()) {
+            lines.pop();
+        }
+        if !self.block.is_empty() {
+            if !lines.is_empty() {
+                lines.push(String::new());
+            }
+            lines.push(MANAGED_BLOCK_BEGIN.to_string());
+            lines.extend(self.block.iter().cloned());
+            lines.push(MANAGED_BLOCK_END.to_string());
+        }
+        let mut content = lines.join("\n");
+        content.push('\n');
+        content
+    }
+
+    fn block_has(&self, binding_line: &str) -> bool {
+        self.block.iter().any(|line| line.trim() == binding_line)
+    }
+}
+
+/// `content` with `binding_line` (running `command`) in the managed block
+/// and the user's bindings of the same keys, per `same_keys` on the
+/// lowercased line, commented out. None when nothing changes, or when the
+/// user binds `command` themselves outside the block.
+fn write_managed_binding(
+    content: &str,
+    command: &str,
+    binding_line: &str,
+    same_keys: impl Fn(&str) -> bool,
+) -> Option<String> {
+    let mut config = ManagedConfig::parse(content, command);
+    if config
+        .outside
+        .iter()
+        .any(|line| !line.trim_start().starts_with('#') && runs_command(line, command))
+    {
+        return None;
+    }
+
+    for line in config.outside.iter_mut() {
+        let trimmed = line.trim().to_lowercase();
+        if !trimmed.starts_with('#') && same_keys(&trimmed) {
+            *line = format!("# {}{}", line, COMMENTED_MARKER);
+        }
+    }
+    if !config.block_has(binding_line) {
+        config.block.retain(|line| !runs_command(line, command));
+        config.block.push(binding_line.to_string());
+    }
+
+    let updated = config.render();
+    (updated != content).then_some(updated)
+}
+
+/// `content` without our binding for `command`, restoring the user's
+/// bindings of the same keys that were commented out for it
+fn remove_managed_binding(
+    content: &str,
+    command: &str,
+    same_keys: impl Fn(&str) -> bool,
+) -> Option<String> {
+    let mut config = ManagedConfig::parse(content, command);
+    config.block.retain(|line| !runs_command(line, command));
+
+    for line in config.outside.iter_mut() {
+        let restored = line
+            .strip_suffix(COMMENTED_MARKER)
+            .and_then(|rest| rest.strip_prefix("# "));
+        if let Some(restored) = restored {
+            if same_keys(&restored.trim().to_lowercase()) {
+                *line = restored.to_string();
+            }
+        }
+    }
+
+    let updated = config.render();
+    (updated != content).then_some(updated)
+}
+
+/// Niri's config is KDL, so it gets its own markers. The block has to live
+/// inside the `binds` section, as niri does not accept a second one.
+const NIRI_BLOCK_BEGIN: &str =
+    "// BEGIN win11-clipboard-history (managed, change the shortcut in the app settings)";
+const NIRI_BLOCK_END: &str = "// END win11-clipboard-history";
+const NIRI_COMMENTED_MARKER: &str = " // Commented by win11-clipboard-history";
+const NIRI_INDENT: &str = "    ";
+
+/// Niri `spawn` action running `command`, each argument its own string
+fn niri_spawn(command: &str) -> String {
+    let args: Vec<String> = command
+        .split_whitespace()
+        .map(|arg| format!("{:?}", arg))
+        .collect();
+    format!("spawn {};", args.join(" "))
+}
+
+/// The `binds` entry for `keys` running `command`,
+/// e.g. `Mod+V { spawn "win11-clipboard-history"; }`
+fn niri_bind_line(keys: &str, command: &str) -> String {
+    format!("{} {{ {} }}", keys, niri_spawn(command))
+}
+
+/// Whether a `binds` entry is for the same keys, ignoring modifier order and
+/// case. Super is spelled Mod by default.
+fn is_same_niri_bind(line: &str, keys: &str) -> bool {
+    let normalized = |keys: &str| {
+        let mut parts: Vec<String> = keys
+            .split('+')
+            .map(|part| match part.trim().to_lowercase().as_str() {
+                "super" | "win" => "mod".to_string(),
+                other => other.to_string(),
+            })
+            .collect();
+        parts.sort();
+        parts
+    };
+    let bound = line
+        .trim_start()
+        .split(|c: char| c.is_whitespace() || c == '{')
+        .next()
+        .unwrap_or_default();
+    !bound.is_empty() && !bound.starts_with('/') && normalized(bound) == normalized(keys)
+}
+
+/// A niri config with our block taken out, remembering where it was
+struct NiriConfig {
+    lines: Vec<String>,
+    block: Vec<String>,
+    block_at: Option<usize>,
+}
+
+impl NiriConfig {
+    fn parse(content: &str) -> Self {
+        let mut config = Self {
+            lines: Vec::new(),
+            block: Vec::new(),
+            block_at: None,
+        };
+        let mut in_block = false;
+
+        for line in content.lines() {
+            let trimmed = line.trim();
+            if trimmed == NIRI_BLOCK_BEGIN {
+                in_block = true;
+                config.block_at = Some(config.lines.len());
+            } else if trimmed == NIRI_BLOCK_END {
+                in_block = false;
+            } else if in_block {
+                config.block.push(line.to_string());
+            } else {
+                config.lines.push(line.to_string());
+            }
+        }
+        config
+    }
+
+    /// The config with the block (if it has anything) back in place, at the
+    /// top of the `binds` section when it is new, in a new section if there
+    /// is none
+    fn render(&self) -> String {
+        let mut lines = self.lines.clone();
+        if !self.block.is_empty() {
+            let binds_section = || {
+                lines.iter().position(|line| {
+                    line.trim()
+                        .strip_prefix("binds")
+                        .is_some_and(|rest| rest.trim() == "{")
+                })
+            };
+            let at = match self.block_at.or_else(|| binds_section().map(|at| at + 1)) {
+                Some(at) => at,
+                None => {
+                    if lines.last().is_some_and(|line| !line.trim().is_empty()) {
+                        lines.push(String::new());
+                    }
+                    lines.push("binds {".to_string());
+                    lines.push("}".to_string());
+                    lines.len() - 1
+                }
+            };
+            let mut block = vec![format!("{}{}", NIRI_INDENT, NIRI_BLOCK_BEGIN)];
+            block.extend(self.block.iter().cloned());
+            block.push(format!("{}{}", NIRI_INDENT, NIRI_BLOCK_END));
+            lines.splice(at..at, block);
+        }
+        let mut content = lines.join("\n");
+        content.push('\n');
+        content
+    }
+}
+
+/// `content` with the niri bind for `keys` running `command` in our block,
+/// and the user's binds of the same keys slashdash-commented out (which
+/// comments the whole node, even when it spans lines). None when nothing
+/// changes, or when the user binds `command` themselves.
+fn write_niri_binding(content: &str, command: &str, keys: &str) -> Option<String> {
+    let spawn = niri_spawn(command);
+    let bind_line = niri_bind_line(keys, command);
+    let mut config = NiriConfig::parse(content);
+    if config
+        .lines
+        .iter()
+        .any(|line| !line.trim_start().starts_with('/') && line.contains(&spawn))
+    {
+        return None;
+    }
+
+    for line in config.lines.iter_mut() {
+        if is_same_niri_bind(line, keys) {
+            let indent = line.len() - line.trim_start().len();
+            *line = format!(
+                "{}/-{}{}",
+                &line[..indent],
+                &line[indent..],
+                NIRI_COMMENTED_MARKER
+            );
+        }
+    }
+    if !config.block.iter().any(|line| line.trim() == bind_line) {
+        config.block.retain(|line| !line.contains(&spawn));
+        config.block.push(format!("{}{}", NIRI_INDENT, bind_line));
+    }
+
+    let updated = config.render();
+    (updated != content).then_some(updated)
+}
+
+/// `content` without our niri bind for `command`, restoring the user's binds
+/// of the same keys that were commented out for it
+fn remove_niri_binding(content: &str, command: &str, keys: &str) -> Option<String> {
+    let spawn = niri_spawn(command);
+    let mut config = NiriConfig::parse(content);
+    config.block.retain(|line| !line.contains(&spawn));
+
+    for line in config.lines.iter_mut() {
+        let indent = line.len() - line.trim_start().len();
+        let restored = line[indent..]
+            .strip_suffix(NIRI_COMMENTED_MARKER)
+            .and_then(|rest| rest.strip_prefix("/-"));
+        if let Some(restored) = restored {
+            if is_same_niri_bind(restored, keys) {
+                *line = format!("{}{}", &line[..indent], restored);
+            }
+        }
+    }
+
+    let updated = config.render();
+    (updated != content).then_some(updated)
+}
 
 // =============================================================================
 // Error Handling
@@ -112,45 +650,171 @@ impl std::error::Error for ShortcutError {}
 
 type Result<T> = std::result::Result<T, ShortcutError>;
 
+/// How often the watchdog checks that our bindings still exist
+const SHORTCUT_WATCH_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Doublings of the watch interval after re-registering did not stick,
+/// i.e. at most 64 minutes between attempts
+const SHORTCUT_WATCH_MAX_BACKOFF: u32 = 6;
+
 // =============================================================================
 // Public API
 // =============================================================================
 
-pub fn register_global_shortcut() {
+/// Registers all shortcuts with the detected desktop environment.
+/// Returns true if at least one shortcut was registered successfully.
+pub fn register_global_shortcut() -> bool {
     let handler = detect_handler();
     println!("[ShortcutManager] Detected Environment: {}", handler.name());
+    println!(
+        "[ShortcutManager] Using command path: {}",
+        get_command_path()
+    );
 
-    let command_path = get_command_path();
-    println!("[ShortcutManager] Using command path: {}", command_path);
+    register_shortcuts(handler.as_ref(), &shortcuts())
+}
 
-    for shortcut in SHORTCUTS {
-        // Create a new config with the correct command path
-        let mut config = shortcut.clone();
-        config.command = command_path;
+fn register_shortcuts(handler: &dyn ShortcutHandler, shortcuts: &[ShortcutConfig]) -> bool {
+    let mut any_registered = false;
 
-        match handler.register(&config) {
-            Ok(_) => println!("[ShortcutManager] \u{2713} Registered '{}'", config.name),
+    for config in shortcuts {
+        match handler.register(config) {
+            Ok(_) => {
+                println!("[ShortcutManager] \u{2713} Registered '{}'", config.name);
+                any_registered = true;
+            }
             Err(e) => eprintln!("[ShortcutManager] \u{2717} Failed '{}': {}", config.name, e),
         }
     }
+
+    any_registered
 }
 
-pub fn unregister_global_shortcut() {
+/// Removes every binding of ours from the DE config, including ones left
+/// behind by earlier hotkeys. Used on uninstall (`--unregister-shortcuts`)
+/// and from the settings window. Returns the name of the environment, or
+/// an error naming it when a binding could not be removed.
+pub fn unregister_global_shortcut() -> std::result::Result<String, String> {
     let handler = detect_handler();
     println!("[ShortcutManager] Environment: {}", handler.name());
 
-    let command_path = get_command_path();
+    let unregistered = unregister_shortcuts(handler.as_ref(), &shortcuts());
+    // Leftovers are removed even when a shortcut failed
+    let cleaned = remove_leftovers(handler.as_ref(), &[]);
+    if unregistered && cleaned {
+        Ok(handler.name().to_string())
+    } else {
+        Err(format!(
+            "Some keyboard shortcuts could not be removed from {}",
+            handler.name()
+        ))
+    }
+}
 
-    for shortcut in SHORTCUTS {
-        // Create a new config with the correct command path
-        let mut config = shortcut.clone();
-        config.command = command_path;
+/// Returns false if the old bindings could not be removed
+fn remove_leftovers(handler: &dyn ShortcutHandler, keep: &[ShortcutConfig]) -> bool {
+    match handler.remove_leftovers(keep) {
+        Ok(_) => true,
+        Err(e) => {
+            eprintln!(
+                "[ShortcutManager] \u{2717} Failed to remove old bindings: {}",
+                e
+            );
+            false
+        }
+    }
+}
 
-        match handler.unregister(&config) {
+/// Returns false if any of the shortcuts could not be unregistered
+fn unregister_shortcuts(handler: &dyn ShortcutHandler, shortcuts: &[ShortcutConfig]) -> bool {
+    let mut all_unregistered = true;
+    for config in shortcuts {
+        match handler.unregister(config) {
             Ok(_) => println!("[ShortcutManager] \u{2713} Unregistered '{}'", config.name),
-            Err(e) => eprintln!("[ShortcutManager] \u{2717} Failed '{}': {}", config.name, e),
+            Err(e) => {
+                eprintln!("[ShortcutManager] \u{2717} Failed '{}': {}", config.name, e);
+                all_unregistered = false;
+            }
         }
     }
+    all_unregistered
+}
+
+/// Whether two settings bind different keys, so the DE config needs updating
+pub fn shortcuts_changed(previous: &UserSettings, current: &UserSettings) -> bool {
+    previous.global_shortcut != current.global_shortcut
+        || previous.emoji_shortcut != current.emoji_shortcut
+        || previous.quick_paste_shortcuts != current.quick_paste_shortcuts
+}
+
+/// Moves our bindings from the keys in `previous` to those in `current` in
+/// the DE config. Returns true if at least one new binding was registered.
+pub fn change_global_shortcut(previous: &UserSettings, current: &UserSettings) -> bool {
+    let handler = detect_handler();
+    println!(
+        "[ShortcutManager] Changing shortcuts from '{}'/'{}' to '{}'/'{}' ({})",
+        previous.global_shortcut,
+        previous.emoji_shortcut,
+        current.global_shortcut,
+        current.emoji_shortcut,
+        handler.name()
+    );
+
+    let current = shortcuts_for(current);
+    unregister_shortcuts(handler.as_ref(), &shortcuts_for(previous));
+    remove_leftovers(handler.as_ref(), &current);
+    register_shortcuts(handler.as_ref(), &current)
+}
+
+/// Returns the names of shortcuts that are no longer present in the DE config.
+/// Shortcuts whose state cannot be determined are not reported as missing.
+pub fn missing_shortcuts() -> Vec<String> {
+    let handler = detect_handler();
+
+    shortcuts()
+        .iter()
+        .filter(|config| matches!(handler.is_registered(config), Ok(false)))
+        .map(|config| config.name.clone())
+        .collect()
+}
+
+/// Periodically checks that our bindings still exist and re-registers them
+/// when a settings sync, reset, or distro upgrade removed them, or when
+/// registering failed at startup. Backs off while re-registering does not
+/// stick, so a config we cannot write is not rewritten every minute.
+/// Runs forever; call from a dedicated thread.
+pub fn watch_shortcut_registration() {
+    let mut failed_repairs = 0;
+    loop {
+        std::thread::sleep(SHORTCUT_WATCH_INTERVAL * 2u32.pow(failed_repairs));
+
+        let stuck = repair_shortcut_registration() && !missing_shortcuts().is_empty();
+        failed_repairs = if stuck {
+            (failed_repairs + 1).min(SHORTCUT_WATCH_MAX_BACKOFF)
+        } else {
+            0
+        };
+    }
+}
+
+/// Re-registers the bindings if any disappeared and auto-repair is enabled.
+/// Returns true if a re-registration was attempted.
+pub fn repair_shortcut_registration() -> bool {
+    if !UserSettingsManager::new().load().auto_repair_shortcut {
+        return false;
+    }
+
+    let missing = missing_shortcuts();
+    if missing.is_empty() {
+        return false;
+    }
+
+    println!(
+        "[ShortcutManager] Binding(s) disappeared from DE config: {}. Re-registering...",
+        missing.join(", ")
+    );
+    register_global_shortcut();
+    true
 }
 
 // =============================================================================
@@ -161,8 +825,19 @@ trait ShortcutHandler {
     fn name(&self) -> &str;
     fn register(&self, shortcut: &ShortcutConfig) -> Result<()>;
     fn unregister(&self, shortcut: &ShortcutConfig) -> Result<()>;
+    /// Whether the DE config still contains our binding
+    fn is_registered(&self, shortcut: &ShortcutConfig) -> Result<bool>;
+    /// Removes bindings of ours that are not in `keep`, e.g. from an earlier
+    /// hotkey or version. Most configs key our bindings by their fixed ids,
+    /// so there is nothing left behind to find.
+    fn remove_leftovers(&self, _keep: &[ShortcutConfig]) -> Result<()> {
+        Ok(())
+    }
 }
 
+/// Every id in `ShortcutConfig` starts with this
+const SHORTCUT_ID_PREFIX: &str = "win11-clipboard-history";
+
 fn detect_handler() -> Box<dyn ShortcutHandler> {
     let xdg_current = env_var("XDG_CURRENT_DESKTOP").to_lowercase();
     let xdg_session = env_var("XDG_SESSION_DESKTOP").to_lowercase();
@@ -209,6 +884,9 @@ fn detect_handler() -> Box<dyn ShortcutHandler> {
     if combined.contains("hyprland") {
         return Box::new(HyprlandHandler);
     }
+    if combined.contains("niri") {
+        return Box::new(NiriHandler);
+    }
 
     // Heuristic Fallback - check running processes for tiling WMs
     if is_process_running("i3") {
@@ -220,12 +898,15 @@ fn detect_handler() -> Box<dyn ShortcutHandler> {
     if is_process_running("hyprland") || is_process_running("Hyprland") {
         return Box::new(HyprlandHandler);
     }
+    if is_process_running("niri") {
+        return Box::new(NiriHandler);
+    }
 
     // Heuristic Fallback for traditional DEs
-    if Utils::command_exists("kwriteconfig5") || Utils::command_exists("kwriteconfig6") {
+    if command_exists("kwriteconfig5") || command_exists("kwriteconfig6") {
         return Box::new(KdeHandler);
     }
-    if Utils::command_exists("xfconf-query") {
+    if command_exists("xfconf-query") {
         return Box::new(XfceHandler);
     }
 
@@ -246,15 +927,26 @@ fn env_var(key: &str) -> String {
     env::var(key).unwrap_or_default()
 }
 
-/// Check if a line contains a $mod+v or mod4+v binding with proper word boundaries.
+/// Check if a lowercased i3/sway config line binds the same keys as `binding`
+/// (e.g. "$mod+v", also matched as "mod4+v") with proper word boundaries.
 /// This ensures we match "bindsym $mod+v" even at end of line or followed by comments.
-fn has_mod_v_binding(trimmed_line: &str) -> bool {
-    for pattern in &["$mod+v", "mod4+v"] {
-        if let Some(idx) = trimmed_line.find(pattern) {
-            // Check what follows the pattern
+fn has_binding(trimmed_line: &str, binding: &str) -> bool {
+    let binding = binding.to_lowercase();
+    let mut patterns = vec![binding.clone()];
+    if let Some(rest) = binding.strip_prefix("$mod+") {
+        patterns.push(format!("mod4+{}", rest));
+    }
+
+    for pattern in &patterns {
+        if let Some(idx) = trimmed_line.find(pattern.as_str()) {
+            // The binding must be a whole word: "bindsym $mod+v" but not "bindsym $mod+shift+v"
+            // or "bindsym $mod+v_x", so check what surrounds the pattern
+            let before = trimmed_line[..idx].chars().next_back();
             let after = trimmed_line[idx + pattern.len()..].chars().next();
             // Valid word boundaries: end of string, space, tab, comment, semicolon
-            if matches!(after, None | Some(' ') | Some('\t') | Some('#') | Some(';')) {
+            if matches!(before, Some(' ') | Some('\t'))
+                && matches!(after, None | Some(' ') | Some('\t') | Some('#') | Some(';'))
+            {
                 return true;
             }
         }
@@ -262,6 +954,28 @@ fn has_mod_v_binding(trimmed_line: &str) -> bool {
     false
 }
 
+/// Check if a lowercased Hyprland `bind = MODS, KEY, ...` line uses the same
+/// modifiers and key as `binding` ("SUPER, V"), in any order or spacing
+fn is_same_hyprland_bind(trimmed_line: &str, binding: &str) -> bool {
+    let normalize = |mods: &str, key: &str| {
+        let mut mods: Vec<String> = mods.split_whitespace().map(str::to_lowercase).collect();
+        mods.sort();
+        (mods, key.trim().to_lowercase())
+    };
+
+    let Some((_, rest)) = trimmed_line.split_once('=') else {
+        return false;
+    };
+    let mut fields = rest.split(',');
+    let (Some(mods), Some(key)) = (fields.next(), fields.next()) else {
+        return false;
+    };
+    let Some((our_mods, our_key)) = binding.split_once(',') else {
+        return false;
+    };
+    normalize(mods, key) == normalize(our_mods, our_key)
+}
+
 // =============================================================================
 // Utilities
 // =============================================================================
@@ -269,14 +983,6 @@ fn has_mod_v_binding(trimmed_line: &str) -> bool {
 struct Utils;
 
 impl Utils {
-    fn command_exists(cmd: &str) -> bool {
-        Command::new("which")
-            .arg(cmd)
-            .output()
-            .map(|o| o.status.success())
-            .unwrap_or(false)
-    }
-
     fn run(cmd: &str, args: &[&str]) -> Result<String> {
         let output = Command::new(cmd).args(args).output()?;
 
@@ -456,7 +1162,7 @@ impl GSettings {
     }
 
     fn register(&self, shortcut: &ShortcutConfig, use_array_for_binding: bool) -> Result<()> {
-        if !Utils::command_exists("gsettings") {
+        if !command_exists("gsettings") {
             return Err(ShortcutError::DependencyMissing("gsettings".into()));
         }
 
@@ -464,10 +1170,10 @@ impl GSettings {
         let schema_path = format!("{}:{}", self.binding_schema, path);
 
         // Idempotent setting
-        Utils::run("gsettings", &["set", &schema_path, "name", shortcut.name])?;
+        Utils::run("gsettings", &["set", &schema_path, "name", &shortcut.name])?;
         Utils::run(
             "gsettings",
-            &["set", &schema_path, "command", shortcut.command],
+            &["set", &schema_path, "command", &shortcut.command],
         )?;
 
         let binding_val = if use_array_for_binding {
@@ -479,7 +1185,7 @@ impl GSettings {
 
         let mut list = self.get_list()?;
         let entry_check = if self.path_prefix.contains("cinnamon") {
-            shortcut.id
+            &shortcut.id
         } else {
             &path
         };
@@ -492,7 +1198,7 @@ impl GSettings {
     }
 
     fn unregister(&self, shortcut: &ShortcutConfig) -> Result<()> {
-        if !Utils::command_exists("gsettings") {
+        if !command_exists("gsettings") {
             return Ok(());
         }
 
@@ -506,7 +1212,7 @@ impl GSettings {
         let mut list = self.get_list()?;
         let initial_len = list.len();
         let entry_check = if self.path_prefix.contains("cinnamon") {
-            shortcut.id
+            &shortcut.id
         } else {
             &path
         };
@@ -518,6 +1224,63 @@ impl GSettings {
         }
         Ok(())
     }
+
+    /// Unregisters list entries with our id prefix that are not in `keep`
+    fn remove_leftovers(&self, keep: &[ShortcutConfig]) -> Result<()> {
+        if !command_exists("gsettings") {
+            return Ok(());
+        }
+
+        let mut list = self.get_list()?;
+        let initial_len = list.len();
+        list.retain(|entry| {
+            // Paths on GNOME, bare ids on Cinnamon
+            let id = entry
+                .trim_end_matches('/')
+                .rsplit('/')
+                .next()
+                .unwrap_or(entry);
+            let leftover = id.starts_with(SHORTCUT_ID_PREFIX) && !keep.iter().any(|s| s.id == id);
+            if leftover {
+                let schema_path = format!("{}:{}/{}/", self.binding_schema, self.path_prefix, id);
+                for key in ["name", "command", "binding"] {
+                    let _ = Utils::run("gsettings", &["reset", &schema_path, key]);
+                }
+            }
+            !leftover
+        });
+
+        if list.len() != initial_len {
+            println!(
+                "[ShortcutManager] Removed {} old binding(s)",
+                initial_len - list.len()
+            );
+            self.set_list(&list)?;
+        }
+        Ok(())
+    }
+
+    fn is_registered(&self, shortcut: &ShortcutConfig) -> Result<bool> {
+        if !command_exists("gsettings") {
+            return Err(ShortcutError::DependencyMissing("gsettings".into()));
+        }
+
+        let path = format!("{}/{}/", self.path_prefix, shortcut.id);
+        let entry_check = if self.path_prefix.contains("cinnamon") {
+            &shortcut.id
+        } else {
+            &path
+        };
+
+        if !self.get_list()?.iter().any(|x| x.contains(entry_check)) {
+            return Ok(false);
+        }
+
+        // The list entry can survive a reset while the keys are wiped
+        let schema_path = format!("{}:{}", self.binding_schema, path);
+        let command = Utils::run("gsettings", &["get", &schema_path, "command"])?;
+        Ok(command.trim_matches('\'') == shortcut.command)
+    }
 }
 
 // Wrappers
@@ -532,6 +1295,12 @@ impl ShortcutHandler for GnomeHandler {
     fn unregister(&self, s: &ShortcutConfig) -> Result<()> {
         GSettings::new_gnome().unregister(s)
     }
+    fn is_registered(&self, s: &ShortcutConfig) -> Result<bool> {
+        GSettings::new_gnome().is_registered(s)
+    }
+    fn remove_leftovers(&self, keep: &[ShortcutConfig]) -> Result<()> {
+        GSettings::new_gnome().remove_leftovers(keep)
+    }
 }
 
 struct CinnamonHandler;
@@ -545,6 +1314,12 @@ impl ShortcutHandler for CinnamonHandler {
     fn unregister(&self, s: &ShortcutConfig) -> Result<()> {
         GSettings::new_cinnamon().unregister(s)
     }
+    fn is_registered(&self, s: &ShortcutConfig) -> Result<bool> {
+        GSettings::new_cinnamon().is_registered(s)
+    }
+    fn remove_leftovers(&self, keep: &[ShortcutConfig]) -> Result<()> {
+        GSettings::new_cinnamon().remove_leftovers(keep)
+    }
 }
 
 // --- KDE Plasma Logic ---
@@ -666,6 +1441,13 @@ impl ShortcutHandler for KdeHandler {
         Self::reload_kde();
         Ok(())
     }
+
+    fn is_registered(&self, s: &ShortcutConfig) -> Result<bool> {
+        let path = Self::get_config_path()?;
+        let section_name = format!("Data_{}", s.id.replace('-', "_"));
+        let content = fs::read_to_string(path).unwrap_or_default();
+        Ok(content.contains(&format!("[{}]", section_name)))
+    }
 }
 
 // --- XFCE ---
@@ -677,7 +1459,7 @@ impl ShortcutHandler for XfceHandler {
     }
 
     fn register(&self, s: &ShortcutConfig) -> Result<()> {
-        if !Utils::command_exists("xfconf-query") {
+        if !command_exists("xfconf-query") {
             return Err(ShortcutError::DependencyMissing("xfconf-query".into()));
         }
         let property = format!("/commands/custom/{}", s.xfce_binding);
@@ -701,7 +1483,7 @@ impl ShortcutHandler for XfceHandler {
                     "-t",
                     "string",
                     "-s",
-                    s.command,
+                    &s.command,
                 ],
             )?;
         }
@@ -709,7 +1491,7 @@ impl ShortcutHandler for XfceHandler {
     }
 
     fn unregister(&self, s: &ShortcutConfig) -> Result<()> {
-        if !Utils::command_exists("xfconf-query") {
+        if !command_exists("xfconf-query") {
             return Ok(());
         }
         let property = format!("/commands/custom/{}", s.xfce_binding);
@@ -720,6 +1502,74 @@ impl ShortcutHandler for XfceHandler {
         );
         Ok(())
     }
+
+    fn is_registered(&self, s: &ShortcutConfig) -> Result<bool> {
+        if !command_exists("xfconf-query") {
+            return Err(ShortcutError::DependencyMissing("xfconf-query".into()));
+        }
+        let property = format!("/commands/custom/{}", s.xfce_binding);
+        let exists = Command::new("xfconf-query")
+            .args(["-c", "xfce4-keyboard-shortcuts", "-p", &property])
+            .output()
+            .map(|o| o.status.success())
+            .unwrap_or(false);
+        Ok(exists)
+    }
+
+    /// XFCE keys custom commands by their key combination, so every earlier
+    /// hotkey leaves a property running our command behind
+    fn remove_leftovers(&self, keep: &[ShortcutConfig]) -> Result<()> {
+        if !command_exists("xfconf-query") {
+            return Ok(());
+        }
+        let listing = Utils::run(
+            "xfconf-query",
+            &[
+                "-c",
+                "xfce4-keyboard-shortcuts",
+                "-p",
+                "/commands/custom",
+                "-l",
+                "-v",
+            ],
+        )?;
+
+        for (property, command) in xfce_leftovers(&listing, get_command_path(), keep) {
+            println!(
+                "[ShortcutManager] Removing old binding {} ({})",
+                property, command
+            );
+            let _ = Utils::run(
+                "xfconf-query",
+                &["-c", "xfce4-keyboard-shortcuts", "-p", property, "-r"],
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Properties in `xfconf-query -l -v` output ("<property>  <value>" lines)
+/// that run our binary but are not one of the `keep` bindings
+fn xfce_leftovers<'a>(
+    listing: &'a str,
+    command_path: &str,
+    keep: &[ShortcutConfig],
+) -> Vec<(&'a str, &'a str)> {
+    listing
+        .lines()
+        .filter_map(|line| {
+            let (property, command) = line.trim().split_once(char::is_whitespace)?;
+            Some((property, command.trim()))
+        })
+        .filter(|(property, command)| {
+            property.starts_with("/commands/custom/")
+                && (*command == command_path || command.starts_with(&format!("{} ", command_path)))
+                && !keep.iter().any(|s| {
+                    *property == format!("/commands/custom/{}", s.xfce_binding)
+                        && *command == s.command
+                })
+        })
+        .collect()
 }
 
 // --- MATE ---
@@ -730,7 +1580,7 @@ impl ShortcutHandler for MateHandler {
         "MATE"
     }
     fn register(&self, s: &ShortcutConfig) -> Result<()> {
-        if !Utils::command_exists("gsettings") {
+        if !command_exists("gsettings") {
             return Err(ShortcutError::DependencyMissing("gsettings".into()));
         }
 
@@ -755,7 +1605,7 @@ impl ShortcutHandler for MateHandler {
                         "set",
                         "org.mate.Marco.keybinding-commands",
                         &cmd_key,
-                        s.command,
+                        &s.command,
                     ],
                 )?;
                 Utils::run(
@@ -764,7 +1614,7 @@ impl ShortcutHandler for MateHandler {
                         "set",
                         "org.mate.Marco.global-keybindings",
                         &binding_key,
-                        s.gnome_binding,
+                        &s.gnome_binding,
                     ],
                 )?;
                 return Ok(());
@@ -776,7 +1626,7 @@ impl ShortcutHandler for MateHandler {
     }
 
     fn unregister(&self, s: &ShortcutConfig) -> Result<()> {
-        if !Utils::command_exists("gsettings") {
+        if !command_exists("gsettings") {
             return Ok(());
         }
         for i in 1..=12 {
@@ -786,7 +1636,7 @@ impl ShortcutHandler for MateHandler {
                 &["get", "org.mate.Marco.keybinding-commands", &cmd_key],
             )?;
 
-            if current.contains(s.command) {
+            if current.contains(&s.command) {
                 Utils::run(
                     "gsettings",
                     &["reset", "org.mate.Marco.keybinding-commands", &cmd_key],
@@ -803,58 +1653,339 @@ impl ShortcutHandler for MateHandler {
         }
         Ok(())
     }
+
+    fn is_registered(&self, s: &ShortcutConfig) -> Result<bool> {
+        if !command_exists("gsettings") {
+            return Err(ShortcutError::DependencyMissing("gsettings".into()));
+        }
+        for i in 1..=12 {
+            let cmd_key = format!("command-{}", i);
+            let current = Utils::run(
+                "gsettings",
+                &["get", "org.mate.Marco.keybinding-commands", &cmd_key],
+            )?;
+            if current.trim_matches('\'') == s.command {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
 }
 
 // --- COSMIC ---
 
-struct CosmicHandler;
-impl ShortcutHandler for CosmicHandler {
-    fn name(&self) -> &str {
-        "COSMIC"
+/// cosmic-config keeps each key of a config in its own file holding a RON
+/// value. Custom shortcuts are the `custom` key, a map of binding to action,
+/// which COSMIC watches and applies as soon as it changes.
+const COSMIC_SHORTCUTS_CONFIG: &str = "cosmic/com.system76.CosmicSettings.Shortcuts/v1";
+
+/// Older versions wrapped the map in `(shortcuts: ...)`, which COSMIC could
+/// not read
+const COSMIC_LEGACY_PREFIX: &str = "(shortcuts:";
+
+/// Shortcuts of the user that one of ours replaced, kept in the same map
+/// format until unregistering puts them back. cosmic-config rewrites the
+/// map without comments, so they cannot stay in it commented out the way
+/// sway and i3 bindings do.
+const COSMIC_DISPLACED_FILE: &str = "win11-clipboard-history/cosmic-displaced-shortcuts.ron";
+
+/// Splits RON `text` at `separator` outside of brackets and strings
+fn split_ron_top_level(text: &str, separator: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut start = 0;
+
+    for (index, c) in text.char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth = depth.saturating_sub(1),
+            _ if c == separator && depth == 0 => {
+                parts.push(&text[start..index]);
+                start = index + c.len_utf8();
+            }
+            _ => {}
+        }
     }
+    parts.push(&text[start..]);
+    parts
+}
 
-    fn register(&self, s: &ShortcutConfig) -> Result<()> {
+/// One `binding: action` pair of the custom shortcuts map, as written
+#[derive(Debug, Clone, PartialEq)]
+struct CosmicShortcut {
+    binding: String,
+    action: String,
+}
+
+impl CosmicShortcut {
+    fn new(s: &ShortcutConfig) -> Self {
+        Self {
+            binding: format!(
+                "(modifiers: [{}], key: {:?}, description: Some({:?}))",
+                s.cosmic_mods, s.cosmic_key, s.name
+            ),
+            action: cosmic_spawn(&s.command),
+        }
+    }
+
+    /// Modifiers (sorted) and key of the binding, lowercased, so bindings
+    /// written by COSMIC Settings compare equal to ours
+    fn keys(&self) -> (Vec<String>, String) {
+        let field = |name: &str| {
+            split_ron_top_level(
+                self.binding
+                    .trim()
+                    .trim_start_matches('(')
+                    .trim_end_matches(')'),
+                ',',
+            )
+            .into_iter()
+            .find_map(|part| {
+                let (field, value) = part.split_once(':')?;
+                (field.trim() == name).then(|| value.trim().to_lowercase())
+            })
+            .unwrap_or_default()
+        };
+        let mut modifiers: Vec<String> = field("modifiers")
+            .trim_matches(|c| c == '[' || c == ']')
+            .split(',')
+            .map(|modifier| modifier.trim().to_string())
+            .filter(|modifier| !modifier.is_empty())
+            .collect();
+        modifiers.sort();
+        let key = field("key");
+        let key = key
+            .strip_prefix("some(")
+            .and_then(|key| key.strip_suffix(')'))
+            .unwrap_or(&key)
+            .trim_matches('"')
+            .to_string();
+        (modifiers, key)
+    }
+}
+
+fn cosmic_spawn(command: &str) -> String {
+    format!("Spawn({:?})", command)
+}
+
+/// Entries of the custom shortcuts map. An empty file is an empty map;
+/// anything that isn't a map is an error rather than something to overwrite.
+fn parse_cosmic_shortcuts(content: &str) -> Result<Vec<CosmicShortcut>> {
+    let invalid = || ShortcutError::ParseError("Invalid COSMIC shortcuts config".into());
+    let content = content.trim();
+    if content.is_empty() {
+        return Ok(Vec::new());
+    }
+    let content = content
+        .strip_prefix(COSMIC_LEGACY_PREFIX)
+        .and_then(|rest| rest.trim_end().strip_suffix(')'))
+        .map(str::trim)
+        .unwrap_or(content);
+    let inner = content
+        .strip_prefix('{')
+        .and_then(|rest| rest.strip_suffix('}'))
+        .ok_or_else(invalid)?;
+
+    split_ron_top_level(inner, ',')
+        .into_iter()
+        .filter(|entry| !entry.trim().is_empty())
+        .map(|entry| match split_ron_top_level(entry, ':').as_slice() {
+            [binding, action] => Ok(CosmicShortcut {
+                binding: binding.trim().to_string(),
+                action: action.trim().to_string(),
+            }),
+            _ => Err(invalid()),
+        })
+        .collect()
+}
+
+fn render_cosmic_shortcuts(shortcuts: &[CosmicShortcut]) -> String {
+    let mut content = String::from("{\n");
+    for shortcut in shortcuts {
+        content.push_str(&format!("    {}: {},\n", shortcut.binding, shortcut.action));
+    }
+    content.push('}');
+    content
+}
+
+/// `content` with `shortcut` in the map, replacing our earlier binding for
+/// the same command and any other shortcut on the same keys. None when it is
+/// already there (and the file is one COSMIC can read).
+fn write_cosmic_shortcut(content: &str, shortcut: &CosmicShortcut) -> Result<Option<String>> {
+    let mut shortcuts = parse_cosmic_shortcuts(content)?;
+    let keys = shortcut.keys();
+    let legacy = content.trim_start().starts_with(COSMIC_LEGACY_PREFIX);
+    if !legacy
+        && shortcuts
+            .iter()
+            .any(|other| other.action == shortcut.action && other.keys() == keys)
+    {
+        return Ok(None);
+    }
+
+    shortcuts.retain(|other| {
+        let replaced = other.action == shortcut.action || other.keys() == keys;
+        if replaced && other.action != shortcut.action {
+            println!(
+                "[ShortcutManager] Replacing COSMIC shortcut {}: {}",
+                other.binding, other.action
+            );
+        }
+        !replaced
+    });
+    shortcuts.push(shortcut.clone());
+    Ok(Some(render_cosmic_shortcuts(&shortcuts)))
+}
+
+/// Shortcuts in `content` that `write_cosmic_shortcut` would replace
+/// because they are on the same keys
+fn displaced_cosmic_shortcuts(
+    content: &str,
+    shortcut: &CosmicShortcut,
+) -> Result<Vec<CosmicShortcut>> {
+    let keys = shortcut.keys();
+    Ok(parse_cosmic_shortcuts(content)?
+        .into_iter()
+        .filter(|other| other.action != shortcut.action && other.keys() == keys)
+        .collect())
+}
+
+/// `content` with the `saved` shortcuts back whose keys are free again,
+/// None when none are
+fn restore_cosmic_shortcuts(content: &str, saved: &[CosmicShortcut]) -> Result<Option<String>> {
+    let mut shortcuts = parse_cosmic_shortcuts(content)?;
+    let before = shortcuts.len();
+    for shortcut in saved {
+        let keys = shortcut.keys();
+        if !shortcuts.iter().any(|other| other.keys() == keys) {
+            println!(
+                "[ShortcutManager] Restoring COSMIC shortcut {}: {}",
+                shortcut.binding, shortcut.action
+            );
+            shortcuts.push(shortcut.clone());
+        }
+    }
+    Ok((shortcuts.len() != before).then(|| render_cosmic_shortcuts(&shortcuts)))
+}
+
+/// `content` without shortcuts running `command`, None when there are none
+fn remove_cosmic_shortcut(content: &str, command: &str) -> Result<Option<String>> {
+    let action = cosmic_spawn(command);
+    let mut shortcuts = parse_cosmic_shortcuts(content)?;
+    let before = shortcuts.len();
+    shortcuts.retain(|shortcut| shortcut.action != action);
+    Ok((shortcuts.len() != before).then(|| render_cosmic_shortcuts(&shortcuts)))
+}
+
+struct CosmicHandler;
+impl CosmicHandler {
+    fn custom_shortcuts_path() -> Result<PathBuf> {
         let home = env::var("HOME")
             .map_err(|_| ShortcutError::UnsupportedEnvironment("HOME not set".into()))?;
-        let path = PathBuf::from(home)
-            .join(".config/cosmic/com.system76.CosmicSettings.Shortcuts/v1/custom");
 
-        // Naive but safer append
-        let entry = format!(
-            "(modifiers: [{}], key: \"{}\"): Spawn(\"{}\"),",
-            s.cosmic_mods, s.cosmic_key, s.command
-        );
+        let xdg_config =
+            env::var("XDG_CONFIG_HOME").unwrap_or_else(|_| format!("{}/.config", home));
 
-        Utils::modify_file_atomic(&path, |content| {
-            if content.contains(&entry) {
-                return Ok(None);
-            }
+        Ok(PathBuf::from(&xdg_config)
+            .join(COSMIC_SHORTCUTS_CONFIG)
+            .join("custom"))
+    }
 
-            let mut new_content = content.clone();
-            if new_content.trim().is_empty() {
-                new_content = format!("(shortcuts: {{\n    {}\n}})", entry);
-            } else {
-                // Find closing brace of 'shortcuts: { ... }'
-                match new_content.rfind('}') {
-                    Some(pos) => {
-                        new_content.insert_str(pos, &format!("\n    {}\n", entry));
-                    }
-                    None => {
-                        return Err(ShortcutError::ParseError(
-                            "Invalid COSMIC config format".into(),
-                        ))
+    fn displaced_shortcuts_path() -> Result<PathBuf> {
+        dirs::config_dir()
+            .map(|dir| dir.join(COSMIC_DISPLACED_FILE))
+            .ok_or_else(|| ShortcutError::UnsupportedEnvironment("No config directory".into()))
+    }
+
+    fn saved_shortcuts() -> Result<Vec<CosmicShortcut>> {
+        let content = fs::read_to_string(Self::displaced_shortcuts_path()?).unwrap_or_default();
+        parse_cosmic_shortcuts(&content)
+    }
+
+    /// Rewrites the side file with `update` applied to the saved shortcuts
+    fn update_saved_shortcuts(update: impl FnOnce(&mut Vec<CosmicShortcut>)) -> Result<()> {
+        Utils::modify_file_atomic(&Self::displaced_shortcuts_path()?, |content| {
+            let mut saved = parse_cosmic_shortcuts(&content)?;
+            let before = saved.clone();
+            update(&mut saved);
+            Ok((saved != before).then(|| render_cosmic_shortcuts(&saved)))
+        })?;
+        Ok(())
+    }
+}
+
+impl ShortcutHandler for CosmicHandler {
+    fn name(&self) -> &str {
+        "COSMIC"
+    }
+
+    fn register(&self, s: &ShortcutConfig) -> Result<()> {
+        let path = Self::custom_shortcuts_path()?;
+        let shortcut = CosmicShortcut::new(s);
+
+        let mut displaced = Vec::new();
+        Utils::modify_file_atomic(&path, |content| {
+            displaced = displaced_cosmic_shortcuts(&content, &shortcut)?;
+            write_cosmic_shortcut(&content, &shortcut)
+        })?;
+        if !displaced.is_empty() {
+            Self::update_saved_shortcuts(|saved| {
+                for shortcut in displaced {
+                    if !saved.contains(&shortcut) {
+                        saved.push(shortcut);
                     }
                 }
-            }
-            Ok(Some(new_content))
-        })?;
+            })?;
+        }
         Ok(())
     }
 
-    fn unregister(&self, _s: &ShortcutConfig) -> Result<()> {
-        // Requires real RON parser
+    fn unregister(&self, s: &ShortcutConfig) -> Result<()> {
+        let path = Self::custom_shortcuts_path()?;
+
+        if !path.exists() {
+            return Ok(());
+        }
+
+        let keys = CosmicShortcut::new(s).keys();
+        let restore: Vec<CosmicShortcut> = Self::saved_shortcuts()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|saved| saved.keys() == keys)
+            .collect();
+
+        Utils::modify_file_atomic(&path, |content| {
+            let removed = remove_cosmic_shortcut(&content, &s.command)?;
+            let content = removed.as_deref().unwrap_or(&content);
+            Ok(restore_cosmic_shortcuts(content, &restore)?.or(removed))
+        })?;
+        if !restore.is_empty() {
+            Self::update_saved_shortcuts(|saved| saved.retain(|other| other.keys() != keys))?;
+        }
         Ok(())
     }
+
+    fn is_registered(&self, s: &ShortcutConfig) -> Result<bool> {
+        let content = fs::read_to_string(Self::custom_shortcuts_path()?).unwrap_or_default();
+        let shortcut = CosmicShortcut::new(s);
+        let keys = shortcut.keys();
+        Ok(parse_cosmic_shortcuts(&content)
+            .unwrap_or_default()
+            .iter()
+            .any(|other| other.action == shortcut.action && other.keys() == keys))
+    }
 }
 
 // --- LXQt ---
@@ -871,7 +2002,7 @@ impl ShortcutHandler for LxqtHandler {
         let path = PathBuf::from(home).join(".config/lxqt/globalkeyshortcuts.conf");
 
         // LXQt uses INI format for shortcuts
-        let section = format!("Meta+V%2F{}", s.id);
+        let section = format!("{}%2F{}", s.kde_binding, s.id);
         let entry = format!(
             "\n[{}]\nComment={}\nEnabled=true\nExec={}",
             section, s.name, s.command
@@ -898,7 +2029,7 @@ impl ShortcutHandler for LxqtHandler {
             return Ok(());
         }
 
-        let section = format!("Meta+V%2F{}", s.id);
+        let section = format!("{}%2F{}", s.kde_binding, s.id);
 
         Utils::modify_file_atomic(&path, |content| {
             if !content.contains(&format!("[{}]", section)) {
@@ -925,6 +2056,15 @@ impl ShortcutHandler for LxqtHandler {
         })?;
         Ok(())
     }
+
+    fn is_registered(&self, s: &ShortcutConfig) -> Result<bool> {
+        let home = env::var("HOME")
+            .map_err(|_| ShortcutError::UnsupportedEnvironment("HOME not set".into()))?;
+        let path = PathBuf::from(home).join(".config/lxqt/globalkeyshortcuts.conf");
+        let section = format!("{}%2F{}", s.kde_binding, s.id);
+        let content = fs::read_to_string(path).unwrap_or_default();
+        Ok(content.contains(&format!("[{}]", section)))
+    }
 }
 
 // --- LXDE (Openbox) ---
@@ -958,12 +2098,12 @@ impl ShortcutHandler for LxdeHandler {
 
         // The keybind XML to add
         let keybind = format!(
-            r#"    <keybind key="W-v">
+            r#"    <keybind key="{}">
       <action name="Execute">
         <command>{}</command>
       </action>
     </keybind>"#,
-            s.command
+            s.openbox_binding, s.command
         );
 
         Utils::modify_file_atomic(&path, |content| {
@@ -1012,12 +2152,12 @@ impl ShortcutHandler for LxdeHandler {
             // Remove the keybind block - this is a simplified approach
             // A proper XML parser would be better but adds dependency
             let pattern = format!(
-                r#"    <keybind key="W-v">
+                r#"    <keybind key="{}">
       <action name="Execute">
         <command>{}</command>
       </action>
     </keybind>"#,
-                s.command
+                s.openbox_binding, s.command
             );
 
             let new_content = content.replace(&pattern, "");
@@ -1029,6 +2169,21 @@ impl ShortcutHandler for LxdeHandler {
         })?;
         Ok(())
     }
+
+    fn is_registered(&self, s: &ShortcutConfig) -> Result<bool> {
+        let home = env::var("HOME")
+            .map_err(|_| ShortcutError::UnsupportedEnvironment("HOME not set".into()))?;
+
+        let path = PathBuf::from(&home).join(".config/openbox/lxde-rc.xml");
+        let path = if path.exists() {
+            path
+        } else {
+            PathBuf::from(&home).join(".config/openbox/rc.xml")
+        };
+
+        let content = fs::read_to_string(path).unwrap_or_default();
+        Ok(content.contains(&format!("<command>{}</command>", s.command)))
+    }
 }
 
 // --- i3 Window Manager ---
@@ -1071,39 +2226,16 @@ impl ShortcutHandler for I3Handler {
 
         // i3 binding format: bindsym $mod+v exec command
         let binding_line = format!("bindsym {} exec {}", s.i3_binding, s.command);
+        let same_keys =
+            |line: &str| line.starts_with("bindsym") && has_binding(line, &s.i3_binding);
 
         let modified = Utils::modify_file_atomic(&path, |content| {
-            // Check if already registered
-            if content.contains(s.command) {
-                return Ok(None);
-            }
-
-            // Check for existing $mod+v binding and comment it out
-            let mut lines: Vec<String> = content.lines().map(String::from).collect();
-            let mut had_existing = false;
-
-            for line in lines.iter_mut() {
-                let trimmed = line.trim().to_lowercase();
-                // Skip if already a comment
-                if trimmed.starts_with('#') {
-                    continue;
-                }
-                // Check for existing mod+v bindings (word boundary check)
-                if trimmed.starts_with("bindsym") && has_mod_v_binding(&trimmed) {
-                    *line = format!("# {} # Commented by win11-clipboard-history", line);
-                    had_existing = true;
-                }
-            }
-
-            // Add our binding at the end
-            lines.push("\n# Clipboard History (added by win11-clipboard-history)".to_string());
-            lines.push(binding_line.clone());
-
-            if had_existing {
-                println!("[i3Handler] Commented out existing $mod+v binding(s)");
-            }
-
-            Ok(Some(lines.join("\n")))
+            Ok(write_managed_binding(
+                &content,
+                &s.command,
+                &binding_line,
+                same_keys,
+            ))
         })?;
 
         // Reload i3 only after file was successfully written
@@ -1120,40 +2252,10 @@ impl ShortcutHandler for I3Handler {
             return Ok(());
         }
 
+        let same_keys =
+            |line: &str| line.starts_with("bindsym") && has_binding(line, &s.i3_binding);
         let modified = Utils::modify_file_atomic(&path, |content| {
-            if !content.contains(s.command) {
-                return Ok(None);
-            }
-
-            let lines: Vec<&str> = content.lines().collect();
-            let mut new_lines: Vec<String> = Vec::new();
-            let mut skip_comment = false;
-
-            for line in lines {
-                // Skip our comment line
-                if line.contains("# Clipboard History (added by win11-clipboard-history)") {
-                    skip_comment = true;
-                    continue;
-                }
-                // Skip our binding line
-                if skip_comment && line.contains(s.command) {
-                    skip_comment = false;
-                    continue;
-                }
-                skip_comment = false;
-
-                // Restore commented out bindings
-                if line.contains("# Commented by win11-clipboard-history") {
-                    let restored = line
-                        .replace("# ", "")
-                        .replace(" # Commented by win11-clipboard-history", "");
-                    new_lines.push(restored);
-                } else {
-                    new_lines.push(line.to_string());
-                }
-            }
-
-            Ok(Some(new_lines.join("\n")))
+            Ok(remove_managed_binding(&content, &s.command, same_keys))
         })?;
 
         // Reload i3 only after file was successfully written
@@ -1162,6 +2264,11 @@ impl ShortcutHandler for I3Handler {
         }
         Ok(())
     }
+
+    fn is_registered(&self, s: &ShortcutConfig) -> Result<bool> {
+        let content = fs::read_to_string(Self::get_config_path()?).unwrap_or_default();
+        Ok(content.contains(&format!("bindsym {} exec {}", s.i3_binding, s.command)))
+    }
 }
 
 // --- Sway ---
@@ -1200,35 +2307,16 @@ impl ShortcutHandler for SwayHandler {
         let path = Self::get_config_path()?;
 
         let binding_line = format!("bindsym {} exec {}", s.sway_binding, s.command);
+        let same_keys =
+            |line: &str| line.starts_with("bindsym") && has_binding(line, &s.sway_binding);
 
         let modified = Utils::modify_file_atomic(&path, |content| {
-            if content.contains(s.command) {
-                return Ok(None);
-            }
-
-            let mut lines: Vec<String> = content.lines().map(String::from).collect();
-            let mut had_existing = false;
-
-            for line in lines.iter_mut() {
-                let trimmed = line.trim().to_lowercase();
-                if trimmed.starts_with('#') {
-                    continue;
-                }
-                // Check for existing mod+v bindings (word boundary check)
-                if trimmed.starts_with("bindsym") && has_mod_v_binding(&trimmed) {
-                    *line = format!("# {} # Commented by win11-clipboard-history", line);
-                    had_existing = true;
-                }
-            }
-
-            lines.push("\n# Clipboard History (added by win11-clipboard-history)".to_string());
-            lines.push(binding_line.clone());
-
-            if had_existing {
-                println!("[SwayHandler] Commented out existing $mod+v binding(s)");
-            }
-
-            Ok(Some(lines.join("\n")))
+            Ok(write_managed_binding(
+                &content,
+                &s.command,
+                &binding_line,
+                same_keys,
+            ))
         })?;
 
         // Reload Sway only after file was successfully written
@@ -1245,37 +2333,10 @@ impl ShortcutHandler for SwayHandler {
             return Ok(());
         }
 
+        let same_keys =
+            |line: &str| line.starts_with("bindsym") && has_binding(line, &s.sway_binding);
         let modified = Utils::modify_file_atomic(&path, |content| {
-            if !content.contains(s.command) {
-                return Ok(None);
-            }
-
-            let lines: Vec<&str> = content.lines().collect();
-            let mut new_lines: Vec<String> = Vec::new();
-            let mut skip_comment = false;
-
-            for line in lines {
-                if line.contains("# Clipboard History (added by win11-clipboard-history)") {
-                    skip_comment = true;
-                    continue;
-                }
-                if skip_comment && line.contains(s.command) {
-                    skip_comment = false;
-                    continue;
-                }
-                skip_comment = false;
-
-                if line.contains("# Commented by win11-clipboard-history") {
-                    let restored = line
-                        .replace("# ", "")
-                        .replace(" # Commented by win11-clipboard-history", "");
-                    new_lines.push(restored);
-                } else {
-                    new_lines.push(line.to_string());
-                }
-            }
-
-            Ok(Some(new_lines.join("\n")))
+            Ok(remove_managed_binding(&content, &s.command, same_keys))
         })?;
 
         // Reload Sway only after file was successfully written
@@ -1284,6 +2345,11 @@ impl ShortcutHandler for SwayHandler {
         }
         Ok(())
     }
+
+    fn is_registered(&self, s: &ShortcutConfig) -> Result<bool> {
+        let content = fs::read_to_string(Self::get_config_path()?).unwrap_or_default();
+        Ok(content.contains(&format!("bindsym {} exec {}", s.sway_binding, s.command)))
+    }
 }
 
 // --- Hyprland ---
@@ -1312,39 +2378,80 @@ impl ShortcutHandler for HyprlandHandler {
 
         // Hyprland format: bind = SUPER, V, exec, command
         let binding_line = format!("bind = {}, exec, {}", s.hyprland_binding, s.command);
+        let same_keys = |line: &str| {
+            line.starts_with("bind") && is_same_hyprland_bind(line, &s.hyprland_binding)
+        };
 
+        // Hyprland auto-reloads config, no explicit reload needed
         Utils::modify_file_atomic(&path, |content| {
-            if content.contains(s.command) {
-                return Ok(None);
-            }
+            Ok(write_managed_binding(
+                &content,
+                &s.command,
+                &binding_line,
+                same_keys,
+            ))
+        })?;
+        Ok(())
+    }
 
-            let mut lines: Vec<String> = content.lines().map(String::from).collect();
-            let mut modified = false;
+    fn unregister(&self, s: &ShortcutConfig) -> Result<()> {
+        let path = Self::get_config_path()?;
 
-            for line in lines.iter_mut() {
-                let trimmed = line.trim().to_lowercase();
-                if trimmed.starts_with('#') {
-                    continue;
-                }
-                // Check for existing SUPER, V bindings
-                if trimmed.starts_with("bind")
-                    && trimmed.contains("super")
-                    && (trimmed.contains(", v,") || trimmed.contains(",v,"))
-                {
-                    *line = format!("# {} # Commented by win11-clipboard-history", line);
-                    modified = true;
-                }
-            }
+        if !path.exists() {
+            return Ok(());
+        }
+
+        let same_keys = |line: &str| {
+            line.starts_with("bind") && is_same_hyprland_bind(line, &s.hyprland_binding)
+        };
+        Utils::modify_file_atomic(&path, |content| {
+            Ok(remove_managed_binding(&content, &s.command, same_keys))
+        })?;
+        Ok(())
+    }
+
+    fn is_registered(&self, s: &ShortcutConfig) -> Result<bool> {
+        let content = fs::read_to_string(Self::get_config_path()?).unwrap_or_default();
+        Ok(content.contains(&format!(
+            "bind = {}, exec, {}",
+            s.hyprland_binding, s.command
+        )))
+    }
+}
 
-            lines.push("\n# Clipboard History (added by win11-clipboard-history)".to_string());
-            lines.push(binding_line.clone());
+// --- Niri ---
 
-            if modified {
-                println!("[HyprlandHandler] Commented out existing SUPER+V binding(s)");
+struct NiriHandler;
+impl NiriHandler {
+    fn get_config_path() -> Result<PathBuf> {
+        // Niri reads this instead of the default path when set
+        if let Ok(path) = env::var("NIRI_CONFIG") {
+            if !path.is_empty() {
+                return Ok(PathBuf::from(path));
             }
+        }
 
-            // Hyprland auto-reloads config, no explicit reload needed
-            Ok(Some(lines.join("\n")))
+        let home = env::var("HOME")
+            .map_err(|_| ShortcutError::UnsupportedEnvironment("HOME not set".into()))?;
+
+        let xdg_config =
+            env::var("XDG_CONFIG_HOME").unwrap_or_else(|_| format!("{}/.config", home));
+
+        Ok(PathBuf::from(&xdg_config).join("niri/config.kdl"))
+    }
+}
+
+impl ShortcutHandler for NiriHandler {
+    fn name(&self) -> &str {
+        "Niri"
+    }
+
+    fn register(&self, s: &ShortcutConfig) -> Result<()> {
+        let path = Self::get_config_path()?;
+
+        // Niri watches its config and reloads it on change
+        Utils::modify_file_atomic(&path, |content| {
+            Ok(write_niri_binding(&content, &s.command, &s.niri_binding))
         })?;
         Ok(())
     }
@@ -1357,37 +2464,263 @@ impl ShortcutHandler for HyprlandHandler {
         }
 
         Utils::modify_file_atomic(&path, |content| {
-            if !content.contains(s.command) {
-                return Ok(None);
-            }
+            Ok(remove_niri_binding(&content, &s.command, &s.niri_binding))
+        })?;
+        Ok(())
+    }
 
-            let lines: Vec<&str> = content.lines().collect();
-            let mut new_lines: Vec<String> = Vec::new();
-            let mut skip_comment = false;
+    fn is_registered(&self, s: &ShortcutConfig) -> Result<bool> {
+        let content = fs::read_to_string(Self::get_config_path()?).unwrap_or_default();
+        Ok(content.contains(&niri_bind_line(&s.niri_binding, &s.command)))
+    }
+}
 
-            for line in lines {
-                if line.contains("# Clipboard History (added by win11-clipboard-history)") {
-                    skip_comment = true;
-                    continue;
-                }
-                if skip_comment && line.contains(s.command) {
-                    skip_comment = false;
-                    continue;
-                }
-                skip_comment = false;
-
-                if line.contains("# Commented by win11-clipboard-history") {
-                    let restored = line
-                        .replace("# ", "")
-                        .replace(" # Commented by win11-clipboard-history", "");
-                    new_lines.push(restored);
-                } else {
-                    new_lines.push(line.to_string());
-                }
-            }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_accelerator_formats() {
+        let keys = Accelerator::parse("super + shift + .").unwrap();
+        assert_eq!(keys.gnome(), "<Super><Shift>period");
+        assert_eq!(keys.kde(), "Meta+Shift+.");
+        assert_eq!(keys.i3(), "$mod+Shift+period");
+        assert_eq!(keys.hyprland(), "SUPER SHIFT, period");
+        assert_eq!(keys.niri(), "Mod+Shift+period");
+        assert_eq!(keys.openbox(), "W-S-period");
+
+        // The defaults keep the bindings written by earlier versions
+        let keys = Accelerator::parse("Super+V").unwrap();
+        assert_eq!(keys, Accelerator::primary_default());
+        assert_eq!(keys.kde(), "Meta+V");
+        assert_eq!(keys.hyprland(), "SUPER, V");
+        assert_eq!(keys.niri(), "Mod+V");
+        assert_eq!(Accelerator::alternative().xfce(), "<Primary><Alt>v");
+        assert_eq!(Accelerator::alternative().i3(), "Ctrl+Mod1+v");
+
+        assert!(Accelerator::parse("Hyper+V").is_none());
+        assert!(Accelerator::parse("Super+").is_none());
+        assert_eq!(Accelerator::parse("Super+Period").unwrap().kde(), "Meta+.");
+    }
+
+    #[test]
+    fn test_emoji_shortcut_is_optional() {
+        let mut settings = UserSettings::default();
+        let shortcuts = shortcuts_for(&settings);
+        assert_eq!(shortcuts.len(), 3);
+        assert!(shortcuts[2].command.ends_with(" --emoji"));
+        assert_eq!(shortcuts[2].gnome_binding, "<Super>period");
+
+        settings.emoji_shortcut.clear();
+        assert_eq!(shortcuts_for(&settings).len(), 2);
+        settings.emoji_shortcut = "Super+V".to_string();
+        assert_eq!(shortcuts_for(&settings).len(), 2);
+    }
+
+    #[test]
+    fn test_quick_paste_shortcuts() {
+        let mut settings = UserSettings {
+            quick_paste_shortcuts: true,
+            ..Default::default()
+        };
+        settings.emoji_shortcut.clear();
+        let shortcuts = shortcuts_for(&settings);
+        assert_eq!(shortcuts.len(), 2 + QUICK_PASTE_COUNT);
+        assert_eq!(shortcuts[2].xfce_binding, "<Super><Primary>1");
+        assert!(shortcuts[10].command.ends_with(" --paste-recent 9"));
+
+        // Each binding is recognized by its own command only
+        let line = format!("bindsym $mod+Ctrl+1 exec {}", shortcuts[2].command);
+        assert!(runs_command(&line, &shortcuts[2].command));
+        assert!(!runs_command(&line, &shortcuts[0].command));
+    }
+
+    #[test]
+    fn test_xfce_leftovers() {
+        let settings = UserSettings::default();
+        let keep = shortcuts_for(&settings);
+        let command = get_command_path();
+        let listing = format!(
+            "/commands/custom/<Super>v  {0}\n/commands/custom/<Super>h  {0}\n\
+             /commands/custom/<Super>period  {0} --emoji\n/commands/custom/<Primary>Escape  xfce4-taskmanager\n",
+            command
+        );
 
-            Ok(Some(new_lines.join("\n")))
-        })?;
-        Ok(())
+        let leftovers = xfce_leftovers(&listing, command, &keep);
+        assert_eq!(
+            leftovers
+                .iter()
+                .map(|(property, _)| *property)
+                .collect::<Vec<_>>(),
+            ["/commands/custom/<Super>h"]
+        );
+        assert_eq!(xfce_leftovers(&listing, command, &[]).len(), 3);
+    }
+
+    #[test]
+    fn test_conflicting_binding_detection() {
+        assert!(has_binding("bindsym $mod+v exec clipman", "$mod+v"));
+        assert!(has_binding("bindsym mod4+v exec clipman # old", "$mod+v"));
+        assert!(!has_binding("bindsym $mod+shift+v exec clipman", "$mod+v"));
+        assert!(!has_binding("bindsym ctrl+$mod+v exec clipman", "$mod+v"));
+
+        assert!(is_same_hyprland_bind(
+            "bind = super, v, exec, cliphist",
+            "SUPER, V"
+        ));
+        assert!(is_same_hyprland_bind(
+            "bind=shift super,v,togglefloating",
+            "SUPER SHIFT, V"
+        ));
+        assert!(!is_same_hyprland_bind(
+            "bind = super shift, v, exec, x",
+            "SUPER, V"
+        ));
+    }
+
+    #[test]
+    fn test_managed_block_round_trip() {
+        let command = "/usr/bin/win11-clipboard-history";
+        let same_keys = |line: &str| has_binding(line, "$mod+v");
+        let original = format!(
+            "set $mod Mod4\nbindsym $mod+v exec clipman pick\n\n{}\nbindsym $mod+h exec {}\n",
+            LEGACY_BINDING_MARKER, command
+        );
+
+        let binding = format!("bindsym $mod+v exec {}", command);
+        let written = write_managed_binding(&original, command, &binding, same_keys).unwrap();
+        assert_eq!(
+            written,
+            format!(
+                "set $mod Mod4\n# bindsym $mod+v exec clipman pick{}\n\n{}\n{}\n{}\n",
+                COMMENTED_MARKER, MANAGED_BLOCK_BEGIN, binding, MANAGED_BLOCK_END
+            )
+        );
+        // Already there
+        assert_eq!(
+            write_managed_binding(&written, command, &binding, same_keys),
+            None
+        );
+
+        let emoji = format!("bindsym $mod+period exec {} --emoji", command);
+        let both =
+            write_managed_binding(&written, &format!("{} --emoji", command), &emoji, |_| false)
+                .unwrap();
+        assert!(both.contains(&format!("{}\n{}\n{}", binding, emoji, MANAGED_BLOCK_END)));
+
+        let removed = remove_managed_binding(&written, command, same_keys).unwrap();
+        assert_eq!(removed, "set $mod Mod4\nbindsym $mod+v exec clipman pick\n");
+    }
+
+    #[test]
+    fn test_managed_block_respects_own_binding() {
+        let command = "/usr/bin/win11-clipboard-history";
+        let config = format!("bind = SUPER, C, exec, {}\n", command);
+        let binding = format!("bind = SUPER, V, exec, {}", command);
+        assert_eq!(
+            write_managed_binding(&config, command, &binding, |_| false),
+            None
+        );
+    }
+
+    #[test]
+    fn test_niri_binds_round_trip() {
+        let command = "/usr/bin/win11-clipboard-history";
+        let original = "input {\n    keyboard {}\n}\n\nbinds {\n    Mod+T { spawn \"alacritty\"; }\n    Super+v hotkey-overlay-title=\"Clipboard\" {\n        spawn \"clipse\";\n    }\n}\n";
+
+        let written = write_niri_binding(original, command, "Mod+V").unwrap();
+        assert_eq!(
+            written,
+            format!(
+                "input {{\n    keyboard {{}}\n}}\n\nbinds {{\n    {}\n    Mod+V {{ spawn \"{}\"; }}\n    {}\n    Mod+T {{ spawn \"alacritty\"; }}\n    /-Super+v hotkey-overlay-title=\"Clipboard\" {{{}\n        spawn \"clipse\";\n    }}\n}}\n",
+                NIRI_BLOCK_BEGIN, command, NIRI_BLOCK_END, NIRI_COMMENTED_MARKER
+            )
+        );
+        assert_eq!(write_niri_binding(&written, command, "Mod+V"), None);
+
+        let emoji = format!("{} --emoji", command);
+        let both = write_niri_binding(&written, &emoji, "Mod+period").unwrap();
+        assert!(both.contains(&format!(
+            "    Mod+period {{ spawn \"{}\" \"--emoji\"; }}\n    {}",
+            command, NIRI_BLOCK_END
+        )));
+
+        assert_eq!(
+            remove_niri_binding(&written, command, "Mod+V").unwrap(),
+            original
+        );
+
+        // No binds section yet
+        let fresh = write_niri_binding("", command, "Mod+V").unwrap();
+        assert!(fresh.starts_with(&format!("binds {{\n    {}\n", NIRI_BLOCK_BEGIN)));
+    }
+
+    #[test]
+    fn test_cosmic_custom_shortcuts() {
+        // As written by COSMIC Settings
+        let original = r#"{
+    (
+        modifiers: [
+            Super,
+        ],
+        key: "v",
+    ): Spawn("cosmic-clipboard"),
+    (
+        modifiers: [Super, Shift],
+        key: "t",
+        description: Some("Terminal"),
+    ): Spawn("cosmic-term"),
+}"#;
+        let config = shortcut_config(
+            "win11-clipboard-history",
+            "Clipboard History",
+            "/usr/bin/win11-clipboard-history".to_string(),
+            &Accelerator::primary_default(),
+        );
+        let shortcut = CosmicShortcut::new(&config);
+
+        let written = write_cosmic_shortcut(original, &shortcut).unwrap().unwrap();
+        let shortcuts = parse_cosmic_shortcuts(&written).unwrap();
+        // Super+V now opens the history, the terminal binding is untouched
+        assert_eq!(shortcuts.len(), 2);
+        assert_eq!(shortcuts[0].action, "Spawn(\"cosmic-term\")");
+        assert_eq!(shortcuts[1], shortcut);
+        assert_eq!(write_cosmic_shortcut(&written, &shortcut).unwrap(), None);
+
+        let removed = remove_cosmic_shortcut(&written, &config.command)
+            .unwrap()
+            .unwrap();
+        assert_eq!(parse_cosmic_shortcuts(&removed).unwrap().len(), 1);
+        assert_eq!(
+            remove_cosmic_shortcut(&removed, &config.command).unwrap(),
+            None
+        );
+
+        // The binding Super+V replaced comes back once ours is gone
+        let displaced = displaced_cosmic_shortcuts(original, &shortcut).unwrap();
+        assert_eq!(displaced.len(), 1);
+        assert_eq!(displaced[0].action, "Spawn(\"cosmic-clipboard\")");
+        assert_eq!(
+            restore_cosmic_shortcuts(&written, &displaced).unwrap(),
+            None
+        );
+        let restored = restore_cosmic_shortcuts(&removed, &displaced)
+            .unwrap()
+            .unwrap();
+        let restored = parse_cosmic_shortcuts(&restored).unwrap();
+        assert_eq!(restored.len(), 2);
+        assert_eq!(restored[1], displaced[0]);
+
+        // Neither clobbered nor accepted
+        assert!(write_cosmic_shortcut("not ron", &shortcut).is_err());
+        // What older versions wrote
+        let legacy = format!(
+            "(shortcuts: {{\n    {}: {},\n}})",
+            shortcut.binding, shortcut.action
+        );
+        assert_eq!(
+            write_cosmic_shortcut(&legacy, &shortcut).unwrap(),
+            Some(render_cosmic_shortcuts(&[shortcut]))
+        );
     }
 }
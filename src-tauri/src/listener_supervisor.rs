@@ -0,0 +1,165 @@
+//! Listener Supervisor
+//! Keeps the event-driven clipboard listeners alive. A listener whose
+//! connection drops (suspend/resume, compositor restart) is restarted with
+//! exponential backoff, the clipboard is polled while it is down so nothing
+//! is missed, and the current state is reported through diagnostics.
+
+use crate::metrics;
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// First retry delay; doubled on every consecutive failure
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Upper bound for the retry delay
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// A listener that ran at least this long is considered to have recovered,
+/// so the next failure starts the backoff from the beginning again
+const STABLE_AFTER: Duration = Duration::from_secs(60);
+
+/// How often the fallback poll runs while a listener is down
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+static STATUSES: Mutex<Vec<ListenerStatus>> = Mutex::new(Vec::new());
+
+/// Bumped by `retry_now`; waiting listeners retry as soon as it changes
+static RETRY_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ListenerState {
+    /// Receiving change events from the display server
+    Listening,
+    /// The connection dropped; polling until the next retry
+    Reconnecting,
+    /// No event source on this session; polling only
+    Polling,
+}
+
+/// State of one supervised listener, surfaced in diagnostics
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ListenerStatus {
+    /// Which selection is watched ("clipboard", "primary")
+    pub name: String,
+    /// Event source in use (protocol name, "XFixes" or "polling")
+    pub backend: String,
+    pub state: ListenerState,
+    /// Number of times the listener had to be restarted
+    pub restarts: u32,
+    pub last_error: Option<String>,
+}
+
+/// Delay before the given restart attempt (1-based)
+pub fn backoff_delay(attempt: u32) -> Duration {
+    let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+    INITIAL_BACKOFF.saturating_mul(factor).min(MAX_BACKOFF)
+}
+
+/// Makes every listener that is waiting out its backoff reconnect immediately.
+/// Called when the session is known to be usable again (resume, compositor restart).
+pub fn retry_now() {
+    RETRY_GENERATION.fetch_add(1, Ordering::SeqCst);
+}
+
+/// Current state of every supervised listener
+pub fn listener_statuses() -> Vec<ListenerStatus> {
+    STATUSES.lock().clone()
+}
+
+fn update_status(name: &str, backend: &str, f: impl FnOnce(&mut ListenerStatus)) {
+    let mut statuses = STATUSES.lock();
+    let index = match statuses.iter().position(|s| s.name == name) {
+        Some(index) => index,
+        None => {
+            statuses.push(ListenerStatus {
+                name: name.to_string(),
+                backend: backend.to_string(),
+                state: ListenerState::Listening,
+                restarts: 0,
+                last_error: None,
+            });
+            statuses.len() - 1
+        }
+    };
+    let status = &mut statuses[index];
+    status.backend = backend.to_string();
+    f(status);
+}
+
+/// Runs a blocking listener forever. Whenever `run` returns or panics, `poll`
+/// is called every 500ms until the backoff delay has passed and `run` is
+/// retried. Release builds set `panic = "abort"`, so there a panicking
+/// listener takes the app down instead and only returns are recovered from.
+pub fn supervise<R, P>(name: &str, backend: &str, mut run: R, mut poll: P) -> !
+where
+    R: FnMut() -> Result<(), String>,
+    P: FnMut(),
+{
+    let mut attempt = 0;
+    loop {
+        update_status(name, backend, |s| s.state = ListenerState::Listening);
+
+        let started = Instant::now();
+        let error = match panic::catch_unwind(AssertUnwindSafe(&mut run)) {
+            Ok(Ok(())) => "listener exited".to_string(),
+            Ok(Err(e)) => e,
+            Err(_) => "listener panicked".to_string(),
+        };
+        if started.elapsed() >= STABLE_AFTER {
+            attempt = 0;
+        }
+        attempt += 1;
+
+        metrics::record_capture_failure(backend);
+        metrics::record_reconnect(name);
+
+        let delay = backoff_delay(attempt);
+        eprintln!(
+            "[ListenerSupervisor] {} listener ({}) stopped: {}. Retrying in {:?}.",
+            name, backend, error, delay
+        );
+        update_status(name, backend, |s| {
+            s.state = ListenerState::Reconnecting;
+            s.restarts += 1;
+            s.last_error = Some(error);
+        });
+
+        let generation = RETRY_GENERATION.load(Ordering::SeqCst);
+        let deadline = Instant::now() + delay;
+        while let Some(remaining) = deadline.checked_duration_since(Instant::now()) {
+            std::thread::sleep(remaining.min(POLL_INTERVAL));
+            poll();
+            if RETRY_GENERATION.load(Ordering::SeqCst) != generation {
+                attempt = 0;
+                break;
+            }
+        }
+    }
+}
+
+/// Polls forever for sessions without an event source
+pub fn poll_forever<P: FnMut()>(name: &str, mut poll: P) -> ! {
+    update_status(name, "polling", |s| s.state = ListenerState::Polling);
+    loop {
+        std::thread::sleep(POLL_INTERVAL);
+        poll();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_doubles_and_caps() {
+        assert_eq!(backoff_delay(1), Duration::from_secs(1));
+        assert_eq!(backoff_delay(2), Duration::from_secs(2));
+        assert_eq!(backoff_delay(4), Duration::from_secs(8));
+        assert_eq!(backoff_delay(7), MAX_BACKOFF);
+        assert_eq!(backoff_delay(u32::MAX), MAX_BACKOFF);
+    }
+}
@@ -2,28 +2,82 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::path::Path;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tauri::{
     image::Image,
-    menu::{Menu, MenuItem},
+    menu::{CheckMenuItem, Menu, MenuItem, PredefinedMenuItem},
     tray::{MouseButton, TrayIconBuilder, TrayIconEvent},
-    AppHandle, Emitter, Manager, Monitor, PhysicalPosition, PhysicalSize, State, WebviewWindow,
-    WindowEvent,
+    AppHandle, Emitter, Manager, PhysicalPosition, PhysicalSize, State, WebviewWindow, WindowEvent,
 };
+use win11_clipboard_history_lib::activity_heatmap::{self, ActivityHeatmap, HeatmapRange};
 use win11_clipboard_history_lib::autostart_manager;
-use win11_clipboard_history_lib::clipboard_manager::{ClipboardItem, ClipboardManager};
-use win11_clipboard_history_lib::config_manager::{resolve_window_position, ConfigManager};
-use win11_clipboard_history_lib::emoji_manager::{EmojiManager, EmojiUsage};
+use win11_clipboard_history_lib::cli;
+use win11_clipboard_history_lib::clipboard_manager::{
+    parse_hash, ClipboardContent, ClipboardItem, ClipboardManager, SelectionKind,
+};
+use win11_clipboard_history_lib::collections::{Collection, Collections};
+use win11_clipboard_history_lib::config_manager::{
+    clamp_to_monitor, dock_geometry, place_near_cursor, resolve_window_geometry,
+    resolve_window_size, ConfigManager, DockEdge, MonitorChoice, MonitorGeometry, PlacementMode,
+};
+use win11_clipboard_history_lib::content_classifier::{self, ContentWarning};
+use win11_clipboard_history_lib::diagnostics;
+use win11_clipboard_history_lib::emoji_dataset::EmojiDataset;
+use win11_clipboard_history_lib::emoji_manager::{CustomEmoji, EmojiManager, EmojiUsage};
+use win11_clipboard_history_lib::emoji_variants;
+#[cfg(target_os = "linux")]
+use win11_clipboard_history_lib::focus_manager::focused_window_center;
 #[cfg(target_os = "linux")]
 use win11_clipboard_history_lib::focus_manager::x11_robust_activate;
 use win11_clipboard_history_lib::focus_manager::{restore_focused_window, save_focused_window};
-use win11_clipboard_history_lib::input_simulator::simulate_paste_keystroke;
+use win11_clipboard_history_lib::gif_manager::{self, FavoriteGif, GifLibrary};
+use win11_clipboard_history_lib::gif_provider::{self, Gif};
+use win11_clipboard_history_lib::history_query::{self, HistoryPage, HistoryQuery};
+use win11_clipboard_history_lib::history_store::{self, HistoryStore, StorageBackend};
+use win11_clipboard_history_lib::i18n;
+use win11_clipboard_history_lib::icon_resolver;
+use win11_clipboard_history_lib::image_compression::{self, StoredImageFormat};
+use win11_clipboard_history_lib::input_simulator::{self, simulate_paste_keystroke, PasteKeys};
+use win11_clipboard_history_lib::item_age;
+use win11_clipboard_history_lib::item_link;
+use win11_clipboard_history_lib::item_summary;
+use win11_clipboard_history_lib::keymap::{self, KeyResolution};
+#[cfg(all(target_os = "linux", feature = "layer-shell"))]
+use win11_clipboard_history_lib::layer_shell;
+use win11_clipboard_history_lib::listener_supervisor;
+use win11_clipboard_history_lib::metrics::{self, MetricsSnapshot, StoreGauges};
+use win11_clipboard_history_lib::paste_profiles;
 use win11_clipboard_history_lib::permission_checker;
+use win11_clipboard_history_lib::pinned_git::{self, PinnedGitMirror};
+use win11_clipboard_history_lib::placeholders::{self, PlaceholderContext};
+#[cfg(target_os = "linux")]
+use win11_clipboard_history_lib::primary_selection;
+use win11_clipboard_history_lib::primary_selection::{
+    PrimaryHistory, SelectionCommitter, SelectionSync,
+};
+use win11_clipboard_history_lib::quick_actions::{self, QuickAction};
 use win11_clipboard_history_lib::session::is_wayland;
+use win11_clipboard_history_lib::shell_actions;
 use win11_clipboard_history_lib::shortcut_setup;
+use win11_clipboard_history_lib::snippet_packs::{self, PackConflict, Snippet, SnippetPack};
+use win11_clipboard_history_lib::sticker_manager::{self, StickerManager, StickerPack};
+use win11_clipboard_history_lib::theme::Theme;
+use win11_clipboard_history_lib::thumbnail_manager;
+use win11_clipboard_history_lib::translation;
+use win11_clipboard_history_lib::tray_badge::{self, TrayBadge};
+use win11_clipboard_history_lib::unified_search::{self, SearchResult};
+use win11_clipboard_history_lib::unit_conversion::{self, Conversion};
+use win11_clipboard_history_lib::usage_stats::{UsageInsights, UsageStats};
 use win11_clipboard_history_lib::user_settings::{UserSettings, UserSettingsManager};
+#[cfg(target_os = "linux")]
+use win11_clipboard_history_lib::wayland_clipboard::{self, WaylandClipboardEvent};
+use win11_clipboard_history_lib::worker_pool;
+#[cfg(target_os = "linux")]
+use win11_clipboard_history_lib::x11_clipboard;
 
 /// Global flag to track if we started in background mode
 /// This is used to block the initial window show
@@ -34,11 +88,34 @@ static STARTED_IN_BACKGROUND: AtomicBool = AtomicBool::new(false);
 /// After the first user toggle, this is set to true to allow normal show/hide behavior
 static INITIAL_SHOW_ALLOWED: AtomicBool = AtomicBool::new(false);
 
+/// Whether PRIMARY selections are recorded (mirrors the user setting)
+static PRIMARY_SELECTION_ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Whether settled PRIMARY selections go into the main history (copy-on-select)
+static COPY_ON_SELECT_ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Whether CLIPBOARD and PRIMARY are kept in sync (Klipper style)
+static SELECTION_SYNC_ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// The PRIMARY watcher thread is started at most once and then idles while disabled
+static PRIMARY_WATCHER_STARTED: AtomicBool = AtomicBool::new(false);
+
+/// Whether the main window was made a layer-shell surface at startup
+#[cfg(all(target_os = "linux", feature = "layer-shell"))]
+static LAYER_SHELL_ACTIVE: AtomicBool = AtomicBool::new(false);
+
 /// Application state shared across all handlers
 pub struct AppState {
     clipboard_manager: Arc<Mutex<ClipboardManager>>,
     emoji_manager: Arc<Mutex<EmojiManager>>,
+    gif_library: Arc<Mutex<GifLibrary>>,
+    sticker_manager: Arc<Mutex<StickerManager>>,
+    usage_stats: Arc<Mutex<UsageStats>>,
+    collections: Arc<Mutex<Collections>>,
     config_manager: Arc<Mutex<ConfigManager>>,
+    primary_history: Arc<Mutex<PrimaryHistory>>,
+    selection_committer: Arc<Mutex<SelectionCommitter>>,
+    selection_sync: Arc<Mutex<SelectionSync>>,
     is_mouse_inside: Arc<AtomicBool>,
 }
 
@@ -49,14 +126,68 @@ fn get_history(state: State<AppState>) -> Vec<ClipboardItem> {
     state.clipboard_manager.lock().get_history()
 }
 
+/// A single item, for items the list has not loaded
+#[tauri::command]
+fn get_item(state: State<AppState>, id: String) -> Option<ClipboardItem> {
+    state.clipboard_manager.lock().get_item(&id).cloned()
+}
+
+/// One page of history matching the filters, for the history list
+#[tauri::command]
+fn query_history(state: State<AppState>, query: HistoryQuery) -> Result<HistoryPage, String> {
+    history_query::query(state.clipboard_manager.lock().items(), &query)
+}
+
+/// History, snippets, emoji and favorite GIFs matching `query`, for the
+/// single search box
+#[tauri::command]
+fn search_all(state: State<AppState>, query: String) -> Vec<SearchResult> {
+    let limit = unified_search::RESULTS_PER_KIND;
+    let mut results =
+        unified_search::search_items(state.clipboard_manager.lock().items(), &query, limit);
+    {
+        let emoji_manager = state.emoji_manager.lock();
+        results.extend(unified_search::search_emojis(
+            &emoji_manager.get_custom_emojis(),
+            emoji_manager.get_dataset(),
+            &query,
+            limit,
+        ));
+    }
+    let favorites = state.gif_library.lock().favorites();
+    results.extend(unified_search::search_gifs(&favorites, &query, limit));
+    results
+}
+
 #[tauri::command]
 fn clear_history(state: State<AppState>) {
-    state.clipboard_manager.lock().clear();
+    let mut manager = state.clipboard_manager.lock();
+    manager.clear();
+    prune_thumbnails(&manager);
 }
 
 #[tauri::command]
-fn delete_item(state: State<AppState>, id: String) {
-    state.clipboard_manager.lock().remove_item(&id);
+fn delete_item(state: State<AppState>, id: String) -> Result<(), String> {
+    check_editable(&state, &id)?;
+    let mut manager = state.clipboard_manager.lock();
+    manager.remove_item(&id);
+    prune_thumbnails(&manager);
+    Ok(())
+}
+
+/// Refuses changes to the items of a collection imported read-only
+fn check_editable(state: &AppState, id: &str) -> Result<(), String> {
+    let collection = state
+        .clipboard_manager
+        .lock()
+        .get_item(id)
+        .and_then(|item| item.collection.clone());
+    match collection.and_then(|id| state.collections.lock().get(&id).cloned()) {
+        Some(collection) if collection.read_only => {
+            Err(format!("'{}' is a read-only snippet pack", collection.name))
+        }
+        _ => Ok(()),
+    }
 }
 
 #[tauri::command]
@@ -68,11 +199,511 @@ fn toggle_pin(state: State<AppState>, id: String) -> Option<ClipboardItem> {
     result
 }
 
+#[tauri::command]
+fn set_item_tags(
+    state: State<AppState>,
+    id: String,
+    tags: Vec<String>,
+) -> Result<ClipboardItem, String> {
+    check_editable(&state, &id)?;
+    state
+        .clipboard_manager
+        .lock()
+        .set_tags(&id, &tags)
+        .ok_or_else(|| format!("Item '{}' not found", id))
+}
+
+/// Ids of the items tagged `tag`, in history order
+#[tauri::command]
+fn get_items_with_tag(state: State<AppState>, tag: String) -> Vec<String> {
+    state
+        .clipboard_manager
+        .lock()
+        .with_tag(&tag)
+        .into_iter()
+        .map(|item| item.id.clone())
+        .collect()
+}
+
+/// Ids of the items copied from `app` (part of a WM_CLASS or app id), in
+/// history order
+#[tauri::command]
+fn get_items_from_app(state: State<AppState>, app: String) -> Vec<String> {
+    state
+        .clipboard_manager
+        .lock()
+        .copied_from(&app)
+        .into_iter()
+        .map(|item| item.id.clone())
+        .collect()
+}
+
+/// Tags in use starting with `prefix`, for autocomplete
+#[tauri::command]
+fn suggest_tags(state: State<AppState>, prefix: String) -> Vec<String> {
+    const TAG_SUGGESTIONS: usize = 8;
+    state
+        .clipboard_manager
+        .lock()
+        .tag_suggestions(&prefix, TAG_SUGGESTIONS)
+}
+
+#[tauri::command]
+fn get_collections(state: State<AppState>) -> Vec<Collection> {
+    state.collections.lock().list()
+}
+
+#[tauri::command]
+fn create_collection(
+    app: AppHandle,
+    state: State<AppState>,
+    name: String,
+) -> Result<Collection, String> {
+    let collection = state.collections.lock().create(&name)?;
+    let _ = app.emit("collections-changed", ());
+    Ok(collection)
+}
+
+#[tauri::command]
+fn rename_collection(
+    app: AppHandle,
+    state: State<AppState>,
+    id: String,
+    name: String,
+) -> Result<Collection, String> {
+    let collection = state.collections.lock().rename(&id, &name)?;
+    let _ = app.emit("collections-changed", ());
+    Ok(collection)
+}
+
+/// Deletes a collection; its items stay in history, unfiled
+#[tauri::command]
+fn delete_collection(app: AppHandle, state: State<AppState>, id: String) -> Result<(), String> {
+    state.collections.lock().delete(&id)?;
+    let mut manager = state.clipboard_manager.lock();
+    manager.unfile_collection(&id);
+    let _ = app.emit("history-sync", ());
+    let _ = app.emit("collections-changed", ());
+    Ok(())
+}
+
+/// Files an item into a collection, or takes it out with no collection
+#[tauri::command]
+fn file_item(
+    state: State<AppState>,
+    id: String,
+    collection: Option<String>,
+) -> Result<ClipboardItem, String> {
+    check_editable(&state, &id)?;
+    if let Some(collection) = &collection {
+        match state.collections.lock().get(collection) {
+            None => return Err("Collection not found".to_string()),
+            Some(target) if target.read_only => {
+                return Err(format!("'{}' is a read-only snippet pack", target.name))
+            }
+            Some(_) => {}
+        }
+    }
+    state
+        .clipboard_manager
+        .lock()
+        .file_item(&id, collection)
+        .ok_or_else(|| format!("Item '{}' not found", id))
+}
+
+/// Writes a collection as a snippet pack to the downloads folder, or the
+/// home folder without one, and returns the path of the file
+#[tauri::command]
+fn export_snippet_pack(
+    state: State<AppState>,
+    id: String,
+    read_only: bool,
+) -> Result<String, String> {
+    let collection = state
+        .collections
+        .lock()
+        .get(&id)
+        .cloned()
+        .ok_or("Collection not found")?;
+    let mut snippets = Vec::new();
+    {
+        let manager = state.clipboard_manager.lock();
+        for item in manager.in_collection(&id) {
+            snippets.extend(Snippet::from_item(item, manager.full_text(item)?));
+        }
+    }
+    let dir = dirs::download_dir()
+        .or_else(dirs::home_dir)
+        .ok_or("No folder to save the pack in")?;
+    let path = dir.join(snippet_packs::file_name(&collection.name));
+    SnippetPack::new(&collection, snippets, read_only).write(&path)?;
+    Ok(path.display().to_string())
+}
+
+/// Imports a snippet pack file as a collection, or into the collection an
+/// earlier import of it made
+#[tauri::command]
+fn import_snippet_pack(
+    app: AppHandle,
+    state: State<AppState>,
+    path: String,
+    conflict: PackConflict,
+) -> Result<Collection, String> {
+    let pack = SnippetPack::read(Path::new(&path))?;
+    let collection = state.collections.lock().for_pack(&pack, conflict)?;
+    state
+        .clipboard_manager
+        .lock()
+        .import_snippets(&collection.id, pack.snippets);
+    let _ = app.emit("history-sync", ());
+    let _ = app.emit("collections-changed", ());
+    Ok(collection)
+}
+
+/// The items filed in a collection, in history order
+#[tauri::command]
+fn get_collection_items(state: State<AppState>, id: String) -> Vec<ClipboardItem> {
+    state
+        .clipboard_manager
+        .lock()
+        .in_collection(&id)
+        .into_iter()
+        .cloned()
+        .collect()
+}
+
+#[tauri::command]
+fn split_item(
+    state: State<AppState>,
+    id: String,
+    delimiter: String,
+) -> Result<Vec<ClipboardItem>, String> {
+    state.clipboard_manager.lock().split_item(&id, &delimiter)
+}
+
+/// Combines an item with the one before it, using the configured separator
+#[tauri::command]
+fn join_with_previous(state: State<AppState>, id: String) -> Result<ClipboardItem, String> {
+    let separator = UserSettingsManager::new().load().join_separator;
+    state
+        .clipboard_manager
+        .lock()
+        .join_with_previous(&id, &separator)
+}
+
+/// Adds a copy of an item without invisible characters or odd spaces
+#[tauri::command]
+fn clean_hidden_chars(state: State<AppState>, id: String) -> Result<ClipboardItem, String> {
+    state.clipboard_manager.lock().clean_hidden_chars(&id)
+}
+
+/// Translates a text item with the service from the settings and adds the
+/// translation as a new item
+#[tauri::command]
+async fn translate_item(state: State<'_, AppState>, id: String) -> Result<ClipboardItem, String> {
+    let text = {
+        let manager = state.clipboard_manager.lock();
+        let item = manager
+            .get_item(&id)
+            .ok_or_else(|| format!("Item '{}' not found", id))?;
+        manager
+            .full_text(item)?
+            .ok_or("Only text items can be translated")?
+    };
+    let settings = UserSettingsManager::new().load().translation;
+    let translated = tokio::task::spawn_blocking(move || translation::translate(&settings, &text))
+        .await
+        .map_err(|e| e.to_string())??;
+    Ok(state.clipboard_manager.lock().add_derived_text(translated))
+}
+
+/// Quantities in a text item ("72°F", "$49.99") converted into the units and
+/// currency from the settings. Without exchange rates only units are
+/// converted.
+#[tauri::command]
+async fn get_conversions(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<Vec<Conversion>, String> {
+    let quantities = {
+        let manager = state.clipboard_manager.lock();
+        let item = manager
+            .get_item(&id)
+            .ok_or_else(|| format!("Item '{}' not found", id))?;
+        unit_conversion::find_quantities(item.text().unwrap_or_default())
+    };
+    if quantities.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let settings = UserSettingsManager::new().load();
+    let (system, currency) = (settings.unit_system(), settings.currency());
+    let rates =
+        if settings.fetch_exchange_rates && unit_conversion::needs_rates(&quantities, &currency) {
+            let base = currency.clone();
+            tokio::task::spawn_blocking(move || unit_conversion::exchange_rates(&base))
+                .await
+                .map_err(|e| e.to_string())?
+                .map_err(|e| eprintln!("[Conversions] Failed to fetch exchange rates: {}", e))
+                .ok()
+        } else {
+            None
+        };
+    Ok(quantities
+        .iter()
+        .filter_map(|quantity| {
+            unit_conversion::convert(quantity, system, &currency, rates.as_ref())
+        })
+        .collect())
+}
+
+/// Copy counts per weekday and hour over the given range, for the stats heatmap
+#[tauri::command]
+fn get_activity_heatmap(state: State<AppState>, range: HeatmapRange) -> ActivityHeatmap {
+    let manager = state.clipboard_manager.lock();
+    let timestamps = manager.items().iter().map(|item| item.timestamp);
+    activity_heatmap::build(timestamps, range, chrono::Utc::now(), &chrono::Local)
+}
+
+/// Copy and paste counts for the insights view, over the last `days` days
+#[tauri::command]
+fn get_usage_insights(state: State<AppState>, days: u32) -> UsageInsights {
+    let items = state.clipboard_manager.lock().get_history();
+    let today = chrono::Local::now().date_naive();
+    state
+        .usage_stats
+        .lock()
+        .insights(&items, today, days.min(365))
+}
+
+#[tauri::command]
+fn clear_usage_stats(state: State<AppState>) {
+    state.usage_stats.lock().clear();
+}
+
+/// Edge the docked sidebar sits on
+#[tauri::command]
+fn get_dock_edge(state: State<AppState>) -> DockEdge {
+    state.config_manager.lock().get_state().dock_edge
+}
+
+/// Names of the connected monitors, for the "open on monitor" setting
+#[tauri::command]
+fn get_monitor_names(app: AppHandle) -> Vec<String> {
+    app.available_monitors()
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|monitor| monitor.name().cloned())
+        .collect()
+}
+
+#[tauri::command]
+fn set_dock_edge(app: AppHandle, state: State<AppState>, edge: DockEdge) {
+    {
+        let mut config = state.config_manager.lock();
+        config.set_dock_edge(edge);
+        config.sync_to_disk();
+    }
+    WindowController::redock(&app);
+}
+
+/// Counters for self-monitoring: captures, listener failures, paste outcomes
+/// per strategy and the store's size
+#[tauri::command]
+fn get_metrics(state: State<AppState>) -> MetricsSnapshot {
+    metrics::snapshot(store_gauges(&state.clipboard_manager))
+}
+
+fn store_gauges(clipboard_manager: &Mutex<ClipboardManager>) -> StoreGauges {
+    let (history_items, location) = {
+        let manager = clipboard_manager.lock();
+        let location = manager.store().location().map(Path::to_path_buf);
+        (manager.items().len(), location)
+    };
+    // Walking the store can take a while, so not under the lock
+    StoreGauges {
+        history_items,
+        store_bytes: history_store::store_size(location.as_deref()),
+    }
+}
+
+/// Starts, moves or stops the Prometheus endpoint for the `metrics_port` setting
+fn serve_metrics(port: u16, clipboard_manager: Arc<Mutex<ClipboardManager>>) {
+    metrics::serve(port, Arc::new(move || store_gauges(&clipboard_manager)));
+}
+
+/// Humanized ages of all items, keyed by id. Kept fresh by the
+/// `item-ages-changed` event.
+#[tauri::command]
+fn get_item_ages(state: State<AppState>) -> HashMap<String, String> {
+    item_ages_now(&state.clipboard_manager.lock())
+}
+
+/// What screen readers say for each item, keyed by id
+#[tauri::command]
+fn get_item_summaries(state: State<AppState>) -> HashMap<String, String> {
+    item_summary::item_summaries(state.clipboard_manager.lock().items(), i18n::current())
+}
+
+fn item_ages_now(manager: &ClipboardManager) -> HashMap<String, String> {
+    item_age::item_ages(
+        manager
+            .items()
+            .iter()
+            .map(|item| (item.id.as_str(), item.timestamp)),
+        chrono::Utc::now(),
+        &chrono::Local,
+        i18n::current(),
+    )
+}
+
+/// Looks up an item by the hex content hash printed by `--list --json`
+#[tauri::command]
+fn find_by_hash(state: State<AppState>, hash: String) -> Result<Option<ClipboardItem>, String> {
+    let hash = parse_hash(&hash).ok_or_else(|| format!("Invalid hash '{}'", hash))?;
+    Ok(state.clipboard_manager.lock().find_by_hash(hash).cloned())
+}
+
+/// Path of a preview sized for the window's current monitor scale factor.
+/// Async, so decoding a large image doesn't hold up other commands.
+#[tauri::command]
+async fn get_image_thumbnail(
+    window: WebviewWindow,
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<String, String> {
+    let item = {
+        let manager = state.clipboard_manager.lock();
+        // Thumbnails are cached on disk; the frontend falls back to the inline image
+        if !manager.persists_to_disk() {
+            return Err("Thumbnails are disabled while history is not saved".to_string());
+        }
+        manager
+            .get_item(&id)
+            .cloned()
+            .ok_or_else(|| format!("Item '{}' not found", id))?
+    };
+    let scale_factor = window.scale_factor().unwrap_or(1.0);
+    let manager = state.clipboard_manager.clone();
+    let path = tokio::task::spawn_blocking(move || {
+        // Usually generated in the background already; skip the queue then
+        if let Some(path) = thumbnail_manager::cached_thumbnail_path(&item, scale_factor) {
+            return Ok(path);
+        }
+        let item = manager.lock().with_image_data(&item)?;
+        worker_pool::global().run("thumbnail", move || {
+            thumbnail_manager::get_thumbnail_path(&item, scale_factor)
+        })?
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+    Ok(path.to_string_lossy().into_owned())
+}
+
+// --- Primary Selection Commands ---
+
+#[tauri::command]
+fn get_primary_history(state: State<AppState>) -> Vec<ClipboardItem> {
+    state.primary_history.lock().get_history()
+}
+
+#[tauri::command]
+fn delete_primary_item(state: State<AppState>, id: String) {
+    state.primary_history.lock().remove_item(&id);
+}
+
+#[tauri::command]
+fn clear_primary_history(state: State<AppState>) {
+    state.primary_history.lock().clear();
+}
+
 #[tauri::command]
 fn get_recent_emojis(state: State<AppState>) -> Vec<EmojiUsage> {
     state.emoji_manager.lock().get_recent()
 }
 
+#[tauri::command]
+fn get_emoji_dataset(state: State<AppState>) -> Option<EmojiDataset> {
+    state.emoji_manager.lock().get_dataset().cloned()
+}
+
+#[tauri::command]
+fn install_emoji_dataset(state: State<AppState>, path: String) -> Result<String, String> {
+    state.emoji_manager.lock().install_dataset(Path::new(&path))
+}
+
+#[tauri::command]
+fn get_custom_emojis(state: State<AppState>) -> Vec<CustomEmoji> {
+    state.emoji_manager.lock().get_custom_emojis()
+}
+
+/// Registers a shortcode for a text, or for an image file sent as base64
+#[tauri::command]
+fn add_custom_emoji(
+    state: State<AppState>,
+    shortcode: String,
+    text: String,
+    image: Option<String>,
+) -> Result<CustomEmoji, String> {
+    state
+        .emoji_manager
+        .lock()
+        .add_custom_emoji(&shortcode, &text, image.as_deref())
+}
+
+#[tauri::command]
+fn remove_custom_emoji(state: State<AppState>, shortcode: String) -> Result<bool, String> {
+    state.emoji_manager.lock().remove_custom_emoji(&shortcode)
+}
+
+/// Pastes what a custom shortcode stands for
+#[tauri::command]
+async fn paste_custom_emoji(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    shortcode: String,
+) -> Result<(), String> {
+    let emoji = state
+        .emoji_manager
+        .lock()
+        .find_custom_emoji(&shortcode)
+        .cloned()
+        .ok_or_else(|| format!("No custom emoji :{}:", shortcode))?;
+    let Some(image) = emoji.image else {
+        return paste_text(app, state, emoji.text, None).await;
+    };
+
+    WindowController::hide(&app);
+    PasteHelper::prepare_target_window().await?;
+    state.clipboard_manager.lock().paste_image(&image)
+}
+
+/// The emojis of the picker with the preferred skin tone and gender
+#[tauri::command]
+fn apply_emoji_preference(state: State<AppState>, emojis: Vec<String>) -> Vec<String> {
+    let manager = state.emoji_manager.lock();
+    emojis
+        .iter()
+        .map(|emoji| manager.preferred(emoji))
+        .collect()
+}
+
+/// Every skin tone and gender of an emoji, for its variant picker
+#[tauri::command]
+fn get_emoji_variants(emoji: String) -> Vec<String> {
+    emoji_variants::variants(&emoji)
+}
+
+#[tauri::command]
+fn get_recent_kaomojis(state: State<AppState>) -> Vec<EmojiUsage> {
+    state.emoji_manager.lock().get_recent_kaomojis()
+}
+
+#[tauri::command]
+fn get_recent_symbols(state: State<AppState>) -> Vec<EmojiUsage> {
+    state.emoji_manager.lock().get_recent_symbols()
+}
+
 #[tauri::command]
 fn set_mouse_state(state: State<AppState>, inside: bool) {
     state.is_mouse_inside.store(inside, Ordering::Relaxed);
@@ -86,6 +717,24 @@ fn get_user_settings() -> Result<UserSettings, String> {
     Ok(manager.load())
 }
 
+/// The desktop's dark/light preference, None where no portal reports it
+#[tauri::command]
+fn get_system_color_scheme() -> Option<String> {
+    #[cfg(target_os = "linux")]
+    {
+        return win11_clipboard_history_lib::color_scheme::current()
+            .map(|scheme| scheme.as_str().to_string());
+    }
+    #[cfg(not(target_os = "linux"))]
+    None
+}
+
+/// Accent color, corner radius and opacities, with presets applied
+#[tauri::command]
+fn get_theme() -> Theme {
+    UserSettingsManager::new().load().theme()
+}
+
 #[tauri::command]
 fn set_user_settings(
     app: AppHandle,
@@ -93,7 +742,25 @@ fn set_user_settings(
     new_settings: UserSettings,
 ) -> Result<(), String> {
     let manager = UserSettingsManager::new();
+    let previous = manager.load();
     manager.save(&new_settings)?;
+    // Presets are applied when saving, so the theme is read back
+    let previous_theme = previous.theme();
+    let theme = manager.load().theme();
+
+    // Rebind in the desktop environment when a shortcut changed
+    #[cfg(target_os = "linux")]
+    {
+        use win11_clipboard_history_lib::linux_shortcut_manager;
+        let current = manager.load();
+        if linux_shortcut_manager::shortcuts_changed(&previous, &current) {
+            std::thread::spawn(move || {
+                linux_shortcut_manager::change_global_shortcut(&previous, &current);
+            });
+        }
+    }
+    #[cfg(not(target_os = "linux"))]
+    let _ = previous;
 
     // Update clipboard manager's max history size if it changed
     {
@@ -101,15 +768,100 @@ fn set_user_settings(
         if clipboard_manager.get_max_history_size() != new_settings.max_history_size {
             clipboard_manager.set_max_history_size(new_settings.max_history_size);
         }
+        let retention = new_settings.retention_policy();
+        if clipboard_manager.get_retention_policy() != retention {
+            clipboard_manager.set_retention_policy(retention);
+            prune_thumbnails(&clipboard_manager);
+        }
+        clipboard_manager.set_capture_policy(new_settings.capture_policy());
+        clipboard_manager.set_memory_budget(new_settings.memory_budget_mb);
+        let backend = new_settings.storage_backend();
+        if clipboard_manager.store().backend() != backend {
+            clipboard_manager.set_store(open_history_store(backend));
+        }
+        let mirror_dir = new_settings.pinned_git_mirror_dir();
+        if clipboard_manager.pinned_mirror_dir() != mirror_dir.as_deref() {
+            clipboard_manager.set_pinned_mirror(mirror_dir.map(PinnedGitMirror::new));
+        }
+    }
+
+    worker_pool::global().set_limit(new_settings.worker_limit());
+    serve_metrics(new_settings.metrics_port, state.clipboard_manager.clone());
+    {
+        let mut config = state.config_manager.lock();
+        config.set_placement_mode(new_settings.placement_mode());
+        config.set_density(new_settings.ui_density());
+        config.set_monitor_choice(new_settings.monitor_choice());
+    }
+    state
+        .emoji_manager
+        .lock()
+        .set_variant_preference(new_settings.emoji_variants.clone());
+    WindowController::redock(&app);
+    set_tray_visible(&app, new_settings.show_tray_icon);
+
+    // Relabel the items right away in a new language
+    let language = new_settings.language();
+    if language != i18n::current() {
+        i18n::set_language(language);
+        let ages = item_ages_now(&state.clipboard_manager.lock());
+        let _ = app.emit("item-ages-changed", &ages);
+    }
+
+    // Start or pause the PRIMARY selection pane and copy-on-select
+    let was_enabled =
+        PRIMARY_SELECTION_ENABLED.swap(new_settings.enable_primary_selection, Ordering::SeqCst);
+    COPY_ON_SELECT_ENABLED.store(new_settings.copy_on_select, Ordering::SeqCst);
+    SELECTION_SYNC_ENABLED.store(new_settings.sync_selections, Ordering::SeqCst);
+    if !new_settings.sync_selections {
+        state.selection_sync.lock().reset();
+    }
+    {
+        let mut committer = state.selection_committer.lock();
+        committer.set_delay(Duration::from_secs(new_settings.copy_on_select_delay_secs));
+        if !new_settings.copy_on_select && !new_settings.sync_selections {
+            committer.clear();
+        }
+    }
+    if primary_watch_enabled() {
+        start_primary_selection_watcher(app.clone(), state.primary_history.clone());
+    }
+    if was_enabled && !new_settings.enable_primary_selection {
+        state.primary_history.lock().clear();
     }
 
     // Emit event to notify all windows that settings have changed
     app.emit("app-settings-changed", &new_settings)
         .map_err(|e| format!("Failed to emit settings changed event: {}", e))?;
+    if theme != previous_theme {
+        app.emit("theme-changed", &theme)
+            .map_err(|e| format!("Failed to emit theme changed event: {}", e))?;
+    }
 
     Ok(())
 }
 
+fn app_data_dir() -> std::path::PathBuf {
+    dirs::data_local_dir()
+        .unwrap_or_else(|| std::path::PathBuf::from("."))
+        .join("win11-clipboard-history")
+}
+
+/// Opens the configured history backend, falling back to the JSON file
+fn open_history_store(backend: StorageBackend) -> Box<dyn HistoryStore> {
+    let data_dir = app_data_dir();
+    history_store::open_store(backend, &data_dir).unwrap_or_else(|e| {
+        eprintln!(
+            "Failed to open {} history store, using JSON: {}",
+            backend.as_str(),
+            e
+        );
+        Box::new(history_store::JsonFileStore::new(
+            data_dir.join("history.json"),
+        ))
+    })
+}
+
 #[tauri::command]
 fn is_settings_window_visible(app: AppHandle) -> bool {
     app.get_webview_window("settings")
@@ -126,14 +878,27 @@ async fn paste_item(app: AppHandle, state: State<'_, AppState>, id: String) -> R
     };
 
     match item {
+        // Don't paste a file:// link to a file that was moved or deleted
+        Some(item) if item.has_missing_files() => {
+            let prune = UserSettingsManager::new().load().prune_missing_files;
+            let mut manager = state.clipboard_manager.lock();
+            manager.check_file_references(prune);
+            let _ = app.emit("history-sync", ());
+            return Err("The copied file no longer exists".to_string());
+        }
         Some(item) => {
             // 2. Prepare Environment (Hide Window -> Restore Focus)
             WindowController::hide(&app);
             PasteHelper::prepare_target_window().await?;
 
-            // 3. Perform Paste
+            // 3. Perform Paste, formatted for the target app
+            let profiles = UserSettingsManager::new().load().paste_profiles;
+            let item = paste_profiles::format_for_target(&item, &profiles);
             let mut manager = state.clipboard_manager.lock();
+            let item = fill_in_placeholders(&item, &mut manager);
             manager.paste_item(&item).map_err(|e| e.to_string())?;
+            manager.record_paste(&item.id, chrono::Utc::now());
+            state.usage_stats.lock().record_paste(&item.id);
         }
         None => {
             eprintln!(
@@ -141,27 +906,274 @@ async fn paste_item(app: AppHandle, state: State<'_, AppState>, id: String) -> R
                 id
             );
             // Emit event to trigger frontend refresh
-            let history = state.clipboard_manager.lock().get_history();
-            let _ = app.emit("history-sync", &history);
+            let _ = app.emit("history-sync", ());
             return Err(format!("Item '{}' not found. History has been synced.", id));
         }
     }
     Ok(())
 }
 
+/// Pinned and filed items with their placeholders filled in, see
+/// `placeholders`; anything else as it is
+fn fill_in_placeholders(item: &ClipboardItem, manager: &mut ClipboardManager) -> ClipboardItem {
+    if !item.is_kept() {
+        return item.clone();
+    }
+    let clipboard = item
+        .text()
+        .filter(|text| placeholders::needs_clipboard(text))
+        .and_then(|_| manager.get_current_text().ok());
+    let context = PlaceholderContext {
+        now: chrono::Local::now(),
+        clipboard,
+    };
+    placeholders::expand_item(item, &context)
+}
+
+/// Pastes an item and presses the `submit_keys` setting right after, for
+/// chats where every paste is sent straight away
 #[tauri::command]
-async fn paste_text(
+async fn paste_item_and_submit(
     app: AppHandle,
     state: State<'_, AppState>,
-    text: String,
-    item_type: Option<String>,
+    id: String,
 ) -> Result<(), String> {
-    // 0. Record usage if applicable
-    if let Some(t) = item_type.as_deref() {
-        if t == "emoji" {
-            state.emoji_manager.lock().record_usage(&text);
-        }
-    }
+    paste_item(app, state, id).await?;
+
+    let keys = PasteKeys::submit_from_setting(&UserSettingsManager::new().load().submit_keys);
+    tokio::task::spawn_blocking(move || input_simulator::simulate_submit_keystroke(keys))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Opens the item's link, shows its files or opens its text in the editor
+#[tauri::command]
+fn run_quick_action(state: State<AppState>, id: String, action: QuickAction) -> Result<(), String> {
+    let item = {
+        let manager = state.clipboard_manager.lock();
+        let item = manager
+            .get_item(&id)
+            .ok_or_else(|| format!("Item '{}' not found", id))?;
+        // The editor gets the whole of a large text
+        manager.with_full_content(item)?
+    };
+    quick_actions::run(&item, action)
+}
+
+/// Pipes an item's text to the shell action `name` from the settings. When
+/// the action captures its output, what the command printed is added to
+/// history and returned.
+#[tauri::command]
+async fn run_shell_action(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    id: String,
+    name: String,
+) -> Result<Option<ClipboardItem>, String> {
+    let action = UserSettingsManager::new()
+        .load()
+        .shell_actions
+        .into_iter()
+        .find(|action| action.name == name)
+        .ok_or_else(|| format!("No action named '{}'", name))?;
+    let text = {
+        let manager = state.clipboard_manager.lock();
+        let item = manager
+            .get_item(&id)
+            .ok_or_else(|| format!("Item '{}' not found", id))?;
+        manager
+            .full_text(item)?
+            .ok_or("Only text items can be piped to a command")?
+    };
+
+    let command = action.command.clone();
+    let output = tokio::task::spawn_blocking(move || shell_actions::run(&command, &text))
+        .await
+        .map_err(|e| e.to_string())??;
+    if !action.capture_output || output.trim().is_empty() {
+        return Ok(None);
+    }
+
+    let item = state.clipboard_manager.lock().add_text(output, None);
+    if let Some(item) = &item {
+        item_captured(&app, item);
+    }
+    Ok(item)
+}
+
+/// Opens a web or mailto link with the default app, for links found in items
+#[tauri::command]
+fn open_url(url: String) -> Result<(), String> {
+    quick_actions::open_url(&url)
+}
+
+/// Shows a path found in an item in the file manager
+#[tauri::command]
+fn reveal_path(path: String) -> Result<(), String> {
+    quick_actions::reveal_path(&path)
+}
+
+/// Pings an IP address found in an item, in a terminal
+#[tauri::command]
+fn ping_address(address: String) -> Result<(), String> {
+    quick_actions::ping(&address)
+}
+
+/// Sanitized HTML for a Markdown item, for the preview. Pasting the item
+/// still gives the raw Markdown.
+#[tauri::command]
+fn render_markdown(state: State<AppState>, id: String) -> Result<String, String> {
+    let manager = state.clipboard_manager.lock();
+    let item = manager
+        .get_item(&id)
+        .ok_or_else(|| format!("Item '{}' not found", id))?;
+    Ok(markdown::render(item.text().unwrap_or_default()))
+}
+
+/// Copies a `clip://item/<id>` link to the item, for pasting into notes
+#[tauri::command]
+async fn copy_item_link(state: State<'_, AppState>, id: String) -> Result<(), String> {
+    if state.clipboard_manager.lock().get_item(&id).is_none() {
+        return Err(format!("Item '{}' not found", id));
+    }
+    copy_text_to_clipboard(state, item_link::item_link(&id)).await
+}
+
+/// Opens the GIF tab searching for `query`, or with the search box focused
+/// when it is empty
+#[tauri::command]
+fn search_gifs(app: AppHandle, query: String) {
+    WindowController::show_tab(&app, "gifs");
+    let _ = app.emit("open-gif-search", query);
+}
+
+/// Resolves the keys typed so far in the history list against the keymap
+#[tauri::command]
+fn resolve_key_sequence(sequence: String) -> KeyResolution {
+    let settings = UserSettingsManager::new().load();
+    if !settings.keymap_enabled() {
+        return KeyResolution::None;
+    }
+    keymap::resolve(&settings.keymap, &sequence)
+}
+
+/// Items pasted often and lately, for the "frequently used" row
+#[tauri::command]
+fn get_frequent_items(state: State<AppState>, count: usize) -> Vec<ClipboardItem> {
+    state
+        .clipboard_manager
+        .lock()
+        .frequent(count, chrono::Utc::now())
+        .into_iter()
+        .cloned()
+        .collect()
+}
+
+/// Pastes the Nth most recently copied item: Ctrl+1..9 in the window, or
+/// Super+Ctrl+1..9 (`--paste-recent N`) while it is closed. Items that should
+/// be looked at first go to the window's paste preview instead.
+#[tauri::command]
+async fn paste_recent_item(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    position: usize,
+) -> Result<(), String> {
+    let id = state
+        .clipboard_manager
+        .lock()
+        .nth_recent(position)
+        .map(|item| item.id.clone())
+        .ok_or_else(|| format!("No item at position {}", position))?;
+    paste_now_or_confirm(app, state, id).await
+}
+
+/// Pastes an item into the focused app while the window is closed, or sends
+/// it to the window's paste preview if it should be looked at first
+async fn paste_now_or_confirm(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<(), String> {
+    let window_visible = app
+        .get_webview_window("main")
+        .is_some_and(|window| window.is_visible().unwrap_or(false));
+    if get_paste_warning(state.clone(), id.clone()).is_some() {
+        if !window_visible {
+            WindowController::toggle(&app);
+        }
+        let _ = app.emit("confirm-paste", &id);
+        return Ok(());
+    }
+
+    // Paste into the app that has focus now, not the one the window last opened over
+    if !window_visible {
+        save_focused_window();
+    }
+    paste_item(app, state, id).await
+}
+
+/// Why an item should be looked at before pasting, if it should. Only text
+/// copied from a browser, or from an unknown app, is checked.
+#[tauri::command]
+fn get_paste_warning(state: State<AppState>, id: String) -> Option<ContentWarning> {
+    if !UserSettingsManager::new().load().confirm_suspicious_paste {
+        return None;
+    }
+    let manager = state.clipboard_manager.lock();
+    let item = manager.get_item(&id)?;
+    if let Some(source_app) = &item.source_app {
+        if !content_classifier::is_browser(source_app) {
+            return None;
+        }
+    }
+    Some(content_classifier::classify(item.text()?)).filter(ContentWarning::is_suspicious)
+}
+
+/// Types a text item into the previous window key by key, for apps that block paste
+#[tauri::command]
+async fn type_item(app: AppHandle, state: State<'_, AppState>, id: String) -> Result<(), String> {
+    let text = {
+        let mut manager = state.clipboard_manager.lock();
+        let item = manager
+            .get_item(&id)
+            .map(|item| manager.with_full_content(item))
+            .transpose()?;
+        let item = item.map(|item| fill_in_placeholders(&item, &mut manager));
+        match item.as_ref().map(|item| &item.content) {
+            Some(ClipboardContent::Text(text)) => text.clone(),
+            Some(ClipboardContent::RichText { plain, .. }) => plain.clone(),
+            Some(ClipboardContent::Image { .. }) => {
+                return Err("Images cannot be typed out".to_string())
+            }
+            None => return Err(format!("Item '{}' not found", id)),
+        }
+    };
+    let delay_ms = UserSettingsManager::new().load().type_out_delay_ms;
+
+    WindowController::hide(&app);
+    PasteHelper::prepare_target_window().await?;
+
+    tokio::task::spawn_blocking(move || input_simulator::simulate_typing(&text, delay_ms))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+async fn paste_text(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    text: String,
+    item_type: Option<String>,
+) -> Result<(), String> {
+    // 0. Record usage if applicable
+    if let Some(t) = item_type.as_deref() {
+        match t {
+            "emoji" => state.emoji_manager.lock().record_usage(&text),
+            "kaomoji" => state.emoji_manager.lock().record_kaomoji_usage(&text),
+            "symbol" => state.emoji_manager.lock().record_symbol_usage(&text),
+            _ => {}
+        }
+    }
 
     // 1. Prepare Environment
     WindowController::hide(&app);
@@ -185,21 +1197,155 @@ async fn paste_text(
     Ok(())
 }
 
+/// Trending GIFs of the configured provider when `query` is blank
+#[tauri::command]
+async fn search_gif_provider(query: String, limit: usize) -> Result<Vec<Gif>, String> {
+    let settings = UserSettingsManager::new().load().gifs;
+    if !settings.is_configured() {
+        return Err("Set up the GIF provider in the settings first".to_string());
+    }
+    tokio::task::spawn_blocking(move || gif_provider::provider_for(&settings).search(&query, limit))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+fn get_gif_favorites(state: State<AppState>) -> Vec<FavoriteGif> {
+    state.gif_library.lock().favorites()
+}
+
+#[tauri::command]
+fn get_recent_gifs(state: State<AppState>) -> Vec<Gif> {
+    state.gif_library.lock().recent()
+}
+
+/// Downloads the GIF and its preview so the favorite pastes offline
+#[tauri::command]
+async fn add_gif_favorite(state: State<'_, AppState>, gif: Gif) -> Result<FavoriteGif, String> {
+    let dir = state.gif_library.lock().favorites_dir();
+    let settings = UserSettingsManager::new().load().gifs;
+    let favorite = tokio::task::spawn_blocking(move || {
+        let provider = gif_provider::provider_for(&settings);
+        gif_manager::download_favorite(provider.as_ref(), gif, &dir)
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+    state.gif_library.lock().add_favorite(favorite.clone());
+    Ok(favorite)
+}
+
+#[tauri::command]
+fn get_sticker_packs(state: State<AppState>) -> Vec<StickerPack> {
+    state.sticker_manager.lock().packs()
+}
+
+/// Imports the PNG and WebP images of a folder as a pack
+#[tauri::command]
+async fn import_sticker_folder(
+    state: State<'_, AppState>,
+    path: String,
+) -> Result<StickerPack, String> {
+    let folder = Path::new(&path).to_path_buf();
+    let images = tokio::task::spawn_blocking(move || {
+        sticker_manager::read_folder(&folder).and_then(sticker_manager::normalize_pack)
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+    state.sticker_manager.lock().import(images, &path)
+}
+
+/// Imports a pack manifest, or a single image, from a URL
+#[tauri::command]
+async fn import_sticker_url(
+    state: State<'_, AppState>,
+    url: String,
+) -> Result<StickerPack, String> {
+    let source = url.clone();
+    let images = tokio::task::spawn_blocking(move || {
+        sticker_manager::fetch_url(&source).and_then(sticker_manager::normalize_pack)
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+    state.sticker_manager.lock().import(images, &url)
+}
+
+#[tauri::command]
+fn remove_sticker_pack(state: State<AppState>, id: String) -> Result<bool, String> {
+    state.sticker_manager.lock().remove_pack(&id)
+}
+
+/// Pastes the sticker as image data, which chat apps show as a sticker
+/// where a file URI would become an attachment
+#[tauri::command]
+async fn paste_sticker(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    pack_id: String,
+    sticker_id: String,
+) -> Result<(), String> {
+    let image = state
+        .sticker_manager
+        .lock()
+        .sticker_image(&pack_id, &sticker_id)?;
+    WindowController::hide(&app);
+    PasteHelper::prepare_target_window().await?;
+    state.clipboard_manager.lock().paste_image(&image)
+}
+
+/// Deletes the cached GIFs, returning what went
+#[tauri::command]
+async fn clear_gif_cache() -> Result<gif_manager::CacheCleanup, String> {
+    tokio::task::spawn_blocking(gif_manager::clear_cache)
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+fn remove_gif_favorite(state: State<AppState>, id: String) -> bool {
+    state.gif_library.lock().remove_favorite(&id)
+}
+
+/// `gif`, when given, goes to the recent GIFs
 #[tauri::command]
 async fn paste_gif_from_url(
     app: AppHandle,
     state: State<'_, AppState>,
     url: String,
+    gif: Option<Gif>,
 ) -> Result<(), String> {
-    // 1. Download (Blocking) - Window stays open to show loading if UI supports it
-    let url_clone = url.clone();
-    let file_uri = tokio::task::spawn_blocking(move || {
-        win11_clipboard_history_lib::gif_manager::paste_gif_to_clipboard_with_uri(&url_clone)
+    // 1. Download (Blocking) - Window stays open to show loading if UI supports it.
+    // Favorites have their file already; others come as WebP when the
+    // target app takes it. The URL fallback of a favorite is its GIF, the
+    // format its file is in.
+    let favorite = state
+        .gif_library
+        .lock()
+        .find_favorite(&url)
+        .map(|favorite| (favorite.file.clone(), favorite.gif.full_url.clone()));
+    let paste_url = gif
+        .as_ref()
+        .map_or(url, |gif| gif_manager::url_for_target(gif).to_string());
+    let settings = UserSettingsManager::new().load().gifs;
+    let file_uri = tokio::task::spawn_blocking(move || match favorite {
+        Some((file, gif_url)) => gif_manager::copy_gif_file(&file, &gif_url),
+        None => {
+            let provider = gif_provider::provider_for(&settings);
+            let result =
+                gif_manager::paste_gif_to_clipboard_with_uri(provider.as_ref(), &paste_url);
+            if let Err(e) = gif_manager::enforce_cache_limit(settings.cache_limit_bytes()) {
+                eprintln!("[GifManager] Cache cleanup failed: {}", e);
+            }
+            result
+        }
     })
     .await
     .map_err(|e| e.to_string())?
     .map_err(|e| e.to_string())?;
 
+    if let Some(gif) = gif {
+        state.gif_library.lock().record_recent(gif);
+    }
+
     // 2. Mark as pasted
     if let Some(uri) = file_uri {
         let mut manager = state.clipboard_manager.lock();
@@ -277,25 +1423,65 @@ impl WindowController {
         }
     }
 
+    /// Shows the main window (if hidden) and opens the Setup Wizard in it
+    pub fn show_setup_wizard(app: &AppHandle) {
+        if let Some(window) = app.get_webview_window("main") {
+            if !window.is_visible().unwrap_or(false) {
+                Self::toggle(app);
+            }
+        }
+        let _ = app.emit("show-setup-wizard", ());
+    }
+
+    /// Shows the main window (if hidden) switched to the given tab
+    pub fn show_tab(app: &AppHandle, tab: &str) {
+        if let Some(window) = app.get_webview_window("main") {
+            if !window.is_visible().unwrap_or(false) {
+                Self::toggle(app);
+            }
+        }
+        let _ = app.emit("open-tab", tab);
+    }
+
     pub fn hide(app: &AppHandle) {
         if let Some(window) = app.get_webview_window("main") {
             // FLUSH CONFIG TO DISK ON HIDE
             if let Some(state) = app.try_state::<AppState>() {
-                if is_wayland() {
-                    state.config_manager.lock().sync_to_disk();
+                let mut config = state.config_manager.lock();
+                config.sync_to_disk();
+                // The sidebar stays up; pasting only hands focus back
+                if config.placement_mode() == PlacementMode::Docked {
+                    return;
                 }
             }
             let _ = window.hide();
         }
     }
 
+    /// Moves a visible window to the dock, after the mode or edge changed
+    pub fn redock(app: &AppHandle) {
+        let Some(window) = app.get_webview_window("main") else {
+            return;
+        };
+        let state = app.state::<AppState>();
+        let docked = state.config_manager.lock().placement_mode() == PlacementMode::Docked;
+        if docked && window.is_visible().unwrap_or(false) {
+            Self::position_docked(&window, &state);
+            let _ = window.set_always_on_top(true);
+        }
+    }
+
     fn position_and_show(window: &WebviewWindow, app: &AppHandle) {
         let state = app.state::<AppState>();
 
-        if is_wayland() {
-            Self::position_for_wayland(window, &state);
-        } else {
-            Self::position_for_non_wayland(window);
+        let placement_mode = state.config_manager.lock().placement_mode();
+        let docked = placement_mode == PlacementMode::Docked;
+        if docked {
+            Self::position_docked(window, &state);
+        } else if !placement_mode.follows_cursor(is_wayland())
+            || !Self::position_near_cursor(window, &state)
+        {
+            Self::position_remembered(window, &state);
         }
 
         #[cfg(target_os = "linux")]
@@ -311,7 +1497,11 @@ impl WindowController {
             let _ = window.set_focus();
         } else {
             // X11 born as normal window.
-            // We do NOT activate always_on_top to avoid focus blocking and glitch.
+            // We do NOT activate always_on_top to avoid focus blocking and glitch,
+            // except for the docked sidebar, which stays on top by design.
+            if docked {
+                let _ = window.set_always_on_top(true);
+            }
             let _ = window.show();
         }
 
@@ -324,7 +1514,9 @@ impl WindowController {
             #[cfg(target_os = "linux")]
             if is_wayland_session {
                 std::thread::sleep(std::time::Duration::from_millis(100));
-                let _ = window_clone.set_always_on_top(false);
+                if !docked {
+                    let _ = window_clone.set_always_on_top(false);
+                }
                 let _ = window_clone.set_focus();
             } else {
                 // Use EWMH _NET_ACTIVE_WINDOW protocol with polling instead of fixed sleep.
@@ -363,231 +1555,1143 @@ impl WindowController {
         }
     }
 
-    fn position_for_wayland(window: &WebviewWindow, state: &State<AppState>) {
-        let config = state.config_manager.lock();
+    /// Where the window was last moved to, bottom-center at first
+    fn position_remembered(window: &WebviewWindow, state: &State<AppState>) {
+        let mut config = state.config_manager.lock();
+        let win_size = Self::default_size(window, &config);
+
+        let window_state = config.get_state();
+        let monitors = Self::target_monitors(window, &config, None);
+        if let Some((pos, size)) = resolve_window_geometry(&window_state, &monitors, win_size) {
+            Self::apply_size(window, &mut config, size);
+            Self::move_to(window, pos, &monitors, None);
+        }
+    }
+
+    /// Along the saved edge of the monitor the window is on
+    fn position_docked(window: &WebviewWindow, state: &State<AppState>) {
+        let mut config = state.config_manager.lock();
+        let window_state = config.get_state();
+        let monitors = Self::target_monitors(window, &config, None);
+        let Some(monitor) = Self::current_monitor(window, &monitors) else {
+            return;
+        };
+        let (pos, size) = dock_geometry(&window_state, &monitor);
+        Self::apply_size(window, &mut config, size);
+        Self::move_to(window, pos, &[monitor], Some(window_state.dock_edge));
+    }
+
+    /// Next to the pointer. False when the pointer position is unknown.
+    fn position_near_cursor(window: &WebviewWindow, state: &State<AppState>) -> bool {
+        let Some((cursor_x, cursor_y)) = Self::get_cursor_position(window) else {
+            return false;
+        };
+
+        let mut config = state.config_manager.lock();
+        let window_state = config.get_state();
+        let win_size = Self::default_size(window, &config);
+        let monitors = Self::target_monitors(window, &config, Some((cursor_x, cursor_y)));
+        let placement = place_near_cursor(cursor_x, cursor_y, &window_state, &monitors, win_size)
+            .or_else(|| {
+                // Pointer outside every monitor it may open on, keep the window on one
+                let monitor = Self::current_monitor(window, &monitors)?;
+                let size = resolve_window_size(&window_state, &monitor, win_size);
+                Some((clamp_to_monitor(cursor_x, cursor_y, &monitor, size), size))
+            });
+
+        match placement {
+            Some((pos, size)) => {
+                Self::apply_size(window, &mut config, size);
+                Self::move_to(window, pos, &monitors, None);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// The monitors the window may open on: the one picked by the "open on
+    /// monitor" setting, or all of them when it picks none
+    fn target_monitors(
+        window: &WebviewWindow,
+        config: &ConfigManager,
+        pointer: Option<(i32, i32)>,
+    ) -> Vec<MonitorGeometry> {
+        let monitors = Self::with_work_areas(
+            window
+                .available_monitors()
+                .unwrap_or_default()
+                .iter()
+                .map(MonitorGeometry::from)
+                .collect(),
+        );
+
+        let (pointer, focused_window) = match config.monitor_choice() {
+            MonitorChoice::Pointer => (pointer.or_else(|| Self::get_cursor_position(window)), None),
+            #[cfg(target_os = "linux")]
+            MonitorChoice::FocusedWindow => (None, focused_window_center()),
+            _ => (None, None),
+        };
+        match config.chosen_monitor(&monitors, pointer, focused_window) {
+            Some(monitor) => vec![monitor],
+            None => monitors,
+        }
+    }
+
+    /// The monitor the window is on if it is one of `monitors`, else the first
+    fn current_monitor(
+        window: &WebviewWindow,
+        monitors: &[MonitorGeometry],
+    ) -> Option<MonitorGeometry> {
+        let current = window
+            .current_monitor()
+            .ok()
+            .flatten()
+            .or_else(|| window.primary_monitor().ok().flatten());
+        current
+            .and_then(|current| monitors.iter().find(|m| m.name.as_ref() == current.name()))
+            .or_else(|| monitors.first())
+            .cloned()
+    }
+
+    /// Adds the space left by panels on X11. Wayland doesn't share it, so
+    /// the usual padding is kept there.
+    fn with_work_areas(mut monitors: Vec<MonitorGeometry>) -> Vec<MonitorGeometry> {
+        #[cfg(target_os = "linux")]
+        if !is_wayland() {
+            win11_clipboard_history_lib::work_area::fill_work_areas(&mut monitors);
+        }
+        monitors
+    }
+
+    /// Puts the window's top-left corner at `pos`. A layer-shell window is
+    /// anchored to its monitor instead, and stretched along `dock_edge`.
+    fn move_to(
+        window: &WebviewWindow,
+        pos: PhysicalPosition<i32>,
+        monitors: &[MonitorGeometry],
+        dock_edge: Option<DockEdge>,
+    ) {
+        #[cfg(all(target_os = "linux", feature = "layer-shell"))]
+        if LAYER_SHELL_ACTIVE.load(Ordering::SeqCst) {
+            if let Some(placement) = layer_placement(pos, monitors, dock_edge) {
+                layer_shell::place(window, placement);
+            }
+            return;
+        }
+        let _ = (monitors, dock_edge);
+        let _ = window.set_position(pos);
+    }
+
+    /// Size for a window that was never resized, from the density setting
+    fn default_size(window: &WebviewWindow, config: &ConfigManager) -> PhysicalSize<u32> {
+        config.default_window_size(window.scale_factor().unwrap_or(1.0))
+    }
+
+    fn apply_size(window: &WebviewWindow, config: &mut ConfigManager, size: PhysicalSize<u32>) {
+        if window.inner_size().ok() != Some(size) {
+            config.set_applied_size(size);
+            let _ = window.set_size(size);
+        }
+    }
+
+    fn get_cursor_position(window: &WebviewWindow) -> Option<(i32, i32)> {
+        if let Ok(pos) = window.cursor_position() {
+            return Some((pos.x as i32, pos.y as i32));
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            if is_wayland() {
+                if let Some(p) = win11_clipboard_history_lib::hyprland_ipc::cursor_position() {
+                    return Some(p);
+                }
+            }
+            if let Some(p) = Self::get_cursor_xdotool() {
+                return Some(p);
+            }
+            if let Some(p) = Self::get_cursor_x11() {
+                return Some(p);
+            }
+        }
+
+        None
+    }
+
+    #[cfg(target_os = "linux")]
+    fn get_cursor_xdotool() -> Option<(i32, i32)> {
+        let output = std::process::Command::new("xdotool")
+            .args(["getmouselocation", "--shell"])
+            .output()
+            .ok()?;
+
+        if !output.status.success() {
+            return None;
+        }
+
+        let s = String::from_utf8_lossy(&output.stdout);
+        let (mut x, mut y) = (None, None);
+        for line in s.lines() {
+            if let Some(v) = line.strip_prefix("X=") {
+                x = v.parse().ok();
+            }
+            if let Some(v) = line.strip_prefix("Y=") {
+                y = v.parse().ok();
+            }
+        }
+        x.zip(y)
+    }
+
+    #[cfg(target_os = "linux")]
+    fn get_cursor_x11() -> Option<(i32, i32)> {
+        use x11rb::connection::Connection;
+        use x11rb::protocol::xproto::ConnectionExt;
+        let (conn, n) = x11rb::connect(None).ok()?;
+        let root = conn.setup().roots.get(n)?.root;
+        let r = conn.query_pointer(root).ok()?.reply().ok()?;
+        Some((r.root_x as i32, r.root_y as i32))
+    }
+}
+
+// --- Settings Window Controller ---
+
+struct SettingsController;
+
+impl SettingsController {
+    /// Shows the settings window, recreating it if somehow destroyed
+    pub fn show(app: &AppHandle) {
+        use tauri::{WebviewUrl, WebviewWindowBuilder};
+
+        match app.get_webview_window("settings") {
+            Some(window) => {
+                let _ = window.show();
+                let _ = window.set_focus();
+            }
+            None => {
+                // Fallback: recreate the window if it was somehow destroyed
+                eprintln!(
+                    "[SettingsController] Settings window missing, recreating as fallback..."
+                );
+
+                match WebviewWindowBuilder::new(
+                    app,
+                    "settings",
+                    WebviewUrl::App("index.html".into()),
+                )
+                .title("Settings - Clipboard History")
+                .inner_size(480.0, 520.0)
+                .resizable(false)
+                .decorations(true)
+                .transparent(false)
+                .visible(true)
+                .skip_taskbar(false)
+                .always_on_top(false)
+                .center()
+                .focused(true)
+                .build()
+                {
+                    Ok(_) => {
+                        println!("[SettingsController] Settings window recreated successfully")
+                    }
+                    Err(e) => eprintln!("[SettingsController] Failed to recreate window: {}", e),
+                }
+            }
+        }
+    }
+}
+
+// --- Window Event Helper ---
+
+/// Remembers where the window was moved to, unless it follows the pointer
+fn handle_window_moved(
+    window: &WebviewWindow,
+    state: &State<AppState>,
+    pos: &PhysicalPosition<i32>,
+) {
+    if !window.is_visible().unwrap_or(false) {
+        return;
+    }
+
+    let monitor_name = window
+        .current_monitor()
+        .ok()
+        .flatten()
+        .and_then(|m| m.name().map(|n| n.to_string()));
+
+    let mut config = state.config_manager.lock();
+    if !config.placement_mode().remembers_position(is_wayland()) {
+        return;
+    }
+    // UPDATE MEMORY ONLY (No Disk I/O here)
+    config.update_state(monitor_name, pos.x, pos.y);
+}
+
+/// Remembers the size the window was resized to, in whichever placement mode
+fn handle_window_resized(window: &WebviewWindow, state: &State<AppState>, size: PhysicalSize<u32>) {
+    if !window.is_visible().unwrap_or(false) || size.width == 0 || size.height == 0 {
+        return;
+    }
+    let scale_factor = window.scale_factor().unwrap_or(1.0);
+    state.config_manager.lock().update_size(size, scale_factor);
+}
+
+// --- Background Listeners ---
+
+fn start_clipboard_watcher(app: AppHandle, clipboard_manager: Arc<Mutex<ClipboardManager>>) {
+    std::thread::spawn(move || {
+        // Used while an event listener is reconnecting, and on sessions without one
+        let mut poll_reader = ClipboardReader::default();
+        let poll = || poll_reader.capture(&app, &clipboard_manager);
+
+        // Event-driven capture on compositors with a data-control protocol
+        #[cfg(target_os = "linux")]
+        if let Some(protocol) = win11_clipboard_history_lib::session::data_control_protocol() {
+            listener_supervisor::supervise(
+                "clipboard",
+                protocol.name(),
+                || {
+                    wayland_clipboard::run_listener(
+                        protocol,
+                        SelectionKind::Clipboard,
+                        || true,
+                        |event| {
+                            // Looked up before locking, some focus strategies take a moment
+                            let (source_app, record) = match &event {
+                                WaylandClipboardEvent::Text { text, .. } => (
+                                    copy_source_app(),
+                                    sync_selection(&app, SelectionKind::Clipboard, text),
+                                ),
+                                _ => (None, true),
+                            };
+                            let mut manager = clipboard_manager.lock();
+                            let item = match event {
+                                WaylandClipboardEvent::Text { text, html }
+                                    if !text.is_empty() && record =>
+                                {
+                                    manager.add_text_from(text, html, source_app)
+                                }
+                                WaylandClipboardEvent::Image { data, hash } => {
+                                    manager.add_image(data, hash)
+                                }
+                                _ => None,
+                            };
+                            if let Some(item) = item {
+                                item_captured(&app, &item);
+                            }
+                        },
+                    )
+                },
+                poll,
+            );
+        }
+
+        // Event-driven capture on X11 through XFixes
+        #[cfg(target_os = "linux")]
+        if win11_clipboard_history_lib::session::is_x11() {
+            let mut reader = ClipboardReader::default();
+            listener_supervisor::supervise(
+                "clipboard",
+                "XFixes",
+                || {
+                    x11_clipboard::run_listener(SelectionKind::Clipboard, || {
+                        reader.capture(&app, &clipboard_manager)
+                    })
+                },
+                poll,
+            );
+        }
+
+        listener_supervisor::poll_forever("clipboard", poll);
+    });
+}
+
+/// Sends a newly recorded item to the windows and counts it in the usage stats
+fn item_captured(app: &AppHandle, item: &ClipboardItem) {
+    let today = chrono::Local::now().date_naive();
+    app.state::<AppState>()
+        .usage_stats
+        .lock()
+        .record_copy(item.source_app.as_deref(), today);
+    let _ = app.emit("clipboard-changed", item);
+    generate_thumbnails(app, vec![item.clone()]);
+    if matches!(item.content, ClipboardContent::Image { .. }) {
+        compress_stored_images(app);
+    }
+}
+
+/// Re-encodes images past the most recent few on the worker pool, per the
+/// image compression settings. The history lock is only held to read the
+/// candidates and swap in the results, which are saved together.
+fn compress_stored_images(app: &AppHandle) {
+    let app = app.clone();
+    worker_pool::global().spawn("image compression", move || {
+        let settings = UserSettingsManager::new().load().image_compression;
+        if settings.format() == StoredImageFormat::Png {
+            return;
+        }
+        let state = app.state::<AppState>();
+        let candidates = image_compression::candidates(
+            state.clipboard_manager.lock().items(),
+            settings.keep_originals,
+        );
+        let replacements: Vec<(String, String)> = candidates
+            .into_iter()
+            .filter_map(|(id, base64)| {
+                image_compression::compress(&base64, &settings).map(|encoded| (id, encoded))
+            })
+            .collect();
+        if !replacements.is_empty() {
+            state
+                .clipboard_manager
+                .lock()
+                .replace_image_data(replacements);
+        }
+    });
+}
+
+/// Generates thumbnails of image items on the worker pool at the main
+/// window's scale, so the list finds them cached. Callers may hold the
+/// history lock, which the task only takes once it runs.
+fn generate_thumbnails(app: &AppHandle, items: Vec<ClipboardItem>) {
+    let images: Vec<ClipboardItem> = items
+        .into_iter()
+        .filter(|item| matches!(item.content, ClipboardContent::Image { .. }))
+        .collect();
+    if images.is_empty() {
+        return;
+    }
+    let scale_factor = app
+        .get_webview_window("main")
+        .and_then(|window| window.scale_factor().ok())
+        .unwrap_or(1.0);
+    let app = app.clone();
+    worker_pool::global().spawn("thumbnail", move || {
+        let state = app.state::<AppState>();
+        // Thumbnails are cached on disk, like the history they belong to
+        if !state.clipboard_manager.lock().persists_to_disk() {
+            return;
+        }
+        for item in images {
+            if thumbnail_manager::cached_thumbnail_path(&item, scale_factor).is_some() {
+                continue;
+            }
+            let item = match state.clipboard_manager.lock().with_image_data(&item) {
+                Ok(item) => item,
+                Err(e) => {
+                    eprintln!("[Thumbnails] Failed for {}: {}", item.id, e);
+                    continue;
+                }
+            };
+            if let Err(e) = thumbnail_manager::get_thumbnail_path(&item, scale_factor) {
+                eprintln!("[Thumbnails] Failed for {}: {}", item.id, e);
+            }
+        }
+    });
+}
+
+/// Drops thumbnails of images that left the history, generates the missing
+/// ones and compresses older images, once at startup
+fn start_thumbnail_warmup(app: AppHandle, manager: Arc<Mutex<ClipboardManager>>) {
+    std::thread::spawn(move || {
+        let history = {
+            let manager = manager.lock();
+            if !manager.persists_to_disk() {
+                return;
+            }
+            manager.get_history()
+        };
+        let pruned = thumbnail_manager::prune_cache(&thumbnail_manager::cache_keys(&history));
+        if pruned > 0 {
+            println!("[Thumbnails] Removed {} stale thumbnail(s)", pruned);
+        }
+        generate_thumbnails(&app, history);
+        compress_stored_images(&app);
+    });
+}
+
+/// Drops the thumbnails of images that just left the history, e.g. on
+/// delete, clear or retention. The directory scan runs off the lock.
+fn prune_thumbnails(manager: &ClipboardManager) {
+    let keep = thumbnail_manager::cache_keys(manager.items());
+    std::thread::spawn(move || thumbnail_manager::prune_cache(&keep));
+}
+
+fn primary_watch_enabled() -> bool {
+    PRIMARY_SELECTION_ENABLED.load(Ordering::Relaxed)
+        || COPY_ON_SELECT_ENABLED.load(Ordering::Relaxed)
+        || SELECTION_SYNC_ENABLED.load(Ordering::Relaxed)
+}
+
+/// Settled selections go into history with copy-on-select, and with
+/// selection sync, where CLIPBOARD follows every step of a drag
+fn commits_selections() -> bool {
+    COPY_ON_SELECT_ENABLED.load(Ordering::Relaxed) || SELECTION_SYNC_ENABLED.load(Ordering::Relaxed)
+}
+
+/// Mirrors new text in `from` to the other selection when selection sync is
+/// on. Returns false when `from` only holds the text because we mirrored it
+/// there, so it is not recorded twice.
+fn sync_selection(app: &AppHandle, from: SelectionKind, text: &str) -> bool {
+    if !SELECTION_SYNC_ENABLED.load(Ordering::Relaxed) {
+        return true;
+    }
+    let state = app.state::<AppState>();
+    let mut sync = state.selection_sync.lock();
+    if sync.should_mirror(from, text) {
+        #[cfg(target_os = "linux")]
+        if let Err(e) = primary_selection::write_selection_text(from.other(), text) {
+            eprintln!("[SelectionSync] Failed to set {:?}: {}", from.other(), e);
+        }
+    }
+    !sync.is_mirrored(from, text)
+}
+
+/// Adds a settled selection to the main history (copy-on-select)
+fn commit_selection(app: &AppHandle, text: String, source_app: Option<String>) {
+    let state = app.state::<AppState>();
+    let item = state
+        .clipboard_manager
+        .lock()
+        .add_text_from(text, None, source_app);
+    if let Some(item) = item {
+        item_captured(app, &item);
+    }
+}
+
+/// Checks every `SELECTION_TICK` whether the held copy-on-select selection
+/// is due, since nothing else happens while the user leaves it selected
+fn start_selection_commit_ticker(app: AppHandle) {
+    const SELECTION_TICK: Duration = Duration::from_millis(500);
+
+    std::thread::spawn(move || loop {
+        std::thread::sleep(SELECTION_TICK);
+        if !commits_selections() {
+            continue;
+        }
+        let committer = app.state::<AppState>().selection_committer.clone();
+        if !committer.lock().is_holding() {
+            continue;
+        }
+        let window = passive_source_app();
+        let due = committer.lock().tick(window.as_deref(), Instant::now());
+        if let Some((text, source_app)) = due {
+            commit_selection(&app, text, source_app);
+        }
+    });
+}
+
+/// Records the PRIMARY selection into its own pane and feeds copy-on-select.
+/// Event-driven like the clipboard watcher, with polling as the fallback.
+/// Safe to call repeatedly.
+fn start_primary_selection_watcher(app: AppHandle, primary_history: Arc<Mutex<PrimaryHistory>>) {
+    if PRIMARY_WATCHER_STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    start_selection_commit_ticker(app.clone());
+
+    std::thread::spawn(move || {
+        let committer = app.state::<AppState>().selection_committer.clone();
+        let record = |text: String| {
+            if !sync_selection(&app, SelectionKind::Primary, &text) {
+                return;
+            }
+            if commits_selections() {
+                let window = selection_source_app();
+                let due = committer
+                    .lock()
+                    .select(text.clone(), window, Instant::now());
+                if let Some((due, source_app)) = due {
+                    commit_selection(&app, due, source_app);
+                }
+            }
+            if !PRIMARY_SELECTION_ENABLED.load(Ordering::Relaxed) {
+                return;
+            }
+            if let Some(item) = primary_history.lock().add_text(text) {
+                let _ = app.emit("primary-selection-changed", &item);
+            }
+        };
+
+        let mut last_text: Option<String> = None;
+        let poll = || {
+            if !primary_watch_enabled() {
+                return;
+            }
+            #[cfg(target_os = "linux")]
+            if let Some(text) = primary_selection::read_primary_text() {
+                if last_text.as_deref() != Some(text.as_str()) {
+                    last_text = Some(text.clone());
+                    record(text);
+                }
+            }
+        };
+
+        #[cfg(target_os = "linux")]
+        if let Some(protocol) = win11_clipboard_history_lib::session::data_control_protocol() {
+            listener_supervisor::supervise(
+                "primary",
+                protocol.name(),
+                || {
+                    wayland_clipboard::run_listener(
+                        protocol,
+                        SelectionKind::Primary,
+                        primary_watch_enabled,
+                        |event| {
+                            if let WaylandClipboardEvent::Text { text, .. } = event {
+                                record(text);
+                            }
+                        },
+                    )
+                },
+                poll,
+            );
+        }
+
+        #[cfg(target_os = "linux")]
+        if win11_clipboard_history_lib::session::is_x11() {
+            listener_supervisor::supervise(
+                "primary",
+                "XFixes",
+                || {
+                    x11_clipboard::run_listener(SelectionKind::Primary, || {
+                        if !primary_watch_enabled() {
+                            return;
+                        }
+                        if let Some(text) = primary_selection::read_primary_text() {
+                            record(text);
+                        }
+                    })
+                },
+                poll,
+            );
+        }
+
+        listener_supervisor::poll_forever("primary", poll);
+    });
+}
+
+/// WM_CLASS class or app id of the window a copy came from
+fn copy_source_app() -> Option<String> {
+    #[cfg(target_os = "linux")]
+    return win11_clipboard_history_lib::focus_manager::active_window_class()
+        .and_then(|names| names.last().cloned());
 
-        if let Ok(monitors) = window.available_monitors() {
-            if !monitors.is_empty() {
-                let win_size = window.outer_size().unwrap_or(PhysicalSize::new(360, 480));
+    #[cfg(not(target_os = "linux"))]
+    None
+}
 
-                let window_state = config.get_state();
-                let pos = resolve_window_position(&window_state, &monitors, win_size);
+/// `copy_source_app` for the ticker, which runs every half second: unknown
+/// rather than a script load when the focus adapter is an expensive one
+fn passive_source_app() -> Option<String> {
+    #[cfg(target_os = "linux")]
+    return win11_clipboard_history_lib::focus_manager::passive_window_class()
+        .and_then(|names| names.last().cloned());
 
-                let _ = window.set_position(pos);
-            }
+    #[cfg(not(target_os = "linux"))]
+    None
+}
+
+/// `copy_source_app` for PRIMARY changes, which arrive many times a second
+/// during a drag. Looked up at most once per `SELECTION_WINDOW_TTL`.
+fn selection_source_app() -> Option<String> {
+    const SELECTION_WINDOW_TTL: Duration = Duration::from_secs(1);
+    static LAST: Mutex<Option<(Instant, Option<String>)>> = Mutex::new(None);
+
+    let mut last = LAST.lock();
+    match &*last {
+        Some((at, window)) if at.elapsed() < SELECTION_WINDOW_TTL => window.clone(),
+        _ => {
+            let window = copy_source_app();
+            *last = Some((Instant::now(), window.clone()));
+            window
         }
     }
+}
 
-    fn position_for_non_wayland(window: &WebviewWindow) {
-        let (cursor_x, cursor_y) = match Self::get_cursor_position(window) {
-            Some(pos) => pos,
-            None => {
-                // Fallback: center the window if we can't get cursor position
-                let _ = window.center();
-                return;
-            }
-        };
+/// Reads the system clipboard and records anything new, remembering the
+/// last seen content so unchanged clipboards are not re-processed.
+#[derive(Default)]
+struct ClipboardReader {
+    last_text_hash: Option<u64>,
+    last_image_hash: Option<u64>,
+}
 
-        let target_monitor = Self::find_monitor_containing(window, cursor_x, cursor_y)
-            .or_else(|| window.current_monitor().ok().flatten())
-            .or_else(|| window.primary_monitor().ok().flatten());
+impl ClipboardReader {
+    fn capture(&mut self, app: &AppHandle, clipboard_manager: &Mutex<ClipboardManager>) {
+        let mut manager = clipboard_manager.lock();
+        let capture = manager.get_capture_policy();
 
-        if let Some(monitor) = target_monitor {
-            let pos = Self::clamp_window_to_monitor(window, &monitor, cursor_x, cursor_y);
-            let _ = window.set_position(pos);
-        }
-    }
+        // Text
+        if let Ok(text) = manager.get_current_text() {
+            if !text.is_empty() {
+                let text_hash =
+                    win11_clipboard_history_lib::clipboard_manager::calculate_hash(&text);
 
-    fn find_monitor_containing(window: &WebviewWindow, x: i32, y: i32) -> Option<Monitor> {
-        window.available_monitors().ok()?.into_iter().find(|m| {
-            let p = m.position();
-            let s = m.size();
-            x >= p.x && x < (p.x + s.width as i32) && y >= p.y && y < (p.y + s.height as i32)
-        })
-    }
+                if Some(text_hash) != self.last_text_hash {
+                    self.last_text_hash = Some(text_hash);
+                    self.last_image_hash = None;
 
-    fn clamp_window_to_monitor(
-        window: &WebviewWindow,
-        monitor: &Monitor,
-        x: i32,
-        y: i32,
-    ) -> PhysicalPosition<i32> {
-        let win_size = window.outer_size().unwrap_or(PhysicalSize::new(360, 480));
-        let m_pos = monitor.position();
-        let m_size = monitor.size();
+                    // Selected text mirrored here by selection sync is
+                    // recorded once the selection settles instead
+                    if !sync_selection(app, SelectionKind::Clipboard, &text) {
+                        return;
+                    }
 
-        let max_x = m_pos.x + m_size.width as i32 - win_size.width as i32;
-        let max_y = m_pos.y + m_size.height as i32 - win_size.height as i32;
+                    // Try to get HTML content for rich text support
+                    let html = if capture.html {
+                        manager.get_current_html()
+                    } else {
+                        None
+                    };
 
-        // Clamp with 10px padding
-        let safe_x = x.clamp(m_pos.x + 10, max_x - 10);
-        let safe_y = y.clamp(m_pos.y + 10, max_y - 10);
+                    if let Some(item) = manager.add_text_from(text, html, copy_source_app()) {
+                        item_captured(app, &item);
+                    }
+                }
+            }
+        }
 
-        PhysicalPosition::new(safe_x, safe_y)
+        // Image (skip reading it at all when images are not captured)
+        if !capture.images {
+            return;
+        }
+        if let Ok(Some((image_data, hash))) = manager.get_current_image() {
+            if Some(hash) != self.last_image_hash {
+                self.last_image_hash = Some(hash);
+                self.last_text_hash = None;
+                if let Some(item) = manager.add_image(image_data, hash) {
+                    item_captured(app, &item);
+                }
+            }
+        }
     }
+}
 
-    fn get_cursor_position(window: &WebviewWindow) -> Option<(i32, i32)> {
-        if let Ok(pos) = window.cursor_position() {
-            return Some((pos.x as i32, pos.y as i32));
+/// Periodically drops items that outlived their per-type retention window
+fn start_retention_pruner(app: AppHandle, clipboard_manager: Arc<Mutex<ClipboardManager>>) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(Duration::from_secs(60 * 60));
+        let prune_missing_files = UserSettingsManager::new().load().prune_missing_files;
+        let mut manager = clipboard_manager.lock();
+        let expired = manager.prune_expired();
+        let files_changed = manager.check_file_references(prune_missing_files);
+        if expired {
+            prune_thumbnails(&manager);
+        }
+        if expired || files_changed {
+            let _ = app.emit("history-sync", ());
         }
+    });
+}
 
-        #[cfg(target_os = "linux")]
-        {
-            if let Some(p) = Self::get_cursor_xdotool() {
-                return Some(p);
-            }
-            if let Some(p) = Self::get_cursor_x11() {
-                return Some(p);
+/// Sends the item ages that changed as `item-ages-changed`, waking when the
+/// soonest one goes stale and at least once a minute for new items
+fn start_item_age_ticker(app: AppHandle, clipboard_manager: Arc<Mutex<ClipboardManager>>) {
+    const MAX_AGE_TICK: Duration = Duration::from_secs(60);
+
+    std::thread::spawn(move || {
+        let mut sent: HashMap<String, String> = HashMap::new();
+        loop {
+            let now = chrono::Utc::now();
+            let (ages, next_change) = {
+                let manager = clipboard_manager.lock();
+                let items = manager.items();
+                let ages = item_age::item_ages(
+                    items.iter().map(|item| (item.id.as_str(), item.timestamp)),
+                    now,
+                    &chrono::Local,
+                    i18n::current(),
+                );
+                let next_change = items
+                    .iter()
+                    .filter_map(|item| item_age::next_change(item.timestamp, now, &chrono::Local))
+                    .min();
+                (ages, next_change)
+            };
+
+            let changed: HashMap<&String, &String> = ages
+                .iter()
+                .filter(|(id, age)| sent.get(*id) != Some(*age))
+                .collect();
+            if !changed.is_empty() {
+                let _ = app.emit("item-ages-changed", &changed);
             }
+            sent = ages;
+
+            let tick = next_change
+                .and_then(|next| next.to_std().ok())
+                .map_or(MAX_AGE_TICK, |next| next.min(MAX_AGE_TICK));
+            std::thread::sleep(tick.max(Duration::from_secs(1)));
         }
+    });
+}
 
-        None
+// --- Tray ---
+
+const TRAY_ID: &str = "main-tray";
+
+const TRAY_TOOLTIP: &str = "Clipboard History";
+const TRAY_TOOLTIP_SESSION_ONLY: &str = "Clipboard History (session only, not saved)";
+const TRAY_TOOLTIP_PAUSED: &str = "Clipboard History (paused)";
+const TRAY_TOOLTIP_SYNC_ERROR: &str = "Clipboard History (pinned items failed to sync)";
+
+/// Recent items listed at the top of the tray menu
+const TRAY_RECENT_COUNT: usize = 5;
+const TRAY_LABEL_MAX_CHARS: usize = 40;
+/// Menu ids of the recent items are this prefix and the item id
+const TRAY_RECENT_PREFIX: &str = "recent:";
+
+fn tray_tooltip(manager: &ClipboardManager) -> &'static str {
+    if pinned_git::last_error().is_some() {
+        TRAY_TOOLTIP_SYNC_ERROR
+    } else if manager.is_paused() {
+        TRAY_TOOLTIP_PAUSED
+    } else if manager.is_session_only() {
+        TRAY_TOOLTIP_SESSION_ONLY
+    } else {
+        TRAY_TOOLTIP
     }
+}
 
-    #[cfg(target_os = "linux")]
-    fn get_cursor_xdotool() -> Option<(i32, i32)> {
-        let output = std::process::Command::new("xdotool")
-            .args(["getmouselocation", "--shell"])
-            .output()
-            .ok()?;
+/// The app icon, badged when capture is paused or pinned items fail to sync
+fn tray_icon(manager: &ClipboardManager) -> Image<'static> {
+    let icon = Image::from_bytes(include_bytes!("../icons/icon.png")).unwrap();
+    let Some(badge) = TrayBadge::current(manager.is_paused(), pinned_git::last_error().is_some())
+    else {
+        return icon;
+    };
+    let (width, height) = (icon.width(), icon.height());
+    let mut rgba = icon.rgba().to_vec();
+    tray_badge::draw_badge(&mut rgba, width, height, badge);
+    Image::new_owned(rgba, width, height)
+}
 
-        if !output.status.success() {
-            return None;
+/// First line of the item's preview, shortened to fit the menu
+fn tray_item_label(item: &ClipboardItem) -> String {
+    let line = item.preview.lines().find(|line| !line.trim().is_empty());
+    let line = line.unwrap_or(&item.preview).trim();
+    let mut label: String = line.chars().take(TRAY_LABEL_MAX_CHARS).collect();
+    if line.chars().count() > TRAY_LABEL_MAX_CHARS {
+        label.push('…');
+    }
+    // A single & marks a mnemonic
+    label.replace('&', "&&")
+}
+
+fn tray_menu(app: &AppHandle) -> tauri::Result<Menu<tauri::Wry>> {
+    let state = app.state::<AppState>();
+    let manager = state.clipboard_manager.lock();
+    let menu = Menu::new(app)?;
+
+    let recent = manager.recent(TRAY_RECENT_COUNT);
+    if recent.is_empty() {
+        menu.append(&MenuItem::with_id(
+            app,
+            "no_recent",
+            "No items yet",
+            false,
+            None::<&str>,
+        )?)?;
+    }
+    for item in recent {
+        let id = format!("{}{}", TRAY_RECENT_PREFIX, item.id);
+        menu.append(&MenuItem::with_id(
+            app,
+            id,
+            tray_item_label(item),
+            true,
+            None::<&str>,
+        )?)?;
+    }
+    menu.append(&PredefinedMenuItem::separator(app)?)?;
+
+    menu.append(&MenuItem::with_id(
+        app,
+        "show",
+        "Show Clipboard",
+        true,
+        None::<&str>,
+    )?)?;
+    menu.append(&CheckMenuItem::with_id(
+        app,
+        "pause_capture",
+        "Pause Capture",
+        true,
+        manager.is_paused(),
+        None::<&str>,
+    )?)?;
+    menu.append(&CheckMenuItem::with_id(
+        app,
+        "session_only",
+        "Session-Only History",
+        true,
+        manager.is_session_only(),
+        None::<&str>,
+    )?)?;
+    menu.append(&MenuItem::with_id(
+        app,
+        "clear_history",
+        "Clear History",
+        true,
+        None::<&str>,
+    )?)?;
+    menu.append(&PredefinedMenuItem::separator(app)?)?;
+    menu.append(&MenuItem::with_id(
+        app,
+        "settings",
+        "Settings",
+        true,
+        None::<&str>,
+    )?)?;
+    menu.append(&MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?)?;
+    Ok(menu)
+}
+
+/// Rebuilds the tray menu, tooltip and badge from the current state. Call on
+/// the main thread.
+fn refresh_tray(app: &AppHandle) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    match tray_menu(app) {
+        Ok(menu) => {
+            let _ = tray.set_menu(Some(menu));
         }
+        Err(e) => eprintln!("[Tray] Failed to rebuild menu: {}", e),
+    }
+    let state = app.state::<AppState>();
+    let manager = state.clipboard_manager.lock();
+    let _ = tray.set_tooltip(Some(tray_tooltip(&manager)));
+    let _ = tray.set_icon(Some(tray_icon(&manager)));
+}
 
-        let s = String::from_utf8_lossy(&output.stdout);
-        let (mut x, mut y) = (None, None);
-        for line in s.lines() {
-            if let Some(v) = line.strip_prefix("X=") {
-                x = v.parse().ok();
-            }
-            if let Some(v) = line.strip_prefix("Y=") {
-                y = v.parse().ok();
-            }
+/// Rebuilds the tray menu whenever the history changes or the pinned items
+/// start or stop failing to sync, once a burst of changes settles
+fn start_tray_refresher(app: AppHandle, clipboard_manager: Arc<Mutex<ClipboardManager>>) {
+    const TRAY_REFRESH_DEBOUNCE: Duration = Duration::from_millis(300);
+
+    let (tx, rx) = std::sync::mpsc::channel::<()>();
+    let sync_tx = tx.clone();
+    pinned_git::set_error_listener(move || {
+        let _ = sync_tx.send(());
+    });
+    clipboard_manager.lock().set_change_listener(move || {
+        let _ = tx.send(());
+    });
+
+    std::thread::spawn(move || {
+        while rx.recv().is_ok() {
+            while rx.recv_timeout(TRAY_REFRESH_DEBOUNCE).is_ok() {}
+            let handle = app.clone();
+            let _ = app.run_on_main_thread(move || refresh_tray(&handle));
         }
-        x.zip(y)
+    });
+}
+
+/// Toggles session-only mode from the tray and refreshes the history view,
+/// since leaving the mode reloads history from disk
+fn toggle_session_only(app: &AppHandle) {
+    {
+        let state = app.state::<AppState>();
+        let mut manager = state.clipboard_manager.lock();
+        let enabled = !manager.is_session_only();
+        manager.set_session_only(enabled);
+        println!(
+            "[Tray] Session-only mode {}",
+            if enabled { "on" } else { "off" }
+        );
+        let _ = app.emit("history-sync", ());
     }
+    refresh_tray(app);
+}
 
-    #[cfg(target_os = "linux")]
-    fn get_cursor_x11() -> Option<(i32, i32)> {
-        use x11rb::connection::Connection;
-        use x11rb::protocol::xproto::ConnectionExt;
-        let (conn, n) = x11rb::connect(None).ok()?;
-        let root = conn.setup().roots.get(n)?.root;
-        let r = conn.query_pointer(root).ok()?.reply().ok()?;
-        Some((r.root_x as i32, r.root_y as i32))
+fn toggle_capture_paused(app: &AppHandle) {
+    {
+        let state = app.state::<AppState>();
+        let mut manager = state.clipboard_manager.lock();
+        let paused = !manager.is_paused();
+        manager.set_paused(paused);
+        println!(
+            "[Tray] Capture {}",
+            if paused { "paused" } else { "resumed" }
+        );
     }
+    refresh_tray(app);
 }
 
-// --- Settings Window Controller ---
+fn clear_history_from_tray(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    let mut manager = state.clipboard_manager.lock();
+    manager.clear();
+    prune_thumbnails(&manager);
+    let _ = app.emit("history-sync", ());
+}
 
-struct SettingsController;
+fn paste_from_tray(app: &AppHandle, id: String) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<AppState>();
+        if let Err(e) = paste_now_or_confirm(app.clone(), state, id).await {
+            eprintln!("[Tray] Failed to paste: {}", e);
+        }
+    });
+}
 
-impl SettingsController {
-    /// Shows the settings window, recreating it if somehow destroyed
-    pub fn show(app: &AppHandle) {
-        use tauri::{WebviewUrl, WebviewWindowBuilder};
+fn build_tray(app: &AppHandle) -> tauri::Result<()> {
+    let menu = tray_menu(app)?;
+    let (tooltip, icon) = {
+        let state = app.state::<AppState>();
+        let manager = state.clipboard_manager.lock();
+        (tray_tooltip(&manager), tray_icon(&manager))
+    };
 
-        match app.get_webview_window("settings") {
-            Some(window) => {
-                let _ = window.show();
-                let _ = window.set_focus();
+    // Get temp directory for tray icon (avoids permission issues with XDG_RUNTIME_DIR)
+    let temp_dir = std::env::temp_dir().join("win11-clipboard-history");
+    std::fs::create_dir_all(&temp_dir).ok();
+
+    TrayIconBuilder::with_id(TRAY_ID)
+        .icon(icon)
+        .tooltip(tooltip)
+        .temp_dir_path(temp_dir)
+        .menu(&menu)
+        .on_menu_event(move |app, event| match event.id.as_ref() {
+            "quit" => app.exit(0),
+            "show" => WindowController::toggle(app),
+            "pause_capture" => toggle_capture_paused(app),
+            "session_only" => toggle_session_only(app),
+            "clear_history" => clear_history_from_tray(app),
+            "settings" => SettingsController::show(app),
+            id => {
+                if let Some(item_id) = id.strip_prefix(TRAY_RECENT_PREFIX) {
+                    paste_from_tray(app, item_id.to_string());
+                }
             }
-            None => {
-                // Fallback: recreate the window if it was somehow destroyed
-                eprintln!(
-                    "[SettingsController] Settings window missing, recreating as fallback..."
-                );
+        })
+        .on_tray_icon_event(|tray, event| {
+            if let TrayIconEvent::Click {
+                button: MouseButton::Left,
+                ..
+            } = event
+            {
+                WindowController::toggle(tray.app_handle());
+            }
+        })
+        .build(app)?;
+    Ok(())
+}
 
-                match WebviewWindowBuilder::new(
-                    app,
-                    "settings",
-                    WebviewUrl::App("index.html".into()),
-                )
-                .title("Settings - Clipboard History")
-                .inner_size(480.0, 520.0)
-                .resizable(false)
-                .decorations(true)
-                .transparent(false)
-                .visible(true)
-                .skip_taskbar(false)
-                .always_on_top(false)
-                .center()
-                .focused(true)
-                .build()
-                {
-                    Ok(_) => {
-                        println!("[SettingsController] Settings window recreated successfully")
-                    }
-                    Err(e) => eprintln!("[SettingsController] Failed to recreate window: {}", e),
-                }
+/// Adds or removes the tray icon to match the "show tray icon" setting
+fn set_tray_visible(app: &AppHandle, visible: bool) {
+    let handle = app.clone();
+    let _ = app.run_on_main_thread(move || {
+        let shown = handle.tray_by_id(TRAY_ID).is_some();
+        if visible && !shown {
+            if let Err(e) = build_tray(&handle) {
+                eprintln!("[Tray] Failed to create tray icon: {}", e);
             }
+        } else if !visible && shown {
+            let _ = handle.remove_tray_by_id(TRAY_ID);
         }
-    }
+    });
 }
 
-// --- Window Event Helper ---
+/// Recreates the tray icon so it registers with a restarted StatusNotifierWatcher
+#[cfg(target_os = "linux")]
+fn rebuild_tray(app: &AppHandle) {
+    let handle = app.clone();
+    let _ = app.run_on_main_thread(move || {
+        // Turned off in the settings
+        if handle.remove_tray_by_id(TRAY_ID).is_none() {
+            return;
+        }
+        if let Err(e) = build_tray(&handle) {
+            eprintln!("[SessionMonitor] Failed to recreate tray icon: {}", e);
+        }
+    });
+}
 
-fn handle_window_moved_for_wayland(
-    window: &WebviewWindow,
-    state: &State<AppState>,
-    pos: &PhysicalPosition<i32>,
-) {
-    if !is_wayland() || !window.is_visible().unwrap_or(false) {
-        return;
-    }
+/// Re-initializes connections after a resume or a shell/compositor restart
+#[cfg(target_os = "linux")]
+fn start_session_monitor(app: AppHandle) {
+    use win11_clipboard_history_lib::session_monitor::{self, SessionEvent};
 
-    let monitor_name = window
-        .current_monitor()
-        .ok()
-        .flatten()
-        .and_then(|m| m.name().map(|n| n.to_string()));
+    session_monitor::watch_session_events(move |event| {
+        println!("[SessionMonitor] {:?}", event);
 
-    let mut config = state.config_manager.lock();
-    // UPDATE MEMORY ONLY (No Disk I/O here)
-    config.update_state(monitor_name, pos.x, pos.y);
-}
+        // Saved window ids and display connections may be stale now
+        win11_clipboard_history_lib::focus_manager::forget_focused_window();
+        listener_supervisor::retry_now();
 
-// --- Background Listeners ---
+        if let SessionEvent::ShellRestarted(name) = &event {
+            if name == "org.kde.StatusNotifierWatcher" {
+                rebuild_tray(&app);
+            }
+        }
 
-fn start_clipboard_watcher(app: AppHandle, clipboard_manager: Arc<Mutex<ClipboardManager>>) {
-    std::thread::spawn(move || {
-        let mut last_text_hash: Option<u64> = None;
-        let mut last_image_hash: Option<u64> = None;
+        // A restarted shell may have reloaded its keybindings without ours;
+        // give it a moment to come up before checking
+        std::thread::spawn(|| {
+            std::thread::sleep(Duration::from_secs(3));
+            win11_clipboard_history_lib::linux_shortcut_manager::repair_shortcut_registration();
+        });
+    });
+}
 
-        loop {
-            std::thread::sleep(Duration::from_millis(500));
-            let mut manager = clipboard_manager.lock();
+/// Tells the windows when the desktop switches between dark and light
+#[cfg(target_os = "linux")]
+fn start_color_scheme_watch(app: AppHandle) {
+    win11_clipboard_history_lib::color_scheme::watch(move |scheme| {
+        println!("[ColorScheme] Desktop prefers {:?}", scheme);
+        let _ = app.emit("system-color-scheme-changed", scheme.as_str());
+    });
+}
 
-            // Text
-            if let Ok(text) = manager.get_current_text() {
-                if !text.is_empty() {
-                    let text_hash =
-                        win11_clipboard_history_lib::clipboard_manager::calculate_hash(&text);
+// --- Single Instance ---
 
-                    if Some(text_hash) != last_text_hash {
-                        last_text_hash = Some(text_hash);
-                        last_image_hash = None;
+/// Runs what a secondary instance was started to do (`--copy "text"`,
+/// `--paste-last`, `--open-tab gifs`, a `clip://` link, ...) in the running
+/// one, in that order
+fn run_instance_actions(app: &AppHandle, options: cli::CliOptions) {
+    if options.settings {
+        println!("[SingleInstance] Secondary instance with --settings flag, opening settings...");
+        SettingsController::show(app);
+    }
 
-                        // Try to get HTML content for rich text support
-                        let html = manager.get_current_html();
+    if let Some(tab) = &options.tab {
+        println!("[SingleInstance] Opening the {} tab...", tab);
+        WindowController::show_tab(app, tab);
+    }
+    if let Some(id) = &options.open_item {
+        println!("[SingleInstance] Opening linked item {}...", id);
+        WindowController::show_tab(app, "clipboard");
+        let _ = app.emit("focus-item", id);
+    }
+    if let Some(query) = &options.search {
+        println!("[SingleInstance] Opening history search...");
+        WindowController::show_tab(app, "clipboard");
+        let _ = app.emit("open-search", query);
+    }
 
-                        if let Some(item) = manager.add_text(text, html) {
-                            let _ = app.emit("clipboard-changed", &item);
-                        }
-                    }
-                }
+    if options.copy.is_none() && options.paste_recent.is_none() {
+        return;
+    }
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<AppState>();
+        if let Some(text) = options.copy {
+            // Add it right away so a following --paste-last finds it; the
+            // watcher skips it later as a duplicate
+            let item = state.clipboard_manager.lock().add_text(text.clone(), None);
+            if let Some(item) = item {
+                item_captured(&app, &item);
             }
-
-            // Image
-            if let Ok(Some((image_data, hash))) = manager.get_current_image() {
-                if Some(hash) != last_image_hash {
-                    last_image_hash = Some(hash);
-                    last_text_hash = None;
-                    if let Some(item) = manager.add_image(image_data, hash) {
-                        let _ = app.emit("clipboard-changed", &item);
-                    }
-                }
+            if let Err(e) = copy_text_to_clipboard(state.clone(), text).await {
+                eprintln!("[SingleInstance] Copy failed: {}", e);
+            }
+        }
+        if let Some(position) = options.paste_recent {
+            println!("[SingleInstance] Pasting item {}...", position);
+            if let Err(e) = paste_recent_item(app.clone(), state, position).await {
+                eprintln!("[SingleInstance] Quick paste failed: {}", e);
             }
         }
     });
@@ -598,42 +2702,31 @@ fn start_clipboard_watcher(app: AppHandle, clipboard_manager: Arc<Mutex<Clipboar
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
 fn main() {
-    let args: Vec<String> = std::env::args().collect();
-
-    // Handle --version / -v
-    if args.iter().any(|arg| arg == "--version" || arg == "-v") {
-        println!("win11-clipboard-history {}", VERSION);
-        return;
-    }
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    let options = match cli::parse_args(&args) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            eprintln!("Run '{} --help' for usage.", cli::BIN_NAME);
+            std::process::exit(cli::EXIT_USAGE);
+        }
+    };
 
-    // Handle --help / -h
-    if args.iter().any(|arg| arg == "--help" || arg == "-h") {
-        println!("win11-clipboard-history {}", VERSION);
-        println!();
-        println!("USAGE:");
-        println!("    win11-clipboard-history [OPTIONS]");
-        println!();
-        println!("OPTIONS:");
-        println!("    -h, --help       Show this help message");
-        println!("    -v, --version    Show version information");
-        println!("        --background Start minimized to system tray (for autostart)");
-        println!("        --settings   Open settings window on startup");
-        println!();
-        println!("SHORTCUTS:");
-        println!("    Super+V          Open clipboard history");
-        println!("    Ctrl+Alt+V       Alternative shortcut");
-        return;
+    // --help, --version, --list, --completions run without the GUI
+    if let Some(code) = cli::run_headless(&options, VERSION) {
+        std::process::exit(code);
     }
 
     // Check if --background flag is present (start minimized to tray)
-    let start_in_background = args.iter().any(|arg| arg == "--background");
+    let start_in_background = options.background;
     if start_in_background {
         println!("[Startup] Starting in background mode (system tray only)");
         STARTED_IN_BACKGROUND.store(true, Ordering::SeqCst);
     }
 
     // Check if --settings flag is present (for first instance startup)
-    let open_settings_on_start = args.iter().any(|arg| arg == "--settings");
+    let open_settings_on_start = options.settings;
 
     // Clone for use in setup closure
     let start_in_background_clone = start_in_background;
@@ -641,27 +2734,68 @@ fn main() {
     win11_clipboard_history_lib::session::init();
 
     let is_mouse_inside = Arc::new(AtomicBool::new(false));
-    let base_dir = dirs::data_local_dir()
-        .unwrap_or_else(|| std::path::PathBuf::from("."))
-        .join("win11-clipboard-history");
+    let base_dir = app_data_dir();
 
     // Ensure base directory exists
     if let Err(e) = std::fs::create_dir_all(&base_dir) {
         eprintln!("Failed to create base directory: {}", e);
     }
 
-    let history_path = base_dir.join("history.json");
-
-    // Load user settings to get max_history_size
+    // Load user settings to get the storage backend and max_history_size
     let user_settings = UserSettingsManager::new().load();
-    let clipboard_manager = Arc::new(Mutex::new(ClipboardManager::new(
-        history_path,
-        user_settings.max_history_size,
-    )));
-
-    let emoji_manager = Arc::new(Mutex::new(EmojiManager::new(base_dir.clone())));
 
-    let config_manager = Arc::new(Mutex::new(ConfigManager::new(base_dir)));
+    // Layer-shell needs GTK on Wayland rather than the XWayland the wrapper
+    // script picks, so this is settled before GTK starts
+    #[cfg(all(target_os = "linux", feature = "layer-shell"))]
+    let use_layer_shell = user_settings.use_layer_shell
+        && win11_clipboard_history_lib::session::layer_shell_available();
+    #[cfg(all(target_os = "linux", feature = "layer-shell"))]
+    if use_layer_shell {
+        std::env::set_var("GDK_BACKEND", "wayland");
+    }
+    #[cfg(not(all(target_os = "linux", feature = "layer-shell")))]
+    if user_settings.use_layer_shell {
+        eprintln!("[Startup] Built without layer-shell support, positioning through XWayland");
+    }
+    let mut history_manager = ClipboardManager::with_store(
+        open_history_store(user_settings.storage_backend()),
+        user_settings.max_history_size,
+    );
+    history_manager.set_retention_policy(user_settings.retention_policy());
+    history_manager.set_capture_policy(user_settings.capture_policy());
+    history_manager.set_memory_budget(user_settings.memory_budget_mb);
+    history_manager.set_pinned_mirror(
+        user_settings
+            .pinned_git_mirror_dir()
+            .map(PinnedGitMirror::new),
+    );
+    let clipboard_manager = Arc::new(Mutex::new(history_manager));
+
+    let mut emoji_manager = EmojiManager::new(base_dir.clone());
+    emoji_manager.set_variant_preference(user_settings.emoji_variants.clone());
+    let emoji_manager = Arc::new(Mutex::new(emoji_manager));
+    let gif_library = Arc::new(Mutex::new(GifLibrary::new(base_dir.clone())));
+    let sticker_manager = Arc::new(Mutex::new(StickerManager::new(base_dir.clone())));
+    let usage_stats = Arc::new(Mutex::new(UsageStats::new(base_dir.clone())));
+    let collections = Arc::new(Mutex::new(Collections::new(base_dir.clone())));
+
+    let mut window_config = ConfigManager::new(base_dir);
+    window_config.set_placement_mode(user_settings.placement_mode());
+    window_config.set_density(user_settings.ui_density());
+    window_config.set_monitor_choice(user_settings.monitor_choice());
+    i18n::set_language(user_settings.language());
+    let config_manager = Arc::new(Mutex::new(window_config));
+
+    let primary_history = Arc::new(Mutex::new(PrimaryHistory::new()));
+    PRIMARY_SELECTION_ENABLED.store(user_settings.enable_primary_selection, Ordering::SeqCst);
+    COPY_ON_SELECT_ENABLED.store(user_settings.copy_on_select, Ordering::SeqCst);
+    SELECTION_SYNC_ENABLED.store(user_settings.sync_selections, Ordering::SeqCst);
+    worker_pool::global().set_limit(user_settings.worker_limit());
+    serve_metrics(user_settings.metrics_port, clipboard_manager.clone());
+    let show_tray_icon = user_settings.show_tray_icon;
+    let selection_committer = Arc::new(Mutex::new(SelectionCommitter::new(Duration::from_secs(
+        user_settings.copy_on_select_delay_secs,
+    ))));
 
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
@@ -670,21 +2804,31 @@ fn main() {
         // Single Instance Plugin: When user triggers shortcut and app is already running,
         // the OS launches a new instance which signals the existing one to toggle
         .plugin(tauri_plugin_single_instance::init(|app, argv, _cwd| {
-            // Check if --settings flag is present
-            if argv.iter().any(|arg| arg == "--settings") {
-                println!(
-                    "[SingleInstance] Secondary instance with --settings flag, opening settings..."
-                );
-                SettingsController::show(app);
-            } else {
-                println!("[SingleInstance] Secondary instance detected, toggling window...");
-                WindowController::toggle(app);
+            match cli::parse_args(argv.get(1..).unwrap_or_default()) {
+                Ok(options) if options.has_instance_action() => {
+                    run_instance_actions(app, options);
+                }
+                Ok(_) => {
+                    println!("[SingleInstance] Secondary instance detected, toggling window...");
+                    WindowController::toggle(app);
+                }
+                Err(e) => {
+                    eprintln!("[SingleInstance] Ignoring invalid arguments: {}", e);
+                    WindowController::toggle(app);
+                }
             }
         }))
         .manage(AppState {
             clipboard_manager: clipboard_manager.clone(),
             emoji_manager: emoji_manager.clone(),
+            gif_library,
+            sticker_manager,
+            usage_stats,
+            collections,
             config_manager: config_manager.clone(),
+            primary_history: primary_history.clone(),
+            selection_committer,
+            selection_sync: Arc::new(Mutex::new(SelectionSync::new())),
             is_mouse_inside: is_mouse_inside.clone(),
         })
         .setup(move |app| {
@@ -699,6 +2843,19 @@ fn main() {
                 }
             }
 
+            #[cfg(all(target_os = "linux", feature = "layer-shell"))]
+            if use_layer_shell {
+                if let Some(main_window) = app.get_webview_window("main") {
+                    match layer_shell::init(&main_window) {
+                        Ok(()) => {
+                            LAYER_SHELL_ACTIVE.store(true, Ordering::SeqCst);
+                            println!("[Setup] Main window is a layer-shell surface");
+                        }
+                        Err(e) => eprintln!("[Setup] Layer shell unavailable: {}", e),
+                    }
+                }
+            }
+
             // Auto-migrate old autostart entries to use the wrapper script
             // This fixes existing installations where autostart points to the binary directly
             match autostart_manager::autostart_migrate() {
@@ -707,38 +2864,15 @@ fn main() {
                 Err(e) => eprintln!("[Setup] Failed to migrate autostart: {}", e),
             }
 
-            let show = MenuItem::with_id(app, "show", "Show Clipboard", true, None::<&str>)?;
-            let settings = MenuItem::with_id(app, "settings", "Settings", true, None::<&str>)?;
-            let quit = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
-            let menu = Menu::with_items(app, &[&show, &settings, &quit])?;
-
-            let icon = Image::from_bytes(include_bytes!("../icons/icon.png")).unwrap();
-
-            // Get temp directory for tray icon (avoids permission issues with XDG_RUNTIME_DIR)
-            let temp_dir = std::env::temp_dir().join("win11-clipboard-history");
-            std::fs::create_dir_all(&temp_dir).ok();
-
-            let _tray = TrayIconBuilder::new()
-                .icon(icon)
-                .tooltip("Clipboard History")
-                .temp_dir_path(temp_dir)
-                .menu(&menu)
-                .on_menu_event(move |app, event| match event.id.as_ref() {
-                    "quit" => app.exit(0),
-                    "show" => WindowController::toggle(app),
-                    "settings" => SettingsController::show(app),
-                    _ => {}
-                })
-                .on_tray_icon_event(|tray, event| {
-                    if let TrayIconEvent::Click {
-                        button: MouseButton::Left,
-                        ..
-                    } = event
-                    {
-                        WindowController::toggle(tray.app_handle());
-                    }
-                })
-                .build(app)?;
+            // Texts opened in an editor last time are no longer needed
+            quick_actions::remove_editor_files();
+
+            if show_tray_icon {
+                build_tray(&app_handle)?;
+            } else {
+                println!("[Setup] Tray icon disabled; the window opens with the shortcut or by running {} again", cli::BIN_NAME);
+            }
+            start_tray_refresher(app_handle.clone(), clipboard_manager.clone());
 
             // Verify that settings window was created from config
             if app.get_webview_window("settings").is_none() {
@@ -773,6 +2907,19 @@ fn main() {
                         return;
                     }
 
+                    // The docked sidebar stays open; remember the window the
+                    // user switched to, which is where the next paste goes
+                    let mut config = state.config_manager.lock();
+                    if config.placement_mode() == PlacementMode::Docked {
+                        config.sync_to_disk();
+                        std::thread::spawn(|| {
+                            std::thread::sleep(Duration::from_millis(100));
+                            save_focused_window();
+                        });
+                        return;
+                    }
+                    drop(config);
+
                     // Don't hide if settings window is visible (for live preview)
                     if let Some(settings_window) =
                         app_handle_for_event.get_webview_window("settings")
@@ -782,30 +2929,70 @@ fn main() {
                         }
                     }
 
-                    if is_wayland() {
-                        state.config_manager.lock().sync_to_disk();
-                    }
+                    state.config_manager.lock().sync_to_disk();
 
                     let _ = w_clone.hide();
                 }
 
                 WindowEvent::Moved(pos) => {
                     let state = w_clone.state::<AppState>();
-                    handle_window_moved_for_wayland(&w_clone, &state, pos);
+                    handle_window_moved(&w_clone, &state, pos);
+                }
+
+                WindowEvent::Resized(size) => {
+                    let state = w_clone.state::<AppState>();
+                    handle_window_resized(&w_clone, &state, *size);
                 }
                 _ => {}
             });
 
             start_clipboard_watcher(app_handle.clone(), clipboard_manager.clone());
+            start_retention_pruner(app_handle.clone(), clipboard_manager.clone());
+            start_item_age_ticker(app_handle.clone(), clipboard_manager.clone());
+            start_thumbnail_warmup(app_handle.clone(), clipboard_manager.clone());
+            if primary_watch_enabled() {
+                start_primary_selection_watcher(app_handle.clone(), primary_history.clone());
+            }
+            #[cfg(target_os = "linux")]
+            start_session_monitor(app_handle.clone());
+            #[cfg(target_os = "linux")]
+            start_color_scheme_watch(app_handle.clone());
 
             // Register global shortcut (Super+V) with the desktop environment
             // This runs in a background thread to avoid blocking startup
             #[cfg(target_os = "linux")]
-            std::thread::spawn(|| {
-                // Give the desktop environment a moment to settle
-                std::thread::sleep(std::time::Duration::from_secs(2));
-                win11_clipboard_history_lib::linux_shortcut_manager::register_global_shortcut();
-            });
+            {
+                let app_handle_for_health = app_handle.clone();
+                std::thread::spawn(move || {
+                    // Give the desktop environment a moment to settle
+                    std::thread::sleep(std::time::Duration::from_secs(2));
+                    let shortcut_registered =
+                        win11_clipboard_history_lib::linux_shortcut_manager::register_global_shortcut();
+
+                    // Report anything degraded once, with a way into the Setup Wizard
+                    let report = diagnostics::probe_startup_health(shortcut_registered);
+                    if report.is_degraded() {
+                        eprintln!("[Startup] Degraded features:\n{}", report.summary());
+                        // With neither a shortcut nor a tray icon there is no
+                        // visible way to summon the app, so open the wizard now
+                        if !shortcut_registered && !show_tray_icon {
+                            WindowController::show_setup_wizard(&app_handle_for_health);
+                        } else {
+                            // Waits for the notification to be dismissed
+                            let app_handle = app_handle_for_health.clone();
+                            std::thread::spawn(move || {
+                                if diagnostics::notify_degraded(&report) {
+                                    WindowController::show_setup_wizard(&app_handle);
+                                }
+                            });
+                        }
+                    }
+
+                    // Keep the binding alive across DE settings resets, and
+                    // retry one that could not be registered yet
+                    win11_clipboard_history_lib::linux_shortcut_manager::watch_shortcut_registration();
+                });
+            }
 
             // If --settings flag was passed on first startup, open the settings window
             if open_settings_on_start {
@@ -850,12 +3037,77 @@ fn main() {
         })
         .invoke_handler(tauri::generate_handler![
             get_history,
+            query_history,
+            search_all,
+            get_item,
             clear_history,
             delete_item,
             toggle_pin,
+            split_item,
+            join_with_previous,
+            set_item_tags,
+            get_items_with_tag,
+            suggest_tags,
+            get_items_from_app,
+            get_collections,
+            create_collection,
+            rename_collection,
+            delete_collection,
+            file_item,
+            get_collection_items,
+            export_snippet_pack,
+            import_snippet_pack,
+            clean_hidden_chars,
+            find_by_hash,
+            get_activity_heatmap,
+            get_usage_insights,
+            clear_usage_stats,
+            get_item_ages,
+            get_item_summaries,
+            get_image_thumbnail,
             paste_item,
+            paste_item_and_submit,
+            paste_recent_item,
+            get_frequent_items,
+            resolve_key_sequence,
+            search_gifs,
+            copy_item_link,
+            run_quick_action,
+            run_shell_action,
+            translate_item,
+            get_conversions,
+            open_url,
+            reveal_path,
+            ping_address,
+            render_markdown,
+            get_paste_warning,
+            type_item,
             paste_text,
             get_recent_emojis,
+            get_recent_kaomojis,
+            get_recent_symbols,
+            apply_emoji_preference,
+            get_emoji_variants,
+            get_emoji_dataset,
+            install_emoji_dataset,
+            get_custom_emojis,
+            add_custom_emoji,
+            remove_custom_emoji,
+            paste_custom_emoji,
+            get_primary_history,
+            delete_primary_item,
+            clear_primary_history,
+            search_gif_provider,
+            get_gif_favorites,
+            get_recent_gifs,
+            add_gif_favorite,
+            remove_gif_favorite,
+            clear_gif_cache,
+            get_sticker_packs,
+            import_sticker_folder,
+            import_sticker_url,
+            remove_sticker_pack,
+            paste_sticker,
             paste_gif_from_url,
             finish_paste,
             set_mouse_state,
@@ -863,6 +3115,15 @@ fn main() {
             set_user_settings,
             is_settings_window_visible,
             copy_text_to_clipboard,
+            diagnostics::get_startup_health,
+            get_metrics,
+            get_dock_edge,
+            get_theme,
+            get_system_color_scheme,
+            get_monitor_names,
+            set_dock_edge,
+            input_simulator::get_ydotool_status,
+            icon_resolver::get_app_icon,
             permission_checker::check_permissions,
             permission_checker::fix_permissions_now,
             permission_checker::is_first_run,
@@ -870,6 +3131,7 @@ fn main() {
             permission_checker::reset_first_run,
             shortcut_setup::get_desktop_environment,
             shortcut_setup::register_de_shortcut,
+            shortcut_setup::unregister_de_shortcut,
             shortcut_setup::check_shortcut_tools,
             shortcut_setup::detect_conflicts,
             shortcut_setup::resolve_conflicts,
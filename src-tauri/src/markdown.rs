@@ -0,0 +1,401 @@
+//! Markdown
+//! Spots text items written in Markdown and renders them for the preview,
+//! while pasting still gives the raw text. The renderer covers the common
+//! subset (headings, paragraphs, lists, quotes, code, emphasis, links, rules)
+//! and is safe by construction: every character of the text is escaped and
+//! only the tags below are ever written. Links keep http, https and mailto
+//! targets only, and images show their alt text so opening the preview
+//! never loads anything remote.
+
+use crate::paste_profiles::escape_html;
+
+/// Texts longer than this are not rendered
+const MAX_RENDERED_CHARS: usize = 100_000;
+
+/// Quotes nested deeper than this are rendered as text
+const MAX_QUOTE_DEPTH: usize = 4;
+
+/// Link targets kept in the rendered HTML
+const LINK_SCHEMES: [&str; 3] = ["http://", "https://", "mailto:"];
+
+/// What Markdown is made of, for the detection
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Signal {
+    Heading,
+    List,
+    Fence,
+    Quote,
+    Link,
+    Emphasis,
+    InlineCode,
+}
+
+fn heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|c| *c == '#').count();
+    let text = line[level..].strip_prefix(' ')?;
+    (1..=6).contains(&level).then_some((level, text.trim()))
+}
+
+/// "- item", "* item", "+ item", or "1. item" / "1) item" with its number
+fn list_item(line: &str) -> Option<(Option<u32>, &str)> {
+    let trimmed = line.trim_start();
+    for bullet in ["- ", "* ", "+ "] {
+        if let Some(text) = trimmed.strip_prefix(bullet) {
+            return Some((None, text));
+        }
+    }
+    let digits = trimmed.chars().take_while(char::is_ascii_digit).count();
+    if digits == 0 || digits > 9 {
+        return None;
+    }
+    let rest = &trimmed[digits..];
+    let text = rest
+        .strip_prefix(". ")
+        .or_else(|| rest.strip_prefix(") "))?;
+    Some((trimmed[..digits].parse().ok(), text))
+}
+
+fn is_fence(line: &str) -> bool {
+    let line = line.trim_start();
+    line.starts_with("```") || line.starts_with("~~~")
+}
+
+fn is_rule(line: &str) -> bool {
+    let line: String = line.chars().filter(|c| !c.is_whitespace()).collect();
+    line.len() >= 3
+        && ['-', '*', '_']
+            .iter()
+            .any(|mark| line.chars().all(|c| c == *mark))
+}
+
+/// Whether `text` reads as Markdown rather than plain text: two different
+/// kinds of Markdown syntax, or a fenced code block
+pub fn looks_like_markdown(text: &str) -> bool {
+    let mut signals: Vec<Signal> = Vec::new();
+    let mut add = |signal| {
+        if !signals.contains(&signal) {
+            signals.push(signal);
+        }
+    };
+    for line in text.lines().take(500) {
+        if is_fence(line) {
+            add(Signal::Fence);
+        } else if heading(line).is_some() {
+            add(Signal::Heading);
+        } else if list_item(line).is_some() {
+            add(Signal::List);
+        } else if line.starts_with("> ") {
+            add(Signal::Quote);
+        }
+        if line.contains("](") && line.contains('[') {
+            add(Signal::Link);
+        }
+        if line.contains("**") || line.contains("__") {
+            add(Signal::Emphasis);
+        }
+        if line.matches('`').count() >= 2 && !is_fence(line) {
+            add(Signal::InlineCode);
+        }
+    }
+    let fenced = text.lines().filter(|line| is_fence(line)).count() >= 2;
+    fenced || signals.len() >= 2
+}
+
+/// Sanitized HTML for the Markdown `text`
+pub fn render(text: &str) -> String {
+    if text.chars().count() > MAX_RENDERED_CHARS {
+        return format!("<pre>{}</pre>", escape_html(text));
+    }
+    let lines: Vec<&str> = text.lines().collect();
+    render_blocks(&lines, 0)
+}
+
+fn render_blocks(lines: &[&str], depth: usize) -> String {
+    let mut html = String::new();
+    let mut paragraph: Vec<&str> = Vec::new();
+    let flush_paragraph = |paragraph: &mut Vec<&str>, html: &mut String| {
+        if !paragraph.is_empty() {
+            let text = paragraph.join(" ");
+            html.push_str(&format!("<p>{}</p>", inline(text.trim())));
+            paragraph.clear();
+        }
+    };
+
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+
+        if is_fence(line) {
+            flush_paragraph(&mut paragraph, &mut html);
+            let fence = &line.trim_start()[..3];
+            let language: String = line.trim_start()[3..]
+                .trim()
+                .chars()
+                .take_while(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == '+')
+                .collect();
+            let mut code = Vec::new();
+            i += 1;
+            while i < lines.len() && !lines[i].trim_start().starts_with(fence) {
+                code.push(lines[i]);
+                i += 1;
+            }
+            let class = if language.is_empty() {
+                String::new()
+            } else {
+                format!(" class=\"language-{}\"", language)
+            };
+            html.push_str(&format!(
+                "<pre><code{}>{}</code></pre>",
+                class,
+                escape_html(&code.join("\n"))
+            ));
+            i += 1;
+            continue;
+        }
+
+        if line.trim().is_empty() {
+            flush_paragraph(&mut paragraph, &mut html);
+            i += 1;
+            continue;
+        }
+
+        if let Some((level, text)) = heading(line) {
+            flush_paragraph(&mut paragraph, &mut html);
+            html.push_str(&format!("<h{0}>{1}</h{0}>", level, inline(text)));
+            i += 1;
+            continue;
+        }
+
+        if is_rule(line) {
+            flush_paragraph(&mut paragraph, &mut html);
+            html.push_str("<hr>");
+            i += 1;
+            continue;
+        }
+
+        if line.starts_with('>') && depth < MAX_QUOTE_DEPTH {
+            flush_paragraph(&mut paragraph, &mut html);
+            let mut quoted = Vec::new();
+            while i < lines.len() && lines[i].starts_with('>') {
+                let inner = &lines[i][1..];
+                quoted.push(inner.strip_prefix(' ').unwrap_or(inner));
+                i += 1;
+            }
+            html.push_str(&format!(
+                "<blockquote>{}</blockquote>",
+                render_blocks(&quoted, depth + 1)
+            ));
+            continue;
+        }
+
+        if let Some((number, _)) = list_item(line) {
+            flush_paragraph(&mut paragraph, &mut html);
+            let ordered = number.is_some();
+            let mut items: Vec<String> = Vec::new();
+            while i < lines.len() {
+                match list_item(lines[i]) {
+                    Some((n, text)) if n.is_some() == ordered => items.push(text.to_string()),
+                    // Indented lines continue the item above
+                    None if !lines[i].trim().is_empty()
+                        && lines[i].starts_with([' ', '\t'])
+                        && !items.is_empty() =>
+                    {
+                        let last = items.len() - 1;
+                        items[last].push(' ');
+                        items[last].push_str(lines[i].trim());
+                    }
+                    _ => break,
+                }
+                i += 1;
+            }
+            let (open, close) = match number.filter(|n| *n != 1) {
+                Some(start) => (format!("<ol start=\"{}\">", start), "</ol>"),
+                None if ordered => ("<ol>".to_string(), "</ol>"),
+                None => ("<ul>".to_string(), "</ul>"),
+            };
+            html.push_str(&open);
+            for item in items {
+                html.push_str(&format!("<li>{}</li>", inline(item.trim())));
+            }
+            html.push_str(close);
+            continue;
+        }
+
+        paragraph.push(line.trim());
+        i += 1;
+    }
+    flush_paragraph(&mut paragraph, &mut html);
+    html
+}
+
+fn is_safe_link(url: &str) -> bool {
+    LINK_SCHEMES.iter().any(|scheme| url.starts_with(scheme))
+}
+
+/// `[text](url)` at the start of `text`: the text, the url and the length
+fn link_at(text: &str) -> Option<(&str, &str, usize)> {
+    let rest = text.strip_prefix('[')?;
+    let close = rest.find("](")?;
+    let label = &rest[..close];
+    let after = &rest[close + 2..];
+    // Brackets inside the url are balanced, as in wiki links
+    let mut depth = 0;
+    let end = after.find(|c| {
+        match c {
+            '(' => depth += 1,
+            ')' if depth == 0 => return true,
+            ')' => depth -= 1,
+            _ => {}
+        }
+        false
+    })?;
+    let url = after[..end].split_whitespace().next().unwrap_or_default();
+    Some((label, url, 1 + close + 2 + end + 1))
+}
+
+/// Emphasis and the like between `marker`s at the start of `text`: the
+/// content and the length with the markers
+fn delimited<'a>(text: &'a str, marker: &str) -> Option<(&'a str, usize)> {
+    let rest = text.strip_prefix(marker)?;
+    if rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+    let end = rest.find(marker)?;
+    let content = &rest[..end];
+    if content.is_empty() || content.ends_with(char::is_whitespace) {
+        return None;
+    }
+    Some((content, marker.len() * 2 + end))
+}
+
+/// HTML for the inline Markdown in `text`
+fn inline(text: &str) -> String {
+    let mut html = String::new();
+    let mut i = 0;
+    while i < text.len() {
+        let rest = &text[i..];
+        let previous = text[..i].chars().next_back();
+        let at_word_start = !previous.is_some_and(char::is_alphanumeric);
+
+        // Backslash escapes
+        if let Some(escaped) = rest
+            .strip_prefix('\\')
+            .and_then(|after| after.chars().next())
+            .filter(char::is_ascii_punctuation)
+        {
+            html.push_str(&escape_html(&escaped.to_string()));
+            i += 1 + escaped.len_utf8();
+            continue;
+        }
+
+        if rest.starts_with('`') {
+            let ticks = rest.chars().take_while(|c| *c == '`').count();
+            let marker = &rest[..ticks];
+            if let Some(end) = rest[ticks..].find(marker) {
+                let code = &rest[ticks..ticks + end];
+                html.push_str(&format!("<code>{}</code>", escape_html(code.trim())));
+                i += ticks * 2 + end;
+                continue;
+            }
+        }
+
+        if let Some(image) = rest.strip_prefix('!') {
+            if let Some((alt, _, len)) = link_at(image) {
+                html.push_str(&escape_html(alt));
+                i += 1 + len;
+                continue;
+            }
+        }
+
+        if let Some((label, url, len)) = link_at(rest) {
+            if is_safe_link(url) {
+                html.push_str(&format!(
+                    "<a href=\"{}\">{}</a>",
+                    escape_html(url),
+                    inline(label)
+                ));
+            } else {
+                html.push_str(&inline(label));
+            }
+            i += len;
+            continue;
+        }
+
+        // Autolinks: <https://example.com>
+        if let Some(url) = rest
+            .strip_prefix('<')
+            .and_then(|after| after.split_once('>'))
+            .map(|(url, _)| url)
+            .filter(|url| is_safe_link(url) && !url.contains(char::is_whitespace))
+        {
+            let url_html = escape_html(url);
+            html.push_str(&format!("<a href=\"{0}\">{0}</a>", url_html));
+            i += url.len() + 2;
+            continue;
+        }
+
+        let emphasis = [
+            ("**", "strong"),
+            ("__", "strong"),
+            ("~~", "del"),
+            ("*", "em"),
+            ("_", "em"),
+        ];
+        let found = emphasis
+            .iter()
+            // Underscores inside words (snake_case) are not emphasis
+            .filter(|(marker, _)| !marker.starts_with('_') || at_word_start)
+            .find_map(|(marker, tag)| delimited(rest, marker).map(|found| (found, tag)));
+        if let Some(((content, len), tag)) = found {
+            html.push_str(&format!("<{0}>{1}</{0}>", tag, inline(content)));
+            i += len;
+            continue;
+        }
+
+        let c = rest.chars().next().unwrap_or_default();
+        html.push_str(&escape_html(&c.to_string()));
+        i += c.len_utf8();
+    }
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_markdown() {
+        assert!(looks_like_markdown("# Notes\n\n- one\n- two"));
+        assert!(looks_like_markdown("```rust\nfn main() {}\n```"));
+        assert!(looks_like_markdown(
+            "See **this** and [docs](https://example.com)"
+        ));
+        assert!(!looks_like_markdown("Just a sentence, with a - dash."));
+        assert!(!looks_like_markdown("- only a list item"));
+        assert!(!looks_like_markdown("#hashtag and more"));
+    }
+
+    #[test]
+    fn test_render_markdown() {
+        assert_eq!(
+            render("# Title\n\nSome *em*, **strong** and `a < b` text\nwrapped.\n\n- one\n- two\n  continued\n\n3. three\n4. four"),
+            "<h1>Title</h1><p>Some <em>em</em>, <strong>strong</strong> and <code>a &lt; b</code> text wrapped.</p>\
+             <ul><li>one</li><li>two continued</li></ul><ol start=\"3\"><li>three</li><li>four</li></ol>"
+        );
+        assert_eq!(
+            render("> quoted\n> more\n\n---\n```sh\necho \"<hi>\"\n```"),
+            "<blockquote><p>quoted more</p></blockquote><hr>\
+             <pre><code class=\"language-sh\">echo &quot;&lt;hi&gt;&quot;</code></pre>"
+        );
+
+        // Raw HTML, unsafe links and remote images never make it through
+        assert_eq!(
+            render("<script>alert(1)</script> [x](javascript:alert(1)) ![logo](https://t.example/p.png) [ok](https://example.com)"),
+            "<p>&lt;script&gt;alert(1)&lt;/script&gt; x logo <a href=\"https://example.com\">ok</a></p>"
+        );
+        assert_eq!(
+            render("snake_case_name and \\*not em\\*"),
+            "<p>snake_case_name and *not em*</p>"
+        );
+    }
+}
@@ -0,0 +1,176 @@
+//! Memory Budget
+//! Every image of the history used to stay in memory as base64 for as long
+//! as the app ran. Past the configured budget, the data of the oldest images
+//! now moves to a file of its own next to the history, and the item keeps
+//! only its metadata and preview; the list shows cached thumbnails anyway.
+//! Pasting, thumbnails and the other readers load the file when they need
+//! the image. Copied files are only references and large texts are already
+//! kept out of line (see `large_text`), so images are what gets evicted.
+//! Without a history on disk everything stays in memory.
+
+use crate::clipboard_manager::{ClipboardContent, ClipboardItem};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Budget when the setting is left out, in megabytes
+pub const DEFAULT_BUDGET_MB: u32 = 256;
+
+/// Larger budgets are cut to this; 0 turns eviction off
+pub const MAX_BUDGET_MB: u32 = 16 * 1024;
+
+/// Directory of the evicted images, inside the data directory
+const BLOB_DIR: &str = "blobs";
+
+/// Directory of the evicted images of the history stored at `location`
+pub fn dir_for(location: &Path) -> Option<PathBuf> {
+    location.parent().map(|data_dir| data_dir.join(BLOB_DIR))
+}
+
+fn file_path(dir: &Path, id: &str) -> PathBuf {
+    dir.join(format!("{}.bin", id))
+}
+
+/// Roughly what the item takes in memory, counting its content and preview
+pub fn resident_bytes(item: &ClipboardItem) -> usize {
+    let content = match &item.content {
+        ClipboardContent::Text(text) => text.len(),
+        ClipboardContent::RichText { plain, html } => plain.len() + html.len(),
+        ClipboardContent::Image { base64, .. } => base64.len(),
+    };
+    content + item.preview.len()
+}
+
+fn is_evictable(item: &ClipboardItem) -> bool {
+    !item.is_kept() && !item.evicted && matches!(item.content, ClipboardContent::Image { .. })
+}
+
+/// Evicts the images of `history`, oldest first, until what stays in memory
+/// fits in `budget` bytes. Pinned and filed images stay, as the git mirror
+/// copies pinned ones, and are loaded back if they were evicted before.
+/// Returns how many items changed.
+pub fn enforce(history: &mut [ClipboardItem], budget: usize, dir: &Path) -> usize {
+    let mut changed = 0;
+    for item in history
+        .iter_mut()
+        .filter(|item| item.is_kept() && item.evicted)
+    {
+        match restore(dir, item) {
+            Ok(()) => changed += 1,
+            Err(e) => eprintln!("[MemoryBudget] {}", e),
+        }
+    }
+
+    let mut resident: usize = history.iter().map(resident_bytes).sum();
+    if resident <= budget {
+        return changed;
+    }
+    let mut oldest: Vec<usize> = (0..history.len())
+        .filter(|&index| is_evictable(&history[index]))
+        .collect();
+    oldest.sort_by_key(|&index| history[index].timestamp);
+    for index in oldest {
+        if resident <= budget {
+            break;
+        }
+        let item = &mut history[index];
+        let before = resident_bytes(item);
+        if let Err(e) = evict(dir, item) {
+            eprintln!("[MemoryBudget] {}", e);
+            break;
+        }
+        resident -= before - resident_bytes(item);
+        changed += 1;
+    }
+    changed
+}
+
+/// Writes the image data of `item` to its file and drops it from the item
+fn evict(dir: &Path, item: &mut ClipboardItem) -> Result<(), String> {
+    let ClipboardContent::Image { base64, .. } = &mut item.content else {
+        return Err("Only images are evicted".to_string());
+    };
+    let bytes = BASE64
+        .decode(base64.as_bytes())
+        .map_err(|e| format!("Invalid image data: {}", e))?;
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    fs::write(file_path(dir, &item.id), bytes)
+        .map_err(|e| format!("Failed to write evicted image: {}", e))?;
+    *base64 = String::new();
+    item.evicted = true;
+    Ok(())
+}
+
+/// The image data of an evicted item, as base64
+pub fn load(dir: &Path, item: &ClipboardItem) -> Result<String, String> {
+    fs::read(file_path(dir, &item.id))
+        .map(|bytes| BASE64.encode(bytes))
+        .map_err(|e| format!("Failed to read the image of the item: {}", e))
+}
+
+/// Puts the image data of an evicted item back into it
+fn restore(dir: &Path, item: &mut ClipboardItem) -> Result<(), String> {
+    let data = load(dir, item)?;
+    if let ClipboardContent::Image { base64, .. } = &mut item.content {
+        *base64 = data;
+    }
+    item.evicted = false;
+    Ok(())
+}
+
+/// Deletes the files of images that are not evicted items of `history`,
+/// returning how many went
+pub fn prune(dir: &Path, history: &[ClipboardItem]) -> usize {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .filter(|entry| {
+            let name = entry.file_name();
+            name.to_str()
+                .and_then(|name| name.strip_suffix(".bin"))
+                .is_some_and(|id| !history.iter().any(|item| item.evicted && item.id == id))
+        })
+        .filter(|entry| fs::remove_file(entry.path()).is_ok())
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, Utc};
+
+    #[test]
+    fn test_enforce_budget() {
+        let dir = std::env::temp_dir().join("memory_budget_test");
+        let _ = fs::remove_dir_all(&dir);
+
+        let data = BASE64.encode([7u8; 3000]);
+        let mut history: Vec<ClipboardItem> = (0..4)
+            .map(|n| {
+                let mut item = ClipboardItem::new_image(data.clone(), 10, 10, n);
+                item.timestamp = Utc::now() - Duration::minutes(n as i64);
+                item
+            })
+            .collect();
+        history.push(ClipboardItem::new_text("note".to_string()));
+        history[3].pinned = true;
+        history[1].collection = Some("snippets".to_string());
+        let total: usize = history.iter().map(resident_bytes).sum();
+
+        // Room for all but one image: the oldest unpinned one goes
+        assert_eq!(enforce(&mut history, total - 100, &dir), 1);
+        assert!(history[2].evicted && !history[3].evicted && !history[1].evicted);
+        assert_eq!(resident_bytes(&history[2]), history[2].preview.len());
+        assert_eq!(load(&dir, &history[2]).unwrap(), data);
+        assert_eq!(enforce(&mut history, total - 100, &dir), 0);
+
+        // Pinning brings it back
+        history[2].pinned = true;
+        assert_eq!(enforce(&mut history, usize::MAX, &dir), 1);
+        assert!(!history[2].evicted);
+        assert_eq!(prune(&dir, &history), 1);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,271 @@
+//! Metrics
+//! Counters kept while the app runs: items captured, listener failures and
+//! reconnects, and paste outcomes per injection strategy, so it can be told
+//! which strategies actually work on a given desktop. Read through the
+//! `get_metrics` command, or scraped in Prometheus text format from
+//! 127.0.0.1 when the `metrics_port` setting is set.
+
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicU16, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+static ITEMS_CAPTURED: AtomicU64 = AtomicU64::new(0);
+static CAPTURE_FAILURES: Mutex<BTreeMap<String, u64>> = Mutex::new(BTreeMap::new());
+static LISTENER_RECONNECTS: Mutex<BTreeMap<String, u64>> = Mutex::new(BTreeMap::new());
+static PASTES: Mutex<BTreeMap<String, PasteCounts>> = Mutex::new(BTreeMap::new());
+
+/// How long a scraper has to send its request line
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Port the endpoint is serving on, 0 when it is off
+static SERVING_PORT: AtomicU16 = AtomicU16::new(0);
+
+#[derive(Debug, Clone, Copy, Default, Serialize, PartialEq, Eq)]
+pub struct PasteCounts {
+    pub succeeded: u64,
+    pub failed: u64,
+}
+
+/// Sizes that are read when the metrics are, not counted
+#[derive(Debug, Clone, Copy, Default, Serialize, PartialEq, Eq)]
+pub struct StoreGauges {
+    pub history_items: usize,
+    /// Bytes used by the history store on disk
+    pub store_bytes: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct MetricsSnapshot {
+    pub items_captured: u64,
+    /// Listener failures, by event source (protocol name or "XFixes")
+    pub capture_failures: BTreeMap<String, u64>,
+    /// Listener restarts, by selection ("clipboard", "primary")
+    pub listener_reconnects: BTreeMap<String, u64>,
+    /// Keystroke injection attempts, by strategy ("wtype", "XTest", ...)
+    pub pastes: BTreeMap<String, PasteCounts>,
+    #[serde(flatten)]
+    pub store: StoreGauges,
+}
+
+pub fn record_capture() {
+    ITEMS_CAPTURED.fetch_add(1, Ordering::Relaxed);
+}
+
+pub fn record_capture_failure(backend: &str) {
+    *CAPTURE_FAILURES
+        .lock()
+        .entry(backend.to_string())
+        .or_default() += 1;
+}
+
+pub fn record_reconnect(listener: &str) {
+    *LISTENER_RECONNECTS
+        .lock()
+        .entry(listener.to_string())
+        .or_default() += 1;
+}
+
+pub fn record_paste(strategy: &str, succeeded: bool) {
+    let mut pastes = PASTES.lock();
+    let counts = pastes.entry(strategy.to_string()).or_default();
+    if succeeded {
+        counts.succeeded += 1;
+    } else {
+        counts.failed += 1;
+    }
+}
+
+/// The counters so far, with the given store sizes
+pub fn snapshot(store: StoreGauges) -> MetricsSnapshot {
+    MetricsSnapshot {
+        items_captured: ITEMS_CAPTURED.load(Ordering::Relaxed),
+        capture_failures: CAPTURE_FAILURES.lock().clone(),
+        listener_reconnects: LISTENER_RECONNECTS.lock().clone(),
+        pastes: PASTES.lock().clone(),
+        store,
+    }
+}
+
+/// Label values are quoted, with backslashes, quotes and newlines escaped
+fn label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// The snapshot in the Prometheus text exposition format
+pub fn render_prometheus(metrics: &MetricsSnapshot) -> String {
+    let mut out = String::new();
+    let mut family = |name: &str, kind: &str, help: &str, samples: Vec<(String, u64)>| {
+        out.push_str(&format!(
+            "# HELP {} {}\n# TYPE {} {}\n",
+            name, help, name, kind
+        ));
+        for (labels, value) in samples {
+            out.push_str(&format!("{}{} {}\n", name, labels, value));
+        }
+    };
+    let labeled = |label: &str, counts: &BTreeMap<String, u64>| {
+        counts
+            .iter()
+            .map(|(key, count)| (format!("{{{}=\"{}\"}}", label, label_value(key)), *count))
+            .collect()
+    };
+
+    family(
+        "clipboard_items_captured_total",
+        "counter",
+        "Items added to the history from the clipboard.",
+        vec![(String::new(), metrics.items_captured)],
+    );
+    family(
+        "clipboard_capture_failures_total",
+        "counter",
+        "Clipboard listener failures by event source.",
+        labeled("backend", &metrics.capture_failures),
+    );
+    family(
+        "clipboard_listener_reconnects_total",
+        "counter",
+        "Clipboard listener restarts by selection.",
+        labeled("listener", &metrics.listener_reconnects),
+    );
+    family(
+        "clipboard_pastes_total",
+        "counter",
+        "Keystroke injection attempts by strategy and outcome.",
+        metrics
+            .pastes
+            .iter()
+            .flat_map(|(strategy, counts)| {
+                let strategy = label_value(strategy);
+                [("success", counts.succeeded), ("failure", counts.failed)].map(
+                    |(outcome, count)| {
+                        (
+                            format!("{{strategy=\"{}\",outcome=\"{}\"}}", strategy, outcome),
+                            count,
+                        )
+                    },
+                )
+            })
+            .collect(),
+    );
+    family(
+        "clipboard_history_items",
+        "gauge",
+        "Items currently in the history.",
+        vec![(String::new(), metrics.store.history_items as u64)],
+    );
+    family(
+        "clipboard_store_bytes",
+        "gauge",
+        "Bytes used by the history store on disk.",
+        vec![(String::new(), metrics.store.store_bytes)],
+    );
+    out
+}
+
+/// Serves the metrics at http://127.0.0.1:`port`/metrics, or stops serving
+/// when `port` is 0. `gauges` is called on every scrape.
+pub fn serve(port: u16, gauges: Arc<dyn Fn() -> StoreGauges + Send + Sync>) {
+    let previous = SERVING_PORT.swap(port, Ordering::SeqCst);
+    if previous == port {
+        return;
+    }
+    if previous != 0 {
+        // Wake the old accept loop so it sees it is no longer wanted
+        let _ = TcpStream::connect((Ipv4Addr::LOCALHOST, previous));
+    }
+    if port == 0 {
+        return;
+    }
+
+    let listener = match TcpListener::bind((Ipv4Addr::LOCALHOST, port)) {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("[Metrics] Failed to listen on 127.0.0.1:{}: {}", port, e);
+            let _ = SERVING_PORT.compare_exchange(port, 0, Ordering::SeqCst, Ordering::SeqCst);
+            return;
+        }
+    };
+    eprintln!("[Metrics] Serving on http://127.0.0.1:{}/metrics", port);
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            if SERVING_PORT.load(Ordering::SeqCst) != port {
+                break;
+            }
+            match stream {
+                Ok(stream) => {
+                    if let Err(e) = respond(stream, gauges.as_ref()) {
+                        eprintln!("[Metrics] Failed to answer a request: {}", e);
+                    }
+                }
+                Err(e) => eprintln!("[Metrics] Failed to accept a connection: {}", e),
+            }
+        }
+    });
+}
+
+fn respond(mut stream: TcpStream, gauges: &dyn Fn() -> StoreGauges) -> std::io::Result<()> {
+    // One request at a time, so a client that never sends one can't stall it
+    stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+    let mut request_line = String::new();
+    BufReader::new(&stream).read_line(&mut request_line)?;
+    let path = request_line.split_whitespace().nth(1).unwrap_or_default();
+
+    let (status, body) = if request_line.starts_with("GET ") && path == "/metrics" {
+        ("200 OK", render_prometheus(&snapshot(gauges())))
+    } else {
+        ("404 Not Found", "Not found\n".to_string())
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_prometheus() {
+        let mut metrics = MetricsSnapshot {
+            items_captured: 12,
+            store: StoreGauges {
+                history_items: 10,
+                store_bytes: 4096,
+            },
+            ..Default::default()
+        };
+        metrics
+            .capture_failures
+            .insert("wlr-data-control".to_string(), 2);
+        metrics.pastes.insert(
+            "wtype".to_string(),
+            PasteCounts {
+                succeeded: 5,
+                failed: 1,
+            },
+        );
+
+        let text = render_prometheus(&metrics);
+        assert!(text.contains("# TYPE clipboard_items_captured_total counter\n"));
+        assert!(text.contains("\nclipboard_items_captured_total 12\n"));
+        assert!(text.contains("clipboard_capture_failures_total{backend=\"wlr-data-control\"} 2\n"));
+        assert!(text.contains("clipboard_pastes_total{strategy=\"wtype\",outcome=\"success\"} 5\n"));
+        assert!(text.contains("clipboard_pastes_total{strategy=\"wtype\",outcome=\"failure\"} 1\n"));
+        assert!(text.contains("clipboard_store_bytes 4096\n"));
+        assert_eq!(label_value("a\"b\\c"), "a\\\"b\\\\c");
+    }
+}
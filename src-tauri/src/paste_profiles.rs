@@ -0,0 +1,172 @@
+//! Paste Profiles
+//! Per-application formatting applied when pasting: plain text into
+//! terminals, fenced code into chat apps, HTML into mail clients. The profile
+//! is picked from the identity (WM_CLASS or app_id) of the target window.
+
+use crate::clipboard_manager::{ClipboardContent, ClipboardItem};
+use serde::{Deserialize, Serialize};
+
+/// How text is rewritten before it is put on the clipboard
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PasteFormat {
+    /// Leave the item untouched
+    AsIs,
+    /// Drop HTML so only plain text is offered
+    PlainText,
+    /// Wrap the text in a Markdown code fence
+    CodeBlock,
+    /// Offer HTML, converting plain text line by line
+    Html,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PasteProfile {
+    pub name: String,
+    /// Window classes or app ids the profile applies to, case-insensitive
+    pub app_ids: Vec<String>,
+    pub format: PasteFormat,
+}
+
+fn profile(name: &str, app_ids: &[&str], format: PasteFormat) -> PasteProfile {
+    PasteProfile {
+        name: name.to_string(),
+        app_ids: app_ids.iter().map(|id| id.to_string()).collect(),
+        format,
+    }
+}
+
+pub fn default_profiles(terminals: &[String]) -> Vec<PasteProfile> {
+    let terminals: Vec<&str> = terminals.iter().map(String::as_str).collect();
+    vec![
+        profile("Terminals", &terminals, PasteFormat::PlainText),
+        profile(
+            "Chat",
+            &[
+                "Slack",
+                "discord",
+                "Element",
+                "org.telegram.desktop",
+                "TelegramDesktop",
+                "signal",
+                "Mattermost",
+            ],
+            PasteFormat::CodeBlock,
+        ),
+        profile(
+            "Mail",
+            &[
+                "thunderbird",
+                "org.mozilla.Thunderbird",
+                "evolution",
+                "org.gnome.Evolution",
+                "geary",
+                "org.gnome.Geary",
+                "org.kde.kmail2",
+            ],
+            PasteFormat::Html,
+        ),
+    ]
+}
+
+/// First profile matching any of the target's names
+pub fn resolve<'a>(profiles: &'a [PasteProfile], target: &[String]) -> Option<&'a PasteProfile> {
+    profiles.iter().find(|profile| {
+        target.iter().any(|name| {
+            profile
+                .app_ids
+                .iter()
+                .any(|id| id.eq_ignore_ascii_case(name))
+        })
+    })
+}
+
+/// `text` with the characters HTML gives meaning to escaped
+pub fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Rewrites a text item for the given format; images pass through as is
+pub fn apply(format: PasteFormat, item: &ClipboardItem) -> ClipboardItem {
+    let Some(plain) = item.text() else {
+        return item.clone();
+    };
+
+    let content = match (format, &item.content) {
+        (PasteFormat::AsIs, _) => return item.clone(),
+        (PasteFormat::PlainText, _) => ClipboardContent::Text(plain.to_string()),
+        (PasteFormat::CodeBlock, _) => {
+            ClipboardContent::Text(format!("```\n{}\n```", plain.trim_end_matches('\n')))
+        }
+        (PasteFormat::Html, ClipboardContent::RichText { .. }) => return item.clone(),
+        (PasteFormat::Html, _) => ClipboardContent::RichText {
+            plain: plain.to_string(),
+            html: escape_html(plain).replace('\n', "<br>"),
+        },
+    };
+
+    ClipboardItem {
+        content,
+        ..item.clone()
+    }
+}
+
+/// Applies the profile matching the window saved by the focus manager
+pub fn format_for_target(item: &ClipboardItem, profiles: &[PasteProfile]) -> ClipboardItem {
+    #[cfg(target_os = "linux")]
+    if let Some(target) = crate::focus_manager::focused_window_class() {
+        if let Some(profile) = resolve(profiles, &target) {
+            eprintln!(
+                "[PasteProfiles] Using '{}' profile for {:?}",
+                profile.name, target
+            );
+            return apply(profile.format, item);
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    let _ = profiles;
+
+    item.clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_matches_case_insensitively() {
+        let profiles = default_profiles(&["kitty".to_string()]);
+        let target = |name: &str| vec![name.to_string()];
+
+        assert_eq!(
+            resolve(&profiles, &target("Kitty")).unwrap().name,
+            "Terminals"
+        );
+        assert_eq!(resolve(&profiles, &target("slack")).unwrap().name, "Chat");
+        assert!(resolve(&profiles, &target("firefox")).is_none());
+    }
+
+    #[test]
+    fn test_apply_formats() {
+        let rich = ClipboardItem::new_rich_text("a < b".to_string(), "<b>a &lt; b</b>".to_string());
+        let text = ClipboardItem::new_text("a < b\nc\n".to_string());
+
+        assert!(matches!(
+            apply(PasteFormat::PlainText, &rich).content,
+            ClipboardContent::Text(t) if t == "a < b"
+        ));
+        assert!(matches!(
+            apply(PasteFormat::CodeBlock, &text).content,
+            ClipboardContent::Text(t) if t == "```\na < b\nc\n```"
+        ));
+        assert!(matches!(
+            apply(PasteFormat::Html, &text).content,
+            ClipboardContent::RichText { html, .. } if html == "a &lt; b<br>c<br>"
+        ));
+        assert_eq!(apply(PasteFormat::Html, &rich).content, rich.content);
+    }
+}
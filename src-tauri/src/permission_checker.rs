@@ -1,6 +1,7 @@
 //! Permission checker module for Windows 11 Clipboard History
 //! Handles uinput permission verification and fixing
 
+use crate::session::command_exists;
 use std::fs::OpenOptions;
 use std::path::PathBuf;
 use std::process::Command;
@@ -57,15 +58,6 @@ pub fn check_permissions() -> PermissionStatus {
     }
 }
 
-/// Check if a command exists in PATH
-fn command_exists(cmd: &str) -> bool {
-    Command::new("which")
-        .arg(cmd)
-        .output()
-        .map(|o| o.status.success())
-        .unwrap_or(false)
-}
-
 /// Apply ACL for immediate access (requires pkexec/sudo)
 #[tauri::command]
 pub fn fix_permissions_now() -> Result<String, String> {
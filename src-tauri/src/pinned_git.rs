@@ -0,0 +1,385 @@
+//! Pinned Git Mirror
+//! Optionally mirrors pinned items into a git-tracked directory, one file per
+//! item, and commits every change. Dotfile users can push the directory
+//! anywhere and browse revisions with `--history-log` or plain git.
+//!
+//! The directory may hold the user's own files too, so the mirror lists the
+//! files it wrote in a manifest and only ever deletes, stages and commits
+//! those.
+
+use crate::clipboard_manager::{calculate_hash, ClipboardContent, ClipboardItem};
+use crate::image_compression;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::mpsc::{self, Receiver, Sender};
+
+const DEFAULT_DIR_NAME: &str = "win11-clipboard-history/pinned";
+
+/// Names of the item files the mirror wrote, one per line
+const MANIFEST_FILE: &str = ".pinned-items";
+
+/// Identity used when the user has no git identity configured
+const FALLBACK_GIT_NAME: &str = "Clipboard History";
+const FALLBACK_GIT_EMAIL: &str = "clipboard-history@localhost";
+
+/// Serializes git invocations; each mirror commits on its own worker thread
+static GIT_LOCK: Mutex<()> = Mutex::new(());
+
+/// Why the last update of the mirror failed, None once one succeeds
+static LAST_ERROR: Mutex<Option<String>> = Mutex::new(None);
+
+/// Called when the mirror starts or stops failing
+static ERROR_LISTENER: Mutex<Option<Box<dyn Fn() + Send>>> = Mutex::new(None);
+
+/// Default mirror location (~/.local/share/win11-clipboard-history/pinned)
+pub fn default_dir() -> Option<PathBuf> {
+    dirs::data_local_dir().map(|dir| dir.join(DEFAULT_DIR_NAME))
+}
+
+/// A revision of the mirror, as printed by `--history-log`
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Revision {
+    pub commit: String,
+    pub date: String,
+    pub message: String,
+}
+
+/// File names and contents of the pinned items at one point in time
+type Snapshot = Vec<(String, Vec<u8>)>;
+
+pub struct PinnedGitMirror {
+    dir: PathBuf,
+    /// Hash of the last synced pinned set, to skip saves that didn't touch pins
+    last_snapshot: Mutex<Option<u64>>,
+    /// To the worker that writes and commits, in order; it stops once the
+    /// mirror is dropped
+    snapshots: Sender<Snapshot>,
+}
+
+impl PinnedGitMirror {
+    pub fn new(dir: PathBuf) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let worker_dir = dir.clone();
+        std::thread::spawn(move || run_worker(&worker_dir, receiver));
+        Self {
+            dir,
+            last_snapshot: Mutex::new(None),
+            snapshots: sender,
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Writes the pinned items into the mirror and commits in the background.
+    /// Does nothing if the pinned set is unchanged since the last call.
+    /// `load` gives an item with the data the history keeps out of memory,
+    /// such as evicted images, loaded back.
+    pub fn sync(
+        &self,
+        history: &[ClipboardItem],
+        load: impl Fn(&ClipboardItem) -> Result<ClipboardItem, String>,
+    ) {
+        let pinned: Vec<&ClipboardItem> = history.iter().filter(|item| item.pinned).collect();
+
+        let snapshot = calculate_hash(
+            &pinned
+                .iter()
+                .map(|item| (item.id.as_str(), item.content_hash()))
+                .collect::<Vec<_>>(),
+        );
+        {
+            let mut last = self.last_snapshot.lock();
+            if *last == Some(snapshot) {
+                return;
+            }
+            *last = Some(snapshot);
+        }
+
+        let mut files: Snapshot = Vec::new();
+        for item in pinned {
+            match load(item) {
+                Ok(item) => files.extend(item_file(&item)),
+                Err(e) => {
+                    // Retried on the next save, rather than dropping the file
+                    eprintln!("[PinnedGit] Failed to load item {}: {}", item.id, e);
+                    *self.last_snapshot.lock() = None;
+                    set_last_error(Some(e));
+                    return;
+                }
+            }
+        }
+        if self.snapshots.send(files).is_err() {
+            eprintln!("[PinnedGit] The mirror worker stopped");
+        }
+    }
+}
+
+/// Commits the snapshots mirrors send, skipping to the latest one when
+/// several are waiting, since only the newest matters
+fn run_worker(dir: &Path, snapshots: Receiver<Snapshot>) {
+    while let Ok(mut files) = snapshots.recv() {
+        while let Ok(newer) = snapshots.try_recv() {
+            files = newer;
+        }
+        let result = write_and_commit(dir, &files);
+        if let Err(e) = &result {
+            eprintln!("[PinnedGit] Failed to update {}: {}", dir.display(), e);
+        }
+        set_last_error(result.err());
+    }
+}
+
+/// Why the last update of the mirror failed, if it did
+pub fn last_error() -> Option<String> {
+    LAST_ERROR.lock().clone()
+}
+
+/// Forgets a failure, e.g. once the mirror is turned off
+pub fn clear_error() {
+    set_last_error(None);
+}
+
+/// Calls `listener` whenever the mirror starts or stops failing
+pub fn set_error_listener(listener: impl Fn() + Send + 'static) {
+    *ERROR_LISTENER.lock() = Some(Box::new(listener));
+}
+
+fn set_last_error(error: Option<String>) {
+    let changed = {
+        let mut last = LAST_ERROR.lock();
+        let changed = last.is_some() != error.is_some();
+        *last = error;
+        changed
+    };
+    if changed {
+        if let Some(listener) = ERROR_LISTENER.lock().as_ref() {
+            listener();
+        }
+    }
+}
+
+/// File name and contents for one pinned item
+fn item_file(item: &ClipboardItem) -> Option<(String, Vec<u8>)> {
+    match &item.content {
+        ClipboardContent::Text(text) => {
+            Some((format!("{}.txt", item.id), text.clone().into_bytes()))
+        }
+        ClipboardContent::RichText { plain, .. } => {
+            Some((format!("{}.txt", item.id), plain.clone().into_bytes()))
+        }
+        ClipboardContent::Image { base64, .. } => BASE64.decode(base64).ok().map(|bytes| {
+            let extension = image_compression::file_extension(&bytes);
+            (format!("{}.{}", item.id, extension), bytes)
+        }),
+    }
+}
+
+fn git(dir: &Path, args: &[&str]) -> Result<String, String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .map_err(|e| format!("failed to run git: {}", e))?;
+
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+fn ensure_repo(dir: &Path) -> Result<(), String> {
+    if dir.join(".git").exists() {
+        return Ok(());
+    }
+
+    fs::create_dir_all(dir).map_err(|e| format!("failed to create directory: {}", e))?;
+    git(dir, &["init", "--quiet"])?;
+
+    // Commits would fail without an identity; only set one locally if none exists
+    if git(dir, &["config", "user.email"]).is_err() {
+        git(dir, &["config", "user.name", FALLBACK_GIT_NAME])?;
+        git(dir, &["config", "user.email", FALLBACK_GIT_EMAIL])?;
+    }
+    Ok(())
+}
+
+/// The item files listed in the manifest of `dir`
+fn read_manifest(dir: &Path) -> Vec<String> {
+    fs::read_to_string(dir.join(MANIFEST_FILE))
+        .unwrap_or_default()
+        .lines()
+        .map(str::trim)
+        // Only bare file names, so an edited manifest can't reach outside
+        .filter(|name| !name.is_empty() && !name.contains('/') && !name.starts_with('.'))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Runs git with `args` followed by `paths`
+fn git_paths(dir: &Path, args: &[&str], paths: &[&str]) -> Result<String, String> {
+    let mut all = args.to_vec();
+    all.push("--");
+    all.extend_from_slice(paths);
+    git(dir, &all)
+}
+
+fn write_and_commit(dir: &Path, files: &[(String, Vec<u8>)]) -> Result<(), String> {
+    let _guard = GIT_LOCK.lock();
+    ensure_repo(dir)?;
+
+    let wanted: HashSet<&str> = files.iter().map(|(name, _)| name.as_str()).collect();
+
+    // Remove items that were unpinned or deleted, of those the mirror wrote
+    let previous = read_manifest(dir);
+    let stale: Vec<&str> = previous
+        .iter()
+        .map(String::as_str)
+        .filter(|name| !wanted.contains(name))
+        .collect();
+    let mut removed = 0;
+    for name in &stale {
+        match fs::remove_file(dir.join(name)) {
+            Ok(()) => removed += 1,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.to_string()),
+        }
+    }
+
+    let mut added = 0;
+    for (name, contents) in files {
+        let path = dir.join(name);
+        if !path.exists() {
+            added += 1;
+        }
+        fs::write(&path, contents).map_err(|e| e.to_string())?;
+    }
+    let manifest: String = files
+        .iter()
+        .map(|(name, _)| format!("{}\n", name))
+        .collect();
+    fs::write(dir.join(MANIFEST_FILE), manifest).map_err(|e| e.to_string())?;
+
+    // Stage only the mirror's own paths; deleted ones count if git knew them
+    let mut paths: Vec<&str> = files.iter().map(|(name, _)| name.as_str()).collect();
+    paths.push(MANIFEST_FILE);
+    git_paths(dir, &["add"], &paths)?;
+    if !stale.is_empty() {
+        git_paths(
+            dir,
+            &["rm", "--cached", "--quiet", "--ignore-unmatch"],
+            &stale,
+        )?;
+        let tracked =
+            git_paths(dir, &["ls-tree", "--name-only", "HEAD"], &stale).unwrap_or_default();
+        paths.extend(
+            stale
+                .iter()
+                .filter(|name| tracked.lines().any(|line| line == **name)),
+        );
+    }
+
+    // Nothing staged means the content on disk already matched
+    if git_paths(dir, &["diff", "--cached", "--quiet"], &paths).is_ok() {
+        return Ok(());
+    }
+
+    // Only these paths, leaving anything the user staged alone
+    let message = commit_message(added, removed, files.len());
+    git_paths(dir, &["commit", "--quiet", "-m", &message], &paths)?;
+    Ok(())
+}
+
+fn commit_message(added: usize, removed: usize, total: usize) -> String {
+    match (added, removed) {
+        (0, 0) => format!("Update pinned items ({} total)", total),
+        (added, 0) => format!("Pin {} item(s) ({} total)", added, total),
+        (0, removed) => format!("Unpin {} item(s) ({} total)", removed, total),
+        (added, removed) => format!(
+            "Pin {} and unpin {} item(s) ({} total)",
+            added, removed, total
+        ),
+    }
+}
+
+/// Reads the most recent revisions of the mirror
+pub fn history_log(dir: &Path, limit: usize) -> Result<Vec<Revision>, String> {
+    if !dir.join(".git").exists() {
+        return Err(format!("{} is not a git repository yet", dir.display()));
+    }
+
+    let limit = limit.to_string();
+    let output = git(
+        dir,
+        &[
+            "log",
+            "-n",
+            &limit,
+            "--date=iso-strict",
+            "--format=%h%x09%ad%x09%s",
+        ],
+    )?;
+    Ok(output.lines().filter_map(parse_log_line).collect())
+}
+
+fn parse_log_line(line: &str) -> Option<Revision> {
+    let mut fields = line.splitn(3, '\t');
+    Some(Revision {
+        commit: fields.next()?.to_string(),
+        date: fields.next()?.to_string(),
+        message: fields.next()?.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_log_line() {
+        let revision =
+            parse_log_line("abc1234\t2026-01-02T03:04:05+00:00\tPin 1 item(s) (3 total)").unwrap();
+        assert_eq!(revision.commit, "abc1234");
+        assert_eq!(revision.date, "2026-01-02T03:04:05+00:00");
+        assert_eq!(revision.message, "Pin 1 item(s) (3 total)");
+        assert!(parse_log_line("garbage").is_none());
+    }
+
+    #[test]
+    fn test_only_the_mirror_files_are_touched() {
+        if Command::new("git").arg("--version").output().is_err() {
+            return;
+        }
+        let dir = std::env::temp_dir().join("pinned_git_manifest_test");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("notes.txt"), "mine").unwrap();
+
+        let file = |name: &str| (name.to_string(), name.as_bytes().to_vec());
+        write_and_commit(&dir, &[file("a.txt"), file("b.txt")]).unwrap();
+        write_and_commit(&dir, &[file("b.txt")]).unwrap();
+
+        assert!(!dir.join("a.txt").exists());
+        assert!(dir.join("b.txt").exists());
+        assert_eq!(fs::read_to_string(dir.join("notes.txt")).unwrap(), "mine");
+        let tracked = git(&dir, &["ls-files"]).unwrap();
+        assert!(!tracked.contains("notes.txt") && !tracked.contains("a.txt"));
+        assert_eq!(history_log(&dir, 5).unwrap().len(), 2);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_commit_message_describes_change() {
+        assert_eq!(commit_message(2, 0, 5), "Pin 2 item(s) (5 total)");
+        assert_eq!(commit_message(0, 1, 4), "Unpin 1 item(s) (4 total)");
+        assert_eq!(commit_message(0, 0, 4), "Update pinned items (4 total)");
+    }
+}
@@ -0,0 +1,207 @@
+//! Placeholders
+//! Pinned and filed items can hold placeholders that are filled in right
+//! before they are pasted or typed out, so templates always paste fresh
+//! values:
+//!
+//! - `{today}` and `{now}`: the local date, and date and time
+//! - `{today+2d}`, `{now-3h}`: the same moved by minutes (m), hours (h),
+//!   days (d) or weeks (w)
+//! - `{now:%H:%M}`, `{today+1w:%A}`: any of those in a strftime format
+//! - `{uuid}`: a random UUID
+//! - `{rand:8}`: that many random letters and digits (up to 64)
+//! - `{clipboard}`: the text on the clipboard when pasting
+//!
+//! Anything else in braces is pasted as it is, so code and JSON in a
+//! snippet are left alone.
+
+use crate::clipboard_manager::{ClipboardContent, ClipboardItem};
+use crate::paste_profiles::escape_html;
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, Duration, Local};
+use uuid::Uuid;
+
+const TODAY_FORMAT: &str = "%Y-%m-%d";
+const NOW_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+/// Longest `{rand:N}`
+const MAX_RANDOM_LEN: usize = 64;
+
+const RANDOM_ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+
+/// Values the placeholders are filled in from
+pub struct PlaceholderContext {
+    pub now: DateTime<Local>,
+    /// Only read when the text has `{clipboard}`, see `needs_clipboard`
+    pub clipboard: Option<String>,
+}
+
+/// Whether filling in `text` needs the current clipboard text
+pub fn needs_clipboard(text: &str) -> bool {
+    text.contains("{clipboard}")
+}
+
+/// "+2d" or "-3h" as a duration
+fn parse_offset(offset: &str) -> Option<Duration> {
+    if offset.is_empty() {
+        return Some(Duration::zero());
+    }
+    let (sign, rest) = match offset.as_bytes()[0] {
+        b'+' => (1, &offset[1..]),
+        b'-' => (-1, &offset[1..]),
+        _ => return None,
+    };
+    let unit = rest.chars().last()?;
+    let amount: i64 = rest[..rest.len() - unit.len_utf8()].parse().ok()?;
+    let duration = match unit {
+        'm' => Duration::try_minutes(amount),
+        'h' => Duration::try_hours(amount),
+        'd' => Duration::try_days(amount),
+        'w' => Duration::try_weeks(amount),
+        _ => None,
+    }?;
+    Some(duration * sign)
+}
+
+/// `format` if chrono can format with it, so a typo doesn't panic
+fn valid_format(format: &str) -> Option<&str> {
+    let valid = StrftimeItems::new(format).all(|item| !matches!(item, Item::Error));
+    valid.then_some(format)
+}
+
+/// `{today...}` and `{now...}`
+fn expand_date(name: &str, now: DateTime<Local>) -> Option<String> {
+    let (base, default_format) = if let Some(rest) = name.strip_prefix("today") {
+        (rest, TODAY_FORMAT)
+    } else {
+        (name.strip_prefix("now")?, NOW_FORMAT)
+    };
+    let (offset, format) = match base.split_once(':') {
+        Some((offset, format)) => (offset, valid_format(format)?),
+        None => (base, default_format),
+    };
+    let at = now.checked_add_signed(parse_offset(offset)?)?;
+    Some(at.format(format).to_string())
+}
+
+/// `len` random letters and digits
+fn random_string(len: usize) -> String {
+    let mut out = String::with_capacity(len);
+    while out.len() < len {
+        for byte in Uuid::new_v4().into_bytes() {
+            // Bytes past the last whole multiple of the alphabet would favor its start
+            if (byte as usize) < 256 - 256 % RANDOM_ALPHABET.len() && out.len() < len {
+                out.push(RANDOM_ALPHABET[byte as usize % RANDOM_ALPHABET.len()] as char);
+            }
+        }
+    }
+    out
+}
+
+/// The value of one placeholder, without its braces, or None to keep it
+fn expand_one(name: &str, context: &PlaceholderContext) -> Option<String> {
+    match name {
+        "uuid" => Some(Uuid::new_v4().to_string()),
+        "clipboard" => context.clipboard.clone(),
+        _ => {
+            if let Some(len) = name.strip_prefix("rand:") {
+                let len: usize = len.parse().ok()?;
+                return (1..=MAX_RANDOM_LEN)
+                    .contains(&len)
+                    .then(|| random_string(len));
+            }
+            expand_date(name, context.now)
+        }
+    }
+}
+
+/// `text` with its placeholders filled in, each value passed through `escape`
+fn expand_with(text: &str, context: &PlaceholderContext, escape: fn(&str) -> String) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let value = after
+            .find(['}', '{', '\n'])
+            .filter(|&end| after.as_bytes()[end] == b'}')
+            .and_then(|end| Some((expand_one(&after[..end], context)?, end)));
+        match value {
+            Some((value, end)) => {
+                out.push_str(&escape(&value));
+                rest = &after[end + 1..];
+            }
+            None => {
+                out.push('{');
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// `text` with its placeholders filled in
+pub fn expand(text: &str, context: &PlaceholderContext) -> String {
+    expand_with(text, context, str::to_string)
+}
+
+/// The item with placeholders in its text and HTML filled in
+pub fn expand_item(item: &ClipboardItem, context: &PlaceholderContext) -> ClipboardItem {
+    let content = match &item.content {
+        ClipboardContent::Text(text) => ClipboardContent::Text(expand(text, context)),
+        ClipboardContent::RichText { plain, html } => ClipboardContent::RichText {
+            plain: expand(plain, context),
+            html: expand_with(html, context, escape_html),
+        },
+        ClipboardContent::Image { .. } => return item.clone(),
+    };
+    ClipboardItem {
+        content,
+        ..item.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_expand() {
+        let context = PlaceholderContext {
+            now: Local.with_ymd_and_hms(2024, 2, 28, 9, 5, 0).unwrap(),
+            clipboard: Some("<b>".to_string()),
+        };
+        let expand = |text: &str| expand(text, &context);
+
+        assert_eq!(expand("Due {today+2d}"), "Due 2024-03-01");
+        assert_eq!(expand("{now-10m}"), "2024-02-28 08:55");
+        assert_eq!(expand("{today+1w:%A %e %B}"), "Wednesday  6 March");
+        assert_eq!(expand("Re: {clipboard}"), "Re: <b>");
+        assert_eq!(expand("{uuid}").len(), 36);
+        let random = expand("{rand:40}");
+        assert_eq!(random.len(), 40);
+        assert!(random.chars().all(|c| c.is_ascii_alphanumeric()));
+
+        // Everything else stays as it is
+        for text in [
+            "fn main() { println!(\"{}\", x); }",
+            "{\"today\": 1}",
+            "{today+2y} {rand:0} {rand:100} {now:%Q} {today",
+        ] {
+            assert_eq!(expand(text), text);
+        }
+
+        let item = ClipboardItem::new_rich_text(
+            "{clipboard}".to_string(),
+            "<p>{clipboard}</p>".to_string(),
+        );
+        match expand_item(&item, &context).content {
+            ClipboardContent::RichText { plain, html } => {
+                assert_eq!(plain, "<b>");
+                assert_eq!(html, "<p>&lt;b&gt;</p>");
+            }
+            other => panic!("unexpected content {:?}", other),
+        }
+    }
+}
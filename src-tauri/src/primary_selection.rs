@@ -0,0 +1,348 @@
+//! Primary Selection Module
+//! Keeps a separate, in-memory history of the PRIMARY selection (highlight to
+//! copy, middle-click to paste). It is never merged into the main clipboard
+//! history, so browsing past selections does not pollute Ctrl+C history.
+//! Copy-on-select mode is the exception: `SelectionCommitter` decides which
+//! selections are deliberate enough to go into the main history.
+//! `SelectionSync` keeps CLIPBOARD and PRIMARY holding the same text, like
+//! Klipper's "synchronize clipboard and selection".
+
+use crate::clipboard_manager::{calculate_hash, ClipboardContent, ClipboardItem, SelectionKind};
+use std::time::{Duration, Instant};
+
+/// Number of selections kept; selections are frequent and short-lived
+pub const PRIMARY_HISTORY_SIZE: usize = 50;
+
+/// Longest copy-on-select delay, in seconds
+pub const MAX_COMMIT_DELAY_SECS: u64 = 60;
+
+/// Selections shorter than this are usually accidental double-clicks
+const MIN_SELECTION_LEN: usize = 2;
+
+#[derive(Default)]
+pub struct PrimaryHistory {
+    items: Vec<ClipboardItem>,
+}
+
+impl PrimaryHistory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a selection. Returns the new item, or None if it was ignored.
+    /// Extending a selection (dragging further) replaces the previous entry
+    /// instead of adding one item per intermediate state.
+    pub fn add_text(&mut self, text: String) -> Option<ClipboardItem> {
+        if text.trim().chars().count() < MIN_SELECTION_LEN {
+            return None;
+        }
+
+        let extends_latest = match self.items.first().map(item_text) {
+            Some(latest) if latest == text => return None,
+            Some(latest) => text.contains(latest) || latest.contains(&text),
+            None => false,
+        };
+        if extends_latest {
+            self.items.remove(0);
+        }
+
+        self.items.retain(|item| item_text(item) != text);
+
+        let item = ClipboardItem::new_text(text);
+        self.items.insert(0, item.clone());
+        self.items.truncate(PRIMARY_HISTORY_SIZE);
+        Some(item)
+    }
+
+    pub fn get_history(&self) -> Vec<ClipboardItem> {
+        self.items.clone()
+    }
+
+    pub fn get_item(&self, id: &str) -> Option<&ClipboardItem> {
+        self.items.iter().find(|item| item.id == id)
+    }
+
+    pub fn remove_item(&mut self, id: &str) {
+        self.items.retain(|item| item.id != id);
+    }
+
+    pub fn clear(&mut self) {
+        self.items.clear();
+    }
+}
+
+/// Copy-on-select: holds a selection back until the user has settled on it,
+/// i.e. it stayed selected for `delay` or focus moved to another window.
+/// Every step of a drag replaces the held selection, so one drag ends up as
+/// one history entry instead of a flood of partial ones.
+pub struct SelectionCommitter {
+    delay: Duration,
+    pending: Option<PendingSelection>,
+    /// Re-reads of the selection that was just committed are not new
+    last_committed: Option<String>,
+}
+
+struct PendingSelection {
+    text: String,
+    window: Option<String>,
+    since: Instant,
+}
+
+impl SelectionCommitter {
+    pub fn new(delay: Duration) -> Self {
+        Self {
+            delay,
+            pending: None,
+            last_committed: None,
+        }
+    }
+
+    pub fn set_delay(&mut self, delay: Duration) {
+        self.delay = delay;
+    }
+
+    /// Holds a new selection made in `window`. Returns the previously held
+    /// selection, with its window, when it was made in a different window
+    /// and is due now.
+    pub fn select(
+        &mut self,
+        text: String,
+        window: Option<String>,
+        now: Instant,
+    ) -> Option<(String, Option<String>)> {
+        if text.trim().chars().count() < MIN_SELECTION_LEN
+            || self.last_committed.as_deref() == Some(text.as_str())
+        {
+            return None;
+        }
+
+        let committed = match &self.pending {
+            Some(pending) if pending.text == text => return None,
+            Some(pending) if pending.window != window => self.commit(),
+            _ => None,
+        };
+        self.pending = Some(PendingSelection {
+            text,
+            window,
+            since: now,
+        });
+        committed
+    }
+
+    /// Called periodically with the focused window, or None when it is not
+    /// known. Returns the held selection and its window once it is old
+    /// enough or focus has moved on.
+    pub fn tick(&mut self, window: Option<&str>, now: Instant) -> Option<(String, Option<String>)> {
+        let pending = self.pending.as_ref()?;
+        let moved = window.is_some() && pending.window.as_deref() != window;
+        if now.duration_since(pending.since) >= self.delay || moved {
+            self.commit()
+        } else {
+            None
+        }
+    }
+
+    pub fn is_holding(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    /// Forgets the held selection, e.g. when the mode is turned off
+    pub fn clear(&mut self) {
+        self.pending = None;
+    }
+
+    fn commit(&mut self) -> Option<(String, Option<String>)> {
+        let pending = self.pending.take()?;
+        self.last_committed = Some(pending.text.clone());
+        Some((pending.text, pending.window))
+    }
+}
+
+/// Loop guard for selection sync. Writing one selection makes the other
+/// listener report the same text right back; that echo must not be mirrored
+/// again.
+#[derive(Default)]
+pub struct SelectionSync {
+    /// Selection the text came from and its hash, once both selections hold it
+    synced: Option<(SelectionKind, u64)>,
+}
+
+impl SelectionSync {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `text` appeared in `from`. Returns true if it should be written to the
+    /// other selection, false for our own write echoing back and for empty
+    /// text (apps clear PRIMARY when deselecting).
+    pub fn should_mirror(&mut self, from: SelectionKind, text: &str) -> bool {
+        if text.trim().is_empty() {
+            return false;
+        }
+        let hash = calculate_hash(&text);
+        if self.synced.is_some_and(|(_, synced)| synced == hash) {
+            return false;
+        }
+        self.synced = Some((from, hash));
+        true
+    }
+
+    /// Whether `kind` holds `text` only because it was mirrored from the
+    /// other selection
+    pub fn is_mirrored(&self, kind: SelectionKind, text: &str) -> bool {
+        self.synced == Some((kind.other(), calculate_hash(&text)))
+    }
+
+    /// Forgets the synced text, e.g. when the option is turned off
+    pub fn reset(&mut self) {
+        self.synced = None;
+    }
+}
+
+impl SelectionKind {
+    pub fn other(self) -> Self {
+        match self {
+            Self::Clipboard => Self::Primary,
+            Self::Primary => Self::Clipboard,
+        }
+    }
+}
+
+/// Full text of a text item (empty for images)
+fn item_text(item: &ClipboardItem) -> &str {
+    match &item.content {
+        ClipboardContent::Text(text) => text,
+        ClipboardContent::RichText { plain, .. } => plain,
+        ClipboardContent::Image { .. } => "",
+    }
+}
+
+/// Reads the current PRIMARY selection as text
+#[cfg(target_os = "linux")]
+pub fn read_primary_text() -> Option<String> {
+    use arboard::{Clipboard, GetExtLinux, LinuxClipboardKind};
+
+    Clipboard::new()
+        .ok()?
+        .get()
+        .clipboard(LinuxClipboardKind::Primary)
+        .text()
+        .ok()
+}
+
+/// Sets CLIPBOARD or PRIMARY to `text`
+#[cfg(target_os = "linux")]
+pub fn write_selection_text(kind: SelectionKind, text: &str) -> Result<(), String> {
+    use arboard::{Clipboard, LinuxClipboardKind, SetExtLinux};
+
+    let kind = match kind {
+        SelectionKind::Clipboard => LinuxClipboardKind::Clipboard,
+        SelectionKind::Primary => LinuxClipboardKind::Primary,
+    };
+    Clipboard::new()
+        .map_err(|e| e.to_string())?
+        .set()
+        .clipboard(kind)
+        .text(text.to_string())
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_growing_selection_replaces_previous_entry() {
+        let mut history = PrimaryHistory::new();
+        history.add_text("hello".to_string());
+        history.add_text("hello wor".to_string());
+        history.add_text("hello world".to_string());
+
+        let items = history.get_history();
+        assert_eq!(items.len(), 1);
+        assert_eq!(item_text(&items[0]), "hello world");
+    }
+
+    #[test]
+    fn test_ignores_tiny_and_repeated_selections() {
+        let mut history = PrimaryHistory::new();
+        assert!(history.add_text("a".to_string()).is_none());
+        assert!(history.add_text("first".to_string()).is_some());
+        assert!(history.add_text("first".to_string()).is_none());
+        assert!(history.add_text("second".to_string()).is_some());
+        assert!(history.add_text("first".to_string()).is_some());
+        assert_eq!(history.get_history().len(), 2);
+    }
+
+    #[test]
+    fn test_committer_merges_drag_and_waits_for_delay() {
+        let start = Instant::now();
+        let at = |ms: u64| start + Duration::from_millis(ms);
+        let window = || Some("firefox".to_string());
+        let mut committer = SelectionCommitter::new(Duration::from_secs(2));
+
+        assert_eq!(committer.select("hel".to_string(), window(), at(0)), None);
+        assert_eq!(
+            committer.select("hello".to_string(), window(), at(100)),
+            None
+        );
+        assert_eq!(committer.tick(Some("firefox"), at(1500)), None);
+        assert_eq!(
+            committer.tick(Some("firefox"), at(2100)),
+            Some(("hello".to_string(), window()))
+        );
+        assert_eq!(committer.tick(Some("firefox"), at(5000)), None);
+        // Selection re-read after committing it
+        assert_eq!(
+            committer.select("hello".to_string(), window(), at(5100)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_committer_commits_on_window_change() {
+        let now = Instant::now();
+        let mut committer = SelectionCommitter::new(Duration::from_secs(10));
+
+        committer.select("first".to_string(), Some("kitty".to_string()), now);
+        let due = committer.select("second".to_string(), Some("gedit".to_string()), now);
+        assert_eq!(due, Some(("first".to_string(), Some("kitty".to_string()))));
+        let due = committer.tick(Some("firefox"), now);
+        assert_eq!(due.map(|(text, _)| text).as_deref(), Some("second"));
+    }
+
+    #[test]
+    fn test_committer_waits_when_window_is_unknown() {
+        let now = Instant::now();
+        let mut committer = SelectionCommitter::new(Duration::from_secs(2));
+
+        committer.select("held".to_string(), Some("kitty".to_string()), now);
+        assert_eq!(committer.tick(None, now + Duration::from_secs(1)), None);
+        let due = committer.tick(None, now + Duration::from_secs(3));
+        assert_eq!(due.map(|(text, _)| text).as_deref(), Some("held"));
+    }
+
+    #[test]
+    fn test_sync_ignores_echo_of_own_write() {
+        let mut sync = SelectionSync::new();
+        assert!(sync.should_mirror(SelectionKind::Clipboard, "copied"));
+        // Our write to PRIMARY reported back by its listener
+        assert!(!sync.should_mirror(SelectionKind::Primary, "copied"));
+        assert!(sync.is_mirrored(SelectionKind::Primary, "copied"));
+        assert!(!sync.is_mirrored(SelectionKind::Clipboard, "copied"));
+
+        assert!(sync.should_mirror(SelectionKind::Primary, "selected"));
+        assert!(!sync.should_mirror(SelectionKind::Clipboard, "selected"));
+        assert!(sync.is_mirrored(SelectionKind::Clipboard, "selected"));
+        assert!(!sync.should_mirror(SelectionKind::Primary, "  "));
+    }
+
+    #[test]
+    fn test_history_is_capped() {
+        let mut history = PrimaryHistory::new();
+        for i in 0..PRIMARY_HISTORY_SIZE + 10 {
+            history.add_text(format!("selection number {} !", i * 1000));
+        }
+        assert_eq!(history.get_history().len(), PRIMARY_HISTORY_SIZE);
+    }
+}
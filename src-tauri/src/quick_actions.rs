@@ -0,0 +1,390 @@
+//! Quick Actions
+//! Things to do with an item besides pasting it: open a link in the browser,
+//! show copied files in the file manager, or open a text in `$VISUAL` /
+//! `$EDITOR` from a temporary file in a directory only the user can enter,
+//! cleared on the next start. Entities found in a text get some too:
+//! showing a path, or pinging an address in a terminal. Terminal editors such as vim are started
+//! in `$TERMINAL` or the first terminal found; without an editor the file
+//! goes to the default text app through xdg-open.
+
+use crate::clipboard_manager::ClipboardItem;
+use crate::session::command_exists;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Where texts opened in the editor are written, inside `$XDG_RUNTIME_DIR`
+/// or the user's data directory
+const EDITOR_DIR: &str = "win11-clipboard-history/editor";
+
+/// Link schemes handed to xdg-open
+const OPENABLE_SCHEMES: [&str; 5] = ["http://", "https://", "ftp://", "mailto:", "tel:"];
+
+/// Editors that need a terminal to run in
+const TERMINAL_EDITORS: [&str; 12] = [
+    "vi", "vim", "nvim", "nano", "micro", "hx", "helix", "kak", "emacs", "joe", "ne", "mcedit",
+];
+
+/// Terminals tried when `$TERMINAL` is not set, with the arguments that come
+/// before the command to run
+const TERMINALS: [(&str, &[&str]); 9] = [
+    ("x-terminal-emulator", &["-e"]),
+    ("gnome-terminal", &["--"]),
+    ("konsole", &["-e"]),
+    ("xfce4-terminal", &["-x"]),
+    ("kitty", &[]),
+    ("alacritty", &["-e"]),
+    ("foot", &[]),
+    ("wezterm", &["start", "--"]),
+    ("xterm", &["-e"]),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuickAction {
+    OpenUrl,
+    RevealFile,
+    OpenInEditor,
+}
+
+/// The link an item is made of, if it is nothing but one
+fn item_url(item: &ClipboardItem) -> Option<&str> {
+    let text = item.text()?.trim();
+    let openable = OPENABLE_SCHEMES
+        .iter()
+        .any(|scheme| text.starts_with(scheme));
+    (openable && !text.contains(char::is_whitespace)).then_some(text)
+}
+
+/// Actions that make sense for `item`
+pub fn available(item: &ClipboardItem) -> Vec<QuickAction> {
+    let mut actions = Vec::new();
+    if item_url(item).is_some() {
+        actions.push(QuickAction::OpenUrl);
+    }
+    if !item.file_paths().is_empty() {
+        actions.push(QuickAction::RevealFile);
+    } else if item.text().is_some() {
+        actions.push(QuickAction::OpenInEditor);
+    }
+    actions
+}
+
+pub fn run(item: &ClipboardItem, action: QuickAction) -> Result<(), String> {
+    if !available(item).contains(&action) {
+        return Err("This action does not apply to the item".to_string());
+    }
+    match action {
+        QuickAction::OpenUrl => open_url(item_url(item).unwrap_or_default()),
+        QuickAction::RevealFile => reveal_files(&item.file_paths()),
+        QuickAction::OpenInEditor => {
+            let file = write_temp_file(&item.id, item.text().unwrap_or_default())?;
+            open_in_editor(&file)
+        }
+    }
+}
+
+/// Opens a web or mailto link with the default app
+pub fn open_url(url: &str) -> Result<(), String> {
+    if !OPENABLE_SCHEMES
+        .iter()
+        .any(|scheme| url.starts_with(scheme))
+    {
+        return Err(format!("Not a link that can be opened: {}", url));
+    }
+    spawn(&["xdg-open".to_string(), url.to_string()])
+}
+
+/// Shows the files selected in the file manager (FileManager1 over D-Bus),
+/// or opens the folder of the first one where that is not offered
+pub fn reveal_files(paths: &[PathBuf]) -> Result<(), String> {
+    let first = paths.first().ok_or("No files to show")?;
+
+    #[cfg(target_os = "linux")]
+    {
+        let uris: Vec<String> = paths.iter().map(|path| file_uri(path)).collect();
+        match show_items(&uris) {
+            Ok(()) => return Ok(()),
+            Err(e) => eprintln!("[QuickActions] FileManager1 unavailable: {}", e),
+        }
+    }
+
+    let folder = first.parent().unwrap_or(first);
+    spawn(&[
+        "xdg-open".to_string(),
+        folder.to_string_lossy().into_owned(),
+    ])
+}
+
+/// Shows a path found in a text, "~/" meaning the home directory
+pub fn reveal_path(path: &str) -> Result<(), String> {
+    let path = match path.strip_prefix("~/") {
+        Some(rest) => dirs::home_dir().ok_or("No home directory")?.join(rest),
+        None => PathBuf::from(path),
+    };
+    if !path.exists() {
+        return Err(format!("{} does not exist", path.display()));
+    }
+    reveal_files(&[path])
+}
+
+/// Pings an IP address in a terminal, where the replies can be watched
+pub fn ping(address: &str) -> Result<(), String> {
+    let address: std::net::IpAddr = address
+        .parse()
+        .map_err(|_| format!("Not an IP address: {}", address))?;
+    let terminal = find_terminal().ok_or("No terminal found to run ping in")?;
+    spawn(&terminal_command(
+        &terminal,
+        ["ping".to_string(), address.to_string()],
+    ))
+}
+
+/// "file://" URI of an absolute path, escaping what URIs can't hold
+#[cfg(target_os = "linux")]
+fn file_uri(path: &Path) -> String {
+    use std::os::unix::ffi::OsStrExt;
+
+    let mut uri = "file://".to_string();
+    for &byte in path.as_os_str().as_bytes() {
+        if byte.is_ascii_alphanumeric() || b"/-._~".contains(&byte) {
+            uri.push(byte as char);
+        } else {
+            uri.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    uri
+}
+
+#[cfg(target_os = "linux")]
+fn show_items(uris: &[String]) -> zbus::Result<()> {
+    let connection = zbus::blocking::Connection::session()?;
+    connection.call_method(
+        Some("org.freedesktop.FileManager1"),
+        "/org/freedesktop/FileManager1",
+        Some("org.freedesktop.FileManager1"),
+        "ShowItems",
+        &(uris, ""),
+    )?;
+    Ok(())
+}
+
+/// Where texts opened in the editor go: `$XDG_RUNTIME_DIR`, which only the
+/// user can enter, or the data directory without one
+fn editor_dir() -> Option<PathBuf> {
+    std::env::var_os("XDG_RUNTIME_DIR")
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute())
+        .or_else(dirs::data_local_dir)
+        .map(|dir| dir.join(EDITOR_DIR))
+}
+
+/// Creates `dir` for the user alone, refusing one that is a symlink or
+/// belongs to someone else, and makes it private if it wasn't
+fn private_dir(dir: &Path) -> Result<(), String> {
+    let mut builder = std::fs::DirBuilder::new();
+    builder.recursive(true);
+    #[cfg(unix)]
+    std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+    builder
+        .create(dir)
+        .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+
+    let metadata = std::fs::symlink_metadata(dir)
+        .map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?;
+    if !metadata.is_dir() {
+        return Err(format!("{} is not a directory", dir.display()));
+    }
+    #[cfg(target_os = "linux")]
+    {
+        use std::os::unix::fs::{MetadataExt, PermissionsExt};
+
+        // SAFETY: getuid has no preconditions and cannot fail
+        if metadata.uid() != unsafe { libc::getuid() } {
+            return Err(format!("{} belongs to another user", dir.display()));
+        }
+        if metadata.mode() & 0o077 != 0 {
+            std::fs::set_permissions(dir, std::fs::Permissions::from_mode(0o700))
+                .map_err(|e| format!("Failed to restrict {}: {}", dir.display(), e))?;
+        }
+    }
+    Ok(())
+}
+
+/// Writes `text` to a file only the user can read, named after the item
+fn write_temp_file(id: &str, text: &str) -> Result<PathBuf, String> {
+    use std::io::Write;
+
+    let dir = editor_dir().ok_or("No directory for the file")?;
+    private_dir(&dir)?;
+    let name: String = id
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .take(8)
+        .collect();
+    let path = dir.join(format!("clip-{}.txt", name));
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = options
+        .open(&path)
+        .map_err(|e| format!("Failed to write temp file: {}", e))?;
+    file.write_all(text.as_bytes())
+        .map_err(|e| format!("Failed to write temp file: {}", e))?;
+    Ok(path)
+}
+
+/// Removes the texts opened in an editor before. Editors opened through
+/// xdg-open or a terminal outlive the command that started them, so the
+/// files are only cleared at the next start.
+pub fn remove_editor_files() {
+    if let Some(dir) = editor_dir().filter(|dir| private_dir(dir).is_ok()) {
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
+
+fn open_in_editor(file: &Path) -> Result<(), String> {
+    let editor = ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|editor| !editor.trim().is_empty());
+    spawn(&editor_command(
+        editor.as_deref(),
+        find_terminal().as_deref(),
+        file,
+    ))
+}
+
+/// `$TERMINAL`, or the first of `TERMINALS` that is installed
+fn find_terminal() -> Option<String> {
+    std::env::var("TERMINAL")
+        .ok()
+        .filter(|terminal| !terminal.trim().is_empty())
+        .or_else(|| {
+            TERMINALS
+                .iter()
+                .map(|(name, _)| name.to_string())
+                .find(|name| command_exists(name))
+        })
+}
+
+/// The command line that opens `file` in `editor`, inside `terminal` when
+/// the editor needs one; xdg-open without an editor, or without a terminal
+/// for a terminal editor
+pub fn editor_command(editor: Option<&str>, terminal: Option<&str>, file: &Path) -> Vec<String> {
+    let file = file.to_string_lossy().into_owned();
+    let editor: Vec<String> = editor
+        .unwrap_or_default()
+        .split_whitespace()
+        .map(str::to_string)
+        .collect();
+    let Some(program) = editor.first() else {
+        return vec!["xdg-open".to_string(), file];
+    };
+
+    let program_name = Path::new(program)
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    if !TERMINAL_EDITORS.contains(&program_name.as_str()) {
+        return editor.into_iter().chain([file]).collect();
+    }
+
+    match terminal {
+        Some(terminal) => terminal_command(terminal, editor.into_iter().chain([file])),
+        None => vec!["xdg-open".to_string(), file],
+    }
+}
+
+/// The command line that runs `command` inside `terminal`
+fn terminal_command(terminal: &str, command: impl IntoIterator<Item = String>) -> Vec<String> {
+    let terminal_name = Path::new(terminal)
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let terminal_args = TERMINALS
+        .iter()
+        .find(|(name, _)| *name == terminal_name)
+        .map_or(&["-e"][..], |(_, args)| args);
+    std::iter::once(terminal.to_string())
+        .chain(terminal_args.iter().map(|arg| arg.to_string()))
+        .chain(command)
+        .collect()
+}
+
+/// Starts the command and reaps it in the background
+fn spawn(command: &[String]) -> Result<(), String> {
+    let (program, args) = command.split_first().ok_or("Nothing to run")?;
+    let mut child = Command::new(program)
+        .args(args)
+        .spawn()
+        .map_err(|e| format!("Failed to run {}: {}", program, e))?;
+    std::thread::spawn(move || {
+        let _ = child.wait();
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_available_and_editor_command() {
+        let actions = |text: &str| available(&ClipboardItem::new_text(text.to_string()));
+        assert_eq!(
+            actions(" https://example.com/a "),
+            [QuickAction::OpenUrl, QuickAction::OpenInEditor]
+        );
+        assert_eq!(actions("file:///tmp/a.txt"), [QuickAction::RevealFile]);
+        assert_eq!(
+            actions("see https://example.com"),
+            [QuickAction::OpenInEditor]
+        );
+        assert!(available(&ClipboardItem::new_image(String::new(), 1, 1, 0)).is_empty());
+
+        let file = Path::new("/tmp/clip.txt");
+        let command = |editor, terminal| editor_command(editor, terminal, file).join(" ");
+        assert_eq!(
+            command(Some("code --wait"), None),
+            "code --wait /tmp/clip.txt"
+        );
+        assert_eq!(
+            command(Some("/usr/bin/nvim"), Some("gnome-terminal")),
+            "gnome-terminal -- /usr/bin/nvim /tmp/clip.txt"
+        );
+        assert_eq!(
+            command(Some("vim"), Some("my-term")),
+            "my-term -e vim /tmp/clip.txt"
+        );
+        assert_eq!(command(Some("nano"), None), "xdg-open /tmp/clip.txt");
+        assert_eq!(command(None, Some("kitty")), "xdg-open /tmp/clip.txt");
+
+        #[cfg(target_os = "linux")]
+        assert_eq!(
+            file_uri(Path::new("/tmp/My Files/ä.txt")),
+            "file:///tmp/My%20Files/%C3%A4.txt"
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_private_dir_is_restricted_and_refuses_symlinks() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let base = std::env::temp_dir().join(format!("quick-actions-{}", uuid::Uuid::new_v4()));
+        let dir = base.join("editor");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o755)).unwrap();
+        private_dir(&dir).unwrap();
+        let mode = std::fs::metadata(&dir).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o700);
+
+        let link = base.join("link");
+        std::os::unix::fs::symlink(&dir, &link).unwrap();
+        assert!(private_dir(&link).is_err());
+        let _ = std::fs::remove_dir_all(&base);
+    }
+}
@@ -8,6 +8,15 @@ use std::sync::OnceLock;
 /// Cached session type singleton
 static SESSION_TYPE: OnceLock<SessionType> = OnceLock::new();
 
+/// Cached result of data-control protocol negotiation
+static DATA_CONTROL: OnceLock<Option<DataControlProtocol>> = OnceLock::new();
+
+/// Cached result of the layer-shell probe
+static LAYER_SHELL: OnceLock<bool> = OnceLock::new();
+
+/// Global of the wlr-layer-shell protocol (wlroots compositors, KDE)
+const LAYER_SHELL_INTERFACE: &str = "zwlr_layer_shell_v1";
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SessionType {
     Wayland,
@@ -63,6 +72,90 @@ pub fn is_x11() -> bool {
     get_session_type() == SessionType::X11
 }
 
+/// Whether `cmd` is an installed program, as `which` finds it in PATH
+pub fn command_exists(cmd: &str) -> bool {
+    std::process::Command::new("which")
+        .arg(cmd)
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+/// Wayland protocols that let a client read the clipboard without focus
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataControlProtocol {
+    /// ext-data-control-v1 (standardized successor)
+    Ext,
+    /// zwlr_data_control_unstable_v1 (wlroots)
+    Wlr,
+}
+
+impl DataControlProtocol {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Ext => "ext-data-control-v1",
+            Self::Wlr => "wlr-data-control-unstable-v1",
+        }
+    }
+
+    /// Picks the best protocol out of those the compositor advertises.
+    /// The standardized ext protocol wins over the wlroots one.
+    pub fn negotiate(advertised: &[Self]) -> Option<Self> {
+        [Self::Ext, Self::Wlr]
+            .into_iter()
+            .find(|p| advertised.contains(p))
+    }
+}
+
+/// Get the data-control protocol to use for event-driven clipboard capture.
+/// Probes the compositor once; None on X11 or when neither protocol is offered.
+pub fn data_control_protocol() -> Option<DataControlProtocol> {
+    *DATA_CONTROL.get_or_init(|| {
+        if !is_wayland() {
+            return None;
+        }
+
+        #[cfg(target_os = "linux")]
+        let advertised = crate::wayland_clipboard::advertised_protocols();
+        #[cfg(not(target_os = "linux"))]
+        let advertised: Vec<DataControlProtocol> = Vec::new();
+
+        let chosen = DataControlProtocol::negotiate(&advertised);
+        match chosen {
+            Some(protocol) => eprintln!("[Session] Using {} for clipboard", protocol.name()),
+            None => eprintln!("[Session] No data-control protocol available, polling clipboard"),
+        }
+        chosen
+    })
+}
+
+/// Whether the compositor offers wlr-layer-shell, which lets the window be
+/// placed natively instead of through XWayland. Probed once; false on X11.
+pub fn layer_shell_available() -> bool {
+    *LAYER_SHELL.get_or_init(|| {
+        if !is_wayland() {
+            return false;
+        }
+
+        #[cfg(target_os = "linux")]
+        let available = crate::wayland_clipboard::advertised_interfaces()
+            .iter()
+            .any(|interface| interface == LAYER_SHELL_INTERFACE);
+        #[cfg(not(target_os = "linux"))]
+        let available = false;
+
+        eprintln!(
+            "[Session] Layer shell {}",
+            if available {
+                "available"
+            } else {
+                "not offered"
+            }
+        );
+        available
+    })
+}
+
 /// Explicitly initialize session detection.
 /// Useful to ensure the log message appears early in the application startup.
 pub fn init() {
@@ -80,4 +173,12 @@ mod tests {
         let second = get_session_type();
         assert_eq!(first, second);
     }
+
+    #[test]
+    fn test_negotiate_prefers_ext_data_control() {
+        use DataControlProtocol::*;
+        assert_eq!(DataControlProtocol::negotiate(&[Wlr, Ext]), Some(Ext));
+        assert_eq!(DataControlProtocol::negotiate(&[Wlr]), Some(Wlr));
+        assert_eq!(DataControlProtocol::negotiate(&[]), None);
+    }
 }
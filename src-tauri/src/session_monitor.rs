@@ -0,0 +1,161 @@
+//! Session Monitor
+//! Watches D-Bus for events after which display connections and desktop
+//! registrations need to be re-established: logind resume from suspend,
+//! and the desktop shell or compositor being restarted (GNOME Shell crash,
+//! `kwin_wayland --replace`, plasmashell restart).
+
+use crate::listener_supervisor::backoff_delay;
+use std::io::{BufRead, BufReader};
+use std::process::{Command, Stdio};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Bus names whose owner changing means the shell/compositor was restarted.
+/// The StatusNotifierWatcher hosts tray icons, so a new owner also drops ours.
+const SHELL_BUS_NAMES: &[&str] = &[
+    "org.kde.StatusNotifierWatcher",
+    "org.gnome.Shell",
+    "org.kde.KWin",
+];
+
+/// A monitor that ran this long is healthy again; backoff starts over
+const MONITOR_STABLE_AFTER: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SessionEvent {
+    /// The system woke up from suspend or hibernation
+    Resumed,
+    /// A shell/compositor bus name got a new owner
+    ShellRestarted(String),
+}
+
+/// Matches logind's `PrepareForSleep(false)`, sent after waking up
+pub fn parse_resume_signal(line: &str) -> bool {
+    line.contains("PrepareForSleep") && line.contains("(false,)")
+}
+
+/// Matches `NameOwnerChanged` for a shell bus name acquiring a new owner.
+/// gdbus prints these as `...NameOwnerChanged ('name', 'old', 'new')`.
+pub fn parse_shell_restart(line: &str) -> Option<String> {
+    if !line.contains("NameOwnerChanged") {
+        return None;
+    }
+
+    let args = &line[line.find('(')? + 1..line.rfind(')')?];
+    let fields: Vec<&str> = args
+        .split(',')
+        .map(|f| f.trim().trim_matches('\''))
+        .collect();
+    let [name, _old_owner, new_owner] = fields.as_slice() else {
+        return None;
+    };
+
+    // The old owner vanishing is reported separately with an empty new owner
+    if new_owner.is_empty() || !SHELL_BUS_NAMES.contains(name) {
+        return None;
+    }
+    Some(name.to_string())
+}
+
+/// Starts background threads that call `on_event` for every session event.
+/// Each monitor is restarted with backoff if `gdbus` exits.
+pub fn watch_session_events<F>(on_event: F)
+where
+    F: Fn(SessionEvent) + Send + Sync + 'static,
+{
+    let on_event = Arc::new(on_event);
+
+    let on_resume = on_event.clone();
+    spawn_monitor(
+        &["--system", "--dest", "org.freedesktop.login1"],
+        move |line| {
+            if parse_resume_signal(line) {
+                on_resume(SessionEvent::Resumed);
+            }
+        },
+    );
+
+    spawn_monitor(
+        &["--session", "--dest", "org.freedesktop.DBus"],
+        move |line| {
+            if let Some(name) = parse_shell_restart(line) {
+                on_event(SessionEvent::ShellRestarted(name));
+            }
+        },
+    );
+}
+
+fn spawn_monitor<F>(bus_args: &'static [&'static str], on_line: F)
+where
+    F: Fn(&str) + Send + 'static,
+{
+    std::thread::spawn(move || {
+        let mut attempt = 0;
+        loop {
+            let started = Instant::now();
+            if let Err(e) = run_monitor(bus_args, &on_line) {
+                eprintln!("[SessionMonitor] gdbus monitor {:?}: {}", bus_args, e);
+            }
+            if started.elapsed() >= MONITOR_STABLE_AFTER {
+                attempt = 0;
+            }
+            attempt += 1;
+            std::thread::sleep(backoff_delay(attempt));
+        }
+    });
+}
+
+fn run_monitor(bus_args: &[&str], on_line: &impl Fn(&str)) -> Result<(), String> {
+    let mut child = Command::new("gdbus")
+        .arg("monitor")
+        .args(bus_args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("failed to start: {}", e))?;
+
+    let stdout = child.stdout.take().ok_or("no stdout")?;
+    for line in BufReader::new(stdout).lines() {
+        match line {
+            Ok(line) => on_line(&line),
+            Err(e) => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!("read failed: {}", e));
+            }
+        }
+    }
+
+    let status = child.wait().map_err(|e| e.to_string())?;
+    Err(format!("exited with {}", status))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_resume_signal() {
+        assert!(parse_resume_signal(
+            "/org/freedesktop/login1: org.freedesktop.login1.Manager.PrepareForSleep (false,)"
+        ));
+        assert!(!parse_resume_signal(
+            "/org/freedesktop/login1: org.freedesktop.login1.Manager.PrepareForSleep (true,)"
+        ));
+    }
+
+    #[test]
+    fn test_parse_shell_restart_only_for_new_owner() {
+        let gained = "/org/freedesktop/DBus: org.freedesktop.DBus.NameOwnerChanged ('org.gnome.Shell', '', ':1.42')";
+        let lost = "/org/freedesktop/DBus: org.freedesktop.DBus.NameOwnerChanged ('org.gnome.Shell', ':1.7', '')";
+        let other =
+            "/org/freedesktop/DBus: org.freedesktop.DBus.NameOwnerChanged (':1.99', '', ':1.99')";
+
+        assert_eq!(
+            parse_shell_restart(gained),
+            Some("org.gnome.Shell".to_string())
+        );
+        assert_eq!(parse_shell_restart(lost), None);
+        assert_eq!(parse_shell_restart(other), None);
+    }
+}
@@ -0,0 +1,182 @@
+//! Shell Actions
+//! User-defined actions ("Upload", "Shorten URL") set up in the settings,
+//! each a command run with `sh -c` that gets the item's text on stdin. What
+//! the command prints can be added to history as a new item. Commands that
+//! run too long are killed along with what they started, and only the start
+//! of a large output is kept.
+
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+/// Commands still running after this long are killed
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Output kept from a command, in bytes
+const MAX_OUTPUT_BYTES: u64 = 1024 * 1024;
+
+/// How often a running command is checked on
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ShellAction {
+    /// Shown on the item's action menu
+    pub name: String,
+    /// Run with `sh -c`, the item's text on stdin
+    pub command: String,
+    /// Add what the command prints to history as a new item
+    #[serde(default)]
+    pub capture_output: bool,
+}
+
+/// Trims names and commands and drops actions missing either, keeping the
+/// first action of each name
+pub fn normalize(actions: &mut Vec<ShellAction>) {
+    let mut seen: Vec<String> = Vec::new();
+    actions.retain_mut(|action| {
+        action.name = action.name.trim().to_string();
+        action.command = action.command.trim().to_string();
+        if action.name.is_empty() || action.command.is_empty() || seen.contains(&action.name) {
+            return false;
+        }
+        seen.push(action.name.clone());
+        true
+    });
+}
+
+/// Runs `command` with `input` on stdin and returns what it printed
+pub fn run(command: &str, input: &str) -> Result<String, String> {
+    run_with_timeout(command, input, COMMAND_TIMEOUT)
+}
+
+fn run_with_timeout(command: &str, input: &str, timeout: Duration) -> Result<String, String> {
+    let mut shell = Command::new("sh");
+    shell
+        .args(["-c", command])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    // A group of its own, so a timeout also stops what the command started
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(&mut shell, 0);
+    let mut child = shell
+        .spawn()
+        .map_err(|e| format!("Failed to run command: {}", e))?;
+
+    // Feed and drain the pipes on their own threads so a command that
+    // prints before reading all its input can't block on a full pipe
+    let mut stdin = child.stdin.take();
+    let input = input.as_bytes().to_vec();
+    std::thread::spawn(move || {
+        if let Some(stdin) = stdin.as_mut() {
+            let _ = stdin.write_all(&input);
+        }
+    });
+    let stdout = child.stdout.take().map(read_in_background);
+    let stderr = child.stderr.take().map(read_in_background);
+
+    let deadline = Instant::now() + timeout;
+    let timed_out = |child: &mut Child| {
+        kill_group(child);
+        format!(
+            "Command did not finish within {} seconds",
+            timeout.as_secs()
+        )
+    };
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if Instant::now() >= deadline => return Err(timed_out(&mut child)),
+            Ok(None) => std::thread::sleep(POLL_INTERVAL),
+            Err(e) => return Err(format!("Process status check failed: {}", e)),
+        }
+    };
+
+    // A process the command left in the background can hold the pipes open
+    // after it exits, so the output is only waited for until the deadline
+    let collect = |reader: Option<Receiver<Vec<u8>>>| match reader {
+        None => Some(String::new()),
+        Some(reader) => {
+            match reader.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+                Ok(bytes) => Some(String::from_utf8_lossy(&bytes).into_owned()),
+                Err(RecvTimeoutError::Timeout) => None,
+                Err(RecvTimeoutError::Disconnected) => Some(String::new()),
+            }
+        }
+    };
+    let Some(stdout) = collect(stdout) else {
+        return Err(timed_out(&mut child));
+    };
+    if !status.success() {
+        let Some(stderr) = collect(stderr) else {
+            return Err(timed_out(&mut child));
+        };
+        let reason = match stderr.trim() {
+            "" => status.to_string(),
+            message => message.to_string(),
+        };
+        return Err(format!("Command failed: {}", reason));
+    }
+    Ok(stdout.trim_end_matches(['\n', '\r']).to_string())
+}
+
+/// Kills the command and whatever is left of its process group
+fn kill_group(child: &mut Child) {
+    #[cfg(target_os = "linux")]
+    // SAFETY: kill has no memory-safety preconditions; the group is the one
+    // the child leads, so nothing outside it is signalled
+    unsafe {
+        libc::kill(-(child.id() as libc::pid_t), libc::SIGKILL);
+    }
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+/// Reads up to `MAX_OUTPUT_BYTES` of `pipe`, then discards the rest so the
+/// command is not left blocked writing; sends the bytes once the pipe closes
+fn read_in_background(pipe: impl Read + Send + 'static) -> Receiver<Vec<u8>> {
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || {
+        let mut pipe = pipe;
+        let mut bytes = Vec::new();
+        let _ = (&mut pipe).take(MAX_OUTPUT_BYTES).read_to_end(&mut bytes);
+        let _ = std::io::copy(&mut pipe, &mut std::io::sink());
+        let _ = sender.send(bytes);
+    });
+    receiver
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_and_normalize() {
+        assert_eq!(run("tr a-z A-Z", "hello\n").unwrap(), "HELLO");
+        assert_eq!(run("echo done", "").unwrap(), "done");
+        let failed = run("echo 'no network' >&2; exit 3", "").unwrap_err();
+        assert_eq!(failed, "Command failed: no network");
+        assert!(run_with_timeout("sleep 5", "", Duration::from_millis(100)).is_err());
+
+        // A background process holding stdout is killed with the command
+        let started = Instant::now();
+        let left_running = "sleep 5 & echo started";
+        assert!(run_with_timeout(left_running, "", Duration::from_millis(300)).is_err());
+        assert!(started.elapsed() < Duration::from_secs(3));
+
+        let action = |name: &str, command: &str| ShellAction {
+            name: name.to_string(),
+            command: command.to_string(),
+            capture_output: false,
+        };
+        let mut actions = vec![
+            action(" Upload ", "curl -F f=@- https://0x0.st"),
+            action("Upload", "true"),
+            action("Empty", " "),
+        ];
+        normalize(&mut actions);
+        assert_eq!(actions, [action("Upload", "curl -F f=@- https://0x0.st")]);
+    }
+}
@@ -1,6 +1,7 @@
 //! Shortcut Conflict Detection for Various Desktop Environments
 //! Detects existing shortcuts that conflict with Super+V across different DEs
 
+use crate::session::command_exists;
 use std::env;
 use std::fs;
 use std::path::PathBuf;
@@ -166,14 +167,6 @@ fn run_resolution_command(cmd: &str) -> Result<(), String> {
     }
 }
 
-fn command_exists(cmd: &str) -> bool {
-    Command::new("which")
-        .arg(cmd)
-        .output()
-        .map(|o| o.status.success())
-        .unwrap_or(false)
-}
-
 fn gsettings_get(schema: &str, key: &str) -> Option<String> {
     if !command_exists("gsettings") {
         return None;
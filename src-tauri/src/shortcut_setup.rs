@@ -3,6 +3,9 @@
 
 use std::env;
 
+use crate::i18n;
+#[cfg(target_os = "linux")]
+use crate::session::command_exists;
 use crate::shortcut_conflict_detector::{
     auto_resolve_conflicts, detect_shortcut_conflicts, ConflictDetectionResult,
 };
@@ -48,6 +51,8 @@ pub fn get_desktop_environment() -> String {
         "Sway".to_string()
     } else if combined.contains("hyprland") {
         "Hyprland".to_string()
+    } else if combined.contains("niri") {
+        "Niri".to_string()
     } else {
         // Check for running tiling WMs
         if is_process_running("i3") {
@@ -56,6 +61,8 @@ pub fn get_desktop_environment() -> String {
             "Sway".to_string()
         } else if is_process_running("hyprland") || is_process_running("Hyprland") {
             "Hyprland".to_string()
+        } else if is_process_running("niri") {
+            "Niri".to_string()
         } else {
             xdg_current.to_uppercase()
         }
@@ -98,14 +105,18 @@ pub fn register_de_shortcut() -> Result<String, String> {
         // Run in a separate thread but wait for completion to avoid race conditions
         let (tx, rx) = std::sync::mpsc::channel();
         std::thread::spawn(move || {
-            crate::linux_shortcut_manager::register_global_shortcut();
-            let _ = tx.send(());
+            let registered = crate::linux_shortcut_manager::register_global_shortcut();
+            let _ = tx.send(registered);
         });
 
         match rx.recv() {
-            Ok(()) => {
+            Ok(true) => {
                 Ok("Shortcut registration completed. Check the app logs for details.".to_string())
             }
+            Ok(false) => Err(
+                "No shortcut could be registered automatically. Check the app logs for details."
+                    .to_string(),
+            ),
             Err(_) => Err("Shortcut registration thread failed unexpectedly.".to_string()),
         }
     }
@@ -116,6 +127,31 @@ pub fn register_de_shortcut() -> Result<String, String> {
     }
 }
 
+/// Remove our bindings, including stale ones from earlier hotkeys, from the
+/// desktop environment config
+#[tauri::command]
+pub fn unregister_de_shortcut() -> Result<String, String> {
+    #[cfg(target_os = "linux")]
+    {
+        let (tx, rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let environment = crate::linux_shortcut_manager::unregister_global_shortcut();
+            let _ = tx.send(environment);
+        });
+
+        match rx.recv() {
+            Ok(Ok(environment)) => Ok(format!("Removed keyboard shortcuts from {}.", environment)),
+            Ok(Err(e)) => Err(format!("{}.", e)),
+            Err(_) => Err("Shortcut removal thread failed unexpectedly.".to_string()),
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        Err("Shortcut removal is only supported on Linux.".to_string())
+    }
+}
+
 /// Check if the DE shortcut manager has the tools needed
 #[tauri::command]
 pub fn check_shortcut_tools() -> ShortcutToolsStatus {
@@ -139,6 +175,7 @@ pub fn check_shortcut_tools() -> ShortcutToolsStatus {
             "i3" => true,       // Uses config files
             "Sway" => true,     // Uses config files
             "Hyprland" => true, // Uses config files
+            "Niri" => true,     // Uses config files
             _ => gsettings,     // Fallback to gsettings
         };
 
@@ -166,7 +203,7 @@ pub fn check_shortcut_tools() -> ShortcutToolsStatus {
             kde_tools_available: false,
             xfce_tools_available: false,
             can_register_automatically: false,
-            manual_instructions: "This feature is only available on Linux.".to_string(),
+            manual_instructions: i18n::tr("error-linux-only"),
             has_conflicts: false,
             conflict_count: 0,
             can_auto_resolve_conflicts: false,
@@ -187,163 +224,22 @@ pub struct ShortcutToolsStatus {
     pub can_auto_resolve_conflicts: bool,
 }
 
-#[cfg(target_os = "linux")]
-fn command_exists(cmd: &str) -> bool {
-    std::process::Command::new("which")
-        .arg(cmd)
-        .output()
-        .map(|o| o.status.success())
-        .unwrap_or(false)
-}
-
 fn get_manual_instructions(de: &str) -> String {
-    match de {
-        "GNOME" => r#"**GNOME Settings:**
-1. Open Settings → Keyboard → Keyboard Shortcuts → Custom Shortcuts
-2. Click "+" to add a new shortcut
-3. Name: "Clipboard History"
-4. Command: `win11-clipboard-history`
-5. Shortcut: Press Super+V
-
-**⚠️ Note:** GNOME uses Super+V for the Notification Center by default.
-To free up Super+V, run:
-```
-gsettings set org.gnome.shell.keybindings toggle-message-tray "['<Super><Shift>v']"
-```"#
-            .to_string(),
-
-        "Pop!_OS" => r#"**Pop!_OS / Pop Shell:**
-1. Open Settings → Keyboard → Keyboard Shortcuts → Custom Shortcuts
-2. Add a new shortcut with command: `win11-clipboard-history`
-3. Set the shortcut to Super+V
-
-**⚠️ Note:** Pop!_OS inherits GNOME's Super+V for Notification Center.
-To free up Super+V, run:
-```
-gsettings set org.gnome.shell.keybindings toggle-message-tray "['<Super><Shift>v']"
-```
-
-If Pop Shell uses Super+V for tiling, change it in:
-Settings → Keyboard → Customize Shortcuts → Pop Shell"#
-            .to_string(),
-
-        "KDE Plasma" => r#"**KDE System Settings:**
-1. Open System Settings → Shortcuts → Custom Shortcuts
-2. Click "Edit" → "New" → "Global Shortcut" → "Command/URL"
-3. Name: "Clipboard History"
-4. Trigger: Click and press Meta+V
-5. Action: `win11-clipboard-history`
-
-**⚠️ Note:** If Klipper (KDE's clipboard) uses Meta+V:
-1. Right-click Klipper in system tray → Configure
-2. Change or disable its shortcut"#
-            .to_string(),
-
-        "Cinnamon" => r#"**Cinnamon Settings:**
-1. Open System Settings → Keyboard → Shortcuts → Custom Shortcuts
-2. Click "Add custom shortcut"
-3. Name: "Clipboard History"
-4. Command: `win11-clipboard-history`
-5. Click on the shortcut area and press Super+V"#
-            .to_string(),
-
-        "XFCE" => r#"**XFCE Settings:**
-1. Open Settings → Keyboard → Application Shortcuts
-2. Click "Add"
-3. Command: `win11-clipboard-history`
-4. Press Super+V when prompted"#
-            .to_string(),
-
-        "MATE" => r#"**MATE Control Center:**
-1. Open Control Center → Keyboard Shortcuts
-2. Click "Add"
-3. Name: "Clipboard History"
-4. Command: `win11-clipboard-history`
-5. Click on the shortcut and press Super+V"#
-            .to_string(),
-
-        "LXQt" => r#"**LXQt Configuration:**
-1. Open LXQt Configuration → Shortcut Keys
-2. Click "Add"
-3. Description: "Clipboard History"
-4. Command: `win11-clipboard-history`
-5. Set shortcut to Meta+V"#
-            .to_string(),
-
-        "LXDE" => r#"**LXDE/Openbox:**
-1. Edit ~/.config/openbox/lxde-rc.xml
-2. Add in <keyboard> section:
-
-<keybind key="Super_L+v">
-  <action name="Execute">
-    <command>win11-clipboard-history</command>
-  </action>
-</keybind>
-
-3. Run: openbox --reconfigure"#
-            .to_string(),
-
-        "COSMIC" => r#"**COSMIC Settings:**
-1. Open Settings → Keyboard → Custom Shortcuts
-2. Add new shortcut
-3. Command: `win11-clipboard-history`
-4. Binding: Super+V
-
-**Note:** If there's a conflict, check System shortcuts for Super+V bindings."#
-            .to_string(),
-
-        "i3" => r#"**i3 Configuration:**
-1. Edit your i3 config: `~/.config/i3/config`
-2. Comment out or remove any existing `bindsym $mod+v` line
-3. Add this line:
-```
-bindsym $mod+v exec win11-clipboard-history
-```
-4. Reload i3: Press $mod+Shift+r
-
-**Alternative shortcut:**
-```
-bindsym Ctrl+Mod1+v exec win11-clipboard-history
-```"#
-            .to_string(),
-
-        "Sway" => r#"**Sway Configuration:**
-1. Edit your Sway config: `~/.config/sway/config`
-2. Comment out or remove any existing `bindsym $mod+v` line
-3. Add this line:
-```
-bindsym $mod+v exec win11-clipboard-history
-```
-4. Reload Sway: Press $mod+Shift+c
-
-**Alternative shortcut:**
-```
-bindsym Ctrl+Mod1+v exec win11-clipboard-history
-```"#
-            .to_string(),
-
-        "Hyprland" => r#"**Hyprland Configuration:**
-1. Edit your Hyprland config: `~/.config/hypr/hyprland.conf`
-2. Comment out or remove any existing `bind = SUPER, V, ...` line
-3. Add this line:
-```
-bind = SUPER, V, exec, win11-clipboard-history
-```
-4. Config auto-reloads (or press Super+M to reload manually)
-
-**Alternative shortcut:**
-```
-bind = CTRL ALT, V, exec, win11-clipboard-history
-```"#
-            .to_string(),
-
-        _ => r#"**Generic Instructions:**
-1. Open your desktop environment's keyboard shortcuts settings
-2. Add a new custom shortcut
-3. Command: `win11-clipboard-history`
-4. Shortcut: Super+V (or your preferred combination)
-
-**Alternative:** Use Ctrl+Alt+V if Super+V conflicts with your DE."#
-            .to_string(),
-    }
+    let key = match de {
+        "GNOME" => "gnome",
+        "Pop!_OS" => "pop-os",
+        "KDE Plasma" => "kde",
+        "Cinnamon" => "cinnamon",
+        "XFCE" => "xfce",
+        "MATE" => "mate",
+        "LXQt" => "lxqt",
+        "LXDE" => "lxde",
+        "COSMIC" => "cosmic",
+        "i3" => "i3",
+        "Sway" => "sway",
+        "Hyprland" => "hyprland",
+        "Niri" => "niri",
+        _ => "generic",
+    };
+    i18n::tr(&format!("shortcut-instructions-{}", key))
 }
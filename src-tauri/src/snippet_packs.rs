@@ -0,0 +1,210 @@
+//! Snippet Packs
+//! A collection written to one JSON file, to hand canned replies and the
+//! like to colleagues, who import it as a collection of their own. A pack
+//! keeps the id of the collection it was made from and the ids of its
+//! snippets, so importing a newer version of a pack updates the earlier
+//! import instead of adding the snippets twice. A pack can be marked
+//! read-only, which the importing side keeps on the collection. Packs hold
+//! text only; images stay behind.
+
+use crate::clipboard_manager::{ClipboardContent, ClipboardItem};
+use crate::collections::Collection;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use uuid::Uuid;
+
+/// Version of the file layout, bumped on changes old versions can't read
+pub const PACK_FORMAT: u32 = 1;
+
+/// Extension of exported packs
+pub const PACK_EXTENSION: &str = "snippets.json";
+
+/// Largest pack file that is read
+const MAX_PACK_BYTES: u64 = 16 * 1024 * 1024;
+
+/// Most snippets in one pack
+const MAX_PACK_SNIPPETS: usize = 1000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnippetPack {
+    pub format: u32,
+    /// Id of the collection the pack was made from
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub read_only: bool,
+    pub exported_at: DateTime<Utc>,
+    pub snippets: Vec<Snippet>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Snippet {
+    pub id: String,
+    pub text: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub html: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+impl Snippet {
+    /// The snippet of a text item, given its full text (see
+    /// `ClipboardManager::full_text`); none for images
+    pub fn from_item(item: &ClipboardItem, full_text: Option<String>) -> Option<Self> {
+        let html = match &item.content {
+            ClipboardContent::RichText { html, .. } => Some(html.clone()),
+            ClipboardContent::Text(_) => None,
+            ClipboardContent::Image { .. } => return None,
+        };
+        Some(Self {
+            id: item.id.clone(),
+            text: full_text?,
+            html,
+            tags: item.tags.clone(),
+        })
+    }
+}
+
+/// What to do with a pack that was imported before
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PackConflict {
+    /// Update the earlier import: snippets are replaced by id, and the ones
+    /// the pack no longer has are taken out of the collection
+    #[default]
+    Update,
+    /// Import it as another collection, next to the earlier one
+    KeepBoth,
+}
+
+impl SnippetPack {
+    pub fn new(collection: &Collection, snippets: Vec<Snippet>, read_only: bool) -> Self {
+        Self {
+            format: PACK_FORMAT,
+            id: collection.id.clone(),
+            name: collection.name.clone(),
+            read_only,
+            exported_at: Utc::now(),
+            snippets,
+        }
+    }
+
+    pub fn write(&self, path: &Path) -> Result<(), String> {
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize pack: {}", e))?;
+        fs::write(path, content).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+
+    /// Reads a pack file, refusing ones too large, from a newer version, or
+    /// with ids that aren't UUIDs (item ids end up in file names)
+    pub fn read(path: &Path) -> Result<Self, String> {
+        let size = fs::metadata(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?
+            .len();
+        if size > MAX_PACK_BYTES {
+            return Err(format!(
+                "Snippet packs are at most {} MB",
+                MAX_PACK_BYTES / (1024 * 1024)
+            ));
+        }
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        Self::parse(&content)
+    }
+
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let pack: Self =
+            serde_json::from_str(content).map_err(|e| format!("Not a snippet pack: {}", e))?;
+        if pack.format > PACK_FORMAT {
+            return Err("The snippet pack is from a newer version of the app".to_string());
+        }
+        if pack.snippets.len() > MAX_PACK_SNIPPETS {
+            return Err(format!(
+                "Snippet packs hold at most {} snippets",
+                MAX_PACK_SNIPPETS
+            ));
+        }
+        let valid_ids = Uuid::parse_str(&pack.id).is_ok()
+            && pack
+                .snippets
+                .iter()
+                .all(|snippet| Uuid::parse_str(&snippet.id).is_ok());
+        if !valid_ids {
+            return Err("Not a snippet pack: invalid ids".to_string());
+        }
+        Ok(pack)
+    }
+}
+
+/// Id of a snippet imported into `collection` when its own id is taken by
+/// another item; the same on every import, so updates still find it
+pub fn derived_id(collection: &str, snippet: &str) -> String {
+    let namespace = Uuid::parse_str(collection).unwrap_or(Uuid::NAMESPACE_OID);
+    Uuid::new_v5(&namespace, snippet.as_bytes()).to_string()
+}
+
+/// A file name for an exported pack of the collection named `name`
+pub fn file_name(name: &str) -> String {
+    let stem: String = name
+        .trim()
+        .chars()
+        .map(|c| match c {
+            c if c.is_alphanumeric() || c == '-' || c == '_' => c,
+            _ => '-',
+        })
+        .collect();
+    let stem = stem.trim_matches('-');
+    match stem {
+        "" => format!("snippets.{}", PACK_EXTENSION),
+        stem => format!("{}.{}", stem, PACK_EXTENSION),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env::temp_dir;
+
+    #[test]
+    fn test_pack_round_trips_and_rejects_bad_files() {
+        let collection = Collection {
+            id: Uuid::new_v4().to_string(),
+            name: "Support replies".to_string(),
+            read_only: false,
+        };
+        let mut rich =
+            ClipboardItem::new_rich_text("Thanks!".to_string(), "<b>Thanks!</b>".to_string());
+        rich.tags = vec!["support".to_string()];
+        let image = ClipboardItem::new_image(String::new(), 1, 1, 0);
+        let snippets: Vec<Snippet> = [&rich, &image]
+            .into_iter()
+            .filter_map(|item| Snippet::from_item(item, item.text().map(str::to_string)))
+            .collect();
+        assert_eq!(snippets.len(), 1);
+
+        let path = temp_dir().join(file_name(&collection.name));
+        assert!(path.ends_with("Support-replies.snippets.json"));
+        SnippetPack::new(&collection, snippets.clone(), true)
+            .write(&path)
+            .unwrap();
+        let pack = SnippetPack::read(&path).unwrap();
+        assert_eq!(pack.id, collection.id);
+        assert!(pack.read_only);
+        assert_eq!(pack.snippets, snippets);
+        let _ = fs::remove_file(&path);
+
+        let mut bad = pack.clone();
+        bad.snippets[0].id = "../../escape".to_string();
+        assert!(SnippetPack::parse(&serde_json::to_string(&bad).unwrap()).is_err());
+        bad.snippets.clear();
+        bad.format = PACK_FORMAT + 1;
+        assert!(SnippetPack::parse(&serde_json::to_string(&bad).unwrap()).is_err());
+        assert!(SnippetPack::parse("{}").is_err());
+
+        let derived = derived_id(&collection.id, &rich.id);
+        assert_eq!(derived, derived_id(&collection.id, &rich.id));
+        assert_ne!(derived, rich.id);
+    }
+}
@@ -0,0 +1,367 @@
+//! Soak Test
+//! `--soak` replays synthetic clipboard activity (copies, re-copies, rich
+//! text, images, pins, deletes) against a real history store in a scratch
+//! directory, for as long as asked, then checks that memory use, the store's
+//! size on disk and the history length stayed bounded. Hidden from --help;
+//! meant for release testing, where weeks of copying can be replayed in an
+//! hour or two.
+
+use crate::clipboard_manager::{calculate_hash, ClipboardManager};
+use crate::history_store::{open_store, store_size, StorageBackend};
+use arboard::ImageData;
+use serde::Serialize;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+/// How often memory and store size are sampled
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Once history is full, resident memory may grow this much before it counts
+/// as a leak; allocators keep some slack
+const MAX_RSS_GROWTH: f64 = 1.5;
+const RSS_GROWTH_SLACK: u64 = 32 * 1024 * 1024;
+
+/// Same for the store on disk, which may keep some garbage until compacted
+const MAX_STORE_GROWTH: f64 = 2.0;
+const STORE_GROWTH_SLACK: u64 = 4 * 1024 * 1024;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SoakConfig {
+    pub duration: Duration,
+    /// Events per second, 0 for as fast as the store allows
+    pub rate: u32,
+    pub backend: StorageBackend,
+    pub max_history_size: usize,
+    /// Scratch directory for the store; never the real history
+    pub dir: PathBuf,
+}
+
+/// Memory and store size at one point of the run
+#[derive(Debug, Clone, Serialize)]
+pub struct SoakSample {
+    pub elapsed_secs: f64,
+    pub events: u64,
+    pub items: usize,
+    /// None where /proc is not available
+    pub rss_bytes: Option<u64>,
+    pub store_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SoakReport {
+    pub backend: &'static str,
+    pub events: u64,
+    pub elapsed_secs: f64,
+    pub events_per_sec: f64,
+    pub samples: Vec<SoakSample>,
+    /// Broken invariants; empty when the run passed
+    pub failures: Vec<String>,
+}
+
+impl SoakReport {
+    pub fn passed(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    /// Human-readable summary for the terminal
+    pub fn summary(&self) -> String {
+        let mut lines = vec![format!(
+            "Soak test ({}): {} events in {:.0}s ({:.0}/s)",
+            self.backend, self.events, self.elapsed_secs, self.events_per_sec
+        )];
+        for sample in &self.samples {
+            lines.push(format!(
+                "  {:>7.0}s  {:>9} events  {:>6} items  rss {:>8}  store {:>8}",
+                sample.elapsed_secs,
+                sample.events,
+                sample.items,
+                sample
+                    .rss_bytes
+                    .map(format_bytes)
+                    .unwrap_or_else(|| "?".to_string()),
+                format_bytes(sample.store_bytes),
+            ));
+        }
+        if self.passed() {
+            lines.push("PASSED".to_string());
+        } else {
+            lines.push("FAILED".to_string());
+            lines.extend(
+                self.failures
+                    .iter()
+                    .map(|failure| format!("  - {}", failure)),
+            );
+        }
+        lines.join("\n")
+    }
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1}{}", value, UNITS[unit])
+}
+
+/// Small deterministic generator (xorshift64*), so a failing run can be
+/// replayed
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n.max(1) as u64) as usize
+    }
+
+    fn percent(&mut self) -> usize {
+        self.below(100)
+    }
+}
+
+const WORDS: &[&str] = &[
+    "clipboard",
+    "history",
+    "paste",
+    "the",
+    "a",
+    "linux",
+    "fn",
+    "main",
+    "let",
+    "err",
+    "https://",
+    "example.com",
+    "SELECT",
+    "*",
+    "FROM",
+    "items",
+    "WHERE",
+    "id",
+    "=",
+    "42;",
+    "TODO",
+    "🙂",
+    "naïve",
+    "\n",
+    "\t",
+];
+
+fn random_text(rng: &mut Rng, serial: u64) -> String {
+    // Mostly short snippets, sometimes pages of text or a log dump
+    let words = match rng.percent() {
+        0..=79 => 1 + rng.below(20),
+        80..=97 => 20 + rng.below(500),
+        _ => 5_000 + rng.below(20_000),
+    };
+    let mut text = format!("#{} ", serial);
+    for _ in 0..words {
+        text.push_str(WORDS[rng.below(WORDS.len())]);
+        text.push(' ');
+    }
+    text
+}
+
+fn random_image(rng: &mut Rng) -> ImageData<'static> {
+    let (width, height) = (1 + rng.below(128), 1 + rng.below(128));
+    let bytes: Vec<u8> = (0..width * height * 4).map(|_| rng.next() as u8).collect();
+    ImageData {
+        width,
+        height,
+        bytes: bytes.into(),
+    }
+}
+
+/// One synthetic event
+fn step(manager: &mut ClipboardManager, rng: &mut Rng, serial: u64) {
+    match rng.percent() {
+        0..=59 => {
+            manager.add_text(random_text(rng, serial), None);
+        }
+        60..=69 => {
+            let text = random_text(rng, serial);
+            let html = format!("<p><b>{}</b></p>", text);
+            manager.add_text(text, Some(html));
+        }
+        // Copying something already in history again
+        70..=79 => {
+            let items = manager.items();
+            if let Some(text) = items
+                .get(rng.below(items.len()))
+                .and_then(|item| item.text())
+            {
+                let text = text.to_string();
+                manager.add_text(text, None);
+            }
+        }
+        80..=84 => {
+            let image = random_image(rng);
+            let hash = calculate_hash(&image.bytes);
+            manager.add_image(image, hash);
+        }
+        85..=89 => {
+            let items = manager.items();
+            if let Some(id) = items
+                .get(rng.below(items.len()))
+                .map(|item| item.id.clone())
+            {
+                manager.toggle_pin(&id);
+            }
+        }
+        _ => {
+            let items = manager.items();
+            if let Some(id) = items
+                .get(rng.below(items.len()))
+                .map(|item| item.id.clone())
+            {
+                manager.remove_item(&id);
+            }
+        }
+    }
+}
+
+/// Resident set size of this process
+fn rss_bytes() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+    let kib: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kib * 1024)
+}
+
+fn sample(manager: &ClipboardManager, started: Instant, events: u64) -> SoakSample {
+    SoakSample {
+        elapsed_secs: started.elapsed().as_secs_f64(),
+        events,
+        items: manager.items().len(),
+        rss_bytes: rss_bytes(),
+        store_bytes: store_size(manager.store().location()),
+    }
+}
+
+/// Runs the soak test. Errors only when the store can't be opened; broken
+/// invariants are reported in `SoakReport::failures`.
+pub fn run(config: &SoakConfig) -> Result<SoakReport, String> {
+    std::fs::create_dir_all(&config.dir)
+        .map_err(|e| format!("failed to create {}: {}", config.dir.display(), e))?;
+    let store = open_store(config.backend, &config.dir)?;
+    let mut manager = ClipboardManager::with_store(store, config.max_history_size);
+    let mut rng = Rng(0x5EED_CAFE_F00D_D00D);
+
+    let started = Instant::now();
+    let mut samples = vec![sample(&manager, started, 0)];
+    let mut next_sample = started + SAMPLE_INTERVAL;
+    // Growth is measured from the first sample after history filled up
+    let mut baseline: Option<SoakSample> = None;
+    let mut events = 0u64;
+
+    while started.elapsed() < config.duration {
+        step(&mut manager, &mut rng, events);
+        events += 1;
+
+        if config.rate > 0 {
+            let due = started + Duration::from_secs_f64(events as f64 / config.rate as f64);
+            if let Some(wait) = due.checked_duration_since(Instant::now()) {
+                std::thread::sleep(wait);
+            }
+        }
+        if Instant::now() >= next_sample {
+            let current = sample(&manager, started, events);
+            if baseline.is_none() && current.items >= config.max_history_size {
+                baseline = Some(current.clone());
+            }
+            samples.push(current);
+            next_sample += SAMPLE_INTERVAL;
+        }
+    }
+
+    let last = sample(&manager, started, events);
+    samples.push(last.clone());
+
+    let mut failures = Vec::new();
+    if last.items > config.max_history_size {
+        failures.push(format!(
+            "history holds {} items, over the limit of {}",
+            last.items, config.max_history_size
+        ));
+    }
+    if let Some(baseline) = &baseline {
+        if let (Some(before), Some(after)) = (baseline.rss_bytes, last.rss_bytes) {
+            if after as f64 > before as f64 * MAX_RSS_GROWTH + RSS_GROWTH_SLACK as f64 {
+                failures.push(format!(
+                    "memory grew from {} to {} after history was full",
+                    format_bytes(before),
+                    format_bytes(after)
+                ));
+            }
+        }
+        let (before, after) = (baseline.store_bytes, last.store_bytes);
+        if after as f64 > before as f64 * MAX_STORE_GROWTH + STORE_GROWTH_SLACK as f64 {
+            failures.push(format!(
+                "store grew from {} to {} after history was full",
+                format_bytes(before),
+                format_bytes(after)
+            ));
+        }
+    }
+
+    // What was saved must load back the same
+    if config.backend != StorageBackend::Memory {
+        let expected: Vec<String> = manager.items().iter().map(|item| item.id.clone()).collect();
+        drop(manager);
+        match open_store(config.backend, &config.dir).and_then(|mut store| store.load()) {
+            Ok(items) => {
+                let loaded: Vec<String> = items.into_iter().map(|item| item.id).collect();
+                if loaded != expected {
+                    failures.push(format!(
+                        "reloaded {} items, expected the {} in memory",
+                        loaded.len(),
+                        expected.len()
+                    ));
+                }
+            }
+            Err(e) => failures.push(format!("failed to reload the store: {}", e)),
+        }
+    }
+
+    let elapsed_secs = started.elapsed().as_secs_f64();
+    Ok(SoakReport {
+        backend: config.backend.as_str(),
+        events,
+        elapsed_secs,
+        events_per_sec: events as f64 / elapsed_secs.max(f64::EPSILON),
+        samples,
+        failures,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_short_soak_passes() {
+        let dir = std::env::temp_dir().join(format!("soak-test-{}", uuid::Uuid::new_v4()));
+        let config = SoakConfig {
+            duration: Duration::from_millis(300),
+            rate: 0,
+            backend: StorageBackend::Json,
+            max_history_size: 25,
+            dir: dir.clone(),
+        };
+        let report = run(&config).unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert!(report.passed(), "{}", report.summary());
+        assert!(report.events > 0);
+        assert!(report.samples.last().unwrap().items <= 25);
+    }
+}
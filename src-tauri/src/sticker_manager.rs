@@ -0,0 +1,403 @@
+//! Sticker Manager
+//! Sticker packs are sets of transparent PNG or WebP images, imported from
+//! a folder or from a URL: a single image, or a JSON manifest such as
+//! `{"name": "Cats", "stickers": ["wave.png", "https://…/nod.webp"]}` whose
+//! relative entries are resolved against it. Each sticker is stored as a
+//! PNG under the data directory, no larger than `STICKER_SIZE`, and pasted
+//! as image data rather than as a file URI like GIFs, which is what
+//! Telegram and Discord take as a sticker or an inline image. Images are
+//! checked and converted by `normalize_pack` before the pack is stored, so
+//! the decoding happens off the manager's lock.
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use image::{ImageFormat, ImageReader, Limits};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::io::{Cursor, Read};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Directory of the packs, inside the data directory
+const STICKERS_DIR: &str = "stickers";
+/// Index of the packs, inside `STICKERS_DIR`
+const INDEX_FILE: &str = "packs.json";
+
+/// Longest side of a stored sticker, Telegram's sticker size
+const STICKER_SIZE: u32 = 512;
+/// Larger images are skipped
+const MAX_STICKER_BYTES: usize = 5 * 1024 * 1024;
+/// Images wider or taller than this are skipped rather than decoded
+const MAX_DECODED_SIDE: u32 = 8192;
+/// Most memory decoding one image may take
+const MAX_DECODED_BYTES: u64 = 256 * 1024 * 1024;
+/// A pack with more images keeps the first ones
+const MAX_STICKERS_PER_PACK: usize = 500;
+/// Images downloaded for one manifest; the rest of its list is left out
+const MAX_DOWNLOADS_PER_IMPORT: usize = 100;
+
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(15);
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Sticker {
+    pub id: String,
+    /// From the file name, for search and the tooltip
+    pub name: String,
+    /// The stored PNG
+    pub file: PathBuf,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct StickerPack {
+    pub id: String,
+    pub name: String,
+    /// Folder or URL it was imported from
+    pub source: String,
+    pub stickers: Vec<Sticker>,
+}
+
+/// Images of a pack before they are stored: the pack's name, then each
+/// image's name and bytes
+pub type PackImages = (String, Vec<(String, Vec<u8>)>);
+
+fn is_sticker_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("png") || ext.eq_ignore_ascii_case("webp"))
+}
+
+/// File name without its extension
+fn stem(name: &str) -> String {
+    let name = name.rsplit('/').next().unwrap_or(name);
+    let name = name.split(['?', '#']).next().unwrap_or(name);
+    match name.rsplit_once('.') {
+        Some((stem, _)) if !stem.is_empty() => stem.to_string(),
+        _ => name.to_string(),
+    }
+}
+
+/// The PNG and WebP images of `folder`, in file name order; the pack is
+/// named after the folder
+pub fn read_folder(folder: &Path) -> Result<PackImages, String> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(folder)
+        .map_err(|e| format!("Cannot read {}: {}", folder.display(), e))?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && is_sticker_file(path))
+        .filter(|path| {
+            path.metadata()
+                .is_ok_and(|m| m.len() <= MAX_STICKER_BYTES as u64)
+        })
+        .collect();
+    paths.sort();
+    let images = paths
+        .into_iter()
+        .take(MAX_STICKERS_PER_PACK)
+        .filter_map(|path| {
+            let name = path.file_name()?.to_string_lossy().into_owned();
+            Some((stem(&name), fs::read(&path).ok()?))
+        })
+        .collect();
+    let name = folder
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "Stickers".to_string());
+    Ok((name, images))
+}
+
+/// The body at `url`, refused once it runs past `MAX_STICKER_BYTES`
+fn download(client: &reqwest::blocking::Client, url: &str) -> Result<Vec<u8>, String> {
+    let response = client
+        .get(url)
+        .send()
+        .map_err(|e| format!("Network request failed: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("HTTP Error: {}", response.status()));
+    }
+    let mut body = Vec::new();
+    response
+        .take(MAX_STICKER_BYTES as u64 + 1)
+        .read_to_end(&mut body)
+        .map_err(|e| format!("Failed to read bytes: {}", e))?;
+    if body.len() > MAX_STICKER_BYTES {
+        return Err("Image too large".to_string());
+    }
+    Ok(body)
+}
+
+/// The image URLs of a manifest, resolved against `base`, and its name
+fn parse_manifest(base: &reqwest::Url, manifest: &Value) -> Option<(String, Vec<String>)> {
+    let urls = manifest["stickers"]
+        .as_array()?
+        .iter()
+        .filter_map(|entry| entry.as_str().or_else(|| entry["url"].as_str()))
+        .filter_map(|entry| base.join(entry).ok())
+        .map(|url| url.to_string())
+        .take(MAX_DOWNLOADS_PER_IMPORT)
+        .collect();
+    let name = manifest["name"].as_str().unwrap_or("Stickers").to_string();
+    Some((name, urls))
+}
+
+/// Downloads the pack at `url`, a manifest or a single image. Blocks on the
+/// requests.
+pub fn fetch_url(url: &str) -> Result<PackImages, String> {
+    let base = reqwest::Url::parse(url).map_err(|e| format!("Invalid URL: {}", e))?;
+    let client = reqwest::blocking::Client::builder()
+        .timeout(DOWNLOAD_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Client build error: {}", e))?;
+    let body = download(&client, url)?;
+    if image::guess_format(&body).is_ok() {
+        return Ok(("Stickers".to_string(), vec![(stem(base.path()), body)]));
+    }
+
+    let manifest: Value = serde_json::from_slice(&body)
+        .map_err(|_| "The URL is neither an image nor a sticker pack manifest".to_string())?;
+    let (name, urls) = parse_manifest(&base, &manifest)
+        .ok_or("The sticker pack manifest has no \"stickers\" list")?;
+    let images = urls
+        .iter()
+        .filter_map(|url| match download(&client, url) {
+            Ok(bytes) => Some((stem(url), bytes)),
+            Err(e) => {
+                eprintln!("[StickerManager] Skipping {}: {}", url, e);
+                None
+            }
+        })
+        .collect();
+    Ok((name, images))
+}
+
+/// `bytes` as a PNG no larger than `STICKER_SIZE`, keeping transparency
+fn normalize_image(bytes: &[u8]) -> Result<Vec<u8>, String> {
+    if bytes.len() > MAX_STICKER_BYTES {
+        return Err("Image too large".to_string());
+    }
+    match image::guess_format(bytes) {
+        Ok(ImageFormat::Png | ImageFormat::WebP) => {}
+        _ => return Err("Not a PNG or WebP image".to_string()),
+    }
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_DECODED_SIDE);
+    limits.max_image_height = Some(MAX_DECODED_SIDE);
+    limits.max_alloc = Some(MAX_DECODED_BYTES);
+    let mut reader = ImageReader::new(Cursor::new(bytes))
+        .with_guessed_format()
+        .map_err(|e| format!("Invalid image: {}", e))?;
+    reader.limits(limits);
+    let mut image = reader
+        .decode()
+        .map_err(|e| format!("Invalid image: {}", e))?;
+    if image.width() > STICKER_SIZE || image.height() > STICKER_SIZE {
+        image = image.thumbnail(STICKER_SIZE, STICKER_SIZE);
+    }
+    let mut png = Cursor::new(Vec::new());
+    image::DynamicImage::ImageRgba8(image.to_rgba8())
+        .write_to(&mut png, ImageFormat::Png)
+        .map_err(|e| format!("Failed to encode sticker: {}", e))?;
+    Ok(png.into_inner())
+}
+
+/// The images of a pack that are PNG or WebP, as stored PNGs; slow for
+/// large packs, so it runs before the manager is locked
+pub fn normalize_pack(images: PackImages) -> Result<PackImages, String> {
+    let (name, images) = images;
+    let stickers: Vec<(String, Vec<u8>)> = images
+        .into_iter()
+        .filter_map(|(sticker_name, bytes)| match normalize_image(&bytes) {
+            Ok(png) => Some((sticker_name, png)),
+            Err(e) => {
+                eprintln!("[StickerManager] Skipping {}: {}", sticker_name, e);
+                None
+            }
+        })
+        .collect();
+    if stickers.is_empty() {
+        return Err("No PNG or WebP stickers found".to_string());
+    }
+    Ok((name, stickers))
+}
+
+/// The user's sticker packs
+pub struct StickerManager {
+    /// In the order they were imported
+    packs: Vec<StickerPack>,
+    data_dir: PathBuf,
+}
+
+impl StickerManager {
+    pub fn new(data_dir: PathBuf) -> Self {
+        let mut manager = Self {
+            packs: Vec::new(),
+            data_dir,
+        };
+        if let Err(e) = manager.load() {
+            eprintln!("[StickerManager] Failed to load sticker packs: {}", e);
+        }
+        manager
+    }
+
+    pub fn packs(&self) -> Vec<StickerPack> {
+        self.packs.clone()
+    }
+
+    fn dir(&self) -> PathBuf {
+        self.data_dir.join(STICKERS_DIR)
+    }
+
+    /// Stores the images of `normalize_pack` as a new pack
+    pub fn import(&mut self, images: PackImages, source: &str) -> Result<StickerPack, String> {
+        let (name, images) = images;
+        if images.is_empty() {
+            return Err("No PNG or WebP stickers found".to_string());
+        }
+        let id = uuid::Uuid::new_v4().to_string();
+        let pack_dir = self.dir().join(&id);
+        fs::create_dir_all(&pack_dir)
+            .map_err(|e| format!("Failed to create {}: {}", pack_dir.display(), e))?;
+
+        let mut stickers = Vec::new();
+        for (index, (sticker_name, png)) in images.into_iter().enumerate() {
+            let file = pack_dir.join(format!("{}.png", index));
+            if let Err(e) = fs::write(&file, png) {
+                let _ = fs::remove_dir_all(&pack_dir);
+                return Err(format!("Failed to write sticker: {}", e));
+            }
+            stickers.push(Sticker {
+                id: index.to_string(),
+                name: sticker_name,
+                file,
+            });
+        }
+
+        let pack = StickerPack {
+            id,
+            name,
+            source: source.to_string(),
+            stickers,
+        };
+        self.packs.push(pack.clone());
+        self.save()?;
+        Ok(pack)
+    }
+
+    /// Deletes the pack and its files; false when there was none
+    pub fn remove_pack(&mut self, id: &str) -> Result<bool, String> {
+        let Some(index) = self.packs.iter().position(|pack| pack.id == id) else {
+            return Ok(false);
+        };
+        self.packs.remove(index);
+        let _ = fs::remove_dir_all(self.dir().join(id));
+        self.save()?;
+        Ok(true)
+    }
+
+    /// The sticker's PNG as base64, for pasting
+    pub fn sticker_image(&self, pack_id: &str, sticker_id: &str) -> Result<String, String> {
+        let sticker = self
+            .packs
+            .iter()
+            .find(|pack| pack.id == pack_id)
+            .and_then(|pack| {
+                pack.stickers
+                    .iter()
+                    .find(|sticker| sticker.id == sticker_id)
+            })
+            .ok_or("Sticker not found")?;
+        fs::read(&sticker.file)
+            .map(|bytes| BASE64.encode(bytes))
+            .map_err(|e| format!("Failed to read sticker: {}", e))
+    }
+
+    fn load(&mut self) -> Result<(), String> {
+        let path = self.dir().join(INDEX_FILE);
+        if !path.exists() {
+            return Ok(());
+        }
+        let content = fs::read_to_string(&path).map_err(|e| format!("Read error: {}", e))?;
+        self.packs = serde_json::from_str(&content).map_err(|e| format!("Parse error: {}", e))?;
+        Ok(())
+    }
+
+    fn save(&self) -> Result<(), String> {
+        fs::create_dir_all(self.dir()).map_err(|e| format!("Failed to create data dir: {}", e))?;
+        let content = serde_json::to_string_pretty(&self.packs)
+            .map_err(|e| format!("Serialize error: {}", e))?;
+        fs::write(self.dir().join(INDEX_FILE), content).map_err(|e| format!("Write error: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let mut png = Cursor::new(Vec::new());
+        image::DynamicImage::new_rgba8(width, height)
+            .write_to(&mut png, ImageFormat::Png)
+            .unwrap();
+        png.into_inner()
+    }
+
+    #[test]
+    fn test_import_folder_and_paste_data() {
+        let dir = std::env::temp_dir().join("sticker_manager_test");
+        let _ = fs::remove_dir_all(&dir);
+        let folder = dir.join("Cats");
+        fs::create_dir_all(&folder).unwrap();
+        fs::write(folder.join("b-nod.png"), png(1024, 512)).unwrap();
+        fs::write(folder.join("a-wave.png"), png(64, 64)).unwrap();
+        fs::write(folder.join("notes.txt"), "not a sticker").unwrap();
+        fs::write(folder.join("broken.webp"), "not an image").unwrap();
+
+        let mut manager = StickerManager::new(dir.join("data"));
+        let images = read_folder(&folder).unwrap();
+        assert_eq!(images.0, "Cats");
+        assert_eq!(images.1.len(), 3);
+        let pack = manager
+            .import(normalize_pack(images).unwrap(), "folder")
+            .unwrap();
+        let names: Vec<&str> = pack.stickers.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["a-wave", "b-nod"]);
+
+        let data = manager
+            .sticker_image(&pack.id, &pack.stickers[1].id)
+            .unwrap();
+        let stored = image::load_from_memory(&BASE64.decode(data).unwrap()).unwrap();
+        assert_eq!((stored.width(), stored.height()), (512, 256));
+
+        let loaded = StickerManager::new(dir.join("data"));
+        assert_eq!(loaded.packs(), vec![pack.clone()]);
+        assert!(manager.remove_pack(&pack.id).unwrap());
+        assert!(!pack.stickers[0].file.exists());
+        assert!(normalize_pack(("Empty".into(), Vec::new())).is_err());
+        assert!(manager.import(("Empty".into(), Vec::new()), "x").is_err());
+
+        // Too large to decode, whatever the file size
+        let huge = png(MAX_DECODED_SIDE + 1, 1);
+        assert!(normalize_image(&huge).is_err());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_manifest_urls() {
+        let base = reqwest::Url::parse("https://example.com/packs/cats.json").unwrap();
+        let manifest: Value = serde_json::from_str(
+            r#"{"name": "Cats", "stickers": ["wave.png", {"url": "https://cdn.example.com/nod.webp"}]}"#,
+        )
+        .unwrap();
+        let (name, urls) = parse_manifest(&base, &manifest).unwrap();
+        assert_eq!(name, "Cats");
+        assert_eq!(
+            urls,
+            [
+                "https://example.com/packs/wave.png",
+                "https://cdn.example.com/nod.webp"
+            ]
+        );
+        assert_eq!(stem("https://cdn.example.com/nod.webp?size=2"), "nod");
+    }
+}
@@ -0,0 +1,56 @@
+//! Sway / i3 IPC
+//! Records the focused container before the popup opens and focuses it again
+//! with `[con_id=...] focus` before pasting. Both compositors speak the same
+//! IPC protocol, so one adapter covers them.
+
+use crate::focus_manager::{AdapterWindow, FocusAdapter};
+use swayipc::{Connection, Node};
+
+/// App id of a container: the Wayland app_id, or WM_CLASS for X clients
+fn node_app_id(node: &Node) -> String {
+    node.app_id
+        .clone()
+        .or_else(|| node.window_properties.as_ref()?.class.clone())
+        .unwrap_or_default()
+}
+
+pub struct SwayAdapter;
+
+impl FocusAdapter for SwayAdapter {
+    fn id(&self) -> &'static str {
+        "sway"
+    }
+
+    fn name(&self) -> &'static str {
+        "sway/i3"
+    }
+
+    fn is_available(&self) -> bool {
+        // Only trust the environment; swayipc would otherwise spawn
+        // `sway --get-socketpath` on every lookup
+        std::env::var_os("SWAYSOCK").is_some() || std::env::var_os("I3SOCK").is_some()
+    }
+
+    fn active_window(&self) -> Option<AdapterWindow> {
+        let tree = Connection::new().ok()?.get_tree().ok()?;
+        let node = tree.find_focused_as_ref(|node| node.focused)?;
+        Some(AdapterWindow {
+            id: node.id.to_string(),
+            app_id: node_app_id(node),
+        })
+    }
+
+    fn activate(&self, window: &AdapterWindow) -> Result<(), String> {
+        let mut connection =
+            Connection::new().map_err(|e| format!("Failed to connect to sway/i3 IPC: {}", e))?;
+        let outcomes = connection
+            .run_command(format!("[con_id={}] focus", window.id))
+            .map_err(|e| format!("Failed to send focus command: {}", e))?;
+
+        outcomes
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()
+            .map(|_| ())
+            .map_err(|e| format!("focus failed: {}", e))
+    }
+}
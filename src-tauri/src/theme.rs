@@ -0,0 +1,237 @@
+//! Theme
+//! The theme section of the user settings: accent color, corner radius and
+//! named presets, next to the background opacities. Picking a preset writes
+//! its values into the settings when they are validated, so what is saved is
+//! always what is shown; editing a value by hand goes back to "custom". The
+//! resolved `Theme` is sent to the windows as `theme-changed`.
+
+use serde::{Deserialize, Serialize};
+
+/// Largest corner radius, in CSS pixels
+pub const MAX_CORNER_RADIUS: u32 = 24;
+
+/// Range of the font scale setting, 1 being the default text size
+pub const MIN_FONT_SCALE: f32 = 0.8;
+pub const MAX_FONT_SCALE: f32 = 1.5;
+
+const DEFAULT_ACCENT_COLOR: &str = "#0078d4";
+const DEFAULT_CORNER_RADIUS: u32 = 8;
+
+/// Named sets of theme values
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThemePreset {
+    /// Whatever was set by hand
+    Custom,
+    Windows11,
+    Midnight,
+    Sunset,
+    Forest,
+}
+
+/// Values a preset sets
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PresetValues {
+    pub accent_color: &'static str,
+    pub corner_radius: u32,
+    pub dark_background_opacity: f32,
+    pub light_background_opacity: f32,
+}
+
+impl ThemePreset {
+    pub const ALL: [ThemePreset; 5] = [
+        Self::Custom,
+        Self::Windows11,
+        Self::Midnight,
+        Self::Sunset,
+        Self::Forest,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Custom => "custom",
+            Self::Windows11 => "windows11",
+            Self::Midnight => "midnight",
+            Self::Sunset => "sunset",
+            Self::Forest => "forest",
+        }
+    }
+
+    pub fn from_setting(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|preset| preset.as_str() == value)
+    }
+
+    /// None for `Custom`
+    pub fn values(self) -> Option<PresetValues> {
+        let values = |accent_color, corner_radius, dark, light| PresetValues {
+            accent_color,
+            corner_radius,
+            dark_background_opacity: dark,
+            light_background_opacity: light,
+        };
+        match self {
+            Self::Custom => None,
+            Self::Windows11 => Some(values(
+                DEFAULT_ACCENT_COLOR,
+                DEFAULT_CORNER_RADIUS,
+                0.70,
+                0.70,
+            )),
+            Self::Midnight => Some(values("#8b5cf6", 10, 0.92, 0.80)),
+            Self::Sunset => Some(values("#f97316", 14, 0.75, 0.85)),
+            Self::Forest => Some(values("#16a34a", 6, 0.85, 0.90)),
+        }
+    }
+}
+
+/// The theme section as saved, beside the opacities
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ThemeSettings {
+    /// "custom" or the name of a preset
+    #[serde(default = "default_preset")]
+    pub preset: String,
+    /// "#rrggbb"
+    #[serde(default = "default_accent_color")]
+    pub accent_color: String,
+    /// Corner radius of the window and cards, in CSS pixels
+    #[serde(default = "default_corner_radius")]
+    pub corner_radius: u32,
+}
+
+fn default_preset() -> String {
+    ThemePreset::Custom.as_str().to_string()
+}
+
+fn default_accent_color() -> String {
+    DEFAULT_ACCENT_COLOR.to_string()
+}
+
+fn default_corner_radius() -> u32 {
+    DEFAULT_CORNER_RADIUS
+}
+
+impl Default for ThemeSettings {
+    fn default() -> Self {
+        Self {
+            preset: default_preset(),
+            accent_color: default_accent_color(),
+            corner_radius: default_corner_radius(),
+        }
+    }
+}
+
+impl ThemeSettings {
+    pub fn preset(&self) -> ThemePreset {
+        ThemePreset::from_setting(&self.preset).unwrap_or(ThemePreset::Custom)
+    }
+
+    /// Falls back to the defaults for unknown presets and colors, and clamps
+    /// the radius
+    pub fn validate(&mut self) {
+        if ThemePreset::from_setting(&self.preset).is_none() {
+            self.preset = default_preset();
+        }
+        self.accent_color =
+            normalize_color(&self.accent_color).unwrap_or_else(default_accent_color);
+        self.corner_radius = self.corner_radius.min(MAX_CORNER_RADIUS);
+    }
+}
+
+/// What the windows are styled with
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Theme {
+    pub preset: String,
+    pub accent_color: String,
+    /// The accent lightened a little, for hover states
+    pub accent_hover_color: String,
+    pub corner_radius: u32,
+    pub dark_background_opacity: f32,
+    pub light_background_opacity: f32,
+    /// "comfortable" or "compact"
+    pub density: String,
+    /// Multiplier for the text size
+    pub font_scale: f32,
+}
+
+impl Theme {
+    pub fn new(settings: &ThemeSettings, dark_opacity: f32, light_opacity: f32) -> Self {
+        Self {
+            preset: settings.preset.clone(),
+            accent_color: settings.accent_color.clone(),
+            accent_hover_color: lighten(&settings.accent_color, 0.1)
+                .unwrap_or_else(|| settings.accent_color.clone()),
+            corner_radius: settings.corner_radius,
+            dark_background_opacity: dark_opacity,
+            light_background_opacity: light_opacity,
+            density: "comfortable".to_string(),
+            font_scale: 1.0,
+        }
+    }
+}
+
+fn parse_color(color: &str) -> Option<[u8; 3]> {
+    let hex = color.trim().strip_prefix('#')?;
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let channel = |digits: &str| u8::from_str_radix(digits, 16).ok();
+    match hex.len() {
+        // #rgb stands for #rrggbb
+        3 => {
+            let mut rgb = [0; 3];
+            for (i, digit) in hex.chars().enumerate() {
+                rgb[i] = channel(&digit.to_string())? * 17;
+            }
+            Some(rgb)
+        }
+        6 => Some([
+            channel(&hex[0..2])?,
+            channel(&hex[2..4])?,
+            channel(&hex[4..6])?,
+        ]),
+        _ => None,
+    }
+}
+
+fn format_color([r, g, b]: [u8; 3]) -> String {
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+/// "#rgb" or "#rrggbb" in any case as lowercase "#rrggbb"
+pub fn normalize_color(color: &str) -> Option<String> {
+    parse_color(color).map(format_color)
+}
+
+/// `color` mixed with `amount` (0 to 1) of white
+fn lighten(color: &str, amount: f32) -> Option<String> {
+    let rgb = parse_color(color)?;
+    Some(format_color(rgb.map(|channel| {
+        (channel as f32 + (255.0 - channel as f32) * amount).round() as u8
+    })))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_theme_settings() {
+        let mut settings = ThemeSettings {
+            preset: "neon".to_string(),
+            accent_color: "#ABC".to_string(),
+            corner_radius: 100,
+        };
+        settings.validate();
+        assert_eq!(settings.preset(), ThemePreset::Custom);
+        assert_eq!(settings.accent_color, "#aabbcc");
+        assert_eq!(settings.corner_radius, MAX_CORNER_RADIUS);
+
+        settings.accent_color = "blue".to_string();
+        settings.validate();
+        assert_eq!(settings.accent_color, DEFAULT_ACCENT_COLOR);
+
+        let theme = Theme::new(&settings, 0.7, 0.7);
+        assert_eq!(theme.accent_hover_color, "#1a86d8");
+    }
+}
@@ -0,0 +1,225 @@
+//! Thumbnail Manager
+//! Generates image previews at 1x/2x/3x resolution for the history list and
+//! caches them on disk, so the webview loads a file sized for the active
+//! monitor's scale factor through the asset protocol instead of decoding the
+//! full base64 image. New images get their thumbnail in the background as
+//! soon as they are copied, and older ones once at startup, so the list
+//! rarely waits on a decode.
+
+use crate::clipboard_manager::{ClipboardContent, ClipboardItem};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use image::ImageFormat;
+use std::fs;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+// --- Constants ---
+
+const THUMBNAIL_CACHE_DIR: &str = "win11-clipboard-history/thumbnails";
+
+/// Size of the preview box in CSS pixels (matches the history item layout)
+const THUMBNAIL_BASE_WIDTH: u32 = 320;
+const THUMBNAIL_BASE_HEIGHT: u32 = 96;
+
+/// Highest scale bucket generated
+const MAX_SCALE: u32 = 3;
+
+/// Half-written files older than this were left by a crash
+const STALE_TEMP_AGE: Duration = Duration::from_secs(60);
+
+/// Maps a fractional scale factor (1.25, 1.5, 2.0...) to the 1x/2x/3x bucket
+/// that is at least as sharp
+pub fn scale_bucket(scale_factor: f64) -> u32 {
+    if !scale_factor.is_finite() || scale_factor <= 1.0 {
+        return 1;
+    }
+    (scale_factor.ceil() as u32).min(MAX_SCALE)
+}
+
+fn cache_dir() -> Result<PathBuf, String> {
+    let dir = dirs::cache_dir()
+        .ok_or("Failed to resolve system cache directory")?
+        .join(THUMBNAIL_CACHE_DIR);
+
+    if !dir.exists() {
+        fs::create_dir_all(&dir).map_err(|e| format!("Failed to create cache dir: {}", e))?;
+    }
+    Ok(dir)
+}
+
+/// Content hash keeps names stable across restarts; fall back to the id for old entries
+fn thumbnail_key(item: &ClipboardItem) -> String {
+    item.extract_image_hash()
+        .map(|hash| hash.to_string())
+        .unwrap_or_else(|| item.id.clone())
+}
+
+/// The key a cached file was written under, from a "<key>@<n>x.png" name
+fn key_of_file(name: &str) -> Option<&str> {
+    let (key, scale) = name.strip_suffix(".png")?.rsplit_once('@')?;
+    scale.strip_suffix('x')?.parse::<u32>().ok().map(|_| key)
+}
+
+fn thumbnail_path(item: &ClipboardItem, scale_factor: f64) -> Result<PathBuf, String> {
+    let scale = scale_bucket(scale_factor);
+    Ok(cache_dir()?.join(format!("{}@{}x.png", thumbnail_key(item), scale)))
+}
+
+/// The thumbnail of an image item at the given scale if it was already
+/// generated, without decoding anything
+pub fn cached_thumbnail_path(item: &ClipboardItem, scale_factor: f64) -> Option<PathBuf> {
+    if !matches!(item.content, ClipboardContent::Image { .. }) {
+        return None;
+    }
+    thumbnail_path(item, scale_factor)
+        .ok()
+        .filter(|path| path.exists())
+}
+
+/// Returns the path of a thumbnail for an image item at the given scale,
+/// generating it on first use. Images already smaller than the target box
+/// are written as-is rather than upscaled.
+pub fn get_thumbnail_path(item: &ClipboardItem, scale_factor: f64) -> Result<PathBuf, String> {
+    let ClipboardContent::Image { base64, .. } = &item.content else {
+        return Err("Item is not an image".to_string());
+    };
+
+    let scale = scale_bucket(scale_factor);
+    let path = thumbnail_path(item, scale_factor)?;
+
+    if path.exists() {
+        return Ok(path);
+    }
+
+    let bytes = BASE64
+        .decode(base64)
+        .map_err(|e| format!("Invalid image data: {}", e))?;
+    let image = image::load_from_memory(&bytes).map_err(|e| format!("Decode failed: {}", e))?;
+
+    let max_width = THUMBNAIL_BASE_WIDTH * scale;
+    let max_height = THUMBNAIL_BASE_HEIGHT * scale;
+
+    let fits = image.width() <= max_width && image.height() <= max_height;
+    // Compressed history images are WebP or JPEG; thumbnails are always PNG
+    let png = if fits && image::guess_format(&bytes).is_ok_and(|format| format == ImageFormat::Png)
+    {
+        bytes
+    } else {
+        let thumbnail = if fits {
+            image
+        } else {
+            image.thumbnail(max_width, max_height)
+        };
+        let mut png = Vec::new();
+        thumbnail
+            .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+            .map_err(|e| format!("Failed to encode thumbnail: {}", e))?;
+        png
+    };
+    write_atomically(&path, &png).map_err(|e| format!("Failed to write thumbnail: {}", e))?;
+
+    Ok(path)
+}
+
+/// Writes a temp file next to `path` and renames it into place, so the
+/// existence check in `cached_thumbnail_path` never sees a partial file.
+/// Concurrent writers of the same thumbnail each get their own temp file.
+fn write_atomically(path: &Path, bytes: &[u8]) -> std::io::Result<()> {
+    static NEXT_TEMP: AtomicU64 = AtomicU64::new(0);
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let temp = path.with_file_name(format!(
+        ".{}.{}-{}.tmp",
+        name,
+        std::process::id(),
+        NEXT_TEMP.fetch_add(1, Ordering::Relaxed)
+    ));
+    fs::write(&temp, bytes)
+        .and_then(|_| fs::rename(&temp, path))
+        .inspect_err(|_| {
+            let _ = fs::remove_file(&temp);
+        })
+}
+
+fn is_stale_temp(entry: &fs::DirEntry) -> bool {
+    entry.file_name().to_string_lossy().ends_with(".tmp")
+        && entry
+            .metadata()
+            .and_then(|metadata| metadata.modified())
+            .is_ok_and(|modified| modified.elapsed().unwrap_or_default() > STALE_TEMP_AGE)
+}
+
+/// Names the thumbnails of `history` are cached under, for `prune_cache`
+pub fn cache_keys(history: &[ClipboardItem]) -> Vec<String> {
+    history
+        .iter()
+        .filter(|item| matches!(item.content, ClipboardContent::Image { .. }))
+        .map(thumbnail_key)
+        .collect()
+}
+
+/// Deletes cached thumbnails whose key is not in `keep`, and temp files left
+/// by a crash, returning how many files went
+pub fn prune_cache(keep: &[String]) -> usize {
+    let Ok(dir) = cache_dir() else {
+        return 0;
+    };
+    let Ok(entries) = fs::read_dir(&dir) else {
+        return 0;
+    };
+
+    entries
+        .flatten()
+        .filter(|entry| {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            key_of_file(&name).is_some_and(|key| !keep.iter().any(|kept| kept == key))
+                || is_stale_temp(entry)
+        })
+        .filter(|entry| fs::remove_file(entry.path()).is_ok())
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scale_bucket_rounds_up_and_clamps() {
+        assert_eq!(scale_bucket(1.0), 1);
+        assert_eq!(scale_bucket(0.5), 1);
+        assert_eq!(scale_bucket(1.25), 2);
+        assert_eq!(scale_bucket(2.0), 2);
+        assert_eq!(scale_bucket(2.5), 3);
+        assert_eq!(scale_bucket(4.0), 3);
+        assert_eq!(scale_bucket(f64::NAN), 1);
+    }
+
+    #[test]
+    fn test_key_of_file() {
+        assert_eq!(key_of_file("1234567890@2x.png"), Some("1234567890"));
+        assert_eq!(
+            key_of_file("6f1c0e9a-uuid-like@1x.png"),
+            Some("6f1c0e9a-uuid-like")
+        );
+        assert_eq!(key_of_file("1234567890.png"), None);
+        assert_eq!(key_of_file("1234567890@big.png"), None);
+        assert_eq!(key_of_file("notes.txt"), None);
+        assert_eq!(key_of_file(".1234567890@2x.png.42-0.tmp"), None);
+    }
+
+    #[test]
+    fn test_write_atomically_replaces_without_leftovers() {
+        let dir = std::env::temp_dir().join(format!("thumbnail-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("1234567890@1x.png");
+
+        write_atomically(&path, b"first").unwrap();
+        write_atomically(&path, b"second").unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"second");
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
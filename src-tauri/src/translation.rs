@@ -0,0 +1,268 @@
+//! Translation
+//! The "Translate" action: a text item is sent to the translation service
+//! picked in the settings, a LibreTranslate server (public or self-hosted)
+//! or DeepL with the user's API key, and the translation is put at the top
+//! of history as a new item. Nothing is sent until the action is used.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::time::Duration;
+
+/// Requests taking longer than this fail
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+const DEFAULT_LIBRETRANSLATE_URL: &str = "https://libretranslate.com";
+const DEFAULT_TARGET_LANGUAGE: &str = "en";
+
+/// DeepL keys of the free plan end with this and use their own host
+const DEEPL_FREE_KEY_SUFFIX: &str = ":fx";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TranslationBackend {
+    LibreTranslate,
+    DeepL,
+}
+
+impl TranslationBackend {
+    pub const ALL: [TranslationBackend; 2] = [Self::LibreTranslate, Self::DeepL];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::LibreTranslate => "libretranslate",
+            Self::DeepL => "deepl",
+        }
+    }
+
+    pub fn from_setting(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|backend| backend.as_str() == value)
+    }
+}
+
+/// The translation section of the user settings
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TranslationSettings {
+    /// "libretranslate" or "deepl"
+    #[serde(default = "default_backend")]
+    pub backend: String,
+    /// Base URL of the LibreTranslate server
+    #[serde(default = "default_libretranslate_url")]
+    pub libretranslate_url: String,
+    /// Only needed by servers that ask for one
+    #[serde(default)]
+    pub libretranslate_api_key: String,
+    #[serde(default)]
+    pub deepl_api_key: String,
+    /// Language code to translate into, e.g. "en" or "pt"
+    #[serde(default = "default_target_language")]
+    pub target_language: String,
+}
+
+fn default_backend() -> String {
+    TranslationBackend::LibreTranslate.as_str().to_string()
+}
+
+fn default_libretranslate_url() -> String {
+    DEFAULT_LIBRETRANSLATE_URL.to_string()
+}
+
+fn default_target_language() -> String {
+    DEFAULT_TARGET_LANGUAGE.to_string()
+}
+
+impl Default for TranslationSettings {
+    fn default() -> Self {
+        Self {
+            backend: default_backend(),
+            libretranslate_url: default_libretranslate_url(),
+            libretranslate_api_key: String::new(),
+            deepl_api_key: String::new(),
+            target_language: default_target_language(),
+        }
+    }
+}
+
+impl TranslationSettings {
+    pub fn backend(&self) -> TranslationBackend {
+        TranslationBackend::from_setting(&self.backend)
+            .unwrap_or(TranslationBackend::LibreTranslate)
+    }
+
+    /// Falls back to the defaults for unknown backends and blank fields, and
+    /// trims keys and the server URL
+    pub fn validate(&mut self) {
+        if TranslationBackend::from_setting(&self.backend).is_none() {
+            self.backend = default_backend();
+        }
+        self.libretranslate_url = self
+            .libretranslate_url
+            .trim()
+            .trim_end_matches('/')
+            .to_string();
+        if self.libretranslate_url.is_empty() {
+            self.libretranslate_url = default_libretranslate_url();
+        }
+        self.libretranslate_api_key = self.libretranslate_api_key.trim().to_string();
+        self.deepl_api_key = self.deepl_api_key.trim().to_string();
+        self.target_language = self.target_language.trim().to_lowercase();
+        if self.target_language.is_empty() {
+            self.target_language = default_target_language();
+        }
+    }
+
+    /// Whether the action can run: DeepL needs a key
+    pub fn is_configured(&self) -> bool {
+        match self.backend() {
+            TranslationBackend::LibreTranslate => !self.libretranslate_url.is_empty(),
+            TranslationBackend::DeepL => !self.deepl_api_key.is_empty(),
+        }
+    }
+}
+
+/// What is posted to the service
+#[derive(Debug, Clone, PartialEq)]
+pub struct TranslationRequest {
+    pub url: String,
+    /// Value of the Authorization header, if the service takes the key there
+    pub authorization: Option<String>,
+    pub body: Value,
+}
+
+pub fn build_request(settings: &TranslationSettings, text: &str) -> TranslationRequest {
+    match settings.backend() {
+        TranslationBackend::LibreTranslate => {
+            let mut body = json!({
+                "q": text,
+                "source": "auto",
+                "target": settings.target_language,
+                "format": "text",
+            });
+            if !settings.libretranslate_api_key.is_empty() {
+                body["api_key"] = json!(settings.libretranslate_api_key);
+            }
+            TranslationRequest {
+                url: format!("{}/translate", settings.libretranslate_url),
+                authorization: None,
+                body,
+            }
+        }
+        TranslationBackend::DeepL => {
+            let host = if settings.deepl_api_key.ends_with(DEEPL_FREE_KEY_SUFFIX) {
+                "api-free.deepl.com"
+            } else {
+                "api.deepl.com"
+            };
+            TranslationRequest {
+                url: format!("https://{}/v2/translate", host),
+                authorization: Some(format!("DeepL-Auth-Key {}", settings.deepl_api_key)),
+                body: json!({
+                    "text": [text],
+                    "target_lang": settings.target_language.to_uppercase(),
+                }),
+            }
+        }
+    }
+}
+
+/// The translated text in a response, or the service's error message
+pub fn parse_response(
+    backend: TranslationBackend,
+    status: u16,
+    body: &str,
+) -> Result<String, String> {
+    let value: Value = serde_json::from_str(body).unwrap_or_default();
+    let translated = match backend {
+        TranslationBackend::LibreTranslate => value["translatedText"].as_str(),
+        TranslationBackend::DeepL => value["translations"][0]["text"].as_str(),
+    };
+    if let Some(text) = translated {
+        return Ok(text.to_string());
+    }
+    match value["error"]
+        .as_str()
+        .or_else(|| value["message"].as_str())
+    {
+        Some(message) => Err(format!("Translation failed: {}", message)),
+        None if !(200..300).contains(&status) => Err(format!("HTTP Error: {}", status)),
+        None => Err("Unexpected response from the service".to_string()),
+    }
+}
+
+/// Translates `text` with the configured service. Blocks on the request.
+pub fn translate(settings: &TranslationSettings, text: &str) -> Result<String, String> {
+    if !settings.is_configured() {
+        return Err("Set up a translation service in the settings first".to_string());
+    }
+    let request = build_request(settings, text);
+
+    let client = reqwest::blocking::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Client build error: {}", e))?;
+    let mut post = client
+        .post(&request.url)
+        .header("Content-Type", "application/json")
+        .body(request.body.to_string());
+    if let Some(authorization) = &request.authorization {
+        post = post.header("Authorization", authorization);
+    }
+    let response = post
+        .send()
+        .map_err(|e| format!("Network request failed: {}", e))?;
+
+    let status = response.status().as_u16();
+    let body = response
+        .text()
+        .map_err(|e| format!("Failed to read response: {}", e))?;
+    parse_response(settings.backend(), status, &body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_requests_and_responses() {
+        let mut settings = TranslationSettings {
+            libretranslate_url: " https://translate.example.org/ ".to_string(),
+            target_language: "PT".to_string(),
+            ..Default::default()
+        };
+        settings.validate();
+        let request = build_request(&settings, "hello");
+        assert_eq!(request.url, "https://translate.example.org/translate");
+        assert_eq!(request.body["target"], "pt");
+        assert!(request.body.get("api_key").is_none());
+        assert_eq!(
+            parse_response(settings.backend(), 200, r#"{"translatedText":"olá"}"#).unwrap(),
+            "olá"
+        );
+
+        settings.backend = "deepl".to_string();
+        assert!(!settings.is_configured());
+        settings.deepl_api_key = "abc:fx".to_string();
+        let request = build_request(&settings, "hello");
+        assert_eq!(request.url, "https://api-free.deepl.com/v2/translate");
+        assert_eq!(
+            request.authorization.as_deref(),
+            Some("DeepL-Auth-Key abc:fx")
+        );
+        assert_eq!(request.body["target_lang"], "PT");
+        assert_eq!(
+            parse_response(
+                TranslationBackend::DeepL,
+                200,
+                r#"{"translations":[{"detected_source_language":"EN","text":"olá"}]}"#
+            )
+            .unwrap(),
+            "olá"
+        );
+        let error = |status, body| parse_response(TranslationBackend::DeepL, status, body);
+        assert_eq!(
+            error(403, r#"{"message":"Wrong key"}"#).unwrap_err(),
+            "Translation failed: Wrong key"
+        );
+        assert_eq!(error(456, "").unwrap_err(), "HTTP Error: 456");
+    }
+}
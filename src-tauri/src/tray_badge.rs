@@ -0,0 +1,86 @@
+//! Tray Badges
+//! Marks the tray icon with a colored dot for states worth noticing without
+//! opening the window: capture paused, and pinned items failing to sync to
+//! their git mirror.
+
+/// A state shown on the tray icon
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrayBadge {
+    Paused,
+    SyncError,
+}
+
+impl TrayBadge {
+    /// The badge for the current state, errors first since they need action
+    pub fn current(paused: bool, sync_failed: bool) -> Option<Self> {
+        if sync_failed {
+            Some(Self::SyncError)
+        } else if paused {
+            Some(Self::Paused)
+        } else {
+            None
+        }
+    }
+
+    fn color(self) -> [u8; 4] {
+        match self {
+            Self::Paused => [0xF5, 0x9E, 0x0B, 0xFF],
+            Self::SyncError => [0xDC, 0x26, 0x26, 0xFF],
+        }
+    }
+}
+
+/// Badge diameter as a share of the icon width
+const BADGE_SIZE: f32 = 0.45;
+/// Width of the transparent ring cut around the dot, as a share of its radius,
+/// so the dot stands out on both the icon and the panel
+const BADGE_RING: f32 = 0.3;
+
+/// Draws the badge in the bottom-right corner of an RGBA icon
+pub fn draw_badge(rgba: &mut [u8], width: u32, height: u32, badge: TrayBadge) {
+    if rgba.len() < (width as usize) * (height as usize) * 4 {
+        return;
+    }
+    let radius = width.min(height) as f32 * BADGE_SIZE / 2.0;
+    let ring = radius * (1.0 + BADGE_RING);
+    let (cx, cy) = (width as f32 - ring, height as f32 - ring);
+
+    for y in 0..height {
+        for x in 0..width {
+            let distance = (x as f32 + 0.5 - cx).hypot(y as f32 + 0.5 - cy);
+            if distance > ring {
+                continue;
+            }
+            let i = ((y * width + x) * 4) as usize;
+            let pixel = &mut rgba[i..i + 4];
+            if distance <= radius {
+                pixel.copy_from_slice(&badge.color());
+            } else {
+                pixel[3] = 0;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_draw_badge_in_corner() {
+        let (width, height) = (32, 32);
+        let mut rgba = vec![0x80; width * height * 4];
+        draw_badge(&mut rgba, 32, 32, TrayBadge::SyncError);
+
+        let pixel = |x: usize, y: usize| &rgba[(y * width + x) * 4..(y * width + x) * 4 + 4];
+        // Icon untouched away from the corner
+        assert_eq!(pixel(2, 2), [0x80; 4]);
+        // Dot near the corner, cut out ring around it
+        assert_eq!(pixel(25, 25), TrayBadge::SyncError.color());
+        assert_eq!(pixel(14, 22)[3], 0);
+
+        assert_eq!(TrayBadge::current(true, true), Some(TrayBadge::SyncError));
+        assert_eq!(TrayBadge::current(true, false), Some(TrayBadge::Paused));
+        assert_eq!(TrayBadge::current(false, false), None);
+    }
+}
@@ -0,0 +1,189 @@
+//! Unified Search
+//! One search box for everything the panel can paste: history items,
+//! snippets (pinned or filed items), emoji and favorite GIFs. Each source
+//! is searched on its own, so the caller locks one manager at a time, and
+//! the results are tagged with their kind for the panel to group them.
+
+use crate::clipboard_manager::ClipboardItem;
+use crate::emoji_dataset::EmojiDataset;
+use crate::emoji_manager::CustomEmoji;
+use crate::gif_manager::FavoriteGif;
+use serde::Serialize;
+
+/// Results of each kind, so a busy history doesn't crowd out the rest
+pub const RESULTS_PER_KIND: usize = 8;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SearchResult {
+    History {
+        item: ClipboardItem,
+    },
+    /// A pinned item, or one filed into a collection
+    Snippet {
+        item: ClipboardItem,
+    },
+    /// From the installed emoji list
+    Emoji {
+        char: String,
+        name: String,
+    },
+    CustomEmoji {
+        emoji: CustomEmoji,
+    },
+    Gif {
+        gif: FavoriteGif,
+    },
+}
+
+/// The query as it is matched, ignoring case; none when it is blank
+fn normalize(query: &str) -> Option<String> {
+    let query = query.trim().to_lowercase();
+    (!query.is_empty()).then_some(query)
+}
+
+/// Items whose text or tags contain `query`, snippets before the rest
+pub fn search_items(items: &[ClipboardItem], query: &str, limit: usize) -> Vec<SearchResult> {
+    let Some(query) = normalize(query) else {
+        return Vec::new();
+    };
+    let matches = |item: &&ClipboardItem| {
+        item.text()
+            .is_some_and(|text| text.to_lowercase().contains(&query))
+            || item.tags.iter().any(|tag| tag.contains(&query))
+    };
+    let is_snippet = |item: &&ClipboardItem| item.pinned || item.collection.is_some();
+
+    let snippets = items
+        .iter()
+        .filter(is_snippet)
+        .filter(matches)
+        .take(limit)
+        .map(|item| SearchResult::Snippet { item: item.clone() });
+    let history = items
+        .iter()
+        .filter(|item| !is_snippet(item))
+        .filter(matches)
+        .take(limit)
+        .map(|item| SearchResult::History { item: item.clone() });
+    snippets.chain(history).collect()
+}
+
+/// Custom emoji by shortcode, then the installed list by name and keyword.
+/// The built-in list ships with the panel, which searches it itself.
+pub fn search_emojis(
+    custom: &[CustomEmoji],
+    dataset: Option<&EmojiDataset>,
+    query: &str,
+    limit: usize,
+) -> Vec<SearchResult> {
+    let Some(query) = normalize(query) else {
+        return Vec::new();
+    };
+    let shortcode = query.trim_matches(':');
+
+    let custom = custom
+        .iter()
+        .filter(|emoji| emoji.shortcode.to_lowercase().contains(shortcode))
+        .map(|emoji| SearchResult::CustomEmoji {
+            emoji: emoji.clone(),
+        });
+    let listed = dataset
+        .into_iter()
+        .flat_map(|dataset| &dataset.emojis)
+        .filter(|emoji| {
+            emoji.name.to_lowercase().contains(&query)
+                || emoji
+                    .keywords
+                    .iter()
+                    .any(|keyword| keyword.to_lowercase().contains(&query))
+        })
+        .map(|emoji| SearchResult::Emoji {
+            char: emoji.char.clone(),
+            name: emoji.name.clone(),
+        });
+    custom.chain(listed).take(limit).collect()
+}
+
+/// Favorite GIFs by title; other GIFs need a request to the provider
+pub fn search_gifs(favorites: &[FavoriteGif], query: &str, limit: usize) -> Vec<SearchResult> {
+    let Some(query) = normalize(query) else {
+        return Vec::new();
+    };
+    favorites
+        .iter()
+        .filter(|gif| gif.gif.title.to_lowercase().contains(&query))
+        .take(limit)
+        .map(|gif| SearchResult::Gif { gif: gif.clone() })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::emoji_dataset::DatasetEmoji;
+    use crate::gif_provider::Gif;
+    use std::path::PathBuf;
+
+    fn kinds(results: &[SearchResult]) -> Vec<String> {
+        results
+            .iter()
+            .map(|result| {
+                serde_json::to_value(result).unwrap()["kind"]
+                    .as_str()
+                    .unwrap()
+                    .to_string()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_search_each_source() {
+        let mut pinned = ClipboardItem::new_text("Deploy checklist".to_string());
+        pinned.pinned = true;
+        let mut tagged = ClipboardItem::new_text("kubectl apply".to_string());
+        tagged.tags = vec!["deploy".to_string()];
+        let items = vec![
+            ClipboardItem::new_text("unrelated".to_string()),
+            tagged,
+            pinned,
+        ];
+        let results = search_items(&items, " DEPLOY ", RESULTS_PER_KIND);
+        assert_eq!(kinds(&results), ["snippet", "history"]);
+        assert!(search_items(&items, "  ", RESULTS_PER_KIND).is_empty());
+
+        let custom = vec![CustomEmoji {
+            shortcode: "partyparrot".to_string(),
+            text: String::new(),
+            image: Some("iVBORw0KGgo=".to_string()),
+        }];
+        let dataset = EmojiDataset {
+            version: "15.1".to_string(),
+            emojis: vec![DatasetEmoji {
+                char: "🪅".to_string(),
+                name: "piñata".to_string(),
+                keywords: vec!["party".to_string()],
+                category: None,
+            }],
+        };
+        let results = search_emojis(&custom, Some(&dataset), "party", RESULTS_PER_KIND);
+        assert_eq!(kinds(&results), ["custom_emoji", "emoji"]);
+        assert_eq!(search_emojis(&custom, None, ":parrot:", 1).len(), 1);
+
+        let favorites = vec![FavoriteGif {
+            gif: Gif {
+                id: "1".to_string(),
+                title: "Party Time".to_string(),
+                preview_url: String::new(),
+                full_url: String::new(),
+                webp_url: None,
+                width: 100,
+                height: 100,
+            },
+            file: PathBuf::from("1.gif"),
+            preview_file: PathBuf::from("1-preview.gif"),
+        }];
+        assert_eq!(kinds(&search_gifs(&favorites, "party", 8)), ["gif"]);
+        assert!(search_gifs(&favorites, "cat", 8).is_empty());
+    }
+}
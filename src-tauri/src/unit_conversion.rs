@@ -0,0 +1,636 @@
+//! Unit Conversion
+//! Finds quantities such as "72°F", "13 mi" or "$49.99" in text items and
+//! converts them into the units of the user: metric or imperial, and one
+//! currency, both taken from the locale (LC_MEASUREMENT, LC_MONETARY) unless
+//! set in the settings. Quantities already in those units are left alone.
+//! Exchange rates come from the ECB reference rates published by
+//! frankfurter.app, fetched only when a text holds a foreign amount and
+//! kept for a few hours.
+
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Quantities looked at per text
+const MAX_QUANTITIES: usize = 5;
+
+/// Exchange rates are fetched again after this long
+const RATES_MAX_AGE: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// A failed fetch is tried again after this long
+const RATES_RETRY_AFTER: Duration = Duration::from_secs(10 * 60);
+
+const RATES_TIMEOUT: Duration = Duration::from_secs(10);
+
+const RATES_URL: &str = "https://api.frankfurter.app/latest";
+
+/// Currencies with ECB reference rates, by ISO code
+const CURRENCIES: [&str; 16] = [
+    "USD", "EUR", "GBP", "JPY", "BRL", "CAD", "AUD", "CHF", "CNY", "INR", "MXN", "SEK", "NOK",
+    "DKK", "PLN", "NZD",
+];
+
+/// Currency symbols written before the amount, longest first
+const CURRENCY_SYMBOLS: [(&str, &str); 9] = [
+    ("US$", "USD"),
+    ("R$", "BRL"),
+    ("C$", "CAD"),
+    ("A$", "AUD"),
+    ("$", "USD"),
+    ("€", "EUR"),
+    ("£", "GBP"),
+    ("¥", "JPY"),
+    ("₹", "INR"),
+];
+
+/// Countries whose currency is the euro, by ISO 3166 code
+const EURO_COUNTRIES: [&str; 20] = [
+    "AT", "BE", "CY", "DE", "EE", "ES", "FI", "FR", "GR", "HR", "IE", "IT", "LT", "LU", "LV", "MT",
+    "NL", "PT", "SI", "SK",
+];
+
+/// Countries that measure in imperial units, by ISO 3166 code
+const IMPERIAL_COUNTRIES: [&str; 3] = ["US", "LR", "MM"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnitSystem {
+    Metric,
+    Imperial,
+}
+
+impl UnitSystem {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Metric => "metric",
+            Self::Imperial => "imperial",
+        }
+    }
+
+    /// "metric", "imperial", or "system" for the locale's
+    pub fn from_setting(value: &str) -> Option<Self> {
+        match value {
+            "metric" => Some(Self::Metric),
+            "imperial" => Some(Self::Imperial),
+            "system" => Some(Self::from_locale(&locale("LC_MEASUREMENT"))),
+            _ => None,
+        }
+    }
+
+    pub fn from_locale(locale: &str) -> Self {
+        match country(locale) {
+            Some(country) if IMPERIAL_COUNTRIES.contains(&country.as_str()) => Self::Imperial,
+            _ => Self::Metric,
+        }
+    }
+}
+
+/// The currency of a locale such as "pt_BR.UTF-8", US dollars when unknown
+pub fn currency_from_locale(locale: &str) -> &'static str {
+    let Some(country) = country(locale) else {
+        return "USD";
+    };
+    if EURO_COUNTRIES.contains(&country.as_str()) {
+        return "EUR";
+    }
+    match country.as_str() {
+        "GB" => "GBP",
+        "JP" => "JPY",
+        "BR" => "BRL",
+        "CA" => "CAD",
+        "AU" => "AUD",
+        "CH" => "CHF",
+        "CN" => "CNY",
+        "IN" => "INR",
+        "MX" => "MXN",
+        "SE" => "SEK",
+        "NO" => "NOK",
+        "DK" => "DKK",
+        "PL" => "PLN",
+        "NZ" => "NZD",
+        _ => "USD",
+    }
+}
+
+/// `code` uppercased, if it is a currency with exchange rates
+pub fn normalize_currency(code: &str) -> Option<String> {
+    let code = code.trim().to_uppercase();
+    CURRENCIES.contains(&code.as_str()).then_some(code)
+}
+
+/// The session's locale for `category` (LC_ALL, the category, then LANG)
+pub fn locale(category: &str) -> String {
+    ["LC_ALL", category, "LANG"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|locale| !locale.is_empty())
+        .unwrap_or_default()
+}
+
+/// "BR" of "pt_BR.UTF-8@euro"
+fn country(locale: &str) -> Option<String> {
+    let locale = locale.split(['.', '@']).next()?;
+    let (_, country) = locale.split_once(['_', '-'])?;
+    (country.len() == 2).then(|| country.to_uppercase())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Unit {
+    Celsius,
+    Fahrenheit,
+    Kilometer,
+    Mile,
+    Meter,
+    Foot,
+    Yard,
+    Centimeter,
+    Inch,
+    Kilogram,
+    Pound,
+    Gram,
+    Ounce,
+    Liter,
+    Gallon,
+    Milliliter,
+    FluidOunce,
+    KilometerPerHour,
+    MilePerHour,
+}
+
+/// Unit names written after the amount, longest first so "km/h" is not
+/// read as "km"
+const UNIT_NAMES: [(&str, Unit); 44] = [
+    ("kilometres", Unit::Kilometer),
+    ("kilometers", Unit::Kilometer),
+    ("kilograms", Unit::Kilogram),
+    ("gallons", Unit::Gallon),
+    ("ounces", Unit::Ounce),
+    ("pounds", Unit::Pound),
+    ("meters", Unit::Meter),
+    ("metres", Unit::Meter),
+    ("inches", Unit::Inch),
+    ("liters", Unit::Liter),
+    ("litres", Unit::Liter),
+    ("fl oz", Unit::FluidOunce),
+    ("grams", Unit::Gram),
+    ("miles", Unit::Mile),
+    ("yards", Unit::Yard),
+    ("km/h", Unit::KilometerPerHour),
+    ("feet", Unit::Foot),
+    ("foot", Unit::Foot),
+    ("inch", Unit::Inch),
+    ("mile", Unit::Mile),
+    ("°F", Unit::Fahrenheit),
+    ("ºF", Unit::Fahrenheit),
+    ("°C", Unit::Celsius),
+    ("ºC", Unit::Celsius),
+    ("kph", Unit::KilometerPerHour),
+    ("mph", Unit::MilePerHour),
+    ("lbs", Unit::Pound),
+    ("gal", Unit::Gallon),
+    ("km", Unit::Kilometer),
+    ("mi", Unit::Mile),
+    ("ft", Unit::Foot),
+    ("yd", Unit::Yard),
+    ("cm", Unit::Centimeter),
+    ("kg", Unit::Kilogram),
+    ("lb", Unit::Pound),
+    ("oz", Unit::Ounce),
+    ("ml", Unit::Milliliter),
+    ("mL", Unit::Milliliter),
+    ("m", Unit::Meter),
+    ("g", Unit::Gram),
+    ("l", Unit::Liter),
+    ("L", Unit::Liter),
+    ("\"", Unit::Inch),
+    ("'", Unit::Foot),
+];
+
+impl Unit {
+    fn symbol(self) -> &'static str {
+        match self {
+            Self::Celsius => "°C",
+            Self::Fahrenheit => "°F",
+            Self::Kilometer => "km",
+            Self::Mile => "mi",
+            Self::Meter => "m",
+            Self::Foot => "ft",
+            Self::Yard => "yd",
+            Self::Centimeter => "cm",
+            Self::Inch => "in",
+            Self::Kilogram => "kg",
+            Self::Pound => "lb",
+            Self::Gram => "g",
+            Self::Ounce => "oz",
+            Self::Liter => "L",
+            Self::Gallon => "gal",
+            Self::Milliliter => "mL",
+            Self::FluidOunce => "fl oz",
+            Self::KilometerPerHour => "km/h",
+            Self::MilePerHour => "mph",
+        }
+    }
+
+    fn system(self) -> UnitSystem {
+        match self {
+            Self::Celsius
+            | Self::Kilometer
+            | Self::Meter
+            | Self::Centimeter
+            | Self::Kilogram
+            | Self::Gram
+            | Self::Liter
+            | Self::Milliliter
+            | Self::KilometerPerHour => UnitSystem::Metric,
+            _ => UnitSystem::Imperial,
+        }
+    }
+
+    /// The unit of the other system and the value in it
+    fn convert(self, value: f64) -> (Unit, f64) {
+        match self {
+            Self::Celsius => (Self::Fahrenheit, value * 9.0 / 5.0 + 32.0),
+            Self::Fahrenheit => (Self::Celsius, (value - 32.0) * 5.0 / 9.0),
+            Self::Kilometer => (Self::Mile, value / 1.609_344),
+            Self::Mile => (Self::Kilometer, value * 1.609_344),
+            Self::Meter => (Self::Foot, value / 0.3048),
+            Self::Foot => (Self::Meter, value * 0.3048),
+            Self::Yard => (Self::Meter, value * 0.9144),
+            Self::Centimeter => (Self::Inch, value / 2.54),
+            Self::Inch => (Self::Centimeter, value * 2.54),
+            Self::Kilogram => (Self::Pound, value / 0.453_592_37),
+            Self::Pound => (Self::Kilogram, value * 0.453_592_37),
+            Self::Gram => (Self::Ounce, value / 28.349_523),
+            Self::Ounce => (Self::Gram, value * 28.349_523),
+            // US gallons and fluid ounces
+            Self::Liter => (Self::Gallon, value / 3.785_411_8),
+            Self::Gallon => (Self::Liter, value * 3.785_411_8),
+            Self::Milliliter => (Self::FluidOunce, value / 29.573_53),
+            Self::FluidOunce => (Self::Milliliter, value * 29.573_53),
+            Self::KilometerPerHour => (Self::MilePerHour, value / 1.609_344),
+            Self::MilePerHour => (Self::KilometerPerHour, value * 1.609_344),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Measure {
+    Unit(Unit),
+    /// ISO code
+    Currency(String),
+}
+
+/// A quantity found in a text
+#[derive(Debug, Clone, PartialEq)]
+pub struct Quantity {
+    /// As written, e.g. "$49.99"
+    pub text: String,
+    value: f64,
+    measure: Measure,
+}
+
+impl Quantity {
+    pub fn currency(&self) -> Option<&str> {
+        match &self.measure {
+            Measure::Currency(code) => Some(code),
+            Measure::Unit(_) => None,
+        }
+    }
+}
+
+/// A quantity and what it comes to in the user's units
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Conversion {
+    pub original: String,
+    pub converted: String,
+}
+
+/// The number at the start of `text` and its length in bytes. "1,234.5",
+/// "1.234,5" and "49,99" are all read; a lone separator followed by three
+/// digits groups thousands.
+fn parse_number(text: &str) -> Option<(f64, usize)> {
+    let mut end = 0;
+    for (i, c) in text.char_indices() {
+        let next_is_digit = text[i + c.len_utf8()..]
+            .chars()
+            .next()
+            .is_some_and(|next| next.is_ascii_digit());
+        if c.is_ascii_digit() || ((c == '.' || c == ',') && next_is_digit && i > 0) {
+            end = i + c.len_utf8();
+        } else {
+            break;
+        }
+    }
+    let number = &text[..end];
+    if number.is_empty() {
+        return None;
+    }
+
+    let decimal = match (number.rfind('.'), number.rfind(',')) {
+        (Some(dot), Some(comma)) => Some(dot.max(comma)),
+        (Some(sep), None) | (None, Some(sep)) => {
+            let separator = number.as_bytes()[sep];
+            let groups_thousands = number.len() - sep - 1 == 3
+                && (separator == b',' || number.matches('.').count() > 1);
+            (!groups_thousands).then_some(sep)
+        }
+        (None, None) => None,
+    };
+    let normalized: String = number
+        .char_indices()
+        .filter_map(|(i, c)| match c {
+            '.' | ',' if Some(i) == decimal => Some('.'),
+            '.' | ',' => None,
+            c => Some(c),
+        })
+        .collect();
+    Some((normalized.parse().ok()?, end))
+}
+
+/// Whether `text` starts with `word` ending there
+fn starts_with_word(text: &str, word: &str) -> bool {
+    text.starts_with(word)
+        && !text[word.len()..]
+            .chars()
+            .next()
+            .is_some_and(char::is_alphanumeric)
+}
+
+/// What follows the amount: a unit or currency code, and its length with
+/// the space before it
+fn measure_after(rest: &str) -> Option<(Measure, usize)> {
+    let (spaces, trimmed) = match rest.strip_prefix(' ') {
+        Some(trimmed) => (1, trimmed),
+        None => (0, rest),
+    };
+    if let Some(code) = CURRENCIES
+        .iter()
+        .find(|code| starts_with_word(trimmed, code))
+    {
+        return Some((Measure::Currency(code.to_string()), spaces + code.len()));
+    }
+    if trimmed.starts_with('€') {
+        return Some((
+            Measure::Currency("EUR".to_string()),
+            spaces + '€'.len_utf8(),
+        ));
+    }
+    // Inch and foot marks go right after the number
+    UNIT_NAMES
+        .iter()
+        .filter(|(name, _)| spaces == 0 || !matches!(*name, "\"" | "'"))
+        .find(|(name, _)| starts_with_word(trimmed, name))
+        .map(|(name, unit)| (Measure::Unit(*unit), spaces + name.len()))
+}
+
+/// Quantities written in `text`, at most `MAX_QUANTITIES`
+pub fn find_quantities(text: &str) -> Vec<Quantity> {
+    let mut quantities = Vec::new();
+    let mut i = 0;
+    while i < text.len() && quantities.len() < MAX_QUANTITIES {
+        let rest = &text[i..];
+        let at_word_start = !text[..i]
+            .chars()
+            .next_back()
+            .is_some_and(|c| c.is_alphanumeric() || c == '.' || c == ',');
+
+        let prefix = CURRENCY_SYMBOLS
+            .iter()
+            .find(|(symbol, _)| rest.starts_with(symbol))
+            .filter(|_| at_word_start);
+        let (number_start, prefix_currency) = match prefix {
+            Some((symbol, code)) => (i + symbol.len(), Some(code)),
+            None => (i, None),
+        };
+        let negative = prefix.is_none() && rest.starts_with('-');
+        let number_start = number_start + usize::from(negative);
+
+        let found = at_word_start
+            .then(|| parse_number(&text[number_start..]))
+            .flatten()
+            .and_then(|(value, len)| {
+                let value = if negative { -value } else { value };
+                let after = number_start + len;
+                match prefix_currency {
+                    Some(code) => Some((value, Measure::Currency(code.to_string()), after)),
+                    None => measure_after(&text[after..])
+                        .map(|(measure, len)| (value, measure, after + len)),
+                }
+            });
+        match found {
+            Some((value, measure, end)) => {
+                quantities.push(Quantity {
+                    text: text[i..end].to_string(),
+                    value,
+                    measure,
+                });
+                i = end;
+            }
+            None => i += rest.chars().next().map_or(1, char::len_utf8),
+        }
+    }
+    quantities
+}
+
+/// `value` with fewer decimals the larger it is, trailing zeros dropped
+fn format_value(value: f64) -> String {
+    let decimals = match value.abs() {
+        v if v >= 100.0 => 0,
+        v if v >= 10.0 => 1,
+        _ => 2,
+    };
+    let formatted = format!("{:.*}", decimals, value);
+    let formatted = if formatted.contains('.') {
+        formatted.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        &formatted
+    };
+    match formatted {
+        "-0" => "0".to_string(),
+        formatted => formatted.to_string(),
+    }
+}
+
+/// Exchange rates, in units of each currency per unit of `base`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExchangeRates {
+    pub base: String,
+    pub rates: HashMap<String, f64>,
+}
+
+impl ExchangeRates {
+    /// `amount` of `currency` in the base currency
+    fn to_base(&self, amount: f64, currency: &str) -> Option<f64> {
+        if currency == self.base {
+            return Some(amount);
+        }
+        let rate = self.rates.get(currency).filter(|rate| **rate > 0.0)?;
+        Some(amount / rate)
+    }
+}
+
+/// What `quantity` comes to in `system` or `currency`; None when it is
+/// already in them, or the rate is missing
+pub fn convert(
+    quantity: &Quantity,
+    system: UnitSystem,
+    currency: &str,
+    rates: Option<&ExchangeRates>,
+) -> Option<Conversion> {
+    let converted = match &quantity.measure {
+        Measure::Unit(unit) if unit.system() != system => {
+            let (target, value) = unit.convert(quantity.value);
+            format!("{} {}", format_value(value), target.symbol())
+        }
+        Measure::Unit(_) => return None,
+        Measure::Currency(code) if code != currency => {
+            let rates = rates.filter(|rates| rates.base == currency)?;
+            let value = rates.to_base(quantity.value, code)?;
+            let decimals = if currency == "JPY" { 0 } else { 2 };
+            format!("{:.*} {}", decimals, value, currency)
+        }
+        Measure::Currency(_) => return None,
+    };
+    Some(Conversion {
+        original: quantity.text.clone(),
+        converted,
+    })
+}
+
+/// Whether converting `quantities` into `currency` needs exchange rates
+pub fn needs_rates(quantities: &[Quantity], currency: &str) -> bool {
+    quantities
+        .iter()
+        .filter_map(Quantity::currency)
+        .any(|code| code != currency)
+}
+
+/// Reads a frankfurter.app response
+pub fn parse_rates(body: &str) -> Result<ExchangeRates, String> {
+    let value: serde_json::Value =
+        serde_json::from_str(body).map_err(|e| format!("Parse error: {}", e))?;
+    let base = value["base"]
+        .as_str()
+        .ok_or("Exchange rates without a base")?
+        .to_string();
+    let rates = value["rates"]
+        .as_object()
+        .ok_or("Exchange rates missing")?
+        .iter()
+        .filter_map(|(code, rate)| Some((code.clone(), rate.as_f64()?)))
+        .collect();
+    Ok(ExchangeRates { base, rates })
+}
+
+/// The last fetch: when, for which base, and its rates or error
+type RatesFetch = (Instant, String, Result<ExchangeRates, String>);
+static RATES_CACHE: Mutex<Option<RatesFetch>> = Mutex::new(None);
+
+/// Exchange rates against `base`, fetched again when older than
+/// `RATES_MAX_AGE`. A failed fetch is not retried for `RATES_RETRY_AFTER`,
+/// so offline rows don't each wait for a request. Blocks on the request.
+pub fn exchange_rates(base: &str) -> Result<ExchangeRates, String> {
+    if let Some((fetched, fetched_base, result)) = RATES_CACHE.lock().as_ref() {
+        let max_age = match result {
+            Ok(_) => RATES_MAX_AGE,
+            Err(_) => RATES_RETRY_AFTER,
+        };
+        if fetched_base == base && fetched.elapsed() < max_age {
+            return result.clone();
+        }
+    }
+
+    let result = fetch_rates(base);
+    *RATES_CACHE.lock() = Some((Instant::now(), base.to_string(), result.clone()));
+    result
+}
+
+fn fetch_rates(base: &str) -> Result<ExchangeRates, String> {
+    let client = reqwest::blocking::Client::builder()
+        .timeout(RATES_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Client build error: {}", e))?;
+    let response = client
+        .get(RATES_URL)
+        .query(&[("from", base)])
+        .send()
+        .map_err(|e| format!("Network request failed: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("HTTP Error: {}", response.status()));
+    }
+    let body = response
+        .text()
+        .map_err(|e| format!("Failed to read response: {}", e))?;
+    parse_rates(&body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_and_convert() {
+        let converted = |text: &str, system| -> Vec<(String, String)> {
+            find_quantities(text)
+                .iter()
+                .filter_map(|q| convert(q, system, "EUR", None))
+                .map(|c| (c.original, c.converted))
+                .collect()
+        };
+        let pair = |a: &str, b: &str| (a.to_string(), b.to_string());
+
+        assert_eq!(
+            converted(
+                "It's 72°F outside, 13 mi to go at 60mph",
+                UnitSystem::Metric
+            ),
+            [
+                pair("72°F", "22.2 °C"),
+                pair("13 mi", "20.9 km"),
+                pair("60mph", "96.6 km/h")
+            ]
+        );
+        assert_eq!(
+            converted("Weighs 2 kg; 1,500 m; -5 °C", UnitSystem::Imperial),
+            [
+                pair("2 kg", "4.41 lb"),
+                pair("1,500 m", "4921 ft"),
+                pair("-5 °C", "23 °F")
+            ]
+        );
+        assert!(converted("13 km", UnitSystem::Metric).is_empty());
+        // Unit letters inside words and numbers inside words are not quantities
+        assert!(converted("3 mice, v2 gone, 5 in the morning", UnitSystem::Metric).is_empty());
+
+        let quantities = find_quantities("Only $49.99, or 1.234,50 € or 300 GBP");
+        let amounts: Vec<(&str, f64)> = quantities
+            .iter()
+            .map(|q| (q.currency().unwrap(), q.value))
+            .collect();
+        assert_eq!(amounts, [("USD", 49.99), ("EUR", 1234.5), ("GBP", 300.0)]);
+        assert!(needs_rates(&quantities, "EUR"));
+
+        let rates = parse_rates(
+            r#"{"amount":1.0,"base":"EUR","date":"2024-05-02","rates":{"USD":1.25,"GBP":0.8}}"#,
+        )
+        .unwrap();
+        let converted: Vec<String> = quantities
+            .iter()
+            .filter_map(|q| convert(q, UnitSystem::Metric, "EUR", Some(&rates)))
+            .map(|c| c.converted)
+            .collect();
+        assert_eq!(converted, ["39.99 EUR", "375.00 EUR"]);
+    }
+
+    #[test]
+    fn test_locale_defaults() {
+        assert_eq!(UnitSystem::from_locale("en_US.UTF-8"), UnitSystem::Imperial);
+        assert_eq!(UnitSystem::from_locale("en_GB.UTF-8"), UnitSystem::Metric);
+        assert_eq!(UnitSystem::from_locale("C"), UnitSystem::Metric);
+        assert_eq!(currency_from_locale("pt_BR.UTF-8"), "BRL");
+        assert_eq!(currency_from_locale("de_AT.UTF-8@euro"), "EUR");
+        assert_eq!(currency_from_locale("POSIX"), "USD");
+        assert_eq!(normalize_currency(" gbp "), Some("GBP".to_string()));
+        assert_eq!(normalize_currency("XYZ"), None);
+    }
+}
@@ -0,0 +1,250 @@
+//! Usage Statistics
+//! Counts copies per day, pastes per item and copies per source app in
+//! `usage_stats.json` beside the history, for the insights view in the
+//! settings. Nothing leaves the machine, and the whole store can be cleared.
+//! Items deleted from history keep their counts until then, but only items
+//! still in history are listed among the most pasted.
+
+use crate::clipboard_manager::ClipboardItem;
+use chrono::{Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::PathBuf;
+
+/// Persistence filename
+const USAGE_STATS_FILE: &str = "usage_stats.json";
+
+/// Days of copy counts kept
+const MAX_DAYS: i64 = 365;
+
+/// Entries in each top list of the insights
+const TOP_ENTRIES: usize = 5;
+
+/// What is saved
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct StatsFile {
+    #[serde(default)]
+    copies_per_day: BTreeMap<NaiveDate, u32>,
+    #[serde(default)]
+    pastes_per_item: HashMap<String, u32>,
+    #[serde(default)]
+    copies_per_app: HashMap<String, u32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DayCount {
+    /// "YYYY-MM-DD", local time
+    pub date: String,
+    pub count: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ItemCount {
+    pub id: String,
+    pub preview: String,
+    pub count: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AppCount {
+    pub app: String,
+    pub count: u32,
+}
+
+/// The aggregated numbers shown in the insights view
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UsageInsights {
+    /// One entry per day of the range, oldest first, days without copies included
+    pub copies_per_day: Vec<DayCount>,
+    /// All copies counted, not only those in the range
+    pub total_copies: u32,
+    pub total_pastes: u32,
+    pub most_pasted: Vec<ItemCount>,
+    pub top_source_apps: Vec<AppCount>,
+}
+
+/// Largest counts first, ties by name so the order is stable
+fn top<'a>(counts: impl IntoIterator<Item = (&'a String, &'a u32)>) -> Vec<(&'a String, u32)> {
+    let mut sorted: Vec<_> = counts.into_iter().map(|(key, n)| (key, *n)).collect();
+    sorted.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+    sorted
+}
+
+/// Keeps the usage counts
+pub struct UsageStats {
+    stats: StatsFile,
+    /// Path to the data directory
+    data_dir: PathBuf,
+}
+
+impl UsageStats {
+    /// Loads the counts saved in `data_dir`, if any
+    pub fn new(data_dir: PathBuf) -> Self {
+        let mut stats = Self {
+            stats: StatsFile::default(),
+            data_dir,
+        };
+        if let Err(e) = stats.load_from_disk() {
+            eprintln!("[UsageStats] Failed to load stats: {}", e);
+        }
+        stats
+    }
+
+    /// Counts an item copied on `day` (local time)
+    pub fn record_copy(&mut self, source_app: Option<&str>, day: NaiveDate) {
+        *self.stats.copies_per_day.entry(day).or_default() += 1;
+        let oldest = day - Duration::days(MAX_DAYS - 1);
+        self.stats.copies_per_day.retain(|date, _| *date >= oldest);
+        if let Some(app) = source_app.filter(|app| !app.is_empty()) {
+            *self
+                .stats
+                .copies_per_app
+                .entry(app.to_string())
+                .or_default() += 1;
+        }
+        self.save();
+    }
+
+    /// Counts a paste of the item `id`
+    pub fn record_paste(&mut self, id: &str) {
+        *self
+            .stats
+            .pastes_per_item
+            .entry(id.to_string())
+            .or_default() += 1;
+        self.save();
+    }
+
+    /// Forgets every count
+    pub fn clear(&mut self) {
+        self.stats = StatsFile::default();
+        self.save();
+    }
+
+    /// The counts for the `days` days up to `today`, with the most pasted of
+    /// the `items` still in history
+    pub fn insights(&self, items: &[ClipboardItem], today: NaiveDate, days: u32) -> UsageInsights {
+        let copies_per_day = (0..days as i64)
+            .rev()
+            .map(|ago| {
+                let date = today - Duration::days(ago);
+                DayCount {
+                    date: date.format("%Y-%m-%d").to_string(),
+                    count: self.stats.copies_per_day.get(&date).copied().unwrap_or(0),
+                }
+            })
+            .collect();
+
+        let previews: HashMap<&str, &str> = items
+            .iter()
+            .map(|item| (item.id.as_str(), item.preview.as_str()))
+            .collect();
+        let most_pasted = top(&self.stats.pastes_per_item)
+            .into_iter()
+            .filter_map(|(id, count)| {
+                Some(ItemCount {
+                    id: id.clone(),
+                    preview: previews.get(id.as_str())?.to_string(),
+                    count,
+                })
+            })
+            .take(TOP_ENTRIES)
+            .collect();
+        let top_source_apps = top(&self.stats.copies_per_app)
+            .into_iter()
+            .take(TOP_ENTRIES)
+            .map(|(app, count)| AppCount {
+                app: app.clone(),
+                count,
+            })
+            .collect();
+
+        UsageInsights {
+            copies_per_day,
+            total_copies: self.stats.copies_per_day.values().sum(),
+            total_pastes: self.stats.pastes_per_item.values().sum(),
+            most_pasted,
+            top_source_apps,
+        }
+    }
+
+    // --- Persistence Helpers ---
+
+    fn stats_path(&self) -> PathBuf {
+        self.data_dir.join(USAGE_STATS_FILE)
+    }
+
+    fn load_from_disk(&mut self) -> Result<(), String> {
+        let path = self.stats_path();
+        if !path.exists() {
+            return Ok(());
+        }
+        let content = fs::read_to_string(&path).map_err(|e| format!("Read error: {}", e))?;
+        self.stats = serde_json::from_str(&content).map_err(|e| format!("Parse error: {}", e))?;
+        Ok(())
+    }
+
+    fn save(&self) {
+        if let Err(e) = self.save_to_disk() {
+            eprintln!("[UsageStats] Failed to save stats: {}", e);
+        }
+    }
+
+    fn save_to_disk(&self) -> Result<(), String> {
+        if !self.data_dir.exists() {
+            fs::create_dir_all(&self.data_dir)
+                .map_err(|e| format!("Failed to create data dir: {}", e))?;
+        }
+        let content =
+            serde_json::to_string(&self.stats).map_err(|e| format!("Serialize error: {}", e))?;
+        fs::write(self.stats_path(), content).map_err(|e| format!("Write error: {}", e))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env::temp_dir;
+
+    #[test]
+    fn test_counts_survive_restart_and_clear() {
+        let data_dir = temp_dir().join("usage_stats_test");
+        let _ = fs::remove_dir_all(&data_dir);
+        let day = |d: u32| NaiveDate::from_ymd_opt(2024, 3, d).unwrap();
+
+        let mut stats = UsageStats::new(data_dir.clone());
+        stats.record_copy(Some("firefox"), day(1));
+        stats.record_copy(Some("code"), day(3));
+        stats.record_copy(Some("firefox"), day(3));
+        stats.record_copy(None, day(3));
+
+        let item = ClipboardItem::new_text("hello".to_string());
+        stats.record_paste(&item.id);
+        stats.record_paste(&item.id);
+        stats.record_paste("deleted-item");
+
+        let mut stats = UsageStats::new(data_dir.clone());
+        let insights = stats.insights(std::slice::from_ref(&item), day(3), 3);
+        let per_day: Vec<u32> = insights.copies_per_day.iter().map(|d| d.count).collect();
+        assert_eq!(per_day, [1, 0, 3]);
+        assert_eq!(insights.copies_per_day[0].date, "2024-03-01");
+        assert_eq!((insights.total_copies, insights.total_pastes), (4, 3));
+        assert_eq!(
+            insights.most_pasted,
+            [ItemCount {
+                id: item.id.clone(),
+                preview: "hello".to_string(),
+                count: 2
+            }]
+        );
+        assert_eq!(insights.top_source_apps[0].app, "firefox");
+        assert_eq!(insights.top_source_apps.len(), 2);
+
+        stats.clear();
+        let cleared = UsageStats::new(data_dir).insights(&[], day(3), 1);
+        assert_eq!(cleared.total_copies, 0);
+        assert!(cleared.top_source_apps.is_empty());
+    }
+}
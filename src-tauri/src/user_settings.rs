@@ -1,6 +1,27 @@
 //! User Settings Module
-//! Handles persistence of user preferences (theme mode, background opacity) in a separate JSON file.
+//! Handles persistence of user preferences (theme mode, background opacity, accent color and
+//! the rest of the theme) in a separate JSON file.
 
+use crate::clipboard_manager::{CapturePolicy, RetentionPolicy, MAX_RETENTION_DAYS};
+use crate::config_manager::{MonitorChoice, PlacementMode, UiDensity};
+use crate::emoji_variants::EmojiVariantSettings;
+use crate::focus_manager::FOCUS_STRATEGY_NAMES;
+use crate::gif_provider::GifSettings;
+use crate::history_store::StorageBackend;
+use crate::i18n::Language;
+use crate::image_compression::ImageCompressionSettings;
+use crate::input_simulator::{
+    MAX_TYPE_DELAY_MS, MIN_TYPE_DELAY_MS, PASTE_STRATEGY_NAMES, SUBMIT_KEY_NAMES,
+};
+use crate::keymap::{self, Keymap};
+use crate::memory_budget::{DEFAULT_BUDGET_MB, MAX_BUDGET_MB};
+use crate::paste_profiles::{self, PasteProfile};
+use crate::primary_selection::MAX_COMMIT_DELAY_SECS;
+use crate::shell_actions::{self, ShellAction};
+use crate::theme::{Theme, ThemeSettings, MAX_FONT_SCALE, MIN_FONT_SCALE};
+use crate::translation::TranslationSettings;
+use crate::unit_conversion::{self, UnitSystem};
+use crate::worker_pool::WorkerLimit;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
@@ -18,6 +39,19 @@ pub struct UserSettings {
     /// Background opacity for light mode (0.0 to 1.0)
     /// Default matches the original glass-effect-light alpha of 0.85
     pub light_background_opacity: f32,
+    /// Accent color, corner radius and preset
+    #[serde(default)]
+    pub theme: ThemeSettings,
+    /// "comfortable" or "compact"; compact also opens a smaller window
+    #[serde(default = "default_ui_density")]
+    pub ui_density: String,
+    /// Multiplier for the text size, 0.8 to 1.5
+    #[serde(default = "default_font_scale")]
+    pub font_scale: f32,
+    /// Language of the app's messages: "system" (the session locale) or a
+    /// language code such as "de"
+    #[serde(default = "default_language")]
+    pub language: String,
 
     // --- Feature Flags ---
     /// Enable Smart Actions (URL, Color, Email detection)
@@ -33,6 +67,216 @@ pub struct UserSettings {
     #[serde(default = "default_max_history_size")]
     pub max_history_size: usize,
 
+    /// Record plain text
+    #[serde(default = "default_true")]
+    pub capture_text: bool,
+
+    /// Record images
+    #[serde(default = "default_true")]
+    pub capture_images: bool,
+
+    /// Record copied files (file:// URIs)
+    #[serde(default = "default_true")]
+    pub capture_files: bool,
+
+    /// Remove copied files from history once the files are gone (otherwise
+    /// they are only marked)
+    #[serde(default)]
+    pub prune_missing_files: bool,
+
+    /// Keep the HTML formatting of rich text
+    #[serde(default = "default_true")]
+    pub capture_html: bool,
+
+    /// Days to keep unpinned text items (0 = forever)
+    #[serde(default)]
+    pub text_retention_days: u32,
+
+    /// Days to keep unpinned images (0 = forever)
+    #[serde(default)]
+    pub image_retention_days: u32,
+
+    /// Days to keep unpinned file references (0 = forever)
+    #[serde(default)]
+    pub file_retention_days: u32,
+
+    /// Where history is persisted: "json", "flat_files", "sqlite", "sled" or "memory"
+    #[serde(default = "default_storage_backend")]
+    pub storage_backend: String,
+
+    /// How much CPU background work such as thumbnails may use: "off",
+    /// "low" or "normal"
+    #[serde(default = "default_background_work")]
+    pub background_work: String,
+
+    /// How older images are re-encoded in history
+    #[serde(default)]
+    pub image_compression: ImageCompressionSettings,
+
+    /// Megabytes of history kept in memory before the oldest images move
+    /// to disk; 0 keeps everything in memory
+    #[serde(default = "default_memory_budget_mb")]
+    pub memory_budget_mb: u32,
+
+    // --- Window Settings ---
+    /// Where the window opens: "auto", "cursor" (next to the pointer),
+    /// "remembered" (where it was last moved to) or "docked" (a sidebar on a
+    /// screen edge)
+    #[serde(default = "default_window_placement")]
+    pub window_placement: String,
+
+    /// Monitor the window opens on: "any", "pointer" (the pointer's),
+    /// "focused_window" (the previously focused window's) or a monitor name
+    #[serde(default = "default_open_on_monitor")]
+    pub open_on_monitor: String,
+
+    /// On Wayland, make the window a layer-shell surface (wlroots, KDE)
+    /// instead of positioning it through XWayland. Read at startup.
+    #[serde(default)]
+    pub use_layer_shell: bool,
+
+    /// Mirror pinned items into a git repository with a commit per change
+    #[serde(default)]
+    pub pinned_git_enabled: bool,
+
+    /// Directory of the pinned items repository (empty = default location)
+    #[serde(default)]
+    pub pinned_git_dir: String,
+
+    /// Record highlighted text (PRIMARY selection) in a separate pane
+    #[serde(default)]
+    pub enable_primary_selection: bool,
+
+    /// Copy-on-select: add highlighted text to the main history once it has
+    /// stayed selected for `copy_on_select_delay_secs` or focus moved on
+    #[serde(default)]
+    pub copy_on_select: bool,
+
+    #[serde(default = "default_copy_on_select_delay_secs")]
+    pub copy_on_select_delay_secs: u64,
+
+    /// Keep CLIPBOARD and PRIMARY in sync: copying also sets the selection
+    /// and selecting also sets the clipboard. Selections are recorded once
+    /// they settle, as with copy-on-select.
+    #[serde(default)]
+    pub sync_selections: bool,
+
+    // --- Paste Settings ---
+    /// WM_CLASS names of terminal emulators, which need different paste keys
+    #[serde(default = "default_terminal_wm_classes")]
+    pub terminal_wm_classes: Vec<String>,
+
+    /// Keys sent to terminals: "ctrl+shift+v" or "shift+insert"
+    #[serde(default = "default_terminal_paste_keys")]
+    pub terminal_paste_keys: String,
+
+    /// Key pressed after the paste by "paste and submit" (Shift+Enter on an
+    /// item): "enter", "ctrl+enter" or "shift+enter"
+    #[serde(default = "default_submit_keys")]
+    pub submit_keys: String,
+
+    /// Delay between characters for "type it out", in milliseconds
+    #[serde(default = "default_type_out_delay_ms")]
+    pub type_out_delay_ms: u32,
+
+    /// Inserted between the two texts by "join with previous"
+    #[serde(default = "default_join_separator")]
+    pub join_separator: String,
+
+    /// Formatting applied per target application when pasting
+    #[serde(default = "default_paste_profiles")]
+    pub paste_profiles: Vec<PasteProfile>,
+
+    /// Focus restore methods to try, in order. Empty uses the built-in order;
+    /// methods left out are disabled.
+    #[serde(default)]
+    pub focus_strategies: Vec<String>,
+
+    /// Paste injection methods to try, in order. Empty uses the default for
+    /// the session type; methods left out are disabled.
+    #[serde(default)]
+    pub paste_strategies: Vec<String>,
+
+    /// Commands the item's text can be piped to, from its action menu
+    #[serde(default)]
+    pub shell_actions: Vec<ShellAction>,
+
+    /// Service used by the "Translate" action
+    #[serde(default)]
+    pub translation: TranslationSettings,
+
+    /// Units quantities in items are converted into: "system" (from the
+    /// locale), "metric" or "imperial"
+    #[serde(default = "default_unit_system")]
+    pub unit_system: String,
+
+    /// ISO code of the currency amounts are converted into, empty for the
+    /// locale's
+    #[serde(default)]
+    pub currency: String,
+
+    /// Fetch exchange rates to convert prices in items. Off by default, as
+    /// it sends a request each time rates are due.
+    #[serde(default)]
+    pub fetch_exchange_rates: bool,
+
+    /// Show a preview before pasting browser or unknown-origin text that hides
+    /// invisible characters, bidi controls or look-alike letters
+    #[serde(default = "default_true")]
+    pub confirm_suspicious_paste: bool,
+
+    // --- Shortcut Settings ---
+    /// Key combination that opens the window, e.g. "Super+V" or "Ctrl+Shift+V"
+    #[serde(default = "default_global_shortcut")]
+    pub global_shortcut: String,
+
+    /// Key combination that opens the window on the emoji panel, empty to disable
+    #[serde(default = "default_emoji_shortcut")]
+    pub emoji_shortcut: String,
+
+    /// Bind Super+Ctrl+1..9 to paste the Nth most recent item without opening the window
+    #[serde(default)]
+    pub quick_paste_shortcuts: bool,
+
+    // --- Keyboard Navigation ---
+    /// Drive the history list with Vim-style keys (see `keymap`)
+    #[serde(default)]
+    pub vim_navigation: bool,
+
+    /// Keyboard-only mode: the keymap is on and the buttons of the focused
+    /// row are shown, so every action can be reached without a mouse
+    #[serde(default)]
+    pub keyboard_only: bool,
+
+    /// Key sequence per action, e.g. {"move_down": "j"}
+    #[serde(default = "keymap::default_keymap")]
+    pub keymap: Keymap,
+
+    /// Re-register the global shortcut if the desktop environment drops it
+    #[serde(default = "default_true")]
+    pub auto_repair_shortcut: bool,
+
+    // --- Tray ---
+    /// Show the system tray icon. Without it the window is opened with the
+    /// global shortcut or by running the binary again.
+    #[serde(default = "default_true")]
+    pub show_tray_icon: bool,
+
+    // --- Diagnostics ---
+    /// Serve Prometheus metrics on 127.0.0.1 at this port; 0 turns it off
+    #[serde(default)]
+    pub metrics_port: u16,
+
+    // --- Emoji ---
+    /// Skin tone and gender applied to the emojis that have them
+    #[serde(default)]
+    pub emoji_variants: EmojiVariantSettings,
+
+    // --- GIFs ---
+    /// Service the GIF panel searches, and its keys
+    #[serde(default)]
+    pub gifs: GifSettings,
+
     // --- Custom Data ---
     /// User-defined Kaomojis
     #[serde(default)]
@@ -55,15 +299,172 @@ fn default_max_history_size() -> usize {
     crate::clipboard_manager::DEFAULT_MAX_HISTORY_SIZE
 }
 
+fn default_storage_backend() -> String {
+    StorageBackend::Json.as_str().to_string()
+}
+
+fn default_background_work() -> String {
+    WorkerLimit::Normal.as_str().to_string()
+}
+
+fn default_window_placement() -> String {
+    PlacementMode::Auto.as_str().to_string()
+}
+
+fn default_ui_density() -> String {
+    UiDensity::Comfortable.as_str().to_string()
+}
+
+fn default_font_scale() -> f32 {
+    1.0
+}
+
+fn default_language() -> String {
+    "system".to_string()
+}
+
+fn default_open_on_monitor() -> String {
+    MonitorChoice::Any.as_setting().to_string()
+}
+
+fn default_terminal_wm_classes() -> Vec<String> {
+    [
+        "gnome-terminal-server",
+        "org.gnome.Console",
+        "org.gnome.Ptyxis",
+        "konsole",
+        "xfce4-terminal",
+        "mate-terminal",
+        "tilix",
+        "terminator",
+        "guake",
+        "yakuake",
+        "Alacritty",
+        "kitty",
+        "org.wezfurlong.wezterm",
+        "com.mitchellh.ghostty",
+        "foot",
+        "XTerm",
+        "URxvt",
+        "st-256color",
+        "qterminal",
+        "lxterminal",
+    ]
+    .iter()
+    .map(|class| class.to_string())
+    .collect()
+}
+
+fn default_terminal_paste_keys() -> String {
+    "ctrl+shift+v".to_string()
+}
+
+fn default_submit_keys() -> String {
+    "enter".to_string()
+}
+
+fn default_copy_on_select_delay_secs() -> u64 {
+    2
+}
+
+fn default_memory_budget_mb() -> u32 {
+    DEFAULT_BUDGET_MB
+}
+
+fn default_type_out_delay_ms() -> u32 {
+    15
+}
+
+fn default_global_shortcut() -> String {
+    "Super+V".to_string()
+}
+
+fn default_emoji_shortcut() -> String {
+    "Super+Period".to_string()
+}
+
+fn default_unit_system() -> String {
+    "system".to_string()
+}
+
+fn default_join_separator() -> String {
+    " ".to_string()
+}
+
+fn normalize_strategies(strategies: &mut Vec<String>, known: &[&str]) {
+    let mut seen = Vec::new();
+    for name in strategies.iter() {
+        let name = name.trim().to_lowercase();
+        if known.contains(&name.as_str()) && !seen.contains(&name) {
+            seen.push(name);
+        }
+    }
+    *strategies = seen;
+}
+
+fn default_paste_profiles() -> Vec<PasteProfile> {
+    paste_profiles::default_profiles(&default_terminal_wm_classes())
+}
+
 impl Default for UserSettings {
     fn default() -> Self {
         Self {
             theme_mode: "system".to_string(),
             dark_background_opacity: 0.70,
             light_background_opacity: 0.70,
+            theme: ThemeSettings::default(),
+            ui_density: default_ui_density(),
+            font_scale: default_font_scale(),
+            language: default_language(),
             enable_smart_actions: true,
             enable_ui_polish: true,
             max_history_size: default_max_history_size(),
+            capture_text: true,
+            capture_images: true,
+            capture_files: true,
+            prune_missing_files: false,
+            capture_html: true,
+            text_retention_days: 0,
+            image_retention_days: 0,
+            file_retention_days: 0,
+            storage_backend: default_storage_backend(),
+            background_work: default_background_work(),
+            image_compression: ImageCompressionSettings::default(),
+            memory_budget_mb: default_memory_budget_mb(),
+            window_placement: default_window_placement(),
+            open_on_monitor: default_open_on_monitor(),
+            use_layer_shell: false,
+            pinned_git_enabled: false,
+            pinned_git_dir: String::new(),
+            enable_primary_selection: false,
+            copy_on_select: false,
+            copy_on_select_delay_secs: default_copy_on_select_delay_secs(),
+            sync_selections: false,
+            terminal_wm_classes: default_terminal_wm_classes(),
+            terminal_paste_keys: default_terminal_paste_keys(),
+            submit_keys: default_submit_keys(),
+            type_out_delay_ms: default_type_out_delay_ms(),
+            join_separator: default_join_separator(),
+            paste_profiles: default_paste_profiles(),
+            focus_strategies: Vec::new(),
+            paste_strategies: Vec::new(),
+            shell_actions: Vec::new(),
+            translation: TranslationSettings::default(),
+            unit_system: default_unit_system(),
+            currency: String::new(),
+            fetch_exchange_rates: false,
+            confirm_suspicious_paste: true,
+            global_shortcut: default_global_shortcut(),
+            emoji_shortcut: default_emoji_shortcut(),
+            quick_paste_shortcuts: false,
+            vim_navigation: false,
+            keyboard_only: false,
+            keymap: keymap::default_keymap(),
+            auto_repair_shortcut: true,
+            show_tray_icon: true,
+            metrics_port: 0,
+            emoji_variants: EmojiVariantSettings::default(),
+            gifs: GifSettings::default(),
             custom_kaomojis: Vec::new(),
         }
     }
@@ -80,8 +481,178 @@ impl UserSettings {
             self.theme_mode = "system".to_string();
         }
 
+        // A preset overrides the values it covers
+        self.theme.validate();
+        if let Some(values) = self.theme.preset().values() {
+            self.theme.accent_color = values.accent_color.to_string();
+            self.theme.corner_radius = values.corner_radius;
+            self.dark_background_opacity = values.dark_background_opacity;
+            self.light_background_opacity = values.light_background_opacity;
+        }
+
         // Validate max_history_size (1 to 100000)
         self.max_history_size = self.max_history_size.clamp(1, 100_000);
+
+        // Validate retention windows (0 = forever)
+        self.text_retention_days = self.text_retention_days.min(MAX_RETENTION_DAYS);
+        self.image_retention_days = self.image_retention_days.min(MAX_RETENTION_DAYS);
+        self.file_retention_days = self.file_retention_days.min(MAX_RETENTION_DAYS);
+
+        // Validate storage_backend
+        if StorageBackend::from_setting(&self.storage_backend).is_none() {
+            self.storage_backend = default_storage_backend();
+        }
+        if WorkerLimit::from_setting(&self.background_work).is_none() {
+            self.background_work = default_background_work();
+        }
+        self.image_compression.validate();
+        self.emoji_variants.validate();
+        self.gifs.validate();
+        self.memory_budget_mb = self.memory_budget_mb.min(MAX_BUDGET_MB);
+        if PlacementMode::from_setting(&self.window_placement).is_none() {
+            self.window_placement = default_window_placement();
+        }
+        self.open_on_monitor = MonitorChoice::from_setting(&self.open_on_monitor)
+            .as_setting()
+            .to_string();
+        if UiDensity::from_setting(&self.ui_density).is_none() {
+            self.ui_density = default_ui_density();
+        }
+        self.font_scale = self.font_scale.clamp(MIN_FONT_SCALE, MAX_FONT_SCALE);
+        if Language::from_code(&self.language).is_none() {
+            self.language = default_language();
+        }
+
+        // Validate terminal_paste_keys
+        if !["ctrl+shift+v", "shift+insert"].contains(&self.terminal_paste_keys.as_str()) {
+            self.terminal_paste_keys = default_terminal_paste_keys();
+        }
+        if !SUBMIT_KEY_NAMES.contains(&self.submit_keys.as_str()) {
+            self.submit_keys = default_submit_keys();
+        }
+        self.terminal_wm_classes
+            .retain(|class| !class.trim().is_empty());
+
+        self.copy_on_select_delay_secs = self
+            .copy_on_select_delay_secs
+            .clamp(1, MAX_COMMIT_DELAY_SECS);
+
+        self.type_out_delay_ms = self
+            .type_out_delay_ms
+            .clamp(MIN_TYPE_DELAY_MS, MAX_TYPE_DELAY_MS);
+
+        // Drop blank app ids and profiles left with none
+        for profile in &mut self.paste_profiles {
+            profile.app_ids.retain(|id| !id.trim().is_empty());
+        }
+        self.paste_profiles
+            .retain(|profile| !profile.app_ids.is_empty());
+
+        // Keep known strategy names only, once each
+        normalize_strategies(&mut self.focus_strategies, FOCUS_STRATEGY_NAMES);
+        normalize_strategies(&mut self.paste_strategies, PASTE_STRATEGY_NAMES);
+        shell_actions::normalize(&mut self.shell_actions);
+        self.translation.validate();
+        if UnitSystem::from_setting(&self.unit_system).is_none() {
+            self.unit_system = default_unit_system();
+        }
+        self.currency = unit_conversion::normalize_currency(&self.currency).unwrap_or_default();
+
+        self.global_shortcut = self.global_shortcut.trim().to_string();
+        if self.global_shortcut.is_empty() {
+            self.global_shortcut = default_global_shortcut();
+        }
+        self.emoji_shortcut = self.emoji_shortcut.trim().to_string();
+        keymap::normalize(&mut self.keymap);
+    }
+
+    /// The theme the windows are styled with
+    pub fn theme(&self) -> Theme {
+        Theme {
+            density: self.ui_density().as_str().to_string(),
+            font_scale: self.font_scale,
+            ..Theme::new(
+                &self.theme,
+                self.dark_background_opacity,
+                self.light_background_opacity,
+            )
+        }
+    }
+
+    /// Per-type retention policy for the clipboard manager
+    pub fn retention_policy(&self) -> RetentionPolicy {
+        RetentionPolicy {
+            text_days: self.text_retention_days,
+            image_days: self.image_retention_days,
+            file_days: self.file_retention_days,
+        }
+    }
+
+    /// Content types the clipboard manager records
+    pub fn capture_policy(&self) -> CapturePolicy {
+        CapturePolicy {
+            text: self.capture_text,
+            images: self.capture_images,
+            files: self.capture_files,
+            html: self.capture_html,
+        }
+    }
+
+    /// Persistence backend for the clipboard history
+    pub fn storage_backend(&self) -> StorageBackend {
+        StorageBackend::from_setting(&self.storage_backend).unwrap_or(StorageBackend::Json)
+    }
+
+    /// Limit for the background worker pool
+    pub fn worker_limit(&self) -> WorkerLimit {
+        WorkerLimit::from_setting(&self.background_work).unwrap_or(WorkerLimit::Normal)
+    }
+
+    pub fn placement_mode(&self) -> PlacementMode {
+        PlacementMode::from_setting(&self.window_placement).unwrap_or(PlacementMode::Auto)
+    }
+
+    pub fn ui_density(&self) -> UiDensity {
+        UiDensity::from_setting(&self.ui_density).unwrap_or_default()
+    }
+
+    /// Whether typed keys are looked up in the keymap
+    pub fn keymap_enabled(&self) -> bool {
+        self.vim_navigation || self.keyboard_only
+    }
+
+    pub fn monitor_choice(&self) -> MonitorChoice {
+        MonitorChoice::from_setting(&self.open_on_monitor)
+    }
+
+    /// Language messages are shown in, with "system" resolved
+    pub fn language(&self) -> Language {
+        Language::from_code(&self.language).unwrap_or_else(Language::system)
+    }
+
+    /// Units quantities are converted into, with "system" resolved
+    pub fn unit_system(&self) -> UnitSystem {
+        UnitSystem::from_setting(&self.unit_system)
+            .unwrap_or_else(|| UnitSystem::from_locale(&unit_conversion::locale("LC_MEASUREMENT")))
+    }
+
+    /// Currency amounts are converted into, with the locale's for none
+    pub fn currency(&self) -> String {
+        unit_conversion::normalize_currency(&self.currency).unwrap_or_else(|| {
+            unit_conversion::currency_from_locale(&unit_conversion::locale("LC_MONETARY"))
+                .to_string()
+        })
+    }
+
+    /// Directory of the pinned items git mirror, or None when it is disabled
+    pub fn pinned_git_mirror_dir(&self) -> Option<PathBuf> {
+        if !self.pinned_git_enabled {
+            return None;
+        }
+        match self.pinned_git_dir.trim() {
+            "" => crate::pinned_git::default_dir(),
+            dir => Some(PathBuf::from(dir)),
+        }
     }
 }
 
@@ -193,4 +764,32 @@ mod tests {
         assert!((settings.dark_background_opacity - 1.0).abs() < f32::EPSILON);
         assert!(settings.light_background_opacity.abs() < f32::EPSILON);
     }
+
+    #[test]
+    fn test_validate_applies_theme_preset() {
+        let mut settings = UserSettings::default();
+        settings.theme.preset = "sunset".to_string();
+        settings.dark_background_opacity = 0.2;
+        settings.validate();
+
+        let theme = settings.theme();
+        assert_eq!(theme.accent_color, "#f97316");
+        assert_eq!(theme.corner_radius, 14);
+        assert!((theme.dark_background_opacity - 0.75).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn test_validate_clamps_retention_days() {
+        let mut settings = UserSettings {
+            text_retention_days: 90,
+            image_retention_days: 100_000,
+            ..Default::default()
+        };
+        settings.validate();
+
+        let policy = settings.retention_policy();
+        assert_eq!(policy.text_days, 90);
+        assert_eq!(policy.image_days, MAX_RETENTION_DAYS);
+        assert_eq!(policy.file_days, 0);
+    }
 }
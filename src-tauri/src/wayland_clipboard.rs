@@ -0,0 +1,539 @@
+//! Wayland Clipboard Module
+//! Event-driven clipboard capture through a data-control protocol, so copies
+//! are seen without polling. Supports ext-data-control-v1 (KDE and newer
+//! compositors) and zwlr_data_control_unstable_v1 (wlroots); the session module
+//! decides which one to use.
+
+use crate::clipboard_manager::{calculate_hash, SelectionKind};
+use crate::session::DataControlProtocol;
+use arboard::ImageData;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, Read};
+use std::os::fd::{AsFd, AsRawFd, FromRawFd, OwnedFd};
+use std::time::{Duration, Instant};
+use wayland_client::backend::ObjectId;
+use wayland_client::globals::{registry_queue_init, GlobalListContents};
+use wayland_client::protocol::{wl_registry, wl_seat};
+use wayland_client::{event_created_child, Connection, Dispatch, Proxy, QueueHandle};
+use wayland_protocols::ext::data_control::v1::client::{
+    ext_data_control_device_v1::{self, ExtDataControlDeviceV1},
+    ext_data_control_manager_v1::{self, ExtDataControlManagerV1},
+    ext_data_control_offer_v1::{self, ExtDataControlOfferV1},
+};
+use wayland_protocols_wlr::data_control::v1::client::{
+    zwlr_data_control_device_v1::{self, ZwlrDataControlDeviceV1},
+    zwlr_data_control_manager_v1::{self, ZwlrDataControlManagerV1},
+    zwlr_data_control_offer_v1::{self, ZwlrDataControlOfferV1},
+};
+
+/// Text mime types in order of preference
+const TEXT_MIME_TYPES: &[&str] = &[
+    "text/plain;charset=utf-8",
+    "UTF8_STRING",
+    "text/plain",
+    "STRING",
+    "TEXT",
+];
+const HTML_MIME_TYPE: &str = "text/html";
+const IMAGE_MIME_TYPE: &str = "image/png";
+/// How long the selection owner may go without writing to the pipe; a
+/// client that never closes it would otherwise stall capture for good
+const RECEIVE_TIMEOUT: Duration = Duration::from_secs(2);
+/// Offers larger than this are dropped rather than read into memory
+const MAX_RECEIVE_BYTES: usize = 256 * 1024 * 1024;
+
+/// Content of a new clipboard selection
+pub enum WaylandClipboardEvent {
+    Text {
+        text: String,
+        html: Option<String>,
+    },
+    /// Decoded RGBA image plus the hash used for duplicate detection
+    Image {
+        data: ImageData<'static>,
+        hash: u64,
+    },
+}
+
+/// A data offer from either protocol
+enum Offer {
+    Ext(ExtDataControlOfferV1),
+    Wlr(ZwlrDataControlOfferV1),
+}
+
+impl Offer {
+    fn id(&self) -> ObjectId {
+        match self {
+            Self::Ext(offer) => offer.id(),
+            Self::Wlr(offer) => offer.id(),
+        }
+    }
+
+    fn receive(&self, mime: &str, fd: std::os::fd::BorrowedFd<'_>) {
+        match self {
+            Self::Ext(offer) => offer.receive(mime.to_string(), fd),
+            Self::Wlr(offer) => offer.receive(mime.to_string(), fd),
+        }
+    }
+
+    fn destroy(&self) {
+        match self {
+            Self::Ext(offer) => offer.destroy(),
+            Self::Wlr(offer) => offer.destroy(),
+        }
+    }
+}
+
+#[derive(Default)]
+struct ListenerState {
+    /// Selection being recorded; offers for the other one are released
+    kind: SelectionKind,
+    /// Mime types advertised by each offer we have not released yet
+    offers: HashMap<ObjectId, Vec<String>>,
+    /// Offer that became the watched selection and still has to be read
+    selection: Option<Offer>,
+    /// Set when the compositor invalidates our data device
+    finished: bool,
+}
+
+impl ListenerState {
+    fn release(&mut self, offer: Offer) {
+        self.offers.remove(&offer.id());
+        offer.destroy();
+    }
+
+    fn set_selection(&mut self, kind: SelectionKind, offer: Option<Offer>) {
+        if kind != self.kind {
+            if let Some(offer) = offer {
+                self.release(offer);
+            }
+            return;
+        }
+        // A newer selection replaces one we have not read yet
+        if let Some(previous) = self.selection.take() {
+            self.release(previous);
+        }
+        self.selection = offer;
+    }
+}
+
+impl Dispatch<wl_registry::WlRegistry, GlobalListContents> for ListenerState {
+    fn event(
+        _: &mut Self,
+        _: &wl_registry::WlRegistry,
+        _: wl_registry::Event,
+        _: &GlobalListContents,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<wl_seat::WlSeat, ()> for ListenerState {
+    fn event(
+        _: &mut Self,
+        _: &wl_seat::WlSeat,
+        _: wl_seat::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<ZwlrDataControlManagerV1, ()> for ListenerState {
+    fn event(
+        _: &mut Self,
+        _: &ZwlrDataControlManagerV1,
+        _: zwlr_data_control_manager_v1::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<ZwlrDataControlDeviceV1, ()> for ListenerState {
+    fn event(
+        state: &mut Self,
+        _: &ZwlrDataControlDeviceV1,
+        event: zwlr_data_control_device_v1::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        match event {
+            zwlr_data_control_device_v1::Event::DataOffer { id } => {
+                state.offers.insert(id.id(), Vec::new());
+            }
+            zwlr_data_control_device_v1::Event::Selection { id } => {
+                state.set_selection(SelectionKind::Clipboard, id.map(Offer::Wlr));
+            }
+            // Only sent by managers bound at version 2 or later
+            zwlr_data_control_device_v1::Event::PrimarySelection { id } => {
+                state.set_selection(SelectionKind::Primary, id.map(Offer::Wlr));
+            }
+            zwlr_data_control_device_v1::Event::Finished => {
+                state.finished = true;
+            }
+            _ => {}
+        }
+    }
+
+    event_created_child!(ListenerState, ZwlrDataControlDeviceV1, [
+        zwlr_data_control_device_v1::EVT_DATA_OFFER_OPCODE => (ZwlrDataControlOfferV1, ()),
+    ]);
+}
+
+impl Dispatch<ZwlrDataControlOfferV1, ()> for ListenerState {
+    fn event(
+        state: &mut Self,
+        offer: &ZwlrDataControlOfferV1,
+        event: zwlr_data_control_offer_v1::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        if let zwlr_data_control_offer_v1::Event::Offer { mime_type } = event {
+            state.offers.entry(offer.id()).or_default().push(mime_type);
+        }
+    }
+}
+
+impl Dispatch<ExtDataControlManagerV1, ()> for ListenerState {
+    fn event(
+        _: &mut Self,
+        _: &ExtDataControlManagerV1,
+        _: ext_data_control_manager_v1::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<ExtDataControlDeviceV1, ()> for ListenerState {
+    fn event(
+        state: &mut Self,
+        _: &ExtDataControlDeviceV1,
+        event: ext_data_control_device_v1::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        match event {
+            ext_data_control_device_v1::Event::DataOffer { id } => {
+                state.offers.insert(id.id(), Vec::new());
+            }
+            ext_data_control_device_v1::Event::Selection { id } => {
+                state.set_selection(SelectionKind::Clipboard, id.map(Offer::Ext));
+            }
+            ext_data_control_device_v1::Event::PrimarySelection { id } => {
+                state.set_selection(SelectionKind::Primary, id.map(Offer::Ext));
+            }
+            ext_data_control_device_v1::Event::Finished => {
+                state.finished = true;
+            }
+            _ => {}
+        }
+    }
+
+    event_created_child!(ListenerState, ExtDataControlDeviceV1, [
+        ext_data_control_device_v1::EVT_DATA_OFFER_OPCODE => (ExtDataControlOfferV1, ()),
+    ]);
+}
+
+impl Dispatch<ExtDataControlOfferV1, ()> for ListenerState {
+    fn event(
+        state: &mut Self,
+        offer: &ExtDataControlOfferV1,
+        event: ext_data_control_offer_v1::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        if let ext_data_control_offer_v1::Event::Offer { mime_type } = event {
+            state.offers.entry(offer.id()).or_default().push(mime_type);
+        }
+    }
+}
+
+/// Data-control protocols the compositor advertises
+pub fn advertised_protocols() -> Vec<DataControlProtocol> {
+    let interfaces = advertised_interfaces();
+    let has = |name: &str| interfaces.iter().any(|interface| interface == name);
+    let mut protocols = Vec::new();
+    if has(ExtDataControlManagerV1::interface().name) {
+        protocols.push(DataControlProtocol::Ext);
+    }
+    if has(ZwlrDataControlManagerV1::interface().name) {
+        protocols.push(DataControlProtocol::Wlr);
+    }
+    protocols
+}
+
+/// Interface names of every global the compositor advertises, empty when
+/// it can't be reached
+pub fn advertised_interfaces() -> Vec<String> {
+    let Ok(conn) = Connection::connect_to_env() else {
+        return Vec::new();
+    };
+    let Ok((globals, _queue)) = registry_queue_init::<ListenerState>(&conn) else {
+        return Vec::new();
+    };
+    globals
+        .contents()
+        .with_list(|list| list.iter().map(|global| global.interface.clone()).collect())
+}
+
+/// Watches the given selection and calls `on_change` for every new one.
+/// Offers that arrive while `wanted` returns false are released unread.
+/// Blocks for as long as the Wayland connection is healthy; on error the
+/// caller should fall back to polling.
+pub fn run_listener<W: Fn() -> bool, F: FnMut(WaylandClipboardEvent)>(
+    protocol: DataControlProtocol,
+    selection: SelectionKind,
+    wanted: W,
+    mut on_change: F,
+) -> Result<(), String> {
+    let conn =
+        Connection::connect_to_env().map_err(|e| format!("Wayland connect failed: {}", e))?;
+    let (globals, mut queue) = registry_queue_init::<ListenerState>(&conn)
+        .map_err(|e| format!("Wayland registry failed: {}", e))?;
+    let qh = queue.handle();
+
+    let seat: wl_seat::WlSeat = globals
+        .bind(&qh, 1..=8, ())
+        .map_err(|e| format!("wl_seat unavailable: {}", e))?;
+
+    // Selection events for the device are delivered to ListenerState
+    match protocol {
+        DataControlProtocol::Ext => {
+            let manager: ExtDataControlManagerV1 = globals
+                .bind(&qh, 1..=1, ())
+                .map_err(|e| format!("ext_data_control_manager_v1 unavailable: {}", e))?;
+            manager.get_data_device(&seat, &qh, ());
+        }
+        DataControlProtocol::Wlr => {
+            let manager: ZwlrDataControlManagerV1 = globals
+                .bind(&qh, 1..=2, ())
+                .map_err(|e| format!("zwlr_data_control_manager_v1 unavailable: {}", e))?;
+            manager.get_data_device(&seat, &qh, ());
+        }
+    }
+
+    eprintln!(
+        "[WaylandClipboard] Listening for {:?} selection changes via {}",
+        selection,
+        protocol.name()
+    );
+
+    let mut state = ListenerState {
+        kind: selection,
+        ..Default::default()
+    };
+    loop {
+        queue
+            .blocking_dispatch(&mut state)
+            .map_err(|e| format!("Wayland dispatch failed: {}", e))?;
+
+        if state.finished {
+            return Err("Data control device was invalidated by the compositor".to_string());
+        }
+
+        let Some(offer) = state.selection.take() else {
+            continue;
+        };
+        if !wanted() {
+            state.release(offer);
+            continue;
+        }
+        let mime_types = state.offers.get(&offer.id()).cloned().unwrap_or_default();
+        let event = read_offer(&conn, &offer, &mime_types);
+        state.release(offer);
+
+        if let Some(event) = event {
+            on_change(event);
+        }
+    }
+}
+
+/// What to read from an offer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OfferFormat {
+    /// Text in this mime type, plus HTML if `html`
+    Text { mime: &'static str, html: bool },
+    /// A PNG image
+    Image,
+}
+
+/// The preferred representation among the advertised `mime_types`: text
+/// (with HTML if offered) first, then PNG images. These come from whichever
+/// app owns the selection, so anything goes.
+pub fn choose_offer_format(mime_types: &[String]) -> Option<OfferFormat> {
+    let offers = |mime: &str| mime_types.iter().any(|m| m == mime);
+
+    if let Some(mime) = TEXT_MIME_TYPES.iter().find(|mime| offers(mime)) {
+        return Some(OfferFormat::Text {
+            mime,
+            html: offers(HTML_MIME_TYPE),
+        });
+    }
+    offers(IMAGE_MIME_TYPE).then_some(OfferFormat::Image)
+}
+
+/// Decodes the PNG an offer sent into the RGBA form the polling path gets
+/// from arboard
+pub fn decode_offer_image(png: &[u8]) -> Option<WaylandClipboardEvent> {
+    let rgba = image::load_from_memory(png).ok()?.to_rgba8();
+    let (width, height) = rgba.dimensions();
+    let bytes = rgba.into_raw();
+    // Same hash the polling path computes from arboard's RGBA bytes
+    let hash = calculate_hash(&bytes);
+    Some(WaylandClipboardEvent::Image {
+        data: ImageData {
+            width: width as usize,
+            height: height as usize,
+            bytes: bytes.into(),
+        },
+        hash,
+    })
+}
+
+/// Reads the preferred representation of an offer
+fn read_offer(
+    conn: &Connection,
+    offer: &Offer,
+    mime_types: &[String],
+) -> Option<WaylandClipboardEvent> {
+    match choose_offer_format(mime_types)? {
+        OfferFormat::Text { mime, html } => {
+            let text = String::from_utf8(receive(conn, offer, mime)?).ok()?;
+            let html = if html {
+                receive(conn, offer, HTML_MIME_TYPE).and_then(|bytes| String::from_utf8(bytes).ok())
+            } else {
+                None
+            };
+            Some(WaylandClipboardEvent::Text { text, html })
+        }
+        OfferFormat::Image => decode_offer_image(&receive(conn, offer, IMAGE_MIME_TYPE)?),
+    }
+}
+
+/// Asks the selection owner to write `mime` into a pipe and reads it to the end
+fn receive(conn: &Connection, offer: &Offer, mime: &str) -> Option<Vec<u8>> {
+    let (read_end, write_end) = pipe()
+        .map_err(|e| eprintln!("[WaylandClipboard] Failed to create pipe: {}", e))
+        .ok()?;
+
+    offer.receive(mime, write_end.as_fd());
+    conn.flush().ok()?;
+    // Close our copy of the write end so the read sees EOF
+    drop(write_end);
+
+    read_with_deadline(File::from(read_end), RECEIVE_TIMEOUT, MAX_RECEIVE_BYTES)
+        .map_err(|e| eprintln!("[WaylandClipboard] Failed to read {}: {}", mime, e))
+        .ok()
+}
+
+/// Reads `file` to EOF, polling it so the read gives up once nothing came
+/// for `timeout`, or once more than `max_bytes` came. A large offer that
+/// keeps arriving is not cut off however long it takes.
+fn read_with_deadline(mut file: File, timeout: Duration, max_bytes: usize) -> io::Result<Vec<u8>> {
+    let mut deadline = Instant::now() + timeout;
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 64 * 1024];
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        let mut poll_fd = libc::pollfd {
+            fd: file.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        let millis = remaining.as_millis().min(i32::MAX as u128) as i32;
+        match unsafe { libc::poll(&mut poll_fd, 1, millis) } {
+            0 => return Err(io::Error::new(io::ErrorKind::TimedOut, "timed out")),
+            ready if ready < 0 => {
+                let error = io::Error::last_os_error();
+                if error.kind() != io::ErrorKind::Interrupted {
+                    return Err(error);
+                }
+                continue;
+            }
+            _ => {}
+        }
+        match file.read(&mut chunk) {
+            Ok(0) => return Ok(buffer),
+            Ok(read) => {
+                if buffer.len() + read > max_bytes {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("larger than {} bytes", max_bytes),
+                    ));
+                }
+                buffer.extend_from_slice(&chunk[..read]);
+                deadline = Instant::now() + timeout;
+            }
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+fn pipe() -> io::Result<(OwnedFd, OwnedFd)> {
+    let mut fds = [0; 2];
+    unsafe {
+        if libc::pipe2(fds.as_mut_ptr(), libc::O_CLOEXEC) != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok((OwnedFd::from_raw_fd(fds[0]), OwnedFd::from_raw_fd(fds[1])))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_read_with_deadline_gives_up_on_an_open_pipe() {
+        let (read_end, write_end) = pipe().unwrap();
+        let mut writer = File::from(write_end);
+        writer.write_all(b"partial").unwrap();
+        let started = Instant::now();
+        let result = read_with_deadline(File::from(read_end), Duration::from_millis(100), 1024);
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::TimedOut);
+        assert!(started.elapsed() < Duration::from_secs(2));
+
+        let (read_end, write_end) = pipe().unwrap();
+        let mut writer = File::from(write_end);
+        writer.write_all(b"complete").unwrap();
+        drop(writer);
+        let result = read_with_deadline(File::from(read_end), Duration::from_secs(1), 1024);
+        assert_eq!(result.unwrap(), b"complete");
+    }
+
+    #[test]
+    fn test_read_with_deadline_waits_on_progress_and_caps_size() {
+        // Keeps writing for longer than the timeout, never pausing that long
+        let (read_end, write_end) = pipe().unwrap();
+        let writer = std::thread::spawn(move || {
+            let mut writer = File::from(write_end);
+            for _ in 0..6 {
+                writer.write_all(b"slow").unwrap();
+                std::thread::sleep(Duration::from_millis(50));
+            }
+        });
+        let result = read_with_deadline(File::from(read_end), Duration::from_millis(150), 1024);
+        assert_eq!(result.unwrap(), b"slow".repeat(6));
+        writer.join().unwrap();
+
+        let (read_end, write_end) = pipe().unwrap();
+        let mut writer = File::from(write_end);
+        writer.write_all(&[0; 100]).unwrap();
+        drop(writer);
+        let result = read_with_deadline(File::from(read_end), Duration::from_secs(1), 64);
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,76 @@
+//! Work Area
+//! Reads the space panels and docks reserve on X11 (EWMH struts), so the
+//! popup can sit just above the taskbar on whichever monitor it opens on.
+//! `_NET_WORKAREA` is not used since it is one rectangle for all monitors.
+//! Layer-shell surfaces are kept out of exclusive zones by the compositor.
+
+use crate::config_manager::{work_area, MonitorGeometry, Strut};
+use tauri::PhysicalSize;
+use x11rb::connection::Connection;
+use x11rb::protocol::xproto::{AtomEnum, ConnectionExt};
+
+/// The root window size and the struts of every managed window. None when
+/// X11 or the window manager's client list is not available.
+pub fn x11_struts() -> Option<(PhysicalSize<u32>, Vec<Strut>)> {
+    let (conn, screen_num) = x11rb::connect(None).ok()?;
+    let screen = conn.setup().roots.get(screen_num)?;
+    let root = screen.root;
+    let screen_size = PhysicalSize::new(
+        screen.width_in_pixels as u32,
+        screen.height_in_pixels as u32,
+    );
+
+    let atom = |name: &[u8]| {
+        conn.intern_atom(false, name)
+            .ok()?
+            .reply()
+            .ok()
+            .map(|reply| reply.atom)
+    };
+    let net_client_list = atom(b"_NET_CLIENT_LIST")?;
+    let net_wm_strut_partial = atom(b"_NET_WM_STRUT_PARTIAL")?;
+    let net_wm_strut = atom(b"_NET_WM_STRUT")?;
+
+    let clients: Vec<u32> = conn
+        .get_property(false, root, net_client_list, AtomEnum::WINDOW, 0, 1024)
+        .ok()?
+        .reply()
+        .ok()?
+        .value32()?
+        .collect();
+
+    let cardinals = |window: u32, property: u32, len: u32| -> Option<Vec<u32>> {
+        let reply = conn
+            .get_property(false, window, property, AtomEnum::CARDINAL, 0, len)
+            .ok()?
+            .reply()
+            .ok()?;
+        let values: Vec<u32> = reply.value32()?.collect();
+        (values.len() == len as usize).then_some(values)
+    };
+
+    // The partial form is preferred; the legacy one spans whole edges
+    let struts = clients
+        .into_iter()
+        .filter_map(|window| {
+            if let Some(values) = cardinals(window, net_wm_strut_partial, 12) {
+                return Some(Strut::from_partial(values.as_slice().try_into().ok()?));
+            }
+            let values = cardinals(window, net_wm_strut, 4)?;
+            Some(Strut::from_legacy(values.as_slice().try_into().ok()?))
+        })
+        .filter(|strut| [strut.left, strut.right, strut.top, strut.bottom] != [0; 4])
+        .collect();
+    Some((screen_size, struts))
+}
+
+/// Fills in each monitor's work area from the X11 struts. Left alone when
+/// they can't be read, so the usual padding is kept.
+pub fn fill_work_areas(monitors: &mut [MonitorGeometry]) {
+    let Some((screen, struts)) = x11_struts() else {
+        return;
+    };
+    for monitor in monitors {
+        monitor.work_area = Some(work_area(monitor, screen, &struts));
+    }
+}
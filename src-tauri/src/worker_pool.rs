@@ -0,0 +1,274 @@
+//! Worker Pool
+//! Runs background enrichment such as thumbnails on a few threads at low CPU
+//! priority, so it never competes with the app or makes a laptop's fans
+//! spin. How much it may do is the "background work" setting.
+
+use crate::i18n;
+use parking_lot::{Condvar, Mutex};
+use std::collections::VecDeque;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{mpsc, Arc, OnceLock};
+use std::time::{Duration, Instant};
+
+/// How much background work is allowed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorkerLimit {
+    /// Nothing runs; callers fall back to doing without
+    Off,
+    /// One thread at the lowest priority
+    Low,
+    /// Up to half the CPUs, niced
+    Normal,
+}
+
+impl WorkerLimit {
+    pub const ALL: [WorkerLimit; 3] = [Self::Off, Self::Low, Self::Normal];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Off => "off",
+            Self::Low => "low",
+            Self::Normal => "normal",
+        }
+    }
+
+    pub fn from_setting(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|limit| limit.as_str() == value)
+    }
+
+    /// Most tasks running at once
+    pub fn concurrency(self) -> usize {
+        match self {
+            Self::Off => 0,
+            Self::Low => 1,
+            Self::Normal => std::thread::available_parallelism()
+                .map(|cpus| (cpus.get() / 2).max(1))
+                .unwrap_or(1),
+        }
+    }
+
+    /// Nice value of the worker threads
+    pub fn nice(self) -> i32 {
+        match self {
+            Self::Off | Self::Low => 19,
+            Self::Normal => 10,
+        }
+    }
+
+    /// How long a task may take, counting the time spent queued. Tasks still
+    /// queued past it are dropped, and callers waiting on one give up. A task
+    /// already running is not stopped; running past it only logs a warning.
+    pub fn task_timeout(self) -> Duration {
+        match self {
+            Self::Off | Self::Low => Duration::from_secs(10),
+            Self::Normal => Duration::from_secs(30),
+        }
+    }
+}
+
+/// Idle workers exit after this long
+const WORKER_IDLE_TIMEOUT: Duration = Duration::from_secs(30);
+
+struct Task {
+    name: &'static str,
+    queued_at: Instant,
+    run: Box<dyn FnOnce() + Send>,
+}
+
+#[derive(Default)]
+struct Queue {
+    tasks: VecDeque<Task>,
+    workers: usize,
+}
+
+pub struct WorkerPool {
+    limit: Mutex<WorkerLimit>,
+    queue: Mutex<Queue>,
+    available: Condvar,
+}
+
+impl WorkerPool {
+    /// Threads are started as tasks come in, up to the limit's concurrency
+    pub fn new(limit: WorkerLimit) -> Arc<Self> {
+        Arc::new(Self {
+            limit: Mutex::new(limit),
+            queue: Mutex::new(Queue::default()),
+            available: Condvar::new(),
+        })
+    }
+
+    pub fn limit(&self) -> WorkerLimit {
+        *self.limit.lock()
+    }
+
+    /// Takes effect for the next tasks. Turning it off drops queued ones.
+    /// Running workers pick up the new nice value before their next task,
+    /// but without CAP_SYS_NICE Linux only lets one go lower, so a move from
+    /// "low" to "normal" keeps them at 19 until they exit idle.
+    pub fn set_limit(&self, limit: WorkerLimit) {
+        *self.limit.lock() = limit;
+        if limit == WorkerLimit::Off {
+            self.queue.lock().tasks.clear();
+        }
+        self.available.notify_all();
+    }
+
+    /// Queues `task`; false (and nothing runs) when background work is off
+    pub fn spawn(
+        self: &Arc<Self>,
+        name: &'static str,
+        task: impl FnOnce() + Send + 'static,
+    ) -> bool {
+        let limit = self.limit();
+        if limit == WorkerLimit::Off {
+            return false;
+        }
+
+        let mut queue = self.queue.lock();
+        queue.tasks.push_back(Task {
+            name,
+            queued_at: Instant::now(),
+            run: Box::new(task),
+        });
+        if queue.workers < limit.concurrency() {
+            queue.workers += 1;
+            let pool = Arc::clone(self);
+            std::thread::spawn(move || pool.work());
+        } else {
+            self.available.notify_one();
+        }
+        true
+    }
+
+    /// Runs `task` on the pool and waits for its result, up to the task
+    /// timeout
+    pub fn run<T: Send + 'static>(
+        self: &Arc<Self>,
+        name: &'static str,
+        task: impl FnOnce() -> T + Send + 'static,
+    ) -> Result<T, String> {
+        let timeout = self.limit().task_timeout();
+        let (tx, rx) = mpsc::channel();
+        if !self.spawn(name, move || {
+            let _ = tx.send(task());
+        }) {
+            return Err(i18n::tr("error-background-work-off"));
+        }
+        rx.recv_timeout(timeout)
+            .map_err(|_| format!("{} did not finish in {:?}", name, timeout))
+    }
+
+    fn work(&self) {
+        let mut nice = self.limit().nice();
+        set_thread_nice(nice);
+
+        let mut queue = self.queue.lock();
+        loop {
+            let limit = self.limit();
+            // Fewer workers allowed since this one started
+            if queue.workers > limit.concurrency() {
+                break;
+            }
+            if limit.nice() != nice {
+                nice = limit.nice();
+                set_thread_nice(nice);
+            }
+            let Some(task) = queue.tasks.pop_front() else {
+                if self
+                    .available
+                    .wait_for(&mut queue, WORKER_IDLE_TIMEOUT)
+                    .timed_out()
+                    && queue.tasks.is_empty()
+                {
+                    break;
+                }
+                continue;
+            };
+
+            let timeout = limit.task_timeout();
+            if task.queued_at.elapsed() > timeout {
+                eprintln!(
+                    "[WorkerPool] Dropped {}, queued longer than {:?}",
+                    task.name, timeout
+                );
+                continue;
+            }
+            drop(queue);
+            // A panicking task must not take the worker, and its slot, with
+            // it. Only in debug builds: release builds set `panic = "abort"`,
+            // so there a panicking task aborts the whole app.
+            if panic::catch_unwind(AssertUnwindSafe(task.run)).is_err() {
+                eprintln!("[WorkerPool] {} panicked", task.name);
+            }
+            if task.queued_at.elapsed() > timeout {
+                eprintln!("[WorkerPool] {} took longer than {:?}", task.name, timeout);
+            }
+            queue = self.queue.lock();
+        }
+        queue.workers -= 1;
+    }
+}
+
+/// Renices the calling thread; on Linux the nice value is per thread
+fn set_thread_nice(nice: i32) {
+    #[cfg(target_os = "linux")]
+    // SAFETY: gettid takes no arguments and cannot fail, and setpriority
+    // only reads its integer arguments; neither touches memory of ours.
+    unsafe {
+        let tid = libc::syscall(libc::SYS_gettid) as libc::id_t;
+        if libc::setpriority(libc::PRIO_PROCESS, tid, nice) != 0 {
+            eprintln!("[WorkerPool] Failed to set worker nice value {}", nice);
+        }
+    }
+    #[cfg(not(target_os = "linux"))]
+    let _ = nice;
+}
+
+/// The pool shared by the app, at the "normal" limit until the settings say
+/// otherwise
+pub fn global() -> &'static Arc<WorkerPool> {
+    static POOL: OnceLock<Arc<WorkerPool>> = OnceLock::new();
+    POOL.get_or_init(|| WorkerPool::new(WorkerLimit::Normal))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn test_pool_respects_limit() {
+        let pool = WorkerPool::new(WorkerLimit::Low);
+        let running = Arc::new(AtomicUsize::new(0));
+        let most = Arc::new(AtomicUsize::new(0));
+
+        let receivers: Vec<_> = (0..3)
+            .map(|_| {
+                let (running, most) = (running.clone(), most.clone());
+                let (tx, rx) = mpsc::channel();
+                pool.spawn("test", move || {
+                    let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                    most.fetch_max(now, Ordering::SeqCst);
+                    std::thread::sleep(Duration::from_millis(20));
+                    running.fetch_sub(1, Ordering::SeqCst);
+                    let _ = tx.send(());
+                });
+                rx
+            })
+            .collect();
+        for rx in receivers {
+            rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        }
+        assert_eq!(most.load(Ordering::SeqCst), 1);
+        assert_eq!(pool.run("answer", || 42), Ok(42));
+
+        // The only worker keeps going after a task panics
+        pool.spawn("panics", || panic!("task failed"));
+        assert_eq!(pool.run("answer", || 42), Ok(42));
+        assert!(pool.queue.lock().workers <= 1);
+
+        pool.set_limit(WorkerLimit::Off);
+        assert!(!pool.spawn("test", || {}));
+        assert!(pool.run("answer", || 42).is_err());
+    }
+}
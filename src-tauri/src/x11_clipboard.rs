@@ -0,0 +1,83 @@
+//! X11 Clipboard Module
+//! Event-driven clipboard change notifications through the XFixes extension,
+//! so the watcher only reads the clipboard when its owner actually changes
+//! instead of waking up on a timer.
+
+use crate::clipboard_manager::SelectionKind;
+use x11rb::connection::Connection;
+use x11rb::protocol::xfixes::{ConnectionExt as XfixesConnectionExt, SelectionEventMask};
+use x11rb::protocol::xproto::{ConnectionExt as XprotoConnectionExt, CreateWindowAux, WindowClass};
+use x11rb::protocol::Event;
+use x11rb::COPY_DEPTH_FROM_PARENT;
+
+/// Calls `on_change` once at startup and then every time a client takes
+/// ownership of the given selection. Blocks for as long as the X
+/// connection is healthy; on error the caller should fall back to polling.
+pub fn run_listener<F: FnMut()>(selection: SelectionKind, mut on_change: F) -> Result<(), String> {
+    let (conn, screen_num) =
+        x11rb::connect(None).map_err(|e| format!("X11 connect failed: {}", e))?;
+
+    // The version must be negotiated before any other XFixes request
+    conn.xfixes_query_version(5, 0)
+        .map_err(|e| format!("XFixes unavailable: {}", e))?
+        .reply()
+        .map_err(|e| format!("XFixes unavailable: {}", e))?;
+
+    // Selection events are delivered to a window; an invisible one is enough
+    let root = conn.setup().roots[screen_num].root;
+    let window = conn
+        .generate_id()
+        .map_err(|e| format!("Failed to allocate window id: {}", e))?;
+    conn.create_window(
+        COPY_DEPTH_FROM_PARENT,
+        window,
+        root,
+        0,
+        0,
+        1,
+        1,
+        0,
+        WindowClass::INPUT_ONLY,
+        0,
+        &CreateWindowAux::new(),
+    )
+    .map_err(|e| format!("Failed to create listener window: {}", e))?;
+
+    let atom_name: &[u8] = match selection {
+        SelectionKind::Clipboard => b"CLIPBOARD",
+        SelectionKind::Primary => b"PRIMARY",
+    };
+    let selection_atom = conn
+        .intern_atom(false, atom_name)
+        .map_err(|e| format!("Failed to intern selection atom: {}", e))?
+        .reply()
+        .map_err(|e| format!("Failed to intern selection atom: {}", e))?
+        .atom;
+
+    conn.xfixes_select_selection_input(
+        window,
+        selection_atom,
+        SelectionEventMask::SET_SELECTION_OWNER,
+    )
+    .map_err(|e| format!("Failed to select selection input: {}", e))?;
+    conn.flush().map_err(|e| format!("Flush failed: {}", e))?;
+
+    eprintln!(
+        "[X11Clipboard] Listening for {:?} selection changes via XFixes",
+        selection
+    );
+
+    // Pick up whatever was copied before we started listening
+    on_change();
+
+    loop {
+        let event = conn
+            .wait_for_event()
+            .map_err(|e| format!("X11 connection lost: {}", e))?;
+        if let Event::XfixesSelectionNotify(notify) = event {
+            if notify.selection == selection_atom {
+                on_change();
+            }
+        }
+    }
+}
@@ -4,23 +4,112 @@ import { getCurrentWindow } from '@tauri-apps/api/window'
 import { listen } from '@tauri-apps/api/event'
 import { invoke } from '@tauri-apps/api/core'
 import { useClipboardHistory } from './hooks/useClipboardHistory'
+import { useSystemColorScheme } from './hooks/useSystemColorScheme'
 import { TabBar, TabBarRef } from './components/TabBar'
 import { DragHandle } from './components/DragHandle'
 import { EmojiPicker } from './components/EmojiPicker'
 import { GifPicker } from './components/GifPicker'
 import { KaomojiPicker } from './components/KaomojiPicker'
+import { StickerPicker } from './components/StickerPicker'
 import { SymbolPicker } from './components/SymbolPicker'
-import { calculateSecondaryOpacity, calculateTertiaryOpacity } from './utils/themeUtils'
-import type { ActiveTab, UserSettings } from './types/clipboard'
+import {
+  applyTheme,
+  calculateSecondaryOpacity,
+  calculateTertiaryOpacity,
+} from './utils/themeUtils'
+import type { ActiveTab, Theme, UserSettings } from './types/clipboard'
 import { ClipboardTab } from './components/ClipboardTab'
+import { PrimarySelectionTab } from './components/PrimarySelectionTab'
+import { PastePreviewModal } from './components/PastePreviewModal'
 
 const DEFAULT_SETTINGS: UserSettings = {
   theme_mode: 'system',
   dark_background_opacity: 0.7,
   light_background_opacity: 0.7,
+  theme: { preset: 'custom', accent_color: '#0078d4', corner_radius: 8 },
+  ui_density: 'comfortable',
+  font_scale: 1,
+  language: 'system',
   enable_smart_actions: true,
   enable_ui_polish: true,
   max_history_size: 50,
+  capture_text: true,
+  capture_images: true,
+  capture_files: true,
+  prune_missing_files: false,
+  capture_html: true,
+  text_retention_days: 0,
+  image_retention_days: 0,
+  file_retention_days: 0,
+  storage_backend: 'json',
+  background_work: 'normal',
+  image_compression: { format: 'png', jpeg_quality: 85, keep_originals: 5 },
+  memory_budget_mb: 256,
+  window_placement: 'auto',
+  open_on_monitor: 'any',
+  use_layer_shell: false,
+  pinned_git_enabled: false,
+  pinned_git_dir: '',
+  enable_primary_selection: false,
+  copy_on_select: false,
+  copy_on_select_delay_secs: 2,
+  sync_selections: false,
+  terminal_wm_classes: [],
+  terminal_paste_keys: 'ctrl+shift+v',
+  submit_keys: 'enter',
+  type_out_delay_ms: 15,
+  join_separator: ' ',
+  paste_profiles: [],
+  focus_strategies: [],
+  paste_strategies: [],
+  shell_actions: [],
+  translation: {
+    backend: 'libretranslate',
+    libretranslate_url: 'https://libretranslate.com',
+    libretranslate_api_key: '',
+    deepl_api_key: '',
+    target_language: 'en',
+  },
+  unit_system: 'system',
+  currency: '',
+  fetch_exchange_rates: false,
+  confirm_suspicious_paste: true,
+  global_shortcut: 'Super+V',
+  emoji_shortcut: 'Super+Period',
+  quick_paste_shortcuts: false,
+  vim_navigation: false,
+  keyboard_only: false,
+  keymap: {
+    move_down: 'j',
+    move_up: 'k',
+    jump_top: 'gg',
+    jump_bottom: 'G',
+    delete: 'dd',
+    paste: 'p',
+    search: '/',
+    paste_and_submit: 'P',
+    toggle_pin: 'm',
+    type_out: 't',
+    split: 's',
+    join_with_previous: 'J',
+    clean_hidden_chars: 'x',
+    copy_link: 'yl',
+    next_tab: 'gt',
+    previous_tab: 'gT',
+    search_gifs: 'gi',
+  },
+  auto_repair_shortcut: true,
+  show_tray_icon: true,
+  metrics_port: 0,
+  emoji_variants: { skin_tone: 'default', gender: 'neutral' },
+  gifs: {
+    provider: 'tenor',
+    tenor_api_key: '',
+    giphy_api_key: '',
+    self_hosted_url: '',
+    self_hosted_api_key: '',
+    cache_limit_mb: 200,
+  },
   custom_kaomojis: [],
 }
 
@@ -28,6 +117,7 @@ const DEFAULT_SETTINGS: UserSettings = {
  * Determines if dark mode should be active based on theme mode setting
  */
 function useThemeMode(themeMode: 'system' | 'dark' | 'light'): boolean {
+  const portalPrefersDark = useSystemColorScheme()
   const [systemPrefersDark, setSystemPrefersDark] = useState(() => {
     if (globalThis.matchMedia) {
       return globalThis.matchMedia('(prefers-color-scheme: dark)').matches
@@ -47,7 +137,7 @@ function useThemeMode(themeMode: 'system' | 'dark' | 'light'): boolean {
   // Determine actual dark mode based on theme setting
   if (themeMode === 'dark') return true
   if (themeMode === 'light') return false
-  return systemPrefersDark // 'system' mode
+  return portalPrefersDark ?? systemPrefersDark // 'system' mode
 }
 
 /**
@@ -81,6 +171,9 @@ function applyThemeClass(isDark: boolean) {
   }
 }
 
+/** Tabs that can be opened directly from outside the window */
+const OPENABLE_TABS: ActiveTab[] = ['clipboard', 'gifs', 'stickers', 'emoji', 'kaomoji', 'symbols']
+
 /**
  * Main Clipboard App Component
  */
@@ -88,14 +181,42 @@ function ClipboardApp() {
   const [activeTab, setActiveTab] = useState<ActiveTab>('clipboard')
   const [settings, setSettings] = useState<UserSettings>(DEFAULT_SETTINGS)
   const [settingsLoaded, setSettingsLoaded] = useState(false)
+  const [searchRequest, setSearchRequest] = useState<{ query: string } | null>(null)
+  const [gifSearchRequest, setGifSearchRequest] = useState<{ query: string } | null>(null)
+  const [focusRequest, setFocusRequest] = useState<{ id: string } | null>(null)
 
   const isDark = useThemeMode(settings.theme_mode)
   const opacity = isDark ? settings.dark_background_opacity : settings.light_background_opacity
   const secondaryOpacity = calculateSecondaryOpacity(opacity)
   const tertiaryOpacity = calculateTertiaryOpacity(opacity)
 
-  const { history, isLoading, clearHistory, deleteItem, togglePin, pasteItem } =
-    useClipboardHistory()
+  const {
+    history,
+    historyTotal,
+    hasMoreHistory,
+    loadMoreHistory,
+    isLoading,
+    clearHistory,
+    deleteItem,
+    togglePin,
+    pasteItem,
+    pasteAndSubmit,
+    pendingPaste,
+    confirmPendingPaste,
+    cancelPendingPaste,
+    pasteRecentItem,
+    typeOutItem,
+    splitItem,
+    joinWithPrevious,
+    cleanHiddenChars,
+    copyItemLink,
+    setItemTags,
+    fileItem,
+    runQuickAction,
+    runShellAction,
+    translateItem,
+  } = useClipboardHistory()
+  const pendingItem = pendingPaste?.item
 
   // Refs for focus management
   const tabBarRef = useRef<TabBarRef>(null)
@@ -116,15 +237,23 @@ function ClipboardApp() {
         setSettingsLoaded(true)
       })
 
+    invoke<Theme>('get_theme').then(applyTheme).catch(console.error)
+    const unlistenThemePromise = listen<Theme>('theme-changed', (event) => applyTheme(event.payload))
+
     // Listen for settings changes from the settings window
     const unlistenPromise = listen<UserSettings>('app-settings-changed', (event) => {
       const newSettings = event.payload
       setSettings(newSettings)
       applyBackgroundOpacity(newSettings)
+      // Leave the selections pane if it was just disabled
+      if (!newSettings.enable_primary_selection) {
+        setActiveTab((tab) => (tab === 'selections' ? 'clipboard' : tab))
+      }
     })
 
     return () => {
       unlistenPromise.then((unlisten) => unlisten())
+      unlistenThemePromise.then((unlisten) => unlisten())
     }
   }, [])
 
@@ -150,6 +279,19 @@ function ClipboardApp() {
     return () => globalThis.removeEventListener('keydown', handleKeyDown)
   }, [])
 
+  // Ctrl+1..9 pastes the Nth most recent item
+  useEffect(() => {
+    const handleKeyDown = (e: KeyboardEvent) => {
+      if (e.ctrlKey && !e.altKey && !e.shiftKey && /^[1-9]$/.test(e.key)) {
+        e.preventDefault()
+        pasteRecentItem(Number(e.key))
+      }
+    }
+
+    globalThis.addEventListener('keydown', handleKeyDown)
+    return () => globalThis.removeEventListener('keydown', handleKeyDown)
+  }, [pasteRecentItem])
+
   // Use refs to store current values for the focus handler (to avoid re-registering listener)
   const activeTabRef = useRef(activeTab)
 
@@ -181,6 +323,38 @@ function ClipboardApp() {
     }
   }, []) // Empty dependency array - listener is registered once
 
+  // Open on the tab, search or item requested by a shortcut, the command line or a link
+  useEffect(() => {
+    const unlistenOpenTab = listen<ActiveTab>('open-tab', (event) => {
+      if (OPENABLE_TABS.includes(event.payload)) {
+        setActiveTab(event.payload)
+      }
+    })
+
+    const unlistenOpenSearch = listen<string>('open-search', (event) => {
+      setActiveTab('clipboard')
+      setSearchRequest({ query: event.payload })
+    })
+
+    const unlistenOpenGifSearch = listen<string>('open-gif-search', (event) => {
+      setActiveTab('gifs')
+      setGifSearchRequest({ query: event.payload })
+    })
+
+    // clip://item/<id> links
+    const unlistenFocusItem = listen<string>('focus-item', (event) => {
+      setActiveTab('clipboard')
+      setFocusRequest({ id: event.payload })
+    })
+
+    return () => {
+      unlistenOpenTab.then((unlisten) => unlisten())
+      unlistenOpenSearch.then((unlisten) => unlisten())
+      unlistenOpenGifSearch.then((unlisten) => unlisten())
+      unlistenFocusItem.then((unlisten) => unlisten())
+    }
+  }, [])
+
   // Handle tab change
   const handleTabChange = useCallback((tab: ActiveTab) => {
     setActiveTab(tab)
@@ -201,6 +375,9 @@ function ClipboardApp() {
         return (
           <ClipboardTab
             history={history}
+            historyTotal={historyTotal}
+            hasMoreHistory={hasMoreHistory}
+            loadMoreHistory={loadMoreHistory}
             isLoading={isLoading}
             isDark={isDark}
             tertiaryOpacity={tertiaryOpacity}
@@ -209,16 +386,56 @@ function ClipboardApp() {
             deleteItem={deleteItem}
             togglePin={togglePin}
             onPaste={pasteItem}
+            onPasteAndSubmit={pasteAndSubmit}
+            onTypeOut={typeOutItem}
+            onSplit={splitItem}
+            onJoinWithPrevious={joinWithPrevious}
+            onCleanHiddenChars={cleanHiddenChars}
+            onCopyLink={copyItemLink}
+            onSetTags={setItemTags}
+            onFileInto={fileItem}
+            onQuickAction={runQuickAction}
+            onShellAction={runShellAction}
+            onTranslate={translateItem}
             settings={settings}
             tabBarRef={tabBarRef}
+            searchRequest={searchRequest}
+            focusRequest={focusRequest}
+          />
+        )
+
+      case 'selections':
+        return (
+          <PrimarySelectionTab
+            isDark={isDark}
+            tertiaryOpacity={tertiaryOpacity}
+            secondaryOpacity={secondaryOpacity}
+            settings={settings}
           />
         )
 
       case 'emoji':
-        return <EmojiPicker isDark={isDark} opacity={secondaryOpacity} />
+        return (
+          <EmojiPicker
+            isDark={isDark}
+            opacity={secondaryOpacity}
+            emojiVariants={settings.emoji_variants}
+            language={settings.language}
+          />
+        )
 
       case 'gifs':
-        return <GifPicker isDark={isDark} opacity={secondaryOpacity} />
+        return (
+          <GifPicker
+            isDark={isDark}
+            opacity={secondaryOpacity}
+            searchRequest={gifSearchRequest}
+            provider={settings.gifs.provider}
+          />
+        )
+
+      case 'stickers':
+        return <StickerPicker isDark={isDark} opacity={secondaryOpacity} />
 
       case 'kaomoji':
         return (
@@ -263,6 +480,7 @@ function ClipboardApp() {
         onTabChange={handleTabChange}
         isDark={isDark}
         tertiaryOpacity={tertiaryOpacity}
+        showSelectionsTab={settings.enable_primary_selection}
       />
 
       {/* Scrollable content area */}
@@ -270,9 +488,10 @@ function ClipboardApp() {
         ref={contentContainerRef}
         className={clsx(
           'flex-1',
-          // Only use scrollbar for non-emoji/gif/kaomoji tabs, they have their own virtualized scrolling or containers
+          // Only use scrollbar for non-emoji/gif/sticker/kaomoji tabs, they have their own virtualized scrolling or containers
           activeTab === 'emoji' ||
             activeTab === 'gifs' ||
+            activeTab === 'stickers' ||
             activeTab === 'kaomoji' ||
             activeTab === 'symbols'
             ? 'overflow-hidden'
@@ -281,6 +500,21 @@ function ClipboardApp() {
       >
         {renderContent()}
       </div>
+
+      {pendingPaste && pendingItem && pendingItem.content.type !== 'Image' && (
+        <PastePreviewModal
+          text={
+            pendingItem.content.type === 'RichText'
+              ? pendingItem.content.data.plain
+              : pendingItem.content.data
+          }
+          sourceApp={pendingItem.source_app}
+          warning={pendingPaste.warning}
+          isDark={isDark}
+          onConfirm={confirmPendingPaste}
+          onCancel={cancelPendingPaste}
+        />
+      )}
     </div>
   )
 }
@@ -5,19 +5,341 @@ import { listen } from '@tauri-apps/api/event'
 import { emit } from '@tauri-apps/api/event'
 import { clsx } from 'clsx'
 
-import type { UserSettings, CustomKaomoji, BooleanSettingKey } from './types/clipboard'
+import type {
+  UserSettings,
+  CustomKaomoji,
+  BooleanSettingKey,
+  StorageBackend,
+  BackgroundWork,
+  StoredImageFormat,
+  SkinTone,
+  EmojiGender,
+  WindowPlacement,
+  DockEdge,
+  OpenOnMonitor,
+  Language,
+  UiDensity,
+  UnitSystem,
+  Theme,
+  ThemePreset,
+  ThemeSettings,
+  TerminalPasteKeys,
+  SubmitKeys,
+  PasteFormat,
+  PasteProfile,
+  KeyAction,
+} from './types/clipboard'
 import { FeaturesSection } from './components/FeaturesSection'
+import { InsightsSection } from './components/InsightsSection'
+import { ShellActionsSection } from './components/ShellActionsSection'
+import { CustomEmojiSection } from './components/CustomEmojiSection'
+import { EmojiDatasetSetting } from './components/EmojiDatasetSetting'
+import { GifProviderSection } from './components/GifProviderSection'
+import { SnippetPacksSection } from './components/SnippetPacksSection'
+import { StickerPacksSection } from './components/StickerPacksSection'
+import { TranslationSection } from './components/TranslationSection'
+import { Switch } from './components/Switch'
+import { useSystemColorScheme } from './hooks/useSystemColorScheme'
+import { applyTheme } from './utils/themeUtils'
 
 const MIN_HISTORY_SIZE = 1
 const MAX_HISTORY_SIZE = 100_000
+const MAX_RETENTION_DAYS = 3650
+const MIN_TYPE_DELAY_MS = 1
+const MAX_TYPE_DELAY_MS = 500
+const MAX_COPY_ON_SELECT_DELAY_SECS = 60
+
+type RetentionKey = 'text_retention_days' | 'image_retention_days' | 'file_retention_days'
+
+const RETENTION_FIELDS: { key: RetentionKey; label: string }[] = [
+  { key: 'text_retention_days', label: 'Keep Text For' },
+  { key: 'image_retention_days', label: 'Keep Images For' },
+  { key: 'file_retention_days', label: 'Keep Files For' },
+]
+
+const CAPTURE_FIELDS: { key: BooleanSettingKey; label: string; desc: string }[] = [
+  { key: 'capture_text', label: 'Record Text', desc: 'Plain and formatted text' },
+  { key: 'capture_images', label: 'Record Images', desc: 'Screenshots and copied pictures' },
+  { key: 'capture_files', label: 'Record Files', desc: 'Files copied in a file manager' },
+  {
+    key: 'prune_missing_files',
+    label: 'Remove Missing Files',
+    desc: 'Drop copied files that were moved or deleted (otherwise they are marked)',
+  },
+  { key: 'capture_html', label: 'Keep Formatting', desc: 'Store the HTML of rich text' },
+  {
+    key: 'copy_on_select',
+    label: 'Copy on Select',
+    desc: 'Add highlighted text to history once you settle on a selection',
+  },
+  {
+    key: 'sync_selections',
+    label: 'Sync Clipboard and Selection',
+    desc: 'Copying also sets the middle-click selection, and selecting also copies',
+  },
+]
+
+const STORAGE_BACKEND_OPTIONS: { value: StorageBackend; label: string }[] = [
+  { value: 'json', label: 'JSON File' },
+  { value: 'flat_files', label: 'One File per Item' },
+  { value: 'sqlite', label: 'SQLite' },
+  { value: 'sled', label: 'sled' },
+  { value: 'event_log', label: 'Event Log' },
+  { value: 'memory', label: 'Memory Only' },
+]
+
+const BACKGROUND_WORK_OPTIONS: { value: BackgroundWork; label: string }[] = [
+  { value: 'off', label: 'Off' },
+  { value: 'low', label: 'Low' },
+  { value: 'normal', label: 'Normal' },
+]
+
+const STORED_IMAGE_FORMAT_OPTIONS: { value: StoredImageFormat; label: string }[] = [
+  { value: 'png', label: 'Off (As Copied)' },
+  { value: 'webp', label: 'WebP (Lossless)' },
+  { value: 'jpeg', label: 'JPEG' },
+]
+
+const SKIN_TONE_OPTIONS: { value: SkinTone; label: string }[] = [
+  { value: 'default', label: '✋ Default' },
+  { value: 'light', label: '✋🏻 Light' },
+  { value: 'medium_light', label: '✋🏼 Medium-Light' },
+  { value: 'medium', label: '✋🏽 Medium' },
+  { value: 'medium_dark', label: '✋🏾 Medium-Dark' },
+  { value: 'dark', label: '✋🏿 Dark' },
+]
+
+const EMOJI_GENDER_OPTIONS: { value: EmojiGender; label: string }[] = [
+  { value: 'neutral', label: 'Person' },
+  { value: 'female', label: 'Woman' },
+  { value: 'male', label: 'Man' },
+]
+
+const WINDOW_PLACEMENT_OPTIONS: { value: WindowPlacement; label: string }[] = [
+  { value: 'auto', label: 'Automatic' },
+  { value: 'cursor', label: 'Near the Pointer' },
+  { value: 'remembered', label: 'Where I Left It' },
+  { value: 'docked', label: 'Docked Sidebar' },
+]
+
+const DOCK_EDGE_OPTIONS: { value: DockEdge; label: string }[] = [
+  { value: 'left', label: 'Left' },
+  { value: 'right', label: 'Right' },
+]
+
+const THEME_PRESET_OPTIONS: { value: ThemePreset; label: string }[] = [
+  { value: 'custom', label: 'Custom' },
+  { value: 'windows11', label: 'Windows 11' },
+  { value: 'midnight', label: 'Midnight' },
+  { value: 'sunset', label: 'Sunset' },
+  { value: 'forest', label: 'Forest' },
+]
+
+const UI_DENSITY_OPTIONS: { value: UiDensity; label: string }[] = [
+  { value: 'comfortable', label: 'Comfortable' },
+  { value: 'compact', label: 'Compact' },
+]
+
+const LANGUAGE_OPTIONS: { value: Language; label: string }[] = [
+  { value: 'system', label: 'System' },
+  { value: 'en', label: 'English' },
+  { value: 'pt', label: 'Português' },
+  { value: 'es', label: 'Español' },
+  { value: 'de', label: 'Deutsch' },
+  { value: 'fr', label: 'Français' },
+]
+
+const UNIT_SYSTEM_OPTIONS: { value: UnitSystem; label: string }[] = [
+  { value: 'system', label: 'System' },
+  { value: 'metric', label: 'Metric' },
+  { value: 'imperial', label: 'Imperial' },
+]
+
+/** Currencies with exchange rates, see unit_conversion.rs */
+const CURRENCY_OPTIONS = [
+  'USD',
+  'EUR',
+  'GBP',
+  'JPY',
+  'BRL',
+  'CAD',
+  'AUD',
+  'CHF',
+  'CNY',
+  'INR',
+  'MXN',
+  'SEK',
+  'NOK',
+  'DKK',
+  'PLN',
+  'NZD',
+]
+
+const OPEN_ON_MONITOR_OPTIONS: { value: OpenOnMonitor; label: string }[] = [
+  { value: 'any', label: 'Any' },
+  { value: 'pointer', label: 'With the Pointer' },
+  { value: 'focused_window', label: 'With the Focused Window' },
+]
+
+const TERMINAL_PASTE_OPTIONS: { value: TerminalPasteKeys; label: string }[] = [
+  { value: 'ctrl+shift+v', label: 'Ctrl+Shift+V' },
+  { value: 'shift+insert', label: 'Shift+Insert' },
+]
+
+const SUBMIT_KEY_OPTIONS: { value: SubmitKeys; label: string }[] = [
+  { value: 'enter', label: 'Enter' },
+  { value: 'ctrl+enter', label: 'Ctrl+Enter' },
+  { value: 'shift+enter', label: 'Shift+Enter' },
+]
+
+const PASTE_FORMAT_OPTIONS: { value: PasteFormat; label: string }[] = [
+  { value: 'as_is', label: 'As copied' },
+  { value: 'plain_text', label: 'Plain text' },
+  { value: 'code_block', label: 'Code block' },
+  { value: 'html', label: 'HTML' },
+]
+
+/** Strategy names understood by the backend, in their default order */
+const STRATEGY_FIELDS: {
+  key: 'focus_strategies' | 'paste_strategies'
+  label: string
+  names: string[]
+}[] = [
+  {
+    key: 'focus_strategies',
+    label: 'Focus Restore Order',
+    names: ['hyprland', 'sway', 'kwin', 'wlr_foreign_toplevel', 'x11'],
+  },
+  {
+    key: 'paste_strategies',
+    label: 'Paste Method Order',
+    names: ['xdotool', 'xtest', 'wtype', 'ydotool', 'uinput'],
+  },
+]
+
+/** Comma-separated values from a text field */
+const parseList = (value: string) =>
+  value
+    .split(',')
+    .map((id) => id.trim())
+    .filter(Boolean)
+
+const JOIN_SEPARATOR_OPTIONS: { value: string; label: string }[] = [
+  { value: ' ', label: 'Space' },
+  { value: '\n', label: 'New line' },
+  { value: '\n\n', label: 'Blank line' },
+  { value: ', ', label: 'Comma' },
+  { value: '', label: 'Nothing' },
+]
+
+const KEYMAP_ACTIONS: { action: KeyAction; label: string }[] = [
+  { action: 'move_down', label: 'Move down' },
+  { action: 'move_up', label: 'Move up' },
+  { action: 'jump_top', label: 'Jump to top' },
+  { action: 'jump_bottom', label: 'Jump to bottom' },
+  { action: 'delete', label: 'Delete' },
+  { action: 'paste', label: 'Paste' },
+  { action: 'search', label: 'Search' },
+  { action: 'paste_and_submit', label: 'Paste and submit' },
+  { action: 'toggle_pin', label: 'Pin or unpin' },
+  { action: 'type_out', label: 'Type it out' },
+  { action: 'split', label: 'Split' },
+  { action: 'join_with_previous', label: 'Join with previous' },
+  { action: 'clean_hidden_chars', label: 'Clean hidden characters' },
+  { action: 'copy_link', label: 'Copy link' },
+  { action: 'next_tab', label: 'Next tab' },
+  { action: 'previous_tab', label: 'Previous tab' },
+  { action: 'search_gifs', label: 'Search GIFs' },
+]
 
 const DEFAULT_SETTINGS: UserSettings = {
   theme_mode: 'system',
   dark_background_opacity: 0.7,
   light_background_opacity: 0.7,
+  theme: { preset: 'custom', accent_color: '#0078d4', corner_radius: 8 },
+  ui_density: 'comfortable',
+  font_scale: 1,
+  language: 'system',
   enable_smart_actions: true,
   enable_ui_polish: true,
   max_history_size: 50,
+  capture_text: true,
+  capture_images: true,
+  capture_files: true,
+  prune_missing_files: false,
+  capture_html: true,
+  text_retention_days: 0,
+  image_retention_days: 0,
+  file_retention_days: 0,
+  storage_backend: 'json',
+  background_work: 'normal',
+  image_compression: { format: 'png', jpeg_quality: 85, keep_originals: 5 },
+  memory_budget_mb: 256,
+  window_placement: 'auto',
+  open_on_monitor: 'any',
+  use_layer_shell: false,
+  pinned_git_enabled: false,
+  pinned_git_dir: '',
+  enable_primary_selection: false,
+  copy_on_select: false,
+  copy_on_select_delay_secs: 2,
+  sync_selections: false,
+  terminal_wm_classes: [],
+  terminal_paste_keys: 'ctrl+shift+v',
+  submit_keys: 'enter',
+  type_out_delay_ms: 15,
+  join_separator: ' ',
+  paste_profiles: [],
+  focus_strategies: [],
+  paste_strategies: [],
+  shell_actions: [],
+  translation: {
+    backend: 'libretranslate',
+    libretranslate_url: 'https://libretranslate.com',
+    libretranslate_api_key: '',
+    deepl_api_key: '',
+    target_language: 'en',
+  },
+  unit_system: 'system',
+  currency: '',
+  fetch_exchange_rates: false,
+  confirm_suspicious_paste: true,
+  global_shortcut: 'Super+V',
+  emoji_shortcut: 'Super+Period',
+  quick_paste_shortcuts: false,
+  vim_navigation: false,
+  keyboard_only: false,
+  keymap: {
+    move_down: 'j',
+    move_up: 'k',
+    jump_top: 'gg',
+    jump_bottom: 'G',
+    delete: 'dd',
+    paste: 'p',
+    search: '/',
+    paste_and_submit: 'P',
+    toggle_pin: 'm',
+    type_out: 't',
+    split: 's',
+    join_with_previous: 'J',
+    clean_hidden_chars: 'x',
+    copy_link: 'yl',
+    next_tab: 'gt',
+    previous_tab: 'gT',
+    search_gifs: 'gi',
+  },
+  auto_repair_shortcut: true,
+  show_tray_icon: true,
+  metrics_port: 0,
+  emoji_variants: { skin_tone: 'default', gender: 'neutral' },
+  gifs: {
+    provider: 'tenor',
+    tenor_api_key: '',
+    giphy_api_key: '',
+    self_hosted_url: '',
+    self_hosted_api_key: '',
+    cache_limit_mb: 200,
+  },
   custom_kaomojis: [],
 }
 
@@ -27,6 +349,7 @@ type ThemeMode = 'system' | 'dark' | 'light'
  * Determines if dark mode should be active based on theme mode setting
  */
 function useThemeMode(themeMode: ThemeMode): boolean {
+  const portalPrefersDark = useSystemColorScheme()
   const [systemPrefersDark, setSystemPrefersDark] = useState(() => {
     if (globalThis.matchMedia) {
       return globalThis.matchMedia('(prefers-color-scheme: dark)').matches
@@ -45,7 +368,7 @@ function useThemeMode(themeMode: ThemeMode): boolean {
 
   if (themeMode === 'dark') return true
   if (themeMode === 'light') return false
-  return systemPrefersDark
+  return portalPrefersDark ?? systemPrefersDark
 }
 
 // --- Icons Components ---
@@ -128,9 +451,13 @@ function SettingsApp() {
   const [isLoading, setIsLoading] = useState(true)
   const [isSaving, setIsSaving] = useState(false)
   const [saveMessage, setSaveMessage] = useState<string | null>(null)
+  const [dockEdge, setDockEdge] = useState<DockEdge>('right')
+  const [monitorNames, setMonitorNames] = useState<string[]>([])
 
   // Custom Kaomoji State
   const [newKaomoji, setNewKaomoji] = useState('')
+  const [newProfileApps, setNewProfileApps] = useState('')
+  const [newProfileFormat, setNewProfileFormat] = useState<PasteFormat>('plain_text')
 
   // Apply theme to settings window itself
   const isDark = useThemeMode(settings.theme_mode)
@@ -154,6 +481,9 @@ function SettingsApp() {
         console.error('Failed to load settings:', err)
         setIsLoading(false)
       })
+    invoke<DockEdge>('get_dock_edge').then(setDockEdge).catch(console.error)
+    invoke<string[]>('get_monitor_names').then(setMonitorNames).catch(console.error)
+    invoke<Theme>('get_theme').then(applyTheme).catch(console.error)
 
     // Show the main clipboard window for live preview
     const mainWindow = new Window('main')
@@ -170,15 +500,36 @@ function SettingsApp() {
     const unlistenSettingsPromise = listen<UserSettings>('app-settings-changed', (event) => {
       setSettings(event.payload)
     })
+    // Presets are applied by the backend, so take its values
+    const unlistenThemePromise = listen<Theme>('theme-changed', (event) => {
+      const theme = event.payload
+      applyTheme(theme)
+      setSettings((prev) => ({
+        ...prev,
+        theme: {
+          preset: theme.preset,
+          accent_color: theme.accent_color,
+          corner_radius: theme.corner_radius,
+        },
+        dark_background_opacity: theme.dark_background_opacity,
+        light_background_opacity: theme.light_background_opacity,
+      }))
+    })
 
     // Hide main window when settings window closes
     return () => {
       mainWindow.hide().catch(console.error)
       unlistenClosePromise.then((unlisten) => unlisten())
       unlistenSettingsPromise.then((unlisten) => unlisten())
+      unlistenThemePromise.then((unlisten) => unlisten())
     }
   }, [])
 
+  const changeDockEdge = (edge: DockEdge) => {
+    setDockEdge(edge)
+    invoke('set_dock_edge', { edge }).catch(console.error)
+  }
+
   // Save settings with debounce-like behavior
   const saveSettings = useCallback(async (newSettings: UserSettings) => {
     setIsSaving(true)
@@ -196,6 +547,17 @@ function SettingsApp() {
     }
   }, [])
 
+  // Leaves the saved hotkeys alone; they come back on the next start
+  const removeDesktopShortcuts = useCallback(async () => {
+    try {
+      setSaveMessage(await invoke<string>('unregister_de_shortcut'))
+    } catch (err) {
+      console.error('Failed to remove shortcuts:', err)
+      setSaveMessage('Error removing shortcuts')
+    }
+    setTimeout(() => setSaveMessage(null), 3000)
+  }, [])
+
   // Centralized settings update helper
   const updateSettings = useCallback(
     (partial: Partial<UserSettings>) => {
@@ -215,12 +577,38 @@ function SettingsApp() {
 
   // Handle dark opacity change (visual only, no disk I/O)
   const handleDarkOpacityChange = (value: number) => {
-    setSettings((prev) => ({ ...prev, dark_background_opacity: value }))
+    setSettings((prev) => ({
+      ...prev,
+      dark_background_opacity: value,
+      theme: { ...prev.theme, preset: 'custom' },
+    }))
   }
 
   // Handle light opacity change (visual only, no disk I/O)
   const handleLightOpacityChange = (value: number) => {
-    setSettings((prev) => ({ ...prev, light_background_opacity: value }))
+    setSettings((prev) => ({
+      ...prev,
+      light_background_opacity: value,
+      theme: { ...prev.theme, preset: 'custom' },
+    }))
+  }
+
+  // Editing a value by hand leaves the preset
+  const updateTheme = (partial: Partial<ThemeSettings>) => {
+    updateSettings({ theme: { ...settings.theme, preset: 'custom', ...partial } })
+  }
+
+  // Handle corner radius change (visual only, no disk I/O)
+  const handleCornerRadiusChange = (value: number) => {
+    setSettings((prev) => ({
+      ...prev,
+      theme: { ...prev.theme, preset: 'custom', corner_radius: value },
+    }))
+  }
+
+  // Handle font scale change (visual only, no disk I/O)
+  const handleFontScaleChange = (value: number) => {
+    setSettings((prev) => ({ ...prev, font_scale: value }))
   }
 
   // Commit opacity changes to disk (called on mouseUp/touchEnd)
@@ -257,6 +645,33 @@ function SettingsApp() {
     [settings.custom_kaomojis, updateSettings]
   )
 
+  // Paste Profile Handlers
+  const addPasteProfile = useCallback(() => {
+    const appIds = parseList(newProfileApps)
+    if (appIds.length === 0) return
+
+    const profile: PasteProfile = { name: appIds[0], app_ids: appIds, format: newProfileFormat }
+    updateSettings({ paste_profiles: [...settings.paste_profiles, profile] })
+    setNewProfileApps('')
+  }, [newProfileApps, newProfileFormat, settings.paste_profiles, updateSettings])
+
+  const updatePasteProfileAt = useCallback(
+    (index: number, partial: Partial<PasteProfile>) => {
+      const newList = settings.paste_profiles.map((profile, i) =>
+        i === index ? { ...profile, ...partial } : profile
+      )
+      updateSettings({ paste_profiles: newList })
+    },
+    [settings.paste_profiles, updateSettings]
+  )
+
+  const removePasteProfileAt = useCallback(
+    (index: number) => {
+      updateSettings({ paste_profiles: settings.paste_profiles.filter((_, i) => i !== index) })
+    },
+    [settings.paste_profiles, updateSettings]
+  )
+
   // Handle window close
   const handleClose = async () => {
     try {
@@ -415,6 +830,105 @@ function SettingsApp() {
               </button>
             ))}
           </div>
+
+          <div className="flex justify-between items-center mt-5">
+            <div>
+              <label htmlFor="language" className="text-sm font-medium">
+                Language
+              </label>
+              <p className={clsx('text-xs mt-0.5', isDark ? 'text-gray-400' : 'text-gray-500')}>
+                Item ages, setup instructions and messages; System follows your locale
+              </p>
+            </div>
+            <select
+              id="language"
+              value={settings.language}
+              onChange={(e) => updateSettings({ language: e.target.value as Language })}
+              className={clsx(
+                'px-2 py-1 text-sm border rounded-md focus:outline-none focus:ring-2 focus:ring-win11-bg-accent/50',
+                isDark
+                  ? 'bg-white/5 border-white/10 text-white'
+                  : 'bg-gray-50 border-gray-200 text-gray-900'
+              )}
+            >
+              {LANGUAGE_OPTIONS.map(({ value, label }) => (
+                <option key={value} value={value}>
+                  {label}
+                </option>
+              ))}
+            </select>
+          </div>
+
+          <div className="flex justify-between items-center mt-5">
+            <div>
+              <label htmlFor="unit_system" className="text-sm font-medium">
+                Units
+              </label>
+              <p className={clsx('text-xs mt-0.5', isDark ? 'text-gray-400' : 'text-gray-500')}>
+                Quantities like 72°F or 13 mi in items are converted into these
+              </p>
+            </div>
+            <select
+              id="unit_system"
+              value={settings.unit_system}
+              onChange={(e) => updateSettings({ unit_system: e.target.value as UnitSystem })}
+              className={clsx(
+                'px-2 py-1 text-sm border rounded-md focus:outline-none focus:ring-2 focus:ring-win11-bg-accent/50',
+                isDark
+                  ? 'bg-white/5 border-white/10 text-white'
+                  : 'bg-gray-50 border-gray-200 text-gray-900'
+              )}
+            >
+              {UNIT_SYSTEM_OPTIONS.map(({ value, label }) => (
+                <option key={value} value={value}>
+                  {label}
+                </option>
+              ))}
+            </select>
+          </div>
+
+          <div className="flex justify-between items-center mt-5">
+            <div>
+              <label htmlFor="currency" className="text-sm font-medium">
+                Currency
+              </label>
+              <p className={clsx('text-xs mt-0.5', isDark ? 'text-gray-400' : 'text-gray-500')}>
+                Prices in other currencies are converted at the ECB reference rates, if fetched
+              </p>
+            </div>
+            <select
+              id="currency"
+              value={settings.currency}
+              onChange={(e) => updateSettings({ currency: e.target.value })}
+              className={clsx(
+                'px-2 py-1 text-sm border rounded-md focus:outline-none focus:ring-2 focus:ring-win11-bg-accent/50',
+                isDark
+                  ? 'bg-white/5 border-white/10 text-white'
+                  : 'bg-gray-50 border-gray-200 text-gray-900'
+              )}
+            >
+              <option value="">System</option>
+              {CURRENCY_OPTIONS.map((code) => (
+                <option key={code} value={code}>
+                  {code}
+                </option>
+              ))}
+            </select>
+          </div>
+
+          <div className="flex justify-between items-center mt-5">
+            <div>
+              <div className="text-sm font-medium">Fetch Exchange Rates</div>
+              <p className={clsx('text-xs mt-0.5', isDark ? 'text-gray-400' : 'text-gray-500')}>
+                Downloads rates from frankfurter.app to convert prices; units convert offline
+              </p>
+            </div>
+            <Switch
+              checked={settings.fetch_exchange_rates}
+              onChange={() => handleToggle('fetch_exchange_rates')}
+              isDark={isDark}
+            />
+          </div>
         </section>
 
         {/* Transparency Section */}
@@ -492,7 +1006,7 @@ function SettingsApp() {
           </div>
         </section>
 
-        {/* History Settings Section */}
+        {/* Accent Section */}
         <section
           className={clsx(
             'rounded-xl border shadow-sm overflow-hidden',
@@ -500,51 +1014,157 @@ function SettingsApp() {
           )}
         >
           <div className="p-6 border-b border-inherit">
-            <h2 className="text-base font-semibold mb-1">History Settings</h2>
+            <h2 className="text-base font-semibold mb-1">Accent and Shape</h2>
             <p className={clsx('text-xs', isDark ? 'text-gray-400' : 'text-gray-500')}>
-              Configure clipboard history behavior
+              Pick a preset, or set the accent color and corner radius yourself
             </p>
           </div>
 
-          <div className="p-6 space-y-4">
+          <div className="p-6 space-y-6">
+            <div className="flex justify-between items-center">
+              <label htmlFor="theme-preset" className="text-sm font-medium">
+                Preset
+              </label>
+              <select
+                id="theme-preset"
+                value={settings.theme.preset}
+                onChange={(e) =>
+                  updateSettings({
+                    theme: { ...settings.theme, preset: e.target.value as ThemePreset },
+                  })
+                }
+                className={clsx(
+                  'px-2 py-1 text-sm border rounded-md focus:outline-none focus:ring-2 focus:ring-win11-bg-accent/50',
+                  isDark
+                    ? 'bg-white/5 border-white/10 text-white'
+                    : 'bg-gray-50 border-gray-200 text-gray-900'
+                )}
+              >
+                {THEME_PRESET_OPTIONS.map(({ value, label }) => (
+                  <option key={value} value={value}>
+                    {label}
+                  </option>
+                ))}
+              </select>
+            </div>
+
+            <div className="flex justify-between items-center">
+              <label htmlFor="accent-color" className="text-sm font-medium">
+                Accent Color
+              </label>
+              <input
+                id="accent-color"
+                type="color"
+                value={settings.theme.accent_color}
+                onChange={(e) => updateTheme({ accent_color: e.target.value })}
+                className="w-10 h-7 rounded cursor-pointer bg-transparent"
+              />
+            </div>
+
+            <div className="space-y-4">
+              <div className="flex justify-between items-center">
+                <label htmlFor="corner-radius" className="text-sm font-medium">
+                  Corner Radius
+                </label>
+                <div
+                  className={clsx(
+                    'px-2 py-1 rounded text-xs font-mono font-medium',
+                    isDark ? 'bg-black/20' : 'bg-gray-100'
+                  )}
+                >
+                  {settings.theme.corner_radius}px
+                </div>
+              </div>
+              <input
+                id="corner-radius"
+                type="range"
+                min="0"
+                max="24"
+                step="1"
+                value={settings.theme.corner_radius}
+                onChange={(e) => handleCornerRadiusChange(Number.parseInt(e.target.value, 10))}
+                onMouseUp={commitOpacityChange}
+                onTouchEnd={commitOpacityChange}
+                className="w-full h-1.5 bg-gray-200 rounded-lg appearance-none cursor-pointer dark:bg-gray-700 accent-win11-bg-accent"
+              />
+            </div>
+          </div>
+        </section>
+
+        {/* Layout Section */}
+        <section
+          className={clsx(
+            'rounded-xl border shadow-sm overflow-hidden',
+            isDark ? 'bg-win11-bg-secondary border-white/5' : 'bg-white border-gray-200/60'
+          )}
+        >
+          <div className="p-6 border-b border-inherit">
+            <h2 className="text-base font-semibold mb-1">Layout</h2>
+            <p className={clsx('text-xs', isDark ? 'text-gray-400' : 'text-gray-500')}>
+              Fit more items on screen, or make text easier to read
+            </p>
+          </div>
+
+          <div className="p-6 space-y-6">
             <div className="flex justify-between items-center">
               <div>
-                <label htmlFor="max-history" className="text-sm font-medium">
-                  Maximum History Size
+                <label htmlFor="ui-density" className="text-sm font-medium">
+                  Density
                 </label>
                 <p className={clsx('text-xs mt-0.5', isDark ? 'text-gray-400' : 'text-gray-500')}>
-                  Number of clipboard items to keep ({MIN_HISTORY_SIZE} -{' '}
-                  {MAX_HISTORY_SIZE.toLocaleString()})
+                  Compact shows smaller rows and opens a smaller window until you resize it
                 </p>
               </div>
-              <input
-                id="max-history"
-                type="number"
-                min={MIN_HISTORY_SIZE}
-                max={MAX_HISTORY_SIZE}
-                value={settings.max_history_size}
-                onChange={(e) => {
-                  const raw = e.target.value
-                  const parsed = Number.parseInt(raw, 10)
-                  // If parsing fails (e.g. empty input), preserve the current setting
-                  // instead of jumping to the maximum value.
-                  const safe = Number.isNaN(parsed) ? settings.max_history_size : parsed
-                  const value = Math.max(MIN_HISTORY_SIZE, Math.min(MAX_HISTORY_SIZE, safe))
-                  updateSettings({ max_history_size: value })
-                }}
+              <select
+                id="ui-density"
+                value={settings.ui_density}
+                onChange={(e) => updateSettings({ ui_density: e.target.value as UiDensity })}
                 className={clsx(
-                  'w-28 text-right font-mono border rounded-md transition-all focus:outline-none focus:ring-2 focus:ring-win11-bg-accent/50',
-                  'input-number-compact no-number-spinner',
+                  'px-2 py-1 text-sm border rounded-md focus:outline-none focus:ring-2 focus:ring-win11-bg-accent/50',
                   isDark
                     ? 'bg-white/5 border-white/10 text-white'
                     : 'bg-gray-50 border-gray-200 text-gray-900'
                 )}
+              >
+                {UI_DENSITY_OPTIONS.map(({ value, label }) => (
+                  <option key={value} value={value}>
+                    {label}
+                  </option>
+                ))}
+              </select>
+            </div>
+
+            <div className="space-y-4">
+              <div className="flex justify-between items-center">
+                <label htmlFor="font-scale" className="text-sm font-medium">
+                  Text Size
+                </label>
+                <div
+                  className={clsx(
+                    'px-2 py-1 rounded text-xs font-mono font-medium',
+                    isDark ? 'bg-black/20' : 'bg-gray-100'
+                  )}
+                >
+                  {Math.round(settings.font_scale * 100)}%
+                </div>
+              </div>
+              <input
+                id="font-scale"
+                type="range"
+                min="0.8"
+                max="1.5"
+                step="0.05"
+                value={settings.font_scale}
+                onChange={(e) => handleFontScaleChange(Number.parseFloat(e.target.value))}
+                onMouseUp={commitOpacityChange}
+                onTouchEnd={commitOpacityChange}
+                className="w-full h-1.5 bg-gray-200 rounded-lg appearance-none cursor-pointer dark:bg-gray-700 accent-win11-bg-accent"
               />
             </div>
           </div>
         </section>
 
-        {/* Custom Kaomoji Section */}
+        {/* Window Section */}
         <section
           className={clsx(
             'rounded-xl border shadow-sm overflow-hidden',
@@ -552,92 +1172,1104 @@ function SettingsApp() {
           )}
         >
           <div className="p-6 border-b border-inherit">
-            <h2 className="text-base font-semibold mb-1">Custom Kaomoji</h2>
+            <h2 className="text-base font-semibold mb-1">Window</h2>
             <p className={clsx('text-xs', isDark ? 'text-gray-400' : 'text-gray-500')}>
-              Add your own personal kaomojis to the collection
+              Choose where the clipboard window opens
             </p>
           </div>
 
-          <div className="p-6 space-y-6">
-            {/* Add New */}
-            <div className="flex gap-2">
-              <input
-                type="text"
-                value={newKaomoji}
-                onChange={(e) => setNewKaomoji(e.target.value)}
-                placeholder="( ˘ ³˘)♥"
+          <div className="p-6 space-y-4">
+            <div className="flex justify-between items-center">
+              <div>
+                <label htmlFor="window-placement" className="text-sm font-medium">
+                  Open Position
+                </label>
+                <p className={clsx('text-xs mt-0.5', isDark ? 'text-gray-400' : 'text-gray-500')}>
+                  Automatic follows the pointer on X11 and remembers the position on Wayland.
+                  The docked sidebar stays on top and open until closed.
+                </p>
+              </div>
+              <select
+                id="window-placement"
+                value={settings.window_placement}
+                onChange={(e) =>
+                  updateSettings({ window_placement: e.target.value as WindowPlacement })
+                }
                 className={clsx(
-                  'flex-1 px-3 py-2 rounded-md border text-sm focus:outline-none focus:ring-2 focus:ring-win11-bg-accent/50 transition-all',
+                  'px-2 py-1 text-sm border rounded-md focus:outline-none focus:ring-2 focus:ring-win11-bg-accent/50',
                   isDark
-                    ? 'bg-white/5 border-white/10 text-white placeholder-gray-500'
-                    : 'bg-gray-50 border-gray-200 text-gray-900 placeholder-gray-400'
+                    ? 'bg-white/5 border-white/10 text-white'
+                    : 'bg-gray-50 border-gray-200 text-gray-900'
                 )}
-                onKeyDown={(e) => {
-                  if (e.key === 'Enter') {
-                    addCustomKaomoji()
-                  }
-                }}
-              />
-              <button
-                onClick={addCustomKaomoji}
-                className="px-4 py-2 bg-win11-bg-accent text-white rounded-md text-sm font-medium hover:opacity-90 active:scale-95 transition-all"
               >
-                Add
-              </button>
+                {WINDOW_PLACEMENT_OPTIONS.map(({ value, label }) => (
+                  <option key={value} value={value}>
+                    {label}
+                  </option>
+                ))}
+              </select>
             </div>
 
-            {/* List */}
-            {settings.custom_kaomojis.length > 0 ? (
-              <div className="grid grid-cols-2 md:grid-cols-3 gap-2 max-h-48 overflow-y-auto custom-scrollbar pr-1">
-                {settings.custom_kaomojis.map((item, idx) => (
-                  <div
-                    key={idx}
-                    className={clsx(
-                      'group flex items-center justify-between px-3 py-2 rounded-md border transition-colors',
-                      isDark ? 'bg-white/5 border-white/10' : 'bg-gray-50 border-gray-200'
-                    )}
-                  >
-                    <span className="font-mono text-sm truncate mr-2" title={item.text}>
-                      {item.text}
-                    </span>
-                    <button
-                      onClick={() => removeCustomKaomojiAt(idx)}
-                      className="opacity-0 group-hover:opacity-100 p-1 text-red-500 hover:bg-red-500/10 rounded transition-all"
-                      title="Delete"
-                    >
-                      <svg
-                        width="14"
-                        height="14"
-                        viewBox="0 0 24 24"
-                        fill="none"
-                        stroke="currentColor"
-                        strokeWidth="2"
-                        strokeLinecap="round"
-                        strokeLinejoin="round"
-                      >
-                        <path d="M18 6 6 18" />
-                        <path d="m6 6 12 12" />
-                      </svg>
-                    </button>
-                  </div>
-                ))}
+            <div className="flex justify-between items-center">
+              <div>
+                <label htmlFor="open-on-monitor" className="text-sm font-medium">
+                  Open on Monitor
+                </label>
+                <p className={clsx('text-xs mt-0.5', isDark ? 'text-gray-400' : 'text-gray-500')}>
+                  A fixed display, or the one with the pointer or the window you were using
+                </p>
               </div>
-            ) : (
-              <div
+              <select
+                id="open-on-monitor"
+                value={settings.open_on_monitor}
+                onChange={(e) => updateSettings({ open_on_monitor: e.target.value })}
                 className={clsx(
-                  'text-center py-4 text-sm italic opacity-60',
-                  isDark ? 'text-gray-500' : 'text-gray-400'
+                  'px-2 py-1 text-sm border rounded-md focus:outline-none focus:ring-2 focus:ring-win11-bg-accent/50',
+                  isDark
+                    ? 'bg-white/5 border-white/10 text-white'
+                    : 'bg-gray-50 border-gray-200 text-gray-900'
                 )}
               >
-                No custom kaomojis yet
+                {OPEN_ON_MONITOR_OPTIONS.map(({ value, label }) => (
+                  <option key={value} value={value}>
+                    {label}
+                  </option>
+                ))}
+                {monitorNames.map((name) => (
+                  <option key={name} value={name}>
+                    {name}
+                  </option>
+                ))}
+                {!OPEN_ON_MONITOR_OPTIONS.some(({ value }) => value === settings.open_on_monitor) &&
+                  !monitorNames.includes(settings.open_on_monitor) && (
+                    <option value={settings.open_on_monitor}>
+                      {settings.open_on_monitor} (disconnected)
+                    </option>
+                  )}
+              </select>
+            </div>
+
+            {settings.window_placement === 'docked' && (
+              <div className="flex justify-between items-center">
+                <div>
+                  <label htmlFor="dock-edge" className="text-sm font-medium">
+                    Dock Edge
+                  </label>
+                  <p className={clsx('text-xs mt-0.5', isDark ? 'text-gray-400' : 'text-gray-500')}>
+                    Side of the screen the sidebar sits on; resize it to set its width
+                  </p>
+                </div>
+                <select
+                  id="dock-edge"
+                  value={dockEdge}
+                  onChange={(e) => changeDockEdge(e.target.value as DockEdge)}
+                  className={clsx(
+                    'px-2 py-1 text-sm border rounded-md focus:outline-none focus:ring-2 focus:ring-win11-bg-accent/50',
+                    isDark
+                      ? 'bg-white/5 border-white/10 text-white'
+                      : 'bg-gray-50 border-gray-200 text-gray-900'
+                  )}
+                >
+                  {DOCK_EDGE_OPTIONS.map(({ value, label }) => (
+                    <option key={value} value={value}>
+                      {label}
+                    </option>
+                  ))}
+                </select>
               </div>
             )}
           </div>
         </section>
 
-        {/* Features Section */}
+        {/* History Settings Section */}
+        <section
+          className={clsx(
+            'rounded-xl border shadow-sm overflow-hidden',
+            isDark ? 'bg-win11-bg-secondary border-white/5' : 'bg-white border-gray-200/60'
+          )}
+        >
+          <div className="p-6 border-b border-inherit">
+            <h2 className="text-base font-semibold mb-1">History Settings</h2>
+            <p className={clsx('text-xs', isDark ? 'text-gray-400' : 'text-gray-500')}>
+              Configure clipboard history behavior
+            </p>
+          </div>
+
+          <div className="p-6 space-y-4">
+            <div className="flex justify-between items-center">
+              <div>
+                <label htmlFor="max-history" className="text-sm font-medium">
+                  Maximum History Size
+                </label>
+                <p className={clsx('text-xs mt-0.5', isDark ? 'text-gray-400' : 'text-gray-500')}>
+                  Number of clipboard items to keep ({MIN_HISTORY_SIZE} -{' '}
+                  {MAX_HISTORY_SIZE.toLocaleString()})
+                </p>
+              </div>
+              <input
+                id="max-history"
+                type="number"
+                min={MIN_HISTORY_SIZE}
+                max={MAX_HISTORY_SIZE}
+                value={settings.max_history_size}
+                onChange={(e) => {
+                  const raw = e.target.value
+                  const parsed = Number.parseInt(raw, 10)
+                  // If parsing fails (e.g. empty input), preserve the current setting
+                  // instead of jumping to the maximum value.
+                  const safe = Number.isNaN(parsed) ? settings.max_history_size : parsed
+                  const value = Math.max(MIN_HISTORY_SIZE, Math.min(MAX_HISTORY_SIZE, safe))
+                  updateSettings({ max_history_size: value })
+                }}
+                className={clsx(
+                  'w-28 text-right font-mono border rounded-md transition-all focus:outline-none focus:ring-2 focus:ring-win11-bg-accent/50',
+                  'input-number-compact no-number-spinner',
+                  isDark
+                    ? 'bg-white/5 border-white/10 text-white'
+                    : 'bg-gray-50 border-gray-200 text-gray-900'
+                )}
+              />
+            </div>
+
+            {CAPTURE_FIELDS.map(({ key, label, desc }) => (
+              <div key={key} className="flex justify-between items-center">
+                <div>
+                  <div className="text-sm font-medium">{label}</div>
+                  <p className={clsx('text-xs mt-0.5', isDark ? 'text-gray-400' : 'text-gray-500')}>
+                    {desc}
+                  </p>
+                </div>
+                <Switch
+                  checked={settings[key]}
+                  onChange={() => handleToggle(key)}
+                  isDark={isDark}
+                />
+              </div>
+            ))}
+
+            {settings.copy_on_select && (
+              <div className="flex justify-between items-center">
+                <div>
+                  <label htmlFor="copy-on-select-delay" className="text-sm font-medium">
+                    Selection Delay
+                  </label>
+                  <p className={clsx('text-xs mt-0.5', isDark ? 'text-gray-400' : 'text-gray-500')}>
+                    Seconds a selection must stay unchanged, unless you switch windows first
+                  </p>
+                </div>
+                <input
+                  id="copy-on-select-delay"
+                  type="number"
+                  min={1}
+                  max={MAX_COPY_ON_SELECT_DELAY_SECS}
+                  value={settings.copy_on_select_delay_secs}
+                  onChange={(e) => {
+                    const parsed = Number.parseInt(e.target.value, 10)
+                    const safe = Number.isNaN(parsed) ? settings.copy_on_select_delay_secs : parsed
+                    const value = Math.max(1, Math.min(MAX_COPY_ON_SELECT_DELAY_SECS, safe))
+                    updateSettings({ copy_on_select_delay_secs: value })
+                  }}
+                  className={clsx(
+                    'w-28 text-right font-mono border rounded-md transition-all focus:outline-none focus:ring-2 focus:ring-win11-bg-accent/50',
+                    'input-number-compact no-number-spinner',
+                    isDark
+                      ? 'bg-white/5 border-white/10 text-white'
+                      : 'bg-gray-50 border-gray-200 text-gray-900'
+                  )}
+                />
+              </div>
+            )}
+
+            <div className="flex justify-between items-center">
+              <div>
+                <label htmlFor="storage-backend" className="text-sm font-medium">
+                  Storage
+                </label>
+                <p className={clsx('text-xs mt-0.5', isDark ? 'text-gray-400' : 'text-gray-500')}>
+                  {settings.storage_backend === 'memory'
+                    ? 'History is never written to disk and is lost on exit'
+                    : 'Where clipboard history is saved'}
+                </p>
+              </div>
+              <select
+                id="storage-backend"
+                value={settings.storage_backend}
+                onChange={(e) =>
+                  updateSettings({ storage_backend: e.target.value as StorageBackend })
+                }
+                className={clsx(
+                  'px-2 py-1 text-sm border rounded-md focus:outline-none focus:ring-2 focus:ring-win11-bg-accent/50',
+                  isDark
+                    ? 'bg-white/5 border-white/10 text-white'
+                    : 'bg-gray-50 border-gray-200 text-gray-900'
+                )}
+              >
+                {STORAGE_BACKEND_OPTIONS.map(({ value, label }) => (
+                  <option key={value} value={value}>
+                    {label}
+                  </option>
+                ))}
+              </select>
+            </div>
+
+            <div className="flex justify-between items-center">
+              <div>
+                <label htmlFor="background-work" className="text-sm font-medium">
+                  Background Work
+                </label>
+                <p className={clsx('text-xs mt-0.5', isDark ? 'text-gray-400' : 'text-gray-500')}>
+                  CPU used for thumbnails and other extras, Off skips them
+                </p>
+              </div>
+              <select
+                id="background-work"
+                value={settings.background_work}
+                onChange={(e) =>
+                  updateSettings({ background_work: e.target.value as BackgroundWork })
+                }
+                className={clsx(
+                  'px-2 py-1 text-sm border rounded-md focus:outline-none focus:ring-2 focus:ring-win11-bg-accent/50',
+                  isDark
+                    ? 'bg-white/5 border-white/10 text-white'
+                    : 'bg-gray-50 border-gray-200 text-gray-900'
+                )}
+              >
+                {BACKGROUND_WORK_OPTIONS.map(({ value, label }) => (
+                  <option key={value} value={value}>
+                    {label}
+                  </option>
+                ))}
+              </select>
+            </div>
+
+            <div className="flex justify-between items-center">
+              <div>
+                <label htmlFor="stored-image-format" className="text-sm font-medium">
+                  Compress Older Images
+                </label>
+                <p className={clsx('text-xs mt-0.5', isDark ? 'text-gray-400' : 'text-gray-500')}>
+                  Re-encodes images past the most recent ones to save memory and disk
+                </p>
+              </div>
+              <select
+                id="stored-image-format"
+                value={settings.image_compression.format}
+                onChange={(e) =>
+                  updateSettings({
+                    image_compression: {
+                      ...settings.image_compression,
+                      format: e.target.value as StoredImageFormat,
+                    },
+                  })
+                }
+                className={clsx(
+                  'px-2 py-1 text-sm border rounded-md focus:outline-none focus:ring-2 focus:ring-win11-bg-accent/50',
+                  isDark
+                    ? 'bg-white/5 border-white/10 text-white'
+                    : 'bg-gray-50 border-gray-200 text-gray-900'
+                )}
+              >
+                {STORED_IMAGE_FORMAT_OPTIONS.map(({ value, label }) => (
+                  <option key={value} value={value}>
+                    {label}
+                  </option>
+                ))}
+              </select>
+            </div>
+
+            {settings.image_compression.format === 'jpeg' && (
+              <div className="flex justify-between items-center">
+                <div>
+                  <label htmlFor="jpeg-quality" className="text-sm font-medium">
+                    JPEG Quality
+                  </label>
+                  <p className={clsx('text-xs mt-0.5', isDark ? 'text-gray-400' : 'text-gray-500')}>
+                    1 to 100; images with transparency use lossless WebP
+                  </p>
+                </div>
+                <input
+                  id="jpeg-quality"
+                  type="number"
+                  min={1}
+                  max={100}
+                  value={settings.image_compression.jpeg_quality}
+                  onChange={(e) => {
+                    const parsed = Number.parseInt(e.target.value, 10)
+                    if (Number.isNaN(parsed)) return
+                    updateSettings({
+                      image_compression: {
+                        ...settings.image_compression,
+                        jpeg_quality: Math.max(1, Math.min(100, parsed)),
+                      },
+                    })
+                  }}
+                  className={clsx(
+                    'w-28 text-right font-mono border rounded-md transition-all focus:outline-none focus:ring-2 focus:ring-win11-bg-accent/50',
+                    'input-number-compact no-number-spinner',
+                    isDark
+                      ? 'bg-white/5 border-white/10 text-white'
+                      : 'bg-gray-50 border-gray-200 text-gray-900'
+                  )}
+                />
+              </div>
+            )}
+
+            {settings.image_compression.format !== 'png' && (
+              <div className="flex justify-between items-center">
+                <div>
+                  <label htmlFor="keep-original-images" className="text-sm font-medium">
+                    Keep Original Images
+                  </label>
+                  <p className={clsx('text-xs mt-0.5', isDark ? 'text-gray-400' : 'text-gray-500')}>
+                    How many of the most recent images stay exactly as copied
+                  </p>
+                </div>
+                <input
+                  id="keep-original-images"
+                  type="number"
+                  min={0}
+                  value={settings.image_compression.keep_originals}
+                  onChange={(e) => {
+                    const parsed = Number.parseInt(e.target.value, 10)
+                    if (Number.isNaN(parsed)) return
+                    updateSettings({
+                      image_compression: {
+                        ...settings.image_compression,
+                        keep_originals: Math.max(0, parsed),
+                      },
+                    })
+                  }}
+                  className={clsx(
+                    'w-28 text-right font-mono border rounded-md transition-all focus:outline-none focus:ring-2 focus:ring-win11-bg-accent/50',
+                    'input-number-compact no-number-spinner',
+                    isDark
+                      ? 'bg-white/5 border-white/10 text-white'
+                      : 'bg-gray-50 border-gray-200 text-gray-900'
+                  )}
+                />
+              </div>
+            )}
+
+            <div className="flex justify-between items-center">
+              <div>
+                <label htmlFor="memory-budget" className="text-sm font-medium">
+                  Memory Budget (MB)
+                </label>
+                <p className={clsx('text-xs mt-0.5', isDark ? 'text-gray-400' : 'text-gray-500')}>
+                  Older images move to disk past this, and load again when pasted. 0 for no limit
+                </p>
+              </div>
+              <input
+                id="memory-budget"
+                type="number"
+                min={0}
+                max={16384}
+                value={settings.memory_budget_mb}
+                onChange={(e) => {
+                  const parsed = Number.parseInt(e.target.value, 10)
+                  if (Number.isNaN(parsed)) return
+                  updateSettings({ memory_budget_mb: Math.min(16384, Math.max(0, parsed)) })
+                }}
+                className={clsx(
+                  'w-28 text-right font-mono border rounded-md transition-all focus:outline-none focus:ring-2 focus:ring-win11-bg-accent/50',
+                  'input-number-compact no-number-spinner',
+                  isDark
+                    ? 'bg-white/5 border-white/10 text-white'
+                    : 'bg-gray-50 border-gray-200 text-gray-900'
+                )}
+              />
+            </div>
+
+            {RETENTION_FIELDS.map(({ key, label }) => (
+              <div key={key} className="flex justify-between items-center">
+                <div>
+                  <label htmlFor={key} className="text-sm font-medium">
+                    {label}
+                  </label>
+                  <p className={clsx('text-xs mt-0.5', isDark ? 'text-gray-400' : 'text-gray-500')}>
+                    Days to keep unpinned items (0 = forever)
+                  </p>
+                </div>
+                <input
+                  id={key}
+                  type="number"
+                  min={0}
+                  max={MAX_RETENTION_DAYS}
+                  value={settings[key]}
+                  onChange={(e) => {
+                    const parsed = Number.parseInt(e.target.value, 10)
+                    const safe = Number.isNaN(parsed) ? settings[key] : parsed
+                    const value = Math.max(0, Math.min(MAX_RETENTION_DAYS, safe))
+                    updateSettings({ [key]: value } as Partial<UserSettings>)
+                  }}
+                  className={clsx(
+                    'w-28 text-right font-mono border rounded-md transition-all focus:outline-none focus:ring-2 focus:ring-win11-bg-accent/50',
+                    'input-number-compact no-number-spinner',
+                    isDark
+                      ? 'bg-white/5 border-white/10 text-white'
+                      : 'bg-gray-50 border-gray-200 text-gray-900'
+                  )}
+                />
+              </div>
+            ))}
+          </div>
+        </section>
+
+        {/* Paste Settings Section */}
+        <section
+          className={clsx(
+            'rounded-xl border shadow-sm overflow-hidden',
+            isDark ? 'bg-win11-bg-secondary border-white/5' : 'bg-white border-gray-200/60'
+          )}
+        >
+          <div className="p-6 border-b border-inherit">
+            <h2 className="text-base font-semibold mb-1">Paste Settings</h2>
+            <p className={clsx('text-xs', isDark ? 'text-gray-400' : 'text-gray-500')}>
+              Terminals treat Ctrl+V as a literal ^V, so they get different paste keys
+            </p>
+          </div>
+
+          <div className="p-6 space-y-4">
+            <div className="flex justify-between items-center">
+              <div>
+                <label htmlFor="terminal-paste-keys" className="text-sm font-medium">
+                  Terminal Paste Keys
+                </label>
+                <p className={clsx('text-xs mt-0.5', isDark ? 'text-gray-400' : 'text-gray-500')}>
+                  Sent instead of Ctrl+V when pasting into a terminal
+                </p>
+              </div>
+              <select
+                id="terminal-paste-keys"
+                value={settings.terminal_paste_keys}
+                onChange={(e) =>
+                  updateSettings({ terminal_paste_keys: e.target.value as TerminalPasteKeys })
+                }
+                className={clsx(
+                  'px-2 py-1 text-sm border rounded-md focus:outline-none focus:ring-2 focus:ring-win11-bg-accent/50',
+                  isDark
+                    ? 'bg-white/5 border-white/10 text-white'
+                    : 'bg-gray-50 border-gray-200 text-gray-900'
+                )}
+              >
+                {TERMINAL_PASTE_OPTIONS.map(({ value, label }) => (
+                  <option key={value} value={value}>
+                    {label}
+                  </option>
+                ))}
+              </select>
+            </div>
+
+            <div className="flex justify-between items-center">
+              <div>
+                <label htmlFor="submit-keys" className="text-sm font-medium">
+                  Submit Key
+                </label>
+                <p className={clsx('text-xs mt-0.5', isDark ? 'text-gray-400' : 'text-gray-500')}>
+                  Pressed after the paste when you use Shift+Enter on an item
+                </p>
+              </div>
+              <select
+                id="submit-keys"
+                value={settings.submit_keys}
+                onChange={(e) => updateSettings({ submit_keys: e.target.value as SubmitKeys })}
+                className={clsx(
+                  'px-2 py-1 text-sm border rounded-md focus:outline-none focus:ring-2 focus:ring-win11-bg-accent/50',
+                  isDark
+                    ? 'bg-white/5 border-white/10 text-white'
+                    : 'bg-gray-50 border-gray-200 text-gray-900'
+                )}
+              >
+                {SUBMIT_KEY_OPTIONS.map(({ value, label }) => (
+                  <option key={value} value={value}>
+                    {label}
+                  </option>
+                ))}
+              </select>
+            </div>
+
+            <div className="flex justify-between items-center">
+              <div>
+                <label htmlFor="type-out-delay" className="text-sm font-medium">
+                  Typing Delay
+                </label>
+                <p className={clsx('text-xs mt-0.5', isDark ? 'text-gray-400' : 'text-gray-500')}>
+                  Milliseconds between characters for &quot;Type it out&quot; ({MIN_TYPE_DELAY_MS}{' '}
+                  - {MAX_TYPE_DELAY_MS})
+                </p>
+              </div>
+              <input
+                id="type-out-delay"
+                type="number"
+                min={MIN_TYPE_DELAY_MS}
+                max={MAX_TYPE_DELAY_MS}
+                value={settings.type_out_delay_ms}
+                onChange={(e) => {
+                  const parsed = Number.parseInt(e.target.value, 10)
+                  const safe = Number.isNaN(parsed) ? settings.type_out_delay_ms : parsed
+                  const value = Math.max(MIN_TYPE_DELAY_MS, Math.min(MAX_TYPE_DELAY_MS, safe))
+                  updateSettings({ type_out_delay_ms: value })
+                }}
+                className={clsx(
+                  'w-28 text-right font-mono border rounded-md transition-all focus:outline-none focus:ring-2 focus:ring-win11-bg-accent/50',
+                  'input-number-compact no-number-spinner',
+                  isDark
+                    ? 'bg-white/5 border-white/10 text-white'
+                    : 'bg-gray-50 border-gray-200 text-gray-900'
+                )}
+              />
+            </div>
+
+            <div className="flex justify-between items-center">
+              <div>
+                <label htmlFor="join-separator" className="text-sm font-medium">
+                  Join Separator
+                </label>
+                <p className={clsx('text-xs mt-0.5', isDark ? 'text-gray-400' : 'text-gray-500')}>
+                  Placed between the texts when joining an item with the previous one (Ctrl+J)
+                </p>
+              </div>
+              <select
+                id="join-separator"
+                value={settings.join_separator}
+                onChange={(e) => updateSettings({ join_separator: e.target.value })}
+                className={clsx(
+                  'px-2 py-1 text-sm border rounded-md focus:outline-none focus:ring-2 focus:ring-win11-bg-accent/50',
+                  isDark
+                    ? 'bg-white/5 border-white/10 text-white'
+                    : 'bg-gray-50 border-gray-200 text-gray-900'
+                )}
+              >
+                {JOIN_SEPARATOR_OPTIONS.map(({ value, label }) => (
+                  <option key={label} value={value}>
+                    {label}
+                  </option>
+                ))}
+              </select>
+            </div>
+
+            <div>
+              <label htmlFor="terminal-wm-classes" className="text-sm font-medium">
+                Terminal Window Classes
+              </label>
+              <p
+                className={clsx('text-xs mt-0.5 mb-2', isDark ? 'text-gray-400' : 'text-gray-500')}
+              >
+                One WM_CLASS per line (see <code>xprop WM_CLASS</code>)
+              </p>
+              <textarea
+                id="terminal-wm-classes"
+                rows={5}
+                value={settings.terminal_wm_classes.join('\n')}
+                onChange={(e) =>
+                  updateSettings({ terminal_wm_classes: e.target.value.split('\n') })
+                }
+                className={clsx(
+                  'w-full px-3 py-2 text-sm font-mono border rounded-md resize-y focus:outline-none focus:ring-2 focus:ring-win11-bg-accent/50',
+                  isDark
+                    ? 'bg-white/5 border-white/10 text-white'
+                    : 'bg-gray-50 border-gray-200 text-gray-900'
+                )}
+              />
+            </div>
+
+            {STRATEGY_FIELDS.map(({ key, label, names }) => (
+              <div key={key}>
+                <label htmlFor={key} className="text-sm font-medium">
+                  {label}
+                </label>
+                <p
+                  className={clsx(
+                    'text-xs mt-0.5 mb-2',
+                    isDark ? 'text-gray-400' : 'text-gray-500'
+                  )}
+                >
+                  Comma separated, tried in order. Leave empty for automatic; methods left out are
+                  disabled. Available: {names.join(', ')}
+                </p>
+                <input
+                  id={key}
+                  type="text"
+                  // Remount when the saved list changes so the uncontrolled input picks it up
+                  key={settings[key].join(',')}
+                  defaultValue={settings[key].join(', ')}
+                  placeholder="automatic"
+                  onBlur={(e) =>
+                    updateSettings({ [key]: parseList(e.target.value) } as Partial<UserSettings>)
+                  }
+                  className={clsx(
+                    'w-full px-3 py-2 text-sm font-mono border rounded-md focus:outline-none focus:ring-2 focus:ring-win11-bg-accent/50',
+                    isDark
+                      ? 'bg-white/5 border-white/10 text-white placeholder-gray-500'
+                      : 'bg-gray-50 border-gray-200 text-gray-900 placeholder-gray-400'
+                  )}
+                />
+              </div>
+            ))}
+
+            <div>
+              <h3 className="text-sm font-medium">Paste Profiles</h3>
+              <p
+                className={clsx('text-xs mt-0.5 mb-2', isDark ? 'text-gray-400' : 'text-gray-500')}
+              >
+                Formatting applied when pasting into these apps. The first matching profile wins.
+              </p>
+
+              <div className="space-y-2">
+                {settings.paste_profiles.map((profile, idx) => (
+                  <div
+                    // Remount when the ids change so the uncontrolled input picks them up
+                    key={`${idx}:${profile.app_ids.join(',')}`}
+                    className={clsx(
+                      'flex gap-2 items-center px-3 py-2 rounded-md border',
+                      isDark ? 'bg-white/5 border-white/10' : 'bg-gray-50 border-gray-200'
+                    )}
+                  >
+                    <span className="w-24 text-sm font-medium truncate" title={profile.name}>
+                      {profile.name}
+                    </span>
+                    <input
+                      type="text"
+                      aria-label={`${profile.name} apps`}
+                      defaultValue={profile.app_ids.join(', ')}
+                      onBlur={(e) =>
+                        updatePasteProfileAt(idx, { app_ids: parseList(e.target.value) })
+                      }
+                      className={clsx(
+                        'flex-1 min-w-0 px-2 py-1 text-xs font-mono border rounded-md focus:outline-none focus:ring-2 focus:ring-win11-bg-accent/50',
+                        isDark
+                          ? 'bg-white/5 border-white/10 text-white'
+                          : 'bg-white border-gray-200 text-gray-900'
+                      )}
+                    />
+                    <select
+                      aria-label={`${profile.name} format`}
+                      value={profile.format}
+                      onChange={(e) =>
+                        updatePasteProfileAt(idx, { format: e.target.value as PasteFormat })
+                      }
+                      className={clsx(
+                        'px-2 py-1 text-sm border rounded-md focus:outline-none focus:ring-2 focus:ring-win11-bg-accent/50',
+                        isDark
+                          ? 'bg-white/5 border-white/10 text-white'
+                          : 'bg-white border-gray-200 text-gray-900'
+                      )}
+                    >
+                      {PASTE_FORMAT_OPTIONS.map(({ value, label }) => (
+                        <option key={value} value={value}>
+                          {label}
+                        </option>
+                      ))}
+                    </select>
+                    <button
+                      onClick={() => removePasteProfileAt(idx)}
+                      className="p-1 text-red-500 hover:bg-red-500/10 rounded transition-all"
+                      title="Delete"
+                    >
+                      <svg
+                        width="14"
+                        height="14"
+                        viewBox="0 0 24 24"
+                        fill="none"
+                        stroke="currentColor"
+                        strokeWidth="2"
+                        strokeLinecap="round"
+                        strokeLinejoin="round"
+                      >
+                        <path d="M18 6 6 18" />
+                        <path d="m6 6 12 12" />
+                      </svg>
+                    </button>
+                  </div>
+                ))}
+
+                <div className="flex gap-2">
+                  <input
+                    type="text"
+                    value={newProfileApps}
+                    onChange={(e) => setNewProfileApps(e.target.value)}
+                    placeholder="App ids, comma separated"
+                    className={clsx(
+                      'flex-1 px-3 py-2 rounded-md border text-sm focus:outline-none focus:ring-2 focus:ring-win11-bg-accent/50 transition-all',
+                      isDark
+                        ? 'bg-white/5 border-white/10 text-white placeholder-gray-500'
+                        : 'bg-gray-50 border-gray-200 text-gray-900 placeholder-gray-400'
+                    )}
+                    onKeyDown={(e) => {
+                      if (e.key === 'Enter') {
+                        addPasteProfile()
+                      }
+                    }}
+                  />
+                  <select
+                    aria-label="New profile format"
+                    value={newProfileFormat}
+                    onChange={(e) => setNewProfileFormat(e.target.value as PasteFormat)}
+                    className={clsx(
+                      'px-2 py-1 text-sm border rounded-md focus:outline-none focus:ring-2 focus:ring-win11-bg-accent/50',
+                      isDark
+                        ? 'bg-white/5 border-white/10 text-white'
+                        : 'bg-gray-50 border-gray-200 text-gray-900'
+                    )}
+                  >
+                    {PASTE_FORMAT_OPTIONS.map(({ value, label }) => (
+                      <option key={value} value={value}>
+                        {label}
+                      </option>
+                    ))}
+                  </select>
+                  <button
+                    onClick={addPasteProfile}
+                    className="px-4 py-2 bg-win11-bg-accent text-white rounded-md text-sm font-medium hover:opacity-90 active:scale-95 transition-all"
+                  >
+                    Add
+                  </button>
+                </div>
+              </div>
+            </div>
+          </div>
+        </section>
+
+        {/* Shortcut Section */}
+        <section
+          className={clsx(
+            'rounded-xl border shadow-sm overflow-hidden',
+            isDark ? 'bg-win11-bg-secondary border-white/5' : 'bg-white border-gray-200/60'
+          )}
+        >
+          <div className="p-6 border-b border-inherit">
+            <h2 className="text-base font-semibold mb-1">Keyboard Shortcut</h2>
+            <p className={clsx('text-xs', isDark ? 'text-gray-400' : 'text-gray-500')}>
+              The key combinations that open clipboard history
+            </p>
+          </div>
+
+          <div className="p-6">
+            <div className="flex justify-between items-center gap-4">
+              <div>
+                <label htmlFor="global-shortcut" className="text-sm font-medium">
+                  Open Clipboard History
+                </label>
+                <p className={clsx('text-xs mt-0.5', isDark ? 'text-gray-400' : 'text-gray-500')}>
+                  e.g. Super+V, Super+Shift+V or Ctrl+Alt+H. Ctrl+Alt+V always works too.
+                </p>
+              </div>
+              <input
+                id="global-shortcut"
+                type="text"
+                // Remount when the saved shortcut changes so the uncontrolled input picks it up
+                key={settings.global_shortcut}
+                defaultValue={settings.global_shortcut}
+                onBlur={(e) => {
+                  const value = e.target.value.trim()
+                  if (value && value !== settings.global_shortcut) {
+                    updateSettings({ global_shortcut: value })
+                  }
+                }}
+                className={clsx(
+                  'w-40 px-3 py-1.5 text-sm font-mono border rounded-md focus:outline-none focus:ring-2 focus:ring-win11-bg-accent/50',
+                  isDark
+                    ? 'bg-white/5 border-white/10 text-white'
+                    : 'bg-gray-50 border-gray-200 text-gray-900'
+                )}
+              />
+            </div>
+
+            <div className="flex justify-between items-center gap-4 mt-4">
+              <div>
+                <label htmlFor="emoji-shortcut" className="text-sm font-medium">
+                  Open Emoji Panel
+                </label>
+                <p className={clsx('text-xs mt-0.5', isDark ? 'text-gray-400' : 'text-gray-500')}>
+                  Opens straight on the emoji tab, like Win+. on Windows. Leave empty to disable.
+                </p>
+              </div>
+              <input
+                id="emoji-shortcut"
+                type="text"
+                key={settings.emoji_shortcut}
+                defaultValue={settings.emoji_shortcut}
+                onBlur={(e) => {
+                  const value = e.target.value.trim()
+                  if (value !== settings.emoji_shortcut) {
+                    updateSettings({ emoji_shortcut: value })
+                  }
+                }}
+                className={clsx(
+                  'w-40 px-3 py-1.5 text-sm font-mono border rounded-md focus:outline-none focus:ring-2 focus:ring-win11-bg-accent/50',
+                  isDark
+                    ? 'bg-white/5 border-white/10 text-white'
+                    : 'bg-gray-50 border-gray-200 text-gray-900'
+                )}
+              />
+            </div>
+
+            {(settings.vim_navigation || settings.keyboard_only) && (
+              <div className="mt-6">
+                <div className="text-sm font-medium">Vim Keys</div>
+                <p className={clsx('text-xs mb-3', isDark ? 'text-gray-400' : 'text-gray-500')}>
+                  Key sequences used in the history list. Named keys go in angle brackets, e.g.
+                  &lt;Enter&gt;.
+                </p>
+                <div className="grid grid-cols-2 gap-x-6 gap-y-2">
+                  {KEYMAP_ACTIONS.map(({ action, label }) => (
+                    <div key={action} className="flex justify-between items-center gap-2">
+                      <label htmlFor={`keymap-${action}`} className="text-xs">
+                        {label}
+                      </label>
+                      <input
+                        id={`keymap-${action}`}
+                        type="text"
+                        key={settings.keymap[action]}
+                        defaultValue={settings.keymap[action]}
+                        onBlur={(e) => {
+                          const value = e.target.value.trim()
+                          if (value !== settings.keymap[action]) {
+                            updateSettings({ keymap: { ...settings.keymap, [action]: value } })
+                          }
+                        }}
+                        className={clsx(
+                          'w-20 px-2 py-1 text-xs font-mono border rounded-md focus:outline-none focus:ring-2 focus:ring-win11-bg-accent/50',
+                          isDark
+                            ? 'bg-white/5 border-white/10 text-white'
+                            : 'bg-gray-50 border-gray-200 text-gray-900'
+                        )}
+                      />
+                    </div>
+                  ))}
+                </div>
+              </div>
+            )}
+
+            <div className="flex justify-between items-center gap-4 mt-6">
+              <div>
+                <div className="text-sm font-medium">Remove From Desktop</div>
+                <p className={clsx('text-xs mt-0.5', isDark ? 'text-gray-400' : 'text-gray-500')}>
+                  Deletes the bindings this app added to your desktop settings, including old ones.
+                </p>
+              </div>
+              <button
+                onClick={removeDesktopShortcuts}
+                className={clsx(
+                  'px-4 py-2 rounded-md text-sm font-medium active:scale-95 transition-all',
+                  isDark ? 'bg-white/10 hover:bg-white/15' : 'bg-gray-100 hover:bg-gray-200'
+                )}
+              >
+                Remove
+              </button>
+            </div>
+          </div>
+        </section>
+
+        {/* Emoji Section */}
+        <section
+          className={clsx(
+            'rounded-xl border shadow-sm overflow-hidden',
+            isDark ? 'bg-win11-bg-secondary border-white/5' : 'bg-white border-gray-200/60'
+          )}
+        >
+          <div className="p-6 border-b border-inherit">
+            <h2 className="text-base font-semibold mb-1">Emoji</h2>
+            <p className={clsx('text-xs', isDark ? 'text-gray-400' : 'text-gray-500')}>
+              Default variants of people and hand emojis. Right-click or hold an emoji for the
+              others
+            </p>
+          </div>
+
+          <div className="p-6 space-y-6">
+            <div className="flex justify-between items-center">
+              <label htmlFor="emoji-skin-tone" className="text-sm font-medium">
+                Skin Tone
+              </label>
+              <select
+                id="emoji-skin-tone"
+                value={settings.emoji_variants.skin_tone}
+                onChange={(e) =>
+                  updateSettings({
+                    emoji_variants: {
+                      ...settings.emoji_variants,
+                      skin_tone: e.target.value as SkinTone,
+                    },
+                  })
+                }
+                className={clsx(
+                  'px-2 py-1 text-sm border rounded-md focus:outline-none focus:ring-2 focus:ring-win11-bg-accent/50',
+                  isDark
+                    ? 'bg-white/5 border-white/10 text-white'
+                    : 'bg-gray-50 border-gray-200 text-gray-900'
+                )}
+              >
+                {SKIN_TONE_OPTIONS.map(({ value, label }) => (
+                  <option key={value} value={value}>
+                    {label}
+                  </option>
+                ))}
+              </select>
+            </div>
+
+            <div className="flex justify-between items-center">
+              <label htmlFor="emoji-gender" className="text-sm font-medium">
+                Gender
+              </label>
+              <select
+                id="emoji-gender"
+                value={settings.emoji_variants.gender}
+                onChange={(e) =>
+                  updateSettings({
+                    emoji_variants: {
+                      ...settings.emoji_variants,
+                      gender: e.target.value as EmojiGender,
+                    },
+                  })
+                }
+                className={clsx(
+                  'px-2 py-1 text-sm border rounded-md focus:outline-none focus:ring-2 focus:ring-win11-bg-accent/50',
+                  isDark
+                    ? 'bg-white/5 border-white/10 text-white'
+                    : 'bg-gray-50 border-gray-200 text-gray-900'
+                )}
+              >
+                {EMOJI_GENDER_OPTIONS.map(({ value, label }) => (
+                  <option key={value} value={value}>
+                    {label}
+                  </option>
+                ))}
+              </select>
+            </div>
+
+            <EmojiDatasetSetting isDark={isDark} />
+          </div>
+        </section>
+
+        {/* GIF Provider Section */}
+        <GifProviderSection
+          gifs={settings.gifs}
+          isDark={isDark}
+          onChange={(partial) => updateSettings({ gifs: { ...settings.gifs, ...partial } })}
+        />
+
+        {/* Snippet Packs Section */}
+        <SnippetPacksSection isDark={isDark} />
+
+        {/* Sticker Packs Section */}
+        <StickerPacksSection isDark={isDark} />
+
+        {/* Custom Emoji Section */}
+        <CustomEmojiSection isDark={isDark} />
+
+        {/* Custom Kaomoji Section */}
+        <section
+          className={clsx(
+            'rounded-xl border shadow-sm overflow-hidden',
+            isDark ? 'bg-win11-bg-secondary border-white/5' : 'bg-white border-gray-200/60'
+          )}
+        >
+          <div className="p-6 border-b border-inherit">
+            <h2 className="text-base font-semibold mb-1">Custom Kaomoji</h2>
+            <p className={clsx('text-xs', isDark ? 'text-gray-400' : 'text-gray-500')}>
+              Add your own personal kaomojis to the collection
+            </p>
+          </div>
+
+          <div className="p-6 space-y-6">
+            {/* Add New */}
+            <div className="flex gap-2">
+              <input
+                type="text"
+                value={newKaomoji}
+                onChange={(e) => setNewKaomoji(e.target.value)}
+                placeholder="( ˘ ³˘)♥"
+                className={clsx(
+                  'flex-1 px-3 py-2 rounded-md border text-sm focus:outline-none focus:ring-2 focus:ring-win11-bg-accent/50 transition-all',
+                  isDark
+                    ? 'bg-white/5 border-white/10 text-white placeholder-gray-500'
+                    : 'bg-gray-50 border-gray-200 text-gray-900 placeholder-gray-400'
+                )}
+                onKeyDown={(e) => {
+                  if (e.key === 'Enter') {
+                    addCustomKaomoji()
+                  }
+                }}
+              />
+              <button
+                onClick={addCustomKaomoji}
+                className="px-4 py-2 bg-win11-bg-accent text-white rounded-md text-sm font-medium hover:opacity-90 active:scale-95 transition-all"
+              >
+                Add
+              </button>
+            </div>
+
+            {/* List */}
+            {settings.custom_kaomojis.length > 0 ? (
+              <div className="grid grid-cols-2 md:grid-cols-3 gap-2 max-h-48 overflow-y-auto custom-scrollbar pr-1">
+                {settings.custom_kaomojis.map((item, idx) => (
+                  <div
+                    key={idx}
+                    className={clsx(
+                      'group flex items-center justify-between px-3 py-2 rounded-md border transition-colors',
+                      isDark ? 'bg-white/5 border-white/10' : 'bg-gray-50 border-gray-200'
+                    )}
+                  >
+                    <span className="font-mono text-sm truncate mr-2" title={item.text}>
+                      {item.text}
+                    </span>
+                    <button
+                      onClick={() => removeCustomKaomojiAt(idx)}
+                      className="opacity-0 group-hover:opacity-100 p-1 text-red-500 hover:bg-red-500/10 rounded transition-all"
+                      title="Delete"
+                    >
+                      <svg
+                        width="14"
+                        height="14"
+                        viewBox="0 0 24 24"
+                        fill="none"
+                        stroke="currentColor"
+                        strokeWidth="2"
+                        strokeLinecap="round"
+                        strokeLinejoin="round"
+                      >
+                        <path d="M18 6 6 18" />
+                        <path d="m6 6 12 12" />
+                      </svg>
+                    </button>
+                  </div>
+                ))}
+              </div>
+            ) : (
+              <div
+                className={clsx(
+                  'text-center py-4 text-sm italic opacity-60',
+                  isDark ? 'text-gray-500' : 'text-gray-400'
+                )}
+              >
+                No custom kaomojis yet
+              </div>
+            )}
+          </div>
+        </section>
+
+        {/* Features Section */}
         <FeaturesSection settings={settings} isDark={isDark} onToggle={handleToggle} />
 
+        {/* Shell Actions Section */}
+        <ShellActionsSection
+          actions={settings.shell_actions}
+          isDark={isDark}
+          onChange={(shell_actions) => updateSettings({ shell_actions })}
+        />
+
+        {/* Translation Section */}
+        <TranslationSection
+          translation={settings.translation}
+          isDark={isDark}
+          onChange={(partial) =>
+            updateSettings({ translation: { ...settings.translation, ...partial } })
+          }
+        />
+
+        {/* Insights Section */}
+        <InsightsSection isDark={isDark} />
+
         {/* Reset Section */}
         <div className="flex justify-end pt-2">
           <button
@@ -1,17 +1,40 @@
 import { useState, useMemo, useRef, useEffect, useCallback } from 'react'
+import { invoke } from '@tauri-apps/api/core'
 import { listen } from '@tauri-apps/api/event'
 import { clsx } from 'clsx'
 
-import type { ClipboardItem, UserSettings } from '../types/clipboard'
+import type {
+  ClipboardItem,
+  HistoryFilters,
+  KeyAction,
+  QuickAction,
+  UserSettings,
+} from '../types/clipboard'
 import type { TabBarRef } from './TabBar'
 import { Header } from './Header'
 import { SearchBar } from './common/SearchBar'
 import { EmptyState } from './EmptyState'
+import { CollectionBar } from './CollectionBar'
+import { SourceAppBar } from './SourceAppBar'
+import { SearchMatches } from './SearchMatches'
 import { HistoryItem } from './HistoryItem'
+import { getSplitDelimiter } from './HistoryItem/_HistoryItemUtils'
 import { useHistoryKeyboardNavigation } from '../hooks/useHistoryKeyboardNavigation'
+import { useItemAges } from '../hooks/useItemAges'
+import { useItemSummaries } from '../hooks/useItemSummaries'
+import { useFrequentItems } from '../hooks/useFrequentItems'
+import { useCollections } from '../hooks/useCollections'
+import { useHistoryQuery } from '../hooks/useHistoryQuery'
+import { useUnifiedSearch } from '../hooks/useUnifiedSearch'
+import { parseSearch } from '../utils/searchOperators'
+import { sourceApps } from '../utils/sourceApps'
 
 export function ClipboardTab(props: {
+  /** The pages of history loaded so far */
   history: ClipboardItem[]
+  historyTotal: number
+  hasMoreHistory: boolean
+  loadMoreHistory: () => void
   isLoading: boolean
   isDark: boolean
   tertiaryOpacity: number
@@ -20,11 +43,29 @@ export function ClipboardTab(props: {
   deleteItem: (id: string) => void
   togglePin: (id: string) => void
   onPaste: (id: string) => void
+  onPasteAndSubmit: (id: string) => void
+  onTypeOut: (id: string) => void
+  onSplit: (id: string, delimiter: string) => void
+  onJoinWithPrevious: (id: string) => void
+  onCleanHiddenChars: (id: string) => void
+  onCopyLink: (id: string) => void
+  onSetTags: (id: string, tags: string[]) => void
+  onFileInto: (id: string, collection: string | null) => void
+  onQuickAction: (id: string, action: QuickAction) => void
+  onShellAction: (id: string, name: string) => void
+  onTranslate: (id: string) => void
   settings: UserSettings
   tabBarRef: React.RefObject<TabBarRef | null>
+  /** Search requested from outside the window (`--search`), a new object each time */
+  searchRequest?: { query: string } | null
+  /** Item to focus, from a clip:// link */
+  focusRequest?: { id: string } | null
 }) {
   const {
     history,
+    historyTotal,
+    hasMoreHistory,
+    loadMoreHistory,
     isLoading,
     isDark,
     tertiaryOpacity,
@@ -33,23 +74,44 @@ export function ClipboardTab(props: {
     deleteItem,
     togglePin,
     onPaste,
+    onPasteAndSubmit,
+    onTypeOut,
+    onSplit,
+    onJoinWithPrevious,
+    onCleanHiddenChars,
+    onCopyLink,
+    onSetTags,
+    onFileInto,
+    onQuickAction,
+    onShellAction,
+    onTranslate,
     settings,
     tabBarRef,
+    searchRequest,
+    focusRequest,
   } = props
 
+  const ages = useItemAges(history)
+  const summaries = useItemSummaries(history)
+  const frequent = useFrequentItems(history)
+  const { collections, createCollection, renameCollection, deleteCollection } = useCollections()
+  // Collection shown instead of the whole history, if any
+  const [activeCollection, setActiveCollection] = useState<string | null>(null)
   const [searchQuery, setSearchQuery] = useState('')
   const [isRegexMode, setIsRegexMode] = useState(false)
 
-  const [isCompact, setIsCompact] = useState(() => {
-    if (typeof window !== 'undefined') {
-      return localStorage.getItem('clipboard-history-compact-mode') === 'true'
+  // The density setting, so the backend can size the window to match
+  const isCompact = settings.ui_density === 'compact'
+  // DeepL can't be used without a key
+  const translationConfigured =
+    settings.translation.backend !== 'deepl' || settings.translation.deepl_api_key !== ''
+  const toggleCompact = () => {
+    const newSettings: UserSettings = {
+      ...settings,
+      ui_density: isCompact ? 'comfortable' : 'compact',
     }
-    return false
-  })
-
-  useEffect(() => {
-    localStorage.setItem('clipboard-history-compact-mode', String(isCompact))
-  }, [isCompact])
+    invoke('set_user_settings', { newSettings }).catch(console.error)
+  }
   const [isSearchVisible, setIsSearchVisible] = useState(false)
   const searchInputRef = useRef<HTMLInputElement>(null)
 
@@ -107,38 +169,174 @@ export function ClipboardTab(props: {
     }
   }, [])
 
-  // Filter history
-  const filteredHistory = useMemo(() => {
-    if (!searchQuery) return history
-
-    let regex: RegExp | null = null
-    if (isRegexMode) {
-      try {
-        regex = new RegExp(searchQuery, 'i')
-      } catch (err) {
-        console.error('Invalid regex pattern in clipboard search query:', searchQuery, err)
-        return []
-      }
+  // Open the search bar with the requested query
+  useEffect(() => {
+    if (searchRequest) {
+      // eslint-disable-next-line react-hooks/set-state-in-effect
+      setIsSearchVisible(true)
+      setSearchQuery(searchRequest.query)
     }
+  }, [searchRequest])
 
-    return history.filter((item) => {
-      let searchableText = ''
-      if (item.content.type === 'Text') {
-        searchableText = item.content.data
-      } else if (item.content.type === 'RichText') {
-        searchableText = item.content.data.plain
-      } else {
-        return false
-      }
+  // Focus the linked item, after the window-shown handler focused the first one
+  useEffect(() => {
+    if (!focusRequest) return
+    // eslint-disable-next-line react-hooks/set-state-in-effect
+    setIsSearchVisible(false)
+    setSearchQuery('')
+    const index = history.findIndex((item) => item.id === focusRequest.id)
+    if (index === -1) return
+    const timer = setTimeout(() => {
+      setFocusedIndex(index)
+      historyItemRefs.current[index]?.focus()
+      historyItemRefs.current[index]?.scrollIntoView({ block: 'center' })
+    }, 150)
+    return () => clearTimeout(timer)
+    // Only when a new link is opened, not on every history change
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+  }, [focusRequest])
 
-      if (isRegexMode && regex) {
-        return regex.test(searchableText)
-      } else if (!isRegexMode) {
-        return searchableText.toLowerCase().includes(searchQuery.toLowerCase())
-      }
+  // "#work" and "app:code" in a plain search, looked up by the backend
+  const search = useMemo(
+    () => (isRegexMode ? { tag: null, app: null, text: searchQuery } : parseSearch(searchQuery)),
+    [searchQuery, isRegexMode]
+  )
+
+  // Plain searches and collections are filtered by the backend, a page at a time
+  const filters = useMemo<HistoryFilters | null>(() => {
+    const text = isRegexMode ? '' : search.text
+    if (!text && !search.tag && !search.app && !activeCollection) return null
+    return { text, tag: search.tag, app: search.app, collection: activeCollection }
+  }, [search, isRegexMode, activeCollection])
+  const queried = useHistoryQuery(filters, history)
+  const apps = useMemo(() => sourceApps(history), [history])
+  // The chips swap the "app:" operator of the search, keeping the rest
+  const selectApp = (app: string | null) => {
+    setSearchQuery((query) => {
+      const rest = query.replace(/(?:^|\s)app:\S+/i, ' ').trim()
+      return app ? `${rest} app:${app}`.trim() : rest
+    })
+    if (app) {
+      setIsRegexMode(false)
+      setIsSearchVisible(true)
+    }
+  }
+  // Emoji and GIFs matching the same words, pasted from above the list
+  const unifiedResults = useUnifiedSearch(isRegexMode ? '' : search.text, settings.language)
+  const shownHistory = queried ? queried.items : history
+  const hasMore = queried ? queried.hasMore : hasMoreHistory
+  const loadMore = queried ? queried.loadMore : loadMoreHistory
+
+  // Regular expressions are matched here, against the items loaded so far
+  const filteredHistory = useMemo(() => {
+    if (!isRegexMode || !search.text) return shownHistory
+
+    let regex: RegExp
+    try {
+      regex = new RegExp(search.text, 'i')
+    } catch (err) {
+      console.error('Invalid regex pattern in clipboard search query:', search.text, err)
+      return []
+    }
+
+    return shownHistory.filter((item) => {
+      if (item.content.type === 'Text') return regex.test(item.content.data)
+      if (item.content.type === 'RichText') return regex.test(item.content.data.plain)
       return false
     })
-  }, [history, searchQuery, isRegexMode])
+  }, [shownHistory, isRegexMode, search])
+  const itemCount =
+    isRegexMode && search.text ? filteredHistory.length : (queried?.total ?? historyTotal)
+
+  // Load the next page once the end of the list scrolls into view
+  const loadMoreRef = useRef<HTMLDivElement>(null)
+  useEffect(() => {
+    const sentinel = loadMoreRef.current
+    if (!sentinel || !hasMore) return
+    const observer = new IntersectionObserver((entries) => {
+      if (entries.some((entry) => entry.isIntersecting)) loadMore()
+    })
+    observer.observe(sentinel)
+    return () => observer.disconnect()
+  }, [hasMore, loadMore])
+
+  // Actions of the Vim-style keymap on the focused item
+  const deleteAt = useCallback(
+    (index: number) => {
+      const item = filteredHistory[index]
+      if (!item) return
+      deleteItem(item.id)
+      setFocusedIndex(Math.max(0, Math.min(index, filteredHistory.length - 2)))
+    },
+    [filteredHistory, deleteItem]
+  )
+  const pasteAt = useCallback(
+    (index: number) => {
+      const item = filteredHistory[index]
+      if (item) onPaste(item.id)
+    },
+    [filteredHistory, onPaste]
+  )
+  const openSearch = useCallback(() => setIsSearchVisible(true), [])
+  const runAction = useCallback(
+    (action: KeyAction, index: number) => {
+      const item = filteredHistory[index]
+      const text =
+        item?.content.type === 'Text'
+          ? item.content.data
+          : item?.content.type === 'RichText'
+            ? item.content.data.plain
+            : null
+      switch (action) {
+        case 'next_tab':
+          tabBarRef.current?.selectAdjacentTab(1)
+          return
+        case 'previous_tab':
+          tabBarRef.current?.selectAdjacentTab(-1)
+          return
+        case 'search_gifs':
+          invoke('search_gifs', { query: '' }).catch(console.error)
+          return
+      }
+      if (!item) return
+      switch (action) {
+        case 'paste_and_submit':
+          onPasteAndSubmit(item.id)
+          break
+        case 'toggle_pin':
+          togglePin(item.id)
+          break
+        case 'type_out':
+          if (text !== null) onTypeOut(item.id)
+          break
+        case 'split': {
+          const delimiter = text === null ? null : getSplitDelimiter(text)
+          if (delimiter !== null) onSplit(item.id, delimiter)
+          break
+        }
+        case 'join_with_previous':
+          onJoinWithPrevious(item.id)
+          break
+        case 'clean_hidden_chars':
+          if (item.hidden_chars) onCleanHiddenChars(item.id)
+          break
+        case 'copy_link':
+          onCopyLink(item.id)
+          break
+      }
+    },
+    [
+      filteredHistory,
+      tabBarRef,
+      onPasteAndSubmit,
+      togglePin,
+      onTypeOut,
+      onSplit,
+      onJoinWithPrevious,
+      onCleanHiddenChars,
+      onCopyLink,
+    ]
+  )
 
   // Keyboard navigation
   useHistoryKeyboardNavigation({
@@ -148,6 +346,11 @@ export function ClipboardTab(props: {
     setFocusedIndex,
     historyItemRefs,
     tabBarRef,
+    vimNavigation: settings.vim_navigation || settings.keyboard_only,
+    onDelete: deleteAt,
+    onPaste: pasteAt,
+    onSearch: openSearch,
+    onAction: runAction,
   })
 
   // Ref for stable access to filtered history in event listener
@@ -156,6 +359,19 @@ export function ClipboardTab(props: {
     filteredHistoryRef.current = filteredHistory
   }, [filteredHistory])
 
+  // Ctrl+J joins the focused item with the previous one
+  useEffect(() => {
+    const handleJoin = (e: KeyboardEvent) => {
+      if (!e.ctrlKey || e.key !== 'j') return
+      const item = filteredHistoryRef.current[focusedIndex]
+      if (!item) return
+      e.preventDefault()
+      onJoinWithPrevious(item.id)
+    }
+    globalThis.addEventListener('keydown', handleJoin)
+    return () => globalThis.removeEventListener('keydown', handleJoin)
+  }, [focusedIndex, onJoinWithPrevious])
+
   useEffect(() => {
     const focusFirstItem = () => {
       setTimeout(() => {
@@ -187,11 +403,11 @@ export function ClipboardTab(props: {
     <>
       <Header
         onClearHistory={clearHistory}
-        itemCount={filteredHistory.length}
+        itemCount={itemCount}
         isDark={isDark}
         tertiaryOpacity={tertiaryOpacity}
         isCompact={isCompact}
-        onToggleCompact={() => setIsCompact(!isCompact)}
+        onToggleCompact={toggleCompact}
       />
       {/* Search Bar - only visible when Ctrl+F is pressed */}
       {isSearchVisible && (
@@ -202,7 +418,7 @@ export function ClipboardTab(props: {
             onChange={setSearchQuery}
             isDark={isDark}
             opacity={secondaryOpacity}
-            placeholder="Search history..."
+            placeholder="Search history, #tag or app:name..."
             isRegex={isRegexMode}
             onToggleRegex={() => setIsRegexMode(!isRegexMode)}
             onClear={() => {
@@ -213,6 +429,50 @@ export function ClipboardTab(props: {
         </div>
       )}
 
+      <CollectionBar
+        collections={collections}
+        activeId={activeCollection}
+        isDark={isDark}
+        onSelect={setActiveCollection}
+        onCreate={createCollection}
+        onRename={renameCollection}
+        onDelete={deleteCollection}
+      />
+
+      <SourceAppBar apps={apps} activeApp={search.app} isDark={isDark} onSelect={selectApp} />
+
+      <SearchMatches results={unifiedResults} isDark={isDark} />
+
+      {/* Things pasted every day, above the one-off copies */}
+      {!searchQuery && !activeCollection && frequent.length > 0 && (
+        <div className="px-3 pt-1" role="group" aria-label="Frequently used">
+          <div
+            className={clsx(
+              'text-xs font-medium mb-1.5',
+              isDark ? 'text-win11-text-secondary' : 'text-win11Light-text-secondary'
+            )}
+          >
+            Frequently used
+          </div>
+          <div className="flex flex-wrap gap-1.5">
+            {frequent.map((item) => (
+              <button
+                key={item.id}
+                onClick={() => onPaste(item.id)}
+                title={item.preview}
+                className={clsx(
+                  'max-w-[45%] truncate px-2.5 py-1 rounded-md text-xs transition-colors',
+                  'focus:outline-none focus-visible:ring-2 focus-visible:ring-win11-bg-accent',
+                  isDark ? 'bg-white/5 hover:bg-white/10' : 'bg-black/5 hover:bg-black/10'
+                )}
+              >
+                {item.content.type === 'Image' ? 'Image' : item.preview}
+              </button>
+            ))}
+          </div>
+        </div>
+      )}
+
       {filteredHistory.length === 0 ? (
         <div className="flex flex-col items-center justify-center p-8 text-center opacity-60">
           <p
@@ -233,9 +493,23 @@ export function ClipboardTab(props: {
                 historyItemRefs.current[index] = el
               }}
               item={item}
+              age={ages[item.id]}
+              summary={summaries[item.id]}
               index={index}
               isFocused={index === focusedIndex}
               onPaste={onPaste}
+              onPasteAndSubmit={onPasteAndSubmit}
+              onTypeOut={onTypeOut}
+              onSplit={onSplit}
+              onCleanHiddenChars={onCleanHiddenChars}
+              onCopyLink={onCopyLink}
+              onSetTags={onSetTags}
+              collections={collections}
+              onFileInto={onFileInto}
+              onQuickAction={onQuickAction}
+              shellActions={settings.shell_actions}
+              onShellAction={onShellAction}
+              onTranslate={translationConfigured ? onTranslate : undefined}
               onDelete={deleteItem}
               onTogglePin={togglePin}
               onFocus={() => setFocusedIndex(index)}
@@ -246,6 +520,7 @@ export function ClipboardTab(props: {
               enableUiPolish={settings.enable_ui_polish}
             />
           ))}
+          {hasMore && <div ref={loadMoreRef} className="h-px" aria-hidden />}
         </div>
       )}
     </>
@@ -0,0 +1,149 @@
+import { useState } from 'react'
+import { clsx } from 'clsx'
+import { Pencil, Plus, Trash2 } from 'lucide-react'
+import type { Collection } from '../types/clipboard'
+
+/**
+ * Chips to show all of history or one collection, with buttons to add a
+ * collection and to rename or delete the one shown
+ */
+export function CollectionBar({
+  collections,
+  activeId,
+  isDark,
+  onSelect,
+  onCreate,
+  onRename,
+  onDelete,
+}: {
+  collections: Collection[]
+  activeId: string | null
+  isDark: boolean
+  onSelect: (id: string | null) => void
+  onCreate: (name: string) => Promise<Collection>
+  onRename: (id: string, name: string) => Promise<unknown>
+  onDelete: (id: string) => Promise<unknown>
+}) {
+  // What the name input is for, if it is open
+  const [editing, setEditing] = useState<{ id: string | null; name: string } | null>(null)
+  const [error, setError] = useState<string | null>(null)
+
+  const chipClasses = (active: boolean) =>
+    clsx(
+      'px-2.5 py-1 rounded-md text-xs whitespace-nowrap transition-colors',
+      'focus:outline-none focus-visible:ring-2 focus-visible:ring-win11-bg-accent',
+      active
+        ? 'bg-win11-bg-accent text-white'
+        : isDark
+          ? 'bg-white/5 hover:bg-white/10'
+          : 'bg-black/5 hover:bg-black/10'
+    )
+  const iconButtonClasses = clsx(
+    'p-1 rounded-md transition-colors',
+    isDark
+      ? 'text-win11-text-tertiary hover:bg-win11-bg-tertiary'
+      : 'text-win11Light-text-secondary hover:bg-win11Light-bg-tertiary'
+  )
+
+  const submit = async () => {
+    if (!editing) return
+    try {
+      if (editing.id) {
+        await onRename(editing.id, editing.name)
+      } else {
+        const created = await onCreate(editing.name)
+        onSelect(created.id)
+      }
+      setEditing(null)
+      setError(null)
+    } catch (err) {
+      setError(String(err))
+    }
+  }
+
+  const active = collections.find((collection) => collection.id === activeId)
+
+  return (
+    <div className="px-3 pt-1">
+      <div className="flex items-center gap-1.5 overflow-x-auto" role="tablist" aria-label="Collections">
+        <button
+          role="tab"
+          aria-selected={activeId === null}
+          onClick={() => onSelect(null)}
+          className={chipClasses(activeId === null)}
+        >
+          All
+        </button>
+        {collections.map((collection) => (
+          <button
+            key={collection.id}
+            role="tab"
+            aria-selected={collection.id === activeId}
+            onClick={() => onSelect(collection.id)}
+            className={chipClasses(collection.id === activeId)}
+            title={collection.read_only ? 'Read-only snippet pack' : undefined}
+          >
+            {collection.name}
+          </button>
+        ))}
+        {editing ? (
+          <input
+            autoFocus
+            value={editing.name}
+            onChange={(e) => setEditing({ ...editing, name: e.target.value })}
+            onKeyDown={(e) => {
+              e.stopPropagation()
+              if (e.key === 'Enter') submit()
+              if (e.key === 'Escape') {
+                setEditing(null)
+                setError(null)
+              }
+            }}
+            onBlur={() => {
+              setEditing(null)
+              setError(null)
+            }}
+            placeholder="Collection name"
+            aria-label="Collection name"
+            className={clsx(
+              'w-32 px-2 py-1 rounded-md text-xs bg-transparent border focus:outline-none',
+              isDark ? 'border-white/10 text-white' : 'border-gray-200 text-gray-900'
+            )}
+          />
+        ) : (
+          <>
+            <button
+              onClick={() => setEditing({ id: null, name: '' })}
+              className={iconButtonClasses}
+              title="New collection"
+            >
+              <Plus className="w-3.5 h-3.5" />
+            </button>
+            {active && (
+              <>
+                <button
+                  onClick={() => setEditing({ id: active.id, name: active.name })}
+                  className={iconButtonClasses}
+                  title={`Rename ${active.name}`}
+                >
+                  <Pencil className="w-3.5 h-3.5" />
+                </button>
+                <button
+                  onClick={() => {
+                    onDelete(active.id).catch((err) => setError(String(err)))
+                    onSelect(null)
+                  }}
+                  className={clsx(iconButtonClasses, 'hover:text-win11-error')}
+                  title={`Delete ${active.name} (its items stay in history)`}
+                >
+                  <Trash2 className="w-3.5 h-3.5" />
+                </button>
+              </>
+            )}
+          </>
+        )}
+      </div>
+      {error && <p className="text-xs mt-1 text-win11-error">{error}</p>}
+    </div>
+  )
+}
@@ -0,0 +1,168 @@
+import { useState, useEffect, useCallback, useRef } from 'react'
+import { invoke } from '@tauri-apps/api/core'
+import { clsx } from 'clsx'
+import type { CustomEmoji } from '../types/clipboard'
+
+/** The file's bytes as base64, without the data URL prefix */
+function readAsBase64(file: File): Promise<string> {
+  return new Promise((resolve, reject) => {
+    const reader = new FileReader()
+    reader.onload = () => resolve(String(reader.result).split(',', 2)[1] ?? '')
+    reader.onerror = () => reject(reader.error)
+    reader.readAsDataURL(file)
+  })
+}
+
+export function CustomEmojiSection({ isDark }: { isDark: boolean }) {
+  const [emojis, setEmojis] = useState<CustomEmoji[]>([])
+  const [shortcode, setShortcode] = useState('')
+  const [text, setText] = useState('')
+  const [imageFile, setImageFile] = useState<File | null>(null)
+  const [error, setError] = useState<string | null>(null)
+  const fileInputRef = useRef<HTMLInputElement>(null)
+
+  const refresh = useCallback(() => {
+    invoke<CustomEmoji[]>('get_custom_emojis').then(setEmojis).catch(console.error)
+  }, [])
+
+  useEffect(() => {
+    refresh()
+  }, [refresh])
+
+  const mutedText = isDark ? 'text-gray-400' : 'text-gray-500'
+  const inputClasses = clsx(
+    'px-3 py-2 rounded-md border text-sm focus:outline-none focus:ring-2 focus:ring-win11-bg-accent/50 transition-all',
+    isDark
+      ? 'bg-white/5 border-white/10 text-white placeholder-gray-500'
+      : 'bg-gray-50 border-gray-200 text-gray-900 placeholder-gray-400'
+  )
+
+  const add = async () => {
+    if (!shortcode.trim() || (!text && !imageFile)) return
+    try {
+      const image = imageFile ? await readAsBase64(imageFile) : null
+      await invoke('add_custom_emoji', { shortcode, text, image })
+      setShortcode('')
+      setText('')
+      setImageFile(null)
+      if (fileInputRef.current) fileInputRef.current.value = ''
+      setError(null)
+      refresh()
+    } catch (err) {
+      setError(String(err))
+    }
+  }
+
+  const remove = async (code: string) => {
+    await invoke('remove_custom_emoji', { shortcode: code }).catch(console.error)
+    refresh()
+  }
+
+  return (
+    <section
+      className={clsx(
+        'rounded-xl border shadow-sm overflow-hidden',
+        isDark ? 'bg-win11-bg-secondary border-white/5' : 'bg-white border-gray-200/60'
+      )}
+    >
+      <div className="p-6 border-b border-inherit">
+        <h2 className="text-base font-semibold mb-1">Custom Emoji</h2>
+        <p className={clsx('text-xs', mutedText)}>
+          Shortcodes for a text or a small image. Search the emoji panel for :shortcode: to paste
+          them.
+        </p>
+      </div>
+      <div className="p-6 space-y-2">
+        {emojis.map((emoji) => (
+          <div
+            key={emoji.shortcode}
+            className={clsx(
+              'flex gap-2 items-center px-3 py-2 rounded-md border',
+              isDark ? 'bg-white/5 border-white/10' : 'bg-gray-50 border-gray-200'
+            )}
+          >
+            <span className="w-32 text-sm font-mono truncate" title={`:${emoji.shortcode}:`}>
+              :{emoji.shortcode}:
+            </span>
+            <span className="flex-1 min-w-0 text-sm truncate" title={emoji.text}>
+              {emoji.image ? (
+                <img
+                  src={`data:image/png;base64,${emoji.image}`}
+                  alt={emoji.shortcode}
+                  className="w-6 h-6 object-contain"
+                />
+              ) : (
+                emoji.text
+              )}
+            </span>
+            <button
+              onClick={() => remove(emoji.shortcode)}
+              className="p-1 text-red-500 hover:bg-red-500/10 rounded transition-all"
+              title="Delete"
+            >
+              <svg
+                width="14"
+                height="14"
+                viewBox="0 0 24 24"
+                fill="none"
+                stroke="currentColor"
+                strokeWidth="2"
+                strokeLinecap="round"
+                strokeLinejoin="round"
+              >
+                <path d="M18 6 6 18" />
+                <path d="m6 6 12 12" />
+              </svg>
+            </button>
+          </div>
+        ))}
+
+        <div className="flex gap-2">
+          <input
+            type="text"
+            value={shortcode}
+            onChange={(e) => setShortcode(e.target.value)}
+            placeholder=":shrug:"
+            className={clsx('w-32 font-mono', inputClasses)}
+          />
+          <input
+            type="text"
+            value={text}
+            onChange={(e) => setText(e.target.value)}
+            placeholder={imageFile ? imageFile.name : '¯\\_(ツ)_/¯'}
+            disabled={imageFile !== null}
+            className={clsx('flex-1 min-w-0', inputClasses)}
+            onKeyDown={(e) => {
+              if (e.key === 'Enter') {
+                add()
+              }
+            }}
+          />
+          <input
+            ref={fileInputRef}
+            type="file"
+            accept="image/*"
+            className="hidden"
+            onChange={(e) => setImageFile(e.target.files?.[0] ?? null)}
+          />
+          <button
+            onClick={() => fileInputRef.current?.click()}
+            className={clsx(
+              'px-3 py-2 rounded-md border text-sm transition-all',
+              isDark ? 'border-white/10 hover:bg-white/5' : 'border-gray-200 hover:bg-gray-50'
+            )}
+          >
+            Image…
+          </button>
+          <button
+            onClick={add}
+            className="px-4 py-2 bg-win11-bg-accent text-white rounded-md text-sm font-medium hover:opacity-90 active:scale-95 transition-all"
+          >
+            Add
+          </button>
+        </div>
+        {error && <p className="text-xs text-win11-error">{error}</p>}
+      </div>
+    </section>
+  )
+}
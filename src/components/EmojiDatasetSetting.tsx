@@ -0,0 +1,68 @@
+import { useState, useEffect } from 'react'
+import { invoke } from '@tauri-apps/api/core'
+import { clsx } from 'clsx'
+import type { EmojiDataset } from '../services/emojiService'
+
+/** Row of the Emoji settings for installing a newer emoji list */
+export function EmojiDatasetSetting({ isDark }: { isDark: boolean }) {
+  const [version, setVersion] = useState<string | null>(null)
+  const [path, setPath] = useState('')
+  const [message, setMessage] = useState<string | null>(null)
+
+  useEffect(() => {
+    invoke<EmojiDataset | null>('get_emoji_dataset')
+      .then((dataset) => setVersion(dataset?.version ?? null))
+      .catch(console.error)
+  }, [])
+
+  const install = async () => {
+    if (!path.trim()) return
+    try {
+      const installed = await invoke<string>('install_emoji_dataset', { path: path.trim() })
+      setVersion(installed)
+      setPath('')
+      setMessage(`Installed emoji ${installed}. Reopen the emoji panel to use it.`)
+    } catch (err) {
+      setMessage(String(err))
+    }
+  }
+
+  return (
+    <div className="space-y-2">
+      <div className="flex justify-between items-center">
+        <span className="text-sm font-medium">Emoji List</span>
+        <span className={clsx('text-xs', isDark ? 'text-gray-400' : 'text-gray-500')}>
+          {version ? `Unicode ${version}` : 'Built-in'}
+        </span>
+      </div>
+      <div className="flex gap-2">
+        <input
+          type="text"
+          value={path}
+          onChange={(e) => setPath(e.target.value)}
+          placeholder="/path/to/emoji_dataset.json"
+          className={clsx(
+            'flex-1 min-w-0 px-3 py-2 rounded-md border text-sm focus:outline-none focus:ring-2 focus:ring-win11-bg-accent/50',
+            isDark
+              ? 'bg-white/5 border-white/10 text-white placeholder-gray-500'
+              : 'bg-gray-50 border-gray-200 text-gray-900 placeholder-gray-400'
+          )}
+          onKeyDown={(e) => {
+            if (e.key === 'Enter') {
+              install()
+            }
+          }}
+        />
+        <button
+          onClick={install}
+          className="px-4 py-2 bg-win11-bg-accent text-white rounded-md text-sm font-medium hover:opacity-90 active:scale-95 transition-all"
+        >
+          Install
+        </button>
+      </div>
+      {message && (
+        <p className={clsx('text-xs', isDark ? 'text-gray-400' : 'text-gray-500')}>{message}</p>
+      )}
+    </div>
+  )
+}
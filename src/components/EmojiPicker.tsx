@@ -1,10 +1,11 @@
-import { useState, useCallback, memo, useRef } from 'react'
+import { useState, useCallback, useEffect, memo, useRef } from 'react'
 import { Grid, useGridRef } from 'react-window'
 import { clsx } from 'clsx'
 import { Clock } from 'lucide-react'
 import { useEmojiPicker } from '../hooks/useEmojiPicker'
 import { SearchBar } from './common/SearchBar'
 import type { Emoji } from '../services/emojiService'
+import type { EmojiVariantSettings, Language } from '../types/clipboard'
 
 import { PickerLayout } from './common/PickerLayout'
 import { CategoryStrip } from './common/CategoryStrip'
@@ -15,11 +16,15 @@ import { useKeyboardNavigation } from '../hooks/useKeyboardNavigation'
 const CELL_SIZE = 40
 /** Padding inside the grid container */
 const GRID_PADDING = 12
+/** How long a press lasts before it opens the variants, in ms */
+const LONG_PRESS_MS = 500
 
 interface EmojiCellProps {
   emoji: Emoji
   onSelect: (emoji: Emoji) => void
   onHover?: (emoji: Emoji | null) => void
+  /** Right click, the menu key or a long press */
+  onShowVariants?: (emoji: Emoji) => void
   tabIndex?: number
   'data-main-index'?: number
   'data-recent-index'?: number
@@ -32,17 +37,49 @@ const EmojiCell = memo(function EmojiCell({
   emoji,
   onSelect,
   onHover,
+  onShowVariants,
   tabIndex = -1,
   'data-main-index': mainIndex,
   'data-recent-index': recentIndex,
   onKeyDown,
   onItemFocus,
 }: EmojiCellProps) {
+  const pressTimer = useRef<number | null>(null)
+  // Set when a long press opened the variants, so its click doesn't paste
+  const longPressed = useRef(false)
+
+  const cancelPress = () => {
+    if (pressTimer.current !== null) window.clearTimeout(pressTimer.current)
+    pressTimer.current = null
+  }
+
   return (
     <button
-      onClick={() => onSelect(emoji)}
+      onClick={() => {
+        if (longPressed.current) {
+          longPressed.current = false
+          return
+        }
+        onSelect(emoji)
+      }}
+      onContextMenu={(e) => {
+        e.preventDefault()
+        onShowVariants?.(emoji)
+      }}
+      onPointerDown={(e) => {
+        if (e.button !== 0 || !onShowVariants) return
+        longPressed.current = false
+        pressTimer.current = window.setTimeout(() => {
+          longPressed.current = true
+          onShowVariants(emoji)
+        }, LONG_PRESS_MS)
+      }}
+      onPointerUp={cancelPress}
       onMouseEnter={() => onHover?.(emoji)}
-      onMouseLeave={() => onHover?.(null)}
+      onMouseLeave={() => {
+        cancelPress()
+        onHover?.(null)
+      }}
       onFocus={onItemFocus}
       onKeyDown={onKeyDown}
       tabIndex={tabIndex}
@@ -50,7 +87,8 @@ const EmojiCell = memo(function EmojiCell({
       data-recent-index={recentIndex}
       className={clsx(
         'flex items-center justify-center',
-        'w-full h-full text-2xl',
+        'w-full h-full',
+        emoji.shortcode && !emoji.image ? 'text-[10px] overflow-hidden' : 'text-2xl',
         'rounded-md transition-transform duration-100',
         'hover:bg-win11Light-bg-tertiary dark:hover:bg-win11-bg-card-hover',
         'hover:scale-110 transform-gpu hover:will-change-transform',
@@ -59,7 +97,11 @@ const EmojiCell = memo(function EmojiCell({
       title={emoji.name}
       aria-label={emoji.name}
     >
-      {emoji.char}
+      {emoji.image ? (
+        <img src={emoji.image} alt={emoji.name} className="w-6 h-6 object-contain" />
+      ) : (
+        emoji.char
+      )}
     </button>
   )
 })
@@ -68,6 +110,7 @@ interface EmojiGridData {
   emojis: Emoji[]
   onSelect: (emoji: Emoji) => void
   onHover: (emoji: Emoji | null) => void
+  onShowVariants: (emoji: Emoji) => void
   focusedIndex: number
   onKeyDown: (e: React.KeyboardEvent, index: number) => void
   onItemFocus: (index: number) => void
@@ -82,6 +125,7 @@ function EmojiGridCell({
   emojis,
   onSelect,
   onHover,
+  onShowVariants,
   focusedIndex,
   onKeyDown,
   onItemFocus,
@@ -118,6 +162,7 @@ function EmojiGridCell({
         emoji={emoji}
         onSelect={onSelect}
         onHover={onHover}
+        onShowVariants={onShowVariants}
         tabIndex={isFocused ? 0 : -1}
         data-main-index={index}
         onKeyDown={(e) => onKeyDown(e, index)}
@@ -130,9 +175,12 @@ function EmojiGridCell({
 export interface EmojiPickerProps {
   isDark: boolean
   opacity: number
+  emojiVariants?: EmojiVariantSettings
+  /** UI language, for the search synonyms */
+  language?: Language
 }
 
-export function EmojiPicker({ isDark, opacity }: EmojiPickerProps) {
+export function EmojiPicker({ isDark, opacity, emojiVariants, language }: EmojiPickerProps) {
   const {
     searchQuery,
     setSearchQuery,
@@ -143,9 +191,32 @@ export function EmojiPicker({ isDark, opacity }: EmojiPickerProps) {
     recentEmojis,
     isLoading,
     pasteEmoji,
-  } = useEmojiPicker()
+    getVariants,
+  } = useEmojiPicker(emojiVariants, language)
 
   const [hoveredEmoji, setHoveredEmoji] = useState<Emoji | null>(null)
+  // Skin tones and genders of the emoji last pressed long, shown in the footer
+  const [variants, setVariants] = useState<Emoji[] | null>(null)
+
+  const showVariants = useCallback(
+    async (emoji: Emoji) => {
+      const found = await getVariants(emoji)
+      setVariants(found.length > 1 ? found : null)
+    },
+    [getVariants]
+  )
+
+  useEffect(() => {
+    if (!variants) return
+    const onKeyDown = (e: KeyboardEvent) => {
+      if (e.key === 'Escape') {
+        e.stopPropagation()
+        setVariants(null)
+      }
+    }
+    window.addEventListener('keydown', onKeyDown, true)
+    return () => window.removeEventListener('keydown', onKeyDown, true)
+  }, [variants])
 
   const { containerRef, dimensions } = useResponsiveGrid()
 
@@ -179,6 +250,7 @@ export function EmojiPicker({ isDark, opacity }: EmojiPickerProps) {
   // Handle emoji selection
   const handleSelect = useCallback(
     (emoji: Emoji) => {
+      setVariants(null)
       pasteEmoji(emoji)
     },
     [pasteEmoji]
@@ -253,6 +325,7 @@ export function EmojiPicker({ isDark, opacity }: EmojiPickerProps) {
                       emoji={emoji}
                       onSelect={handleSelect}
                       onHover={setHoveredEmoji}
+                      onShowVariants={showVariants}
                       tabIndex={index === recentFocusedIndex ? 0 : -1}
                       data-recent-index={index}
                       onKeyDown={(e) => handleRecentKeyDown(e, index)}
@@ -279,7 +352,30 @@ export function EmojiPicker({ isDark, opacity }: EmojiPickerProps) {
         </>
       }
       footer={
-        hoveredEmoji ? (
+        variants ? (
+          <div
+            className="flex items-center gap-0.5 overflow-x-auto scrollbar-win11"
+            role="listbox"
+            aria-label={`Variants of ${variants[0].name}`}
+          >
+            {variants.map((variant) => (
+              <button
+                key={variant.char}
+                onClick={() => handleSelect(variant)}
+                className={clsx(
+                  'w-8 h-8 flex-shrink-0 flex items-center justify-center text-xl rounded-md',
+                  'hover:bg-win11Light-bg-tertiary dark:hover:bg-win11-bg-card-hover',
+                  'focus:outline-none focus-visible:ring-2 focus-visible:ring-win11-bg-accent'
+                )}
+                role="option"
+                aria-selected={false}
+                aria-label={variant.name}
+              >
+                {variant.char}
+              </button>
+            ))}
+          </div>
+        ) : hoveredEmoji ? (
           <>
             <span className="text-xl">{hoveredEmoji.char}</span>
             <span className="text-xs dark:text-win11-text-secondary text-win11Light-text-secondary truncate">
@@ -324,6 +420,7 @@ export function EmojiPicker({ isDark, opacity }: EmojiPickerProps) {
                   emojis: filteredEmojis,
                   onSelect: handleSelect,
                   onHover: setHoveredEmoji,
+                  onShowVariants: showVariants,
                   focusedIndex: mainFocusedIndex,
                   onKeyDown: handleMainGridKeyDown,
                   onItemFocus: setMainFocusedIndex,
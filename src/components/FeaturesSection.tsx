@@ -13,6 +13,46 @@ const FEATURES = [
     label: 'UI Polish',
     desc: 'Enable animations and compact mode support.',
   },
+  {
+    key: 'enable_primary_selection',
+    label: 'Selections Pane',
+    desc: 'Keep highlighted text in a separate tab, apart from clipboard history.',
+  },
+  {
+    key: 'pinned_git_enabled',
+    label: 'Git Versioning',
+    desc: 'Commit pinned items to a git repository (~/.local/share/win11-clipboard-history/pinned).',
+  },
+  {
+    key: 'confirm_suspicious_paste',
+    label: 'Paste Preview',
+    desc: 'Ask before pasting browser text with hidden characters or look-alike letters.',
+  },
+  {
+    key: 'quick_paste_shortcuts',
+    label: 'Quick Paste Shortcuts',
+    desc: 'Super+Ctrl+1..9 pastes the Nth most recent item without opening the window.',
+  },
+  {
+    key: 'vim_navigation',
+    label: 'Vim Keys',
+    desc: 'Move through history with j/k and gg/G, delete with dd, paste with p, search with /.',
+  },
+  {
+    key: 'keyboard_only',
+    label: 'Keyboard-Only Mode',
+    desc: 'Turn on the keys below for every action (pin, split, switch tabs, search GIFs...) and show the buttons of the focused item.',
+  },
+  {
+    key: 'show_tray_icon',
+    label: 'Tray Icon',
+    desc: 'Show the icon in the system tray. Without it, open the window with the shortcut or by running win11-clipboard-history.',
+  },
+  {
+    key: 'use_layer_shell',
+    label: 'Native Wayland Window',
+    desc: 'Place the window with wlr-layer-shell on wlroots compositors and KDE instead of through XWayland. Applies after a restart.',
+  },
 ] as const
 
 export function FeaturesSection({
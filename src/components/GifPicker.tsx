@@ -1,10 +1,11 @@
-import { useState, memo, useRef, useCallback } from 'react'
+import { useState, memo, useRef, useCallback, useEffect } from 'react'
 import { Grid, useGridRef } from 'react-window'
 import { clsx } from 'clsx'
-import { Search, RefreshCw, TrendingUp } from 'lucide-react'
+import { Search, RefreshCw, TrendingUp, Star, Clock } from 'lucide-react'
 import { useGifPicker } from '../hooks/useGifPicker'
 import { SearchBar } from './common/SearchBar'
-import type { Gif } from '../types/gif'
+import type { Gif, GifView } from '../types/gif'
+import type { GifProvider } from '../types/clipboard'
 import { getTertiaryBackgroundStyle } from '../utils/themeUtils'
 
 import { PickerLayout } from './common/PickerLayout'
@@ -26,6 +27,8 @@ interface GifCellProps {
   'data-gif-index'?: number
   onKeyDown?: (e: React.KeyboardEvent) => void
   onItemFocus?: () => void
+  isFavorite: boolean
+  onToggleFavorite: (gif: Gif) => void
 }
 
 /** Individual GIF cell - memoized for performance */
@@ -37,12 +40,14 @@ const GifCell = memo(function GifCell({
   'data-gif-index': gifIndex,
   onKeyDown,
   onItemFocus,
+  isFavorite,
+  onToggleFavorite,
 }: GifCellProps) {
   const [isLoaded, setIsLoaded] = useState(false)
   const [hasError, setHasError] = useState(false)
 
   return (
-    <div style={style} className="p-1">
+    <div style={style} className="p-1 relative group">
       <button
         onClick={() => onSelect(gif)}
         onFocus={onItemFocus}
@@ -87,6 +92,22 @@ const GifCell = memo(function GifCell({
           onError={() => setHasError(true)}
         />
       </button>
+
+      {/* Favorite toggle, beside the cell button as buttons can't nest */}
+      <button
+        onClick={() => onToggleFavorite(gif)}
+        tabIndex={-1}
+        className={clsx(
+          'absolute top-2 right-2 p-1 rounded-full bg-black/50 text-white',
+          'transition-opacity duration-150',
+          isFavorite ? 'opacity-100' : 'opacity-0 group-hover:opacity-100'
+        )}
+        title={isFavorite ? 'Remove from favorites' : 'Add to favorites'}
+        aria-label={isFavorite ? 'Remove from favorites' : 'Add to favorites'}
+        aria-pressed={isFavorite}
+      >
+        <Star size={12} fill={isFavorite ? 'currentColor' : 'none'} />
+      </button>
     </div>
   )
 })
@@ -141,6 +162,8 @@ interface GifGridData {
   focusedIndex: number
   onKeyDown: (e: React.KeyboardEvent, index: number) => void
   onItemFocus: (index: number) => void
+  isFavorite: (gif: Gif) => boolean
+  onToggleFavorite: (gif: Gif) => void
 }
 
 function GifGridCell({
@@ -152,6 +175,8 @@ function GifGridCell({
   focusedIndex,
   onKeyDown,
   onItemFocus,
+  isFavorite,
+  onToggleFavorite,
   ariaAttributes,
 }: {
   columnIndex: number
@@ -179,6 +204,8 @@ function GifGridCell({
         data-gif-index={index}
         onKeyDown={(e) => onKeyDown(e, index)}
         onItemFocus={() => onItemFocus(index)}
+        isFavorite={isFavorite(gif)}
+        onToggleFavorite={onToggleFavorite}
       />
     </div>
   )
@@ -187,9 +214,20 @@ function GifGridCell({
 export interface GifPickerProps {
   isDark: boolean
   opacity: number
+  /** Search requested by the backend (`search_gifs`), a new object each time */
+  searchRequest?: { query: string } | null
+  /** Provider picked in the settings, named in the footer */
+  provider?: GifProvider
 }
 
-export function GifPicker({ isDark, opacity }: GifPickerProps) {
+/** Footer credit of each provider */
+const PROVIDER_NAMES: Record<GifProvider, string> = {
+  tenor: 'Tenor',
+  giphy: 'GIPHY',
+  self_hosted: 'your GIF server',
+}
+
+export function GifPicker({ isDark, opacity, searchRequest, provider = 'tenor' }: GifPickerProps) {
   const {
     searchQuery,
     setSearchQuery,
@@ -199,7 +237,11 @@ export function GifPicker({ isDark, opacity }: GifPickerProps) {
     error,
     pasteGif,
     refreshTrending,
-  } = useGifPicker()
+    view,
+    setView,
+    isFavorite,
+    toggleFavorite,
+  } = useGifPicker(provider)
 
   const inputRef = useRef<HTMLInputElement>(null)
   const gridRef = useGridRef(null)
@@ -217,6 +259,13 @@ export function GifPicker({ isDark, opacity }: GifPickerProps) {
     [pasteGif]
   )
 
+  // An empty query only focuses the search box
+  useEffect(() => {
+    if (!searchRequest) return
+    if (searchRequest.query) setSearchQuery(searchRequest.query)
+    inputRef.current?.focus()
+  }, [searchRequest, setSearchQuery])
+
   // Clear search
   const handleClearSearch = useCallback(() => {
     setSearchQuery('')
@@ -241,6 +290,32 @@ export function GifPicker({ isDark, opacity }: GifPickerProps) {
     dataAttributeName: 'data-gif-index',
   })
 
+  // Header button switching what the grid lists
+  const viewButton = (
+    target: GifView,
+    icon: React.ReactNode,
+    title: string,
+    onClick: () => void
+  ) => (
+    <button
+      onClick={onClick}
+      className={clsx(
+        'p-1 rounded',
+        view === target
+          ? 'dark:text-win11-text-primary text-win11Light-text-primary'
+          : 'dark:text-win11-text-disabled text-win11Light-text-disabled',
+        'hover:dark:text-win11-text-primary hover:text-win11Light-text-primary',
+        'hover:dark:bg-win11-bg-card-hover hover:bg-win11Light-bg-card-hover',
+        'transition-colors duration-150'
+      )}
+      title={title}
+      aria-pressed={view === target}
+      style={getTertiaryBackgroundStyle(isDark, opacity)}
+    >
+      {icon}
+    </button>
+  )
+
   // Render grid content based on state
   const renderGridContent = () => {
     if (isLoading && gifs.length === 0) {
@@ -252,7 +327,17 @@ export function GifPicker({ isDark, opacity }: GifPickerProps) {
     }
 
     if (gifs.length === 0) {
-      return <EmptyState message="No GIFs found. Try a different search!" />
+      return (
+        <EmptyState
+          message={
+            view === 'favorites'
+              ? 'No favorites yet. Star a GIF to keep it here.'
+              : view === 'recent'
+                ? 'GIFs you paste show up here.'
+                : 'No GIFs found. Try a different search!'
+          }
+        />
+      )
     }
 
     if (dimensions.width > 0 && dimensions.height > 0) {
@@ -279,6 +364,8 @@ export function GifPicker({ isDark, opacity }: GifPickerProps) {
               focusedIndex,
               onKeyDown: handleGridKeyDown,
               onItemFocus: setFocusedIndex,
+              isFavorite,
+              onToggleFavorite: toggleFavorite,
             }}
             cellComponent={GifGridCell}
           />
@@ -297,31 +384,34 @@ export function GifPicker({ isDark, opacity }: GifPickerProps) {
           value={searchQuery}
           onChange={(val: string) => setSearchQuery(val)}
           onClear={handleClearSearch}
-          placeholder="Search Tenor GIFs..."
-          aria-label="Search Tenor GIFs"
+          placeholder="Search GIFs..."
+          aria-label="Search GIFs"
           isDark={isDark}
           opacity={opacity}
           rightActions={
-            <button
-              onClick={refreshTrending}
-              className={clsx(
-                'p-1 rounded',
-                'dark:text-win11-text-disabled text-win11Light-text-disabled',
-                'hover:dark:text-win11-text-primary hover:text-win11Light-text-primary',
-                'hover:dark:bg-win11-bg-card-hover hover:bg-win11Light-bg-card-hover',
-                'transition-colors duration-150'
+            <div className="flex gap-1">
+              {viewButton('browse', <TrendingUp size={14} />, 'Show trending', refreshTrending)}
+              {viewButton('favorites', <Star size={14} />, 'Show favorites', () =>
+                setView('favorites')
               )}
-              title="Show trending"
-              style={getTertiaryBackgroundStyle(isDark, opacity)}
-            >
-              <TrendingUp size={14} />
-            </button>
+              {viewButton('recent', <Clock size={14} />, 'Show recent', () => setView('recent'))}
+            </div>
           }
         />
       }
       subHeader={
         <div className="flex items-center gap-2 text-xs dark:text-win11-text-secondary text-win11Light-text-secondary">
-          {searchQuery ? (
+          {view === 'favorites' ? (
+            <>
+              <Star size={12} />
+              <span>Favorite GIFs</span>
+            </>
+          ) : view === 'recent' ? (
+            <>
+              <Clock size={12} />
+              <span>Recent GIFs</span>
+            </>
+          ) : searchQuery ? (
             <>
               <Search size={12} />
               <span>Results for "{searchQuery}"</span>
@@ -338,7 +428,7 @@ export function GifPicker({ isDark, opacity }: GifPickerProps) {
       footer={
         <div className="w-full text-center">
           <span className="text-[10px] dark:text-win11-text-disabled text-win11Light-text-disabled">
-            Powered by Tenor
+            Powered by {PROVIDER_NAMES[provider]}
           </span>
         </div>
       }
@@ -0,0 +1,152 @@
+import { useState } from 'react'
+import { invoke } from '@tauri-apps/api/core'
+import { clsx } from 'clsx'
+import type { CacheCleanup, GifProvider, GifSettings } from '../types/clipboard'
+
+/** Kept in step with MAX_CACHE_LIMIT_MB in gif_provider.rs */
+const MAX_CACHE_LIMIT_MB = 10240
+
+const PROVIDER_OPTIONS: { value: GifProvider; label: string }[] = [
+  { value: 'tenor', label: 'Tenor' },
+  { value: 'giphy', label: 'GIPHY' },
+  { value: 'self_hosted', label: 'Self-hosted' },
+]
+
+export function GifProviderSection({
+  gifs,
+  isDark,
+  onChange,
+}: {
+  gifs: GifSettings
+  isDark: boolean
+  onChange: (partial: Partial<GifSettings>) => void
+}) {
+  const mutedText = isDark ? 'text-gray-400' : 'text-gray-500'
+  const [cleared, setCleared] = useState<string | null>(null)
+
+  const clearCache = async () => {
+    try {
+      const { files, bytes } = await invoke<CacheCleanup>('clear_gif_cache')
+      setCleared(`Deleted ${files} GIFs, ${(bytes / (1024 * 1024)).toFixed(1)} MB`)
+    } catch (err) {
+      setCleared(String(err))
+    }
+  }
+  const inputClasses = clsx(
+    'w-full px-3 py-2 text-sm border rounded-md focus:outline-none focus:ring-2 focus:ring-win11-bg-accent/50',
+    isDark
+      ? 'bg-white/5 border-white/10 text-white placeholder-gray-500'
+      : 'bg-gray-50 border-gray-200 text-gray-900 placeholder-gray-400'
+  )
+
+  // Text fields save on blur, so typing a key doesn't write the settings on every keystroke
+  const textField = (
+    key: 'tenor_api_key' | 'giphy_api_key' | 'self_hosted_url' | 'self_hosted_api_key',
+    label: string,
+    placeholder: string,
+    secret = false
+  ) => (
+    <div>
+      <label htmlFor={`gifs-${key}`} className="text-sm font-medium">
+        {label}
+      </label>
+      <input
+        id={`gifs-${key}`}
+        type={secret ? 'password' : 'text'}
+        // Remount when the saved value changes so the uncontrolled input picks it up
+        key={gifs[key]}
+        defaultValue={gifs[key]}
+        placeholder={placeholder}
+        onBlur={(e) => onChange({ [key]: e.target.value })}
+        className={clsx('mt-1', inputClasses)}
+      />
+    </div>
+  )
+
+  return (
+    <section
+      className={clsx(
+        'rounded-xl border shadow-sm overflow-hidden',
+        isDark ? 'bg-win11-bg-secondary border-white/5' : 'bg-white border-gray-200/60'
+      )}
+    >
+      <div className="p-6 border-b border-inherit">
+        <h2 className="text-base font-semibold mb-1">GIFs</h2>
+        <p className={clsx('text-xs', mutedText)}>Service the GIF panel searches.</p>
+      </div>
+      <div className="p-6 space-y-4">
+        <div>
+          <label htmlFor="gifs-provider" className="text-sm font-medium">
+            Provider
+          </label>
+          <select
+            id="gifs-provider"
+            value={gifs.provider}
+            onChange={(e) => onChange({ provider: e.target.value as GifProvider })}
+            className={clsx('mt-1', inputClasses)}
+          >
+            {PROVIDER_OPTIONS.map(({ value, label }) => (
+              <option key={value} value={value}>
+                {label}
+              </option>
+            ))}
+          </select>
+        </div>
+
+        {gifs.provider === 'tenor' &&
+          textField('tenor_api_key', 'Tenor API key', 'Blank for the shared demo key', true)}
+        {gifs.provider === 'giphy' &&
+          textField('giphy_api_key', 'GIPHY API key', 'From developers.giphy.com', true)}
+        {gifs.provider === 'self_hosted' && (
+          <>
+            {textField('self_hosted_url', 'Server URL', 'https://gifs.example.org/v1')}
+            {textField('self_hosted_api_key', 'API key', 'Only if the server asks for one', true)}
+          </>
+        )}
+
+        <div className="flex justify-between items-center">
+          <div>
+            <label htmlFor="gifs-cache-limit" className="text-sm font-medium">
+              Cache Size (MB)
+            </label>
+            <p className={clsx('text-xs', mutedText)}>
+              The least recently pasted GIFs go past this. 0 for no limit
+            </p>
+          </div>
+          <input
+            id="gifs-cache-limit"
+            type="number"
+            min={0}
+            max={MAX_CACHE_LIMIT_MB}
+            value={gifs.cache_limit_mb}
+            onChange={(e) => {
+              const parsed = Number.parseInt(e.target.value, 10)
+              if (Number.isNaN(parsed)) return
+              onChange({ cache_limit_mb: Math.min(MAX_CACHE_LIMIT_MB, Math.max(0, parsed)) })
+            }}
+            className={clsx(
+              'w-28 text-right font-mono border rounded-md transition-all focus:outline-none focus:ring-2 focus:ring-win11-bg-accent/50',
+              'input-number-compact no-number-spinner',
+              isDark
+                ? 'bg-white/5 border-white/10 text-white'
+                : 'bg-gray-50 border-gray-200 text-gray-900'
+            )}
+          />
+        </div>
+
+        <div className="flex justify-between items-center">
+          <span className={clsx('text-xs', mutedText)}>{cleared}</span>
+          <button
+            onClick={clearCache}
+            className={clsx(
+              'px-3 py-2 rounded-md border text-sm transition-all',
+              isDark ? 'border-white/10 hover:bg-white/5' : 'border-gray-200 hover:bg-gray-50'
+            )}
+          >
+            Clear GIF Cache
+          </button>
+        </div>
+      </div>
+    </section>
+  )
+}
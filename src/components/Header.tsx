@@ -11,6 +11,7 @@ interface HeaderProps {
   isCompact: boolean
   onToggleCompact: () => void
   showCompactToggle?: boolean
+  title?: string
 }
 
 /**
@@ -24,6 +25,7 @@ export function Header({
   isCompact,
   onToggleCompact,
   showCompactToggle = true,
+  title = 'Clipboard',
 }: HeaderProps) {
   const [isHovered, setIsHovered] = useState(false)
   const [isCompactHovered, setIsCompactHovered] = useState(false)
@@ -38,7 +40,7 @@ export function Header({
             isDark ? 'text-win11-text-primary' : 'text-win11Light-text-primary'
           )}
         >
-          Clipboard
+          {title}
         </h1>
         {itemCount > 0 && (
           <span
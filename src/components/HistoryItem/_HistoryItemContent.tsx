@@ -1,5 +1,24 @@
 import { clsx } from 'clsx'
 import type { ClipboardItem } from '../../types/clipboard'
+import { useImageThumbnail } from '../../hooks/useImageThumbnail'
+import { HiddenCharText } from '../common/HiddenCharText'
+import { AppIcon } from '../SourceAppBar'
+
+/** Older images are stored as WebP or JPEG once compressed; tell them apart by their signature */
+function imageDataUrl(base64: string) {
+  const mime = base64.startsWith('UklGR')
+    ? 'image/webp'
+    : base64.startsWith('/9j/')
+      ? 'image/jpeg'
+      : 'image/png'
+  return `data:${mime};base64,${base64}`
+}
+
+function formatTextSize(bytes: number) {
+  return bytes >= 1024 * 1024
+    ? `${(bytes / (1024 * 1024)).toFixed(1)} MB`
+    : `${Math.round(bytes / 1024)} KB`
+}
 
 export function TextContent({
   item,
@@ -15,15 +34,32 @@ export function TextContent({
   const textToDisplay = item.content.type === 'Text' ? item.content.data : item.content.data.plain
 
   return (
-    <p
-      className={clsx(
-        'text-sm break-words whitespace-pre-wrap',
-        effectiveCompact ? 'line-clamp-1' : 'line-clamp-3',
-        isDark ? 'text-win11-text-primary' : 'text-win11Light-text-primary'
+    <>
+      <p
+        className={clsx(
+          'text-sm break-words whitespace-pre-wrap',
+          effectiveCompact ? 'line-clamp-1' : 'line-clamp-3',
+          isDark ? 'text-win11-text-primary' : 'text-win11Light-text-primary',
+          item.files_missing && 'line-through opacity-60'
+        )}
+      >
+        {item.hidden_chars ? <HiddenCharText text={textToDisplay} /> : textToDisplay}
+      </p>
+      {item.files_missing && (
+        <p className="text-xs mt-0.5 text-win11-error">File no longer exists</p>
       )}
-    >
-      {textToDisplay}
-    </p>
+      {item.large_text && !effectiveCompact && (
+        <p
+          className={clsx(
+            'text-xs mt-0.5',
+            isDark ? 'text-win11-text-tertiary' : 'text-win11Light-text-secondary'
+          )}
+        >
+          Start of {formatTextSize(item.large_text.bytes)} (
+          {item.large_text.lines.toLocaleString()} lines); pasting uses the full text
+        </p>
+      )}
+    </>
   )
 }
 
@@ -36,6 +72,9 @@ export function ImageContent({
   isDark: boolean
   effectiveCompact: boolean
 }) {
+  const isImage = item.content.type === 'Image'
+  const thumbnail = useImageThumbnail(item.id, isImage && !effectiveCompact)
+
   if (item.content.type !== 'Image') return null
   const { width, height, base64 } = item.content.data
 
@@ -52,13 +91,23 @@ export function ImageContent({
     )
   }
 
+  // Decoding the full image is what the thumbnails avoid; only do it when they
+  // fail. Evicted images have no data here to fall back to.
+  const src =
+    thumbnail.src ?? (thumbnail.failed && !item.evicted ? imageDataUrl(base64) : null)
+
   return (
     <div className="relative">
-      <img
-        src={`data:image/png;base64,${base64}`}
-        alt="Clipboard image"
-        className="max-w-full max-h-24 rounded object-contain bg-black/10"
-      />
+      {src ? (
+        <img
+          src={src}
+          alt="Clipboard image"
+          className="max-w-full max-h-24 rounded object-contain bg-black/10"
+        />
+      ) : (
+        // Holds the row's height until the thumbnail arrives
+        <div className="w-full rounded bg-black/10" style={{ height: Math.min(height, 96) }} />
+      )}
       <span className="absolute bottom-1 right-1 text-xs px-1.5 py-0.5 rounded bg-black/60 text-white">
         {width}×{height}
       </span>
@@ -70,10 +119,15 @@ export function Timestamp({
   show,
   isDark,
   timestamp,
+  age,
+  sourceApp,
 }: {
   show: boolean
   isDark: boolean
   timestamp: string
+  age?: string
+  /** Shown as its icon next to the age */
+  sourceApp?: string
 }) {
   if (!show) return null
 
@@ -93,11 +147,13 @@ export function Timestamp({
   return (
     <span
       className={clsx(
-        'text-xs mt-1 block',
+        'text-xs mt-1 flex items-center gap-1',
         isDark ? 'text-win11-text-tertiary' : 'text-win11Light-text-secondary'
       )}
+      title={sourceApp ? `Copied from ${sourceApp}` : undefined}
     >
-      {formatTime(timestamp)}
+      <AppIcon appId={sourceApp} className="w-3 h-3" />
+      {age ?? formatTime(timestamp)}
     </span>
   )
 }
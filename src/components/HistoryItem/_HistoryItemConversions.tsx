@@ -0,0 +1,74 @@
+import { useEffect, useState } from 'react'
+import { clsx } from 'clsx'
+import { invoke } from '@tauri-apps/api/core'
+import { ClipboardPaste } from 'lucide-react'
+import type { ClipboardItem, Conversion } from '../../types/clipboard'
+
+/** A number followed by a unit or currency, or a currency sign before one */
+const QUANTITY_HINT = /\d\s?[^\s\d.,]|[$€£¥₹]\s?\d/
+
+/** Quantities of a text clip converted into the user's units, each pastable on its own */
+export function ConversionEntries({ item, isDark }: { item: ClipboardItem; isDark: boolean }) {
+  const [conversions, setConversions] = useState<Conversion[]>([])
+
+  const text = item.content.type === 'Text' ? item.content.data : ''
+
+  useEffect(() => {
+    // Skip the round trip for texts without anything that looks like a quantity
+    if (!QUANTITY_HINT.test(text)) {
+      setConversions([])
+      return
+    }
+    let cancelled = false
+    invoke<Conversion[]>('get_conversions', { id: item.id })
+      .then((found) => {
+        if (!cancelled) setConversions(found)
+      })
+      .catch(console.error)
+    return () => {
+      cancelled = true
+    }
+  }, [item.id, text])
+
+  if (conversions.length === 0) return null
+
+  const handlePaste = (e: React.MouseEvent, converted: string) => {
+    e.stopPropagation()
+    invoke('paste_text', { text: converted }).catch(console.error)
+  }
+
+  return (
+    <ul
+      className={clsx(
+        'mt-2 flex flex-col gap-0.5 border-l-2 pl-2',
+        isDark ? 'border-win11-border-subtle' : 'border-win11Light-border'
+      )}
+    >
+      {conversions.map(({ original, converted }) => (
+        <li key={original} className="flex items-center gap-1 min-w-0">
+          <span
+            className={clsx(
+              'flex-1 truncate text-xs',
+              isDark ? 'text-win11-text-secondary' : 'text-win11Light-text-secondary'
+            )}
+          >
+            {original} ≈ {converted}
+          </span>
+          <button
+            onClick={(e) => handlePaste(e, converted)}
+            className={clsx(
+              'p-1 rounded-md transition-colors',
+              isDark
+                ? 'text-win11-text-tertiary hover:bg-win11-bg-tertiary'
+                : 'text-win11Light-text-secondary hover:bg-win11Light-bg-tertiary'
+            )}
+            title={`Paste ${converted}`}
+            tabIndex={-1}
+          >
+            <ClipboardPaste className="w-3.5 h-3.5" />
+          </button>
+        </li>
+      ))}
+    </ul>
+  )
+}
@@ -0,0 +1,103 @@
+import { clsx } from 'clsx'
+import { invoke } from '@tauri-apps/api/core'
+import { Copy, FolderOpen, Mail, Phone, Radar } from 'lucide-react'
+import type { LucideIcon } from 'lucide-react'
+import type { Entity, EntityKind } from '../../types/clipboard'
+
+/** The second button of each kind of entity, next to "copy" */
+const ENTITY_ACTIONS: Record<
+  EntityKind,
+  { title: string; icon: LucideIcon; run: (value: string) => Promise<unknown> }
+> = {
+  email: {
+    title: 'Write an email',
+    icon: Mail,
+    run: (value) => invoke('open_url', { url: `mailto:${value}` }),
+  },
+  phone: {
+    title: 'Call',
+    icon: Phone,
+    run: (value) => invoke('open_url', { url: `tel:${value.replace(/[^\d+]/g, '')}` }),
+  },
+  ip: {
+    title: 'Ping this address',
+    icon: Radar,
+    run: (address) => invoke('ping_address', { address }),
+  },
+  path: {
+    title: 'Show in file manager',
+    icon: FolderOpen,
+    run: (path) => invoke('reveal_path', { path }),
+  },
+}
+
+const KIND_LABELS: Record<EntityKind, string> = {
+  email: 'email',
+  phone: 'phone number',
+  ip: 'address',
+  path: 'path',
+}
+
+/** One row per email, phone number, IP or path found in a text clip */
+export function EntityEntries({ entities, isDark }: { entities: Entity[]; isDark: boolean }) {
+  if (entities.length === 0) return null
+
+  const buttonClasses = clsx(
+    'p-1 rounded-md transition-colors',
+    isDark
+      ? 'text-win11-text-tertiary hover:bg-win11-bg-tertiary'
+      : 'text-win11Light-text-secondary hover:bg-win11Light-bg-tertiary'
+  )
+
+  const handleCopy = (e: React.MouseEvent, value: string) => {
+    e.stopPropagation()
+    invoke('copy_text_to_clipboard', { text: value }).catch(console.error)
+  }
+
+  const handleAction = (e: React.MouseEvent, entity: Entity) => {
+    e.stopPropagation()
+    ENTITY_ACTIONS[entity.kind].run(entity.value).catch(console.error)
+  }
+
+  return (
+    <ul
+      className={clsx(
+        'mt-2 flex flex-col gap-0.5 border-l-2 pl-2',
+        isDark ? 'border-win11-border-subtle' : 'border-win11Light-border'
+      )}
+    >
+      {entities.map((entity) => {
+        const action = ENTITY_ACTIONS[entity.kind]
+        return (
+          <li key={entity.value} className="flex items-center gap-1 min-w-0">
+            <span
+              className={clsx(
+                'flex-1 truncate text-xs',
+                isDark ? 'text-win11-text-secondary' : 'text-win11Light-text-secondary'
+              )}
+              title={entity.value}
+            >
+              {entity.value}
+            </span>
+            <button
+              onClick={(e) => handleCopy(e, entity.value)}
+              className={buttonClasses}
+              title={`Copy just this ${KIND_LABELS[entity.kind]}`}
+              tabIndex={-1}
+            >
+              <Copy className="w-3.5 h-3.5" />
+            </button>
+            <button
+              onClick={(e) => handleAction(e, entity)}
+              className={buttonClasses}
+              title={action.title}
+              tabIndex={-1}
+            >
+              <action.icon className="w-3.5 h-3.5" />
+            </button>
+          </li>
+        )
+      })}
+    </ul>
+  )
+}
@@ -0,0 +1,53 @@
+import { useEffect, useState } from 'react'
+import { clsx } from 'clsx'
+import { invoke } from '@tauri-apps/api/core'
+
+/**
+ * A Markdown clip rendered as formatted text. The HTML comes sanitized from
+ * the backend; clicking the row still pastes the raw Markdown.
+ */
+export function MarkdownPreview({ itemId, isDark }: { itemId: string; isDark: boolean }) {
+  const [html, setHtml] = useState<string | null>(null)
+
+  useEffect(() => {
+    let cancelled = false
+    invoke<string>('render_markdown', { id: itemId })
+      .then((rendered) => {
+        if (!cancelled) setHtml(rendered)
+      })
+      .catch(console.error)
+    return () => {
+      cancelled = true
+    }
+  }, [itemId])
+
+  if (html === null) return null
+
+  // Links open in the browser instead of pasting the item
+  const handleClick = (e: React.MouseEvent) => {
+    const link = (e.target as HTMLElement).closest('a')
+    if (!link) return
+    e.preventDefault()
+    e.stopPropagation()
+    invoke('open_url', { url: link.getAttribute('href') }).catch(console.error)
+  }
+
+  return (
+    <div
+      onClick={handleClick}
+      className={clsx(
+        'text-sm break-words max-h-48 overflow-y-auto',
+        '[&_h1]:text-base [&_h1]:font-semibold [&_h2]:font-semibold [&_h3]:font-semibold',
+        '[&_h4]:font-medium [&_h5]:font-medium [&_h6]:font-medium',
+        '[&_p]:my-1 [&_ul]:list-disc [&_ol]:list-decimal [&_ul]:pl-5 [&_ol]:pl-5',
+        '[&_blockquote]:border-l-2 [&_blockquote]:pl-2 [&_blockquote]:opacity-80',
+        '[&_pre]:my-1 [&_pre]:p-2 [&_pre]:rounded-md [&_pre]:overflow-x-auto [&_pre]:text-xs',
+        '[&_code]:font-mono [&_a]:underline [&_hr]:my-2',
+        isDark
+          ? 'text-win11-text-primary [&_pre]:bg-win11-bg-tertiary [&_hr]:border-win11-border-subtle'
+          : 'text-win11Light-text-primary [&_pre]:bg-win11Light-bg-tertiary [&_hr]:border-win11Light-border'
+      )}
+      dangerouslySetInnerHTML={{ __html: html }}
+    />
+  )
+}
@@ -0,0 +1,109 @@
+import { useEffect, useId, useState } from 'react'
+import { clsx } from 'clsx'
+import { invoke } from '@tauri-apps/api/core'
+import { X } from 'lucide-react'
+
+/**
+ * The item's tags as removable chips, plus an input with autocomplete from
+ * the tags already in use while `isEditing`
+ */
+export function TagList({
+  tags,
+  isEditing,
+  isDark,
+  onChange,
+  onDoneEditing,
+}: {
+  tags: string[]
+  isEditing: boolean
+  isDark: boolean
+  onChange: (tags: string[]) => void
+  onDoneEditing: () => void
+}) {
+  const [draft, setDraft] = useState('')
+  const [suggestions, setSuggestions] = useState<string[]>([])
+  const listId = useId()
+
+  useEffect(() => {
+    if (!isEditing) return
+    let cancelled = false
+    invoke<string[]>('suggest_tags', { prefix: draft })
+      .then((found) => {
+        if (!cancelled) setSuggestions(found.filter((tag) => !tags.includes(tag)))
+      })
+      .catch(console.error)
+    return () => {
+      cancelled = true
+    }
+  }, [draft, isEditing, tags])
+
+  if (tags.length === 0 && !isEditing) return null
+
+  const commitDraft = () => {
+    if (draft.trim()) onChange([...tags, draft])
+    setDraft('')
+  }
+
+  return (
+    <div className="mt-1.5 flex flex-wrap items-center gap-1" onClick={(e) => e.stopPropagation()}>
+      {tags.map((tag) => (
+        <span
+          key={tag}
+          className={clsx(
+            'inline-flex items-center gap-0.5 px-1.5 py-0.5 rounded text-[11px]',
+            isDark ? 'bg-white/10 text-win11-text-secondary' : 'bg-black/5 text-win11Light-text-secondary'
+          )}
+        >
+          #{tag}
+          {isEditing && (
+            <button
+              onClick={() => onChange(tags.filter((other) => other !== tag))}
+              className="hover:text-win11-error"
+              title={`Remove ${tag}`}
+              tabIndex={-1}
+            >
+              <X className="w-3 h-3" />
+            </button>
+          )}
+        </span>
+      ))}
+      {isEditing && (
+        <>
+          <input
+            autoFocus
+            value={draft}
+            list={listId}
+            onChange={(e) => setDraft(e.target.value)}
+            onKeyDown={(e) => {
+              e.stopPropagation()
+              if (e.key === 'Enter' || e.key === ',') {
+                e.preventDefault()
+                commitDraft()
+              } else if (e.key === 'Escape') {
+                setDraft('')
+                onDoneEditing()
+              } else if (e.key === 'Backspace' && !draft && tags.length > 0) {
+                onChange(tags.slice(0, -1))
+              }
+            }}
+            onBlur={() => {
+              commitDraft()
+              onDoneEditing()
+            }}
+            placeholder="Add tag"
+            aria-label="Add tag"
+            className={clsx(
+              'w-24 px-1.5 py-0.5 rounded text-[11px] bg-transparent border focus:outline-none',
+              isDark ? 'border-white/10 text-white' : 'border-gray-200 text-gray-900'
+            )}
+          />
+          <datalist id={listId}>
+            {suggestions.map((tag) => (
+              <option key={tag} value={tag} />
+            ))}
+          </datalist>
+        </>
+      )}
+    </div>
+  )
+}
@@ -0,0 +1,65 @@
+import { clsx } from 'clsx'
+import { invoke } from '@tauri-apps/api/core'
+import { ClipboardPaste, ExternalLink } from 'lucide-react'
+import { smartActionService } from '../../services/smartActionService'
+
+/** One row per URL found in a multi-link clip, each pastable and openable on its own */
+export function UrlEntries({ urls, isDark }: { urls: string[]; isDark: boolean }) {
+  if (urls.length === 0) return null
+
+  const buttonClasses = clsx(
+    'p-1 rounded-md transition-colors',
+    isDark
+      ? 'text-win11-text-tertiary hover:bg-win11-bg-tertiary'
+      : 'text-win11Light-text-secondary hover:bg-win11Light-bg-tertiary'
+  )
+
+  const handlePaste = (e: React.MouseEvent, url: string) => {
+    e.stopPropagation()
+    invoke('paste_text', { text: url }).catch(console.error)
+  }
+
+  const handleOpen = (e: React.MouseEvent, url: string) => {
+    e.stopPropagation()
+    smartActionService.execute({ id: 'open-link', label: 'Open Link', data: url }).catch(() => {})
+  }
+
+  return (
+    <ul
+      className={clsx(
+        'mt-2 flex flex-col gap-0.5 border-l-2 pl-2',
+        isDark ? 'border-win11-border-subtle' : 'border-win11Light-border'
+      )}
+    >
+      {urls.map((url) => (
+        <li key={url} className="flex items-center gap-1 min-w-0">
+          <span
+            className={clsx(
+              'flex-1 truncate text-xs',
+              isDark ? 'text-win11-text-secondary' : 'text-win11Light-text-secondary'
+            )}
+            title={url}
+          >
+            {url}
+          </span>
+          <button
+            onClick={(e) => handlePaste(e, url)}
+            className={buttonClasses}
+            title="Paste this link"
+            tabIndex={-1}
+          >
+            <ClipboardPaste className="w-3.5 h-3.5" />
+          </button>
+          <button
+            onClick={(e) => handleOpen(e, url)}
+            className={buttonClasses}
+            title="Open this link"
+            tabIndex={-1}
+          >
+            <ExternalLink className="w-3.5 h-3.5" />
+          </button>
+        </li>
+      ))}
+    </ul>
+  )
+}
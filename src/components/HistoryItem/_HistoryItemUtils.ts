@@ -4,6 +4,18 @@ export function getIconSize(effectiveCompact: boolean) {
   return effectiveCompact ? 'w-3 h-3' : 'w-4 h-4'
 }
 
+/**
+ * Picks the delimiter a text item would be split on: lines first, then
+ * semicolons, tabs and commas. Returns null when there is nothing to split.
+ */
+export function getSplitDelimiter(text: string): string | null {
+  for (const delimiter of ['\n', ';', '\t', ',']) {
+    const pieces = text.split(delimiter).filter((piece) => piece.trim() !== '')
+    if (pieces.length > 1) return delimiter
+  }
+  return null
+}
+
 export function getIconContainerClasses(effectiveCompact: boolean) {
   return clsx(
     'flex-shrink-0 rounded-md flex items-center justify-center',
@@ -1,18 +1,67 @@
-import { useCallback, forwardRef } from 'react'
+import { useCallback, useMemo, useState, forwardRef } from 'react'
 import { clsx } from 'clsx'
-import { Pin, X, Image as ImageIcon, Type } from 'lucide-react'
-import type { ClipboardItem } from '../../types/clipboard'
+import {
+  Pin,
+  X,
+  Image as ImageIcon,
+  Type,
+  Keyboard,
+  Scissors,
+  Eraser,
+  Link,
+  Tag,
+  FolderInput,
+  FolderOpen,
+  SquarePen,
+  SquareTerminal,
+  Languages,
+  FileText,
+} from 'lucide-react'
+import type { ClipboardItem, Collection, QuickAction, ShellAction } from '../../types/clipboard'
 import { getCardBackgroundStyle, getTertiaryBackgroundStyle } from '../../utils/themeUtils'
 import { useSmartActions } from '../../hooks/useSmartActions'
 import { HistorySmartActions } from '../HistorySmartActions'
 import { TextContent, ImageContent, Timestamp } from './_HistoryItemContent'
-import { getIconSize, getIconContainerClasses } from './_HistoryItemUtils'
+import { UrlEntries } from './_HistoryItemUrls'
+import { ConversionEntries } from './_HistoryItemConversions'
+import { EntityEntries } from './_HistoryItemEntities'
+import { MarkdownPreview } from './_HistoryItemMarkdown'
+import { TagList } from './_HistoryItemTags'
+import { getIconSize, getIconContainerClasses, getSplitDelimiter } from './_HistoryItemUtils'
 
 interface HistoryItemProps {
   item: ClipboardItem
+  /** Humanized age from the backend, worked out locally until it arrives */
+  age?: string
+  /** What screen readers say for the item, from the backend */
+  summary?: string
   onPaste: (id: string) => void
+  /** Shift+Enter: paste, then press the submit key */
+  onPasteAndSubmit?: (id: string) => void
+  /** Types the text key by key instead of pasting; omit to hide the button */
+  onTypeOut?: (id: string) => void
+  /** Splits the text into one item per line or delimited piece; omit to hide the button */
+  onSplit?: (id: string, delimiter: string) => void
+  /** Adds a copy without invisible characters; shown only for items that have some */
+  onCleanHiddenChars?: (id: string) => void
+  /** Adds a translation of the text as a new item; omit to hide the button */
+  onTranslate?: (id: string) => void
+  /** Copies a clip:// link back to this item; omit to hide the button */
+  onCopyLink?: (id: string) => void
+  /** Replaces the item's tags; omit to hide the button */
+  onSetTags?: (id: string, tags: string[]) => void
+  /** Collections the item can be filed into */
+  collections?: Collection[]
+  /** Files the item into a collection, or out of one with null; omit to hide the button */
+  onFileInto?: (id: string, collection: string | null) => void
+  /** Shows copied files or opens the text in the editor; omit to hide the buttons */
+  onQuickAction?: (id: string, action: QuickAction) => void
+  /** User-defined commands the text can be piped to */
+  shellActions?: ShellAction[]
+  onShellAction?: (id: string, name: string) => void
   onDelete: (id: string) => void
-  onTogglePin: (id: string) => void
+  /** Omit to hide the pin button (e.g. for the selections pane) */
+  onTogglePin?: (id: string) => void
   onFocus?: () => void
   index: number
   isFocused?: boolean
@@ -27,7 +76,21 @@ interface HistoryItemProps {
 export const HistoryItem = forwardRef<HTMLDivElement, HistoryItemProps>(function HistoryItem(
   {
     item,
+    age,
+    summary,
     onPaste,
+    onPasteAndSubmit,
+    onTypeOut,
+    onSplit,
+    onCleanHiddenChars,
+    onTranslate,
+    onCopyLink,
+    onSetTags,
+    collections = [],
+    onFileInto,
+    onQuickAction,
+    shellActions = [],
+    onShellAction,
     onDelete,
     onTogglePin,
     onFocus,
@@ -42,6 +105,18 @@ export const HistoryItem = forwardRef<HTMLDivElement, HistoryItemProps>(function
   ref
 ) {
   const isText = item.content.type === 'Text' || item.content.type === 'RichText'
+  const isFileList =
+    item.content.type === 'Text' && item.content.data.trimStart().startsWith('file://')
+  const splitDelimiter = useMemo(() => {
+    if (item.content.type === 'Text') return getSplitDelimiter(item.content.data)
+    if (item.content.type === 'RichText') return getSplitDelimiter(item.content.data.plain)
+    return null
+  }, [item])
+
+  const [isEditingTags, setIsEditingTags] = useState(false)
+  const [isFilingMenuOpen, setIsFilingMenuOpen] = useState(false)
+  const [isShellMenuOpen, setIsShellMenuOpen] = useState(false)
+  const [showFormatted, setShowFormatted] = useState(false)
 
   // Use compact mode only if enabled by flag
   const effectiveCompact = enableUiPolish ? isCompact : false
@@ -49,7 +124,7 @@ export const HistoryItem = forwardRef<HTMLDivElement, HistoryItemProps>(function
   const iconContainerClasses = getIconContainerClasses(effectiveCompact)
 
   // Smart Actions Hook
-  const { colorPreview, linkAction, emailAction, handleSmartAction } = useSmartActions(
+  const { colorPreview, linkAction, emailAction, urlEntries, handleSmartAction } = useSmartActions(
     item,
     enableSmartActions
   )
@@ -68,11 +143,82 @@ export const HistoryItem = forwardRef<HTMLDivElement, HistoryItemProps>(function
     [item.id, onDelete]
   )
 
+  // Handle type-out with stopPropagation
+  const handleTypeOut = useCallback(
+    (e: React.MouseEvent) => {
+      e.stopPropagation()
+      onTypeOut?.(item.id)
+    },
+    [item.id, onTypeOut]
+  )
+
+  // Handle split with stopPropagation
+  const handleSplit = useCallback(
+    (e: React.MouseEvent) => {
+      e.stopPropagation()
+      if (splitDelimiter !== null) onSplit?.(item.id, splitDelimiter)
+    },
+    [item.id, onSplit, splitDelimiter]
+  )
+
+  // Handle cleaning with stopPropagation
+  const handleCleanHiddenChars = useCallback(
+    (e: React.MouseEvent) => {
+      e.stopPropagation()
+      onCleanHiddenChars?.(item.id)
+    },
+    [item.id, onCleanHiddenChars]
+  )
+
+  // Handle translation with stopPropagation
+  const handleTranslate = useCallback(
+    (e: React.MouseEvent) => {
+      e.stopPropagation()
+      onTranslate?.(item.id)
+    },
+    [item.id, onTranslate]
+  )
+
+  // Handle link copy with stopPropagation
+  const handleCopyLink = useCallback(
+    (e: React.MouseEvent) => {
+      e.stopPropagation()
+      onCopyLink?.(item.id)
+    },
+    [item.id, onCopyLink]
+  )
+
+  // Open the tag editor with stopPropagation
+  const handleEditTags = useCallback((e: React.MouseEvent) => {
+    e.stopPropagation()
+    setIsEditingTags(true)
+  }, [])
+
+  // Toggle the collection menu with stopPropagation
+  const handleToggleFilingMenu = useCallback((e: React.MouseEvent) => {
+    e.stopPropagation()
+    setIsFilingMenuOpen((open) => !open)
+  }, [])
+
+  const handleToggleShellMenu = useCallback((e: React.MouseEvent) => {
+    e.stopPropagation()
+    setIsShellMenuOpen((open) => !open)
+  }, [])
+
+  // Run a backend quick action with stopPropagation
+  const handleQuickAction = useCallback(
+    (e: React.MouseEvent, action: QuickAction) => {
+      e.stopPropagation()
+      onQuickAction?.(item.id, action)
+    },
+    [item.id, onQuickAction]
+  )
+
   // Handle pin toggle with stopPropagation
   const handleTogglePin = useCallback(
     (e: React.MouseEvent) => {
       e.stopPropagation()
-      onTogglePin(item.id)
+      onTogglePin?.(item.id)
     },
     [item.id, onTogglePin]
   )
@@ -99,9 +245,13 @@ export const HistoryItem = forwardRef<HTMLDivElement, HistoryItemProps>(function
       onClick={handleClick}
       onFocus={onFocus}
       role="button"
+      aria-label={summary ? [summary, age].filter(Boolean).join(', ') : undefined}
       tabIndex={isFocused ? 0 : -1}
       onKeyDown={(e) => {
-        if (e.key === 'Enter' || e.key === ' ') {
+        if (e.key === 'Enter' && e.shiftKey && onPasteAndSubmit) {
+          e.preventDefault()
+          onPasteAndSubmit(item.id)
+        } else if (e.key === 'Enter' || e.key === ' ') {
           e.preventDefault()
           handleClick()
         }
@@ -142,15 +292,39 @@ export const HistoryItem = forwardRef<HTMLDivElement, HistoryItemProps>(function
 
         {/* Content */}
         <div className="flex-1 min-w-0">
-          <TextContent item={item} isDark={isDark} effectiveCompact={effectiveCompact} />
+          {showFormatted && item.markdown && !effectiveCompact ? (
+            <MarkdownPreview itemId={item.id} isDark={isDark} />
+          ) : (
+            <TextContent item={item} isDark={isDark} effectiveCompact={effectiveCompact} />
+          )}
           <ImageContent item={item} isDark={isDark} effectiveCompact={effectiveCompact} />
-          <Timestamp show={!effectiveCompact} isDark={isDark} timestamp={item.timestamp} />
+          {!effectiveCompact && <UrlEntries urls={urlEntries} isDark={isDark} />}
+          {!effectiveCompact && enableSmartActions && (
+            <ConversionEntries item={item} isDark={isDark} />
+          )}
+          {!effectiveCompact && enableSmartActions && (
+            <EntityEntries entities={item.entities ?? []} isDark={isDark} />
+          )}
+          <TagList
+            tags={item.tags ?? []}
+            isEditing={isEditingTags}
+            isDark={isDark}
+            onChange={(tags) => onSetTags?.(item.id, tags)}
+            onDoneEditing={() => setIsEditingTags(false)}
+          />
+          <Timestamp
+            show={!effectiveCompact}
+            isDark={isDark}
+            timestamp={item.timestamp}
+            age={age}
+            sourceApp={item.source_app}
+          />
         </div>
 
         {/* Action buttons - visible on hover */}
         <div
           className={clsx(
-            'flex items-center gap-1 opacity-0 group-hover:opacity-100',
+            'flex items-center gap-1 opacity-0 group-hover:opacity-100 group-focus-visible:opacity-100',
             'transition-opacity duration-150'
           )}
         >
@@ -162,23 +336,293 @@ export const HistoryItem = forwardRef<HTMLDivElement, HistoryItemProps>(function
             onActionClick={handleSmartAction}
           />
 
+          {/* Reveal copied files button */}
+          {onQuickAction && isFileList && (
+            <button
+              onClick={(e) => handleQuickAction(e, 'reveal_file')}
+              className={clsx(
+                'p-1.5 rounded-md transition-colors',
+                isDark
+                  ? 'text-win11-text-tertiary hover:bg-win11-bg-tertiary'
+                  : 'text-win11Light-text-secondary hover:bg-win11Light-bg-tertiary'
+              )}
+              title="Show in file manager"
+              tabIndex={-1}
+            >
+              <FolderOpen className="w-4 h-4" />
+            </button>
+          )}
+
+          {/* Open in editor button */}
+          {onQuickAction && isText && !isFileList && (
+            <button
+              onClick={(e) => handleQuickAction(e, 'open_in_editor')}
+              className={clsx(
+                'p-1.5 rounded-md transition-colors',
+                isDark
+                  ? 'text-win11-text-tertiary hover:bg-win11-bg-tertiary'
+                  : 'text-win11Light-text-secondary hover:bg-win11Light-bg-tertiary'
+              )}
+              title="Open in editor"
+              tabIndex={-1}
+            >
+              <SquarePen className="w-4 h-4" />
+            </button>
+          )}
+
+          {/* Formatted Markdown toggle; pasting stays raw */}
+          {item.markdown && !effectiveCompact && (
+            <button
+              onClick={(e) => {
+                e.stopPropagation()
+                setShowFormatted((shown) => !shown)
+              }}
+              className={clsx(
+                'p-1.5 rounded-md transition-colors',
+                showFormatted
+                  ? isDark
+                    ? 'text-win11-text-primary bg-win11-bg-tertiary'
+                    : 'text-win11Light-text-primary bg-win11Light-bg-tertiary'
+                  : isDark
+                    ? 'text-win11-text-tertiary hover:bg-win11-bg-tertiary'
+                    : 'text-win11Light-text-secondary hover:bg-win11Light-bg-tertiary'
+              )}
+              title={showFormatted ? 'Show Markdown source' : 'Show formatted'}
+              tabIndex={-1}
+            >
+              <FileText className="w-4 h-4" />
+            </button>
+          )}
+
+          {/* Type-out button */}
+          {onTypeOut && isText && (
+            <button
+              onClick={handleTypeOut}
+              className={clsx(
+                'p-1.5 rounded-md transition-colors',
+                isDark
+                  ? 'text-win11-text-tertiary hover:bg-win11-bg-tertiary'
+                  : 'text-win11Light-text-secondary hover:bg-win11Light-bg-tertiary'
+              )}
+              title="Type it out"
+              tabIndex={-1}
+            >
+              <Keyboard className="w-4 h-4" />
+            </button>
+          )}
+
+          {/* Split button */}
+          {onSplit && splitDelimiter !== null && (
+            <button
+              onClick={handleSplit}
+              className={clsx(
+                'p-1.5 rounded-md transition-colors',
+                isDark
+                  ? 'text-win11-text-tertiary hover:bg-win11-bg-tertiary'
+                  : 'text-win11Light-text-secondary hover:bg-win11Light-bg-tertiary'
+              )}
+              title="Split into separate items"
+              tabIndex={-1}
+            >
+              <Scissors className="w-4 h-4" />
+            </button>
+          )}
+
+          {/* Clean invisible characters button */}
+          {onCleanHiddenChars && item.hidden_chars && (
+            <button
+              onClick={handleCleanHiddenChars}
+              className={clsx(
+                'p-1.5 rounded-md transition-colors',
+                isDark
+                  ? 'text-win11-text-tertiary hover:bg-win11-bg-tertiary'
+                  : 'text-win11Light-text-secondary hover:bg-win11Light-bg-tertiary'
+              )}
+              title="Clean invisible characters"
+              tabIndex={-1}
+            >
+              <Eraser className="w-4 h-4" />
+            </button>
+          )}
+
+          {/* Translate button */}
+          {onTranslate && isText && !isFileList && (
+            <button
+              onClick={handleTranslate}
+              className={clsx(
+                'p-1.5 rounded-md transition-colors',
+                isDark
+                  ? 'text-win11-text-tertiary hover:bg-win11-bg-tertiary'
+                  : 'text-win11Light-text-secondary hover:bg-win11Light-bg-tertiary'
+              )}
+              title="Translate"
+              tabIndex={-1}
+            >
+              <Languages className="w-4 h-4" />
+            </button>
+          )}
+
+          {/* Copy link button */}
+          {onCopyLink && (
+            <button
+              onClick={handleCopyLink}
+              className={clsx(
+                'p-1.5 rounded-md transition-colors',
+                isDark
+                  ? 'text-win11-text-tertiary hover:bg-win11-bg-tertiary'
+                  : 'text-win11Light-text-secondary hover:bg-win11Light-bg-tertiary'
+              )}
+              title="Copy link to this item"
+              tabIndex={-1}
+            >
+              <Link className="w-4 h-4" />
+            </button>
+          )}
+
+          {/* Tag button */}
+          {onSetTags && (
+            <button
+              onClick={handleEditTags}
+              className={clsx(
+                'p-1.5 rounded-md transition-colors',
+                isDark
+                  ? 'text-win11-text-tertiary hover:bg-win11-bg-tertiary'
+                  : 'text-win11Light-text-secondary hover:bg-win11Light-bg-tertiary'
+              )}
+              title="Edit tags"
+              tabIndex={-1}
+            >
+              <Tag className="w-4 h-4" />
+            </button>
+          )}
+
+          {/* File into collection button */}
+          {onFileInto && collections.length > 0 && (
+            <div className="relative">
+              <button
+                onClick={handleToggleFilingMenu}
+                className={clsx(
+                  'p-1.5 rounded-md transition-colors',
+                  isDark ? 'hover:bg-win11-bg-tertiary' : 'hover:bg-win11Light-bg-tertiary',
+                  item.collection
+                    ? 'text-win11-bg-accent'
+                    : isDark
+                      ? 'text-win11-text-tertiary'
+                      : 'text-win11Light-text-secondary'
+                )}
+                title="File into collection"
+                aria-haspopup="menu"
+                aria-expanded={isFilingMenuOpen}
+                tabIndex={-1}
+              >
+                <FolderInput className="w-4 h-4" />
+              </button>
+              {isFilingMenuOpen && (
+                <div
+                  role="menu"
+                  className={clsx(
+                    'absolute right-0 top-full mt-1 z-10 min-w-[9rem] py-1 rounded-md border shadow-lg text-xs',
+                    isDark
+                      ? 'bg-win11-bg-secondary border-win11-border-subtle'
+                      : 'bg-white border-win11Light-border'
+                  )}
+                  onMouseLeave={() => setIsFilingMenuOpen(false)}
+                >
+                  {[{ id: null, name: 'No collection' }, ...collections].map((collection) => (
+                    <button
+                      key={collection.id ?? ''}
+                      role="menuitemradio"
+                      aria-checked={(item.collection ?? null) === collection.id}
+                      onClick={(e) => {
+                        e.stopPropagation()
+                        setIsFilingMenuOpen(false)
+                        onFileInto(item.id, collection.id)
+                      }}
+                      className={clsx(
+                        'block w-full text-left px-3 py-1.5 truncate',
+                        isDark ? 'hover:bg-white/10' : 'hover:bg-black/5',
+                        (item.collection ?? null) === collection.id && 'font-semibold'
+                      )}
+                    >
+                      {collection.name}
+                    </button>
+                  ))}
+                </div>
+              )}
+            </div>
+          )}
+
+          {/* Shell actions menu */}
+          {onShellAction && isText && shellActions.length > 0 && (
+            <div className="relative">
+              <button
+                onClick={handleToggleShellMenu}
+                className={clsx(
+                  'p-1.5 rounded-md transition-colors',
+                  isDark
+                    ? 'text-win11-text-tertiary hover:bg-win11-bg-tertiary'
+                    : 'text-win11Light-text-secondary hover:bg-win11Light-bg-tertiary'
+                )}
+                title="Run action"
+                aria-haspopup="menu"
+                aria-expanded={isShellMenuOpen}
+                tabIndex={-1}
+              >
+                <SquareTerminal className="w-4 h-4" />
+              </button>
+              {isShellMenuOpen && (
+                <div
+                  role="menu"
+                  className={clsx(
+                    'absolute right-0 top-full mt-1 z-10 min-w-[9rem] py-1 rounded-md border shadow-lg text-xs',
+                    isDark
+                      ? 'bg-win11-bg-secondary border-win11-border-subtle'
+                      : 'bg-white border-win11Light-border'
+                  )}
+                  onMouseLeave={() => setIsShellMenuOpen(false)}
+                >
+                  {shellActions.map((action) => (
+                    <button
+                      key={action.name}
+                      role="menuitem"
+                      title={action.command}
+                      onClick={(e) => {
+                        e.stopPropagation()
+                        setIsShellMenuOpen(false)
+                        onShellAction(item.id, action.name)
+                      }}
+                      className={clsx(
+                        'block w-full text-left px-3 py-1.5 truncate',
+                        isDark ? 'hover:bg-white/10' : 'hover:bg-black/5'
+                      )}
+                    >
+                      {action.name}
+                    </button>
+                  ))}
+                </div>
+              )}
+            </div>
+          )}
+
           {/* Pin button */}
-          <button
-            onClick={handleTogglePin}
-            className={clsx(
-              'p-1.5 rounded-md transition-colors',
-              isDark ? 'hover:bg-win11-bg-tertiary' : 'hover:bg-win11Light-bg-tertiary',
-              item.pinned
-                ? 'text-win11-bg-accent'
-                : isDark
-                  ? 'text-win11-text-tertiary'
-                  : 'text-win11Light-text-secondary'
-            )}
-            title={item.pinned ? 'Unpin' : 'Pin'}
-            tabIndex={-1}
-          >
-            <Pin className="w-4 h-4" fill={item.pinned ? 'currentColor' : 'none'} />
-          </button>
+          {onTogglePin && (
+            <button
+              onClick={handleTogglePin}
+              className={clsx(
+                'p-1.5 rounded-md transition-colors',
+                isDark ? 'hover:bg-win11-bg-tertiary' : 'hover:bg-win11Light-bg-tertiary',
+                item.pinned
+                  ? 'text-win11-bg-accent'
+                  : isDark
+                    ? 'text-win11-text-tertiary'
+                    : 'text-win11Light-text-secondary'
+              )}
+              title={item.pinned ? 'Unpin' : 'Pin'}
+              tabIndex={-1}
+            >
+              <Pin className="w-4 h-4" fill={item.pinned ? 'currentColor' : 'none'} />
+            </button>
+          )}
 
           {/* Delete button */}
           <button
@@ -0,0 +1,118 @@
+import { useState, useEffect, useCallback } from 'react'
+import { invoke } from '@tauri-apps/api/core'
+import { clsx } from 'clsx'
+import type { UsageInsights } from '../types/clipboard'
+
+/** Days shown in the copies chart */
+const INSIGHT_DAYS = 30
+
+export function InsightsSection({ isDark }: { isDark: boolean }) {
+  const [insights, setInsights] = useState<UsageInsights | null>(null)
+
+  const refresh = useCallback(() => {
+    invoke<UsageInsights>('get_usage_insights', { days: INSIGHT_DAYS })
+      .then(setInsights)
+      .catch(console.error)
+  }, [])
+
+  useEffect(() => {
+    refresh()
+  }, [refresh])
+
+  const clear = async () => {
+    await invoke('clear_usage_stats')
+    refresh()
+  }
+
+  const maxPerDay = Math.max(1, ...(insights?.copies_per_day.map((day) => day.count) ?? []))
+  const mutedText = isDark ? 'text-gray-400' : 'text-gray-500'
+
+  return (
+    <section
+      className={clsx(
+        'rounded-xl border shadow-sm overflow-hidden',
+        isDark ? 'bg-win11-bg-secondary border-white/5' : 'bg-white border-gray-200/60'
+      )}
+    >
+      <div className="p-6 border-b border-inherit">
+        <h2 className="text-base font-semibold mb-1">Insights</h2>
+        <p className={clsx('text-xs', mutedText)}>
+          How you use the clipboard. Counted on this computer only.
+        </p>
+      </div>
+      {insights && (
+        <div className="p-6 space-y-6">
+          <div>
+            <div className="flex justify-between text-sm font-medium mb-2">
+              <span>Copies, last {INSIGHT_DAYS} days</span>
+              <span className={mutedText}>
+                {insights.total_copies} copies, {insights.total_pastes} pastes in total
+              </span>
+            </div>
+            <div className="flex items-end gap-0.5 h-16" role="img" aria-label="Copies per day">
+              {insights.copies_per_day.map((day) => (
+                <div
+                  key={day.date}
+                  title={`${day.date}: ${day.count}`}
+                  className="flex-1 rounded-sm bg-win11-bg-accent"
+                  style={{
+                    height: `${(day.count / maxPerDay) * 100}%`,
+                    minHeight: 2,
+                    opacity: day.count ? 1 : 0.2,
+                  }}
+                />
+              ))}
+            </div>
+          </div>
+
+          <div className="grid grid-cols-2 gap-6">
+            <div>
+              <h3 className="text-sm font-medium mb-2">Most Pasted</h3>
+              {insights.most_pasted.length ? (
+                <ol className="space-y-1 text-xs">
+                  {insights.most_pasted.map((item) => (
+                    <li key={item.id} className="flex justify-between gap-2">
+                      <span className="truncate">{item.preview}</span>
+                      <span className={mutedText}>{item.count}</span>
+                    </li>
+                  ))}
+                </ol>
+              ) : (
+                <p className={clsx('text-xs italic', mutedText)}>Nothing pasted yet</p>
+              )}
+            </div>
+            <div>
+              <h3 className="text-sm font-medium mb-2">Copied From</h3>
+              {insights.top_source_apps.length ? (
+                <ol className="space-y-1 text-xs">
+                  {insights.top_source_apps.map((entry) => (
+                    <li key={entry.app} className="flex justify-between gap-2">
+                      <span className="truncate">{entry.app}</span>
+                      <span className={mutedText}>{entry.count}</span>
+                    </li>
+                  ))}
+                </ol>
+              ) : (
+                <p className={clsx('text-xs italic', mutedText)}>No apps recorded yet</p>
+              )}
+            </div>
+          </div>
+
+          <div className="flex justify-end">
+            <button
+              onClick={clear}
+              className={clsx(
+                'px-4 py-2 text-sm font-medium rounded-lg transition-all',
+                isDark
+                  ? 'text-gray-400 hover:bg-red-500/10 hover:text-red-400'
+                  : 'text-gray-500 hover:bg-red-50 hover:text-red-600'
+              )}
+            >
+              Clear statistics
+            </button>
+          </div>
+        </div>
+      )}
+    </section>
+  )
+}
@@ -43,6 +43,21 @@ export function KaomojiPicker({ isDark, opacity, customKaomojis = [] }: KaomojiP
     setGridFocusedIndex(0)
   }, [searchQuery, selectedCategory])
 
+  const [recentTexts, setRecentTexts] = useState<string[]>([])
+
+  const loadRecent = useCallback(async () => {
+    try {
+      const recent: { char: string }[] = await invoke('get_recent_kaomojis')
+      setRecentTexts(recent.map((r) => r.char))
+    } catch (err) {
+      console.error('Failed to load recent kaomojis:', err)
+    }
+  }, [])
+
+  useEffect(() => {
+    loadRecent()
+  }, [loadRecent])
+
   const kaomojis = useMemo(() => {
     const mappedCustom = customKaomojis.map((c, i) => ({
       id: `custom-${i}`,
@@ -50,16 +65,28 @@ export function KaomojiPicker({ isDark, opacity, customKaomojis = [] }: KaomojiP
       category: c.category,
       keywords: c.keywords,
     }))
-    return getKaomojis(selectedCategory, searchQuery, mappedCustom)
-  }, [selectedCategory, searchQuery, customKaomojis])
+    const list = getKaomojis(selectedCategory, searchQuery, mappedCustom)
+    if (selectedCategory || searchQuery.trim() || recentTexts.length === 0) return list
 
-  const handlePaste = useCallback(async (text: string) => {
-    try {
-      await invoke('paste_text', { text })
-    } catch (err) {
-      console.error('Failed to paste kaomoji', err)
-    }
-  }, [])
+    // Default: show recent first, then all
+    const rank = new Map(recentTexts.map((text, index) => [text, index]))
+    const recent = list
+      .filter((k) => rank.has(k.text))
+      .sort((a, b) => rank.get(a.text)! - rank.get(b.text)!)
+    return [...recent, ...list.filter((k) => !rank.has(k.text))]
+  }, [selectedCategory, searchQuery, customKaomojis, recentTexts])
+
+  const handlePaste = useCallback(
+    async (text: string) => {
+      try {
+        await invoke('paste_text', { text, itemType: 'kaomoji' })
+        await loadRecent()
+      } catch (err) {
+        console.error('Failed to paste kaomoji', err)
+      }
+    },
+    [loadRecent]
+  )
 
   const handleGridKeyDown = useKeyboardNavigation({
     items: kaomojis,
@@ -0,0 +1,110 @@
+import { clsx } from 'clsx'
+import { ShieldAlert } from 'lucide-react'
+import type { ContentWarning } from '../types/clipboard'
+import { HiddenCharText } from './common/HiddenCharText'
+
+function describeWarning(warning: ContentWarning): string[] {
+  const reasons: string[] = []
+  if (warning.invisible_chars > 0) {
+    reasons.push(`${warning.invisible_chars} invisible character(s)`)
+  }
+  if (warning.bidi_controls > 0) {
+    reasons.push(`${warning.bidi_controls} text direction control(s) that reorder what you see`)
+  }
+  if (warning.mixed_script_words.length > 0) {
+    reasons.push(`Look-alike letters in: ${warning.mixed_script_words.join(', ')}`)
+  }
+  return reasons
+}
+
+/**
+ * Shows exactly what is about to be pasted, with hidden characters spelled out,
+ * before pasting text that may be disguised (e.g. "paste this into your terminal").
+ */
+export function PastePreviewModal({
+  text,
+  sourceApp,
+  warning,
+  isDark,
+  onConfirm,
+  onCancel,
+}: {
+  text: string
+  sourceApp?: string
+  warning: ContentWarning
+  isDark: boolean
+  onConfirm: () => void
+  onCancel: () => void
+}) {
+  return (
+    <div
+      className="fixed inset-0 flex items-center justify-center z-50 p-4"
+      onKeyDown={(e) => {
+        if (e.key === 'Escape') {
+          // Keep the window open, only the preview closes
+          e.stopPropagation()
+          onCancel()
+        }
+      }}
+    >
+      {/* Backdrop */}
+      <div className="absolute inset-0 bg-black/50 backdrop-blur-sm" onClick={onCancel} />
+
+      {/* Modal */}
+      <div
+        role="dialog"
+        aria-modal="true"
+        aria-labelledby="paste-preview-title"
+        className={clsx(
+          'relative w-full max-w-sm rounded-win11-lg p-5 shadow-win11-elevated animate-scale-in',
+          isDark ? 'glass-effect' : 'glass-effect-light',
+          isDark ? 'text-win11-text-primary' : 'text-win11Light-text-primary'
+        )}
+      >
+        <div className="flex items-center gap-2 mb-2">
+          <ShieldAlert className="w-5 h-5 text-amber-500" />
+          <h2 id="paste-preview-title" className="text-sm font-semibold">
+            Check before pasting
+          </h2>
+        </div>
+        <p className={clsx('text-xs mb-2', isDark ? 'text-gray-400' : 'text-gray-500')}>
+          Copied from {sourceApp ?? 'an unknown app'}. It contains:
+        </p>
+        <ul className="text-xs list-disc pl-4 mb-3 space-y-0.5">
+          {describeWarning(warning).map((reason) => (
+            <li key={reason}>{reason}</li>
+          ))}
+        </ul>
+
+        <pre
+          className={clsx(
+            'text-xs font-mono whitespace-pre-wrap break-all max-h-40 overflow-y-auto scrollbar-win11',
+            'rounded-win11 p-2 mb-4',
+            isDark ? 'bg-black/30' : 'bg-black/5'
+          )}
+        >
+          <HiddenCharText text={text} />
+        </pre>
+
+        <div className="flex justify-end gap-2">
+          <button
+            autoFocus
+            onClick={onCancel}
+            className={clsx(
+              'px-3 py-1.5 rounded-win11 text-sm transition-colors',
+              isDark ? 'bg-white/10 hover:bg-white/15' : 'bg-black/5 hover:bg-black/10'
+            )}
+          >
+            Cancel
+          </button>
+          <button
+            onClick={onConfirm}
+            className="px-3 py-1.5 rounded-win11 text-sm bg-amber-600 hover:bg-amber-700 text-white transition-colors"
+          >
+            Paste anyway
+          </button>
+        </div>
+      </div>
+    </div>
+  )
+}
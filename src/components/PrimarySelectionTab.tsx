@@ -0,0 +1,115 @@
+import { useState, useEffect, useRef, useCallback } from 'react'
+import { invoke } from '@tauri-apps/api/core'
+import { listen } from '@tauri-apps/api/event'
+import { clsx } from 'clsx'
+
+import type { ClipboardItem, UserSettings } from '../types/clipboard'
+import { Header } from './Header'
+import { HistoryItem } from './HistoryItem'
+
+/**
+ * Pane listing recent PRIMARY selections (highlighted text).
+ * Kept separate from the clipboard history and never persisted.
+ */
+export function PrimarySelectionTab(props: {
+  isDark: boolean
+  tertiaryOpacity: number
+  secondaryOpacity: number
+  settings: UserSettings
+}) {
+  const { isDark, tertiaryOpacity, secondaryOpacity, settings } = props
+
+  const [items, setItems] = useState<ClipboardItem[]>([])
+  const [focusedIndex, setFocusedIndex] = useState(0)
+  const itemRefs = useRef<(HTMLDivElement | null)[]>([])
+
+  useEffect(() => {
+    invoke<ClipboardItem[]>('get_primary_history')
+      .then(setItems)
+      .catch((err) => console.error('Failed to load selections:', err))
+
+    const unlistenPromise = listen<ClipboardItem>('primary-selection-changed', () => {
+      // The backend may have replaced the previous entry, so refetch instead of prepending
+      invoke<ClipboardItem[]>('get_primary_history').then(setItems).catch(console.error)
+    })
+
+    return () => {
+      unlistenPromise.then((unlisten) => unlisten())
+    }
+  }, [])
+
+  const handlePaste = useCallback(
+    (id: string) => {
+      const item = items.find((i) => i.id === id)
+      if (!item) return
+      const text = item.content.type === 'RichText' ? item.content.data.plain : item.content.data
+      if (typeof text === 'string') {
+        invoke('paste_text', { text }).catch(console.error)
+      }
+    },
+    [items]
+  )
+
+  const handleDelete = useCallback((id: string) => {
+    invoke('delete_primary_item', { id })
+      .then(() => setItems((prev) => prev.filter((item) => item.id !== id)))
+      .catch(console.error)
+  }, [])
+
+  const handleClear = useCallback(() => {
+    invoke('clear_primary_history')
+      .then(() => setItems([]))
+      .catch(console.error)
+  }, [])
+
+  if (items.length === 0) {
+    return (
+      <div className="flex flex-col items-center justify-center h-full p-8 text-center opacity-60">
+        <p
+          className={clsx(
+            'text-sm',
+            isDark ? 'text-win11-text-secondary' : 'text-win11Light-text-secondary'
+          )}
+        >
+          Highlighted text will appear here
+        </p>
+      </div>
+    )
+  }
+
+  return (
+    <>
+      <Header
+        title="Selections"
+        onClearHistory={handleClear}
+        itemCount={items.length}
+        isDark={isDark}
+        tertiaryOpacity={tertiaryOpacity}
+        isCompact={true}
+        onToggleCompact={() => {}}
+        showCompactToggle={false}
+      />
+      <div className="flex flex-col gap-2 p-3" role="listbox" aria-label="Selection history">
+        {items.map((item, index) => (
+          <HistoryItem
+            key={item.id}
+            ref={(el) => {
+              itemRefs.current[index] = el
+            }}
+            item={item}
+            index={index}
+            isFocused={index === focusedIndex}
+            onPaste={handlePaste}
+            onDelete={handleDelete}
+            onFocus={() => setFocusedIndex(index)}
+            isDark={isDark}
+            secondaryOpacity={secondaryOpacity}
+            isCompact={true}
+            enableSmartActions={settings.enable_smart_actions}
+            enableUiPolish={settings.enable_ui_polish}
+          />
+        ))}
+      </div>
+    </>
+  )
+}
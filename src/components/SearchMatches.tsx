@@ -0,0 +1,93 @@
+import { clsx } from 'clsx'
+import { convertFileSrc, invoke } from '@tauri-apps/api/core'
+import type { SearchResult } from '../types/search'
+
+/** The emoji and favorite GIFs found by a history search, pasted with a click */
+export function SearchMatches({ results, isDark }: { results: SearchResult[]; isDark: boolean }) {
+  // History and snippets are in the list below
+  const matches = results.filter((r) => r.kind !== 'history' && r.kind !== 'snippet')
+  if (matches.length === 0) return null
+
+  const paste = async (result: SearchResult) => {
+    try {
+      switch (result.kind) {
+        case 'emoji':
+          await invoke('paste_text', { text: result.char, itemType: 'emoji' })
+          break
+        case 'custom_emoji':
+          await invoke('paste_custom_emoji', { shortcode: result.emoji.shortcode })
+          break
+        case 'gif': {
+          const { id, title, previewUrl, fullUrl, webpUrl, width, height } = result.gif
+          const gif = { id, title, previewUrl, fullUrl, webpUrl, width, height }
+          await invoke('paste_gif_from_url', { url: fullUrl, gif })
+          // Let the UI settle before the window hides
+          setTimeout(() => invoke('finish_paste').catch(console.error), 100)
+          break
+        }
+      }
+    } catch (err) {
+      console.error('Failed to paste search result:', err)
+    }
+  }
+
+  const label = (result: SearchResult) => {
+    switch (result.kind) {
+      case 'emoji':
+        return result.name
+      case 'custom_emoji':
+        return `:${result.emoji.shortcode}:`
+      case 'gif':
+        return result.gif.title
+      default:
+        return ''
+    }
+  }
+
+  return (
+    <div className="px-3 pt-1" role="group" aria-label="Also found">
+      <div
+        className={clsx(
+          'text-xs font-medium mb-1.5',
+          isDark ? 'text-win11-text-secondary' : 'text-win11Light-text-secondary'
+        )}
+      >
+        Also found
+      </div>
+      <div className="flex flex-wrap gap-1.5">
+        {matches.map((result, index) => (
+          <button
+            key={`${result.kind}-${index}`}
+            onClick={() => paste(result)}
+            title={label(result)}
+            aria-label={label(result)}
+            className={clsx(
+              'h-9 min-w-9 flex items-center justify-center rounded-md overflow-hidden transition-colors',
+              'focus:outline-none focus-visible:ring-2 focus-visible:ring-win11-bg-accent',
+              isDark ? 'bg-white/5 hover:bg-white/10' : 'bg-black/5 hover:bg-black/10'
+            )}
+          >
+            {result.kind === 'emoji' && <span className="text-xl px-1.5">{result.char}</span>}
+            {result.kind === 'custom_emoji' &&
+              (result.emoji.image ? (
+                <img
+                  src={`data:image/png;base64,${result.emoji.image}`}
+                  alt=""
+                  className="w-6 h-6 object-contain"
+                />
+              ) : (
+                <span className="text-sm px-1.5">{result.emoji.text}</span>
+              ))}
+            {result.kind === 'gif' && (
+              <img
+                src={convertFileSrc(result.gif.previewFile)}
+                alt=""
+                className="h-9 w-auto object-cover"
+              />
+            )}
+          </button>
+        ))}
+      </div>
+    </div>
+  )
+}
@@ -22,6 +22,14 @@ interface PermissionStatus {
   suggestion: string
 }
 
+interface YdotoolStatus {
+  installed: boolean
+  daemon_running: boolean
+  socket_path: string | null
+  recommended: boolean
+  suggestion: string
+}
+
 interface ShortcutToolsStatus {
   desktop_environment: string
   gsettings_available: boolean
@@ -125,6 +133,7 @@ const WizardButton = ({
 export function SetupWizard({ onComplete }: SetupWizardProps) {
   const [step, setStep] = useState(0)
   const [permissions, setPermissions] = useState<PermissionStatus | null>(null)
+  const [ydotool, setYdotool] = useState<YdotoolStatus | null>(null)
   const [shortcutTools, setShortcutTools] = useState<ShortcutToolsStatus | null>(null)
   const [conflicts, setConflicts] = useState<ConflictDetectionResult | null>(null)
   const [fixing, setFixing] = useState(false)
@@ -169,6 +178,7 @@ export function SetupWizard({ onComplete }: SetupWizardProps) {
 
   useEffect(() => {
     checkPermissions()
+    checkYdotool()
     checkShortcutTools()
     checkConflicts()
   }, [])
@@ -182,6 +192,15 @@ export function SetupWizard({ onComplete }: SetupWizardProps) {
     }
   }
 
+  const checkYdotool = async () => {
+    try {
+      const status = await invoke<YdotoolStatus>('get_ydotool_status')
+      setYdotool(status)
+    } catch (e) {
+      console.error('Failed to check ydotool:', e)
+    }
+  }
+
   const checkShortcutTools = async () => {
     try {
       const status = await invoke<ShortcutToolsStatus>('check_shortcut_tools')
@@ -266,6 +285,10 @@ export function SetupWizard({ onComplete }: SetupWizardProps) {
   }
 
   // Status message styles
+  // Only relevant when direct uinput access is missing
+  const showYdotoolHint =
+    !permissions?.uinput_accessible && (ydotool?.recommended || ydotool?.installed)
+
   const statusCardClass = (type: 'success' | 'warning' | 'error') =>
     clsx(
       'p-4 rounded-win11 flex items-start gap-3 text-sm',
@@ -381,6 +404,17 @@ export function SetupWizard({ onComplete }: SetupWizardProps) {
         </div>
       )}
 
+      {ydotool && showYdotoolHint && (
+        <div className={clsx('mb-4', statusCardClass(ydotool.daemon_running ? 'success' : 'warning'))}>
+          {ydotool.daemon_running ? (
+            <CheckCircle className="w-5 h-5 flex-shrink-0 mt-0.5" />
+          ) : (
+            <AlertTriangle className="w-5 h-5 flex-shrink-0 mt-0.5" />
+          )}
+          <span>{ydotool.suggestion}</span>
+        </div>
+      )}
+
       {fixError && <div className={clsx('mb-4', statusCardClass('error'))}>{fixError}</div>}
 
       <div className="flex gap-3 justify-center">
@@ -0,0 +1,138 @@
+import { useState } from 'react'
+import { clsx } from 'clsx'
+import { Switch } from './Switch'
+import type { ShellAction } from '../types/clipboard'
+
+export function ShellActionsSection({
+  actions,
+  isDark,
+  onChange,
+}: {
+  actions: ShellAction[]
+  isDark: boolean
+  onChange: (actions: ShellAction[]) => void
+}) {
+  const [newName, setNewName] = useState('')
+  const [newCommand, setNewCommand] = useState('')
+
+  const mutedText = isDark ? 'text-gray-400' : 'text-gray-500'
+  const inputClasses = clsx(
+    'px-3 py-2 rounded-md border text-sm focus:outline-none focus:ring-2 focus:ring-win11-bg-accent/50 transition-all',
+    isDark
+      ? 'bg-white/5 border-white/10 text-white placeholder-gray-500'
+      : 'bg-gray-50 border-gray-200 text-gray-900 placeholder-gray-400'
+  )
+
+  const addAction = () => {
+    const name = newName.trim()
+    const command = newCommand.trim()
+    if (!name || !command || actions.some((action) => action.name === name)) return
+
+    onChange([...actions, { name, command, capture_output: false }])
+    setNewName('')
+    setNewCommand('')
+  }
+
+  const updateActionAt = (index: number, partial: Partial<ShellAction>) => {
+    onChange(actions.map((action, i) => (i === index ? { ...action, ...partial } : action)))
+  }
+
+  return (
+    <section
+      className={clsx(
+        'rounded-xl border shadow-sm overflow-hidden',
+        isDark ? 'bg-win11-bg-secondary border-white/5' : 'bg-white border-gray-200/60'
+      )}
+    >
+      <div className="p-6 border-b border-inherit">
+        <h2 className="text-base font-semibold mb-1">Custom Actions</h2>
+        <p className={clsx('text-xs', mutedText)}>
+          Shell commands an item's text is piped to, from the item's action menu. Turn on
+          capture to add what the command prints to history.
+        </p>
+      </div>
+      <div className="p-6 space-y-2">
+        {actions.map((action, idx) => (
+          <div
+            key={action.name}
+            className={clsx(
+              'flex gap-2 items-center px-3 py-2 rounded-md border',
+              isDark ? 'bg-white/5 border-white/10' : 'bg-gray-50 border-gray-200'
+            )}
+          >
+            <span className="w-24 text-sm font-medium truncate" title={action.name}>
+              {action.name}
+            </span>
+            <input
+              type="text"
+              aria-label={`${action.name} command`}
+              defaultValue={action.command}
+              onBlur={(e) => {
+                if (e.target.value.trim()) updateActionAt(idx, { command: e.target.value })
+              }}
+              className={clsx(
+                'flex-1 min-w-0 px-2 py-1 text-xs font-mono border rounded-md focus:outline-none focus:ring-2 focus:ring-win11-bg-accent/50',
+                isDark
+                  ? 'bg-white/5 border-white/10 text-white'
+                  : 'bg-white border-gray-200 text-gray-900'
+              )}
+            />
+            <span className={clsx('text-xs', mutedText)}>Capture</span>
+            <Switch
+              checked={action.capture_output}
+              onChange={() => updateActionAt(idx, { capture_output: !action.capture_output })}
+              isDark={isDark}
+            />
+            <button
+              onClick={() => onChange(actions.filter((_, i) => i !== idx))}
+              className="p-1 text-red-500 hover:bg-red-500/10 rounded transition-all"
+              title="Delete"
+            >
+              <svg
+                width="14"
+                height="14"
+                viewBox="0 0 24 24"
+                fill="none"
+                stroke="currentColor"
+                strokeWidth="2"
+                strokeLinecap="round"
+                strokeLinejoin="round"
+              >
+                <path d="M18 6 6 18" />
+                <path d="m6 6 12 12" />
+              </svg>
+            </button>
+          </div>
+        ))}
+
+        <div className="flex gap-2">
+          <input
+            type="text"
+            value={newName}
+            onChange={(e) => setNewName(e.target.value)}
+            placeholder="Name, e.g. Shorten URL"
+            className={clsx('w-36', inputClasses)}
+          />
+          <input
+            type="text"
+            value={newCommand}
+            onChange={(e) => setNewCommand(e.target.value)}
+            placeholder="Command, e.g. curl -sF 'file=@-' https://0x0.st"
+            className={clsx('flex-1 min-w-0 font-mono', inputClasses)}
+            onKeyDown={(e) => {
+              if (e.key === 'Enter') {
+                addAction()
+              }
+            }}
+          />
+          <button
+            onClick={addAction}
+            className="px-4 py-2 bg-win11-bg-accent text-white rounded-md text-sm font-medium hover:opacity-90 active:scale-95 transition-all"
+          >
+            Add
+          </button>
+        </div>
+      </div>
+    </section>
+  )
+}
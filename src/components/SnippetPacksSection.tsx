@@ -0,0 +1,139 @@
+import { useState, useEffect, useCallback } from 'react'
+import { invoke } from '@tauri-apps/api/core'
+import { clsx } from 'clsx'
+import { Switch } from './Switch'
+import type { Collection, PackConflict } from '../types/clipboard'
+
+export function SnippetPacksSection({ isDark }: { isDark: boolean }) {
+  const [collections, setCollections] = useState<Collection[]>([])
+  const [readOnly, setReadOnly] = useState(false)
+  const [keepBoth, setKeepBoth] = useState(false)
+  const [path, setPath] = useState('')
+  const [isImporting, setIsImporting] = useState(false)
+  const [exported, setExported] = useState<string | null>(null)
+  const [error, setError] = useState<string | null>(null)
+
+  const refresh = useCallback(() => {
+    invoke<Collection[]>('get_collections').then(setCollections).catch(console.error)
+  }, [])
+
+  useEffect(() => {
+    refresh()
+  }, [refresh])
+
+  const mutedText = isDark ? 'text-gray-400' : 'text-gray-500'
+
+  const exportPack = async (id: string) => {
+    try {
+      setExported(await invoke<string>('export_snippet_pack', { id, readOnly }))
+      setError(null)
+    } catch (err) {
+      setError(String(err))
+    }
+  }
+
+  const importPack = async () => {
+    const trimmed = path.trim()
+    if (!trimmed || isImporting) return
+    setIsImporting(true)
+    try {
+      const conflict: PackConflict = keepBoth ? 'keep_both' : 'update'
+      await invoke('import_snippet_pack', { path: trimmed, conflict })
+      setPath('')
+      setError(null)
+      refresh()
+    } catch (err) {
+      setError(String(err))
+    } finally {
+      setIsImporting(false)
+    }
+  }
+
+  return (
+    <section
+      className={clsx(
+        'rounded-xl border shadow-sm overflow-hidden',
+        isDark ? 'bg-win11-bg-secondary border-white/5' : 'bg-white border-gray-200/60'
+      )}
+    >
+      <div className="p-6 border-b border-inherit">
+        <h2 className="text-base font-semibold mb-1">Snippet Packs</h2>
+        <p className={clsx('text-xs', mutedText)}>
+          Export a collection as a pack file to share its text snippets, or import a pack from a
+          colleague. Importing a pack again updates its collection.
+        </p>
+      </div>
+      <div className="p-6 space-y-2">
+        {collections.map((collection) => (
+          <div
+            key={collection.id}
+            className={clsx(
+              'flex gap-2 items-center px-3 py-2 rounded-md border',
+              isDark ? 'bg-white/5 border-white/10' : 'bg-gray-50 border-gray-200'
+            )}
+          >
+            <span className="flex-1 min-w-0 text-sm truncate">{collection.name}</span>
+            {collection.read_only && <span className={clsx('text-xs', mutedText)}>Read-only</span>}
+            <button
+              onClick={() => exportPack(collection.id)}
+              className="px-3 py-1 rounded-md text-xs font-medium text-win11-bg-accent hover:bg-win11-bg-accent/10 transition-all"
+            >
+              Export
+            </button>
+          </div>
+        ))}
+        {collections.length > 0 && (
+          <div className="flex justify-between items-center">
+            <span className={clsx('text-xs', mutedText)}>Mark exported packs read-only</span>
+            <Switch checked={readOnly} onChange={setReadOnly} isDark={isDark} />
+          </div>
+        )}
+        {exported && (
+          <p className={clsx('text-xs', mutedText)}>
+            Saved to{' '}
+            <button
+              onClick={() => invoke('reveal_path', { path: exported }).catch(console.error)}
+              className="underline"
+            >
+              {exported}
+            </button>
+          </p>
+        )}
+
+        <div className="flex gap-2">
+          <input
+            type="text"
+            value={path}
+            onChange={(e) => setPath(e.target.value)}
+            placeholder="/path/to/pack.snippets.json"
+            className={clsx(
+              'flex-1 min-w-0 px-3 py-2 rounded-md border text-sm focus:outline-none focus:ring-2 focus:ring-win11-bg-accent/50 transition-all',
+              isDark
+                ? 'bg-white/5 border-white/10 text-white placeholder-gray-500'
+                : 'bg-gray-50 border-gray-200 text-gray-900 placeholder-gray-400'
+            )}
+            onKeyDown={(e) => {
+              if (e.key === 'Enter') {
+                importPack()
+              }
+            }}
+          />
+          <button
+            onClick={importPack}
+            disabled={isImporting}
+            className="px-4 py-2 bg-win11-bg-accent text-white rounded-md text-sm font-medium hover:opacity-90 active:scale-95 transition-all disabled:opacity-50"
+          >
+            {isImporting ? 'Importing…' : 'Import'}
+          </button>
+        </div>
+        <div className="flex justify-between items-center">
+          <span className={clsx('text-xs', mutedText)}>
+            Keep both copies when a pack was imported before
+          </span>
+          <Switch checked={keepBoth} onChange={setKeepBoth} isDark={isDark} />
+        </div>
+        {error && <p className="text-xs text-win11-error">{error}</p>}
+      </div>
+    </section>
+  )
+}
@@ -0,0 +1,60 @@
+import { clsx } from 'clsx'
+import { useAppIcon } from '../hooks/useAppIcon'
+import { appLabel } from '../utils/sourceApps'
+
+/** The icon of a source app, or nothing when it has none */
+export function AppIcon({ appId, className }: { appId?: string | null; className?: string }) {
+  const icon = useAppIcon(appId)
+  if (!icon) return null
+  return <img src={icon} alt="" aria-hidden className={clsx('shrink-0', className)} />
+}
+
+/**
+ * Chips to show only the items copied from one app, with its icon. Shown
+ * once items came from more than one app.
+ */
+export function SourceAppBar({
+  apps,
+  activeApp,
+  isDark,
+  onSelect,
+}: {
+  apps: string[]
+  activeApp: string | null
+  isDark: boolean
+  onSelect: (app: string | null) => void
+}) {
+  if (apps.length < 2) return null
+
+  return (
+    <div
+      className="px-3 pt-1 flex items-center gap-1.5 overflow-x-auto"
+      role="group"
+      aria-label="Source apps"
+    >
+      {apps.map((app) => {
+        const active = app.toLowerCase() === activeApp?.toLowerCase()
+        return (
+          <button
+            key={app}
+            aria-pressed={active}
+            onClick={() => onSelect(active ? null : app)}
+            title={`Copied from ${app}`}
+            className={clsx(
+              'flex items-center gap-1 px-2.5 py-1 rounded-md text-xs whitespace-nowrap transition-colors',
+              'focus:outline-none focus-visible:ring-2 focus-visible:ring-win11-bg-accent',
+              active
+                ? 'bg-win11-bg-accent text-white'
+                : isDark
+                  ? 'bg-white/5 hover:bg-white/10'
+                  : 'bg-black/5 hover:bg-black/10'
+            )}
+          >
+            <AppIcon appId={app} className="w-3.5 h-3.5" />
+            {appLabel(app)}
+          </button>
+        )
+      })}
+    </div>
+  )
+}
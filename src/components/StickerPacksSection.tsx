@@ -0,0 +1,125 @@
+import { useState, useEffect, useCallback } from 'react'
+import { invoke } from '@tauri-apps/api/core'
+import { clsx } from 'clsx'
+import type { StickerPack } from '../types/sticker'
+
+export function StickerPacksSection({ isDark }: { isDark: boolean }) {
+  const [packs, setPacks] = useState<StickerPack[]>([])
+  const [source, setSource] = useState('')
+  const [isImporting, setIsImporting] = useState(false)
+  const [error, setError] = useState<string | null>(null)
+
+  const refresh = useCallback(() => {
+    invoke<StickerPack[]>('get_sticker_packs').then(setPacks).catch(console.error)
+  }, [])
+
+  useEffect(() => {
+    refresh()
+  }, [refresh])
+
+  const mutedText = isDark ? 'text-gray-400' : 'text-gray-500'
+
+  const importPack = async () => {
+    const trimmed = source.trim()
+    if (!trimmed || isImporting) return
+    setIsImporting(true)
+    try {
+      const isUrl = /^https?:\/\//i.test(trimmed)
+      await invoke(isUrl ? 'import_sticker_url' : 'import_sticker_folder', {
+        [isUrl ? 'url' : 'path']: trimmed,
+      })
+      setSource('')
+      setError(null)
+      refresh()
+    } catch (err) {
+      setError(String(err))
+    } finally {
+      setIsImporting(false)
+    }
+  }
+
+  const remove = async (id: string) => {
+    await invoke('remove_sticker_pack', { id }).catch(console.error)
+    refresh()
+  }
+
+  return (
+    <section
+      className={clsx(
+        'rounded-xl border shadow-sm overflow-hidden',
+        isDark ? 'bg-win11-bg-secondary border-white/5' : 'bg-white border-gray-200/60'
+      )}
+    >
+      <div className="p-6 border-b border-inherit">
+        <h2 className="text-base font-semibold mb-1">Sticker Packs</h2>
+        <p className={clsx('text-xs', mutedText)}>
+          Import a folder of PNG or WebP images, or a URL to an image or a pack manifest.
+          Stickers paste as images, which Telegram and Discord show inline.
+        </p>
+      </div>
+      <div className="p-6 space-y-2">
+        {packs.map((pack) => (
+          <div
+            key={pack.id}
+            className={clsx(
+              'flex gap-2 items-center px-3 py-2 rounded-md border',
+              isDark ? 'bg-white/5 border-white/10' : 'bg-gray-50 border-gray-200'
+            )}
+          >
+            <span className="flex-1 min-w-0 text-sm truncate" title={pack.source}>
+              {pack.name}
+            </span>
+            <span className={clsx('text-xs', mutedText)}>{pack.stickers.length} stickers</span>
+            <button
+              onClick={() => remove(pack.id)}
+              className="p-1 text-red-500 hover:bg-red-500/10 rounded transition-all"
+              title="Delete"
+            >
+              <svg
+                width="14"
+                height="14"
+                viewBox="0 0 24 24"
+                fill="none"
+                stroke="currentColor"
+                strokeWidth="2"
+                strokeLinecap="round"
+                strokeLinejoin="round"
+              >
+                <path d="M18 6 6 18" />
+                <path d="m6 6 12 12" />
+              </svg>
+            </button>
+          </div>
+        ))}
+
+        <div className="flex gap-2">
+          <input
+            type="text"
+            value={source}
+            onChange={(e) => setSource(e.target.value)}
+            placeholder="/path/to/folder or https://…/pack.json"
+            className={clsx(
+              'flex-1 min-w-0 px-3 py-2 rounded-md border text-sm focus:outline-none focus:ring-2 focus:ring-win11-bg-accent/50 transition-all',
+              isDark
+                ? 'bg-white/5 border-white/10 text-white placeholder-gray-500'
+                : 'bg-gray-50 border-gray-200 text-gray-900 placeholder-gray-400'
+            )}
+            onKeyDown={(e) => {
+              if (e.key === 'Enter') {
+                importPack()
+              }
+            }}
+          />
+          <button
+            onClick={importPack}
+            disabled={isImporting}
+            className="px-4 py-2 bg-win11-bg-accent text-white rounded-md text-sm font-medium hover:opacity-90 active:scale-95 transition-all disabled:opacity-50"
+          >
+            {isImporting ? 'Importing…' : 'Import'}
+          </button>
+        </div>
+        {error && <p className="text-xs text-win11-error">{error}</p>}
+      </div>
+    </section>
+  )
+}
@@ -0,0 +1,165 @@
+import { useState, useMemo, useRef, useCallback, useEffect } from 'react'
+import { clsx } from 'clsx'
+import { convertFileSrc, invoke } from '@tauri-apps/api/core'
+import { SearchBar } from './common/SearchBar'
+import type { Sticker, StickerPack } from '../types/sticker'
+
+import { PickerLayout } from './common/PickerLayout'
+import { CategoryStrip } from './common/CategoryStrip'
+import { useResponsiveGrid } from '../hooks/useResponsiveGrid'
+import { useKeyboardNavigation } from '../hooks/useKeyboardNavigation'
+
+interface StickerPickerProps {
+  isDark: boolean
+  opacity: number
+}
+
+/** A sticker and the pack it is pasted from */
+interface PackSticker extends Sticker {
+  packId: string
+  packName: string
+}
+
+export function StickerPicker({ isDark, opacity }: StickerPickerProps) {
+  const [packs, setPacks] = useState<StickerPack[]>([])
+  const [searchQuery, setSearchQuery] = useState('')
+  const [selectedPack, setSelectedPack] = useState<string | null>(null)
+  const [categoryFocusedIndex, setCategoryFocusedIndex] = useState(0)
+  const [gridFocusedIndex, setGridFocusedIndex] = useState(0)
+  const [hovered, setHovered] = useState<PackSticker | null>(null)
+  const gridContainerRef = useRef<HTMLDivElement>(null)
+
+  const { containerRef, dimensions } = useResponsiveGrid()
+
+  const columnCount = useMemo(() => {
+    const width = dimensions.width
+    if (width >= 640) return 5 // sm
+    return 4 // default
+  }, [dimensions.width])
+
+  useEffect(() => {
+    invoke<StickerPack[]>('get_sticker_packs').then(setPacks).catch(console.error)
+  }, [])
+
+  const stickers = useMemo(() => {
+    const query = searchQuery.trim().toLowerCase()
+    return packs
+      .filter((pack) => !selectedPack || pack.name === selectedPack)
+      .flatMap((pack) =>
+        pack.stickers.map((sticker) => ({ ...sticker, packId: pack.id, packName: pack.name }))
+      )
+      .filter(
+        (sticker) =>
+          !query ||
+          sticker.name.toLowerCase().includes(query) ||
+          sticker.packName.toLowerCase().includes(query)
+      )
+  }, [packs, selectedPack, searchQuery])
+
+  const handlePaste = useCallback(async (sticker: PackSticker) => {
+    try {
+      await invoke('paste_sticker', { packId: sticker.packId, stickerId: sticker.id })
+    } catch (err) {
+      console.error('Failed to paste sticker', err)
+    }
+  }, [])
+
+  const handleGridKeyDown = useKeyboardNavigation({
+    items: stickers,
+    columnCount,
+    onSelect: handlePaste,
+    setFocusedIndex: setGridFocusedIndex,
+    containerRef: gridContainerRef,
+    dataAttributeName: 'data-sticker-index',
+  })
+
+  return (
+    <PickerLayout
+      header={
+        <SearchBar
+          value={searchQuery}
+          onChange={(val: string) => {
+            setSearchQuery(val)
+            setGridFocusedIndex(0)
+          }}
+          placeholder="Search stickers..."
+          isDark={isDark}
+          opacity={opacity}
+        />
+      }
+      subHeader={
+        packs.length > 1 ? (
+          <CategoryStrip
+            categories={packs.map((pack) => pack.name)}
+            selectedCategory={selectedPack}
+            onSelectCategory={(pack) => {
+              setSelectedPack(pack)
+              setGridFocusedIndex(0)
+            }}
+            focusedIndex={categoryFocusedIndex}
+            setFocusedIndex={setCategoryFocusedIndex}
+            isDark={isDark}
+            opacity={opacity}
+          />
+        ) : undefined
+      }
+      footer={
+        hovered ? (
+          <span className="text-xs dark:text-win11-text-secondary text-win11Light-text-secondary truncate">
+            {hovered.name} · {hovered.packName}
+          </span>
+        ) : (
+          <span className="text-xs dark:text-win11-text-tertiary text-win11Light-text-secondary">
+            Click to paste sticker
+          </span>
+        )
+      }
+    >
+      {/* Grid Area */}
+      <div ref={containerRef} className="h-full overflow-y-scroll p-3 pt-3 scrollbar-win11">
+        <div
+          ref={gridContainerRef}
+          className="grid grid-cols-4 sm:grid-cols-5 gap-2"
+          role="grid"
+          aria-label="Sticker grid"
+        >
+          {stickers.map((sticker, index) => (
+            <button
+              key={`${sticker.packId}-${sticker.id}`}
+              data-sticker-index={index}
+              tabIndex={index === gridFocusedIndex ? 0 : -1}
+              onClick={() => handlePaste(sticker)}
+              onFocus={() => setGridFocusedIndex(index)}
+              onKeyDown={(e) => handleGridKeyDown(e, index)}
+              onMouseEnter={() => setHovered(sticker)}
+              onMouseLeave={() => setHovered(null)}
+              className={clsx(
+                'aspect-square p-1 flex items-center justify-center rounded-md',
+                'hover:scale-105 transition-transform duration-100 transform-gpu',
+                'border border-transparent hover:border-win11-border-subtle',
+                'focus:outline-none focus-visible:ring-2 focus-visible:ring-win11-bg-accent',
+                isDark ? 'hover:bg-win11-bg-card-hover' : 'hover:bg-win11Light-bg-card-hover'
+              )}
+              title={sticker.name}
+              aria-label={`${sticker.name} - ${sticker.packName}`}
+            >
+              <img
+                src={convertFileSrc(sticker.file)}
+                alt={sticker.name}
+                loading="lazy"
+                className="max-w-full max-h-full object-contain"
+              />
+            </button>
+          ))}
+          {stickers.length === 0 && (
+            <div className="col-span-full py-8 text-center text-sm opacity-60">
+              {packs.length === 0
+                ? 'No sticker packs yet. Import one in Settings.'
+                : 'No stickers found'}
+            </div>
+          )}
+        </div>
+      </div>
+    </PickerLayout>
+  )
+}
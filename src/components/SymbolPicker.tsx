@@ -4,7 +4,7 @@ import { clsx } from 'clsx'
 import { Clock } from 'lucide-react'
 import { useSymbolPicker } from '../hooks/useSymbolPicker'
 import { SearchBar } from './common/SearchBar'
-import type { SymbolItem } from '../services/symbolService'
+import { formatCodepoint, type SymbolItem } from '../services/symbolService'
 
 import { PickerLayout } from './common/PickerLayout'
 import { CategoryStrip } from './common/CategoryStrip'
@@ -271,6 +271,9 @@ export function SymbolPicker({ isDark, opacity }: SymbolPickerProps) {
             <span className="text-xs dark:text-win11-text-secondary text-win11Light-text-secondary truncate">
               {hoveredSymbol.name}
             </span>
+            <span className="text-xs dark:text-win11-text-tertiary text-win11Light-text-secondary ml-auto shrink-0">
+              {formatCodepoint(hoveredSymbol.char)}
+            </span>
           </>
         ) : (
           <span className="text-xs dark:text-win11-text-tertiary text-win11Light-text-secondary">
@@ -1,6 +1,6 @@
-import { forwardRef, useRef, useImperativeHandle, useCallback, useState } from 'react'
+import { forwardRef, useRef, useImperativeHandle, useCallback, useMemo, useState } from 'react'
 import { clsx } from 'clsx'
-import { ClipboardList, Smile, Image, Type, Omega } from 'lucide-react'
+import { ClipboardList, Smile, Image, Type, Omega, TextSelect, Sticker } from 'lucide-react'
 import type { ActiveTab } from '../types/clipboard'
 
 import { getTertiaryBackgroundStyle } from '../utils/themeUtils'
@@ -10,33 +10,47 @@ interface TabBarProps {
   onTabChange: (tab: ActiveTab) => void
   isDark: boolean
   tertiaryOpacity: number
+  /** Show the PRIMARY selection pane (opt-in setting) */
+  showSelectionsTab?: boolean
 }
 
 export interface TabBarRef {
   focusFirstTab: () => void
+  /** Switches to the next (1) or previous (-1) visible tab, wrapping around */
+  selectAdjacentTab: (direction: 1 | -1) => void
 }
 
 const ALL_TABS: { id: ActiveTab; label: string; icon: typeof ClipboardList }[] = [
   { id: 'clipboard', label: 'Clipboard', icon: ClipboardList },
+  { id: 'selections', label: 'Selections', icon: TextSelect },
   { id: 'symbols', label: 'Symbols', icon: Omega },
   { id: 'emoji', label: 'Emoji', icon: Smile },
   { id: 'kaomoji', label: 'Kaomoji', icon: Type },
   { id: 'gifs', label: 'GIFs', icon: Image },
+  { id: 'stickers', label: 'Stickers', icon: Sticker },
 ]
 
 export const TabBar = forwardRef<TabBarRef, TabBarProps>(function TabBar(
-  { activeTab, onTabChange, isDark, tertiaryOpacity },
+  { activeTab, onTabChange, isDark, tertiaryOpacity, showSelectionsTab = false },
   ref
 ) {
   const tabRefs = useRef<(HTMLButtonElement | null)[]>([])
   const [hoveredTab, setHoveredTab] = useState<ActiveTab | null>(null)
 
-  const visibleTabs = ALL_TABS
+  const visibleTabs = useMemo(
+    () => ALL_TABS.filter((tab) => tab.id !== 'selections' || showSelectionsTab),
+    [showSelectionsTab]
+  )
 
   useImperativeHandle(ref, () => ({
     focusFirstTab: () => {
       tabRefs.current[0]?.focus()
     },
+    selectAdjacentTab: (direction) => {
+      const index = visibleTabs.findIndex((tab) => tab.id === activeTab)
+      const next = visibleTabs[(index + direction + visibleTabs.length) % visibleTabs.length]
+      if (next) onTabChange(next.id)
+    },
   }))
 
   const handleKeyDown = useCallback(
@@ -0,0 +1,95 @@
+import { clsx } from 'clsx'
+import type { TranslationBackend, TranslationSettings } from '../types/clipboard'
+
+const BACKEND_OPTIONS: { value: TranslationBackend; label: string }[] = [
+  { value: 'libretranslate', label: 'LibreTranslate' },
+  { value: 'deepl', label: 'DeepL' },
+]
+
+export function TranslationSection({
+  translation,
+  isDark,
+  onChange,
+}: {
+  translation: TranslationSettings
+  isDark: boolean
+  onChange: (partial: Partial<TranslationSettings>) => void
+}) {
+  const mutedText = isDark ? 'text-gray-400' : 'text-gray-500'
+  const inputClasses = clsx(
+    'w-full px-3 py-2 text-sm border rounded-md focus:outline-none focus:ring-2 focus:ring-win11-bg-accent/50',
+    isDark
+      ? 'bg-white/5 border-white/10 text-white placeholder-gray-500'
+      : 'bg-gray-50 border-gray-200 text-gray-900 placeholder-gray-400'
+  )
+
+  // Text fields save on blur, so typing a key doesn't write the settings on every keystroke
+  const textField = (
+    key: 'libretranslate_url' | 'libretranslate_api_key' | 'deepl_api_key' | 'target_language',
+    label: string,
+    placeholder: string,
+    secret = false
+  ) => (
+    <div>
+      <label htmlFor={`translation-${key}`} className="text-sm font-medium">
+        {label}
+      </label>
+      <input
+        id={`translation-${key}`}
+        type={secret ? 'password' : 'text'}
+        // Remount when the saved value changes so the uncontrolled input picks it up
+        key={translation[key]}
+        defaultValue={translation[key]}
+        placeholder={placeholder}
+        onBlur={(e) => onChange({ [key]: e.target.value })}
+        className={clsx('mt-1', inputClasses)}
+      />
+    </div>
+  )
+
+  return (
+    <section
+      className={clsx(
+        'rounded-xl border shadow-sm overflow-hidden',
+        isDark ? 'bg-win11-bg-secondary border-white/5' : 'bg-white border-gray-200/60'
+      )}
+    >
+      <div className="p-6 border-b border-inherit">
+        <h2 className="text-base font-semibold mb-1">Translation</h2>
+        <p className={clsx('text-xs', mutedText)}>
+          Service used by the Translate button. The text is only sent when you use it.
+        </p>
+      </div>
+      <div className="p-6 space-y-4">
+        <div>
+          <label htmlFor="translation-backend" className="text-sm font-medium">
+            Service
+          </label>
+          <select
+            id="translation-backend"
+            value={translation.backend}
+            onChange={(e) => onChange({ backend: e.target.value as TranslationBackend })}
+            className={clsx('mt-1', inputClasses)}
+          >
+            {BACKEND_OPTIONS.map(({ value, label }) => (
+              <option key={value} value={value}>
+                {label}
+              </option>
+            ))}
+          </select>
+        </div>
+
+        {translation.backend === 'libretranslate' ? (
+          <>
+            {textField('libretranslate_url', 'Server URL', 'https://libretranslate.com')}
+            {textField('libretranslate_api_key', 'API key', 'Only if the server asks for one', true)}
+          </>
+        ) : (
+          textField('deepl_api_key', 'DeepL API key', 'Free plan keys end in :fx', true)
+        )}
+
+        {textField('target_language', 'Translate into', 'Language code, e.g. en, pt, de')}
+      </div>
+    </section>
+  )
+}
@@ -0,0 +1,21 @@
+import { codePointLabel, splitHiddenChars } from '../../utils/hiddenChars'
+
+/** Renders text with every invisible, bidi or non-breaking space character spelled out */
+export function HiddenCharText({ text }: { text: string }) {
+  return (
+    <>
+      {splitHiddenChars(text).map((segment, index) =>
+        segment.hidden ? (
+          <span
+            key={index}
+            className="px-0.5 rounded text-[0.7em] font-mono bg-amber-500/30 text-amber-600 dark:text-amber-300"
+          >
+            {codePointLabel(segment.text)}
+          </span>
+        ) : (
+          <span key={index}>{segment.text}</span>
+        )
+      )}
+    </>
+  )
+}
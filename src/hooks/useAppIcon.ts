@@ -0,0 +1,43 @@
+import { invoke } from '@tauri-apps/api/core'
+import { useEffect, useState } from 'react'
+
+// The backend caches per process; this avoids repeat IPC within a session
+const iconCache = new Map<string, string | null>()
+
+/**
+ * Resolves the icon for a source app (WM_CLASS / Wayland app_id) as a data URL.
+ * Returns null while loading or when the app has no icon.
+ */
+export function useAppIcon(appId: string | null | undefined) {
+  const [icon, setIcon] = useState<string | null>(() =>
+    appId ? (iconCache.get(appId) ?? null) : null
+  )
+
+  useEffect(() => {
+    if (!appId) {
+      setIcon(null)
+      return
+    }
+
+    if (iconCache.has(appId)) {
+      setIcon(iconCache.get(appId) ?? null)
+      return
+    }
+
+    let cancelled = false
+    invoke<string | null>('get_app_icon', { appId })
+      .then((url) => {
+        iconCache.set(appId, url)
+        if (!cancelled) setIcon(url)
+      })
+      .catch((e) => {
+        console.error('Failed to resolve app icon:', e)
+      })
+
+    return () => {
+      cancelled = true
+    }
+  }, [appId])
+
+  return icon
+}
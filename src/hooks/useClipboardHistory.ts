@@ -1,7 +1,18 @@
-import { useState, useEffect, useCallback } from 'react'
+import { useState, useEffect, useCallback, useRef } from 'react'
 import { invoke } from '@tauri-apps/api/core'
 import { listen, UnlistenFn } from '@tauri-apps/api/event'
-import type { ClipboardItem } from '../types/clipboard'
+import type { ClipboardItem, ContentWarning, HistoryPage, QuickAction } from '../types/clipboard'
+import { HISTORY_PAGE_SIZE } from './useHistoryQuery'
+
+/** A paste waiting for the user to confirm it */
+export interface PendingPaste {
+  id: string
+  /** Shown in the preview; it may not be on a loaded page */
+  item: ClipboardItem | null
+  warning: ContentWarning
+  /** Press the submit key after pasting */
+  submit: boolean
+}
 
 /**
  * Hook for managing clipboard history
@@ -10,13 +21,29 @@ export function useClipboardHistory() {
   const [history, setHistory] = useState<ClipboardItem[]>([])
   const [isLoading, setIsLoading] = useState(true)
   const [error, setError] = useState<string | null>(null)
+  // Item held back for confirmation because it looks suspicious
+  const [pendingPaste, setPendingPaste] = useState<PendingPaste | null>(null)
+  // History comes a page at a time; where the next page starts, and how many
+  // items are past the loaded ones
+  const [nextCursor, setNextCursor] = useState<string | null>(null)
+  const [notLoaded, setNotLoaded] = useState(0)
+  const loadedCount = useRef(0)
+  const loadingMore = useRef(false)
+
+  useEffect(() => {
+    loadedCount.current = history.length
+  }, [history])
 
-  // Fetch initial history
+  // Fetch the first page, or as many items as were already scrolled through
   const fetchHistory = useCallback(async () => {
     try {
       setIsLoading(true)
-      const items = await invoke<ClipboardItem[]>('get_history')
-      setHistory(items)
+      const page = await invoke<HistoryPage>('query_history', {
+        query: { limit: Math.max(HISTORY_PAGE_SIZE, loadedCount.current) },
+      })
+      setHistory(page.items)
+      setNextCursor(page.next_cursor)
+      setNotLoaded(page.total - page.items.length)
       setError(null)
     } catch (err) {
       setError(err instanceof Error ? err.message : 'Failed to fetch history')
@@ -25,11 +52,34 @@ export function useClipboardHistory() {
     }
   }, [])
 
+  // Append the next page, when the list is scrolled to its end
+  const loadMoreHistory = useCallback(async () => {
+    if (!nextCursor || loadingMore.current) return
+    loadingMore.current = true
+    try {
+      const page = await invoke<HistoryPage>('query_history', {
+        query: { after: nextCursor, limit: HISTORY_PAGE_SIZE },
+      })
+      setHistory((prev) => {
+        const known = new Set(prev.map((item) => item.id))
+        return [...prev, ...page.items.filter((item) => !known.has(item.id))]
+      })
+      setNextCursor(page.next_cursor)
+      setNotLoaded((count) => Math.max(0, count - page.items.length))
+    } catch (err) {
+      // The last loaded item was deleted meanwhile; start over
+      console.warn('[useClipboardHistory] Failed to load more history, refreshing', err)
+      await fetchHistory()
+    } finally {
+      loadingMore.current = false
+    }
+  }, [nextCursor, fetchHistory])
+
   // Clear all history
   const clearHistory = useCallback(async () => {
     try {
       await invoke('clear_history')
-      setHistory((prev) => prev.filter((item) => item.pinned))
+      setHistory((prev) => prev.filter((item) => item.pinned || item.collection))
     } catch (err) {
       setError(err instanceof Error ? err.message : 'Failed to clear history')
     }
@@ -41,7 +91,7 @@ export function useClipboardHistory() {
       await invoke('delete_item', { id })
       setHistory((prev) => prev.filter((item) => item.id !== id))
     } catch (err) {
-      setError(err instanceof Error ? err.message : 'Failed to delete item')
+      setError(typeof err === 'string' ? err : 'Failed to delete item')
     }
   }, [])
 
@@ -83,11 +133,11 @@ export function useClipboardHistory() {
     [fetchHistory]
   )
 
-  // Paste an item
-  const pasteItem = useCallback(
-    async (id: string) => {
+  // Paste an item without any checks, optionally pressing the submit key after
+  const pasteNow = useCallback(
+    async (id: string, submit = false) => {
       try {
-        await invoke('paste_item', { id })
+        await invoke(submit ? 'paste_item_and_submit' : 'paste_item', { id })
       } catch (err) {
         const errorMessage = err instanceof Error ? err.message : String(err)
         console.warn('[useClipboardHistory] Paste failed, refreshing history:', errorMessage)
@@ -100,6 +150,163 @@ export function useClipboardHistory() {
     [fetchHistory]
   )
 
+  // Paste an item, asking first if it carries hidden or look-alike characters
+  const pasteItem = useCallback(
+    async (id: string, submit = false) => {
+      try {
+        const warning = await invoke<ContentWarning | null>('get_paste_warning', { id })
+        if (warning) {
+          const item = await invoke<ClipboardItem | null>('get_item', { id })
+          setPendingPaste({ id, item, submit, warning })
+          return
+        }
+      } catch (err) {
+        console.warn('[useClipboardHistory] Paste check failed:', err)
+      }
+      await pasteNow(id, submit)
+    },
+    [pasteNow]
+  )
+
+  // Paste and press Enter (or the configured submit key), for chats
+  const pasteAndSubmit = useCallback((id: string) => pasteItem(id, true), [pasteItem])
+
+  const confirmPendingPaste = useCallback(async () => {
+    if (!pendingPaste) return
+    setPendingPaste(null)
+    await pasteNow(pendingPaste.id, pendingPaste.submit)
+  }, [pendingPaste, pasteNow])
+
+  const cancelPendingPaste = useCallback(() => setPendingPaste(null), [])
+
+  // Paste the Nth most recently copied item (Ctrl+1..9)
+  const pasteRecentItem = useCallback(async (position: number) => {
+    try {
+      await invoke('paste_recent_item', { position })
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err))
+    }
+  }, [])
+
+  // Quick paste hands over items that need a look first
+  useEffect(() => {
+    const unlistenPromise = listen<string>('confirm-paste', (event) => {
+      pasteItem(event.payload)
+    })
+
+    return () => {
+      unlistenPromise.then((unlisten) => unlisten())
+    }
+  }, [pasteItem])
+
+  // Type an item out key by key, for apps that block paste
+  const typeOutItem = useCallback(async (id: string) => {
+    try {
+      await invoke('type_item', { id })
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err))
+    }
+  }, [])
+
+  // Split a text item into several new items (by lines when delimiter is empty)
+  const splitItem = useCallback(
+    async (id: string, delimiter: string) => {
+      try {
+        await invoke<ClipboardItem[]>('split_item', { id, delimiter })
+        await fetchHistory()
+      } catch (err) {
+        setError(err instanceof Error ? err.message : String(err))
+      }
+    },
+    [fetchHistory]
+  )
+
+  // Combine an item with the one before it into a new item
+  const joinWithPrevious = useCallback(
+    async (id: string) => {
+      try {
+        await invoke<ClipboardItem>('join_with_previous', { id })
+        await fetchHistory()
+      } catch (err) {
+        setError(err instanceof Error ? err.message : String(err))
+      }
+    },
+    [fetchHistory]
+  )
+
+  // Add a copy of an item without invisible characters or non-breaking spaces
+  const cleanHiddenChars = useCallback(
+    async (id: string) => {
+      try {
+        await invoke<ClipboardItem>('clean_hidden_chars', { id })
+        await fetchHistory()
+      } catch (err) {
+        setError(err instanceof Error ? err.message : String(err))
+      }
+    },
+    [fetchHistory]
+  )
+
+  // Add a translation of an item as a new item
+  const translateItem = useCallback(
+    async (id: string) => {
+      try {
+        await invoke<ClipboardItem>('translate_item', { id })
+        await fetchHistory()
+      } catch (err) {
+        setError(err instanceof Error ? err.message : String(err))
+      }
+    },
+    [fetchHistory]
+  )
+
+  // Replace an item's tags
+  const setItemTags = useCallback(async (id: string, tags: string[]) => {
+    try {
+      const updatedItem = await invoke<ClipboardItem>('set_item_tags', { id, tags })
+      setHistory((prev) => prev.map((item) => (item.id === id ? updatedItem : item)))
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err))
+    }
+  }, [])
+
+  // File an item into a collection, or out of one with null
+  const fileItem = useCallback(async (id: string, collection: string | null) => {
+    try {
+      const updatedItem = await invoke<ClipboardItem>('file_item', { id, collection })
+      setHistory((prev) => prev.map((item) => (item.id === id ? updatedItem : item)))
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err))
+    }
+  }, [])
+
+  // Show an item's files, or open its text in the editor
+  const runQuickAction = useCallback(async (id: string, action: QuickAction) => {
+    try {
+      await invoke('run_quick_action', { id, action })
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err))
+    }
+  }, [])
+
+  // Pipe an item to a shell action; a captured output arrives as a new item
+  const runShellAction = useCallback(async (id: string, name: string) => {
+    try {
+      await invoke('run_shell_action', { id, name })
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err))
+    }
+  }, [])
+
+  // Copy a clip:// link that opens the window on this item
+  const copyItemLink = useCallback(async (id: string) => {
+    try {
+      await invoke('copy_item_link', { id })
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err))
+    }
+  }, [])
+
   // Listen for clipboard changes
   useEffect(() => {
     fetchHistory()
@@ -109,6 +316,13 @@ export function useClipboardHistory() {
     let unlistenSync: UnlistenFn | undefined
 
     const setupListeners = async () => {
+      // The backend changed history in ways a delta can't describe
+      unlistenSync = await listen('history-sync', () => {
+        fetchHistory().catch((e) => {
+          console.warn('[useClipboardHistory] Failed to refresh history on history-sync', e)
+        })
+      })
+
       unlistenChanged = await listen<ClipboardItem>('clipboard-changed', async () => {
         // Backend emits the event and already enforces trimming. Fetch full history
         // to keep frontend in sync with backend limits and ordering.
@@ -161,6 +375,10 @@ export function useClipboardHistory() {
 
   return {
     history,
+    /** Items in history, loaded or not */
+    historyTotal: history.length + notLoaded,
+    hasMoreHistory: nextCursor !== null,
+    loadMoreHistory,
     isLoading,
     error,
     fetchHistory,
@@ -168,5 +386,20 @@ export function useClipboardHistory() {
     deleteItem,
     togglePin,
     pasteItem,
+    pasteAndSubmit,
+    pendingPaste,
+    confirmPendingPaste,
+    cancelPendingPaste,
+    pasteRecentItem,
+    typeOutItem,
+    splitItem,
+    joinWithPrevious,
+    cleanHiddenChars,
+    copyItemLink,
+    setItemTags,
+    fileItem,
+    runQuickAction,
+    runShellAction,
+    translateItem,
   }
 }
@@ -0,0 +1,38 @@
+import { invoke } from '@tauri-apps/api/core'
+import { listen } from '@tauri-apps/api/event'
+import { useCallback, useEffect, useState } from 'react'
+import type { Collection } from '../types/clipboard'
+
+/** The named collections items can be filed into, kept in sync with the backend */
+export function useCollections() {
+  const [collections, setCollections] = useState<Collection[]>([])
+
+  const fetchCollections = useCallback(() => {
+    invoke<Collection[]>('get_collections')
+      .then(setCollections)
+      .catch((e) => {
+        console.warn('[useCollections] Failed to load collections:', e)
+      })
+  }, [])
+
+  useEffect(() => {
+    fetchCollections()
+    const unlistenPromise = listen('collections-changed', fetchCollections)
+
+    return () => {
+      unlistenPromise.then((unlisten) => unlisten())
+    }
+  }, [fetchCollections])
+
+  const createCollection = useCallback(
+    (name: string) => invoke<Collection>('create_collection', { name }),
+    []
+  )
+  const renameCollection = useCallback(
+    (id: string, name: string) => invoke<Collection>('rename_collection', { id, name }),
+    []
+  )
+  const deleteCollection = useCallback((id: string) => invoke('delete_collection', { id }), [])
+
+  return { collections, createCollection, renameCollection, deleteCollection }
+}
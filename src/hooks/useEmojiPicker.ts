@@ -4,8 +4,19 @@
  */
 import { useState, useEffect, useMemo, useCallback } from 'react'
 import { invoke } from '@tauri-apps/api/core'
-import { loadEmojis, getCategories, type Emoji } from '../services/emojiService'
-import { searchEmojis, preloadSearchIndex } from '../services/emojiSearch'
+import {
+  loadEmojis,
+  loadInstalledDataset,
+  getCategories,
+  type Emoji,
+} from '../services/emojiService'
+import {
+  searchEmojis,
+  searchCustomEmojis,
+  preloadSearchIndex,
+  resetSearchIndex,
+} from '../services/emojiSearch'
+import type { CustomEmoji, EmojiVariantSettings, Language } from '../types/clipboard'
 
 /** Recently used emoji from backend */
 interface RecentEmoji {
@@ -13,15 +24,78 @@ interface RecentEmoji {
   use_count: number
 }
 
-export function useEmojiPicker() {
+/** An emoji without its skin tone, gender sign and presentation selector */
+function baseOf(char: string) {
+  return char
+    .replace(/\u200D[\u2640\u2642]\uFE0F?$/, '')
+    .replace(/[\u{1F3FB}-\u{1F3FF}\uFE0F]/gu, '')
+}
+
+/**
+ * Maps recent chars back to full Emoji objects. Variants picked explicitly
+ * take the data of their base emoji.
+ */
+function resolveRecent(recent: RecentEmoji[], emojis: Emoji[]): Emoji[] {
+  const emojiMap = new Map(emojis.map((e) => [e.char, e]))
+  // The first emoji of a base is the plain one, ahead of its gendered forms
+  const baseMap = new Map<string, Emoji>()
+  for (const e of emojis) {
+    if (!baseMap.has(baseOf(e.char))) baseMap.set(baseOf(e.char), e)
+  }
+  return recent
+    .map((r) => {
+      const emoji = emojiMap.get(r.char) ?? baseMap.get(baseOf(r.char))
+      return emoji && { ...emoji, char: r.char }
+    })
+    .filter((e): e is Emoji => e !== undefined)
+}
+
+export function useEmojiPicker(variants?: EmojiVariantSettings, language: Language = 'system') {
+  // Search synonyms follow the UI language, 'system' being the webview's locale
+  const searchLanguage =
+    language === 'system' ? navigator.language.split('-')[0].toLowerCase() : language
+
   const [searchQuery, setSearchQuery] = useState('')
   const [selectedCategory, setSelectedCategory] = useState<string | null>(null)
   const [recentEmojis, setRecentEmojis] = useState<Emoji[]>([])
   const [isLoading, setIsLoading] = useState(true)
 
-  // Load all emojis once
-  const allEmojis = useMemo(() => loadEmojis(), [])
-  const categories = useMemo(() => getCategories(), [])
+  // Load all emojis once, then again when a newer dataset is installed
+  const [allEmojis, setAllEmojis] = useState<Emoji[]>(() => loadEmojis())
+  // getCategories reads the merged list, so it is redone with it
+  const categories = useMemo(() => getCategories(), [allEmojis])
+  useEffect(() => {
+    loadInstalledDataset().then((dataset) => {
+      if (!dataset) return
+      resetSearchIndex()
+      setAllEmojis(loadEmojis())
+    })
+  }, [])
+
+  // Base emoji → the same emoji with the preferred skin tone and gender
+  const [preferred, setPreferred] = useState<Map<string, string>>(() => new Map())
+  const skinTone = variants?.skin_tone
+  const gender = variants?.gender
+  useEffect(() => {
+    invoke<string[]>('apply_emoji_preference', { emojis: allEmojis.map((e) => e.char) })
+      .then((chars) => setPreferred(new Map(allEmojis.map((e, i) => [e.char, chars[i]]))))
+      .catch(console.error)
+  }, [allEmojis, skinTone, gender])
+
+  const withPreference = useCallback(
+    (emoji: Emoji): Emoji => {
+      const char = preferred.get(emoji.char)
+      return char && char !== emoji.char ? { ...emoji, char } : emoji
+    },
+    [preferred]
+  )
+  // Custom shortcodes, found by search ahead of the Unicode emoji
+  const [customEmojis, setCustomEmojis] = useState<CustomEmoji[]>([])
+  useEffect(() => {
+    invoke<CustomEmoji[]>('get_custom_emojis').then(setCustomEmojis).catch(console.error)
+  }, [])
+
+  const preferredEmojis = useMemo(() => allEmojis.map(withPreference), [allEmojis, withPreference])
 
   // Preload search index on mount
   useEffect(() => {
@@ -37,53 +111,73 @@ export function useEmojiPicker() {
     async function loadRecent() {
       try {
         const recent: RecentEmoji[] = await invoke('get_recent_emojis')
-        // Map recent chars back to full Emoji objects
-        const emojiMap = new Map(allEmojis.map((e) => [e.char, e]))
-        const recentWithData = recent
-          .map((r) => emojiMap.get(r.char))
-          .filter((e): e is Emoji => e !== undefined)
-        setRecentEmojis(recentWithData)
+        setRecentEmojis(resolveRecent(recent, preferredEmojis))
       } catch (err) {
         console.error('Failed to load recent emojis:', err)
         setRecentEmojis([])
       }
     }
     loadRecent()
-  }, [allEmojis])
+  }, [preferredEmojis])
 
   // Filtered emojis based on search or category
   const filteredEmojis = useMemo(() => {
     if (searchQuery.trim()) {
-      return searchEmojis(searchQuery, 100)
+      return [
+        ...searchCustomEmojis(searchQuery, customEmojis),
+        ...searchEmojis(searchQuery, 100, searchLanguage).map(withPreference),
+      ]
     }
 
     if (selectedCategory) {
-      return allEmojis.filter((e) => e.category === selectedCategory)
+      return preferredEmojis.filter((e) => e.category === selectedCategory)
     }
 
     // Default: show recent first, then all
     if (recentEmojis.length > 0) {
       const recentChars = new Set(recentEmojis.map((e) => e.char))
-      const nonRecent = allEmojis.filter((e) => !recentChars.has(e.char))
+      const nonRecent = preferredEmojis.filter((e) => !recentChars.has(e.char))
       return [...recentEmojis, ...nonRecent]
     }
 
-    return allEmojis
-  }, [searchQuery, selectedCategory, allEmojis, recentEmojis])
+    return preferredEmojis
+  }, [
+    searchQuery,
+    selectedCategory,
+    preferredEmojis,
+    recentEmojis,
+    withPreference,
+    customEmojis,
+    searchLanguage,
+  ])
 
   // Paste an emoji
-  const pasteEmoji = useCallback(async (emoji: Emoji) => {
+  const pasteEmoji = useCallback(
+    async (emoji: Emoji) => {
+      try {
+        if (emoji.shortcode) {
+          await invoke('paste_custom_emoji', { shortcode: emoji.shortcode })
+          return
+        }
+        await invoke('paste_text', { text: emoji.char, itemType: 'emoji' })
+        // Refresh recent emojis after paste
+        const recent: RecentEmoji[] = await invoke('get_recent_emojis')
+        setRecentEmojis(resolveRecent(recent, preferredEmojis))
+      } catch (err) {
+        console.error('Failed to paste emoji:', err)
+      }
+    },
+    [preferredEmojis]
+  )
+
+  /** Every skin tone and gender of `emoji`; just itself when it has none */
+  const getVariants = useCallback(async (emoji: Emoji): Promise<Emoji[]> => {
     try {
-      await invoke('paste_text', { text: emoji.char, itemType: 'emoji' })
-      // Refresh recent emojis after paste
-      const recent: RecentEmoji[] = await invoke('get_recent_emojis')
-      const emojiMap = new Map(loadEmojis().map((e) => [e.char, e]))
-      const recentWithData = recent
-        .map((r) => emojiMap.get(r.char))
-        .filter((e): e is Emoji => e !== undefined)
-      setRecentEmojis(recentWithData)
+      const chars: string[] = await invoke('get_emoji_variants', { emoji: emoji.char })
+      return chars.map((char) => ({ ...emoji, char }))
     } catch (err) {
-      console.error('Failed to paste emoji:', err)
+      console.error('Failed to load emoji variants:', err)
+      return [emoji]
     }
   }, [])
 
@@ -97,5 +191,6 @@ export function useEmojiPicker() {
     recentEmojis,
     isLoading,
     pasteEmoji,
+    getVariants,
   }
 }
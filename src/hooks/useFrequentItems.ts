@@ -0,0 +1,44 @@
+import { invoke } from '@tauri-apps/api/core'
+import { listen } from '@tauri-apps/api/event'
+import { useEffect, useState } from 'react'
+import type { ClipboardItem } from '../types/clipboard'
+
+/** Items shown in the "frequently used" row */
+const FREQUENT_COUNT = 5
+
+/**
+ * Items pasted often and lately, highest score first. Scores fade with time,
+ * so the list is fetched again each time the window opens, not only when the
+ * history changes.
+ */
+export function useFrequentItems(history: ClipboardItem[]) {
+  const [frequent, setFrequent] = useState<ClipboardItem[]>([])
+  const [shownCount, setShownCount] = useState(0)
+
+  useEffect(() => {
+    let cancelled = false
+    invoke<ClipboardItem[]>('get_frequent_items', { count: FREQUENT_COUNT })
+      .then((items) => {
+        if (!cancelled) setFrequent(items)
+      })
+      .catch((e) => {
+        console.warn('[useFrequentItems] Failed to load frequent items:', e)
+      })
+
+    return () => {
+      cancelled = true
+    }
+  }, [history, shownCount])
+
+  useEffect(() => {
+    const unlistenPromise = listen('window-shown', () => {
+      setShownCount((count) => count + 1)
+    })
+
+    return () => {
+      unlistenPromise.then((unlisten) => unlisten())
+    }
+  }, [])
+
+  return frequent
+}
@@ -1,11 +1,12 @@
 /**
  * GIF Picker Hook
- * Manages GIF state, search, and debouncing
+ * Manages GIF state, search, and debouncing, and the favorite and recent GIFs
  */
-import { useState, useEffect, useCallback, useRef } from 'react'
-import { invoke } from '@tauri-apps/api/core'
+import { useState, useEffect, useCallback, useRef, useMemo } from 'react'
+import { convertFileSrc, invoke } from '@tauri-apps/api/core'
 import { fetchTrendingGifs, searchGifs } from '../services/gifService'
-import type { Gif } from '../types/gif'
+import type { FavoriteGif, Gif, GifView } from '../types/gif'
+import type { GifProvider } from '../types/clipboard'
 
 /** Debounce delay for search input (ms) */
 const SEARCH_DEBOUNCE_MS = 300
@@ -13,43 +14,84 @@ const SEARCH_DEBOUNCE_MS = 300
 /** Number of GIFs to fetch */
 const GIF_LIMIT = 30
 
-export function useGifPicker() {
+/** Kept in step with MAX_RECENT_GIFS in gif_manager.rs */
+const MAX_RECENT_GIFS = 24
+
+/** The GIF without the fields of a favorite */
+function toGif({ id, title, previewUrl, fullUrl, webpUrl, width, height }: Gif): Gif {
+  return { id, title, previewUrl, fullUrl, webpUrl, width, height }
+}
+
+export function useGifPicker(provider?: GifProvider) {
   const [searchQuery, setSearchQuery] = useState('')
   const [gifs, setGifs] = useState<Gif[]>([])
   const [isLoading, setIsLoading] = useState(true)
   const [isPasting, setIsPasting] = useState(false)
   const [error, setError] = useState<string | null>(null)
+  const [view, setView] = useState<GifView>('browse')
+  const [favorites, setFavorites] = useState<FavoriteGif[]>([])
+  const [recent, setRecent] = useState<Gif[]>([])
+
+  useEffect(() => {
+    invoke<FavoriteGif[]>('get_gif_favorites').then(setFavorites).catch(console.error)
+    invoke<Gif[]>('get_recent_gifs').then(setRecent).catch(console.error)
+  }, [])
+
+  const favoriteIds = useMemo(() => new Set(favorites.map((gif) => gif.id)), [favorites])
+  const isFavorite = useCallback((gif: Gif) => favoriteIds.has(gif.id), [favoriteIds])
+
+  // Favorites show their downloaded preview, so they work offline
+  const shownGifs = useMemo(() => {
+    if (view === 'favorites') {
+      return favorites.map((gif) => ({
+        ...toGif(gif),
+        previewUrl: convertFileSrc(gif.previewFile),
+      }))
+    }
+    return view === 'recent' ? recent : gifs
+  }, [view, favorites, recent, gifs])
 
   // Debounce timer ref
   const debounceTimerRef = useRef<ReturnType<typeof setTimeout> | null>(null)
   // Track if component is mounted
   const isMountedRef = useRef(true)
 
-  // Fetch GIFs (trending or search)
-  const fetchGifs = useCallback(async (query: string) => {
-    setIsLoading(true)
-    setError(null)
+  // Fetch GIFs (trending or search), again when the provider changes
+  const fetchGifs = useCallback(
+    async (query: string) => {
+      setIsLoading(true)
+      setError(null)
 
-    try {
-      const results = query.trim()
-        ? await searchGifs(query, GIF_LIMIT)
-        : await fetchTrendingGifs(GIF_LIMIT)
+      try {
+        const results = query.trim()
+          ? await searchGifs(query, GIF_LIMIT)
+          : await fetchTrendingGifs(GIF_LIMIT)
 
-      if (isMountedRef.current) {
-        setGifs(results)
-      }
-    } catch (err) {
-      console.error('Failed to fetch GIFs:', err)
-      if (isMountedRef.current) {
-        setError(err instanceof Error ? err.message : 'Failed to load GIFs')
-        setGifs([])
-      }
-    } finally {
-      if (isMountedRef.current) {
-        setIsLoading(false)
+        if (isMountedRef.current) {
+          setGifs(results)
+        }
+      } catch (err) {
+        console.error('Failed to fetch GIFs:', err)
+        if (isMountedRef.current) {
+          // Backend errors, such as a missing key, come as strings
+          setError(
+            err instanceof Error
+              ? err.message
+              : typeof err === 'string'
+                ? err
+                : 'Failed to load GIFs'
+          )
+          setGifs([])
+        }
+      } finally {
+        if (isMountedRef.current) {
+          setIsLoading(false)
+        }
       }
-    }
-  }, [])
+    },
+    // eslint-disable-next-line react-hooks/exhaustive-deps -- the provider is read by the backend
+    [provider]
+  )
 
   // Load trending GIFs on mount
   useEffect(() => {
@@ -68,6 +110,7 @@ export function useGifPicker() {
   const handleSearchChange = useCallback(
     (query: string) => {
       setSearchQuery(query)
+      setView('browse')
 
       // Clear existing timer
       if (debounceTimerRef.current) {
@@ -96,29 +139,55 @@ export function useGifPicker() {
   }, [])
 
   // Paste a GIF
-  const pasteGif = useCallback(async (gif: Gif) => {
-    setIsPasting(true)
-    try {
-      // 1. Download and copy to clipboard
-      await invoke('paste_gif_from_url', { url: gif.fullUrl })
-
-      // 2. Reset loading state BEFORE hiding window
-      setIsPasting(false)
-
-      // 3. Finish paste sequence (hide window, simulate Ctrl+V)
-      // We use a small timeout to ensure the UI update has painted
-      setTimeout(async () => {
-        try {
-          await invoke('finish_paste')
-        } catch (err) {
-          console.error('Failed to finish paste:', err)
+  const pasteGif = useCallback(
+    async (shown: Gif) => {
+      // Favorites are listed with their local preview
+      const gif = toGif(favorites.find((favorite) => favorite.id === shown.id) ?? shown)
+      setIsPasting(true)
+      try {
+        // 1. Download and copy to clipboard
+        await invoke('paste_gif_from_url', { url: gif.fullUrl, gif })
+        setRecent((current) =>
+          [gif, ...current.filter((r) => r.id !== gif.id)].slice(0, MAX_RECENT_GIFS)
+        )
+
+        // 2. Reset loading state BEFORE hiding window
+        setIsPasting(false)
+
+        // 3. Finish paste sequence (hide window, simulate Ctrl+V)
+        // We use a small timeout to ensure the UI update has painted
+        setTimeout(async () => {
+          try {
+            await invoke('finish_paste')
+          } catch (err) {
+            console.error('Failed to finish paste:', err)
+          }
+        }, 100)
+      } catch (err) {
+        console.error('Failed to paste GIF:', err)
+        setIsPasting(false)
+      }
+    },
+    [favorites]
+  )
+
+  // Favorite a GIF, downloading it, or unfavorite it
+  const toggleFavorite = useCallback(
+    async (shown: Gif) => {
+      try {
+        if (favoriteIds.has(shown.id)) {
+          await invoke('remove_gif_favorite', { id: shown.id })
+          setFavorites((current) => current.filter((gif) => gif.id !== shown.id))
+        } else {
+          const favorite = await invoke<FavoriteGif>('add_gif_favorite', { gif: toGif(shown) })
+          setFavorites((current) => [favorite, ...current.filter((gif) => gif.id !== favorite.id)])
         }
-      }, 100)
-    } catch (err) {
-      console.error('Failed to paste GIF:', err)
-      setIsPasting(false)
-    }
-  }, [])
+      } catch (err) {
+        console.error('Failed to update GIF favorites:', err)
+      }
+    },
+    [favoriteIds]
+  )
 
   // Refresh trending GIFs
   const refreshTrending = useCallback(() => {
@@ -127,17 +196,22 @@ export function useGifPicker() {
       clearTimeout(debounceTimerRef.current)
     }
     setSearchQuery('')
+    setView('browse')
     fetchGifs('')
   }, [fetchGifs])
 
   return {
     searchQuery,
     setSearchQuery: handleSearchChange,
-    gifs,
-    isLoading,
+    gifs: shownGifs,
+    isLoading: view === 'browse' && isLoading,
     isPasting,
-    error,
+    error: view === 'browse' ? error : null,
     pasteGif,
     refreshTrending,
+    view,
+    setView,
+    isFavorite,
+    toggleFavorite,
   }
 }
@@ -1,8 +1,19 @@
-import { useEffect } from 'react'
+import { useEffect, useRef } from 'react'
 import type { MutableRefObject, RefObject } from 'react'
-import type { ActiveTab } from '../types/clipboard'
+import { invoke } from '@tauri-apps/api/core'
+import type { ActiveTab, KeyAction, KeyResolution } from '../types/clipboard'
 import type { TabBarRef } from '../components/TabBar'
 
+/** A multi-key sequence like "gg" is dropped if the next key takes longer than this */
+const SEQUENCE_TIMEOUT_MS = 1000
+
+/** Keymap notation of a key press: printable keys as themselves, others as "<Name>" */
+function keyNotation(e: KeyboardEvent): string {
+  if (e.key.length === 1) return e.key
+  const names: Record<string, string> = { Delete: 'Del', Backspace: 'BS', Enter: 'Enter' }
+  return `<${names[e.key] ?? e.key.replace(/^Arrow/, '')}>`
+}
+
 export function useHistoryKeyboardNavigation(params: {
   activeTab: ActiveTab
   itemsLength: number
@@ -10,9 +21,28 @@ export function useHistoryKeyboardNavigation(params: {
   setFocusedIndex: (i: number) => void
   historyItemRefs: MutableRefObject<(HTMLElement | null)[]>
   tabBarRef: RefObject<TabBarRef | null>
+  /** Vim-style sequences from the keymap setting, resolved by the backend */
+  vimNavigation?: boolean
+  onDelete?: (index: number) => void
+  onPaste?: (index: number) => void
+  onSearch?: () => void
+  /** Every other keymap action, on the focused item where it applies */
+  onAction?: (action: KeyAction, index: number) => void
 }) {
-  const { activeTab, itemsLength, focusedIndex, setFocusedIndex, historyItemRefs, tabBarRef } =
-    params
+  const {
+    activeTab,
+    itemsLength,
+    focusedIndex,
+    setFocusedIndex,
+    historyItemRefs,
+    tabBarRef,
+    vimNavigation,
+    onDelete,
+    onPaste,
+    onSearch,
+    onAction,
+  } = params
+  const sequenceRef = useRef({ keys: '', at: 0 })
 
   useEffect(() => {
     if (activeTab !== 'clipboard' || itemsLength === 0) return
@@ -32,35 +62,89 @@ export function useHistoryKeyboardNavigation(params: {
 
       if (e.key === 'ArrowDown') {
         e.preventDefault()
-        const newIndex = Math.min(focusedIndex + 1, itemsLength - 1)
-        setFocusedIndex(newIndex)
-        historyItemRefs.current[newIndex]?.focus()
-        historyItemRefs.current[newIndex]?.scrollIntoView({ block: 'nearest' })
+        moveTo(Math.min(focusedIndex + 1, itemsLength - 1))
       } else if (e.key === 'ArrowUp') {
         e.preventDefault()
-        const newIndex = Math.max(focusedIndex - 1, 0)
-        setFocusedIndex(newIndex)
-        historyItemRefs.current[newIndex]?.focus()
-        historyItemRefs.current[newIndex]?.scrollIntoView({ block: 'nearest' })
+        moveTo(Math.max(focusedIndex - 1, 0))
       } else if (e.key === 'Home') {
         e.preventDefault()
-        setFocusedIndex(0)
-        historyItemRefs.current[0]?.focus()
-        historyItemRefs.current[0]?.scrollIntoView({ block: 'nearest' })
+        moveTo(0)
       } else if (e.key === 'End') {
         e.preventDefault()
-        const lastIndex = itemsLength - 1
-        setFocusedIndex(lastIndex)
-        historyItemRefs.current[lastIndex]?.focus()
-        historyItemRefs.current[lastIndex]?.scrollIntoView({ block: 'nearest' })
+        moveTo(itemsLength - 1)
       } else if (e.key === 'Tab' && !e.shiftKey) {
         // When pressing Tab on a history item, go back to the tab bar
         e.preventDefault()
         tabBarRef.current?.focusFirstTab()
+      } else if (vimNavigation && !e.ctrlKey && !e.altKey && !e.metaKey && e.key !== 'Escape') {
+        if (e.key.length === 1) e.preventDefault()
+        handleSequenceKey(keyNotation(e))
+      }
+    }
+
+    const moveTo = (index: number) => {
+      setFocusedIndex(index)
+      historyItemRefs.current[index]?.focus()
+      historyItemRefs.current[index]?.scrollIntoView({ block: 'nearest' })
+    }
+
+    const handleSequenceKey = async (key: string) => {
+      const now = Date.now()
+      const previous = sequenceRef.current
+      const keys = (now - previous.at < SEQUENCE_TIMEOUT_MS ? previous.keys : '') + key
+      sequenceRef.current = { keys, at: now }
+
+      let resolution: KeyResolution
+      try {
+        resolution = await invoke<KeyResolution>('resolve_key_sequence', { sequence: keys })
+      } catch (err) {
+        console.error('Failed to resolve key sequence:', err)
+        resolution = { kind: 'none' }
+      }
+      if (resolution.kind === 'pending') return
+      sequenceRef.current = { keys: '', at: 0 }
+      if (resolution.kind === 'none') return
+
+      switch (resolution.action) {
+        case 'move_down':
+          moveTo(Math.min(focusedIndex + 1, itemsLength - 1))
+          break
+        case 'move_up':
+          moveTo(Math.max(focusedIndex - 1, 0))
+          break
+        case 'jump_top':
+          moveTo(0)
+          break
+        case 'jump_bottom':
+          moveTo(itemsLength - 1)
+          break
+        case 'delete':
+          onDelete?.(focusedIndex)
+          break
+        case 'paste':
+          onPaste?.(focusedIndex)
+          break
+        case 'search':
+          onSearch?.()
+          break
+        default:
+          onAction?.(resolution.action, focusedIndex)
       }
     }
 
     globalThis.addEventListener('keydown', handleArrowKeys)
     return () => globalThis.removeEventListener('keydown', handleArrowKeys)
-  }, [activeTab, itemsLength, focusedIndex, setFocusedIndex, historyItemRefs, tabBarRef])
+  }, [
+    activeTab,
+    itemsLength,
+    focusedIndex,
+    setFocusedIndex,
+    historyItemRefs,
+    tabBarRef,
+    vimNavigation,
+    onDelete,
+    onPaste,
+    onSearch,
+    onAction,
+  ])
 }
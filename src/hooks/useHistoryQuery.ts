@@ -0,0 +1,70 @@
+import { invoke } from '@tauri-apps/api/core'
+import { useCallback, useEffect, useRef, useState } from 'react'
+import type { ClipboardItem, HistoryFilters, HistoryPage } from '../types/clipboard'
+
+/** Items per page of history */
+export const HISTORY_PAGE_SIZE = 100
+
+const NO_RESULTS: HistoryPage = { items: [], total: 0, next_cursor: null }
+
+/**
+ * Pages of the history items matching `filters`, filtered by the backend and
+ * fetched again from the first page when the filters or the history change.
+ * The previous results stay up while new ones load. Null while `filters` is
+ * null, meaning no filter.
+ */
+export function useHistoryQuery(filters: HistoryFilters | null, history: ClipboardItem[]) {
+  // The page and the filters it was fetched for
+  const [result, setResult] = useState<{ key: string; page: HistoryPage } | null>(null)
+  const key = filters && JSON.stringify(filters)
+  const loadingMore = useRef(false)
+
+  useEffect(() => {
+    if (!key) {
+      // eslint-disable-next-line react-hooks/set-state-in-effect
+      setResult(null)
+      return
+    }
+    let cancelled = false
+    invoke<HistoryPage>('query_history', {
+      query: { ...JSON.parse(key), limit: HISTORY_PAGE_SIZE },
+    })
+      .then((page) => {
+        if (!cancelled) setResult({ key, page })
+      })
+      .catch(console.error)
+    return () => {
+      cancelled = true
+    }
+  }, [key, history])
+
+  const loadMore = useCallback(async () => {
+    const cursor = result?.page.next_cursor
+    if (!key || result?.key !== key || !cursor || loadingMore.current) return
+    loadingMore.current = true
+    try {
+      const next = await invoke<HistoryPage>('query_history', {
+        query: { ...JSON.parse(key), after: cursor, limit: HISTORY_PAGE_SIZE },
+      })
+      // Dropped if the filters or the history changed meanwhile
+      setResult((prev) =>
+        prev && prev.key === key && prev.page.next_cursor === cursor
+          ? { key, page: { ...next, items: [...prev.page.items, ...next.items] } }
+          : prev
+      )
+    } catch (err) {
+      console.error(err)
+    } finally {
+      loadingMore.current = false
+    }
+  }, [key, result])
+
+  if (!key) return null
+  const page = result?.page ?? NO_RESULTS
+  return {
+    items: page.items,
+    total: page.total,
+    hasMore: page.next_cursor !== null,
+    loadMore,
+  }
+}
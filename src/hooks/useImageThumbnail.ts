@@ -0,0 +1,46 @@
+import { convertFileSrc, invoke } from '@tauri-apps/api/core'
+import { getCurrentWindow } from '@tauri-apps/api/window'
+import { useEffect, useState } from 'react'
+
+/**
+ * Resolves an asset URL for an image item's thumbnail, sized for the scale
+ * factor of the monitor the window is on. Re-resolves when the window moves
+ * to a monitor with a different scale. `src` is null until it is available;
+ * `failed` says to fall back to the inline image.
+ */
+export function useImageThumbnail(id: string, enabled = true) {
+  const [src, setSrc] = useState<string | null>(null)
+  const [failed, setFailed] = useState(false)
+  const [scaleKey, setScaleKey] = useState(0)
+
+  useEffect(() => {
+    let unlisten: (() => void) | undefined
+    getCurrentWindow()
+      .onScaleChanged(() => setScaleKey((k) => k + 1))
+      .then((fn) => {
+        unlisten = fn
+      })
+      .catch(() => {})
+    return () => unlisten?.()
+  }, [])
+
+  useEffect(() => {
+    if (!enabled) return
+
+    let cancelled = false
+    invoke<string>('get_image_thumbnail', { id })
+      .then((path) => {
+        if (!cancelled) setSrc(convertFileSrc(path))
+      })
+      .catch((e) => {
+        console.warn('[useImageThumbnail] Falling back to inline image:', e)
+        if (!cancelled) setFailed(true)
+      })
+
+    return () => {
+      cancelled = true
+    }
+  }, [id, enabled, scaleKey])
+
+  return { src, failed }
+}
@@ -0,0 +1,40 @@
+import { invoke } from '@tauri-apps/api/core'
+import { listen } from '@tauri-apps/api/event'
+import { useEffect, useState } from 'react'
+import type { ClipboardItem } from '../types/clipboard'
+
+/**
+ * Humanized ages ("2 min ago", "Yesterday") of the history items, keyed by id.
+ * The backend works them out and sends the ones that change, so rows don't
+ * need timers of their own.
+ */
+export function useItemAges(history: ClipboardItem[]) {
+  const [ages, setAges] = useState<Record<string, string>>({})
+
+  useEffect(() => {
+    let cancelled = false
+    invoke<Record<string, string>>('get_item_ages')
+      .then((fresh) => {
+        if (!cancelled) setAges(fresh)
+      })
+      .catch((e) => {
+        console.warn('[useItemAges] Failed to load item ages:', e)
+      })
+
+    return () => {
+      cancelled = true
+    }
+  }, [history])
+
+  useEffect(() => {
+    const unlistenPromise = listen<Record<string, string>>('item-ages-changed', (event) => {
+      setAges((prev) => ({ ...prev, ...event.payload }))
+    })
+
+    return () => {
+      unlistenPromise.then((unlisten) => unlisten())
+    }
+  }, [])
+
+  return ages
+}
@@ -0,0 +1,41 @@
+import { invoke } from '@tauri-apps/api/core'
+import { listen } from '@tauri-apps/api/event'
+import { useEffect, useState } from 'react'
+import type { ClipboardItem } from '../types/clipboard'
+
+/**
+ * Short descriptions of the history items for screen readers, keyed by id.
+ * Worded by the backend in the app's language, so they are fetched again when
+ * the history or the settings change.
+ */
+export function useItemSummaries(history: ClipboardItem[]) {
+  const [summaries, setSummaries] = useState<Record<string, string>>({})
+  const [settingsVersion, setSettingsVersion] = useState(0)
+
+  useEffect(() => {
+    let cancelled = false
+    invoke<Record<string, string>>('get_item_summaries')
+      .then((fresh) => {
+        if (!cancelled) setSummaries(fresh)
+      })
+      .catch((e) => {
+        console.warn('[useItemSummaries] Failed to load item summaries:', e)
+      })
+
+    return () => {
+      cancelled = true
+    }
+  }, [history, settingsVersion])
+
+  useEffect(() => {
+    const unlistenPromise = listen('app-settings-changed', () => {
+      setSettingsVersion((version) => version + 1)
+    })
+
+    return () => {
+      unlistenPromise.then((unlisten) => unlisten())
+    }
+  }, [])
+
+  return summaries
+}
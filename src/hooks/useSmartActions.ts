@@ -13,6 +13,19 @@ export function useSmartActions(item: ClipboardItem, enableSmartActions: boolean
     return []
   }, [item, enableSmartActions])
 
+  // A clip with several links gets one sub-entry per URL
+  const urlEntries = useMemo(() => {
+    if (!enableSmartActions) return []
+    const text =
+      item.content.type === 'Text'
+        ? item.content.data
+        : item.content.type === 'RichText'
+          ? item.content.data.plain
+          : ''
+    const urls = smartActionService.extractUrls(text)
+    return urls.length > 1 ? urls : []
+  }, [item, enableSmartActions])
+
   const handleSmartAction = useCallback(async (e: MouseEvent, action: SmartAction) => {
     e.stopPropagation()
     try {
@@ -29,6 +42,7 @@ export function useSmartActions(item: ClipboardItem, enableSmartActions: boolean
     colorPreview: smartActions.find((a) => a.id === 'color-preview'),
     linkAction: smartActions.find((a) => a.id === 'open-link'),
     emailAction: smartActions.find((a) => a.id === 'compose-email'),
+    urlEntries,
     handleSmartAction,
   }
 }
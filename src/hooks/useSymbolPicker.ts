@@ -2,28 +2,34 @@
  * Symbol Picker Hook
  * Manages symbol state, search, and recently used symbols
  */
-import { useState, useMemo, useCallback } from 'react'
+import { useState, useMemo, useCallback, useEffect } from 'react'
 import { invoke } from '@tauri-apps/api/core'
-import { getSymbols, getSymbolCategories, type SymbolItem } from '../services/symbolService'
+import {
+  findSymbol,
+  getSymbols,
+  getSymbolCategories,
+  type SymbolItem,
+} from '../services/symbolService'
 
-const RECENT_SYMBOLS_KEY = 'win11_clipboard_recent_symbols'
-const MAX_RECENT_SYMBOLS = 24
+/** Kept in step with MAX_RECENT_EMOJIS in emoji_manager.rs */
+const MAX_RECENT_SYMBOLS = 20
 
 export function useSymbolPicker() {
   const [searchQuery, setSearchQuery] = useState('')
   const [selectedCategory, setSelectedCategory] = useState<string | null>(null)
 
-  const [recentSymbols, setRecentSymbols] = useState<SymbolItem[]>(() => {
-    try {
-      const stored = localStorage.getItem(RECENT_SYMBOLS_KEY)
-      if (stored) {
-        return JSON.parse(stored) as SymbolItem[]
-      }
-    } catch (e) {
-      console.error('Failed to load recent symbols', e)
-    }
-    return []
-  })
+  const [recentSymbols, setRecentSymbols] = useState<SymbolItem[]>([])
+
+  // Recents are kept by the backend, with the emoji and kaomoji ones
+  useEffect(() => {
+    invoke<{ char: string }[]>('get_recent_symbols')
+      .then((recent) =>
+        setRecentSymbols(
+          recent.map((r) => findSymbol(r.char)).filter((s): s is SymbolItem => s !== undefined)
+        )
+      )
+      .catch((err) => console.error('Failed to load recent symbols:', err))
+  }, [])
 
   const categories = useMemo(() => getSymbolCategories(), [])
 
@@ -35,15 +41,13 @@ export function useSymbolPicker() {
   // Paste symbol
   const pasteSymbol = useCallback(async (symbol: SymbolItem) => {
     try {
-      // Use the generic paste_text command
-      await invoke('paste_text', { text: symbol.char })
+      // Use the generic paste_text command, which records the usage
+      await invoke('paste_text', { text: symbol.char, itemType: 'symbol' })
 
       // Update recent
       setRecentSymbols((prev) => {
         const filtered = prev.filter((s) => s.char !== symbol.char)
-        const newRecent = [symbol, ...filtered].slice(0, MAX_RECENT_SYMBOLS)
-        localStorage.setItem(RECENT_SYMBOLS_KEY, JSON.stringify(newRecent))
-        return newRecent
+        return [symbol, ...filtered].slice(0, MAX_RECENT_SYMBOLS)
       })
     } catch (err) {
       console.error('Failed to paste symbol:', err)
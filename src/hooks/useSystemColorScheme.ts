@@ -0,0 +1,30 @@
+import { invoke } from '@tauri-apps/api/core'
+import { listen } from '@tauri-apps/api/event'
+import { useState, useEffect } from 'react'
+
+type SystemColorScheme = 'dark' | 'light' | 'no_preference'
+
+/**
+ * The desktop's dark/light preference from the settings portal, which the
+ * backend watches. Null when there is no portal or it states no preference,
+ * in which case prefers-color-scheme is the best guess.
+ */
+export function useSystemColorScheme(): boolean | null {
+  const [scheme, setScheme] = useState<SystemColorScheme | null>(null)
+
+  useEffect(() => {
+    invoke<SystemColorScheme | null>('get_system_color_scheme')
+      .then(setScheme)
+      .catch(console.error)
+    const unlistenPromise = listen<SystemColorScheme>('system-color-scheme-changed', (event) =>
+      setScheme(event.payload)
+    )
+    return () => {
+      unlistenPromise.then((unlisten) => unlisten())
+    }
+  }, [])
+
+  if (scheme === 'dark') return true
+  if (scheme === 'light') return false
+  return null
+}
@@ -0,0 +1,53 @@
+/**
+ * Unified Search Hook
+ * Looks a query up in history, snippets, emoji and favorite GIFs at once
+ */
+import { useEffect, useState } from 'react'
+import { invoke } from '@tauri-apps/api/core'
+import { searchEmojis } from '../services/emojiSearch'
+import type { SearchResult } from '../types/search'
+
+/** Debounce delay for search input (ms) */
+const SEARCH_DEBOUNCE_MS = 150
+
+/** Kept in step with RESULTS_PER_KIND in unified_search.rs */
+const RESULTS_PER_KIND = 8
+
+export function useUnifiedSearch(query: string, language: string) {
+  const [results, setResults] = useState<SearchResult[]>([])
+
+  // Search synonyms follow the UI language, 'system' being the webview's locale
+  const searchLanguage =
+    language === 'system' ? navigator.language.split('-')[0].toLowerCase() : language
+
+  useEffect(() => {
+    let cancelled = false
+    const timer = setTimeout(
+      async () => {
+        if (!query.trim()) {
+          setResults([])
+          return
+        }
+        try {
+          const found = await invoke<SearchResult[]>('search_all', { query })
+          // The built-in emoji list is only searched here
+          const listed = new Set(found.map((r) => (r.kind === 'emoji' ? r.char : null)))
+          const builtIn: SearchResult[] = searchEmojis(query, RESULTS_PER_KIND, searchLanguage)
+            .filter((emoji) => !emoji.shortcode && !listed.has(emoji.char))
+            .map((emoji) => ({ kind: 'emoji', char: emoji.char, name: emoji.name }))
+          if (!cancelled) setResults([...found, ...builtIn])
+        } catch (err) {
+          console.error('Failed to search:', err)
+        }
+      },
+      query.trim() ? SEARCH_DEBOUNCE_MS : 0
+    )
+
+    return () => {
+      cancelled = true
+      clearTimeout(timer)
+    }
+  }, [query, searchLanguage])
+
+  return results
+}
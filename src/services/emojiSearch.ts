@@ -4,6 +4,14 @@
  */
 import Fuse, { IFuseOptions } from 'fuse.js'
 import { loadEmojis, type Emoji } from './emojiService'
+import type { CustomEmoji } from '../types/clipboard'
+import synonymsData from '../data/emojiSynonyms.json'
+
+/** Query word → English keywords it also finds, by language code */
+const SYNONYMS: Record<string, Record<string, string[]>> = synonymsData
+
+/** Added to the score of results found through a synonym, so direct matches come first */
+const SYNONYM_PENALTY = 0.15
 
 /** Fuse.js search options optimized for emoji search */
 const FUSE_OPTIONS: IFuseOptions<Emoji> = {
@@ -41,21 +49,61 @@ function getFuseInstance(): Fuse<Emoji> {
   return fuseInstance
 }
 
+/**
+ * The English keywords a query also stands for: its synonyms in the given
+ * language, then those of the resulting English words
+ * @param query Search query string
+ * @param language Language code of the UI
+ */
+export function expandQuery(query: string, language: string = 'en'): string[] {
+  const term = query.trim().toLowerCase()
+  const english = SYNONYMS.en
+  const direct = [...(SYNONYMS[language]?.[term] ?? []), ...(english[term] ?? [])]
+  const expanded = new Set(direct)
+  for (const word of direct) {
+    for (const synonym of english[word] ?? []) expanded.add(synonym)
+  }
+  expanded.delete(term)
+  return [...expanded]
+}
+
 /**
  * Search emojis by query
  * @param query Search query string
  * @param limit Maximum results to return (default: 50)
+ * @param language Language code of the UI, for its synonyms (default: 'en')
  * @returns Array of matching emojis sorted by relevance
  */
-export function searchEmojis(query: string, limit: number = 50): Emoji[] {
+export function searchEmojis(
+  query: string,
+  limit: number = 50,
+  language: string = 'en'
+): Emoji[] {
   if (!query.trim()) {
     return []
   }
 
   const fuse = getFuseInstance()
-  const results = fuse.search(query, { limit })
+  // Best score of each emoji over the query and its synonyms
+  const scores = new Map<string, { item: Emoji; score: number }>()
+  const add = (term: string, penalty: number) => {
+    for (const result of fuse.search(term, { limit })) {
+      const score = (result.score ?? 1) + penalty
+      const best = scores.get(result.item.char)
+      if (!best || score < best.score) {
+        scores.set(result.item.char, { item: result.item, score })
+      }
+    }
+  }
+  add(query, 0)
+  for (const synonym of expandQuery(query, language)) {
+    add(synonym, SYNONYM_PENALTY)
+  }
 
-  return results.map((result: { item: Emoji }) => result.item)
+  return [...scores.values()]
+    .sort((a, b) => a.score - b.score)
+    .slice(0, limit)
+    .map((result) => result.item)
 }
 
 /**
@@ -66,9 +114,39 @@ export function isSearchReady(): boolean {
   return fuseInstance !== null
 }
 
+/**
+ * Drop the search index, after the emoji list changed
+ */
+export function resetSearchIndex(): void {
+  fuseInstance = null
+}
+
 /**
  * Preload the search index (call on app init for faster first search)
  */
 export function preloadSearchIndex(): void {
   getFuseInstance()
 }
+
+/**
+ * Custom emoji whose shortcode contains the query, colons or not
+ * @param query Search query string
+ * @param custom The user's custom emoji
+ */
+export function searchCustomEmojis(query: string, custom: CustomEmoji[]): Emoji[] {
+  const term = query.trim().replace(/^:|:$/g, '').toLowerCase()
+  if (!term) {
+    return []
+  }
+
+  return custom
+    .filter((emoji) => emoji.shortcode.includes(term))
+    .map((emoji) => ({
+      char: emoji.image ? `:${emoji.shortcode}:` : emoji.text,
+      name: `:${emoji.shortcode}:`,
+      keywords: [emoji.shortcode],
+      category: 'Custom',
+      shortcode: emoji.shortcode,
+      image: emoji.image ? `data:image/png;base64,${emoji.image}` : undefined,
+    }))
+}
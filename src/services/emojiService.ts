@@ -1,8 +1,10 @@
 /**
  * Emoji Service
- * Loads emoji data from emojilib and provides a clean interface for the application
+ * Loads emoji data from emojilib and provides a clean interface for the application.
+ * A newer emoji list installed in the backend is put over it, see emoji_dataset.rs
  */
 import emojilib from 'emojilib'
+import { invoke } from '@tauri-apps/api/core'
 
 /** Lightweight emoji interface for the app */
 export interface Emoji {
@@ -10,6 +12,16 @@ export interface Emoji {
   name: string
   keywords: string[]
   category: string
+  /** Set on the user's custom emoji, which paste through their shortcode */
+  shortcode?: string
+  /** Data URL of an image custom emoji */
+  image?: string
+}
+
+/** An emoji list for a later Unicode version than emojilib's */
+export interface EmojiDataset {
+  version: string
+  emojis: { char: string; name: string; keywords: string[]; category?: string }[]
 }
 
 /** Category mapping for emojis based on keywords */
@@ -143,6 +155,50 @@ export function loadEmojis(): Emoji[] {
   return emojis
 }
 
+/**
+ * Put an installed dataset over the emoji list: its emojis replace those with
+ * the same char, and the new ones are added at the end
+ * @param dataset The dataset from the backend
+ * @returns The merged emoji list
+ */
+export function applyDataset(dataset: EmojiDataset): Emoji[] {
+  const merged = new Map(loadEmojis().map((e) => [e.char, e]))
+  for (const entry of dataset.emojis) {
+    const keywords = entry.keywords.length > 0 ? entry.keywords : [entry.name]
+    merged.set(entry.char, {
+      char: entry.char,
+      name: entry.name,
+      keywords,
+      category: entry.category ?? detectCategory(keywords),
+    })
+  }
+
+  cachedEmojis = Array.from(merged.values())
+  return cachedEmojis
+}
+
+/** Pending or finished load of the installed dataset */
+let datasetLoad: Promise<EmojiDataset | null> | null = null
+
+/**
+ * Load the dataset installed in the backend, once, and apply it
+ * @returns The dataset, or null when the built-in list is used
+ */
+export function loadInstalledDataset(): Promise<EmojiDataset | null> {
+  if (!datasetLoad) {
+    datasetLoad = invoke<EmojiDataset | null>('get_emoji_dataset')
+      .then((dataset) => {
+        if (dataset) applyDataset(dataset)
+        return dataset
+      })
+      .catch((err) => {
+        console.error('Failed to load the emoji dataset:', err)
+        return null
+      })
+  }
+  return datasetLoad
+}
+
 /**
  * Get all unique categories from the emoji list
  * @returns Sorted array of category names
@@ -48,10 +48,14 @@ export function getKaomojis(
 
   if (search) {
     const term = search.toLowerCase()
+    // Spaces are ignored, so "fliptable" finds "flip table" too
+    const compact = term.replace(/\s+/g, '')
     list = list.filter(
       (k) =>
         k.text.toLowerCase().includes(term) ||
-        k.keywords.some((key) => key.includes(term)) ||
+        k.keywords.some(
+          (key) => key.includes(term) || key.replace(/\s+/g, '').includes(compact)
+        ) ||
         k.category.toLowerCase().includes(term)
     )
   }
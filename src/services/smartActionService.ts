@@ -1,4 +1,4 @@
-import { open } from '@tauri-apps/plugin-shell'
+import { invoke } from '@tauri-apps/api/core'
 
 export type SmartActionType = 'open-link' | 'compose-email' | 'color-preview'
 
@@ -8,7 +8,26 @@ export interface SmartAction {
   data?: string // extra data like the color hex or the formatted url
 }
 
+// Loose match for URLs embedded in running text; trailing punctuation is trimmed separately
+const embeddedUrlRegex = /\b(?:https?|ftp):\/\/[^\s<>"'`]+/gi
+const trailingPunctuationRegex = /[.,;:!?)\]}>]+$/
+
 export const smartActionService = {
+  /**
+   * Every distinct URL in the text, in order of appearance.
+   * Used to split a clip with several links into per-URL sub-entries.
+   */
+  extractUrls(content: string): string[] {
+    if (!content) return []
+
+    const urls: string[] = []
+    for (const match of content.matchAll(embeddedUrlRegex)) {
+      const url = match[0].replace(trailingPunctuationRegex, '')
+      if (!urls.includes(url)) urls.push(url)
+    }
+    return urls
+  },
+
   detectActions(content: string): SmartAction[] {
     const actions: SmartAction[] = []
     if (!content) return actions
@@ -54,11 +73,12 @@ export const smartActionService = {
   async execute(action: SmartAction) {
     try {
       switch (action.id) {
+        // Opened by the backend with xdg-open
         case 'open-link':
-          if (action.data) await open(action.data)
+          if (action.data) await invoke('open_url', { url: action.data })
           break
         case 'compose-email':
-          if (action.data) await open(action.data)
+          if (action.data) await invoke('open_url', { url: action.data })
           break
         // Color preview actions are passive; no additional execution is required
         default:
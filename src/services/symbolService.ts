@@ -33,6 +33,24 @@ export type SymbolCategory = (typeof SYMBOL_CATEGORIES)[number]
 // prettier-ignore
 export const SYMBOLS: SymbolItem[] = symbolsData as SymbolItem[];
 
+/** The codepoint a query like "U+2014", "0x2014" or "2014" names, if any */
+function parseCodepoint(query: string): number | null {
+  const hex = query.trim().replace(/^(u\+|0x)/i, '')
+  if (!/^[0-9a-f]{2,6}$/i.test(hex)) return null
+  return parseInt(hex, 16)
+}
+
+/** The codepoint of a symbol, written as "U+2014" */
+export function formatCodepoint(char: string): string {
+  const codepoint = char.codePointAt(0) ?? 0
+  return `U+${codepoint.toString(16).toUpperCase().padStart(4, '0')}`
+}
+
+/** The symbol for a character, if it is in the dataset */
+export function findSymbol(char: string): SymbolItem | undefined {
+  return SYMBOLS.find((s) => s.char === char)
+}
+
 export function getSymbols(category?: string | null, searchQuery?: string): SymbolItem[] {
   let filtered = SYMBOLS
 
@@ -42,8 +60,10 @@ export function getSymbols(category?: string | null, searchQuery?: string): Symb
 
   if (searchQuery) {
     const query = searchQuery.toLowerCase()
+    const codepoint = parseCodepoint(query)
     filtered = filtered.filter(
       (s) =>
+        s.char.codePointAt(0) === codepoint ||
         s.name.toLowerCase().includes(query) ||
         s.char.includes(query) ||
         s.keywords.some((k) => k.toLowerCase().includes(query))
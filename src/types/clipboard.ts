@@ -36,14 +36,144 @@ export interface ClipboardItem {
   timestamp: string
   pinned: boolean
   preview: string
+  /** WM_CLASS or app id of the window it was copied from, if known */
+  source_app?: string
+  /** Set when the text contains characters that do not show as what they are */
+  hidden_chars?: HiddenChars
+  /** Emails, phone numbers, IPs and paths found in the text */
+  entities?: Entity[]
+  /** Set when the text reads as Markdown; the preview can show it formatted */
+  markdown?: boolean
+  /** Set on very large texts, of which the content is only the start */
+  large_text?: LargeText
+  /** Set once the image data moved to disk under the memory budget; its base64 is empty */
+  evicted?: boolean
+  /** Set on copied files once one of them no longer exists */
+  files_missing?: boolean
+  /** Paste score as of the last paste, which halves every week */
+  paste_frequency?: { score: number; last_pasted: string }
+  /** User labels, lowercase without the '#' */
+  tags?: string[]
+  /** Id of the collection the item is filed in */
+  collection?: string
+}
+
+/** Filters of a history page; left out matches everything */
+export interface HistoryFilters {
+  text?: string
+  tag?: string | null
+  app?: string | null
+  collection?: string | null
+}
+
+export interface HistoryQuery extends HistoryFilters {
+  /** Id of the last item of the previous page */
+  after?: string
+  limit?: number
+}
+
+export interface HistoryPage {
+  items: ClipboardItem[]
+  /** Items matching the filters across all pages */
+  total: number
+  /** `after` for the next page; null on the last one */
+  next_cursor: string | null
+}
+
+export type EntityKind = 'email' | 'phone' | 'ip' | 'path'
+
+export interface Entity {
+  kind: EntityKind
+  value: string
+}
+
+/** Backend actions on an item besides pasting it */
+export type QuickAction = 'open_url' | 'reveal_file' | 'open_in_editor'
+
+/** A named folder items can be filed into */
+export interface Collection {
+  id: string
+  name: string
+  /** Imported from a snippet pack marked read-only */
+  read_only?: boolean
+}
+
+/** What importing a snippet pack again does with the earlier import */
+export type PackConflict = 'update' | 'keep_both'
+
+/** Size of a text kept out of line; pasting reads the whole of it */
+export interface LargeText {
+  hash: number
+  bytes: number
+  lines: number
+}
+
+export interface HiddenChars {
+  invisible: number
+  bidi: number
+  nonbreaking_spaces: number
+}
+
+/** Why an item is shown for confirmation before it is pasted */
+export interface ContentWarning {
+  invisible_chars: number
+  bidi_controls: number
+  mixed_script_words: string[]
+}
+
+export type HeatmapRange = 'week' | 'month' | 'year' | 'all'
+
+/** Result of `get_activity_heatmap`: copies per weekday (Monday first) and hour */
+export interface ActivityHeatmap {
+  counts: number[][]
+  total: number
+  max: number
+}
+
+/** Result of `get_usage_insights`: local copy and paste counts */
+export interface UsageInsights {
+  /** One entry per day of the range, oldest first */
+  copies_per_day: { date: string; count: number }[]
+  total_copies: number
+  total_pastes: number
+  most_pasted: { id: string; preview: string; count: number }[]
+  top_source_apps: { app: string; count: number }[]
 }
 
 /** Active tab in the UI */
-export type ActiveTab = 'clipboard' | 'gifs' | 'emoji' | 'kaomoji' | 'symbols'
+export type ActiveTab =
+  | 'clipboard'
+  | 'selections'
+  | 'gifs'
+  | 'stickers'
+  | 'emoji'
+  | 'kaomoji'
+  | 'symbols'
 
 /** Theme mode */
 export type ThemeMode = 'light' | 'dark' | 'system'
 
+export type ThemePreset = 'custom' | 'windows11' | 'midnight' | 'sunset' | 'forest'
+
+/** The theme section of the settings; a preset overrides the values when saved */
+export interface ThemeSettings {
+  preset: ThemePreset
+  accent_color: string
+  corner_radius: number
+}
+
+/** The resolved theme sent with 'theme-changed' */
+export interface Theme {
+  preset: ThemePreset
+  accent_color: string
+  accent_hover_color: string
+  corner_radius: number
+  dark_background_opacity: number
+  light_background_opacity: number
+  density: UiDensity
+  font_scale: number
+}
+
 export interface Kaomoji {
   id: string
   text: string
@@ -53,16 +183,211 @@ export interface Kaomoji {
 
 export type CustomKaomoji = Omit<Kaomoji, 'id'>
 
+/** A user-registered shortcode, stored by EmojiManager */
+export interface CustomEmoji {
+  /** Without the colons */
+  shortcode: string
+  /** Empty for images */
+  text: string
+  /** PNG as base64 */
+  image?: string
+}
+
+export type StorageBackend =
+  | 'json'
+  | 'flat_files'
+  | 'sqlite'
+  | 'sled'
+  | 'event_log'
+  | 'memory'
+
+export type BackgroundWork = 'off' | 'low' | 'normal'
+
+export type StoredImageFormat = 'png' | 'webp' | 'jpeg'
+
+export type SkinTone = 'default' | 'light' | 'medium_light' | 'medium' | 'medium_dark' | 'dark'
+
+export type EmojiGender = 'neutral' | 'female' | 'male'
+
+/** Applied to the emojis of the picker that take them, see emoji_variants.rs */
+export interface EmojiVariantSettings {
+  skin_tone: SkinTone
+  gender: EmojiGender
+}
+
+/** How images past the most recent few are re-encoded in history */
+export interface ImageCompressionSettings {
+  format: StoredImageFormat
+  /** 1 to 100, for "jpeg" */
+  jpeg_quality: number
+  keep_originals: number
+}
+
+/** Where the window opens, see PlacementMode in config_manager.rs */
+export type WindowPlacement = 'auto' | 'cursor' | 'remembered' | 'docked'
+
+/** Screen edge of the docked sidebar, kept with the window state */
+export type DockEdge = 'left' | 'right'
+
+/** 'any', 'pointer', 'focused_window', or the name of a monitor */
+export type OpenOnMonitor = 'any' | 'pointer' | 'focused_window' | (string & {})
+
+/** How tightly the history list is laid out; compact also opens a smaller window */
+export type UiDensity = 'comfortable' | 'compact'
+
+/** 'system' (the session locale) or a language code, see i18n.rs */
+export type Language = 'system' | 'en' | 'pt' | 'es' | 'de' | 'fr'
+
+export type TerminalPasteKeys = 'ctrl+shift+v' | 'shift+insert'
+
+export type SubmitKeys = 'enter' | 'ctrl+enter' | 'shift+enter'
+
+export type PasteFormat = 'as_is' | 'plain_text' | 'code_block' | 'html'
+
+/** Formatting applied when pasting into the listed apps (WM_CLASS or app_id) */
+export interface PasteProfile {
+  name: string
+  app_ids: string[]
+  format: PasteFormat
+}
+
+/** A command the item's text is piped to, see shell_actions.rs */
+export interface ShellAction {
+  name: string
+  command: string
+  /** Add what the command prints to history */
+  capture_output: boolean
+}
+
+export type TranslationBackend = 'libretranslate' | 'deepl'
+
+/** Service used by the "Translate" action, see translation.rs */
+export interface TranslationSettings {
+  backend: TranslationBackend
+  libretranslate_url: string
+  libretranslate_api_key: string
+  deepl_api_key: string
+  target_language: string
+}
+
+export type GifProvider = 'tenor' | 'giphy' | 'self_hosted'
+
+/** Service the GIF panel searches, see gif_provider.rs */
+export interface GifSettings {
+  provider: GifProvider
+  /** Tenor's demo key is used when blank */
+  tenor_api_key: string
+  giphy_api_key: string
+  /** Base URL of a server speaking Tenor's v1 API */
+  self_hosted_url: string
+  self_hosted_api_key: string
+  /** Cache of downloaded GIFs, in MB; 0 for no limit */
+  cache_limit_mb: number
+}
+
+/** What clearing the GIF cache deleted */
+export interface CacheCleanup {
+  files: number
+  bytes: number
+}
+
+export type UnitSystem = 'system' | 'metric' | 'imperial'
+
+/** A quantity found in an item and what it comes to in the user's units */
+export interface Conversion {
+  original: string
+  converted: string
+}
+
 export interface UserSettings {
   theme_mode: ThemeMode
   dark_background_opacity: number
   light_background_opacity: number
+  theme: ThemeSettings
+  ui_density: UiDensity
+  font_scale: number
+  language: Language
   enable_smart_actions: boolean
   enable_ui_polish: boolean
   max_history_size: number
+  capture_text: boolean
+  capture_images: boolean
+  capture_files: boolean
+  prune_missing_files: boolean
+  capture_html: boolean
+  text_retention_days: number
+  image_retention_days: number
+  file_retention_days: number
+  storage_backend: StorageBackend
+  background_work: BackgroundWork
+  image_compression: ImageCompressionSettings
+  /** Megabytes of history kept in memory before older images move to disk; 0 for no limit */
+  memory_budget_mb: number
+  window_placement: WindowPlacement
+  open_on_monitor: OpenOnMonitor
+  use_layer_shell: boolean
+  pinned_git_enabled: boolean
+  pinned_git_dir: string
+  enable_primary_selection: boolean
+  copy_on_select: boolean
+  copy_on_select_delay_secs: number
+  sync_selections: boolean
+  terminal_wm_classes: string[]
+  terminal_paste_keys: TerminalPasteKeys
+  submit_keys: SubmitKeys
+  type_out_delay_ms: number
+  join_separator: string
+  paste_profiles: PasteProfile[]
+  focus_strategies: string[]
+  paste_strategies: string[]
+  shell_actions: ShellAction[]
+  translation: TranslationSettings
+  unit_system: UnitSystem
+  /** ISO code, empty for the locale's currency */
+  currency: string
+  /** Fetch exchange rates to convert prices; sends a request to the rates service */
+  fetch_exchange_rates: boolean
+  confirm_suspicious_paste: boolean
+  global_shortcut: string
+  emoji_shortcut: string
+  quick_paste_shortcuts: boolean
+  vim_navigation: boolean
+  keyboard_only: boolean
+  keymap: Record<string, string>
+  auto_repair_shortcut: boolean
+  show_tray_icon: boolean
+  /** Port of the Prometheus metrics endpoint on 127.0.0.1, 0 when off */
+  metrics_port: number
+  emoji_variants: EmojiVariantSettings
+  gifs: GifSettings
   custom_kaomojis: CustomKaomoji[]
 }
 
+/** Actions of the Vim-style history navigation, see keymap.rs */
+export type KeyAction =
+  | 'move_down'
+  | 'move_up'
+  | 'jump_top'
+  | 'jump_bottom'
+  | 'delete'
+  | 'paste'
+  | 'search'
+  | 'paste_and_submit'
+  | 'toggle_pin'
+  | 'type_out'
+  | 'split'
+  | 'join_with_previous'
+  | 'clean_hidden_chars'
+  | 'copy_link'
+  | 'next_tab'
+  | 'previous_tab'
+  | 'search_gifs'
+
+export type KeyResolution =
+  | { kind: 'action'; action: KeyAction }
+  | { kind: 'pending' }
+  | { kind: 'none' }
+
 /** Helper type for boolean settings keys */
 export type BooleanSettingKey = {
   [K in keyof UserSettings]: UserSettings[K] extends boolean ? K : never
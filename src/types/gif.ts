@@ -50,6 +50,17 @@ export interface Gif {
   title: string
   previewUrl: string
   fullUrl: string
+  /** The same as animated WebP, pasted into apps that take it */
+  webpUrl?: string
   width: number
   height: number
 }
+
+/** A favorite GIF, downloaded so it pastes offline, see gif_manager.rs */
+export interface FavoriteGif extends Gif {
+  file: string
+  previewFile: string
+}
+
+/** What the GIF panel lists */
+export type GifView = 'browse' | 'favorites' | 'recent'
@@ -0,0 +1,14 @@
+/**
+ * Unified Search Types
+ * Results of the single search box, tagged by kind, see unified_search.rs
+ */
+import type { ClipboardItem, CustomEmoji } from './clipboard'
+import type { FavoriteGif } from './gif'
+
+export type SearchResult =
+  | { kind: 'history'; item: ClipboardItem }
+  /** A pinned item, or one filed into a collection */
+  | { kind: 'snippet'; item: ClipboardItem }
+  | { kind: 'emoji'; char: string; name: string }
+  | { kind: 'custom_emoji'; emoji: CustomEmoji }
+  | { kind: 'gif'; gif: FavoriteGif }
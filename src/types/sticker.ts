@@ -0,0 +1,19 @@
+/**
+ * Sticker Types
+ * Sticker packs imported from a folder or a URL, see sticker_manager.rs
+ */
+
+export interface Sticker {
+  id: string
+  name: string
+  /** Stored PNG, shown through the asset protocol */
+  file: string
+}
+
+export interface StickerPack {
+  id: string
+  name: string
+  /** Folder or URL it was imported from */
+  source: string
+  stickers: Sticker[]
+}
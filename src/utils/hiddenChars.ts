@@ -0,0 +1,23 @@
+// Same ranges as is_invisible, is_bidi_control and is_nonbreaking_space in
+// content_classifier.rs
+const HIDDEN_CHAR_PATTERN =
+  /([\u00A0\u00AD\u061C\u180E\u2007\u200B-\u200F\u202A-\u202F\u2060-\u2064\u2066-\u2069\uFEFF\u{E0000}-\u{E007F}])/u
+
+export interface TextSegment {
+  text: string
+  hidden: boolean
+}
+
+/** "U+200B" style label for a single character */
+export function codePointLabel(char: string): string {
+  const codePoint = char.codePointAt(0) ?? 0
+  return `U+${codePoint.toString(16).toUpperCase().padStart(4, '0')}`
+}
+
+/** Splits text so every hidden character is its own segment */
+export function splitHiddenChars(text: string): TextSegment[] {
+  return text
+    .split(HIDDEN_CHAR_PATTERN)
+    .filter((part) => part !== '')
+    .map((part) => ({ text: part, hidden: HIDDEN_CHAR_PATTERN.test(part) }))
+}
@@ -0,0 +1,25 @@
+/** A history search split into its operators and the text left to match */
+export interface ParsedSearch {
+  /** From "#work" */
+  tag: string | null
+  /** From "app:code" */
+  app: string | null
+  text: string
+}
+
+const TAG_OPERATOR = /(?:^|\s)#(\S+)/
+const APP_OPERATOR = /(?:^|\s)app:(\S+)/i
+
+/** Takes the "#tag" and "app:name" operators out of a search query */
+export function parseSearch(query: string): ParsedSearch {
+  let text = query
+  const take = (pattern: RegExp) => {
+    const match = text.match(pattern)
+    if (!match) return null
+    text = text.replace(match[0], ' ')
+    return match[1]
+  }
+  const tag = take(TAG_OPERATOR)
+  const app = take(APP_OPERATOR)
+  return { tag, app, text: text.trim() }
+}
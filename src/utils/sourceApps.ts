@@ -0,0 +1,22 @@
+/** Most apps listed by `sourceApps` */
+const MAX_APPS = 6
+
+/** A readable name for an app id, like "firefox" for "org.mozilla.firefox" */
+export function appLabel(appId: string) {
+  return appId.split('.').pop() || appId
+}
+
+/** The apps items were copied from, the most frequent first */
+export function sourceApps(items: { source_app?: string }[]) {
+  const counts = new Map<string, number>()
+  for (const { source_app } of items) {
+    // "app:" search takes a single word
+    if (source_app && !/\s/.test(source_app)) {
+      counts.set(source_app, (counts.get(source_app) ?? 0) + 1)
+    }
+  }
+  return [...counts.entries()]
+    .sort((a, b) => b[1] - a[1])
+    .slice(0, MAX_APPS)
+    .map(([app]) => app)
+}
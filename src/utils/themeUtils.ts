@@ -1,3 +1,5 @@
+import type { Theme } from '../types/clipboard'
+
 export function calculateSecondaryOpacity(baseOpacity: number): number {
   if (baseOpacity < 0) {
     return 0.2
@@ -33,3 +35,17 @@ export function getCardBackgroundStyle(isDark: boolean, opacity: number) {
     ? { backgroundColor: 'rgb(255, 255, 255)' } // win11Light-bg-card solid
     : { backgroundColor: `rgba(255, 255, 255, ${opacity})` }
 }
+
+/**
+ * Applies the accent color and corner radius from the backend's theme as CSS
+ * variables; the Tailwind accent colors and radii read them. The font scale
+ * sizes the root font, which every rem-based size follows.
+ */
+export function applyTheme(theme: Theme) {
+  const root = document.documentElement
+  root.style.setProperty('--win11-accent', theme.accent_color)
+  root.style.setProperty('--win11-accent-hover', theme.accent_hover_color)
+  root.style.setProperty('--win11-radius', `${theme.corner_radius}px`)
+  root.style.setProperty('--win11-font-scale', String(theme.font_scale))
+  root.dataset.density = theme.density
+}
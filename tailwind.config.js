@@ -16,8 +16,9 @@ export default {
           'bg-tertiary': '#383838',
           'bg-card': '#2d2d2d',
           'bg-card-hover': '#3d3d3d',
-          'bg-accent': '#0078d4',
-          'bg-accent-hover': '#1a86d9',
+          // Accent from the theme settings, see applyTheme
+          'bg-accent': 'var(--win11-accent)',
+          'bg-accent-hover': 'var(--win11-accent-hover)',
           
           // Text
           'text-primary': '#ffffff',
@@ -53,8 +54,8 @@ export default {
         }
       },
       borderRadius: {
-        'win11': '8px',
-        'win11-lg': '12px',
+        'win11': 'var(--win11-radius)',
+        'win11-lg': 'calc(var(--win11-radius) * 1.5)',
       },
       boxShadow: {
         'win11': '0 8px 32px rgba(0, 0, 0, 0.25)',